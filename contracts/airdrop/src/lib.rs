@@ -0,0 +1,651 @@
+#![no_std]
+
+//! # Airdrop Contract
+//!
+//! Merkle-root based token distributions for graduated-token communities.
+//!
+//! ## Features
+//! - One active airdrop per token, funded in full when its root is set
+//! - Claims verified against the root, tracked in a claim bitmap
+//! - Claim deadline, after which unclaimed tokens are clawed back to the
+//!   treasury
+//!
+//! ## Merkle Tree
+//! Leaves are `sha256(index (u32 BE) || account (XDR) || amount (i128 BE))`.
+//! Internal nodes hash the sorted pair `sha256(min(a, b) || max(a, b))`, so
+//! proofs don't need left/right flags.
+
+use astro_core_shared::{
+    events::{emit_treasury_changed, EventBuilder},
+    math::{safe_add, safe_sub},
+    types::{
+        extend_instance_ttl, get_persistent, get_ttl_config, set_persistent, set_ttl_config,
+        ContractInfo, SharedError, TtlConfig,
+    },
+    upgrade::{self, PendingUpgrade},
+};
+use soroban_sdk::{
+    contract, contractimpl, contracttype, token, xdr::ToXdr, Address, Bytes, BytesN, Env, Vec,
+};
+
+// ════════════════════════════════════════════════════════════════════════════
+// Constants
+// ════════════════════════════════════════════════════════════════════════════
+
+/// Storage layout version of this code (see `migrate`)
+const STORAGE_VERSION: u32 = 1;
+
+/// Default claim window of a new airdrop (90 days)
+const DEFAULT_CLAIM_WINDOW: u64 = 90 * 24 * 60 * 60;
+
+/// Claims tracked per bitmap word
+const BITS_PER_WORD: u32 = 128;
+
+/// Maximum proof length (trees up to 2^32 leaves)
+const MAX_PROOF_LENGTH: u32 = 32;
+
+// ════════════════════════════════════════════════════════════════════════════
+// Storage Keys
+// ════════════════════════════════════════════════════════════════════════════
+
+#[contracttype]
+#[derive(Clone, Debug)]
+pub enum DataKey {
+    /// Admin address
+    Admin,
+    /// Whether contract is initialized
+    Initialized,
+    /// Whether contract is paused
+    Paused,
+    /// Treasury receiving clawed-back tokens
+    Treasury,
+    /// Current airdrop per token (Address -> Airdrop)
+    Airdrop(Address),
+    /// Claim bitmap word ((token, round, word) -> u128)
+    Claimed(Address, u32, u32),
+    /// Protocol registry address (reported by `contract_info`)
+    Registry,
+}
+
+// ════════════════════════════════════════════════════════════════════════════
+// Types
+// ════════════════════════════════════════════════════════════════════════════
+
+/// A token's current airdrop
+#[contracttype]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Airdrop {
+    /// Merkle root of (index, account, amount) leaves
+    pub root: BytesN<32>,
+    /// Tokens funded for the airdrop
+    pub total: i128,
+    /// Tokens claimed so far
+    pub claimed: i128,
+    /// Last timestamp claims are accepted
+    pub deadline: u64,
+    /// Airdrop number for this token (separates claim bitmaps)
+    pub round: u32,
+    /// Whether unclaimed tokens were clawed back
+    pub closed: bool,
+}
+
+// ════════════════════════════════════════════════════════════════════════════
+// Contract Implementation
+// ════════════════════════════════════════════════════════════════════════════
+
+#[contract]
+pub struct MerkleAirdrop;
+
+#[contractimpl]
+impl MerkleAirdrop {
+    // ────────────────────────────────────────────────────────────────────────
+    // Initialization
+    // ────────────────────────────────────────────────────────────────────────
+
+    /// Initialize the airdrop distributor
+    pub fn initialize(env: Env, admin: Address, treasury: Address) -> Result<(), SharedError> {
+        if env.storage().instance().has(&DataKey::Initialized) {
+            return Err(SharedError::AlreadyInitialized);
+        }
+
+        env.storage().instance().set(&DataKey::Admin, &admin);
+        env.storage().instance().set(&DataKey::Treasury, &treasury);
+        env.storage().instance().set(&DataKey::Initialized, &true);
+        upgrade::init_version(&env, STORAGE_VERSION);
+        env.storage().instance().set(&DataKey::Paused, &false);
+
+        extend_instance_ttl(&env);
+
+        let events = EventBuilder::new(&env);
+        events.publish(
+            "airdrop",
+            "initialized",
+            (admin, treasury, env.ledger().timestamp()),
+        );
+
+        Ok(())
+    }
+
+    // ────────────────────────────────────────────────────────────────────────
+    // Airdrop Management (admin only)
+    // ────────────────────────────────────────────────────────────────────────
+
+    /// Start an airdrop of `total` tokens (pulled from the admin) for `root`.
+    /// The previous airdrop of the token must have been clawed back.
+    pub fn set_root(
+        env: Env,
+        token: Address,
+        root: BytesN<32>,
+        total: i128,
+    ) -> Result<(), SharedError> {
+        let admin = Self::require_admin(&env)?;
+
+        if total <= 0 {
+            return Err(SharedError::InvalidAmount);
+        }
+
+        let round = match Self::get_airdrop(env.clone(), token.clone()) {
+            Some(previous) if !previous.closed => return Err(SharedError::InvalidState),
+            Some(previous) => previous.round + 1,
+            None => 0,
+        };
+
+        token::Client::new(&env, &token).transfer(&admin, env.current_contract_address(), &total);
+
+        let airdrop = Airdrop {
+            root: root.clone(),
+            total,
+            claimed: 0,
+            deadline: env.ledger().timestamp() + DEFAULT_CLAIM_WINDOW,
+            round,
+            closed: false,
+        };
+        Self::save_airdrop(&env, &token, &airdrop);
+
+        let events = EventBuilder::new(&env);
+        events.publish(
+            "airdrop",
+            "root_set",
+            (
+                token,
+                root,
+                total,
+                airdrop.deadline,
+                env.ledger().timestamp(),
+            ),
+        );
+
+        extend_instance_ttl(&env);
+        Ok(())
+    }
+
+    /// Move the claim deadline of a token's open airdrop
+    pub fn set_deadline(env: Env, token: Address, deadline: u64) -> Result<(), SharedError> {
+        Self::require_admin(&env)?;
+
+        if deadline <= env.ledger().timestamp() {
+            return Err(SharedError::InvalidTimestamp);
+        }
+
+        let mut airdrop = Self::load_open_airdrop(&env, &token)?;
+        airdrop.deadline = deadline;
+        Self::save_airdrop(&env, &token, &airdrop);
+
+        extend_instance_ttl(&env);
+        Ok(())
+    }
+
+    // ────────────────────────────────────────────────────────────────────────
+    // Claims
+    // ────────────────────────────────────────────────────────────────────────
+
+    /// Claim `amount` for `account` (callable by anyone; tokens go to `account`)
+    pub fn claim(
+        env: Env,
+        token: Address,
+        index: u32,
+        account: Address,
+        amount: i128,
+        proof: Vec<BytesN<32>>,
+    ) -> Result<(), SharedError> {
+        Self::require_initialized(&env)?;
+        Self::require_not_paused(&env)?;
+
+        let mut airdrop = Self::load_open_airdrop(&env, &token)?;
+        if env.ledger().timestamp() > airdrop.deadline {
+            return Err(SharedError::DeadlineExpired);
+        }
+        if amount <= 0 {
+            return Err(SharedError::InvalidAmount);
+        }
+        if proof.len() > MAX_PROOF_LENGTH {
+            return Err(SharedError::LimitExceeded);
+        }
+
+        let word_key = DataKey::Claimed(token.clone(), airdrop.round, index / BITS_PER_WORD);
+        let word: u128 = get_persistent(&env, &word_key).unwrap_or(0);
+        let bit = 1u128 << (index % BITS_PER_WORD);
+        if word & bit != 0 {
+            return Err(SharedError::AlreadyExecuted);
+        }
+
+        let leaf = Self::leaf_hash(&env, index, &account, amount);
+        if Self::process_proof(&env, leaf, &proof) != airdrop.root {
+            return Err(SharedError::Unauthorized);
+        }
+
+        airdrop.claimed = safe_add(airdrop.claimed, amount)?;
+        if airdrop.claimed > airdrop.total {
+            return Err(SharedError::InsufficientBalance);
+        }
+        Self::save_airdrop(&env, &token, &airdrop);
+
+        set_persistent(&env, &word_key, &(word | bit));
+
+        token::Client::new(&env, &token).transfer(
+            &env.current_contract_address(),
+            &account,
+            &amount,
+        );
+
+        let events = EventBuilder::new(&env);
+        events.publish(
+            "airdrop",
+            "claimed",
+            (token, index, account, amount, env.ledger().timestamp()),
+        );
+
+        extend_instance_ttl(&env);
+        Ok(())
+    }
+
+    /// Send a token's unclaimed tokens to the treasury after the deadline
+    /// (callable by anyone). Returns the amount clawed back.
+    pub fn clawback(env: Env, token: Address) -> Result<i128, SharedError> {
+        Self::require_initialized(&env)?;
+
+        let mut airdrop = Self::load_open_airdrop(&env, &token)?;
+        if env.ledger().timestamp() <= airdrop.deadline {
+            return Err(SharedError::CooldownNotElapsed);
+        }
+
+        let remaining = safe_sub(airdrop.total, airdrop.claimed)?;
+        airdrop.closed = true;
+        Self::save_airdrop(&env, &token, &airdrop);
+
+        let treasury: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::Treasury)
+            .ok_or(SharedError::NotInitialized)?;
+        if remaining > 0 {
+            token::Client::new(&env, &token).transfer(
+                &env.current_contract_address(),
+                &treasury,
+                &remaining,
+            );
+        }
+
+        let events = EventBuilder::new(&env);
+        events.publish(
+            "airdrop",
+            "clawback",
+            (token, treasury, remaining, env.ledger().timestamp()),
+        );
+
+        extend_instance_ttl(&env);
+        Ok(remaining)
+    }
+
+    // ────────────────────────────────────────────────────────────────────────
+    // Admin Functions
+    // ────────────────────────────────────────────────────────────────────────
+
+    /// Set treasury address
+    pub fn set_treasury(env: Env, new_treasury: Address) -> Result<(), SharedError> {
+        Self::require_admin(&env)?;
+
+        let old_treasury = env.storage().instance().get(&DataKey::Treasury);
+        env.storage()
+            .instance()
+            .set(&DataKey::Treasury, &new_treasury);
+        emit_treasury_changed(&env, old_treasury, &new_treasury);
+        extend_instance_ttl(&env);
+
+        Ok(())
+    }
+
+    /// Set the protocol registry address reported by `contract_info`
+    pub fn set_registry(env: Env, registry: Address) -> Result<(), SharedError> {
+        Self::require_admin(&env)?;
+        env.storage().instance().set(&DataKey::Registry, &registry);
+        extend_instance_ttl(&env);
+        Ok(())
+    }
+
+    /// Set admin address
+    pub fn set_admin(env: Env, new_admin: Address) -> Result<(), SharedError> {
+        Self::require_admin(&env)?;
+
+        env.storage().instance().set(&DataKey::Admin, &new_admin);
+        extend_instance_ttl(&env);
+
+        Ok(())
+    }
+
+    /// Pause/unpause claims
+    pub fn set_paused(env: Env, paused: bool) -> Result<(), SharedError> {
+        Self::require_admin(&env)?;
+
+        env.storage().instance().set(&DataKey::Paused, &paused);
+        extend_instance_ttl(&env);
+
+        Ok(())
+    }
+
+    /// Set how far storage TTLs are extended (see `TtlConfig`)
+    pub fn set_ttl_config(env: Env, config: TtlConfig) -> Result<(), SharedError> {
+        Self::require_admin(&env)?;
+
+        set_ttl_config(&env, &config)?;
+        extend_instance_ttl(&env);
+
+        Ok(())
+    }
+
+    // ────────────────────────────────────────────────────────────────────────
+    // Upgrades (admin only)
+    // ────────────────────────────────────────────────────────────────────────
+
+    /// Queue a WASM upgrade behind the upgrade timelock. Returns its eta.
+    pub fn queue_upgrade(env: Env, new_wasm_hash: BytesN<32>) -> Result<u64, SharedError> {
+        Self::require_admin(&env)?;
+        upgrade::queue(&env, new_wasm_hash)
+    }
+
+    /// Cancel the queued upgrade
+    pub fn cancel_upgrade(env: Env) -> Result<(), SharedError> {
+        Self::require_admin(&env)?;
+        upgrade::cancel(&env)
+    }
+
+    /// Switch to the queued WASM once the timelock has elapsed
+    pub fn upgrade(env: Env, new_wasm_hash: BytesN<32>) -> Result<(), SharedError> {
+        Self::require_admin(&env)?;
+        upgrade::execute(&env, new_wasm_hash)
+    }
+
+    /// Migrate storage to `STORAGE_VERSION` after an upgrade
+    pub fn migrate(env: Env) -> Result<u32, SharedError> {
+        Self::require_admin(&env)?;
+        upgrade::migrate(&env, STORAGE_VERSION, |_, _| Ok(()))
+    }
+
+    // ────────────────────────────────────────────────────────────────────────
+    // Query Functions
+    // ────────────────────────────────────────────────────────────────────────
+
+    /// Get the storage layout version
+    pub fn version(env: Env) -> u32 {
+        upgrade::version(&env)
+    }
+
+    /// Get the queued upgrade, if any
+    pub fn get_pending_upgrade(env: Env) -> Option<PendingUpgrade> {
+        upgrade::pending(&env)
+    }
+
+    /// Get a token's current airdrop
+    pub fn get_airdrop(env: Env, token: Address) -> Option<Airdrop> {
+        get_persistent(&env, &DataKey::Airdrop(token))
+    }
+
+    /// Check whether a leaf of a token's current airdrop was claimed
+    pub fn is_claimed(env: Env, token: Address, index: u32) -> bool {
+        let airdrop = match Self::get_airdrop(env.clone(), token.clone()) {
+            Some(airdrop) => airdrop,
+            None => return false,
+        };
+        let word: u128 = get_persistent(
+            &env,
+            &DataKey::Claimed(token, airdrop.round, index / BITS_PER_WORD),
+        )
+        .unwrap_or(0);
+        word & (1u128 << (index % BITS_PER_WORD)) != 0
+    }
+
+    /// Get admin address
+    pub fn admin(env: Env) -> Result<Address, SharedError> {
+        env.storage()
+            .instance()
+            .get(&DataKey::Admin)
+            .ok_or(SharedError::NotInitialized)
+    }
+
+    /// Get the storage TTL config
+    pub fn ttl_config(env: Env) -> TtlConfig {
+        get_ttl_config(&env)
+    }
+
+    /// Check if contract is paused
+    pub fn is_paused(env: Env) -> bool {
+        env.storage()
+            .instance()
+            .get(&DataKey::Paused)
+            .unwrap_or(false)
+    }
+
+    /// Describe this contract for explorers and wallets
+    pub fn contract_info(env: Env) -> Result<ContractInfo, SharedError> {
+        let admin = Self::admin(env.clone())?;
+        let info = ContractInfo::new(
+            &env,
+            "airdrop",
+            env!("CARGO_PKG_VERSION"),
+            admin,
+            Self::is_paused(env.clone()),
+            env.storage().instance().get(&DataKey::Registry),
+        );
+        Ok(info.with("default_claim_window", DEFAULT_CLAIM_WINDOW as i128))
+    }
+
+    // ────────────────────────────────────────────────────────────────────────
+    // Internal Functions
+    // ────────────────────────────────────────────────────────────────────────
+
+    fn require_initialized(env: &Env) -> Result<(), SharedError> {
+        let initialized: bool = env
+            .storage()
+            .instance()
+            .get(&DataKey::Initialized)
+            .unwrap_or(false);
+
+        if !initialized {
+            return Err(SharedError::NotInitialized);
+        }
+        Ok(())
+    }
+
+    fn require_not_paused(env: &Env) -> Result<(), SharedError> {
+        let paused: bool = env
+            .storage()
+            .instance()
+            .get(&DataKey::Paused)
+            .unwrap_or(false);
+
+        if paused {
+            return Err(SharedError::ContractPaused);
+        }
+        Ok(())
+    }
+
+    fn require_admin(env: &Env) -> Result<Address, SharedError> {
+        let admin: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::Admin)
+            .ok_or(SharedError::NotInitialized)?;
+
+        admin.require_auth();
+        Ok(admin)
+    }
+
+    fn load_open_airdrop(env: &Env, token: &Address) -> Result<Airdrop, SharedError> {
+        let airdrop: Airdrop = get_persistent(env, &DataKey::Airdrop(token.clone()))
+            .ok_or(SharedError::TokenNotFound)?;
+        if airdrop.closed {
+            return Err(SharedError::InvalidState);
+        }
+        Ok(airdrop)
+    }
+
+    fn save_airdrop(env: &Env, token: &Address, airdrop: &Airdrop) {
+        let key = DataKey::Airdrop(token.clone());
+        set_persistent(env, &key, airdrop);
+    }
+
+    fn leaf_hash(env: &Env, index: u32, account: &Address, amount: i128) -> BytesN<32> {
+        let mut data = Bytes::from_array(env, &index.to_be_bytes());
+        data.append(&account.clone().to_xdr(env));
+        data.extend_from_array(&amount.to_be_bytes());
+        env.crypto().sha256(&data).into()
+    }
+
+    fn hash_pair(env: &Env, a: &BytesN<32>, b: &BytesN<32>) -> BytesN<32> {
+        let (first, second) = if a.to_array() <= b.to_array() {
+            (a, b)
+        } else {
+            (b, a)
+        };
+        let mut data = Bytes::from_array(env, &first.to_array());
+        data.extend_from_array(&second.to_array());
+        env.crypto().sha256(&data).into()
+    }
+
+    fn process_proof(env: &Env, leaf: BytesN<32>, proof: &Vec<BytesN<32>>) -> BytesN<32> {
+        let mut current = leaf;
+        for sibling in proof.iter() {
+            current = Self::hash_pair(env, &current, &sibling);
+        }
+        current
+    }
+}
+
+// ════════════════════════════════════════════════════════════════════════════
+// Tests
+// ════════════════════════════════════════════════════════════════════════════
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use astro_core_shared::testutils::create_token;
+    use soroban_sdk::testutils::{Address as _, Ledger as _};
+
+    /// Four-leaf tree; returns the root and each leaf's proof
+    fn build_tree(
+        env: &Env,
+        accounts: &[Address; 4],
+        amounts: &[i128; 4],
+    ) -> (BytesN<32>, [Vec<BytesN<32>>; 4]) {
+        let leaves: [BytesN<32>; 4] = core::array::from_fn(|i| {
+            MerkleAirdrop::leaf_hash(env, i as u32, &accounts[i], amounts[i])
+        });
+        let left = MerkleAirdrop::hash_pair(env, &leaves[0], &leaves[1]);
+        let right = MerkleAirdrop::hash_pair(env, &leaves[2], &leaves[3]);
+        let root = MerkleAirdrop::hash_pair(env, &left, &right);
+        let proofs = [
+            Vec::from_array(env, [leaves[1].clone(), right.clone()]),
+            Vec::from_array(env, [leaves[0].clone(), right.clone()]),
+            Vec::from_array(env, [leaves[3].clone(), left.clone()]),
+            Vec::from_array(env, [leaves[2].clone(), left.clone()]),
+        ];
+        (root, proofs)
+    }
+
+    #[test]
+    fn test_claim_and_clawback() {
+        let env = Env::default();
+        env.mock_all_auths();
+        env.ledger().set_timestamp(1_000);
+
+        let admin = Address::generate(&env);
+        let treasury = Address::generate(&env);
+        let token = create_token(&env, &admin);
+        token.mint(&admin, 10_000);
+
+        let client = MerkleAirdropClient::new(&env, &env.register(MerkleAirdrop, ()));
+        client.initialize(&admin, &treasury);
+
+        let accounts: [Address; 4] = core::array::from_fn(|_| Address::generate(&env));
+        let amounts = [100, 200, 300, 400];
+        let (root, proofs) = build_tree(&env, &accounts, &amounts);
+        client.set_root(&token.address, &root, &1_000);
+        assert_eq!(token.balance(&client.address), 1_000);
+
+        // Anyone can submit a claim; tokens go to the account
+        client.claim(&token.address, &1, &accounts[1], &200, &proofs[1]);
+        assert_eq!(token.balance(&accounts[1]), 200);
+        assert!(client.is_claimed(&token.address, &1));
+        assert!(!client.is_claimed(&token.address, &0));
+
+        // Double claim, wrong amount, wrong proof
+        assert!(client
+            .try_claim(&token.address, &1, &accounts[1], &200, &proofs[1])
+            .is_err());
+        assert!(client
+            .try_claim(&token.address, &0, &accounts[0], &101, &proofs[0])
+            .is_err());
+        assert!(client
+            .try_claim(&token.address, &2, &accounts[2], &300, &proofs[3])
+            .is_err());
+
+        client.claim(&token.address, &2, &accounts[2], &300, &proofs[2]);
+
+        // No clawback before the deadline, no claims after it
+        assert!(client.try_clawback(&token.address).is_err());
+        let deadline = client.get_airdrop(&token.address).unwrap().deadline;
+        env.ledger().set_timestamp(deadline + 1);
+        assert!(client
+            .try_claim(&token.address, &0, &accounts[0], &100, &proofs[0])
+            .is_err());
+
+        assert_eq!(client.clawback(&token.address), 500);
+        assert_eq!(token.balance(&treasury), 500);
+        assert!(client.get_airdrop(&token.address).unwrap().closed);
+        assert!(client.try_clawback(&token.address).is_err());
+    }
+
+    #[test]
+    fn test_new_round_after_clawback() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let admin = Address::generate(&env);
+        let treasury = Address::generate(&env);
+        let token = create_token(&env, &admin);
+        token.mint(&admin, 10_000);
+
+        let client = MerkleAirdropClient::new(&env, &env.register(MerkleAirdrop, ()));
+        client.initialize(&admin, &treasury);
+
+        let accounts: [Address; 4] = core::array::from_fn(|_| Address::generate(&env));
+        let amounts = [100, 200, 300, 400];
+        let (root, proofs) = build_tree(&env, &accounts, &amounts);
+        client.set_root(&token.address, &root, &1_000);
+        client.claim(&token.address, &0, &accounts[0], &100, &proofs[0]);
+
+        // One open airdrop per token
+        assert!(client.try_set_root(&token.address, &root, &1_000).is_err());
+
+        client.set_deadline(&token.address, &10);
+        env.ledger().set_timestamp(11);
+        client.clawback(&token.address);
+
+        // A new round has a fresh claim bitmap
+        client.set_root(&token.address, &root, &1_000);
+        assert_eq!(client.get_airdrop(&token.address).unwrap().round, 1);
+        assert!(!client.is_claimed(&token.address, &0));
+        client.claim(&token.address, &0, &accounts[0], &100, &proofs[0]);
+        assert_eq!(token.balance(&accounts[0]), 200);
+    }
+}
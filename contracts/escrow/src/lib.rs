@@ -0,0 +1,858 @@
+#![no_std]
+
+//! # Escrow Contract
+//!
+//! OTC deals: two parties atomically swap fixed amounts of two tokens.
+//!
+//! ## Features
+//! - Maker deposits the offered tokens when creating a deal
+//! - Optional designated taker; otherwise anyone may accept
+//! - Deals expire; expired deals can be cancelled by anyone (funds always
+//!   return to the maker)
+//! - Optional fee on both legs, routed to the fee distributor (fees below
+//!   its minimum, or that it rejects, are held until a later hand-off)
+//!
+//! The fee rate is fixed into each deal at creation, so a later fee change
+//! never alters terms a maker already committed to.
+
+use astro_core_shared::{
+    events::{emit_fee_distributor_changed, EventBuilder},
+    interfaces::{FeeDistributorClient, IntoSharedResult},
+    limits::MIN_FEE_AMOUNT,
+    math::{apply_bps, safe_add, safe_sub},
+    types::{
+        extend_instance_ttl, get_persistent, get_ttl_config, set_persistent, set_ttl_config,
+        ContractInfo, FeeConfig, SharedError, TtlConfig,
+    },
+    upgrade::{self, PendingUpgrade},
+};
+use soroban_sdk::{
+    auth::{ContractContext, InvokerContractAuthEntry, SubContractInvocation},
+    contract, contractimpl, contracttype, token, vec, Address, BytesN, Env, IntoVal, Symbol, Vec,
+};
+
+// ════════════════════════════════════════════════════════════════════════════
+// Constants
+// ════════════════════════════════════════════════════════════════════════════
+
+/// Storage layout version of this code (see `migrate`)
+const STORAGE_VERSION: u32 = 1;
+
+/// Longest a deal may stay open (180 days)
+const MAX_DEAL_DURATION: u64 = 180 * 24 * 60 * 60;
+
+// ════════════════════════════════════════════════════════════════════════════
+// Storage Keys
+// ════════════════════════════════════════════════════════════════════════════
+
+#[contracttype]
+#[derive(Clone, Debug)]
+pub enum DataKey {
+    /// Admin address
+    Admin,
+    /// Whether contract is initialized
+    Initialized,
+    /// Whether contract is paused
+    Paused,
+    /// Fee distributor receiving deal fees
+    FeeDistributor,
+    /// Fee charged on each leg of new deals, in basis points
+    FeeBps,
+    /// Number of deals created (next deal id)
+    DealCount,
+    /// Deal by id (u64 -> Deal)
+    Deal(u64),
+    /// Protocol registry address (reported by `contract_info`)
+    Registry,
+    /// Fees held in the escrow: dust below `MIN_FEE_AMOUNT` and fees the fee
+    /// distributor rejected (Address -> i128)
+    PendingFees(Address),
+}
+
+// ════════════════════════════════════════════════════════════════════════════
+// Types
+// ════════════════════════════════════════════════════════════════════════════
+
+/// Deal lifecycle
+#[contracttype]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[repr(u32)]
+pub enum DealStatus {
+    /// Deposited and waiting for a taker
+    Open = 0,
+    /// Swapped
+    Completed = 1,
+    /// Refunded to the maker
+    Cancelled = 2,
+}
+
+/// An OTC deal
+#[contracttype]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Deal {
+    /// Deal id
+    pub id: u64,
+    /// Party offering `amount_give` of `token_give`
+    pub maker: Address,
+    /// Only address allowed to accept (anyone if `None`)
+    pub taker: Option<Address>,
+    /// Token deposited by the maker
+    pub token_give: Address,
+    /// Amount deposited by the maker
+    pub amount_give: i128,
+    /// Token the maker wants
+    pub token_want: Address,
+    /// Amount the maker wants
+    pub amount_want: i128,
+    /// Fee on each leg in basis points, fixed at creation
+    pub fee_bps: u32,
+    /// Last timestamp the deal can be accepted
+    pub expiry: u64,
+    /// Current status
+    pub status: DealStatus,
+}
+
+// ════════════════════════════════════════════════════════════════════════════
+// Contract Implementation
+// ════════════════════════════════════════════════════════════════════════════
+
+#[contract]
+pub struct Escrow;
+
+#[contractimpl]
+impl Escrow {
+    // ────────────────────────────────────────────────────────────────────────
+    // Initialization
+    // ────────────────────────────────────────────────────────────────────────
+
+    /// Initialize the escrow
+    pub fn initialize(
+        env: Env,
+        admin: Address,
+        fee_distributor: Address,
+        fee_bps: u32,
+    ) -> Result<(), SharedError> {
+        if env.storage().instance().has(&DataKey::Initialized) {
+            return Err(SharedError::AlreadyInitialized);
+        }
+        if fee_bps > FeeConfig::MAX_FEE_BPS {
+            return Err(SharedError::InvalidBps);
+        }
+
+        env.storage().instance().set(&DataKey::Admin, &admin);
+        env.storage()
+            .instance()
+            .set(&DataKey::FeeDistributor, &fee_distributor);
+        env.storage().instance().set(&DataKey::FeeBps, &fee_bps);
+        env.storage().instance().set(&DataKey::DealCount, &0_u64);
+        env.storage().instance().set(&DataKey::Initialized, &true);
+        upgrade::init_version(&env, STORAGE_VERSION);
+        env.storage().instance().set(&DataKey::Paused, &false);
+
+        extend_instance_ttl(&env);
+
+        let events = EventBuilder::new(&env);
+        events.publish(
+            "escrow",
+            "initialized",
+            (admin, fee_distributor, fee_bps, env.ledger().timestamp()),
+        );
+
+        Ok(())
+    }
+
+    // ────────────────────────────────────────────────────────────────────────
+    // Deals
+    // ────────────────────────────────────────────────────────────────────────
+
+    /// Create a deal, depositing `amount_give` of `token_give` from the maker.
+    /// Returns the deal id.
+    #[allow(clippy::too_many_arguments)]
+    pub fn create_deal(
+        env: Env,
+        maker: Address,
+        token_give: Address,
+        amount_give: i128,
+        token_want: Address,
+        amount_want: i128,
+        taker: Option<Address>,
+        expiry: u64,
+    ) -> Result<u64, SharedError> {
+        Self::require_initialized(&env)?;
+        Self::require_not_paused(&env)?;
+        maker.require_auth();
+
+        if amount_give <= 0 || amount_want <= 0 {
+            return Err(SharedError::InvalidAmount);
+        }
+        if token_give == token_want || taker.as_ref() == Some(&maker) {
+            return Err(SharedError::InvalidAddress);
+        }
+        let now = env.ledger().timestamp();
+        if expiry <= now || expiry - now > MAX_DEAL_DURATION {
+            return Err(SharedError::InvalidTimestamp);
+        }
+
+        token::Client::new(&env, &token_give).transfer(
+            &maker,
+            env.current_contract_address(),
+            &amount_give,
+        );
+
+        let id: u64 = env
+            .storage()
+            .instance()
+            .get(&DataKey::DealCount)
+            .unwrap_or(0);
+        env.storage().instance().set(&DataKey::DealCount, &(id + 1));
+
+        let deal = Deal {
+            id,
+            maker: maker.clone(),
+            taker: taker.clone(),
+            token_give: token_give.clone(),
+            amount_give,
+            token_want: token_want.clone(),
+            amount_want,
+            fee_bps: Self::fee_bps(env.clone()),
+            expiry,
+            status: DealStatus::Open,
+        };
+        Self::save_deal(&env, &deal);
+
+        let events = EventBuilder::new(&env);
+        events.publish(
+            "escrow",
+            "deal_created",
+            (
+                id,
+                maker,
+                taker,
+                token_give,
+                amount_give,
+                token_want,
+                amount_want,
+                expiry,
+            ),
+        );
+
+        extend_instance_ttl(&env);
+        Ok(id)
+    }
+
+    /// Accept an open deal: the taker pays `amount_want` and receives
+    /// `amount_give`, each less the deal's fee
+    pub fn accept_deal(env: Env, taker: Address, deal_id: u64) -> Result<(), SharedError> {
+        Self::require_initialized(&env)?;
+        Self::require_not_paused(&env)?;
+        taker.require_auth();
+
+        let mut deal = Self::load_open_deal(&env, deal_id)?;
+        if env.ledger().timestamp() > deal.expiry {
+            return Err(SharedError::DeadlineExpired);
+        }
+        if taker == deal.maker {
+            return Err(SharedError::InvalidAddress);
+        }
+        if let Some(designated) = &deal.taker {
+            if *designated != taker {
+                return Err(SharedError::Unauthorized);
+            }
+        }
+
+        let fee_want = apply_bps(deal.amount_want, deal.fee_bps)?;
+        let fee_give = apply_bps(deal.amount_give, deal.fee_bps)?;
+
+        deal.status = DealStatus::Completed;
+        deal.taker = Some(taker.clone());
+        Self::save_deal(&env, &deal);
+
+        let this = env.current_contract_address();
+        let want = token::Client::new(&env, &deal.token_want);
+        want.transfer(&taker, &deal.maker, &safe_sub(deal.amount_want, fee_want)?);
+        if fee_want > 0 {
+            want.transfer(&taker, &this, &fee_want);
+            Self::route_fee(&env, &deal.token_want, fee_want)?;
+        }
+
+        token::Client::new(&env, &deal.token_give).transfer(
+            &this,
+            &taker,
+            &safe_sub(deal.amount_give, fee_give)?,
+        );
+        if fee_give > 0 {
+            Self::route_fee(&env, &deal.token_give, fee_give)?;
+        }
+
+        let events = EventBuilder::new(&env);
+        events.publish(
+            "escrow",
+            "deal_accepted",
+            (
+                deal_id,
+                deal.maker,
+                taker,
+                fee_give,
+                fee_want,
+                env.ledger().timestamp(),
+            ),
+        );
+
+        extend_instance_ttl(&env);
+        Ok(())
+    }
+
+    /// Cancel an open deal and refund the maker. Only the maker may cancel
+    /// before expiry; anyone may cancel after it.
+    pub fn cancel_deal(env: Env, caller: Address, deal_id: u64) -> Result<(), SharedError> {
+        Self::require_initialized(&env)?;
+        caller.require_auth();
+
+        let mut deal = Self::load_open_deal(&env, deal_id)?;
+        if caller != deal.maker && env.ledger().timestamp() <= deal.expiry {
+            return Err(SharedError::Unauthorized);
+        }
+
+        deal.status = DealStatus::Cancelled;
+        Self::save_deal(&env, &deal);
+
+        token::Client::new(&env, &deal.token_give).transfer(
+            &env.current_contract_address(),
+            &deal.maker,
+            &deal.amount_give,
+        );
+
+        let events = EventBuilder::new(&env);
+        events.publish(
+            "escrow",
+            "deal_cancelled",
+            (deal_id, deal.maker, caller, env.ledger().timestamp()),
+        );
+
+        extend_instance_ttl(&env);
+        Ok(())
+    }
+
+    /// Retry handing fees the fee distributor rejected earlier (paused,
+    /// source not allowlisted). Anyone can call it; fails while the
+    /// distributor still rejects them. Returns the amount sent (zero while
+    /// the held amount is below `MIN_FEE_AMOUNT`).
+    pub fn flush_fees(env: Env, token: Address) -> Result<i128, SharedError> {
+        Self::require_initialized(&env)?;
+
+        let fee_distributor = Self::load_fee_distributor(&env)?;
+        let key = DataKey::PendingFees(token.clone());
+        let pending: i128 = get_persistent(&env, &key).unwrap_or(0);
+        if pending < MIN_FEE_AMOUNT {
+            return Ok(0);
+        }
+
+        Self::authorize_transfer(&env, &token, &fee_distributor, pending);
+        FeeDistributorClient::new(&env, &fee_distributor)
+            .try_receive_fees(&env.current_contract_address(), &token, &pending)
+            .into_shared()?;
+        set_persistent(&env, &key, &0_i128);
+
+        let events = EventBuilder::new(&env);
+        events.publish(
+            "escrow",
+            "fees_flushed",
+            (token, pending, env.ledger().timestamp()),
+        );
+
+        extend_instance_ttl(&env);
+        Ok(pending)
+    }
+
+    // ────────────────────────────────────────────────────────────────────────
+    // Admin Functions
+    // ────────────────────────────────────────────────────────────────────────
+
+    /// Set the fee charged on each leg of new deals
+    pub fn set_fee_bps(env: Env, fee_bps: u32) -> Result<(), SharedError> {
+        Self::require_admin(&env)?;
+
+        if fee_bps > FeeConfig::MAX_FEE_BPS {
+            return Err(SharedError::InvalidBps);
+        }
+        env.storage().instance().set(&DataKey::FeeBps, &fee_bps);
+
+        let events = EventBuilder::new(&env);
+        events.publish("escrow", "fee_set", (fee_bps, env.ledger().timestamp()));
+
+        extend_instance_ttl(&env);
+        Ok(())
+    }
+
+    /// Set fee distributor address
+    pub fn set_fee_distributor(env: Env, fee_distributor: Address) -> Result<(), SharedError> {
+        Self::require_admin(&env)?;
+
+        let old_fee_distributor = env.storage().instance().get(&DataKey::FeeDistributor);
+        env.storage()
+            .instance()
+            .set(&DataKey::FeeDistributor, &fee_distributor);
+        emit_fee_distributor_changed(&env, old_fee_distributor, &fee_distributor);
+        extend_instance_ttl(&env);
+
+        Ok(())
+    }
+
+    /// Set the protocol registry address reported by `contract_info`
+    pub fn set_registry(env: Env, registry: Address) -> Result<(), SharedError> {
+        Self::require_admin(&env)?;
+        env.storage().instance().set(&DataKey::Registry, &registry);
+        extend_instance_ttl(&env);
+        Ok(())
+    }
+
+    /// Set admin address
+    pub fn set_admin(env: Env, new_admin: Address) -> Result<(), SharedError> {
+        Self::require_admin(&env)?;
+
+        env.storage().instance().set(&DataKey::Admin, &new_admin);
+        extend_instance_ttl(&env);
+
+        Ok(())
+    }
+
+    /// Pause/unpause deal creation and acceptance (cancels stay open)
+    pub fn set_paused(env: Env, paused: bool) -> Result<(), SharedError> {
+        Self::require_admin(&env)?;
+
+        env.storage().instance().set(&DataKey::Paused, &paused);
+        extend_instance_ttl(&env);
+
+        Ok(())
+    }
+
+    /// Set how far storage TTLs are extended (see `TtlConfig`)
+    pub fn set_ttl_config(env: Env, config: TtlConfig) -> Result<(), SharedError> {
+        Self::require_admin(&env)?;
+
+        set_ttl_config(&env, &config)?;
+        extend_instance_ttl(&env);
+
+        Ok(())
+    }
+
+    // ────────────────────────────────────────────────────────────────────────
+    // Upgrades (admin only)
+    // ────────────────────────────────────────────────────────────────────────
+
+    /// Queue a WASM upgrade behind the upgrade timelock. Returns its eta.
+    pub fn queue_upgrade(env: Env, new_wasm_hash: BytesN<32>) -> Result<u64, SharedError> {
+        Self::require_admin(&env)?;
+        upgrade::queue(&env, new_wasm_hash)
+    }
+
+    /// Cancel the queued upgrade
+    pub fn cancel_upgrade(env: Env) -> Result<(), SharedError> {
+        Self::require_admin(&env)?;
+        upgrade::cancel(&env)
+    }
+
+    /// Switch to the queued WASM once the timelock has elapsed
+    pub fn upgrade(env: Env, new_wasm_hash: BytesN<32>) -> Result<(), SharedError> {
+        Self::require_admin(&env)?;
+        upgrade::execute(&env, new_wasm_hash)
+    }
+
+    /// Migrate storage to `STORAGE_VERSION` after an upgrade
+    pub fn migrate(env: Env) -> Result<u32, SharedError> {
+        Self::require_admin(&env)?;
+        upgrade::migrate(&env, STORAGE_VERSION, |_, _| Ok(()))
+    }
+
+    // ────────────────────────────────────────────────────────────────────────
+    // Query Functions
+    // ────────────────────────────────────────────────────────────────────────
+
+    /// Get the storage layout version
+    pub fn version(env: Env) -> u32 {
+        upgrade::version(&env)
+    }
+
+    /// Get the queued upgrade, if any
+    pub fn get_pending_upgrade(env: Env) -> Option<PendingUpgrade> {
+        upgrade::pending(&env)
+    }
+
+    /// Get a deal
+    pub fn get_deal(env: Env, deal_id: u64) -> Option<Deal> {
+        get_persistent(&env, &DataKey::Deal(deal_id))
+    }
+
+    /// Fees held in the escrow until the next successful hand-off
+    pub fn get_pending_fees(env: Env, token: Address) -> i128 {
+        get_persistent(&env, &DataKey::PendingFees(token)).unwrap_or(0)
+    }
+
+    /// Get the number of deals created
+    pub fn deal_count(env: Env) -> u64 {
+        env.storage()
+            .instance()
+            .get(&DataKey::DealCount)
+            .unwrap_or(0)
+    }
+
+    /// Get the fee for new deals in basis points
+    pub fn fee_bps(env: Env) -> u32 {
+        env.storage().instance().get(&DataKey::FeeBps).unwrap_or(0)
+    }
+
+    /// Get admin address
+    pub fn admin(env: Env) -> Result<Address, SharedError> {
+        env.storage()
+            .instance()
+            .get(&DataKey::Admin)
+            .ok_or(SharedError::NotInitialized)
+    }
+
+    /// Get the storage TTL config
+    pub fn ttl_config(env: Env) -> TtlConfig {
+        get_ttl_config(&env)
+    }
+
+    /// Check if contract is paused
+    pub fn is_paused(env: Env) -> bool {
+        env.storage()
+            .instance()
+            .get(&DataKey::Paused)
+            .unwrap_or(false)
+    }
+
+    /// Describe this contract for explorers and wallets
+    pub fn contract_info(env: Env) -> Result<ContractInfo, SharedError> {
+        let admin = Self::admin(env.clone())?;
+        let info = ContractInfo::new(
+            &env,
+            "escrow",
+            env!("CARGO_PKG_VERSION"),
+            admin,
+            Self::is_paused(env.clone()),
+            env.storage().instance().get(&DataKey::Registry),
+        );
+        Ok(info
+            .with("fee_bps", Self::fee_bps(env.clone()) as i128)
+            .with("deal_count", Self::deal_count(env) as i128))
+    }
+
+    // ────────────────────────────────────────────────────────────────────────
+    // Internal Functions
+    // ────────────────────────────────────────────────────────────────────────
+
+    fn require_initialized(env: &Env) -> Result<(), SharedError> {
+        let initialized: bool = env
+            .storage()
+            .instance()
+            .get(&DataKey::Initialized)
+            .unwrap_or(false);
+
+        if !initialized {
+            return Err(SharedError::NotInitialized);
+        }
+        Ok(())
+    }
+
+    fn require_not_paused(env: &Env) -> Result<(), SharedError> {
+        let paused: bool = env
+            .storage()
+            .instance()
+            .get(&DataKey::Paused)
+            .unwrap_or(false);
+
+        if paused {
+            return Err(SharedError::ContractPaused);
+        }
+        Ok(())
+    }
+
+    fn require_admin(env: &Env) -> Result<(), SharedError> {
+        let admin: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::Admin)
+            .ok_or(SharedError::NotInitialized)?;
+
+        admin.require_auth();
+        Ok(())
+    }
+
+    fn load_open_deal(env: &Env, deal_id: u64) -> Result<Deal, SharedError> {
+        let deal: Deal =
+            get_persistent(env, &DataKey::Deal(deal_id)).ok_or(SharedError::InvalidState)?;
+        if deal.status != DealStatus::Open {
+            return Err(SharedError::AlreadyExecuted);
+        }
+        Ok(deal)
+    }
+
+    fn save_deal(env: &Env, deal: &Deal) {
+        let key = DataKey::Deal(deal.id);
+        set_persistent(env, &key, deal);
+    }
+
+    /// Hand a fee held by the escrow to the fee distributor. Dust below the
+    /// distributor's minimum and fees it rejects are held in the escrow, and
+    /// go out with the next hand-off that reaches the minimum (or `flush_fees`).
+    fn route_fee(env: &Env, token: &Address, amount: i128) -> Result<(), SharedError> {
+        let fee_distributor = Self::load_fee_distributor(env)?;
+        let key = DataKey::PendingFees(token.clone());
+        let held = safe_add(get_persistent(env, &key).unwrap_or(0), amount)?;
+        let mut sent = false;
+        if held >= MIN_FEE_AMOUNT {
+            Self::authorize_transfer(env, token, &fee_distributor, held);
+            sent = FeeDistributorClient::new(env, &fee_distributor)
+                .try_receive_fees(&env.current_contract_address(), token, &held)
+                .into_shared()
+                .is_ok();
+        }
+        set_persistent(env, &key, &if sent { 0 } else { held });
+        Ok(())
+    }
+
+    fn load_fee_distributor(env: &Env) -> Result<Address, SharedError> {
+        env.storage()
+            .instance()
+            .get(&DataKey::FeeDistributor)
+            .ok_or(SharedError::ExternalContractNotSet)
+    }
+
+    /// Let a contract called next pull `amount` of `token` from the escrow
+    fn authorize_transfer(env: &Env, token: &Address, to: &Address, amount: i128) {
+        let this = env.current_contract_address();
+        env.authorize_as_current_contract(vec![
+            env,
+            InvokerContractAuthEntry::Contract(SubContractInvocation {
+                context: ContractContext {
+                    contract: token.clone(),
+                    fn_name: Symbol::new(env, "transfer"),
+                    args: (this, to.clone(), amount).into_val(env),
+                },
+                sub_invocations: Vec::new(env),
+            }),
+        ]);
+    }
+}
+
+// ════════════════════════════════════════════════════════════════════════════
+// Tests
+// ════════════════════════════════════════════════════════════════════════════
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use astro_core_shared::testutils::{create_token, TestToken};
+    use soroban_sdk::testutils::{Address as _, Ledger as _};
+
+    /// Minimal fee distributor: pulls fees from the escrow
+    #[contract]
+    pub struct MockFeeDistributor;
+
+    #[contractimpl]
+    impl MockFeeDistributor {
+        pub fn set_paused(env: Env, paused: bool) {
+            env.storage().instance().set(&DataKey::Paused, &paused);
+        }
+
+        pub fn receive_fees(env: Env, caller: Address, token: Address, amount: i128) {
+            let paused: bool = env
+                .storage()
+                .instance()
+                .get(&DataKey::Paused)
+                .unwrap_or(false);
+            assert!(!paused, "paused");
+            caller.require_auth();
+            token::Client::new(&env, &token).transfer(
+                &caller,
+                env.current_contract_address(),
+                &amount,
+            );
+        }
+    }
+
+    struct Setup<'a> {
+        env: Env,
+        client: EscrowClient<'a>,
+        give: TestToken<'a>,
+        want: TestToken<'a>,
+        fee_distributor: Address,
+        maker: Address,
+        taker: Address,
+    }
+
+    fn setup<'a>() -> Setup<'a> {
+        let env = Env::default();
+        env.mock_all_auths();
+        env.ledger().set_timestamp(1_000);
+
+        let admin = Address::generate(&env);
+        let maker = Address::generate(&env);
+        let taker = Address::generate(&env);
+        let give = create_token(&env, &admin);
+        let want = create_token(&env, &admin);
+        give.mint(&maker, 1_000_000);
+        want.mint(&taker, 1_000_000);
+
+        let fee_distributor = env.register(MockFeeDistributor, ());
+        let client = EscrowClient::new(&env, &env.register(Escrow, ()));
+        client.initialize(&admin, &fee_distributor, &50);
+
+        Setup {
+            env,
+            client,
+            give,
+            want,
+            fee_distributor,
+            maker,
+            taker,
+        }
+    }
+
+    #[test]
+    fn test_create_and_accept_deal() {
+        let s = setup();
+        let id = s.client.create_deal(
+            &s.maker,
+            &s.give.address,
+            &100_000,
+            &s.want.address,
+            &400_000,
+            &None,
+            &2_000,
+        );
+        assert_eq!(s.give.balance(&s.client.address), 100_000);
+
+        // The fee is fixed into the deal
+        s.client.set_fee_bps(&100);
+        s.client.accept_deal(&s.taker, &id);
+
+        // 0.5% of each leg goes to the fee distributor
+        assert_eq!(s.want.balance(&s.maker), 398_000);
+        assert_eq!(s.give.balance(&s.taker), 99_500);
+        assert_eq!(s.want.balance(&s.fee_distributor), 2_000);
+        assert_eq!(s.give.balance(&s.fee_distributor), 500);
+        assert_eq!(s.give.balance(&s.client.address), 0);
+
+        let deal = s.client.get_deal(&id).unwrap();
+        assert_eq!(deal.status, DealStatus::Completed);
+        assert_eq!(deal.taker, Some(s.taker.clone()));
+        assert!(s.client.try_accept_deal(&s.taker, &id).is_err());
+        assert!(s.client.try_cancel_deal(&s.maker, &id).is_err());
+    }
+
+    #[test]
+    fn test_fees_held_until_distributor_accepts() {
+        let s = setup();
+        let distributor = MockFeeDistributorClient::new(&s.env, &s.fee_distributor);
+        distributor.set_paused(&true);
+
+        // A rejected hand-off doesn't block the deal; the fees wait here
+        let id = s.client.create_deal(
+            &s.maker,
+            &s.give.address,
+            &100_000,
+            &s.want.address,
+            &400_000,
+            &None,
+            &2_000,
+        );
+        s.client.accept_deal(&s.taker, &id);
+        assert_eq!(s.want.balance(&s.maker), 398_000);
+        assert_eq!(s.client.get_pending_fees(&s.want.address), 2_000);
+        assert_eq!(s.client.get_pending_fees(&s.give.address), 500);
+        assert!(s.client.try_flush_fees(&s.want.address).is_err());
+
+        distributor.set_paused(&false);
+        assert_eq!(s.client.flush_fees(&s.want.address), 2_000);
+        assert_eq!(s.want.balance(&s.fee_distributor), 2_000);
+        assert_eq!(s.client.get_pending_fees(&s.want.address), 0);
+        assert_eq!(s.client.flush_fees(&s.want.address), 0);
+
+        // Dust accumulates until it reaches the minimum
+        let id = s.client.create_deal(
+            &s.maker,
+            &s.give.address,
+            &10_000,
+            &s.want.address,
+            &10_000,
+            &None,
+            &2_000,
+        );
+        s.client.accept_deal(&s.taker, &id);
+        assert_eq!(s.client.get_pending_fees(&s.want.address), 50);
+        assert_eq!(s.want.balance(&s.fee_distributor), 2_000);
+        assert_eq!(s.client.flush_fees(&s.want.address), 0);
+
+        let id = s.client.create_deal(
+            &s.maker,
+            &s.give.address,
+            &10_000,
+            &s.want.address,
+            &10_000,
+            &None,
+            &2_000,
+        );
+        s.client.accept_deal(&s.taker, &id);
+        assert_eq!(s.client.get_pending_fees(&s.want.address), 0);
+        assert_eq!(s.want.balance(&s.fee_distributor), 2_100);
+        // The held give-side fees went out with the first deal's dust
+        assert_eq!(s.give.balance(&s.fee_distributor), 550);
+        assert_eq!(s.client.get_pending_fees(&s.give.address), 50);
+    }
+
+    #[test]
+    fn test_designated_taker_and_expiry() {
+        let s = setup();
+        let id = s.client.create_deal(
+            &s.maker,
+            &s.give.address,
+            &100_000,
+            &s.want.address,
+            &400_000,
+            &Some(s.taker.clone()),
+            &2_000,
+        );
+
+        let stranger = Address::generate(&s.env);
+        assert!(s.client.try_accept_deal(&stranger, &id).is_err());
+        assert!(s.client.try_cancel_deal(&stranger, &id).is_err());
+
+        // Expired deals can't be accepted; anyone may return the deposit
+        s.env.ledger().set_timestamp(2_001);
+        assert!(s.client.try_accept_deal(&s.taker, &id).is_err());
+        s.client.cancel_deal(&stranger, &id);
+        assert_eq!(s.give.balance(&s.maker), 1_000_000);
+        assert_eq!(
+            s.client.get_deal(&id).unwrap().status,
+            DealStatus::Cancelled
+        );
+
+        // Invalid terms
+        assert!(s
+            .client
+            .try_create_deal(
+                &s.maker,
+                &s.give.address,
+                &100_000,
+                &s.give.address,
+                &400_000,
+                &None,
+                &3_000,
+            )
+            .is_err());
+        assert!(s
+            .client
+            .try_create_deal(
+                &s.maker,
+                &s.give.address,
+                &100_000,
+                &s.want.address,
+                &400_000,
+                &None,
+                &2_001,
+            )
+            .is_err());
+    }
+}
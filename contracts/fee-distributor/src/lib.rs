@@ -7,14 +7,77 @@
 //! - Staking Pool (30% default)
 //! - Burn address (20% default)
 //!
-//! Supports multiple tokens and configurable distribution ratios.
+//! Supports multiple tokens and a configurable recipient list, so further
+//! wallets (LP incentives, dev fund, ...) can be added without redeploying.
+//!
+//! ## Lifecycle Modes
+//! - `Bootstrap`: deployed before the staking pool exists; no staking recipient
+//! - `Normal`: at least one staking recipient
+//! - `Sunset`: winding down; staking recipients removed, no way back
+//!
+//! Transitions only move forward (Bootstrap -> Normal -> Sunset) and swap in a
+//! config that is valid for the new mode.
+//!
+//! ## Fee Conversion
+//! Tokens with a conversion route are swapped into the canonical reward token
+//! through their AMM pair before being split, so stakers are not paid in
+//! tokens they don't want.
+//!
+//! ## Epochs
+//! With an epoch length set, each token distributes at most once per epoch
+//! (epoch `n` covers `[n * length, (n + 1) * length)`), unless pending fees
+//! exceed the bypass threshold. Per-epoch totals are kept as history.
+//!
+//! ## Circuit Breaker
+//! A distribution larger than the config's `max_distribution_per_call`, or
+//! one that would take a token past `max_distribution_per_day`, is not paid
+//! out: distributions pause and a `circuit_breaker` alert is emitted so the
+//! admin can investigate before unpausing. Fee intake keeps running.
 
 use astro_core_shared::{
-    events::{emit_distribution, EventBuilder},
-    math::{safe_add, safe_div, safe_mul, BPS_DENOMINATOR},
-    types::{extend_instance_ttl, DistributionConfig, DistributionResult, SharedError},
+    access_control::{self, CONFIG_ADMIN, PAUSER, TREASURER, UPGRADER},
+    events::{
+        emit_config_updated, emit_distribution, emit_fee_received, set_compact_mask, EventBuilder,
+    },
+    interfaces::{
+        AmmPairClient, IntoSharedResult, OracleClient, StakingPoolClient, TokenRegistryClient,
+    },
+    limits::{validate_amount, MIN_FEE_AMOUNT, NO_MAX},
+    math::{safe_add, safe_div, safe_mul, safe_sub, BPS_DENOMINATOR},
+    ownership,
+    pausable::{self, PauseConfig, PauseGroup},
+    types::{
+        check_optional_deadline, extend_instance_ttl, extend_persistent_ttl, get_persistent,
+        get_ttl_config, require_distinct, set_persistent, set_ttl_config, Bps, BurnMode,
+        ContractInfo, DistributionConfig, DistributionRecipient, DistributionResult, RecipientKind,
+        SharedError, TokenSolvency, TtlConfig,
+    },
+    upgrade::{self, PendingUpgrade},
+};
+use soroban_sdk::{
+    auth::{ContractContext, InvokerContractAuthEntry, SubContractInvocation},
+    contract, contractimpl, contracttype, panic_with_error, token, vec, Address, Bytes, BytesN,
+    Env, IntoVal, Map, Symbol, Vec,
 };
-use soroban_sdk::{contract, contractimpl, contracttype, token, Address, Env, Vec};
+
+// ════════════════════════════════════════════════════════════════════════════
+// Constants
+// ════════════════════════════════════════════════════════════════════════════
+
+/// Storage layout version of this code (see `migrate`)
+const STORAGE_VERSION: u32 = 4;
+
+/// Number of recent distributions kept per token
+const MAX_HISTORY: u32 = 50;
+
+/// Maximum tokens handled by one `distribute_range` / `get_tokens_page` call
+const MAX_PAGE_SIZE: u32 = 20;
+
+/// Delay between `queue_config` and `apply_config` (2 days)
+const CONFIG_TIMELOCK: u64 = 2 * 24 * 60 * 60;
+
+/// Length of the circuit breaker's daily window
+const SECONDS_PER_DAY: u64 = 24 * 60 * 60;
 
 // ════════════════════════════════════════════════════════════════════════════
 // Storage Keys
@@ -29,16 +92,261 @@ pub enum DataKey {
     Config,
     /// Whether contract is initialized
     Initialized,
-    /// Whether contract is paused
+    /// Legacy pause flag (storage version 1, see `migrate`)
     Paused,
     /// Pending fees per token (Address -> i128)
     PendingFees(Address),
     /// Total distributed per token (Address -> i128)
     TotalDistributed(Address),
-    /// Supported tokens list
-    SupportedTokens,
+    /// Number of supported tokens
+    TokenCount,
+    /// Supported token by index, append-only (u32 -> Address)
+    TokenAt(u32),
+    /// Index of a supported token (Address -> u32)
+    TokenIndex(Address),
     /// Emergency withdrawal address
     EmergencyAddress,
+    /// Double-entry ledger per token (Address -> TokenLedger)
+    Ledger(Address),
+    /// Maximum tolerated shortfall before `assert_solvency` auto-pauses
+    SolvencyTolerance,
+    /// Per-token distribution config override (Address -> DistributionConfig)
+    TokenConfig(Address),
+    /// Cumulative burn share per token (Address -> i128)
+    TotalBurned(Address),
+    /// Current lifecycle mode
+    Mode,
+    /// Epoch length in seconds (0 = epochs disabled)
+    EpochLength,
+    /// Pending amount above which a token may distribute again within an epoch
+    EpochBypassThreshold,
+    /// Distribution history per token and epoch number ((Address, u64) -> DistributionEpoch)
+    Epoch(Address, u64),
+    /// Protocol registry address (reported by `contract_info`)
+    Registry,
+    /// Token registry consulted before accepting a token (optional)
+    TokenRegistry,
+    /// Canonical reward token that converted fees are swapped into
+    RewardToken,
+    /// Conversion route per fee token (Address -> ConversionRoute)
+    ConversionRoute(Address),
+    /// Recent distributions per token, ring buffer slot ((Address, u32) -> DistributionResult)
+    History(Address, u32),
+    /// Number of distributions ever recorded per token (Address -> u32)
+    HistoryCount(Address),
+    /// Cumulative staking share per token (Address -> i128)
+    TotalToStaking(Address),
+    /// Cumulative insurance share per token (Address -> i128)
+    TotalToInsurance(Address),
+    /// Whether `receive_fees` only accepts allowlisted sources
+    RequireFeeSource,
+    /// Allowlisted fee source (Address -> bool)
+    FeeSource(Address),
+    /// Native ASTRO token and its initial supply ((Address, i128))
+    NativeToken,
+    /// Stellar Asset Contract of native XLM
+    NativeAsset,
+    /// Config waiting for its timelock to elapse
+    QueuedConfig,
+    /// Per-token minimum distribution override (Address -> i128)
+    MinDistribution(Address),
+    /// Amount of a token distributed today (Address -> DailyDistribution)
+    DailyDistributed(Address),
+}
+
+// ════════════════════════════════════════════════════════════════════════════
+// Types
+// ════════════════════════════════════════════════════════════════════════════
+
+/// Double-entry accounting for a single fee token.
+///
+/// Credits are fees received; debits are amounts allocated to recipients or
+/// withdrawn in an emergency. At any point:
+/// - `pending == received - total_allocated - withdrawn`
+/// - `paid_out == total_allocated` (transfers settle atomically)
+/// - `balance >= received - paid_out - withdrawn`
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct TokenLedger {
+    /// Total fees credited via `receive_fees` or `sync`
+    pub received: i128,
+    /// Amount allocated to each recipient by distributions
+    pub allocated: Map<Address, i128>,
+    /// Sum of all allocations
+    pub total_allocated: i128,
+    /// Amount actually transferred out to recipients
+    pub paid_out: i128,
+    /// Pending fees removed through `emergency_withdraw`
+    pub withdrawn: i128,
+}
+
+/// Distribution config queued behind the config timelock
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct QueuedConfig {
+    /// Config to activate
+    pub config: DistributionConfig,
+    /// Earliest time `apply_config` succeeds
+    pub eta: u64,
+}
+
+/// A token's distributions in the current day, checked against
+/// `max_distribution_per_day`
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct DailyDistribution {
+    /// Day index (`timestamp / SECONDS_PER_DAY`)
+    pub day: u64,
+    /// Distributed so far that day
+    pub amount: i128,
+}
+
+/// `DistributionRecipient` as stored before storage version 3 (plain `u32` bps)
+#[contracttype]
+#[derive(Clone, Debug)]
+struct LegacyDistributionRecipient {
+    address: Address,
+    bps: u32,
+    kind: RecipientKind,
+}
+
+/// `DistributionConfig` as stored before storage version 3
+#[contracttype]
+#[derive(Clone, Debug)]
+struct LegacyDistributionConfig {
+    recipients: Vec<LegacyDistributionRecipient>,
+    min_distribution: i128,
+    burn_mode: BurnMode,
+    staking_raw_transfer: bool,
+}
+
+impl LegacyDistributionConfig {
+    fn into_v3(self, env: &Env) -> Result<DistributionConfigV3, SharedError> {
+        let mut recipients = Vec::new(env);
+        for recipient in self.recipients.iter() {
+            recipients.push_back(DistributionRecipient {
+                address: recipient.address,
+                bps: Bps::new(recipient.bps)?,
+                kind: recipient.kind,
+            });
+        }
+        Ok(DistributionConfigV3 {
+            recipients,
+            min_distribution: self.min_distribution,
+            burn_mode: self.burn_mode,
+            staking_raw_transfer: self.staking_raw_transfer,
+        })
+    }
+}
+
+/// `DistributionConfig` as stored before storage version 4 (no circuit breaker)
+#[contracttype]
+#[derive(Clone, Debug)]
+struct DistributionConfigV3 {
+    recipients: Vec<DistributionRecipient>,
+    min_distribution: i128,
+    burn_mode: BurnMode,
+    staking_raw_transfer: bool,
+}
+
+impl DistributionConfigV3 {
+    fn into_current(self) -> DistributionConfig {
+        DistributionConfig {
+            recipients: self.recipients,
+            min_distribution: self.min_distribution,
+            burn_mode: self.burn_mode,
+            staking_raw_transfer: self.staking_raw_transfer,
+            max_distribution_per_call: 0,
+            max_distribution_per_day: 0,
+        }
+    }
+}
+
+/// `QueuedConfig` as stored before storage version 3
+#[contracttype]
+#[derive(Clone, Debug)]
+struct LegacyQueuedConfig {
+    config: LegacyDistributionConfig,
+    eta: u64,
+}
+
+/// `QueuedConfig` as stored before storage version 4
+#[contracttype]
+#[derive(Clone, Debug)]
+struct QueuedConfigV3 {
+    config: DistributionConfigV3,
+    eta: u64,
+}
+
+/// How a fee token is swapped into the canonical reward token
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct ConversionRoute {
+    /// AMM pair trading the fee token against the reward token
+    pub pair: Address,
+    /// TWAP oracle tracking `pair`
+    pub oracle: Address,
+    /// TWAP window in seconds
+    pub window: u64,
+    /// Minimum output as bps of the TWAP quote
+    pub min_out_bps: u32,
+}
+
+/// Distributions of a single token within one epoch
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct DistributionEpoch {
+    /// Epoch number (`timestamp / epoch_length`)
+    pub epoch: u64,
+    /// Total amount distributed in this epoch
+    pub amount: i128,
+    /// Number of distributions in this epoch
+    pub distributions: u32,
+    /// Timestamp of the first distribution in this epoch
+    pub first_at: u64,
+    /// Timestamp of the latest distribution in this epoch
+    pub last_at: u64,
+}
+
+/// Distributor lifecycle mode; restricts which recipients a config may use
+#[contracttype]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[repr(u32)]
+pub enum LifecycleMode {
+    /// Before the staking pool exists: staking recipients are not allowed
+    Bootstrap = 0,
+    /// Regular operation: a staking recipient is required
+    Normal = 1,
+    /// Winding down: staking recipients are not allowed (terminal)
+    Sunset = 2,
+}
+
+impl LifecycleMode {
+    /// Whether a config's recipients are allowed in this mode (any config,
+    /// including per-token overrides)
+    pub fn allows(&self, config: &DistributionConfig) -> bool {
+        let has_staking = config.recipient_of(RecipientKind::Staking).is_some();
+        match self {
+            LifecycleMode::Bootstrap | LifecycleMode::Sunset => !has_staking,
+            LifecycleMode::Normal => true,
+        }
+    }
+
+    /// Whether a config may be the global default in this mode.
+    /// `Normal` additionally requires a staking recipient.
+    pub fn allows_default(&self, config: &DistributionConfig) -> bool {
+        let has_staking = config.recipient_of(RecipientKind::Staking).is_some();
+        self.allows(config) && (*self != LifecycleMode::Normal || has_staking)
+    }
+
+    /// Whether moving from this mode to `next` is allowed
+    pub fn can_transition_to(&self, next: LifecycleMode) -> bool {
+        matches!(
+            (self, next),
+            (LifecycleMode::Bootstrap, LifecycleMode::Normal)
+                | (LifecycleMode::Normal, LifecycleMode::Sunset)
+        )
+    }
 }
 
 // ════════════════════════════════════════════════════════════════════════════
@@ -67,50 +375,82 @@ impl FeeDistributor {
             return Err(SharedError::AlreadyInitialized);
         }
 
-        // Validate addresses are different
-        if treasury_vault == staking_pool
-            || treasury_vault == burn_address
-            || staking_pool == burn_address
-        {
-            return Err(SharedError::InvalidAddress);
-        }
+        require_distinct(&[
+            treasury_vault.clone(),
+            staking_pool.clone(),
+            burn_address.clone(),
+        ])?;
 
         // Create default config (50/30/20 split)
         let config = DistributionConfig {
-            treasury_vault,
-            staking_pool,
-            burn_address,
-            treasury_bps: 5000,           // 50%
-            staking_bps: 3000,            // 30%
-            burn_bps: 2000,               // 20%
+            recipients: Vec::from_array(
+                &env,
+                [
+                    DistributionRecipient {
+                        address: treasury_vault,
+                        bps: Bps::from_const(5000), // 50%
+                        kind: RecipientKind::Treasury,
+                    },
+                    DistributionRecipient {
+                        address: staking_pool,
+                        bps: Bps::from_const(3000), // 30%
+                        kind: RecipientKind::Staking,
+                    },
+                    DistributionRecipient {
+                        address: burn_address,
+                        bps: Bps::from_const(2000), // 20%
+                        kind: RecipientKind::Burn,
+                    },
+                ],
+            ),
             min_distribution: 10_000_000, // 1 token minimum (7 decimals)
+            burn_mode: BurnMode::Transfer,
+            staking_raw_transfer: false,
+            max_distribution_per_call: 0,
+            max_distribution_per_day: 0,
         };
 
-        // Validate percentages sum to 100%
-        if config.treasury_bps + config.staking_bps + config.burn_bps != 10_000 {
-            return Err(SharedError::InvalidPercentage);
-        }
-
-        // Store initial state
-        env.storage().instance().set(&DataKey::Admin, &admin);
-        env.storage().instance().set(&DataKey::Config, &config);
-        env.storage().instance().set(&DataKey::Initialized, &true);
-        env.storage().instance().set(&DataKey::Paused, &false);
-        env.storage()
-            .instance()
-            .set(&DataKey::SupportedTokens, &Vec::<Address>::new(&env));
+        Self::init_state(&env, &admin, &config, LifecycleMode::Normal)
+    }
 
-        extend_instance_ttl(&env);
+    /// Initialize in `Bootstrap` mode, before the staking pool exists.
+    /// Fees split 80/20 between treasury and burn until `set_mode(Normal, ..)`.
+    pub fn initialize_bootstrap(
+        env: Env,
+        admin: Address,
+        treasury_vault: Address,
+        burn_address: Address,
+    ) -> Result<(), SharedError> {
+        // Check not already initialized
+        if env.storage().instance().has(&DataKey::Initialized) {
+            return Err(SharedError::AlreadyInitialized);
+        }
+        require_distinct(&[treasury_vault.clone(), burn_address.clone()])?;
 
-        // Emit init event
-        let events = EventBuilder::new(&env);
-        events.publish(
-            "fee_dist",
-            "initialized",
-            (admin.clone(), env.ledger().timestamp()),
-        );
+        let config = DistributionConfig {
+            recipients: Vec::from_array(
+                &env,
+                [
+                    DistributionRecipient {
+                        address: treasury_vault,
+                        bps: Bps::from_const(8000), // 80%
+                        kind: RecipientKind::Treasury,
+                    },
+                    DistributionRecipient {
+                        address: burn_address,
+                        bps: Bps::from_const(2000), // 20%
+                        kind: RecipientKind::Burn,
+                    },
+                ],
+            ),
+            min_distribution: 10_000_000, // 1 token minimum (7 decimals)
+            burn_mode: BurnMode::Transfer,
+            staking_raw_transfer: false,
+            max_distribution_per_call: 0,
+            max_distribution_per_day: 0,
+        };
 
-        Ok(())
+        Self::init_state(&env, &admin, &config, LifecycleMode::Bootstrap)
     }
 
     // ────────────────────────────────────────────────────────────────────────
@@ -126,412 +466,2960 @@ impl FeeDistributor {
     ) -> Result<(), SharedError> {
         caller.require_auth();
         Self::require_initialized(&env)?;
-        Self::require_not_paused(&env)?;
+        pausable::require_not_paused(&env, PauseGroup::Deposits)?;
+        validate_amount(amount, MIN_FEE_AMOUNT, NO_MAX)?;
+        Self::require_token_allowed(&env, &token)?;
 
-        if amount <= 0 {
-            return Err(SharedError::InvalidAmount);
+        // Keep garbage tokens out of the supported list when sources are enforced
+        if Self::fee_source_required(&env) && !Self::is_fee_source_internal(&env, &caller) {
+            return Err(SharedError::Unauthorized);
         }
 
         // Transfer tokens to this contract
         let token_client = token::Client::new(&env, &token);
-        token_client.transfer(&caller, &env.current_contract_address(), &amount);
+        token_client.transfer(&caller, env.current_contract_address(), &amount);
 
         // Add to pending fees
         let current = Self::get_pending_fees(&env, &token);
         let new_pending = safe_add(current, amount)?;
         let pending_fees_key = DataKey::PendingFees(token.clone());
-        env.storage()
-            .persistent()
-            .set(&pending_fees_key, &new_pending);
+        set_persistent(&env, &pending_fees_key, &new_pending);
 
-        // FIX #M1: Extend TTL for PendingFees to prevent expiration
-        env.storage()
-            .persistent()
-            .extend_ttl(&pending_fees_key, 200_000, 200_000);
+        // Credit the ledger
+        let mut ledger = Self::get_ledger(&env, &token);
+        ledger.received = safe_add(ledger.received, amount)?;
+        Self::save_ledger(&env, &token, &ledger);
 
         // Ensure token is in supported list
         Self::add_supported_token(&env, &token);
 
+        emit_fee_received(&env, &token, &caller, amount);
         extend_instance_ttl(&env);
 
         Ok(())
     }
 
-    /// Distribute pending fees for a token
-    pub fn distribute(env: Env, token: Address) -> Result<DistributionResult, SharedError> {
-        Self::require_initialized(&env)?;
-        Self::require_not_paused(&env)?;
-
-        let config: DistributionConfig = env
-            .storage()
-            .instance()
-            .get(&DataKey::Config)
-            .ok_or(SharedError::NotInitialized)?;
+    /// Send native XLM fees (pulled from `caller` through the native SAC)
+    pub fn receive_native(env: Env, caller: Address, amount: i128) -> Result<(), SharedError> {
+        let native = Self::native_asset(env.clone());
+        Self::receive_fees(env, caller, native, amount)
+    }
 
-        let pending = Self::get_pending_fees(&env, &token);
+    /// Credit tokens sent straight to this contract (without `receive_fees`)
+    /// as pending fees. Returns the amount credited.
+    ///
+    /// Callable by anyone. While fee sources are enforced, only tokens that
+    /// are already supported can be synced.
+    pub fn sync(env: Env, token: Address) -> Result<i128, SharedError> {
+        Self::require_initialized(&env)?;
+        pausable::require_not_paused(&env, PauseGroup::Deposits)?;
+        Self::require_token_allowed(&env, &token)?;
 
-        if pending < config.min_distribution {
-            return Err(SharedError::BelowMinimum);
+        if Self::fee_source_required(&env)
+            && !env
+                .storage()
+                .persistent()
+                .has(&DataKey::TokenIndex(token.clone()))
+        {
+            return Err(SharedError::Unauthorized);
         }
 
-        let token_client = token::Client::new(&env, &token);
-
-        // Calculate distribution amounts
-        let treasury_amount = safe_div(
-            safe_mul(pending, config.treasury_bps as i128)?,
-            BPS_DENOMINATOR,
-        )?;
-
-        let staking_amount = safe_div(
-            safe_mul(pending, config.staking_bps as i128)?,
-            BPS_DENOMINATOR,
+        let mut ledger = Self::get_ledger(&env, &token);
+        let tracked = safe_sub(
+            safe_sub(ledger.received, ledger.paid_out)?,
+            ledger.withdrawn,
         )?;
-
-        let burn_amount = safe_div(safe_mul(pending, config.burn_bps as i128)?, BPS_DENOMINATOR)?;
-
-        // Handle rounding - any dust goes to treasury
-        let total_calculated = safe_add(safe_add(treasury_amount, staking_amount)?, burn_amount)?;
-        let dust = pending - total_calculated;
-        let final_treasury = safe_add(treasury_amount, dust)?;
-
-        // Execute transfers
-        if final_treasury > 0 {
-            token_client.transfer(
-                &env.current_contract_address(),
-                &config.treasury_vault,
-                &final_treasury,
-            );
+        let actual = token::Client::new(&env, &token).balance(&env.current_contract_address());
+        let surplus = actual - tracked;
+        if surplus <= 0 {
+            return Ok(0);
         }
 
-        if staking_amount > 0 {
-            token_client.transfer(
-                &env.current_contract_address(),
-                &config.staking_pool,
-                &staking_amount,
-            );
-        }
+        let pending = safe_add(Self::get_pending_fees(&env, &token), surplus)?;
+        set_persistent(&env, &DataKey::PendingFees(token.clone()), &pending);
+        ledger.received = safe_add(ledger.received, surplus)?;
+        Self::save_ledger(&env, &token, &ledger);
+        Self::add_supported_token(&env, &token);
 
-        if burn_amount > 0 {
-            // For burn, we transfer to burn address (could be zero address or actual burn)
-            token_client.transfer(
-                &env.current_contract_address(),
-                &config.burn_address,
-                &burn_amount,
-            );
-        }
+        let events = EventBuilder::new(&env);
+        events.publish(
+            "fee_dist",
+            "synced",
+            (token, surplus, pending, env.ledger().timestamp()),
+        );
 
-        // Update state
-        let pending_fees_key = DataKey::PendingFees(token.clone());
-        env.storage()
-            .persistent()
-            .set(&pending_fees_key, &0_i128);
+        extend_instance_ttl(&env);
+        Ok(surplus)
+    }
 
-        // FIX #M1: Extend TTL for PendingFees to prevent expiration
-        env.storage()
-            .persistent()
-            .extend_ttl(&pending_fees_key, 200_000, 200_000);
+    /// Distribute pending fees for a token.
+    /// Fails with `DeadlineExpired` after `deadline`, if given.
+    pub fn distribute(
+        env: Env,
+        token: Address,
+        deadline: Option<u64>,
+    ) -> Result<DistributionResult, SharedError> {
+        Self::require_initialized(&env)?;
+        check_optional_deadline(&env, deadline)?;
+        pausable::require_not_paused(&env, PauseGroup::Withdrawals)?;
 
-        let prev_total = Self::get_total_distributed(&env, &token);
-        let new_total = safe_add(prev_total, pending)?;
-        env.storage()
-            .persistent()
-            .set(&DataKey::TotalDistributed(token.clone()), &new_total);
+        let pending = Self::get_pending_fees(&env, &token);
+        let (token, amount) = Self::convert_pending(&env, &token, pending)?;
+        Self::execute_distribution(&env, &token, amount)
+    }
 
-        // Emit event
-        emit_distribution(
-            &env,
-            &token,
-            pending,
-            final_treasury,
-            staking_amount,
-            burn_amount,
-        );
+    /// Distribute `bps` of the pending fees for a token, leaving the rest pending.
+    /// Useful for cautious first runs after a config change or to smooth
+    /// staking inflows.
+    pub fn distribute_partial(
+        env: Env,
+        token: Address,
+        bps: u32,
+    ) -> Result<DistributionResult, SharedError> {
+        Self::require_initialized(&env)?;
+        pausable::require_not_paused(&env, PauseGroup::Withdrawals)?;
 
-        extend_instance_ttl(&env);
+        if bps == 0 || bps as i128 > BPS_DENOMINATOR {
+            return Err(SharedError::InvalidBps);
+        }
 
-        Ok(DistributionResult {
-            token,
-            total_amount: pending,
-            treasury_amount: final_treasury,
-            staking_amount,
-            burn_amount,
-            timestamp: env.ledger().timestamp(),
-        })
+        let pending = Self::get_pending_fees(&env, &token);
+        let amount = safe_div(safe_mul(pending, bps as i128)?, BPS_DENOMINATOR)?;
+        let (token, amount) = Self::convert_pending(&env, &token, amount)?;
+        Self::execute_distribution(&env, &token, amount)
     }
 
     /// Distribute all pending fees for all supported tokens
     pub fn distribute_all(env: Env) -> Result<Vec<DistributionResult>, SharedError> {
         Self::require_initialized(&env)?;
-        Self::require_not_paused(&env)?;
+        pausable::require_not_paused(&env, PauseGroup::Withdrawals)?;
 
-        let tokens = Self::get_supported_tokens(&env);
-        let mut results = Vec::new(&env);
+        let count = Self::get_token_count(&env);
+        Self::distribute_tokens(&env, 0, count)
+    }
 
-        for token in tokens.iter() {
-            let pending = Self::get_pending_fees(&env, &token);
-            let config: DistributionConfig = env
-                .storage()
-                .instance()
-                .get(&DataKey::Config)
-                .ok_or(SharedError::NotInitialized)?;
+    /// Distribute pending fees for up to `count` supported tokens (capped at 20)
+    /// starting at `start_index`. Returns the results and the cursor to pass
+    /// next; the cursor equals the token count once every token was visited.
+    pub fn distribute_range(
+        env: Env,
+        start_index: u32,
+        count: u32,
+    ) -> Result<(Vec<DistributionResult>, u32), SharedError> {
+        Self::require_initialized(&env)?;
+        pausable::require_not_paused(&env, PauseGroup::Withdrawals)?;
 
-            if pending >= config.min_distribution {
-                match Self::distribute(env.clone(), token) {
-                    Ok(result) => results.push_back(result),
-                    Err(_) => continue, // Skip failed distributions
-                }
-            }
-        }
+        let end = start_index
+            .saturating_add(count.min(MAX_PAGE_SIZE))
+            .min(Self::get_token_count(&env));
+        let results = Self::distribute_tokens(&env, start_index, end)?;
 
-        Ok(results)
+        Ok((results, end.max(start_index)))
     }
 
     // ────────────────────────────────────────────────────────────────────────
     // Admin Functions
     // ────────────────────────────────────────────────────────────────────────
 
-    /// Update distribution configuration
-    pub fn update_config(env: Env, new_config: DistributionConfig) -> Result<(), SharedError> {
+    /// Set the protocol registry address reported by `contract_info`
+    pub fn set_registry(env: Env, registry: Address) -> Result<(), SharedError> {
         Self::require_admin(&env)?;
+        env.storage().instance().set(&DataKey::Registry, &registry);
+        extend_instance_ttl(&env);
+        Ok(())
+    }
 
-        // Validate percentages sum to 100%
-        if new_config.treasury_bps + new_config.staking_bps + new_config.burn_bps != 10_000 {
-            return Err(SharedError::InvalidPercentage);
-        }
+    /// Override the native XLM contract used by `receive_native`
+    pub fn set_native_asset(env: Env, native: Address) -> Result<(), SharedError> {
+        Self::require_admin(&env)?;
+        env.storage().instance().set(&DataKey::NativeAsset, &native);
+        emit_config_updated(&env, &Self::admin(env.clone())?);
+        extend_instance_ttl(&env);
+        Ok(())
+    }
 
-        // Validate addresses are different
-        if new_config.treasury_vault == new_config.staking_pool
-            || new_config.treasury_vault == new_config.burn_address
-            || new_config.staking_pool == new_config.burn_address
-        {
-            return Err(SharedError::InvalidAddress);
+    /// Set the token registry consulted before accepting a token
+    /// (`None` accepts every token)
+    pub fn set_token_registry(env: Env, registry: Option<Address>) -> Result<(), SharedError> {
+        Self::require_admin(&env)?;
+        match registry {
+            Some(registry) => env
+                .storage()
+                .instance()
+                .set(&DataKey::TokenRegistry, &registry),
+            None => env.storage().instance().remove(&DataKey::TokenRegistry),
         }
+        extend_instance_ttl(&env);
+        Ok(())
+    }
+
+    /// Update distribution configuration
+    pub fn update_config(
+        env: Env,
+        caller: Address,
+        new_config: DistributionConfig,
+    ) -> Result<(), SharedError> {
+        Self::require_role(&env, &caller, &CONFIG_ADMIN)?;
+        pausable::require_not_paused(&env, PauseGroup::AdminOps)?;
+
+        // Validate percentages sum to 100%, addresses are different and the
+        // recipients are allowed in the current mode
+        Self::check_config(&env, &new_config)?;
 
         env.storage().instance().set(&DataKey::Config, &new_config);
+        emit_config_updated(&env, &caller);
         extend_instance_ttl(&env);
 
         Ok(())
     }
 
-    /// Update admin address
-    pub fn set_admin(env: Env, new_admin: Address) -> Result<(), SharedError> {
+    /// Queue a new default config; it becomes active via `apply_config` once
+    /// `CONFIG_TIMELOCK` has elapsed. Replaces any previously queued config.
+    pub fn queue_config(env: Env, new_config: DistributionConfig) -> Result<u64, SharedError> {
         Self::require_admin(&env)?;
 
-        let old_admin: Address = env
+        Self::check_config(&env, &new_config)?;
+
+        let eta = env.ledger().timestamp() + CONFIG_TIMELOCK;
+        env.storage().instance().set(
+            &DataKey::QueuedConfig,
+            &QueuedConfig {
+                config: new_config.clone(),
+                eta,
+            },
+        );
+
+        let events = EventBuilder::new(&env);
+        events.publish(
+            "fee_dist",
+            "config_queued",
+            (new_config.recipients.len(), eta, env.ledger().timestamp()),
+        );
+
+        extend_instance_ttl(&env);
+        Ok(eta)
+    }
+
+    /// Activate the queued config once its timelock has elapsed (callable by anyone)
+    pub fn apply_config(env: Env) -> Result<(), SharedError> {
+        Self::require_initialized(&env)?;
+
+        let queued: QueuedConfig = env
             .storage()
             .instance()
-            .get(&DataKey::Admin)
-            .ok_or(SharedError::NotInitialized)?;
+            .get(&DataKey::QueuedConfig)
+            .ok_or(SharedError::InvalidState)?;
+        if env.ledger().timestamp() < queued.eta {
+            return Err(SharedError::CooldownNotElapsed);
+        }
 
-        env.storage().instance().set(&DataKey::Admin, &new_admin);
+        // The mode may have changed since queueing
+        Self::save_config(&env, &queued.config)?;
+        env.storage().instance().remove(&DataKey::QueuedConfig);
 
-        // Emit admin change event
         let events = EventBuilder::new(&env);
         events.publish(
             "fee_dist",
-            "admin_changed",
-            (old_admin, new_admin, env.ledger().timestamp()),
+            "config_applied",
+            (queued.config.recipients.len(), env.ledger().timestamp()),
         );
 
         extend_instance_ttl(&env);
         Ok(())
     }
 
-    /// Pause/unpause the contract
-    pub fn set_paused(env: Env, paused: bool) -> Result<(), SharedError> {
+    /// Drop the queued config
+    pub fn cancel_queued_config(env: Env) -> Result<(), SharedError> {
         Self::require_admin(&env)?;
 
-        env.storage().instance().set(&DataKey::Paused, &paused);
+        if !env.storage().instance().has(&DataKey::QueuedConfig) {
+            return Err(SharedError::InvalidState);
+        }
+        env.storage().instance().remove(&DataKey::QueuedConfig);
 
         let events = EventBuilder::new(&env);
-        events.publish("fee_dist", "paused", (paused, env.ledger().timestamp()));
+        events.publish("fee_dist", "config_cancelled", (env.ledger().timestamp(),));
 
         extend_instance_ttl(&env);
         Ok(())
     }
 
-    /// Emergency withdrawal (admin only, when paused)
-    pub fn emergency_withdraw(
+    /// Move to the next lifecycle mode, swapping in a config valid for it.
+    /// Allowed: Bootstrap -> Normal, Normal -> Sunset. Token overrides for
+    /// supported tokens must already fit the new mode.
+    pub fn set_mode(
         env: Env,
-        token: Address,
-        to: Address,
-        amount: i128,
+        mode: LifecycleMode,
+        config: DistributionConfig,
     ) -> Result<(), SharedError> {
         Self::require_admin(&env)?;
 
-        // Only allow emergency withdrawal when paused
-        let paused: bool = env
-            .storage()
-            .instance()
-            .get(&DataKey::Paused)
-            .unwrap_or(false);
-        if !paused {
-            return Err(SharedError::ContractNotPaused);
+        let current = Self::get_mode(&env);
+        if !current.can_transition_to(mode) {
+            return Err(SharedError::InvalidState);
+        }
+        if !config.is_valid() {
+            return Err(SharedError::InvalidPercentage);
+        }
+        if !mode.allows_default(&config) {
+            return Err(SharedError::InvalidState);
+        }
+        for token in Self::get_supported_tokens(&env).iter() {
+            let token_config: Option<DistributionConfig> =
+                get_persistent(&env, &DataKey::TokenConfig(token));
+            if let Some(token_config) = token_config {
+                if !mode.allows(&token_config) {
+                    return Err(SharedError::InvalidState);
+                }
+            }
         }
 
-        let token_client = token::Client::new(&env, &token);
-        token_client.transfer(&env.current_contract_address(), &to, &amount);
+        env.storage().instance().set(&DataKey::Mode, &mode);
+        env.storage().instance().set(&DataKey::Config, &config);
 
         let events = EventBuilder::new(&env);
         events.publish(
             "fee_dist",
-            "emergency_withdraw",
-            (token, to, amount, env.ledger().timestamp()),
+            "mode_changed",
+            (current, mode, env.ledger().timestamp()),
         );
 
+        extend_instance_ttl(&env);
         Ok(())
     }
 
-    // ────────────────────────────────────────────────────────────────────────
-    // Query Functions
-    // ────────────────────────────────────────────────────────────────────────
+    /// Set the canonical reward token that converted fees are swapped into
+    pub fn set_reward_token(env: Env, reward_token: Address) -> Result<(), SharedError> {
+        Self::require_admin(&env)?;
 
-    /// Get current distribution configuration
-    pub fn get_config(env: Env) -> Result<DistributionConfig, SharedError> {
         env.storage()
             .instance()
-            .get(&DataKey::Config)
-            .ok_or(SharedError::NotInitialized)
+            .set(&DataKey::RewardToken, &reward_token);
+
+        let events = EventBuilder::new(&env);
+        events.publish(
+            "fee_dist",
+            "reward_token_set",
+            (reward_token, env.ledger().timestamp()),
+        );
+
+        extend_instance_ttl(&env);
+        Ok(())
     }
 
-    /// Get pending fees for a token
-    pub fn get_pending_distribution(env: Env, token: Address) -> i128 {
-        Self::get_pending_fees(&env, &token)
+    /// Swap `token` into the reward token through `pair` before distributing.
+    /// `min_out_bps` bounds slippage against `oracle`'s TWAP over `window`
+    /// seconds, so spot reserves moved within the transaction cannot set it.
+    pub fn set_conversion_route(
+        env: Env,
+        token: Address,
+        pair: Address,
+        oracle: Address,
+        window: u64,
+        min_out_bps: u32,
+    ) -> Result<(), SharedError> {
+        Self::require_admin(&env)?;
+
+        if min_out_bps == 0 || min_out_bps as i128 > BPS_DENOMINATOR {
+            return Err(SharedError::InvalidBps);
+        }
+        if window == 0 {
+            return Err(SharedError::InvalidTimestamp);
+        }
+
+        let reward_token = Self::get_reward_token(&env).ok_or(SharedError::InvalidState)?;
+        if token == reward_token {
+            return Err(SharedError::InvalidAddress);
+        }
+
+        // The pair must trade exactly this token against the reward token
+        let pair_client = AmmPairClient::new(&env, &pair);
+        let token_0 = pair_client.try_token_0().into_shared()?;
+        let token_1 = pair_client.try_token_1().into_shared()?;
+        let matches = (token_0 == token && token_1 == reward_token)
+            || (token_1 == token && token_0 == reward_token);
+        if !matches {
+            return Err(SharedError::InvalidAddress);
+        }
+
+        let route = ConversionRoute {
+            pair,
+            oracle,
+            window,
+            min_out_bps,
+        };
+        let key = DataKey::ConversionRoute(token.clone());
+        set_persistent(&env, &key, &route);
+
+        let events = EventBuilder::new(&env);
+        events.publish(
+            "fee_dist",
+            "route_set",
+            (
+                token,
+                route.pair,
+                route.oracle,
+                window,
+                min_out_bps,
+                env.ledger().timestamp(),
+            ),
+        );
+
+        extend_instance_ttl(&env);
+        Ok(())
     }
 
-    /// Get total distributed for a token
-    pub fn get_total_distributed_for_token(env: Env, token: Address) -> i128 {
-        Self::get_total_distributed(&env, &token)
+    /// Remove a token's conversion route so it is distributed as-is again
+    pub fn remove_conversion_route(env: Env, token: Address) -> Result<(), SharedError> {
+        Self::require_admin(&env)?;
+
+        env.storage()
+            .persistent()
+            .remove(&DataKey::ConversionRoute(token.clone()));
+
+        let events = EventBuilder::new(&env);
+        events.publish(
+            "fee_dist",
+            "route_removed",
+            (token, env.ledger().timestamp()),
+        );
+
+        extend_instance_ttl(&env);
+        Ok(())
     }
 
-    /// Get all supported tokens
-    pub fn get_tokens(env: Env) -> Vec<Address> {
-        Self::get_supported_tokens(&env)
+    /// Override the distribution config for a single token
+    /// (e.g. 100% burn for the native ASTRO token)
+    pub fn set_token_config(
+        env: Env,
+        token: Address,
+        config: DistributionConfig,
+    ) -> Result<(), SharedError> {
+        Self::require_admin(&env)?;
+
+        if !config.is_valid() {
+            return Err(SharedError::InvalidPercentage);
+        }
+        if !Self::get_mode(&env).allows(&config) {
+            return Err(SharedError::InvalidState);
+        }
+
+        let key = DataKey::TokenConfig(token.clone());
+        set_persistent(&env, &key, &config);
+
+        let events = EventBuilder::new(&env);
+        events.publish(
+            "fee_dist",
+            "token_config_set",
+            (token, config.recipients.len(), env.ledger().timestamp()),
+        );
+
+        extend_instance_ttl(&env);
+        Ok(())
     }
 
-    /// Get admin address
-    pub fn admin(env: Env) -> Result<Address, SharedError> {
+    /// Remove a token's config override so it uses the global default again
+    pub fn clear_token_config(env: Env, token: Address) -> Result<(), SharedError> {
+        Self::require_admin(&env)?;
+
         env.storage()
-            .instance()
-            .get(&DataKey::Admin)
-            .ok_or(SharedError::NotInitialized)
+            .persistent()
+            .remove(&DataKey::TokenConfig(token.clone()));
+
+        let events = EventBuilder::new(&env);
+        events.publish(
+            "fee_dist",
+            "token_config_cleared",
+            (token, env.ledger().timestamp()),
+        );
+
+        extend_instance_ttl(&env);
+        Ok(())
     }
 
-    /// Check if contract is paused
-    pub fn is_paused(env: Env) -> bool {
+    /// Set the minimum pending amount that triggers a distribution of
+    /// `token`, overriding `min_distribution` of whichever config it uses
+    pub fn set_min_distribution(env: Env, token: Address, amount: i128) -> Result<(), SharedError> {
+        Self::require_admin(&env)?;
+
+        if amount < 0 {
+            return Err(SharedError::InvalidAmount);
+        }
+        set_persistent(&env, &DataKey::MinDistribution(token.clone()), &amount);
+
+        let events = EventBuilder::new(&env);
+        events.publish("fee_dist", "min_distribution_set", (token, amount));
+
+        extend_instance_ttl(&env);
+        Ok(())
+    }
+
+    /// Remove a token's minimum distribution override
+    pub fn clear_min_distribution(env: Env, token: Address) -> Result<(), SharedError> {
+        Self::require_admin(&env)?;
+
+        env.storage()
+            .persistent()
+            .remove(&DataKey::MinDistribution(token.clone()));
+
+        let events = EventBuilder::new(&env);
+        events.publish("fee_dist", "min_distribution_cleared", token);
+
+        extend_instance_ttl(&env);
+        Ok(())
+    }
+
+    /// Add a distribution recipient.
+    /// Its share is taken from `take_from` so the total stays at 100%.
+    pub fn add_recipient(
+        env: Env,
+        address: Address,
+        bps: u32,
+        kind: RecipientKind,
+        take_from: Address,
+    ) -> Result<(), SharedError> {
+        Self::require_admin(&env)?;
+        let share = Bps::new(bps)?;
+
+        let mut config = Self::get_config_internal(&env)?;
+        if config.recipient_index(&address).is_some() {
+            return Err(SharedError::InvalidAddress);
+        }
+
+        let donor_index = config
+            .recipient_index(&take_from)
+            .ok_or(SharedError::InvalidAddress)?;
+        let mut donor = config.recipients.get(donor_index).unwrap();
+        donor.bps = donor
+            .bps
+            .checked_sub(share)
+            .ok_or(SharedError::InvalidBps)?;
+        config.recipients.set(donor_index, donor);
+        config.recipients.push_back(DistributionRecipient {
+            address: address.clone(),
+            bps: share,
+            kind,
+        });
+
+        Self::save_config(&env, &config)?;
+
+        let events = EventBuilder::new(&env);
+        events.publish(
+            "fee_dist",
+            "recipient_added",
+            (address, bps, kind, env.ledger().timestamp()),
+        );
+
+        extend_instance_ttl(&env);
+        Ok(())
+    }
+
+    /// Remove a distribution recipient, giving its share to `give_to`
+    pub fn remove_recipient(
+        env: Env,
+        address: Address,
+        give_to: Address,
+    ) -> Result<(), SharedError> {
+        Self::require_admin(&env)?;
+
+        let mut config = Self::get_config_internal(&env)?;
+        let index = config
+            .recipient_index(&address)
+            .ok_or(SharedError::InvalidAddress)?;
+        let removed = config.recipients.get(index).unwrap();
+        config.recipients.remove(index);
+
+        let heir_index = config
+            .recipient_index(&give_to)
+            .ok_or(SharedError::InvalidAddress)?;
+        let mut heir = config.recipients.get(heir_index).unwrap();
+        heir.bps = heir
+            .bps
+            .checked_add(removed.bps)
+            .ok_or(SharedError::InvalidBps)?;
+        config.recipients.set(heir_index, heir);
+
+        Self::save_config(&env, &config)?;
+
+        let events = EventBuilder::new(&env);
+        events.publish(
+            "fee_dist",
+            "recipient_removed",
+            (address, give_to, env.ledger().timestamp()),
+        );
+
+        extend_instance_ttl(&env);
+        Ok(())
+    }
+
+    /// Change a recipient's share; the difference is balanced against
+    /// `counterparty` so the total stays at 100%
+    pub fn set_recipient_bps(
+        env: Env,
+        address: Address,
+        bps: u32,
+        counterparty: Address,
+    ) -> Result<(), SharedError> {
+        Self::require_admin(&env)?;
+
+        require_distinct(&[address.clone(), counterparty.clone()])?;
+        let share = Bps::new(bps)?;
+
+        let mut config = Self::get_config_internal(&env)?;
+        let index = config
+            .recipient_index(&address)
+            .ok_or(SharedError::InvalidAddress)?;
+        let other_index = config
+            .recipient_index(&counterparty)
+            .ok_or(SharedError::InvalidAddress)?;
+
+        let mut recipient = config.recipients.get(index).unwrap();
+        let mut other = config.recipients.get(other_index).unwrap();
+        let combined = recipient
+            .bps
+            .checked_add(other.bps)
+            .ok_or(SharedError::InvalidBps)?;
+        other.bps = combined.checked_sub(share).ok_or(SharedError::InvalidBps)?;
+        recipient.bps = share;
+        config.recipients.set(index, recipient);
+        config.recipients.set(other_index, other);
+
+        Self::save_config(&env, &config)?;
+
+        let events = EventBuilder::new(&env);
+        events.publish(
+            "fee_dist",
+            "recipient_bps_set",
+            (address, bps, counterparty, env.ledger().timestamp()),
+        );
+
+        extend_instance_ttl(&env);
+        Ok(())
+    }
+
+    /// Propose a new admin; it takes over once it calls `accept_admin`
+    pub fn propose_admin(env: Env, new_admin: Address) -> Result<(), SharedError> {
+        Self::require_admin(&env)?;
+
+        let admin = Self::admin(env.clone())?;
+        ownership::propose(&env, &admin, &new_admin)?;
+        extend_instance_ttl(&env);
+
+        Ok(())
+    }
+
+    /// Accept a pending admin proposal (proposed admin only)
+    pub fn accept_admin(env: Env) -> Result<(), SharedError> {
+        let old_admin = Self::admin(env.clone())?;
+        let new_admin = ownership::accept(&env, &old_admin)?;
+
+        env.storage().instance().set(&DataKey::Admin, &new_admin);
+
+        let events = EventBuilder::new(&env);
+        events.publish(
+            "fee_dist",
+            "admin_changed",
+            (old_admin, new_admin, env.ledger().timestamp()),
+        );
+
+        extend_instance_ttl(&env);
+
+        Ok(())
+    }
+
+    /// Withdraw a pending admin proposal
+    pub fn cancel_admin_transfer(env: Env) -> Result<(), SharedError> {
+        Self::require_admin(&env)?;
+
+        ownership::cancel(&env)?;
+        extend_instance_ttl(&env);
+
+        Ok(())
+    }
+
+    /// Pause/unpause every function group (admin or `PAUSER`)
+    pub fn set_paused(env: Env, caller: Address, paused: bool) -> Result<(), SharedError> {
+        Self::require_role(&env, &caller, &PAUSER)?;
+
+        pausable::set(&env, &PauseConfig::uniform(paused));
+
+        let events = EventBuilder::new(&env);
+        events.publish("fee_dist", "paused", (paused, env.ledger().timestamp()));
+
+        extend_instance_ttl(&env);
+        Ok(())
+    }
+
+    /// Pause individual function groups, e.g. halt deposits while
+    /// withdrawals stay open (admin or `PAUSER`)
+    pub fn set_pause_config(
+        env: Env,
+        caller: Address,
+        config: PauseConfig,
+    ) -> Result<(), SharedError> {
+        Self::require_role(&env, &caller, &PAUSER)?;
+
+        pausable::set(&env, &config);
+
+        let events = EventBuilder::new(&env);
+        events.publish(
+            "fee_dist",
+            "pause_config",
+            (
+                config.deposits,
+                config.withdrawals,
+                config.admin_ops,
+                env.ledger().timestamp(),
+            ),
+        );
+
+        extend_instance_ttl(&env);
+        Ok(())
+    }
+
+    /// Select which high-frequency events use compact encoding
+    /// (bitmask of `CompactEventKind::bit()`)
+    pub fn set_compact_events(env: Env, mask: u32) -> Result<(), SharedError> {
+        Self::require_admin(&env)?;
+
+        set_compact_mask(&env, mask);
+        extend_instance_ttl(&env);
+
+        Ok(())
+    }
+
+    /// Set the shortfall tolerated by `assert_solvency` before it pauses the contract
+    pub fn set_solvency_tolerance(env: Env, tolerance: i128) -> Result<(), SharedError> {
+        Self::require_admin(&env)?;
+
+        if tolerance < 0 {
+            return Err(SharedError::InvalidAmount);
+        }
+
         env.storage()
             .instance()
-            .get(&DataKey::Paused)
-            .unwrap_or(false)
+            .set(&DataKey::SolvencyTolerance, &tolerance);
+        extend_instance_ttl(&env);
+
+        Ok(())
     }
 
-    /// Get contract balance for a token
-    pub fn balance(env: Env, token: Address) -> i128 {
-        let token_client = token::Client::new(&env, &token);
-        token_client.balance(&env.current_contract_address())
+    /// Allow an address (launchpad, AMM, staking pool, ...) to send fees
+    pub fn add_fee_source(env: Env, source: Address) -> Result<(), SharedError> {
+        Self::require_admin(&env)?;
+
+        let key = DataKey::FeeSource(source.clone());
+        set_persistent(&env, &key, &true);
+
+        let events = EventBuilder::new(&env);
+        events.publish(
+            "fee_dist",
+            "fee_source_added",
+            (source, env.ledger().timestamp()),
+        );
+
+        extend_instance_ttl(&env);
+        Ok(())
+    }
+
+    /// Remove an address from the fee source allowlist
+    pub fn remove_fee_source(env: Env, source: Address) -> Result<(), SharedError> {
+        Self::require_admin(&env)?;
+
+        env.storage()
+            .persistent()
+            .remove(&DataKey::FeeSource(source.clone()));
+
+        let events = EventBuilder::new(&env);
+        events.publish(
+            "fee_dist",
+            "fee_source_removed",
+            (source, env.ledger().timestamp()),
+        );
+
+        extend_instance_ttl(&env);
+        Ok(())
+    }
+
+    /// Require (or stop requiring) `receive_fees` callers to be allowlisted.
+    /// Remember to allowlist the staking pool, which forwards stake fees.
+    pub fn set_require_fee_source(env: Env, required: bool) -> Result<(), SharedError> {
+        Self::require_admin(&env)?;
+
+        env.storage()
+            .instance()
+            .set(&DataKey::RequireFeeSource, &required);
+
+        let events = EventBuilder::new(&env);
+        events.publish(
+            "fee_dist",
+            "fee_source_policy",
+            (required, env.ledger().timestamp()),
+        );
+
+        extend_instance_ttl(&env);
+        Ok(())
+    }
+
+    /// Register the native ASTRO token and its initial supply, used by
+    /// `effective_circulating_supply`
+    pub fn set_native_token(
+        env: Env,
+        token: Address,
+        initial_supply: i128,
+    ) -> Result<(), SharedError> {
+        Self::require_admin(&env)?;
+
+        if initial_supply <= 0 {
+            return Err(SharedError::InvalidAmount);
+        }
+
+        env.storage()
+            .instance()
+            .set(&DataKey::NativeToken, &(token.clone(), initial_supply));
+
+        let events = EventBuilder::new(&env);
+        events.publish(
+            "fee_dist",
+            "native_token_set",
+            (token, initial_supply, env.ledger().timestamp()),
+        );
+
+        extend_instance_ttl(&env);
+        Ok(())
+    }
+
+    /// Set the epoch length in seconds (0 disables epochs)
+    pub fn set_epoch_length(env: Env, secs: u64) -> Result<(), SharedError> {
+        Self::require_admin(&env)?;
+
+        env.storage().instance().set(&DataKey::EpochLength, &secs);
+
+        let events = EventBuilder::new(&env);
+        events.publish(
+            "fee_dist",
+            "epoch_length_set",
+            (secs, env.ledger().timestamp()),
+        );
+
+        extend_instance_ttl(&env);
+        Ok(())
+    }
+
+    /// Set the pending amount above which a token may distribute again within
+    /// the same epoch
+    pub fn set_epoch_bypass_threshold(env: Env, threshold: i128) -> Result<(), SharedError> {
+        Self::require_admin(&env)?;
+
+        if threshold <= 0 {
+            return Err(SharedError::InvalidAmount);
+        }
+
+        env.storage()
+            .instance()
+            .set(&DataKey::EpochBypassThreshold, &threshold);
+        extend_instance_ttl(&env);
+
+        Ok(())
+    }
+
+    /// Emergency withdrawal (admin or `TREASURER`, while withdrawals are paused)
+    pub fn emergency_withdraw(
+        env: Env,
+        caller: Address,
+        token: Address,
+        to: Address,
+        amount: i128,
+    ) -> Result<(), SharedError> {
+        Self::require_role(&env, &caller, &TREASURER)?;
+
+        // Only allow emergency withdrawal while distributions are paused
+        pausable::require_paused(&env, PauseGroup::Withdrawals)?;
+
+        if amount <= 0 {
+            return Err(SharedError::InvalidAmount);
+        }
+
+        // Withdrawn funds come out of pending first so the books stay balanced;
+        // anything beyond pending is untracked surplus.
+        let pending = Self::get_pending_fees(&env, &token);
+        let from_pending = if amount < pending { amount } else { pending };
+        if from_pending > 0 {
+            set_persistent(
+                &env,
+                &DataKey::PendingFees(token.clone()),
+                &(pending - from_pending),
+            );
+
+            let mut ledger = Self::get_ledger(&env, &token);
+            ledger.withdrawn = safe_add(ledger.withdrawn, from_pending)?;
+            Self::save_ledger(&env, &token, &ledger);
+        }
+
+        let token_client = token::Client::new(&env, &token);
+        token_client.transfer(&env.current_contract_address(), &to, &amount);
+
+        let events = EventBuilder::new(&env);
+        events.publish(
+            "fee_dist",
+            "emergency_withdraw",
+            (token, to, amount, env.ledger().timestamp()),
+        );
+
+        Ok(())
+    }
+
+    /// Set how far storage TTLs are extended (see `TtlConfig`), e.g. shorter
+    /// lifetimes on testnet
+    pub fn set_ttl_config(env: Env, caller: Address, config: TtlConfig) -> Result<(), SharedError> {
+        Self::require_role(&env, &caller, &CONFIG_ADMIN)?;
+        pausable::require_not_paused(&env, PauseGroup::AdminOps)?;
+
+        set_ttl_config(&env, &config)?;
+        emit_config_updated(&env, &caller);
+        extend_instance_ttl(&env);
+
+        Ok(())
+    }
+
+    // ────────────────────────────────────────────────────────────────────────
+    // Solvency Monitoring
+    // ────────────────────────────────────────────────────────────────────────
+
+    /// Verify fee conservation for a token.
+    ///
+    /// Returns the discrepancy between what the ledger says the contract holds
+    /// and its actual balance (positive = shortfall, negative = untracked surplus).
+    /// Callable by anyone; if the shortfall exceeds the configured tolerance or
+    /// the ledger entries don't balance, the contract is paused.
+    pub fn assert_solvency(env: Env, token: Address) -> Result<i128, SharedError> {
+        Self::require_initialized(&env)?;
+
+        let ledger = Self::get_ledger(&env, &token);
+        let pending = Self::get_pending_fees(&env, &token);
+
+        let expected = safe_sub(
+            safe_sub(ledger.received, ledger.paid_out)?,
+            ledger.withdrawn,
+        )?;
+        let actual = token::Client::new(&env, &token).balance(&env.current_contract_address());
+        let discrepancy = expected - actual;
+
+        let books_balanced = ledger.paid_out == ledger.total_allocated
+            && pending == ledger.received - ledger.total_allocated - ledger.withdrawn;
+
+        let tolerance: i128 = env
+            .storage()
+            .instance()
+            .get(&DataKey::SolvencyTolerance)
+            .unwrap_or(0);
+
+        if discrepancy > tolerance || !books_balanced {
+            pausable::set(&env, &PauseConfig::uniform(true));
+
+            let events = EventBuilder::new(&env);
+            events.publish(
+                "fee_dist",
+                "solvency_breach",
+                (token, discrepancy, books_balanced, env.ledger().timestamp()),
+            );
+        }
+
+        extend_instance_ttl(&env);
+
+        Ok(discrepancy)
+    }
+
+    // ────────────────────────────────────────────────────────────────────────
+    // Roles (admin only)
+    // ────────────────────────────────────────────────────────────────────────
+
+    /// Grant a role (`PAUSER`, `TREASURER`, `CFG_ADMIN`, `UPGRADER`) to an account
+    pub fn grant_role(env: Env, role: Symbol, account: Address) -> Result<(), SharedError> {
+        Self::require_admin(&env)?;
+        access_control::grant_role(&env, role, account)?;
+        extend_instance_ttl(&env);
+        Ok(())
+    }
+
+    /// Revoke a role from an account
+    pub fn revoke_role(env: Env, role: Symbol, account: Address) -> Result<(), SharedError> {
+        Self::require_admin(&env)?;
+        access_control::revoke_role(&env, role, account)?;
+        extend_instance_ttl(&env);
+        Ok(())
+    }
+
+    // ────────────────────────────────────────────────────────────────────────
+    // Upgrades (admin or UPGRADER)
+    // ────────────────────────────────────────────────────────────────────────
+
+    /// Queue a WASM upgrade behind the upgrade timelock. Returns its eta.
+    pub fn queue_upgrade(
+        env: Env,
+        caller: Address,
+        new_wasm_hash: BytesN<32>,
+    ) -> Result<u64, SharedError> {
+        Self::require_role(&env, &caller, &UPGRADER)?;
+        upgrade::queue(&env, new_wasm_hash)
+    }
+
+    /// Cancel the queued upgrade
+    pub fn cancel_upgrade(env: Env, caller: Address) -> Result<(), SharedError> {
+        Self::require_role(&env, &caller, &UPGRADER)?;
+        upgrade::cancel(&env)
+    }
+
+    /// Switch to the queued WASM once the timelock has elapsed
+    pub fn upgrade(
+        env: Env,
+        caller: Address,
+        new_wasm_hash: BytesN<32>,
+    ) -> Result<(), SharedError> {
+        Self::require_role(&env, &caller, &UPGRADER)?;
+        upgrade::execute(&env, new_wasm_hash)
+    }
+
+    /// Migrate storage to `STORAGE_VERSION` after an upgrade
+    pub fn migrate(env: Env, caller: Address) -> Result<u32, SharedError> {
+        Self::require_role(&env, &caller, &UPGRADER)?;
+        upgrade::migrate(&env, STORAGE_VERSION, |env, from| {
+            if from < 2 {
+                // v2 moved the single pause flag to per-group `PauseConfig`
+                let paused: bool = env
+                    .storage()
+                    .instance()
+                    .get(&DataKey::Paused)
+                    .unwrap_or(false);
+                pausable::set(env, &PauseConfig::uniform(paused));
+                env.storage().instance().remove(&DataKey::Paused);
+            }
+            if from < 3 {
+                // v3 stores recipient shares as `Bps`. Overrides of tokens
+                // that never received fees aren't enumerable; re-set those.
+                let legacy: Option<LegacyDistributionConfig> =
+                    env.storage().instance().get(&DataKey::Config);
+                if let Some(legacy) = legacy {
+                    let config = legacy.into_v3(env)?;
+                    env.storage().instance().set(&DataKey::Config, &config);
+                }
+
+                let queued: Option<LegacyQueuedConfig> =
+                    env.storage().instance().get(&DataKey::QueuedConfig);
+                if let Some(queued) = queued {
+                    let queued = QueuedConfigV3 {
+                        config: queued.config.into_v3(env)?,
+                        eta: queued.eta,
+                    };
+                    env.storage()
+                        .instance()
+                        .set(&DataKey::QueuedConfig, &queued);
+                }
+
+                for token in Self::get_supported_tokens(env).iter() {
+                    let key = DataKey::TokenConfig(token);
+                    let legacy: Option<LegacyDistributionConfig> = get_persistent(env, &key);
+                    if let Some(legacy) = legacy {
+                        set_persistent(env, &key, &legacy.into_v3(env)?);
+                    }
+                }
+            }
+            if from < 4 {
+                // v4 added the circuit breaker caps, off by default
+                let v3: Option<DistributionConfigV3> =
+                    env.storage().instance().get(&DataKey::Config);
+                if let Some(v3) = v3 {
+                    env.storage()
+                        .instance()
+                        .set(&DataKey::Config, &v3.into_current());
+                }
+
+                let queued: Option<QueuedConfigV3> =
+                    env.storage().instance().get(&DataKey::QueuedConfig);
+                if let Some(queued) = queued {
+                    let queued = QueuedConfig {
+                        config: queued.config.into_current(),
+                        eta: queued.eta,
+                    };
+                    env.storage()
+                        .instance()
+                        .set(&DataKey::QueuedConfig, &queued);
+                }
+
+                for token in Self::get_supported_tokens(env).iter() {
+                    let key = DataKey::TokenConfig(token);
+                    let v3: Option<DistributionConfigV3> = get_persistent(env, &key);
+                    if let Some(v3) = v3 {
+                        set_persistent(env, &key, &v3.into_current());
+                    }
+                }
+            }
+            Ok(())
+        })
+    }
+
+    // ────────────────────────────────────────────────────────────────────────
+    // Query Functions
+    // ────────────────────────────────────────────────────────────────────────
+
+    /// Whether an account was granted a role (the admin implicitly holds all)
+    pub fn has_role(env: Env, role: Symbol, account: Address) -> bool {
+        access_control::has_role(&env, &role, &account)
+    }
+
+    /// Get the storage layout version
+    pub fn version(env: Env) -> u32 {
+        upgrade::version(&env)
+    }
+
+    /// Get the queued upgrade, if any
+    pub fn get_pending_upgrade(env: Env) -> Option<PendingUpgrade> {
+        upgrade::pending(&env)
+    }
+
+    /// Get the double-entry ledger for a token
+    pub fn get_token_ledger(env: Env, token: Address) -> TokenLedger {
+        Self::get_ledger(&env, &token)
+    }
+
+    /// Get the shortfall tolerated before auto-pause
+    pub fn solvency_tolerance(env: Env) -> i128 {
+        env.storage()
+            .instance()
+            .get(&DataKey::SolvencyTolerance)
+            .unwrap_or(0)
+    }
+
+    /// Get current distribution configuration
+    pub fn get_config(env: Env) -> Result<DistributionConfig, SharedError> {
+        env.storage()
+            .instance()
+            .get(&DataKey::Config)
+            .ok_or(SharedError::NotInitialized)
+    }
+
+    /// Get the current lifecycle mode
+    pub fn mode(env: Env) -> LifecycleMode {
+        Self::get_mode(&env)
+    }
+
+    /// Get the epoch length in seconds (0 = epochs disabled)
+    pub fn get_epoch_length(env: Env) -> u64 {
+        Self::load_epoch_length(&env)
+    }
+
+    /// Get the distribution history of a token for epoch `n`
+    pub fn get_epoch(env: Env, token: Address, n: u64) -> Option<DistributionEpoch> {
+        get_persistent(&env, &DataKey::Epoch(token, n))
+    }
+
+    /// Get the current epoch number, if epochs are enabled
+    pub fn current_epoch(env: Env) -> Option<u64> {
+        match Self::load_epoch_length(&env) {
+            0 => None,
+            length => Some(env.ledger().timestamp() / length),
+        }
+    }
+
+    /// Get the cumulative amount burned (or sent to the burn address) for a token
+    pub fn get_total_burned(env: Env, token: Address) -> i128 {
+        get_persistent(&env, &DataKey::TotalBurned(token)).unwrap_or(0)
+    }
+
+    /// Get the cumulative staking share distributed for a token
+    pub fn get_total_to_staking(env: Env, token: Address) -> i128 {
+        get_persistent(&env, &DataKey::TotalToStaking(token)).unwrap_or(0)
+    }
+
+    /// Get the cumulative insurance share distributed for a token
+    pub fn get_total_to_insurance(env: Env, token: Address) -> i128 {
+        get_persistent(&env, &DataKey::TotalToInsurance(token)).unwrap_or(0)
+    }
+
+    /// Get the default config's combined insurance share in basis points
+    pub fn insurance_bps(env: Env) -> Result<u32, SharedError> {
+        let config = Self::get_config_internal(&env)?;
+        Ok(config
+            .recipients
+            .iter()
+            .filter(|r| r.kind == RecipientKind::Insurance)
+            .map(|r| r.bps.get())
+            .sum())
+    }
+
+    /// Get up to `limit` of the most recent distributions for a token, newest first.
+    /// Only the last `MAX_HISTORY` (50) are kept.
+    pub fn get_distribution_history(
+        env: Env,
+        token: Address,
+        limit: u32,
+    ) -> Vec<DistributionResult> {
+        let count: u32 = get_persistent(&env, &DataKey::HistoryCount(token.clone())).unwrap_or(0);
+        let available = count.min(MAX_HISTORY);
+
+        let mut history = Vec::new(&env);
+        for i in 0..limit.min(available) {
+            let slot = (count - 1 - i) % MAX_HISTORY;
+            if let Some(result) = get_persistent(&env, &DataKey::History(token.clone(), slot)) {
+                history.push_back(result);
+            }
+        }
+        history
+    }
+
+    /// Get the deflation-adjusted ASTRO supply: initial supply minus everything
+    /// burned through this distributor. Only available when the native token is
+    /// registered and its burn share goes through the token's own `burn`.
+    pub fn effective_circulating_supply(env: Env) -> Result<i128, SharedError> {
+        let (token, initial_supply): (Address, i128) = env
+            .storage()
+            .instance()
+            .get(&DataKey::NativeToken)
+            .ok_or(SharedError::InvalidState)?;
+
+        if Self::effective_config(&env, &token)?.burn_mode != BurnMode::Burn {
+            return Err(SharedError::InvalidState);
+        }
+
+        let burned: i128 = get_persistent(&env, &DataKey::TotalBurned(token)).unwrap_or(0);
+        safe_sub(initial_supply, burned)
+    }
+
+    /// Get the queued config and its activation time, if any
+    pub fn get_queued_config(env: Env) -> Option<QueuedConfig> {
+        env.storage().instance().get(&DataKey::QueuedConfig)
+    }
+
+    /// Compute how the pending fees of a token would be split right now,
+    /// without moving funds (conversion routes are not simulated)
+    pub fn preview_distribution(
+        env: Env,
+        token: Address,
+    ) -> Result<DistributionResult, SharedError> {
+        let config = Self::effective_config(&env, &token)?;
+        let pending = Self::get_pending_fees(&env, &token);
+        Self::preview_split(&env, &token, &config, pending)
+    }
+
+    /// Like `preview_distribution`, but with the queued config (tokens with a
+    /// per-token override are unaffected by it and preview their override)
+    pub fn preview_queued_distribution(
+        env: Env,
+        token: Address,
+    ) -> Result<DistributionResult, SharedError> {
+        let queued: QueuedConfig = env
+            .storage()
+            .instance()
+            .get(&DataKey::QueuedConfig)
+            .ok_or(SharedError::InvalidState)?;
+        let mut config =
+            Self::get_token_config(env.clone(), token.clone()).unwrap_or(queued.config);
+        if let Some(min) = Self::get_min_distribution_override(&env, &token) {
+            config.min_distribution = min;
+        }
+        let pending = Self::get_pending_fees(&env, &token);
+        Self::preview_split(&env, &token, &config, pending)
+    }
+
+    /// Preview every token `distribute_all` would distribute, without moving
+    /// funds, so keepers can see which tokens are worth the fee first.
+    /// Tokens below their minimum are left out; as in `preview_distribution`,
+    /// conversion routes are not simulated, and neither is the circuit
+    /// breaker.
+    pub fn preview_distribute_all(env: Env) -> Vec<DistributionResult> {
+        let mut results = Vec::new(&env);
+        let count = Self::get_token_count(&env);
+        for token in Self::get_tokens_between(&env, 0, count).iter() {
+            if let Ok(result) = Self::preview_distribution(env.clone(), token) {
+                results.push_back(result);
+            }
+        }
+        results
+    }
+
+    /// Get the canonical reward token, if set
+    pub fn reward_token(env: Env) -> Option<Address> {
+        Self::get_reward_token(&env)
+    }
+
+    /// Get the conversion route for a token, if any
+    pub fn get_conversion_route(env: Env, token: Address) -> Option<ConversionRoute> {
+        get_persistent(&env, &DataKey::ConversionRoute(token))
+    }
+
+    /// Get a token's minimum distribution override, if any
+    pub fn min_distribution_override(env: Env, token: Address) -> Option<i128> {
+        Self::get_min_distribution_override(&env, &token)
+    }
+
+    /// Get the pending amount `token` needs before it can be distributed:
+    /// its own override, else the `min_distribution` of its effective config
+    pub fn effective_min_distribution(env: Env, token: Address) -> Result<i128, SharedError> {
+        Ok(Self::effective_config(&env, &token)?.min_distribution)
+    }
+
+    /// Get the distribution config override for a token, if any
+    pub fn get_token_config(env: Env, token: Address) -> Option<DistributionConfig> {
+        get_persistent(&env, &DataKey::TokenConfig(token))
+    }
+
+    /// Get the config that will be used to distribute a token
+    pub fn get_effective_config(
+        env: Env,
+        token: Address,
+    ) -> Result<DistributionConfig, SharedError> {
+        Self::effective_config(&env, &token)
+    }
+
+    /// Get pending fees for a token
+    pub fn get_pending_distribution(env: Env, token: Address) -> i128 {
+        Self::get_pending_fees(&env, &token)
+    }
+
+    /// Compare the balance of each supported token with its pending fees
+    pub fn solvency_report(env: Env) -> Vec<TokenSolvency> {
+        let this = env.current_contract_address();
+        let mut report = Vec::new(&env);
+        for fee_token in Self::get_supported_tokens(&env).iter() {
+            report.push_back(TokenSolvency {
+                balance: token::Client::new(&env, &fee_token).balance(&this),
+                liabilities: Self::get_pending_fees(&env, &fee_token),
+                token: fee_token,
+            });
+        }
+        report
+    }
+
+    /// Get total distributed for a token
+    pub fn get_total_distributed_for_token(env: Env, token: Address) -> i128 {
+        Self::get_total_distributed(&env, &token)
+    }
+
+    /// Get all supported tokens
+    pub fn get_tokens(env: Env) -> Vec<Address> {
+        Self::get_supported_tokens(&env)
+    }
+
+    /// Get up to `limit` supported tokens (capped at 20) starting at `start`
+    pub fn get_tokens_page(env: Env, start: u32, limit: u32) -> Vec<Address> {
+        let end = start
+            .saturating_add(limit.min(MAX_PAGE_SIZE))
+            .min(Self::get_token_count(&env));
+        Self::get_tokens_between(&env, start, end)
+    }
+
+    /// Check if an address is an allowlisted fee source
+    pub fn is_fee_source(env: Env, source: Address) -> bool {
+        Self::is_fee_source_internal(&env, &source)
+    }
+
+    /// Check if `receive_fees` only accepts allowlisted sources
+    pub fn requires_fee_source(env: Env) -> bool {
+        Self::fee_source_required(&env)
+    }
+
+    /// Get the number of supported tokens
+    pub fn token_count(env: Env) -> u32 {
+        Self::get_token_count(&env)
+    }
+
+    /// Get admin address
+    pub fn admin(env: Env) -> Result<Address, SharedError> {
+        env.storage()
+            .instance()
+            .get(&DataKey::Admin)
+            .ok_or(SharedError::NotInitialized)
+    }
+
+    /// Get the storage TTL config
+    pub fn ttl_config(env: Env) -> TtlConfig {
+        get_ttl_config(&env)
+    }
+
+    /// Get the token registry, if set
+    pub fn token_registry(env: Env) -> Option<Address> {
+        env.storage().instance().get(&DataKey::TokenRegistry)
+    }
+
+    /// Get the native XLM contract used by `receive_native`
+    pub fn native_asset(env: Env) -> Address {
+        env.storage()
+            .instance()
+            .get(&DataKey::NativeAsset)
+            .unwrap_or_else(|| Self::native_sac_address(&env))
+    }
+
+    /// Get the proposed admin awaiting acceptance
+    pub fn pending_admin(env: Env) -> Option<Address> {
+        ownership::pending(&env)
+    }
+
+    /// Whether any function group is paused
+    pub fn is_paused(env: Env) -> bool {
+        pausable::get(&env).is_any()
+    }
+
+    /// Get the pause flags per function group
+    pub fn pause_config(env: Env) -> PauseConfig {
+        pausable::get(&env)
+    }
+
+    /// Describe this contract for explorers and wallets
+    pub fn contract_info(env: Env) -> Result<ContractInfo, SharedError> {
+        let admin = Self::admin(env.clone())?;
+        let config = Self::get_config_internal(&env)?;
+        let info = ContractInfo::new(
+            &env,
+            "fee_dist",
+            env!("CARGO_PKG_VERSION"),
+            admin,
+            Self::is_paused(env.clone()),
+            env.storage().instance().get(&DataKey::Registry),
+        );
+        Ok(info
+            .with("recipients", config.recipients.len() as i128)
+            .with("min_distribution", config.min_distribution)
+            .with("burn_mode", config.burn_mode as i128)
+            .with("mode", Self::get_mode(&env) as i128)
+            .with("epoch_length", Self::load_epoch_length(&env) as i128)
+            .with("tokens", Self::get_token_count(&env) as i128))
+    }
+
+    /// Get contract balance for a token
+    pub fn balance(env: Env, token: Address) -> i128 {
+        let token_client = token::Client::new(&env, &token);
+        token_client.balance(&env.current_contract_address())
+    }
+
+    // ────────────────────────────────────────────────────────────────────────
+    // Internal Helpers
+    // ────────────────────────────────────────────────────────────────────────
+
+    fn get_config_internal(env: &Env) -> Result<DistributionConfig, SharedError> {
+        env.storage()
+            .instance()
+            .get(&DataKey::Config)
+            .ok_or(SharedError::NotInitialized)
+    }
+
+    /// Hand the staking share to the pool through `add_rewards` so it updates
+    /// reward-per-share accounting (a plain transfer would be invisible to stakers).
+    /// Returns false, leaving the share here, if the pool rejects the call.
+    fn push_staking_rewards(
+        env: &Env,
+        token: &Address,
+        staking_pool: &Address,
+        amount: i128,
+    ) -> bool {
+        let this = env.current_contract_address();
+
+        // The pool pulls the rewards from this contract
+        env.authorize_as_current_contract(vec![
+            env,
+            InvokerContractAuthEntry::Contract(SubContractInvocation {
+                context: ContractContext {
+                    contract: token.clone(),
+                    fn_name: Symbol::new(env, "transfer"),
+                    args: (this.clone(), staking_pool.clone(), amount).into_val(env),
+                },
+                sub_invocations: Vec::new(env),
+            }),
+        ]);
+
+        StakingPoolClient::new(env, staking_pool)
+            .try_add_rewards(&this, token, &amount)
+            .into_shared()
+            .is_ok()
+    }
+
+    fn get_reward_token(env: &Env) -> Option<Address> {
+        env.storage().instance().get(&DataKey::RewardToken)
+    }
+
+    /// Swap `amount` of a token's pending fees into the reward token when it has
+    /// a conversion route. Returns the token and amount to distribute.
+    /// Panics with `BelowMinimum` if the swap pays out less than the
+    /// TWAP-bounded minimum.
+    fn convert_pending(
+        env: &Env,
+        token: &Address,
+        amount: i128,
+    ) -> Result<(Address, i128), SharedError> {
+        let route: Option<ConversionRoute> =
+            get_persistent(env, &DataKey::ConversionRoute(token.clone()));
+        let route = match route {
+            Some(route) => route,
+            None => return Ok((token.clone(), amount)),
+        };
+        let reward_token = Self::get_reward_token(env).ok_or(SharedError::InvalidState)?;
+
+        let pending = Self::get_pending_fees(env, token);
+        if amount > pending {
+            return Err(SharedError::InsufficientBalance);
+        }
+        if amount <= 0 {
+            return Err(SharedError::BelowMinimum);
+        }
+
+        // Quote at the TWAP and bound slippage (spot reserves can be moved
+        // by a sandwiching trade)
+        let quote = OracleClient::new(env, &route.oracle)
+            .try_consult(&route.pair, token, &amount, &route.window)
+            .into_shared()?;
+        let min_out = safe_div(safe_mul(quote, route.min_out_bps as i128)?, BPS_DENOMINATOR)?;
+
+        let this = env.current_contract_address();
+        let reward_client = token::Client::new(env, &reward_token);
+        let before = reward_client.balance(&this);
+
+        // The pair pulls the input from this contract. The authorization only
+        // covers the next contract call, so nothing may run before the swap.
+        env.authorize_as_current_contract(vec![
+            env,
+            InvokerContractAuthEntry::Contract(SubContractInvocation {
+                context: ContractContext {
+                    contract: token.clone(),
+                    fn_name: Symbol::new(env, "transfer"),
+                    args: (this.clone(), route.pair.clone(), amount).into_val(env),
+                },
+                sub_invocations: Vec::new(env),
+            }),
+        ]);
+
+        AmmPairClient::new(env, &route.pair).swap(&this, token, &amount, &min_out);
+        let received = safe_sub(reward_client.balance(&this), before)?;
+        // The swap already moved tokens, so a short output must revert it
+        // rather than return an error a batch caller could skip
+        if received < min_out {
+            panic_with_error!(env, SharedError::BelowMinimum);
+        }
+
+        // Input leaves through the pair
+        let mut ledger = Self::get_ledger(env, token);
+        Self::record_allocation(&mut ledger, &route.pair, amount)?;
+        ledger.paid_out = safe_add(ledger.paid_out, amount)?;
+        Self::save_ledger(env, token, &ledger);
+
+        let pending_key = DataKey::PendingFees(token.clone());
+        set_persistent(env, &pending_key, &safe_sub(pending, amount)?);
+
+        // Output is credited as reward-token fees
+        let mut reward_ledger = Self::get_ledger(env, &reward_token);
+        reward_ledger.received = safe_add(reward_ledger.received, received)?;
+        Self::save_ledger(env, &reward_token, &reward_ledger);
+
+        let reward_pending_key = DataKey::PendingFees(reward_token.clone());
+        let reward_pending = Self::get_pending_fees(env, &reward_token);
+        set_persistent(
+            env,
+            &reward_pending_key,
+            &safe_add(reward_pending, received)?,
+        );
+
+        Self::add_supported_token(env, &reward_token);
+
+        let events = EventBuilder::new(env);
+        events.publish(
+            "fee_dist",
+            "fees_converted",
+            (
+                token.clone(),
+                reward_token.clone(),
+                amount,
+                received,
+                env.ledger().timestamp(),
+            ),
+        );
+
+        Ok((reward_token, received))
+    }
+
+    /// Destroy a burn share: a real `burn` when configured and supported,
+    /// otherwise a transfer to the burn address
+    fn burn_share(
+        env: &Env,
+        token: &Address,
+        burn_address: &Address,
+        amount: i128,
+        mode: BurnMode,
+    ) -> Result<(), SharedError> {
+        let token_client = token::Client::new(env, token);
+        let this = env.current_contract_address();
+
+        let burned =
+            mode == BurnMode::Burn && matches!(token_client.try_burn(&this, &amount), Ok(Ok(())));
+        if !burned {
+            token_client.transfer(&this, burn_address, &amount);
+        }
+
+        let key = DataKey::TotalBurned(token.clone());
+        let total: i128 = get_persistent(env, &key).unwrap_or(0);
+        set_persistent(env, &key, &safe_add(total, amount)?);
+
+        let events = EventBuilder::new(env);
+        events.publish(
+            "fee_dist",
+            "burned",
+            (token.clone(), amount, burned, env.ledger().timestamp()),
+        );
+
+        Ok(())
+    }
+
+    /// Token override if set, otherwise the global config, with the token's
+    /// minimum distribution override applied on top
+    fn effective_config(env: &Env, token: &Address) -> Result<DistributionConfig, SharedError> {
+        let mut config = match get_persistent(env, &DataKey::TokenConfig(token.clone())) {
+            Some(config) => config,
+            None => Self::get_config_internal(env)?,
+        };
+        if let Some(min) = Self::get_min_distribution_override(env, token) {
+            config.min_distribution = min;
+        }
+        Ok(config)
+    }
+
+    fn get_min_distribution_override(env: &Env, token: &Address) -> Option<i128> {
+        get_persistent(env, &DataKey::MinDistribution(token.clone()))
+    }
+
+    /// Validate a default config's split and that its recipients fit the mode
+    fn check_config(env: &Env, config: &DistributionConfig) -> Result<(), SharedError> {
+        if !config.is_valid() {
+            return Err(SharedError::InvalidPercentage);
+        }
+        if !Self::get_mode(env).allows_default(config) {
+            return Err(SharedError::InvalidState);
+        }
+        Ok(())
+    }
+
+    fn get_mode(env: &Env) -> LifecycleMode {
+        env.storage()
+            .instance()
+            .get(&DataKey::Mode)
+            .unwrap_or(LifecycleMode::Normal)
+    }
+
+    /// Validate and store initial state
+    fn init_state(
+        env: &Env,
+        admin: &Address,
+        config: &DistributionConfig,
+        mode: LifecycleMode,
+    ) -> Result<(), SharedError> {
+        // Validate percentages sum to 100%
+        if !config.is_valid() || !mode.allows_default(config) {
+            return Err(SharedError::InvalidPercentage);
+        }
+
+        // Store initial state
+        env.storage().instance().set(&DataKey::Admin, admin);
+        env.storage().instance().set(&DataKey::Config, config);
+        env.storage().instance().set(&DataKey::Mode, &mode);
+        env.storage().instance().set(&DataKey::Initialized, &true);
+        upgrade::init_version(env, STORAGE_VERSION);
+        env.storage().instance().set(&DataKey::TokenCount, &0u32);
+        env.storage()
+            .instance()
+            .set(&DataKey::NativeAsset, &Self::native_sac_address(env));
+
+        extend_instance_ttl(env);
+
+        // Emit init event
+        let events = EventBuilder::new(env);
+        events.publish(
+            "fee_dist",
+            "initialized",
+            (admin.clone(), mode, env.ledger().timestamp()),
+        );
+
+        Ok(())
+    }
+
+    /// Validate and store the distribution config
+    fn save_config(env: &Env, config: &DistributionConfig) -> Result<(), SharedError> {
+        Self::check_config(env, config)?;
+        env.storage().instance().set(&DataKey::Config, config);
+        Ok(())
+    }
+
+    /// Split `amount` of a token's pending fees between recipients.
+    /// The remainder (if any) stays pending.
+    fn execute_distribution(
+        env: &Env,
+        token: &Address,
+        amount: i128,
+    ) -> Result<DistributionResult, SharedError> {
+        let config = Self::effective_config(env, token)?;
+
+        let pending = Self::get_pending_fees(env, token);
+        if amount > pending {
+            return Err(SharedError::InsufficientBalance);
+        }
+        if amount <= 0 || amount < config.min_distribution {
+            return Err(SharedError::BelowMinimum);
+        }
+
+        // A tripped breaker must still return Ok so the pause persists
+        if Self::check_circuit_breaker(env, token, &config, amount)? {
+            let shares = Self::calculate_shares(env, &config, 0)?;
+            return Self::summarize(env, token, &config, &shares, 0);
+        }
+
+        Self::record_epoch(env, token, pending, amount)?;
+
+        let token_client = token::Client::new(env, token);
+
+        // Calculate distribution amounts
+        let shares = Self::calculate_shares(env, &config, amount)?;
+
+        // Debit the ledger: allocate to each recipient before any transfer
+        let mut ledger = Self::get_ledger(env, token);
+        for (recipient, share) in config.recipients.iter().zip(shares.iter()) {
+            Self::record_allocation(&mut ledger, &recipient.address, share)?;
+        }
+
+        // Execute transfers
+        for (recipient, share) in config.recipients.iter().zip(shares.iter()) {
+            if share > 0 && recipient.kind == RecipientKind::Burn {
+                Self::burn_share(env, token, &recipient.address, share, config.burn_mode)?;
+            } else if share > 0
+                && recipient.kind == RecipientKind::Staking
+                && !config.staking_raw_transfer
+            {
+                // A pool that rejects add_rewards (paused, token not allowed)
+                // still gets its share, as a raw transfer
+                if !Self::push_staking_rewards(env, token, &recipient.address, share) {
+                    token_client.transfer(
+                        &env.current_contract_address(),
+                        &recipient.address,
+                        &share,
+                    );
+                    let events = EventBuilder::new(env);
+                    events.publish(
+                        "fee_dist",
+                        "staking_push_failed",
+                        (token.clone(), recipient.address.clone(), share),
+                    );
+                }
+            } else if share > 0 {
+                token_client.transfer(&env.current_contract_address(), &recipient.address, &share);
+            }
+        }
+
+        // Settle the ledger
+        ledger.paid_out = safe_add(ledger.paid_out, amount)?;
+        Self::save_ledger(env, token, &ledger);
+
+        // Update state
+        let pending_fees_key = DataKey::PendingFees(token.clone());
+        set_persistent(env, &pending_fees_key, &safe_sub(pending, amount)?);
+
+        let prev_total = Self::get_total_distributed(env, token);
+        let new_total = safe_add(prev_total, amount)?;
+        set_persistent(env, &DataKey::TotalDistributed(token.clone()), &new_total);
+
+        let result = Self::summarize(env, token, &config, &shares, amount)?;
+
+        // Emit event
+        emit_distribution(
+            env,
+            token,
+            amount,
+            result.treasury_amount,
+            result.staking_amount,
+            result.burn_amount,
+        );
+
+        Self::record_history(env, &result)?;
+
+        extend_instance_ttl(env);
+
+        Ok(result)
+    }
+
+    /// Count `amount` against the token's daily cap, or, if it breaks the
+    /// per-call or per-day cap, pause distributions and raise an alert.
+    /// Returns whether the breaker tripped.
+    fn check_circuit_breaker(
+        env: &Env,
+        token: &Address,
+        config: &DistributionConfig,
+        amount: i128,
+    ) -> Result<bool, SharedError> {
+        let day = env.ledger().timestamp() / SECONDS_PER_DAY;
+        let key = DataKey::DailyDistributed(token.clone());
+        let mut daily = match get_persistent::<_, DailyDistribution>(env, &key) {
+            Some(daily) if daily.day == day => daily,
+            _ => DailyDistribution { day, amount: 0 },
+        };
+        let day_total = safe_add(daily.amount, amount)?;
+
+        let breach = if config.max_distribution_per_call > 0
+            && amount > config.max_distribution_per_call
+        {
+            Some((
+                Symbol::new(env, "per_call"),
+                config.max_distribution_per_call,
+            ))
+        } else if config.max_distribution_per_day > 0 && day_total > config.max_distribution_per_day
+        {
+            Some((Symbol::new(env, "per_day"), config.max_distribution_per_day))
+        } else {
+            None
+        };
+
+        match breach {
+            Some((limit_kind, limit)) => {
+                let mut pause = pausable::get(env);
+                pause.withdrawals = true;
+                pausable::set(env, &pause);
+
+                let events = EventBuilder::new(env);
+                events.publish(
+                    "fee_dist",
+                    "circuit_breaker",
+                    (
+                        token.clone(),
+                        limit_kind,
+                        amount,
+                        limit,
+                        env.ledger().timestamp(),
+                    ),
+                );
+                extend_instance_ttl(env);
+                Ok(true)
+            }
+            None => {
+                daily.amount = day_total;
+                set_persistent(env, &key, &daily);
+                Ok(false)
+            }
+        }
+    }
+
+    /// Split `amount` by recipient bps; rounding dust goes to the treasury
+    /// (first recipient if none)
+    fn calculate_shares(
+        env: &Env,
+        config: &DistributionConfig,
+        amount: i128,
+    ) -> Result<Vec<i128>, SharedError> {
+        let mut shares: Vec<i128> = Vec::new(env);
+        let mut total_calculated: i128 = 0;
+        for recipient in config.recipients.iter() {
+            let share = safe_div(
+                safe_mul(amount, recipient.bps.get() as i128)?,
+                BPS_DENOMINATOR,
+            )?;
+            total_calculated = safe_add(total_calculated, share)?;
+            shares.push_back(share);
+        }
+
+        let dust = amount - total_calculated;
+        let dust_index = config
+            .recipients
+            .iter()
+            .position(|r| r.kind == RecipientKind::Treasury)
+            .unwrap_or(0) as u32;
+        shares.set(dust_index, safe_add(shares.get(dust_index).unwrap(), dust)?);
+
+        Ok(shares)
+    }
+
+    /// Aggregate per-recipient shares by recipient kind
+    fn summarize(
+        env: &Env,
+        token: &Address,
+        config: &DistributionConfig,
+        shares: &Vec<i128>,
+        amount: i128,
+    ) -> Result<DistributionResult, SharedError> {
+        let mut result = DistributionResult {
+            token: token.clone(),
+            total_amount: amount,
+            treasury_amount: 0,
+            staking_amount: 0,
+            burn_amount: 0,
+            other_amount: 0,
+            insurance_amount: 0,
+            timestamp: env.ledger().timestamp(),
+        };
+        for (recipient, share) in config.recipients.iter().zip(shares.iter()) {
+            let total = match recipient.kind {
+                RecipientKind::Treasury => &mut result.treasury_amount,
+                RecipientKind::Staking => &mut result.staking_amount,
+                RecipientKind::Burn => &mut result.burn_amount,
+                RecipientKind::Other => &mut result.other_amount,
+                RecipientKind::Insurance => &mut result.insurance_amount,
+            };
+            *total = safe_add(*total, share)?;
+        }
+        Ok(result)
+    }
+
+    /// Split `amount` of a token under `config` without executing it
+    fn preview_split(
+        env: &Env,
+        token: &Address,
+        config: &DistributionConfig,
+        amount: i128,
+    ) -> Result<DistributionResult, SharedError> {
+        if amount <= 0 || amount < config.min_distribution {
+            return Err(SharedError::BelowMinimum);
+        }
+        let shares = Self::calculate_shares(env, config, amount)?;
+        Self::summarize(env, token, config, &shares, amount)
+    }
+
+    /// Push a distribution into the token's ring buffer and update aggregates
+    fn record_history(env: &Env, result: &DistributionResult) -> Result<(), SharedError> {
+        let count_key = DataKey::HistoryCount(result.token.clone());
+        let count: u32 = get_persistent(env, &count_key).unwrap_or(0);
+
+        let slot_key = DataKey::History(result.token.clone(), count % MAX_HISTORY);
+        set_persistent(env, &slot_key, result);
+
+        set_persistent(env, &count_key, &count.saturating_add(1));
+
+        if result.staking_amount > 0 {
+            let key = DataKey::TotalToStaking(result.token.clone());
+            let total: i128 = get_persistent(env, &key).unwrap_or(0);
+            set_persistent(env, &key, &safe_add(total, result.staking_amount)?);
+        }
+        if result.insurance_amount > 0 {
+            let key = DataKey::TotalToInsurance(result.token.clone());
+            let total: i128 = get_persistent(env, &key).unwrap_or(0);
+            set_persistent(env, &key, &safe_add(total, result.insurance_amount)?);
+        }
+
+        Ok(())
+    }
+
+    fn load_epoch_length(env: &Env) -> u64 {
+        env.storage()
+            .instance()
+            .get(&DataKey::EpochLength)
+            .unwrap_or(0)
+    }
+
+    /// Enforce one distribution per token per epoch (unless `pending` exceeds
+    /// the bypass threshold) and add `amount` to the epoch's history
+    fn record_epoch(
+        env: &Env,
+        token: &Address,
+        pending: i128,
+        amount: i128,
+    ) -> Result<(), SharedError> {
+        let length = Self::load_epoch_length(env);
+        if length == 0 {
+            return Ok(());
+        }
+
+        let now = env.ledger().timestamp();
+        let n = now / length;
+        let key = DataKey::Epoch(token.clone(), n);
+        let existing: Option<DistributionEpoch> = get_persistent(env, &key);
+
+        let epoch = match existing {
+            Some(mut epoch) => {
+                let threshold: Option<i128> =
+                    env.storage().instance().get(&DataKey::EpochBypassThreshold);
+                match threshold {
+                    Some(threshold) if pending > threshold => {}
+                    _ => return Err(SharedError::CooldownNotElapsed),
+                }
+                epoch.amount = safe_add(epoch.amount, amount)?;
+                epoch.distributions += 1;
+                epoch.last_at = now;
+                epoch
+            }
+            None => DistributionEpoch {
+                epoch: n,
+                amount,
+                distributions: 1,
+                first_at: now,
+                last_at: now,
+            },
+        };
+
+        set_persistent(env, &key, &epoch);
+        Ok(())
+    }
+
+    /// Address of the native XLM Stellar Asset Contract on this network
+    fn native_sac_address(env: &Env) -> Address {
+        // XDR of `Asset::Native` (discriminant 0)
+        let native = Bytes::from_array(env, &[0u8; 4]);
+        env.deployer().with_stellar_asset(native).deployed_address()
+    }
+
+    /// Reject `token` if a token registry is set and does not allow it
+    fn require_token_allowed(env: &Env, token: &Address) -> Result<(), SharedError> {
+        let registry: Option<Address> = env.storage().instance().get(&DataKey::TokenRegistry);
+        if let Some(registry) = registry {
+            if !TokenRegistryClient::new(env, &registry).is_token_allowed(token) {
+                return Err(SharedError::TokenNotAllowed);
+            }
+        }
+        Ok(())
+    }
+
+    fn require_initialized(env: &Env) -> Result<(), SharedError> {
+        let initialized: bool = env
+            .storage()
+            .instance()
+            .get(&DataKey::Initialized)
+            .unwrap_or(false);
+
+        if !initialized {
+            return Err(SharedError::NotInitialized);
+        }
+        Ok(())
+    }
+
+    fn require_admin(env: &Env) -> Result<(), SharedError> {
+        let admin: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::Admin)
+            .ok_or(SharedError::NotInitialized)?;
+
+        admin.require_auth();
+        Ok(())
+    }
+
+    /// Require `caller`'s auth and that it is the admin or holds `role`
+    fn require_role(env: &Env, caller: &Address, role: &Symbol) -> Result<(), SharedError> {
+        let admin: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::Admin)
+            .ok_or(SharedError::NotInitialized)?;
+        access_control::require_role(env, role, caller, &admin)
+    }
+
+    fn get_pending_fees(env: &Env, token: &Address) -> i128 {
+        get_persistent(env, &DataKey::PendingFees(token.clone())).unwrap_or(0)
+    }
+
+    fn get_total_distributed(env: &Env, token: &Address) -> i128 {
+        get_persistent(env, &DataKey::TotalDistributed(token.clone())).unwrap_or(0)
+    }
+
+    fn get_ledger(env: &Env, token: &Address) -> TokenLedger {
+        get_persistent(env, &DataKey::Ledger(token.clone())).unwrap_or(TokenLedger {
+            received: 0,
+            allocated: Map::new(env),
+            total_allocated: 0,
+            paid_out: 0,
+            withdrawn: 0,
+        })
+    }
+
+    fn save_ledger(env: &Env, token: &Address, ledger: &TokenLedger) {
+        let key = DataKey::Ledger(token.clone());
+        set_persistent(env, &key, ledger);
+    }
+
+    fn record_allocation(
+        ledger: &mut TokenLedger,
+        recipient: &Address,
+        amount: i128,
+    ) -> Result<(), SharedError> {
+        if amount <= 0 {
+            return Ok(());
+        }
+        let current = ledger.allocated.get(recipient.clone()).unwrap_or(0);
+        ledger
+            .allocated
+            .set(recipient.clone(), safe_add(current, amount)?);
+        ledger.total_allocated = safe_add(ledger.total_allocated, amount)?;
+        Ok(())
+    }
+
+    /// Distribute every token in `[start, end)` that has reached its minimum.
+    /// Tokens with nothing to distribute, and the rest once a circuit breaker
+    /// paused distributions, are skipped; any other failure fails the batch
+    /// so nothing it already changed persists.
+    fn distribute_tokens(
+        env: &Env,
+        start: u32,
+        end: u32,
+    ) -> Result<Vec<DistributionResult>, SharedError> {
+        let mut results = Vec::new(env);
+
+        for token in Self::get_tokens_between(env, start, end).iter() {
+            let pending = Self::get_pending_fees(env, &token);
+            let config = Self::effective_config(env, &token)?;
+
+            if pending >= config.min_distribution {
+                match Self::distribute(env.clone(), token, None) {
+                    Ok(result) => results.push_back(result),
+                    Err(SharedError::BelowMinimum | SharedError::ContractPaused) => continue,
+                    Err(err) => return Err(err),
+                }
+            }
+        }
+
+        Ok(results)
+    }
+
+    fn fee_source_required(env: &Env) -> bool {
+        env.storage()
+            .instance()
+            .get(&DataKey::RequireFeeSource)
+            .unwrap_or(false)
+    }
+
+    fn is_fee_source_internal(env: &Env, source: &Address) -> bool {
+        get_persistent(env, &DataKey::FeeSource(source.clone())).unwrap_or(false)
+    }
+
+    fn get_token_count(env: &Env) -> u32 {
+        env.storage()
+            .instance()
+            .get(&DataKey::TokenCount)
+            .unwrap_or(0)
+    }
+
+    fn get_tokens_between(env: &Env, start: u32, end: u32) -> Vec<Address> {
+        let mut tokens = Vec::new(env);
+        for index in start..end {
+            if let Some(token) = get_persistent::<DataKey, Address>(env, &DataKey::TokenAt(index)) {
+                tokens.push_back(token);
+            }
+        }
+        tokens
+    }
+
+    /// All supported tokens; prefer paging (`get_tokens_between`) in hot paths
+    fn get_supported_tokens(env: &Env) -> Vec<Address> {
+        Self::get_tokens_between(env, 0, Self::get_token_count(env))
+    }
+
+    fn add_supported_token(env: &Env, token: &Address) {
+        let index_key = DataKey::TokenIndex(token.clone());
+        if env.storage().persistent().has(&index_key) {
+            extend_persistent_ttl(env, &index_key);
+            return;
+        }
+
+        let count = Self::get_token_count(env);
+        let slot_key = DataKey::TokenAt(count);
+        set_persistent(env, &slot_key, token);
+        set_persistent(env, &index_key, &count);
+        env.storage()
+            .instance()
+            .set(&DataKey::TokenCount, &(count + 1));
+    }
+}
+
+// ════════════════════════════════════════════════════════════════════════════
+// Tests
+// ════════════════════════════════════════════════════════════════════════════
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use astro_core_shared::testutils::create_token;
+    use soroban_sdk::testutils::{Address as _, Events as _, Ledger as _};
+
+    /// Staking pool stand-in that pulls rewards like `StakingPool::add_rewards`
+    #[contract]
+    pub struct MockStakingPool;
+
+    #[contractimpl]
+    impl MockStakingPool {
+        pub fn add_rewards(env: Env, caller: Address, reward_token: Address, amount: i128) -> i128 {
+            caller.require_auth();
+            token::Client::new(&env, &reward_token).transfer(
+                &caller,
+                env.current_contract_address(),
+                &amount,
+            );
+            let total: i128 = env.storage().instance().get(&reward_token).unwrap_or(0);
+            env.storage()
+                .instance()
+                .set(&reward_token, &(total + amount));
+            amount
+        }
+
+        pub fn rewards_added(env: Env, reward_token: Address) -> i128 {
+            env.storage().instance().get(&reward_token).unwrap_or(0)
+        }
+    }
+
+    /// Staking pool whose `add_rewards` always fails
+    #[contract]
+    pub struct MockRejectingStakingPool;
+
+    #[contractimpl]
+    impl MockRejectingStakingPool {
+        pub fn add_rewards(
+            _env: Env,
+            _caller: Address,
+            _reward_token: Address,
+            _amount: i128,
+        ) -> Result<i128, SharedError> {
+            Err(SharedError::ContractPaused)
+        }
+    }
+
+    /// Constant-product pair stand-in (no swap fee) priced off its balances
+    #[contract]
+    pub struct MockPair;
+
+    #[contractimpl]
+    impl MockPair {
+        pub fn setup(env: Env, token_0: Address, token_1: Address) {
+            env.storage().instance().set(&0u32, &token_0);
+            env.storage().instance().set(&1u32, &token_1);
+        }
+
+        pub fn token_0(env: Env) -> Address {
+            env.storage().instance().get(&0u32).unwrap()
+        }
+
+        pub fn token_1(env: Env) -> Address {
+            env.storage().instance().get(&1u32).unwrap()
+        }
+
+        /// Skip the `min_out` check, like a pair that doesn't enforce it
+        pub fn set_unchecked(env: Env, unchecked: bool) {
+            env.storage().instance().set(&2u32, &unchecked);
+        }
+
+        pub fn get_reserves(env: Env) -> (i128, i128) {
+            let this = env.current_contract_address();
+            (
+                token::Client::new(&env, &Self::token_0(env.clone())).balance(&this),
+                token::Client::new(&env, &Self::token_1(env.clone())).balance(&this),
+            )
+        }
+
+        pub fn swap(
+            env: Env,
+            user: Address,
+            token_in: Address,
+            amount_in: i128,
+            min_out: i128,
+        ) -> i128 {
+            user.require_auth();
+            let (reserve_0, reserve_1) = Self::get_reserves(env.clone());
+            let (reserve_in, reserve_out, token_out) = if token_in == Self::token_0(env.clone()) {
+                (reserve_0, reserve_1, Self::token_1(env.clone()))
+            } else {
+                (reserve_1, reserve_0, Self::token_0(env.clone()))
+            };
+            let amount_out = amount_in * reserve_out / (reserve_in + amount_in);
+            let unchecked: bool = env.storage().instance().get(&2u32).unwrap_or(false);
+            assert!(unchecked || amount_out >= min_out);
+
+            let this = env.current_contract_address();
+            token::Client::new(&env, &token_in).transfer(&user, &this, &amount_in);
+            token::Client::new(&env, &token_out).transfer(&this, &user, &amount_out);
+            amount_out
+        }
+    }
+
+    /// Oracle pricing every token 1:1 against the other pair token
+    #[contract]
+    pub struct MockOracle;
+
+    #[contractimpl]
+    impl MockOracle {
+        pub fn consult(
+            _env: Env,
+            _pair: Address,
+            _token_in: Address,
+            amount_in: i128,
+            _window: u64,
+        ) -> i128 {
+            amount_in
+        }
+    }
+
+    #[test]
+    fn test_initialize() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let contract_id = env.register(FeeDistributor, ());
+        let client = FeeDistributorClient::new(&env, &contract_id);
+
+        let admin = Address::generate(&env);
+        let treasury = Address::generate(&env);
+        let staking = env.register(MockStakingPool, ());
+        let burn = Address::generate(&env);
+
+        client.initialize(&admin, &treasury, &staking, &burn);
+
+        let config = client.get_config();
+        let treasury_recipient = config.recipient_of(RecipientKind::Treasury).unwrap();
+        let staking_recipient = config.recipient_of(RecipientKind::Staking).unwrap();
+        let burn_recipient = config.recipient_of(RecipientKind::Burn).unwrap();
+        assert_eq!(treasury_recipient.address, treasury);
+        assert_eq!(staking_recipient.address, staking);
+        assert_eq!(burn_recipient.address, burn);
+        assert_eq!(treasury_recipient.bps.get(), 5000);
+        assert_eq!(staking_recipient.bps.get(), 3000);
+        assert_eq!(burn_recipient.bps.get(), 2000);
+    }
+
+    #[test]
+    fn test_receive_and_distribute() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let contract_id = env.register(FeeDistributor, ());
+        let client = FeeDistributorClient::new(&env, &contract_id);
+
+        let admin = Address::generate(&env);
+        let treasury = Address::generate(&env);
+        let staking = env.register(MockStakingPool, ());
+        let burn = Address::generate(&env);
+        let user = Address::generate(&env);
+
+        // Create and mint test token
+        let token_client = create_token(&env, &admin);
+        token_client.mint(&user, 1_000_000_000_000); // 100,000 tokens
+
+        // Initialize contract
+        client.initialize(&admin, &treasury, &staking, &burn);
+
+        // Receive fees
+        let fee_amount = 100_000_000_000_i128; // 10,000 tokens
+        client.receive_fees(&user, &token_client.address, &fee_amount);
+
+        // Check pending
+        let pending = client.get_pending_distribution(&token_client.address);
+        assert_eq!(pending, fee_amount);
+        let entry = client.solvency_report().get(0).unwrap();
+        assert_eq!((entry.balance, entry.liabilities), (fee_amount, fee_amount));
+
+        // Distribute
+        let result = client.distribute(&token_client.address, &None);
+
+        // Verify distribution (50/30/20 split)
+        assert_eq!(result.total_amount, fee_amount);
+        // 50% = 50,000,000,000
+        // 30% = 30,000,000,000
+        // 20% = 20,000,000,000
+        assert!(result.treasury_amount >= 50_000_000_000);
+        assert_eq!(result.staking_amount, 30_000_000_000);
+        assert_eq!(result.burn_amount, 20_000_000_000);
+
+        // Verify balances
+        assert_eq!(token_client.balance(&treasury), result.treasury_amount);
+        assert_eq!(token_client.balance(&staking), result.staking_amount);
+
+        // Staking share went through add_rewards
+        let pool = MockStakingPoolClient::new(&env, &staking);
+        assert_eq!(
+            pool.rewards_added(&token_client.address),
+            result.staking_amount
+        );
+        assert_eq!(token_client.balance(&burn), result.burn_amount);
+    }
+
+    #[test]
+    fn test_staking_raw_transfer() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let contract_id = env.register(FeeDistributor, ());
+        let client = FeeDistributorClient::new(&env, &contract_id);
+
+        let admin = Address::generate(&env);
+        let staking = env.register(MockStakingPool, ());
+        let rejecting = env.register(MockRejectingStakingPool, ());
+        let user = Address::generate(&env);
+
+        let token_client = create_token(&env, &admin);
+        token_client.mint(&user, 1_000_000_000_000);
+
+        client.initialize(
+            &admin,
+            &Address::generate(&env),
+            &staking,
+            &Address::generate(&env),
+        );
+
+        // Raw-transfer mode bypasses add_rewards
+        let mut config = client.get_config();
+        config.staking_raw_transfer = true;
+        client.update_config(&admin, &config);
+        client.receive_fees(&user, &token_client.address, &100_000_000_000);
+        let result = client.distribute(&token_client.address, &None);
+        assert_eq!(token_client.balance(&staking), result.staking_amount);
+        let pool = MockStakingPoolClient::new(&env, &staking);
+        assert_eq!(pool.rewards_added(&token_client.address), 0);
+
+        // A pool rejecting add_rewards falls back to a raw transfer
+        let mut config = client.get_config();
+        config.staking_raw_transfer = false;
+        for (i, recipient) in config.recipients.clone().iter().enumerate() {
+            if recipient.kind == RecipientKind::Staking {
+                let mut recipient = recipient.clone();
+                recipient.address = rejecting.clone();
+                config.recipients.set(i as u32, recipient);
+            }
+        }
+        client.update_config(&admin, &config);
+        client.receive_fees(&user, &token_client.address, &100_000_000_000);
+        let result = client.distribute(&token_client.address, &None);
+        assert_eq!(token_client.balance(&rejecting), result.staking_amount);
+        assert_eq!(client.get_pending_distribution(&token_client.address), 0);
+    }
+
+    #[test]
+    fn test_distribute_partial() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let contract_id = env.register(FeeDistributor, ());
+        let client = FeeDistributorClient::new(&env, &contract_id);
+
+        let admin = Address::generate(&env);
+        let treasury = Address::generate(&env);
+        let staking = env.register(MockStakingPool, ());
+        let burn = Address::generate(&env);
+        let user = Address::generate(&env);
+
+        let token_client = create_token(&env, &admin);
+        token_client.mint(&user, 1_000_000_000_000);
+
+        client.initialize(&admin, &treasury, &staking, &burn);
+        client.receive_fees(&user, &token_client.address, &100_000_000_000);
+
+        // Distribute 25% of pending
+        let result = client.distribute_partial(&token_client.address, &2500);
+        assert_eq!(result.total_amount, 25_000_000_000);
+        assert_eq!(result.staking_amount, 7_500_000_000);
+        assert_eq!(
+            client.get_pending_distribution(&token_client.address),
+            75_000_000_000
+        );
+        assert_eq!(
+            client.get_total_distributed_for_token(&token_client.address),
+            25_000_000_000
+        );
+
+        // Invalid bps rejected
+        assert!(client
+            .try_distribute_partial(&token_client.address, &0)
+            .is_err());
+        assert!(client
+            .try_distribute_partial(&token_client.address, &10_001)
+            .is_err());
+
+        // Remainder distributes normally and the books still balance
+        let result = client.distribute(&token_client.address, &None);
+        assert_eq!(result.total_amount, 75_000_000_000);
+        assert_eq!(client.assert_solvency(&token_client.address), 0);
+    }
+
+    #[test]
+    fn test_token_config_override() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let contract_id = env.register(FeeDistributor, ());
+        let client = FeeDistributorClient::new(&env, &contract_id);
+
+        let admin = Address::generate(&env);
+        let treasury = Address::generate(&env);
+        let staking = env.register(MockStakingPool, ());
+        let burn = Address::generate(&env);
+        let user = Address::generate(&env);
+
+        let astro = create_token(&env, &admin);
+        let other = create_token(&env, &admin);
+        astro.mint(&user, 1_000_000_000_000);
+        other.mint(&user, 1_000_000_000_000);
+
+        client.initialize(&admin, &treasury, &staking, &burn);
+
+        // 100% burn for ASTRO
+        let burn_all = DistributionConfig {
+            recipients: Vec::from_array(
+                &env,
+                [DistributionRecipient {
+                    address: burn.clone(),
+                    bps: Bps::from_const(10_000),
+                    kind: RecipientKind::Burn,
+                }],
+            ),
+            min_distribution: 10_000_000,
+            burn_mode: BurnMode::Transfer,
+            staking_raw_transfer: false,
+            max_distribution_per_call: 0,
+            max_distribution_per_day: 0,
+        };
+        client.set_token_config(&astro.address, &burn_all);
+        assert_eq!(
+            client.get_effective_config(&astro.address).recipients.len(),
+            1
+        );
+        assert_eq!(
+            client.get_effective_config(&other.address).recipients.len(),
+            3
+        );
+
+        client.receive_fees(&user, &astro.address, &100_000_000_000);
+        client.receive_fees(&user, &other.address, &100_000_000_000);
+
+        let result = client.distribute(&astro.address, &None);
+        assert_eq!(result.burn_amount, 100_000_000_000);
+        assert_eq!(astro.balance(&burn), 100_000_000_000);
+
+        let result = client.distribute(&other.address, &None);
+        assert_eq!(result.burn_amount, 20_000_000_000);
+
+        // Cleared override falls back to the global split
+        client.clear_token_config(&astro.address);
+        assert!(client.get_token_config(&astro.address).is_none());
+        assert_eq!(
+            client.get_effective_config(&astro.address).recipients.len(),
+            3
+        );
+    }
+
+    #[test]
+    fn test_burn_mode() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let contract_id = env.register(FeeDistributor, ());
+        let client = FeeDistributorClient::new(&env, &contract_id);
+
+        let admin = Address::generate(&env);
+        let treasury = Address::generate(&env);
+        let staking = env.register(MockStakingPool, ());
+        let burn = Address::generate(&env);
+        let user = Address::generate(&env);
+
+        let token_client = create_token(&env, &admin);
+        token_client.mint(&user, 1_000_000_000_000);
+
+        client.initialize(&admin, &treasury, &staking, &burn);
+
+        // Default: transfer to the burn address
+        client.receive_fees(&user, &token_client.address, &100_000_000_000);
+        client.distribute(&token_client.address, &None);
+        assert_eq!(token_client.balance(&burn), 20_000_000_000);
+
+        // Real burn: nothing reaches the burn address and nothing is left behind
+        let mut config = client.get_config();
+        config.burn_mode = BurnMode::Burn;
+        client.update_config(&admin, &config);
+
+        client.receive_fees(&user, &token_client.address, &100_000_000_000);
+        client.distribute(&token_client.address, &None);
+        assert_eq!(token_client.balance(&burn), 20_000_000_000);
+        assert_eq!(token_client.balance(&contract_id), 0);
+        assert_eq!(
+            client.get_total_burned(&token_client.address),
+            40_000_000_000
+        );
+    }
+
+    #[test]
+    fn test_lifecycle_modes() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let contract_id = env.register(FeeDistributor, ());
+        let client = FeeDistributorClient::new(&env, &contract_id);
+
+        let admin = Address::generate(&env);
+        let treasury = Address::generate(&env);
+        let burn = Address::generate(&env);
+        let user = Address::generate(&env);
+
+        let token_client = create_token(&env, &admin);
+        token_client.mint(&user, 1_000_000_000_000);
+
+        // Deploy before the staking pool exists
+        client.initialize_bootstrap(&admin, &treasury, &burn);
+        assert_eq!(client.mode(), LifecycleMode::Bootstrap);
+
+        client.receive_fees(&user, &token_client.address, &100_000_000_000);
+        let result = client.distribute(&token_client.address, &None);
+        assert_eq!(result.treasury_amount, 80_000_000_000);
+        assert_eq!(result.staking_amount, 0);
+
+        // Staking recipients are rejected while bootstrapping
+        let staking = env.register(MockStakingPool, ());
+        assert!(client
+            .try_add_recipient(&staking, &3000, &RecipientKind::Staking, &treasury)
+            .is_err());
+
+        // Bootstrap -> Normal requires a staking recipient
+        let mut normal = client.get_config();
+        assert!(client
+            .try_set_mode(&LifecycleMode::Normal, &normal)
+            .is_err());
+        normal.recipients.set(
+            0,
+            DistributionRecipient {
+                address: treasury.clone(),
+                bps: Bps::from_const(5000),
+                kind: RecipientKind::Treasury,
+            },
+        );
+        normal.recipients.push_back(DistributionRecipient {
+            address: staking.clone(),
+            bps: Bps::from_const(3000),
+            kind: RecipientKind::Staking,
+        });
+        client.set_mode(&LifecycleMode::Normal, &normal);
+        assert_eq!(client.mode(), LifecycleMode::Normal);
+
+        // No going back
+        let bootstrap = client.get_config();
+        assert!(client
+            .try_set_mode(&LifecycleMode::Bootstrap, &bootstrap)
+            .is_err());
+
+        // Normal -> Sunset drops staking
+        let mut sunset = client.get_config();
+        sunset.recipients.remove(2);
+        sunset.recipients.set(
+            0,
+            DistributionRecipient {
+                address: treasury.clone(),
+                bps: Bps::from_const(8000),
+                kind: RecipientKind::Treasury,
+            },
+        );
+        client.set_mode(&LifecycleMode::Sunset, &sunset);
+        assert_eq!(client.mode(), LifecycleMode::Sunset);
+    }
+
+    #[test]
+    fn test_receive_native() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let client = FeeDistributorClient::new(&env, &env.register(FeeDistributor, ()));
+        let admin = Address::generate(&env);
+        let user = Address::generate(&env);
+        client.initialize(
+            &admin,
+            &Address::generate(&env),
+            &env.register(MockStakingPool, ()),
+            &Address::generate(&env),
+        );
+
+        // Stand-in for the native SAC, which the test environment doesn't deploy
+        let xlm = create_token(&env, &admin);
+        assert_ne!(client.native_asset(), xlm.address);
+        client.set_native_asset(&xlm.address);
+        assert_eq!(env.events().all().events().len(), 1);
+        xlm.mint(&user, 1_000_000_000);
+
+        client.receive_native(&user, &100_000_000);
+        assert_eq!(client.get_pending_distribution(&xlm.address), 100_000_000);
+        assert_eq!(xlm.balance(&user), 900_000_000);
+    }
+
+    #[test]
+    fn test_sync() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let client = FeeDistributorClient::new(&env, &env.register(FeeDistributor, ()));
+        let admin = Address::generate(&env);
+        let user = Address::generate(&env);
+        client.initialize(
+            &admin,
+            &Address::generate(&env),
+            &env.register(MockStakingPool, ()),
+            &Address::generate(&env),
+        );
+
+        let token_client = create_token(&env, &admin);
+        token_client.mint(&user, 1_000_000_000);
+        client.receive_fees(&user, &token_client.address, &100_000_000);
+        assert_eq!(client.sync(&token_client.address), 0);
+
+        // Sent directly, invisible until synced
+        token_client.transfer(&user, &client.address, &50_000_000);
+        assert_eq!(
+            client.get_pending_distribution(&token_client.address),
+            100_000_000
+        );
+        assert_eq!(client.sync(&token_client.address), 50_000_000);
+        assert_eq!(
+            client.get_pending_distribution(&token_client.address),
+            150_000_000
+        );
+        assert_eq!(client.sync(&token_client.address), 0);
+        assert_eq!(client.assert_solvency(&token_client.address), 0);
+        assert!(!client.is_paused());
+
+        // Unknown tokens are rejected while fee sources are enforced
+        let other = create_token(&env, &admin);
+        other.mint(&client.address, 10_000_000);
+        client.set_require_fee_source(&true);
+        assert_eq!(
+            client.try_sync(&other.address),
+            Err(Ok(SharedError::Unauthorized))
+        );
+        client.set_require_fee_source(&false);
+        assert_eq!(client.sync(&other.address), 10_000_000);
+        assert!(client.get_tokens().contains(&other.address));
+    }
+
+    #[test]
+    fn test_below_minimum() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let contract_id = env.register(FeeDistributor, ());
+        let client = FeeDistributorClient::new(&env, &contract_id);
+
+        let admin = Address::generate(&env);
+        let treasury = Address::generate(&env);
+        let staking = env.register(MockStakingPool, ());
+        let burn = Address::generate(&env);
+        let user = Address::generate(&env);
+
+        let token_client = create_token(&env, &admin);
+        token_client.mint(&user, 1_000_000_000);
+
+        client.initialize(&admin, &treasury, &staking, &burn);
+
+        // Receive small fee (below minimum)
+        let small_fee = 1_000_000_i128; // 0.1 tokens (below 1 token minimum)
+        client.receive_fees(&user, &token_client.address, &small_fee);
+
+        // Distribution should fail due to below minimum
+        let result = client.try_distribute(&token_client.address, &None);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_contract_info() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let contract_id = env.register(FeeDistributor, ());
+        let client = FeeDistributorClient::new(&env, &contract_id);
+
+        let admin = Address::generate(&env);
+        let treasury = Address::generate(&env);
+        let staking = env.register(MockStakingPool, ());
+        let burn = Address::generate(&env);
+        let registry = Address::generate(&env);
+
+        client.initialize(&admin, &treasury, &staking, &burn);
+
+        let info = client.contract_info();
+        assert_eq!(info.name, Symbol::new(&env, "fee_dist"));
+        assert_eq!(info.admin, admin);
+        assert_eq!(info.registry, None);
+        assert_eq!(info.config.get(Symbol::new(&env, "recipients")), Some(3));
+
+        client.set_registry(&registry);
+        assert_eq!(client.contract_info().registry, Some(registry));
+    }
+
+    #[test]
+    fn test_conversion_route() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let contract_id = env.register(FeeDistributor, ());
+        let client = FeeDistributorClient::new(&env, &contract_id);
+
+        let admin = Address::generate(&env);
+        let treasury = Address::generate(&env);
+        let staking = env.register(MockStakingPool, ());
+        let burn = Address::generate(&env);
+        let user = Address::generate(&env);
+
+        let meme = create_token(&env, &admin);
+        let xlm = create_token(&env, &admin);
+        meme.mint(&user, 1_000_000_000_000);
+
+        // 1:1 pool with deep liquidity
+        let pair = env.register(MockPair, ());
+        MockPairClient::new(&env, &pair).setup(&meme.address, &xlm.address);
+        meme.mint(&pair, 1_000_000_000_000_000);
+        xlm.mint(&pair, 1_000_000_000_000_000);
+
+        let oracle = env.register(MockOracle, ());
+
+        client.initialize(&admin, &treasury, &staking, &burn);
+
+        // Route requires a reward token, and the pair must trade it
+        assert!(client
+            .try_set_conversion_route(&meme.address, &pair, &oracle, &3600, &9500)
+            .is_err());
+        client.set_reward_token(&xlm.address);
+        let other = create_token(&env, &admin);
+        assert!(client
+            .try_set_conversion_route(&other.address, &pair, &oracle, &3600, &9500)
+            .is_err());
+        client.set_conversion_route(&meme.address, &pair, &oracle, &3600, &9500);
+
+        client.receive_fees(&user, &meme.address, &100_000_000_000);
+
+        // Spot reserves pushed off the TWAP cannot set the bound
+        meme.mint(&pair, 1_000_000_000_000_000);
+        assert!(client.try_distribute(&meme.address, &None).is_err());
+
+        // A short swap through a pair that doesn't check `min_out` reverts
+        // the whole batch, swap included
+        let pair_client = MockPairClient::new(&env, &pair);
+        pair_client.set_unchecked(&true);
+        assert_eq!(
+            client.try_distribute_all(),
+            Err(Ok(SharedError::BelowMinimum))
+        );
+        assert_eq!(
+            client.get_pending_distribution(&meme.address),
+            100_000_000_000
+        );
+        assert_eq!(meme.balance(&contract_id), 100_000_000_000);
+        assert_eq!(xlm.balance(&contract_id), 0);
+        pair_client.set_unchecked(&false);
+        xlm.mint(&pair, 1_000_000_000_000_000);
+
+        let result = client.distribute(&meme.address, &None);
+
+        // Paid out in the reward token, nothing left pending in the fee token
+        assert_eq!(result.token, xlm.address);
+        assert!(result.total_amount > 99_000_000_000);
+        assert_eq!(client.get_pending_distribution(&meme.address), 0);
+        assert_eq!(xlm.balance(&treasury), result.treasury_amount);
+        assert_eq!(meme.balance(&treasury), 0);
     }
 
-    // ────────────────────────────────────────────────────────────────────────
-    // Internal Helpers
-    // ────────────────────────────────────────────────────────────────────────
+    #[test]
+    fn test_distribution_history() {
+        let env = Env::default();
+        env.mock_all_auths();
 
-    fn require_initialized(env: &Env) -> Result<(), SharedError> {
-        let initialized: bool = env
-            .storage()
-            .instance()
-            .get(&DataKey::Initialized)
-            .unwrap_or(false);
+        let contract_id = env.register(FeeDistributor, ());
+        let client = FeeDistributorClient::new(&env, &contract_id);
 
-        if !initialized {
-            return Err(SharedError::NotInitialized);
-        }
-        Ok(())
-    }
+        let admin = Address::generate(&env);
+        let treasury = Address::generate(&env);
+        let staking = env.register(MockStakingPool, ());
+        let burn = Address::generate(&env);
+        let user = Address::generate(&env);
 
-    fn require_not_paused(env: &Env) -> Result<(), SharedError> {
-        let paused: bool = env
-            .storage()
-            .instance()
-            .get(&DataKey::Paused)
-            .unwrap_or(false);
+        let token_client = create_token(&env, &admin);
+        token_client.mint(&user, 1_000_000_000_000);
+        let token = token_client.address.clone();
+
+        client.initialize(&admin, &treasury, &staking, &burn);
 
-        if paused {
-            return Err(SharedError::ContractPaused);
+        // Wrap the ring buffer
+        for i in 1..=(MAX_HISTORY as i128 + 5) {
+            client.receive_fees(&user, &token, &(i * 10_000_000));
+            client.distribute(&token, &None);
         }
-        Ok(())
-    }
 
-    fn require_admin(env: &Env) -> Result<(), SharedError> {
-        let admin: Address = env
-            .storage()
-            .instance()
-            .get(&DataKey::Admin)
-            .ok_or(SharedError::NotInitialized)?;
+        let history = client.get_distribution_history(&token, &100);
+        assert_eq!(history.len(), MAX_HISTORY);
+        assert_eq!(history.get(0).unwrap().total_amount, 550_000_000);
+        assert_eq!(
+            history.get(MAX_HISTORY - 1).unwrap().total_amount,
+            60_000_000
+        );
+        assert_eq!(client.get_distribution_history(&token, &2).len(), 2);
 
-        admin.require_auth();
-        Ok(())
+        // 30% of 1 + 2 + ... + 55 tokens
+        assert_eq!(client.get_total_to_staking(&token), 4_620_000_000);
+        assert_eq!(client.get_total_burned(&token), 3_080_000_000);
     }
 
-    fn get_pending_fees(env: &Env, token: &Address) -> i128 {
-        env.storage()
-            .persistent()
-            .get(&DataKey::PendingFees(token.clone()))
-            .unwrap_or(0)
-    }
+    #[test]
+    fn test_distribute_range() {
+        let env = Env::default();
+        env.mock_all_auths();
 
-    fn get_total_distributed(env: &Env, token: &Address) -> i128 {
-        env.storage()
-            .persistent()
-            .get(&DataKey::TotalDistributed(token.clone()))
-            .unwrap_or(0)
-    }
+        let contract_id = env.register(FeeDistributor, ());
+        let client = FeeDistributorClient::new(&env, &contract_id);
 
-    fn get_supported_tokens(env: &Env) -> Vec<Address> {
-        env.storage()
-            .instance()
-            .get(&DataKey::SupportedTokens)
-            .unwrap_or(Vec::new(env))
-    }
+        let admin = Address::generate(&env);
+        let treasury = Address::generate(&env);
+        let staking = env.register(MockStakingPool, ());
+        let burn = Address::generate(&env);
+        let user = Address::generate(&env);
 
-    fn add_supported_token(env: &Env, token: &Address) {
-        let mut tokens = Self::get_supported_tokens(env);
+        client.initialize(&admin, &treasury, &staking, &burn);
 
-        // Check if already in list
-        for t in tokens.iter() {
-            if t == *token {
-                return;
-            }
+        for _ in 0..5 {
+            let token_client = create_token(&env, &admin);
+            token_client.mint(&user, 200_000_000);
+            client.receive_fees(&user, &token_client.address, &100_000_000);
+            // Repeat deposits don't duplicate the token
+            client.receive_fees(&user, &token_client.address, &1_000);
         }
+        assert_eq!(client.token_count(), 5);
+        assert_eq!(client.get_tokens_page(&3, &10).len(), 2);
 
-        tokens.push_back(token.clone());
-        env.storage()
-            .instance()
-            .set(&DataKey::SupportedTokens, &tokens);
+        let (results, cursor) = client.distribute_range(&0, &3);
+        assert_eq!(results.len(), 3);
+        assert_eq!(cursor, 3);
+
+        let (results, cursor) = client.distribute_range(&cursor, &3);
+        assert_eq!(results.len(), 2);
+        assert_eq!(cursor, 5);
+
+        // Past the end: nothing to do, cursor stays put
+        let (results, cursor) = client.distribute_range(&cursor, &3);
+        assert_eq!(results.len(), 0);
+        assert_eq!(cursor, 5);
     }
-}
 
-// ════════════════════════════════════════════════════════════════════════════
-// Tests
-// ════════════════════════════════════════════════════════════════════════════
+    #[test]
+    fn test_fee_source_allowlist() {
+        let env = Env::default();
+        env.mock_all_auths();
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use soroban_sdk::testutils::Address as _;
+        let contract_id = env.register(FeeDistributor, ());
+        let client = FeeDistributorClient::new(&env, &contract_id);
 
-    fn create_token<'a>(
-        env: &Env,
-        admin: &Address,
-    ) -> (token::Client<'a>, token::StellarAssetClient<'a>) {
-        let contract_id = env.register_stellar_asset_contract_v2(admin.clone());
-        (
-            token::Client::new(env, &contract_id.address()),
-            token::StellarAssetClient::new(env, &contract_id.address()),
-        )
+        let admin = Address::generate(&env);
+        let treasury = Address::generate(&env);
+        let staking = env.register(MockStakingPool, ());
+        let burn = Address::generate(&env);
+        let launchpad = Address::generate(&env);
+        let attacker = Address::generate(&env);
+
+        let token_client = create_token(&env, &admin);
+        token_client.mint(&launchpad, 1_000_000_000);
+        let garbage = create_token(&env, &attacker);
+        garbage.mint(&attacker, 1_000_000_000);
+
+        client.initialize(&admin, &treasury, &staking, &burn);
+        client.add_fee_source(&launchpad);
+        client.set_require_fee_source(&true);
+        assert!(client.requires_fee_source());
+
+        client.receive_fees(&launchpad, &token_client.address, &100_000_000);
+        assert!(client
+            .try_receive_fees(&attacker, &garbage.address, &100_000_000)
+            .is_err());
+        assert_eq!(client.token_count(), 1);
+
+        client.remove_fee_source(&launchpad);
+        assert!(!client.is_fee_source(&launchpad));
+        assert!(client
+            .try_receive_fees(&launchpad, &token_client.address, &100_000_000)
+            .is_err());
     }
 
     #[test]
-    fn test_initialize() {
+    fn test_effective_circulating_supply() {
         let env = Env::default();
         env.mock_all_auths();
 
@@ -540,93 +3428,178 @@ mod tests {
 
         let admin = Address::generate(&env);
         let treasury = Address::generate(&env);
-        let staking = Address::generate(&env);
+        let staking = env.register(MockStakingPool, ());
         let burn = Address::generate(&env);
+        let user = Address::generate(&env);
+
+        let astro = create_token(&env, &admin);
+        astro.mint(&user, 1_000_000_000_000);
 
         client.initialize(&admin, &treasury, &staking, &burn);
+        assert!(client.try_effective_circulating_supply().is_err());
 
-        let config = client.get_config();
-        assert_eq!(config.treasury_vault, treasury);
-        assert_eq!(config.staking_pool, staking);
-        assert_eq!(config.burn_address, burn);
-        assert_eq!(config.treasury_bps, 5000);
-        assert_eq!(config.staking_bps, 3000);
-        assert_eq!(config.burn_bps, 2000);
+        client.set_native_token(&astro.address, &1_000_000_000_000);
+
+        // Transfer-mode burns don't count as real burns
+        assert!(client.try_effective_circulating_supply().is_err());
+
+        let mut config = client.get_config();
+        config.burn_mode = BurnMode::Burn;
+        client.update_config(&admin, &config);
+        assert_eq!(client.effective_circulating_supply(), 1_000_000_000_000);
+
+        client.receive_fees(&user, &astro.address, &100_000_000_000);
+        client.distribute(&astro.address, &None);
+        assert_eq!(client.effective_circulating_supply(), 980_000_000_000);
     }
 
     #[test]
-    fn test_receive_and_distribute() {
+    fn test_queued_config_and_preview() {
         let env = Env::default();
         env.mock_all_auths();
+        env.ledger().set_timestamp(1000);
 
         let contract_id = env.register(FeeDistributor, ());
         let client = FeeDistributorClient::new(&env, &contract_id);
 
         let admin = Address::generate(&env);
         let treasury = Address::generate(&env);
-        let staking = Address::generate(&env);
+        let staking = env.register(MockStakingPool, ());
         let burn = Address::generate(&env);
         let user = Address::generate(&env);
 
-        // Create and mint test token
-        let (token_client, token_admin) = create_token(&env, &admin);
-        token_admin.mint(&user, &1_000_000_000_000); // 100,000 tokens
+        let token_client = create_token(&env, &admin);
+        token_client.mint(&user, 1_000_000_000_000);
+        let token = token_client.address.clone();
 
-        // Initialize contract
         client.initialize(&admin, &treasury, &staking, &burn);
+        client.receive_fees(&user, &token, &100_000_000_000);
+
+        let preview = client.preview_distribution(&token);
+        assert_eq!(preview.staking_amount, 30_000_000_000);
+
+        // Queue 40/40/20
+        let mut config = client.get_config();
+        let mut recipient = config.recipients.get(0).unwrap();
+        recipient.bps = Bps::from_const(4000);
+        config.recipients.set(0, recipient);
+        let mut recipient = config.recipients.get(1).unwrap();
+        recipient.bps = Bps::from_const(4000);
+        config.recipients.set(1, recipient);
+        let eta = client.queue_config(&config);
+
+        let queued = client.preview_queued_distribution(&token);
+        assert_eq!(queued.staking_amount, 40_000_000_000);
+        assert_eq!(
+            client.get_config().recipients.get(1).unwrap().bps.get(),
+            3000
+        );
 
-        // Receive fees
-        let fee_amount = 100_000_000_000_i128; // 10,000 tokens
-        client.receive_fees(&user, &token_client.address, &fee_amount);
+        // Timelock
+        assert!(client.try_apply_config().is_err());
+        env.ledger().set_timestamp(eta);
+        client.apply_config();
+        assert!(client.get_queued_config().is_none());
+
+        // Preview matches the real distribution
+        let preview = client.preview_distribution(&token);
+        let result = client.distribute(&token, &None);
+        assert_eq!(preview.staking_amount, result.staking_amount);
+        assert_eq!(preview.treasury_amount, result.treasury_amount);
+        assert_eq!(client.get_pending_distribution(&token), 0);
+    }
 
-        // Check pending
-        let pending = client.get_pending_distribution(&token_client.address);
-        assert_eq!(pending, fee_amount);
+    #[test]
+    fn test_preview_distribute_all() {
+        let env = Env::default();
+        env.mock_all_auths();
 
-        // Distribute
-        let result = client.distribute(&token_client.address);
+        let contract_id = env.register(FeeDistributor, ());
+        let client = FeeDistributorClient::new(&env, &contract_id);
 
-        // Verify distribution (50/30/20 split)
-        assert_eq!(result.total_amount, fee_amount);
-        // 50% = 50,000,000,000
-        // 30% = 30,000,000,000
-        // 20% = 20,000,000,000
-        assert!(result.treasury_amount >= 50_000_000_000);
-        assert_eq!(result.staking_amount, 30_000_000_000);
-        assert_eq!(result.burn_amount, 20_000_000_000);
+        let admin = Address::generate(&env);
+        let treasury = Address::generate(&env);
+        let staking = env.register(MockStakingPool, ());
+        let burn = Address::generate(&env);
+        let user = Address::generate(&env);
 
-        // Verify balances
-        assert_eq!(token_client.balance(&treasury), result.treasury_amount);
-        assert_eq!(token_client.balance(&staking), result.staking_amount);
-        assert_eq!(token_client.balance(&burn), result.burn_amount);
+        let token_a = create_token(&env, &admin);
+        let token_b = create_token(&env, &admin);
+        token_a.mint(&user, 1_000_000_000_000);
+        token_b.mint(&user, 1_000_000_000_000);
+
+        client.initialize(&admin, &treasury, &staking, &burn);
+        client.receive_fees(&user, &token_a.address, &100_000_000_000);
+        client.receive_fees(&user, &token_b.address, &100_000_000_000);
+
+        // Token B isn't worth distributing yet
+        client.set_min_distribution(&token_b.address, &200_000_000_000);
+
+        let preview = client.preview_distribute_all();
+        assert_eq!(preview.len(), 1);
+        let preview = preview.get(0).unwrap();
+        assert_eq!(preview.token, token_a.address);
+        assert_eq!(preview.staking_amount, 30_000_000_000);
+
+        // Nothing moved
+        assert_eq!(
+            client.get_pending_distribution(&token_a.address),
+            100_000_000_000
+        );
+        assert_eq!(token_a.balance(&treasury), 0);
+
+        let results = client.distribute_all();
+        assert_eq!(results.len(), 1);
+        let result = results.get(0).unwrap();
+        assert_eq!(result.treasury_amount, preview.treasury_amount);
+        assert_eq!(result.staking_amount, preview.staking_amount);
+        assert_eq!(result.burn_amount, preview.burn_amount);
     }
 
     #[test]
-    fn test_below_minimum() {
+    fn test_epochs() {
         let env = Env::default();
         env.mock_all_auths();
+        env.ledger().set_timestamp(1000);
 
         let contract_id = env.register(FeeDistributor, ());
         let client = FeeDistributorClient::new(&env, &contract_id);
 
         let admin = Address::generate(&env);
         let treasury = Address::generate(&env);
-        let staking = Address::generate(&env);
+        let staking = env.register(MockStakingPool, ());
         let burn = Address::generate(&env);
         let user = Address::generate(&env);
 
-        let (token_client, token_admin) = create_token(&env, &admin);
-        token_admin.mint(&user, &1_000_000_000);
+        let token_client = create_token(&env, &admin);
+        token_client.mint(&user, 1_000_000_000_000);
+        let token = token_client.address.clone();
 
         client.initialize(&admin, &treasury, &staking, &burn);
-
-        // Receive small fee (below minimum)
-        let small_fee = 1_000_000_i128; // 0.1 tokens (below 1 token minimum)
-        client.receive_fees(&user, &token_client.address, &small_fee);
-
-        // Distribution should fail due to below minimum
-        let result = client.try_distribute(&token_client.address);
-        assert!(result.is_err());
+        client.set_epoch_length(&86400);
+        assert_eq!(client.current_epoch(), Some(0));
+
+        client.receive_fees(&user, &token, &100_000_000);
+        client.distribute(&token, &None);
+
+        // Second distribution in the same epoch is rejected
+        client.receive_fees(&user, &token, &100_000_000);
+        assert!(client.try_distribute(&token, &None).is_err());
+
+        // ...unless pending exceeds the bypass threshold
+        client.set_epoch_bypass_threshold(&500_000_000);
+        client.receive_fees(&user, &token, &500_000_000);
+        client.distribute(&token, &None);
+
+        let epoch = client.get_epoch(&token, &0).unwrap();
+        assert_eq!(epoch.amount, 700_000_000);
+        assert_eq!(epoch.distributions, 2);
+
+        // Next epoch
+        env.ledger().set_timestamp(1000 + 86400);
+        client.receive_fees(&user, &token, &100_000_000);
+        client.distribute(&token, &None);
+        assert_eq!(client.get_epoch(&token, &1).unwrap().amount, 100_000_000);
     }
 
     #[test]
@@ -639,27 +3612,172 @@ mod tests {
 
         let admin = Address::generate(&env);
         let treasury = Address::generate(&env);
-        let staking = Address::generate(&env);
+        let staking = env.register(MockStakingPool, ());
         let burn = Address::generate(&env);
 
         client.initialize(&admin, &treasury, &staking, &burn);
 
         // Update to 40/40/20 split
         let new_config = DistributionConfig {
-            treasury_vault: treasury.clone(),
-            staking_pool: staking.clone(),
-            burn_address: burn.clone(),
-            treasury_bps: 4000,
-            staking_bps: 4000,
-            burn_bps: 2000,
+            recipients: Vec::from_array(
+                &env,
+                [
+                    DistributionRecipient {
+                        address: treasury.clone(),
+                        bps: Bps::from_const(4000),
+                        kind: RecipientKind::Treasury,
+                    },
+                    DistributionRecipient {
+                        address: staking.clone(),
+                        bps: Bps::from_const(4000),
+                        kind: RecipientKind::Staking,
+                    },
+                    DistributionRecipient {
+                        address: burn.clone(),
+                        bps: Bps::from_const(2000),
+                        kind: RecipientKind::Burn,
+                    },
+                ],
+            ),
             min_distribution: 10_000_000,
+            burn_mode: BurnMode::Transfer,
+            staking_raw_transfer: false,
+            max_distribution_per_call: 0,
+            max_distribution_per_day: 0,
         };
 
-        client.update_config(&new_config);
+        client.update_config(&admin, &new_config);
+
+        let config = client.get_config();
+        assert_eq!(
+            config
+                .recipient_of(RecipientKind::Treasury)
+                .unwrap()
+                .bps
+                .get(),
+            4000
+        );
+        assert_eq!(
+            config
+                .recipient_of(RecipientKind::Staking)
+                .unwrap()
+                .bps
+                .get(),
+            4000
+        );
+
+        // Duplicate addresses are rejected
+        let mut bad_config = new_config.clone();
+        bad_config.recipients.set(
+            2,
+            DistributionRecipient {
+                address: treasury.clone(),
+                bps: Bps::from_const(2000),
+                kind: RecipientKind::Burn,
+            },
+        );
+        assert!(client.try_update_config(&admin, &bad_config).is_err());
+    }
+
+    #[test]
+    fn test_recipient_management() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let contract_id = env.register(FeeDistributor, ());
+        let client = FeeDistributorClient::new(&env, &contract_id);
+
+        let admin = Address::generate(&env);
+        let treasury = Address::generate(&env);
+        let staking = env.register(MockStakingPool, ());
+        let burn = Address::generate(&env);
+        let lp_wallet = Address::generate(&env);
+        let dev_fund = Address::generate(&env);
+        let user = Address::generate(&env);
+
+        let token_client = create_token(&env, &admin);
+        token_client.mint(&user, 1_000_000_000_000);
+
+        client.initialize(&admin, &treasury, &staking, &burn);
+
+        // 50/30/20 -> 40/30/20/10 -> 40/25/20/10/5
+        client.add_recipient(&lp_wallet, &1000, &RecipientKind::Other, &treasury);
+        client.add_recipient(&dev_fund, &500, &RecipientKind::Other, &staking);
+        assert_eq!(client.get_config().recipients.len(), 5);
+
+        // Cannot take more than the donor has
+        let extra = Address::generate(&env);
+        assert!(client
+            .try_add_recipient(&extra, &3000, &RecipientKind::Other, &burn)
+            .is_err());
 
+        client.receive_fees(&user, &token_client.address, &100_000_000_000);
+        let result = client.distribute(&token_client.address, &None);
+        assert_eq!(result.treasury_amount, 40_000_000_000);
+        assert_eq!(result.staking_amount, 25_000_000_000);
+        assert_eq!(result.burn_amount, 20_000_000_000);
+        assert_eq!(result.other_amount, 15_000_000_000);
+        assert_eq!(token_client.balance(&lp_wallet), 10_000_000_000);
+        assert_eq!(token_client.balance(&dev_fund), 5_000_000_000);
+
+        // Rebalance and remove
+        client.set_recipient_bps(&lp_wallet, &1500, &treasury);
+        let config = client.get_config();
+        assert_eq!(
+            config
+                .recipient_of(RecipientKind::Treasury)
+                .unwrap()
+                .bps
+                .get(),
+            3500
+        );
+
+        client.remove_recipient(&dev_fund, &staking);
         let config = client.get_config();
-        assert_eq!(config.treasury_bps, 4000);
-        assert_eq!(config.staking_bps, 4000);
+        assert_eq!(config.recipients.len(), 4);
+        assert_eq!(
+            config
+                .recipient_of(RecipientKind::Staking)
+                .unwrap()
+                .bps
+                .get(),
+            3000
+        );
+    }
+
+    #[test]
+    fn test_insurance_recipient() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let contract_id = env.register(FeeDistributor, ());
+        let client = FeeDistributorClient::new(&env, &contract_id);
+
+        let admin = Address::generate(&env);
+        let treasury = Address::generate(&env);
+        let staking = env.register(MockStakingPool, ());
+        let burn = Address::generate(&env);
+        let insurance = Address::generate(&env);
+        let user = Address::generate(&env);
+
+        let token_client = create_token(&env, &admin);
+        token_client.mint(&user, 1_000_000_000_000);
+
+        client.initialize(&admin, &treasury, &staking, &burn);
+        assert_eq!(client.insurance_bps(), 0);
+
+        client.add_recipient(&insurance, &500, &RecipientKind::Insurance, &treasury);
+        assert_eq!(client.insurance_bps(), 500);
+
+        client.receive_fees(&user, &token_client.address, &100_000_000_000);
+        let result = client.distribute(&token_client.address, &None);
+        assert_eq!(result.insurance_amount, 5_000_000_000);
+        assert_eq!(result.treasury_amount, 45_000_000_000);
+        assert_eq!(token_client.balance(&insurance), 5_000_000_000);
+        assert_eq!(
+            client.get_total_to_insurance(&token_client.address),
+            5_000_000_000
+        );
     }
 
     #[test]
@@ -672,13 +3790,13 @@ mod tests {
 
         let admin = Address::generate(&env);
         let treasury = Address::generate(&env);
-        let staking = Address::generate(&env);
+        let staking = env.register(MockStakingPool, ());
         let burn = Address::generate(&env);
         let user = Address::generate(&env);
         let emergency_to = Address::generate(&env);
 
-        let (token_client, token_admin) = create_token(&env, &admin);
-        token_admin.mint(&user, &1_000_000_000_000);
+        let token_client = create_token(&env, &admin);
+        token_client.mint(&user, 1_000_000_000_000);
 
         client.initialize(&admin, &treasury, &staking, &burn);
 
@@ -686,13 +3804,164 @@ mod tests {
         client.receive_fees(&user, &token_client.address, &100_000_000_000);
 
         // Pause contract
-        client.set_paused(&true);
+        client.set_paused(&admin, &true);
         assert!(client.is_paused());
 
         // Emergency withdraw
         let withdraw_amount = 50_000_000_000_i128;
-        client.emergency_withdraw(&token_client.address, &emergency_to, &withdraw_amount);
+        client.emergency_withdraw(
+            &admin,
+            &token_client.address,
+            &emergency_to,
+            &withdraw_amount,
+        );
 
         assert_eq!(token_client.balance(&emergency_to), withdraw_amount);
     }
+
+    #[test]
+    fn test_ledger_and_solvency() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let contract_id = env.register(FeeDistributor, ());
+        let client = FeeDistributorClient::new(&env, &contract_id);
+
+        let admin = Address::generate(&env);
+        let treasury = Address::generate(&env);
+        let staking = env.register(MockStakingPool, ());
+        let burn = Address::generate(&env);
+        let user = Address::generate(&env);
+
+        let token_client = create_token(&env, &admin);
+        token_client.mint(&user, 1_000_000_000_000);
+
+        client.initialize(&admin, &treasury, &staking, &burn);
+
+        client.receive_fees(&user, &token_client.address, &100_000_000_000);
+        client.distribute(&token_client.address, &None);
+        client.receive_fees(&user, &token_client.address, &40_000_000_000);
+
+        let ledger = client.get_token_ledger(&token_client.address);
+        assert_eq!(ledger.received, 140_000_000_000);
+        assert_eq!(ledger.total_allocated, 100_000_000_000);
+        assert_eq!(ledger.paid_out, 100_000_000_000);
+        assert_eq!(
+            ledger.allocated.get(staking.clone()).unwrap(),
+            30_000_000_000
+        );
+
+        // Books balance and the contract holds exactly what it owes
+        assert_eq!(client.assert_solvency(&token_client.address), 0);
+        assert!(!client.is_paused());
+
+        // Emergency withdrawal drains pending and stays balanced
+        client.set_paused(&admin, &true);
+        client.emergency_withdraw(&admin, &token_client.address, &admin, &40_000_000_000);
+        assert_eq!(client.get_pending_distribution(&token_client.address), 0);
+        assert_eq!(client.assert_solvency(&token_client.address), 0);
+    }
+
+    #[test]
+    fn test_min_distribution_override() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let contract_id = env.register(FeeDistributor, ());
+        let client = FeeDistributorClient::new(&env, &contract_id);
+
+        let admin = Address::generate(&env);
+        let staking = env.register(MockStakingPool, ());
+        let user = Address::generate(&env);
+
+        let token_client = create_token(&env, &admin);
+        let token = token_client.address.clone();
+        token_client.mint(&user, 1_000_000_000);
+
+        client.initialize(
+            &admin,
+            &Address::generate(&env),
+            &staking,
+            &Address::generate(&env),
+        );
+        assert_eq!(client.effective_min_distribution(&token), 10_000_000);
+
+        client.receive_fees(&user, &token, &1_000_000);
+        assert_eq!(
+            client.try_distribute(&token, &None).err(),
+            Some(Ok(SharedError::BelowMinimum))
+        );
+        assert_eq!(
+            client.try_set_min_distribution(&token, &-1),
+            Err(Ok(SharedError::InvalidAmount))
+        );
+
+        // A cheap token's threshold is lowered without touching the global one
+        client.set_min_distribution(&token, &1_000_000);
+        assert_eq!(client.min_distribution_override(&token), Some(1_000_000));
+        assert_eq!(client.effective_min_distribution(&token), 1_000_000);
+        assert_eq!(client.get_config().min_distribution, 10_000_000);
+        client.distribute(&token, &None);
+
+        client.clear_min_distribution(&token);
+        assert_eq!(client.effective_min_distribution(&token), 10_000_000);
+    }
+
+    #[test]
+    fn test_circuit_breaker() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let contract_id = env.register(FeeDistributor, ());
+        let client = FeeDistributorClient::new(&env, &contract_id);
+
+        let admin = Address::generate(&env);
+        let staking = env.register(MockStakingPool, ());
+        let user = Address::generate(&env);
+
+        let token_client = create_token(&env, &admin);
+        let token = token_client.address.clone();
+        token_client.mint(&user, 10_000_000_000);
+
+        client.initialize(
+            &admin,
+            &Address::generate(&env),
+            &staking,
+            &Address::generate(&env),
+        );
+        let mut config = client.get_config();
+        config.max_distribution_per_call = 500_000_000;
+        config.max_distribution_per_day = 800_000_000;
+        client.update_config(&admin, &config);
+
+        // Too large for one call: nothing moves, distributions pause
+        env.ledger().set_timestamp(SECONDS_PER_DAY);
+        client.receive_fees(&user, &token, &600_000_000);
+        assert_eq!(client.distribute(&token, &None).total_amount, 0);
+        assert_eq!(client.get_pending_distribution(&token), 600_000_000);
+        let pause = client.pause_config();
+        assert!(pause.withdrawals && !pause.deposits);
+        assert_eq!(
+            client.try_distribute(&token, &None).err(),
+            Some(Ok(SharedError::ContractPaused))
+        );
+
+        // Fee intake keeps running while distributions are halted
+        client.receive_fees(&user, &token, &300_000_000);
+
+        // Within both caps after the admin reviews and unpauses
+        client.set_paused(&admin, &false);
+        let result = client.distribute_partial(&token, &5000);
+        assert_eq!(result.total_amount, 450_000_000);
+
+        // The next 450 would take the day past 800
+        assert_eq!(client.distribute(&token, &None).total_amount, 0);
+        assert!(client.is_paused());
+
+        // A new day resets the daily cap
+        env.ledger().set_timestamp(2 * SECONDS_PER_DAY);
+        client.set_paused(&admin, &false);
+        assert_eq!(client.distribute(&token, &None).total_amount, 450_000_000);
+        assert_eq!(client.get_pending_distribution(&token), 0);
+    }
 }
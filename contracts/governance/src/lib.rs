@@ -0,0 +1,927 @@
+#![no_std]
+
+//! # Governance Contract
+//!
+//! On-chain proposals voted on with staking power.
+//!
+//! ## Flow
+//! 1. `propose` - a staker above the proposal threshold submits a list of
+//!    cross-contract calls; voting power is snapshotted just before creation
+//! 2. `vote` - stakers vote For/Against/Abstain with their snapshot stake;
+//!    the staking pool pins the voted stake until voting ends
+//! 3. `queue` - a succeeded proposal (quorum and threshold met) is queued
+//!    behind the timelock
+//! 4. `execute` - anyone executes the calls once the timelock has elapsed,
+//!    within the grace period
+//!
+//! ## Protocol Integration
+//! Transfer admin of the treasury, fee distributor, locker or staking pool
+//! to this contract with their `propose_admin`, then pass a proposal calling
+//! their `accept_admin`; proposals can then call any of their admin
+//! functions. The staking pool must have this contract set as its governor
+//! so votes can lock stake. Config, guardian and registry changes are
+//! proposals calling `update_config`, `set_admin` or `set_registry` on this
+//! contract itself.
+//!
+//! ## Upgrades
+//! The proposal timelock stands in for the upgrade timelock here: a proposal
+//! calling `upgrade(new_wasm_hash)` on this contract swaps its code, and a
+//! follow-up proposal calling `migrate()` runs the storage migration.
+
+use astro_core_shared::{
+    events::EventBuilder,
+    interfaces::StakingPoolClient,
+    math::{safe_add, safe_mul, BPS_DENOMINATOR},
+    types::{
+        extend_instance_ttl, get_persistent, get_ttl_config, set_persistent, set_ttl_config,
+        ContractInfo, SharedError, TtlConfig,
+    },
+    upgrade,
+};
+use soroban_sdk::{
+    contract, contractimpl, contracttype, Address, BytesN, Env, IntoVal, String, Symbol,
+    TryFromVal, Val, Vec,
+};
+
+// ════════════════════════════════════════════════════════════════════════════
+// Constants
+// ════════════════════════════════════════════════════════════════════════════
+
+/// Storage layout version of this code (see `migrate`)
+const STORAGE_VERSION: u32 = 1;
+
+/// Maximum calls in one proposal
+const MAX_CALLS: u32 = 10;
+
+/// Window after the timelock during which a queued proposal can be executed (14 days)
+const GRACE_PERIOD: u64 = 14 * 24 * 60 * 60;
+
+/// Maximum description length
+const MAX_DESCRIPTION_LENGTH: u32 = 1024;
+
+// ════════════════════════════════════════════════════════════════════════════
+// Storage Keys
+// ════════════════════════════════════════════════════════════════════════════
+
+#[contracttype]
+#[derive(Clone, Debug)]
+pub enum DataKey {
+    /// Guardian address (can cancel proposals)
+    Admin,
+    /// Governance configuration
+    Config,
+    /// Whether contract is initialized
+    Initialized,
+    /// Staking pool providing voting power
+    StakingPool,
+    /// Number of proposals created (also the next ID)
+    ProposalCount,
+    /// Proposal by ID (u64 -> Proposal)
+    Proposal(u64),
+    /// Vote receipt ((u64, Address) -> Receipt)
+    Receipt(u64, Address),
+    /// Protocol registry address (reported by `contract_info`)
+    Registry,
+}
+
+// ════════════════════════════════════════════════════════════════════════════
+// Types
+// ════════════════════════════════════════════════════════════════════════════
+
+/// Governance parameters
+#[contracttype]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct GovernanceConfig {
+    /// Voting window in seconds
+    pub voting_period: u64,
+    /// Delay between queueing and execution in seconds
+    pub timelock: u64,
+    /// Minimum participation as bps of total staked at the snapshot
+    pub quorum_bps: u32,
+    /// Minimum For share of For + Against votes in bps
+    pub threshold_bps: u32,
+    /// Minimum snapshot stake required to propose
+    pub proposal_threshold: i128,
+}
+
+impl GovernanceConfig {
+    /// Validate configuration
+    pub fn is_valid(&self) -> bool {
+        self.voting_period > 0
+            && self.quorum_bps <= BPS_DENOMINATOR as u32
+            && self.threshold_bps > 0
+            && self.threshold_bps <= BPS_DENOMINATOR as u32
+            && self.proposal_threshold >= 0
+    }
+}
+
+/// A cross-contract call executed by a proposal
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct ProposalCall {
+    /// Contract to call
+    pub contract: Address,
+    /// Function name
+    pub function: Symbol,
+    /// Call arguments
+    pub args: Vec<Val>,
+}
+
+/// Vote direction
+#[contracttype]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[repr(u32)]
+pub enum VoteType {
+    /// Vote against the proposal
+    Against = 0,
+    /// Vote for the proposal
+    For = 1,
+    /// Count toward quorum only
+    Abstain = 2,
+}
+
+/// Proposal lifecycle state (derived from the proposal and the clock)
+#[contracttype]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[repr(u32)]
+pub enum ProposalState {
+    /// Voting is open
+    Active = 0,
+    /// Voting ended without quorum or threshold
+    Defeated = 1,
+    /// Voting ended and the proposal passed
+    Succeeded = 2,
+    /// Waiting for the timelock
+    Queued = 3,
+    /// Grace period passed without execution
+    Expired = 4,
+    /// Calls executed
+    Executed = 5,
+    /// Cancelled by the proposer or guardian
+    Canceled = 6,
+}
+
+/// A governance proposal
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct Proposal {
+    /// Proposal ID
+    pub id: u64,
+    /// Proposer address
+    pub proposer: Address,
+    /// Human-readable description
+    pub description: String,
+    /// Calls executed on success
+    pub calls: Vec<ProposalCall>,
+    /// Voting power snapshot timestamp
+    pub snapshot: u64,
+    /// Voting end timestamp
+    pub end: u64,
+    /// Total For votes
+    pub for_votes: i128,
+    /// Total Against votes
+    pub against_votes: i128,
+    /// Total Abstain votes
+    pub abstain_votes: i128,
+    /// Earliest execution time once queued (0 = not queued)
+    pub eta: u64,
+    /// Whether the calls were executed
+    pub executed: bool,
+    /// Whether the proposal was cancelled
+    pub canceled: bool,
+}
+
+/// A voter's recorded vote
+#[contracttype]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Receipt {
+    /// Vote direction
+    pub support: VoteType,
+    /// Voting power used
+    pub weight: i128,
+}
+
+// ════════════════════════════════════════════════════════════════════════════
+// Contract Implementation
+// ════════════════════════════════════════════════════════════════════════════
+
+#[contract]
+pub struct Governance;
+
+#[contractimpl]
+impl Governance {
+    // ────────────────────────────────────────────────────────────────────────
+    // Initialization
+    // ────────────────────────────────────────────────────────────────────────
+
+    /// Initialize governance. `admin` is a guardian that can only cancel.
+    pub fn initialize(
+        env: Env,
+        admin: Address,
+        staking_pool: Address,
+        config: GovernanceConfig,
+    ) -> Result<(), SharedError> {
+        if env.storage().instance().has(&DataKey::Initialized) {
+            return Err(SharedError::AlreadyInitialized);
+        }
+
+        if !config.is_valid() {
+            return Err(SharedError::InvalidInitParams);
+        }
+
+        env.storage().instance().set(&DataKey::Admin, &admin);
+        env.storage()
+            .instance()
+            .set(&DataKey::StakingPool, &staking_pool);
+        env.storage().instance().set(&DataKey::Config, &config);
+        env.storage()
+            .instance()
+            .set(&DataKey::ProposalCount, &0_u64);
+        env.storage().instance().set(&DataKey::Initialized, &true);
+        upgrade::init_version(&env, STORAGE_VERSION);
+
+        extend_instance_ttl(&env);
+
+        let events = EventBuilder::new(&env);
+        events.publish(
+            "governance",
+            "initialized",
+            (admin, staking_pool, env.ledger().timestamp()),
+        );
+
+        Ok(())
+    }
+
+    // ────────────────────────────────────────────────────────────────────────
+    // Proposals
+    // ────────────────────────────────────────────────────────────────────────
+
+    /// Create a proposal. Voting opens immediately.
+    pub fn propose(
+        env: Env,
+        proposer: Address,
+        description: String,
+        calls: Vec<ProposalCall>,
+    ) -> Result<u64, SharedError> {
+        proposer.require_auth();
+        Self::require_initialized(&env)?;
+
+        if calls.is_empty() || calls.len() > MAX_CALLS {
+            return Err(SharedError::LimitExceeded);
+        }
+        if description.len() > MAX_DESCRIPTION_LENGTH {
+            return Err(SharedError::LimitExceeded);
+        }
+
+        let config = Self::load_config(&env)?;
+        let now = env.ledger().timestamp();
+        // Stake added in the proposal's own ledger doesn't count
+        let snapshot = now.saturating_sub(1);
+        let power = Self::staking(&env)?.get_stake_at(&proposer, &snapshot);
+        if power < config.proposal_threshold {
+            return Err(SharedError::BelowMinimum);
+        }
+
+        let id = Self::get_proposal_count(&env);
+        let proposal = Proposal {
+            id,
+            proposer: proposer.clone(),
+            description,
+            calls,
+            snapshot,
+            end: now + config.voting_period,
+            for_votes: 0,
+            against_votes: 0,
+            abstain_votes: 0,
+            eta: 0,
+            executed: false,
+            canceled: false,
+        };
+        Self::save_proposal(&env, &proposal);
+        env.storage()
+            .instance()
+            .set(&DataKey::ProposalCount, &(id + 1));
+
+        let events = EventBuilder::new(&env);
+        events.publish(
+            "governance",
+            "proposal_created",
+            (id, proposer, proposal.end, now),
+        );
+
+        extend_instance_ttl(&env);
+        Ok(id)
+    }
+
+    /// Vote on an active proposal with snapshot stake.
+    /// Returns the voting power used.
+    pub fn vote(
+        env: Env,
+        voter: Address,
+        proposal_id: u64,
+        support: VoteType,
+    ) -> Result<i128, SharedError> {
+        voter.require_auth();
+        Self::require_initialized(&env)?;
+
+        let mut proposal = Self::load_proposal(&env, proposal_id)?;
+        if Self::state_of(&env, &proposal)? != ProposalState::Active {
+            return Err(SharedError::InvalidState);
+        }
+
+        let receipt_key = DataKey::Receipt(proposal_id, voter.clone());
+        if env.storage().persistent().has(&receipt_key) {
+            return Err(SharedError::AlreadyExecuted);
+        }
+
+        let staking = Self::staking(&env)?;
+        let weight = staking.get_stake_at(&voter, &proposal.snapshot);
+        if weight <= 0 {
+            return Err(SharedError::InsufficientBalance);
+        }
+
+        // Prevent vote-then-exit until voting ends
+        staking.lock_stake_for_vote(&voter, &proposal_id, &proposal.end);
+
+        match support {
+            VoteType::For => proposal.for_votes = safe_add(proposal.for_votes, weight)?,
+            VoteType::Against => proposal.against_votes = safe_add(proposal.against_votes, weight)?,
+            VoteType::Abstain => proposal.abstain_votes = safe_add(proposal.abstain_votes, weight)?,
+        }
+        Self::save_proposal(&env, &proposal);
+
+        set_persistent(&env, &receipt_key, &Receipt { support, weight });
+
+        let events = EventBuilder::new(&env);
+        events.publish(
+            "governance",
+            "vote_cast",
+            (
+                proposal_id,
+                voter,
+                support,
+                weight,
+                env.ledger().timestamp(),
+            ),
+        );
+
+        extend_instance_ttl(&env);
+        Ok(weight)
+    }
+
+    /// Queue a succeeded proposal behind the timelock (callable by anyone).
+    /// Returns the execution time.
+    pub fn queue(env: Env, proposal_id: u64) -> Result<u64, SharedError> {
+        Self::require_initialized(&env)?;
+
+        let mut proposal = Self::load_proposal(&env, proposal_id)?;
+        if Self::state_of(&env, &proposal)? != ProposalState::Succeeded {
+            return Err(SharedError::InvalidState);
+        }
+
+        let config = Self::load_config(&env)?;
+        proposal.eta = env.ledger().timestamp() + config.timelock;
+        Self::save_proposal(&env, &proposal);
+
+        let events = EventBuilder::new(&env);
+        events.publish(
+            "governance",
+            "proposal_queued",
+            (proposal_id, proposal.eta, env.ledger().timestamp()),
+        );
+
+        extend_instance_ttl(&env);
+        Ok(proposal.eta)
+    }
+
+    /// Execute a queued proposal's calls once the timelock has elapsed
+    /// (callable by anyone). Returns each call's result.
+    pub fn execute(env: Env, proposal_id: u64) -> Result<Vec<Val>, SharedError> {
+        Self::require_initialized(&env)?;
+
+        let mut proposal = Self::load_proposal(&env, proposal_id)?;
+        if Self::state_of(&env, &proposal)? != ProposalState::Queued {
+            return Err(SharedError::InvalidState);
+        }
+        if env.ledger().timestamp() < proposal.eta {
+            return Err(SharedError::CooldownNotElapsed);
+        }
+
+        // Mark executed before calling out
+        proposal.executed = true;
+        Self::save_proposal(&env, &proposal);
+
+        let this = env.current_contract_address();
+        let mut results = Vec::new(&env);
+        for call in proposal.calls.iter() {
+            let result: Val = if call.contract == this {
+                Self::execute_self_call(&env, &call)?
+            } else {
+                env.invoke_contract(&call.contract, &call.function, call.args)
+            };
+            results.push_back(result);
+        }
+
+        let events = EventBuilder::new(&env);
+        events.publish(
+            "governance",
+            "proposal_executed",
+            (proposal_id, env.ledger().timestamp()),
+        );
+
+        extend_instance_ttl(&env);
+        Ok(results)
+    }
+
+    /// Cancel a proposal that hasn't been executed (proposer or guardian)
+    pub fn cancel(env: Env, caller: Address, proposal_id: u64) -> Result<(), SharedError> {
+        caller.require_auth();
+        Self::require_initialized(&env)?;
+
+        let mut proposal = Self::load_proposal(&env, proposal_id)?;
+        let admin: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::Admin)
+            .ok_or(SharedError::NotInitialized)?;
+        if caller != proposal.proposer && caller != admin {
+            return Err(SharedError::Unauthorized);
+        }
+        if proposal.executed || proposal.canceled {
+            return Err(SharedError::InvalidState);
+        }
+
+        proposal.canceled = true;
+        Self::save_proposal(&env, &proposal);
+
+        let events = EventBuilder::new(&env);
+        events.publish(
+            "governance",
+            "proposal_canceled",
+            (proposal_id, caller, env.ledger().timestamp()),
+        );
+
+        extend_instance_ttl(&env);
+        Ok(())
+    }
+
+    // ────────────────────────────────────────────────────────────────────────
+    // Admin Functions
+    // ────────────────────────────────────────────────────────────────────────
+
+    /// Set how far storage TTLs are extended (see `TtlConfig`)
+    pub fn set_ttl_config(env: Env, config: TtlConfig) -> Result<(), SharedError> {
+        let admin = Self::admin(env.clone())?;
+        admin.require_auth();
+
+        set_ttl_config(&env, &config)?;
+        extend_instance_ttl(&env);
+
+        Ok(())
+    }
+
+    // ────────────────────────────────────────────────────────────────────────
+    // Query Functions
+    // ────────────────────────────────────────────────────────────────────────
+
+    /// Get the storage layout version
+    pub fn version(env: Env) -> u32 {
+        upgrade::version(&env)
+    }
+
+    /// Get a proposal
+    pub fn get_proposal(env: Env, proposal_id: u64) -> Option<Proposal> {
+        get_persistent(&env, &DataKey::Proposal(proposal_id))
+    }
+
+    /// Get the current state of a proposal
+    pub fn state(env: Env, proposal_id: u64) -> Result<ProposalState, SharedError> {
+        let proposal = Self::load_proposal(&env, proposal_id)?;
+        Self::state_of(&env, &proposal)
+    }
+
+    /// Get a voter's receipt for a proposal
+    pub fn get_receipt(env: Env, proposal_id: u64, voter: Address) -> Option<Receipt> {
+        get_persistent(&env, &DataKey::Receipt(proposal_id, voter))
+    }
+
+    /// Number of proposals created
+    pub fn proposal_count(env: Env) -> u64 {
+        Self::get_proposal_count(&env)
+    }
+
+    /// Get configuration
+    pub fn get_config(env: Env) -> Result<GovernanceConfig, SharedError> {
+        Self::load_config(&env)
+    }
+
+    /// Get the guardian address
+    pub fn admin(env: Env) -> Result<Address, SharedError> {
+        env.storage()
+            .instance()
+            .get(&DataKey::Admin)
+            .ok_or(SharedError::NotInitialized)
+    }
+
+    /// Get the storage TTL config
+    pub fn ttl_config(env: Env) -> TtlConfig {
+        get_ttl_config(&env)
+    }
+
+    /// Describe this contract for explorers and wallets
+    pub fn contract_info(env: Env) -> Result<ContractInfo, SharedError> {
+        let admin = Self::admin(env.clone())?;
+        let config = Self::load_config(&env)?;
+        let info = ContractInfo::new(
+            &env,
+            "governance",
+            env!("CARGO_PKG_VERSION"),
+            admin,
+            false,
+            env.storage().instance().get(&DataKey::Registry),
+        );
+        Ok(info
+            .with("voting_period", config.voting_period as i128)
+            .with("timelock", config.timelock as i128)
+            .with("quorum_bps", config.quorum_bps as i128)
+            .with("threshold_bps", config.threshold_bps as i128)
+            .with("proposal_threshold", config.proposal_threshold))
+    }
+
+    // ────────────────────────────────────────────────────────────────────────
+    // Internal Functions
+    // ────────────────────────────────────────────────────────────────────────
+
+    fn require_initialized(env: &Env) -> Result<(), SharedError> {
+        let initialized: bool = env
+            .storage()
+            .instance()
+            .get(&DataKey::Initialized)
+            .unwrap_or(false);
+
+        if !initialized {
+            return Err(SharedError::NotInitialized);
+        }
+        Ok(())
+    }
+
+    /// Apply a proposal call targeting this contract. Contracts can't re-enter
+    /// themselves, so self-governed settings are dispatched here.
+    fn execute_self_call(env: &Env, call: &ProposalCall) -> Result<Val, SharedError> {
+        if call.function == Symbol::new(env, "migrate") {
+            let version = upgrade::migrate(env, STORAGE_VERSION, |_, _| Ok(()))?;
+            return Ok(version.into_val(env));
+        }
+
+        let arg = call.args.get(0).ok_or(SharedError::InvalidState)?;
+        if call.function == Symbol::new(env, "upgrade") {
+            let wasm_hash =
+                BytesN::<32>::try_from_val(env, &arg).map_err(|_| SharedError::InvalidState)?;
+            let events = EventBuilder::new(env);
+            events.publish(
+                "upgrade",
+                "executed",
+                (
+                    wasm_hash.clone(),
+                    upgrade::version(env),
+                    env.ledger().timestamp(),
+                ),
+            );
+            env.deployer().update_current_contract_wasm(wasm_hash);
+        } else if call.function == Symbol::new(env, "update_config") {
+            let config =
+                GovernanceConfig::try_from_val(env, &arg).map_err(|_| SharedError::InvalidState)?;
+            if !config.is_valid() {
+                return Err(SharedError::InvalidInitParams);
+            }
+            env.storage().instance().set(&DataKey::Config, &config);
+        } else if call.function == Symbol::new(env, "set_admin") {
+            let admin = Address::try_from_val(env, &arg).map_err(|_| SharedError::InvalidState)?;
+            env.storage().instance().set(&DataKey::Admin, &admin);
+        } else if call.function == Symbol::new(env, "set_registry") {
+            let registry =
+                Address::try_from_val(env, &arg).map_err(|_| SharedError::InvalidState)?;
+            env.storage().instance().set(&DataKey::Registry, &registry);
+        } else {
+            return Err(SharedError::InvalidState);
+        }
+        Ok(().into_val(env))
+    }
+
+    fn load_config(env: &Env) -> Result<GovernanceConfig, SharedError> {
+        env.storage()
+            .instance()
+            .get(&DataKey::Config)
+            .ok_or(SharedError::NotInitialized)
+    }
+
+    fn staking(env: &Env) -> Result<StakingPoolClient<'_>, SharedError> {
+        let pool: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::StakingPool)
+            .ok_or(SharedError::NotInitialized)?;
+        Ok(StakingPoolClient::new(env, &pool))
+    }
+
+    fn get_proposal_count(env: &Env) -> u64 {
+        env.storage()
+            .instance()
+            .get(&DataKey::ProposalCount)
+            .unwrap_or(0)
+    }
+
+    fn load_proposal(env: &Env, proposal_id: u64) -> Result<Proposal, SharedError> {
+        get_persistent(env, &DataKey::Proposal(proposal_id)).ok_or(SharedError::InvalidState)
+    }
+
+    fn save_proposal(env: &Env, proposal: &Proposal) {
+        let key = DataKey::Proposal(proposal.id);
+        set_persistent(env, &key, proposal);
+    }
+
+    fn state_of(env: &Env, proposal: &Proposal) -> Result<ProposalState, SharedError> {
+        let now = env.ledger().timestamp();
+        if proposal.canceled {
+            return Ok(ProposalState::Canceled);
+        }
+        if proposal.executed {
+            return Ok(ProposalState::Executed);
+        }
+        if now < proposal.end {
+            return Ok(ProposalState::Active);
+        }
+        if proposal.eta > 0 {
+            if now >= proposal.eta + GRACE_PERIOD {
+                return Ok(ProposalState::Expired);
+            }
+            return Ok(ProposalState::Queued);
+        }
+        if Self::passed(env, proposal)? {
+            Ok(ProposalState::Succeeded)
+        } else {
+            Ok(ProposalState::Defeated)
+        }
+    }
+
+    /// Quorum (all votes vs snapshot total stake) and threshold (For vs For + Against)
+    fn passed(env: &Env, proposal: &Proposal) -> Result<bool, SharedError> {
+        let config = Self::load_config(env)?;
+        let total_staked = Self::staking(env)?.total_staked_at(&proposal.snapshot);
+
+        let participation = safe_add(
+            safe_add(proposal.for_votes, proposal.against_votes)?,
+            proposal.abstain_votes,
+        )?;
+        let quorum_met = safe_mul(participation, BPS_DENOMINATOR)?
+            >= safe_mul(total_staked, config.quorum_bps as i128)?;
+
+        let decisive = safe_add(proposal.for_votes, proposal.against_votes)?;
+        let threshold_met = proposal.for_votes > 0
+            && safe_mul(proposal.for_votes, BPS_DENOMINATOR)?
+                >= safe_mul(decisive, config.threshold_bps as i128)?;
+
+        Ok(quorum_met && threshold_met)
+    }
+}
+
+// ════════════════════════════════════════════════════════════════════════════
+// Tests
+// ════════════════════════════════════════════════════════════════════════════
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use soroban_sdk::testutils::{Address as _, Ledger as _};
+
+    /// Staking pool with fixed stakes and recorded vote locks
+    #[contract]
+    pub struct MockStakingPool;
+
+    #[contractimpl]
+    impl MockStakingPool {
+        pub fn set_stake(env: Env, user: Address, amount: i128) {
+            let total: i128 = env.storage().instance().get(&0u32).unwrap_or(0);
+            env.storage().instance().set(&0u32, &(total + amount));
+            env.storage().instance().set(&user, &amount);
+        }
+
+        pub fn get_stake_at(env: Env, user: Address, _timestamp: u64) -> i128 {
+            env.storage().instance().get(&user).unwrap_or(0)
+        }
+
+        pub fn total_staked_at(env: Env, _timestamp: u64) -> i128 {
+            env.storage().instance().get(&0u32).unwrap_or(0)
+        }
+
+        pub fn lock_stake_for_vote(env: Env, user: Address, _proposal_id: u64, until: u64) -> i128 {
+            env.storage().instance().set(&1u32, &until);
+            env.storage().instance().get(&user).unwrap_or(0)
+        }
+
+        pub fn locked_until(env: Env) -> u64 {
+            env.storage().instance().get(&1u32).unwrap_or(0)
+        }
+    }
+
+    /// Contract administered by governance
+    #[contract]
+    pub struct MockTarget;
+
+    #[contractimpl]
+    impl MockTarget {
+        pub fn set_admin(env: Env, admin: Address) {
+            env.storage().instance().set(&0u32, &admin);
+        }
+
+        pub fn set_value(env: Env, value: u32) -> u32 {
+            let admin: Address = env.storage().instance().get(&0u32).unwrap();
+            admin.require_auth();
+            env.storage().instance().set(&1u32, &value);
+            value
+        }
+
+        pub fn value(env: Env) -> u32 {
+            env.storage().instance().get(&1u32).unwrap_or(0)
+        }
+    }
+
+    fn config() -> GovernanceConfig {
+        GovernanceConfig {
+            voting_period: 1_000,
+            timelock: 500,
+            quorum_bps: 4000,
+            threshold_bps: 5000,
+            proposal_threshold: 100,
+        }
+    }
+
+    struct Setup<'a> {
+        env: Env,
+        client: GovernanceClient<'a>,
+        staking: MockStakingPoolClient<'a>,
+        target: MockTargetClient<'a>,
+        admin: Address,
+    }
+
+    fn setup<'a>() -> Setup<'a> {
+        let env = Env::default();
+        env.mock_all_auths();
+        env.ledger().set_timestamp(10_000);
+
+        let admin = Address::generate(&env);
+        let staking = MockStakingPoolClient::new(&env, &env.register(MockStakingPool, ()));
+        let client = GovernanceClient::new(&env, &env.register(Governance, ()));
+        client.initialize(&admin, &staking.address, &config());
+
+        // Governance administers the target
+        let target = MockTargetClient::new(&env, &env.register(MockTarget, ()));
+        target.set_admin(&client.address);
+
+        Setup {
+            env,
+            client,
+            staking,
+            target,
+            admin,
+        }
+    }
+
+    fn set_value_call(s: &Setup, value: u32) -> Vec<ProposalCall> {
+        Vec::from_array(
+            &s.env,
+            [ProposalCall {
+                contract: s.target.address.clone(),
+                function: Symbol::new(&s.env, "set_value"),
+                args: (value,).into_val(&s.env),
+            }],
+        )
+    }
+
+    #[test]
+    fn test_proposal_lifecycle() {
+        let s = setup();
+        let proposer = Address::generate(&s.env);
+        let voter = Address::generate(&s.env);
+        s.staking.set_stake(&proposer, &300);
+        s.staking.set_stake(&voter, &700);
+
+        let description = String::from_str(&s.env, "Set value to 42");
+        let id = s
+            .client
+            .propose(&proposer, &description, &set_value_call(&s, 42));
+        assert_eq!(s.client.state(&id), ProposalState::Active);
+
+        assert_eq!(s.client.vote(&proposer, &id, &VoteType::For), 300);
+        assert_eq!(s.client.vote(&voter, &id, &VoteType::For), 700);
+        assert!(s.client.try_vote(&voter, &id, &VoteType::Against).is_err());
+        assert_eq!(s.staking.locked_until(), 11_000);
+
+        // Can't queue or execute while voting
+        assert!(s.client.try_queue(&id).is_err());
+
+        s.env.ledger().set_timestamp(11_000);
+        assert_eq!(s.client.state(&id), ProposalState::Succeeded);
+        let eta = s.client.queue(&id);
+        assert_eq!(eta, 11_500);
+        assert!(s.client.try_execute(&id).is_err());
+
+        s.env.ledger().set_timestamp(eta);
+        s.client.execute(&id);
+        assert_eq!(s.target.value(), 42);
+        assert_eq!(s.client.state(&id), ProposalState::Executed);
+        assert!(s.client.try_execute(&id).is_err());
+    }
+
+    #[test]
+    fn test_quorum_and_threshold() {
+        let s = setup();
+        let small = Address::generate(&s.env);
+        let large = Address::generate(&s.env);
+        s.staking.set_stake(&small, &200);
+        s.staking.set_stake(&large, &800);
+
+        let description = String::from_str(&s.env, "");
+
+        // 20% participation < 40% quorum
+        let id = s
+            .client
+            .propose(&small, &description, &set_value_call(&s, 1));
+        s.client.vote(&small, &id, &VoteType::For);
+        s.env.ledger().set_timestamp(11_000);
+        assert_eq!(s.client.state(&id), ProposalState::Defeated);
+        assert!(s.client.try_queue(&id).is_err());
+
+        // Quorum met but majority against
+        let id = s
+            .client
+            .propose(&small, &description, &set_value_call(&s, 2));
+        s.client.vote(&small, &id, &VoteType::For);
+        s.client.vote(&large, &id, &VoteType::Against);
+        s.env.ledger().set_timestamp(12_000);
+        assert_eq!(s.client.state(&id), ProposalState::Defeated);
+
+        // Below proposal threshold
+        let dust = Address::generate(&s.env);
+        s.staking.set_stake(&dust, &50);
+        assert!(s
+            .client
+            .try_propose(&dust, &description, &set_value_call(&s, 3))
+            .is_err());
+    }
+
+    #[test]
+    fn test_cancel_and_expiry() {
+        let s = setup();
+        let proposer = Address::generate(&s.env);
+        s.staking.set_stake(&proposer, &1_000);
+        let description = String::from_str(&s.env, "");
+
+        let id = s
+            .client
+            .propose(&proposer, &description, &set_value_call(&s, 7));
+        let stranger = Address::generate(&s.env);
+        assert!(s.client.try_cancel(&stranger, &id).is_err());
+        s.client.cancel(&s.admin, &id);
+        assert_eq!(s.client.state(&id), ProposalState::Canceled);
+        assert!(s.client.try_vote(&proposer, &id, &VoteType::For).is_err());
+
+        let id = s
+            .client
+            .propose(&proposer, &description, &set_value_call(&s, 8));
+        s.client.vote(&proposer, &id, &VoteType::For);
+        s.env.ledger().set_timestamp(11_000);
+        let eta = s.client.queue(&id);
+        s.env.ledger().set_timestamp(eta + GRACE_PERIOD);
+        assert_eq!(s.client.state(&id), ProposalState::Expired);
+        assert!(s.client.try_execute(&id).is_err());
+        assert_eq!(s.target.value(), 0);
+    }
+
+    #[test]
+    fn test_config_only_via_proposal() {
+        let s = setup();
+        let mut new_config = config();
+        new_config.quorum_bps = 1000;
+
+        let proposer = Address::generate(&s.env);
+        s.staking.set_stake(&proposer, &1_000);
+        let calls = Vec::from_array(
+            &s.env,
+            [ProposalCall {
+                contract: s.client.address.clone(),
+                function: Symbol::new(&s.env, "update_config"),
+                args: (new_config.clone(),).into_val(&s.env),
+            }],
+        );
+        let description = String::from_str(&s.env, "Lower quorum");
+        let id = s.client.propose(&proposer, &description, &calls);
+        s.client.vote(&proposer, &id, &VoteType::For);
+        s.env.ledger().set_timestamp(11_000);
+        let eta = s.client.queue(&id);
+        s.env.ledger().set_timestamp(eta);
+        s.client.execute(&id);
+
+        assert_eq!(s.client.get_config(), new_config);
+    }
+}
@@ -0,0 +1,496 @@
+#![no_std]
+
+//! # Guardian Contract
+//!
+//! Protocol-wide emergency pause controller. Incident response used to take
+//! one `set_paused` transaction per contract; the guardian pauses (or
+//! unpauses) every registered contract in a single call.
+//!
+//! ## Features
+//! - Admin registers the protocol contracts to control
+//! - `GUARDIAN` role holders (and the admin) call `pause_all` / `unpause_all`
+//! - One contract failing to pause never blocks the others; failures are
+//!   returned and emitted as events
+//!
+//! ## Setup
+//! Each registered contract must grant this contract its `PAUSER` role
+//! (`grant_role(PAUSER, guardian)`), since the guardian calls
+//! `set_paused(guardian, paused)` on it.
+
+use astro_core_shared::{
+    access_control::{self, GUARDIAN},
+    events::{emit_admin_changed, EventBuilder},
+    interfaces::{IntoSharedResult, PausableClient},
+    ownership,
+    types::{
+        extend_instance_ttl, get_ttl_config, set_ttl_config, ContractInfo, SharedError, TtlConfig,
+    },
+    upgrade::{self, PendingUpgrade},
+};
+use soroban_sdk::{contract, contractimpl, contracttype, Address, BytesN, Env, Symbol, Vec};
+
+// ════════════════════════════════════════════════════════════════════════════
+// Constants
+// ════════════════════════════════════════════════════════════════════════════
+
+/// Storage layout version of this code (see `migrate`)
+const STORAGE_VERSION: u32 = 1;
+
+/// Maximum number of registered contracts (bounds `pause_all` cost)
+const MAX_CONTRACTS: u32 = 20;
+
+// ════════════════════════════════════════════════════════════════════════════
+// Storage Keys
+// ════════════════════════════════════════════════════════════════════════════
+
+#[contracttype]
+#[derive(Clone, Debug)]
+pub enum DataKey {
+    /// Admin address
+    Admin,
+    /// Whether contract is initialized
+    Initialized,
+    /// Contracts paused and unpaused together (Vec<Address>)
+    Contracts,
+    /// Protocol registry address (reported by `contract_info`)
+    Registry,
+}
+
+// ════════════════════════════════════════════════════════════════════════════
+// Contract Implementation
+// ════════════════════════════════════════════════════════════════════════════
+
+#[contract]
+pub struct Guardian;
+
+#[contractimpl]
+impl Guardian {
+    // ────────────────────────────────────────────────────────────────────────
+    // Initialization
+    // ────────────────────────────────────────────────────────────────────────
+
+    /// Initialize the guardian
+    pub fn initialize(env: Env, admin: Address) -> Result<(), SharedError> {
+        if env.storage().instance().has(&DataKey::Initialized) {
+            return Err(SharedError::AlreadyInitialized);
+        }
+
+        env.storage().instance().set(&DataKey::Admin, &admin);
+        env.storage()
+            .instance()
+            .set(&DataKey::Contracts, &Vec::<Address>::new(&env));
+        env.storage().instance().set(&DataKey::Initialized, &true);
+        upgrade::init_version(&env, STORAGE_VERSION);
+
+        extend_instance_ttl(&env);
+
+        let events = EventBuilder::new(&env);
+        events.publish("guardian", "initialized", (admin, env.ledger().timestamp()));
+
+        Ok(())
+    }
+
+    // ────────────────────────────────────────────────────────────────────────
+    // Emergency Pause (admin or GUARDIAN)
+    // ────────────────────────────────────────────────────────────────────────
+
+    /// Pause every registered contract. Returns the contracts that could not
+    /// be paused (empty when all succeeded).
+    pub fn pause_all(env: Env, caller: Address) -> Result<Vec<Address>, SharedError> {
+        Self::require_guardian(&env, &caller)?;
+        Ok(Self::set_all_paused(&env, &caller, true))
+    }
+
+    /// Unpause every registered contract. Returns the contracts that could
+    /// not be unpaused (empty when all succeeded).
+    pub fn unpause_all(env: Env, caller: Address) -> Result<Vec<Address>, SharedError> {
+        Self::require_guardian(&env, &caller)?;
+        Ok(Self::set_all_paused(&env, &caller, false))
+    }
+
+    // ────────────────────────────────────────────────────────────────────────
+    // Admin Functions
+    // ────────────────────────────────────────────────────────────────────────
+
+    /// Register a contract to be paused and unpaused by the guardian
+    pub fn register_contract(env: Env, contract: Address) -> Result<(), SharedError> {
+        Self::require_admin(&env)?;
+
+        let mut contracts = Self::get_contracts(env.clone());
+        if contracts.contains(&contract) {
+            return Err(SharedError::InvalidState);
+        }
+        if contracts.len() >= MAX_CONTRACTS {
+            return Err(SharedError::LimitExceeded);
+        }
+        contracts.push_back(contract.clone());
+        env.storage()
+            .instance()
+            .set(&DataKey::Contracts, &contracts);
+
+        let events = EventBuilder::new(&env);
+        events.publish(
+            "guardian",
+            "contract_registered",
+            (contract, env.ledger().timestamp()),
+        );
+
+        extend_instance_ttl(&env);
+        Ok(())
+    }
+
+    /// Stop controlling a contract
+    pub fn unregister_contract(env: Env, contract: Address) -> Result<(), SharedError> {
+        Self::require_admin(&env)?;
+
+        let mut contracts = Self::get_contracts(env.clone());
+        let index = contracts
+            .first_index_of(&contract)
+            .ok_or(SharedError::InvalidAddress)?;
+        contracts.remove(index);
+        env.storage()
+            .instance()
+            .set(&DataKey::Contracts, &contracts);
+
+        let events = EventBuilder::new(&env);
+        events.publish(
+            "guardian",
+            "contract_unregistered",
+            (contract, env.ledger().timestamp()),
+        );
+
+        extend_instance_ttl(&env);
+        Ok(())
+    }
+
+    /// Grant a role (e.g. `GUARDIAN`) to an account (admin only)
+    pub fn grant_role(env: Env, role: Symbol, account: Address) -> Result<(), SharedError> {
+        Self::require_admin(&env)?;
+        access_control::grant_role(&env, role, account)?;
+        extend_instance_ttl(&env);
+        Ok(())
+    }
+
+    /// Revoke a role from an account (admin only)
+    pub fn revoke_role(env: Env, role: Symbol, account: Address) -> Result<(), SharedError> {
+        Self::require_admin(&env)?;
+        access_control::revoke_role(&env, role, account)?;
+        extend_instance_ttl(&env);
+        Ok(())
+    }
+
+    /// Propose a new admin (two-step transfer)
+    pub fn propose_admin(env: Env, new_admin: Address) -> Result<(), SharedError> {
+        Self::require_admin(&env)?;
+
+        let admin = Self::admin(env.clone())?;
+        ownership::propose(&env, &admin, &new_admin)?;
+        extend_instance_ttl(&env);
+
+        Ok(())
+    }
+
+    /// Accept a pending admin proposal (proposed admin only)
+    pub fn accept_admin(env: Env) -> Result<(), SharedError> {
+        let old_admin = Self::admin(env.clone())?;
+        let new_admin = ownership::accept(&env, &old_admin)?;
+
+        env.storage().instance().set(&DataKey::Admin, &new_admin);
+
+        emit_admin_changed(&env, &old_admin, &new_admin);
+        extend_instance_ttl(&env);
+
+        Ok(())
+    }
+
+    /// Cancel a pending admin proposal
+    pub fn cancel_admin_transfer(env: Env) -> Result<(), SharedError> {
+        Self::require_admin(&env)?;
+
+        ownership::cancel(&env)?;
+        extend_instance_ttl(&env);
+
+        Ok(())
+    }
+
+    /// Set the protocol registry address reported by `contract_info`
+    pub fn set_registry(env: Env, registry: Address) -> Result<(), SharedError> {
+        Self::require_admin(&env)?;
+        env.storage().instance().set(&DataKey::Registry, &registry);
+        extend_instance_ttl(&env);
+        Ok(())
+    }
+
+    /// Set how far storage TTLs are extended (see `TtlConfig`)
+    pub fn set_ttl_config(env: Env, config: TtlConfig) -> Result<(), SharedError> {
+        Self::require_admin(&env)?;
+
+        set_ttl_config(&env, &config)?;
+        extend_instance_ttl(&env);
+
+        Ok(())
+    }
+
+    // ────────────────────────────────────────────────────────────────────────
+    // Upgrades (admin only)
+    // ────────────────────────────────────────────────────────────────────────
+
+    /// Queue a WASM upgrade behind the upgrade timelock. Returns its eta.
+    pub fn queue_upgrade(env: Env, new_wasm_hash: BytesN<32>) -> Result<u64, SharedError> {
+        Self::require_admin(&env)?;
+        upgrade::queue(&env, new_wasm_hash)
+    }
+
+    /// Cancel the queued upgrade
+    pub fn cancel_upgrade(env: Env) -> Result<(), SharedError> {
+        Self::require_admin(&env)?;
+        upgrade::cancel(&env)
+    }
+
+    /// Switch to the queued WASM once the timelock has elapsed
+    pub fn upgrade(env: Env, new_wasm_hash: BytesN<32>) -> Result<(), SharedError> {
+        Self::require_admin(&env)?;
+        upgrade::execute(&env, new_wasm_hash)
+    }
+
+    /// Migrate storage to `STORAGE_VERSION` after an upgrade
+    pub fn migrate(env: Env) -> Result<u32, SharedError> {
+        Self::require_admin(&env)?;
+        upgrade::migrate(&env, STORAGE_VERSION, |_, _| Ok(()))
+    }
+
+    // ────────────────────────────────────────────────────────────────────────
+    // Query Functions
+    // ────────────────────────────────────────────────────────────────────────
+
+    /// Get the storage layout version
+    pub fn version(env: Env) -> u32 {
+        upgrade::version(&env)
+    }
+
+    /// Get the queued upgrade, if any
+    pub fn get_pending_upgrade(env: Env) -> Option<PendingUpgrade> {
+        upgrade::pending(&env)
+    }
+
+    /// Get the registered contracts
+    pub fn get_contracts(env: Env) -> Vec<Address> {
+        env.storage()
+            .instance()
+            .get(&DataKey::Contracts)
+            .unwrap_or(Vec::new(&env))
+    }
+
+    /// Get each registered contract's pause state (`false` if the contract
+    /// can't be queried)
+    pub fn pause_status(env: Env) -> Vec<(Address, bool)> {
+        let mut status = Vec::new(&env);
+        for contract in Self::get_contracts(env.clone()).iter() {
+            let paused = PausableClient::new(&env, &contract)
+                .try_is_paused()
+                .into_shared()
+                .unwrap_or(false);
+            status.push_back((contract, paused));
+        }
+        status
+    }
+
+    /// Check if an account holds a role
+    pub fn has_role(env: Env, role: Symbol, account: Address) -> bool {
+        access_control::has_role(&env, &role, &account)
+    }
+
+    /// Get admin address
+    pub fn admin(env: Env) -> Result<Address, SharedError> {
+        env.storage()
+            .instance()
+            .get(&DataKey::Admin)
+            .ok_or(SharedError::NotInitialized)
+    }
+
+    /// Get the pending admin, if a transfer is in progress
+    pub fn pending_admin(env: Env) -> Option<Address> {
+        ownership::pending(&env)
+    }
+
+    /// Get the storage TTL config
+    pub fn ttl_config(env: Env) -> TtlConfig {
+        get_ttl_config(&env)
+    }
+
+    /// Describe this contract for explorers and wallets
+    pub fn contract_info(env: Env) -> Result<ContractInfo, SharedError> {
+        let admin = Self::admin(env.clone())?;
+        let info = ContractInfo::new(
+            &env,
+            "guardian",
+            env!("CARGO_PKG_VERSION"),
+            admin,
+            false,
+            env.storage().instance().get(&DataKey::Registry),
+        );
+        Ok(info.with("contracts", Self::get_contracts(env.clone()).len() as i128))
+    }
+
+    // ────────────────────────────────────────────────────────────────────────
+    // Internal Functions
+    // ────────────────────────────────────────────────────────────────────────
+
+    fn require_admin(env: &Env) -> Result<(), SharedError> {
+        let admin = Self::admin(env.clone())?;
+        admin.require_auth();
+        Ok(())
+    }
+
+    /// Require `caller`'s auth and that it is the admin or a `GUARDIAN`
+    fn require_guardian(env: &Env, caller: &Address) -> Result<(), SharedError> {
+        let admin = Self::admin(env.clone())?;
+        access_control::require_role(env, &GUARDIAN, caller, &admin)
+    }
+
+    /// Call `set_paused` on every registered contract, collecting failures
+    fn set_all_paused(env: &Env, caller: &Address, paused: bool) -> Vec<Address> {
+        let this = env.current_contract_address();
+        let events = EventBuilder::new(env);
+        let mut failed = Vec::new(env);
+
+        for contract in Self::get_contracts(env.clone()).iter() {
+            let result = PausableClient::new(env, &contract)
+                .try_set_paused(&this, &paused)
+                .into_shared();
+            match result {
+                Ok(()) => events.publish(
+                    "guardian",
+                    "contract_paused",
+                    (contract, paused, env.ledger().timestamp()),
+                ),
+                Err(error) => {
+                    events.publish(
+                        "guardian",
+                        "pause_failed",
+                        (
+                            contract.clone(),
+                            paused,
+                            error as u32,
+                            env.ledger().timestamp(),
+                        ),
+                    );
+                    failed.push_back(contract);
+                }
+            }
+        }
+
+        let count = Self::get_contracts(env.clone()).len();
+        events.publish(
+            "guardian",
+            if paused { "paused_all" } else { "unpaused_all" },
+            (
+                caller.clone(),
+                count - failed.len(),
+                failed.len(),
+                env.ledger().timestamp(),
+            ),
+        );
+
+        extend_instance_ttl(env);
+        failed
+    }
+}
+
+// ════════════════════════════════════════════════════════════════════════════
+// Tests
+// ════════════════════════════════════════════════════════════════════════════
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use astro_core_shared::access_control::PAUSER;
+    use astro_fee_distributor::{FeeDistributor, FeeDistributorClient};
+    use astro_treasury::{TreasuryVault, TreasuryVaultClient};
+    use soroban_sdk::testutils::Address as _;
+
+    #[test]
+    fn test_pause_all() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let admin = Address::generate(&env);
+        let responder = Address::generate(&env);
+
+        let guardian_id = env.register(Guardian, ());
+        let guardian = GuardianClient::new(&env, &guardian_id);
+        guardian.initialize(&admin);
+        guardian.grant_role(&GUARDIAN, &responder);
+
+        let treasury_id = env.register(TreasuryVault, ());
+        let treasury = TreasuryVaultClient::new(&env, &treasury_id);
+        treasury.initialize(&admin);
+        treasury.grant_role(&PAUSER, &guardian_id);
+
+        let distributor_id = env.register(FeeDistributor, ());
+        let distributor = FeeDistributorClient::new(&env, &distributor_id);
+        distributor.initialize(
+            &admin,
+            &treasury_id,
+            &Address::generate(&env),
+            &Address::generate(&env),
+        );
+        distributor.grant_role(&PAUSER, &guardian_id);
+
+        guardian.register_contract(&treasury_id);
+        guardian.register_contract(&distributor_id);
+        assert_eq!(
+            guardian.try_register_contract(&treasury_id),
+            Err(Ok(SharedError::InvalidState))
+        );
+
+        // Only the admin and guardians may pull the switch
+        assert_eq!(
+            guardian.try_pause_all(&Address::generate(&env)),
+            Err(Ok(SharedError::Unauthorized))
+        );
+
+        assert!(guardian.pause_all(&responder).is_empty());
+        assert!(treasury.is_paused());
+        assert!(distributor.is_paused());
+        assert!(guardian.pause_status().iter().all(|(_, paused)| paused));
+
+        assert!(guardian.unpause_all(&responder).is_empty());
+        assert!(!treasury.is_paused());
+        assert!(!distributor.is_paused());
+    }
+
+    #[test]
+    fn test_pause_all_reports_failures() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let admin = Address::generate(&env);
+        let guardian_id = env.register(Guardian, ());
+        let guardian = GuardianClient::new(&env, &guardian_id);
+        guardian.initialize(&admin);
+
+        // One contract never granted the guardian `PAUSER`
+        let granted = env.register(TreasuryVault, ());
+        TreasuryVaultClient::new(&env, &granted).initialize(&admin);
+        TreasuryVaultClient::new(&env, &granted).grant_role(&PAUSER, &guardian_id);
+        let forgotten = env.register(TreasuryVault, ());
+        TreasuryVaultClient::new(&env, &forgotten).initialize(&admin);
+
+        guardian.register_contract(&forgotten);
+        guardian.register_contract(&granted);
+
+        // The failure doesn't stop the rest from pausing
+        let failed = guardian.pause_all(&admin);
+        assert_eq!(failed, soroban_sdk::vec![&env, forgotten.clone()]);
+        assert!(TreasuryVaultClient::new(&env, &granted).is_paused());
+        assert!(!TreasuryVaultClient::new(&env, &forgotten).is_paused());
+
+        guardian.unregister_contract(&forgotten);
+        assert_eq!(guardian.get_contracts(), soroban_sdk::vec![&env, granted]);
+        assert_eq!(
+            guardian.try_unregister_contract(&forgotten),
+            Err(Ok(SharedError::InvalidAddress))
+        );
+    }
+}
@@ -0,0 +1,184 @@
+#![no_std]
+#![cfg(not(target_family = "wasm"))]
+
+//! # Protocol Test Harness
+//!
+//! Deploys the core contracts (treasury, fee distributor, staking pool,
+//! liquidity locker) into one test `Env` and wires them together, so
+//! integration tests start from a working protocol instead of repeating the
+//! setup. Built on the deterministic fixtures in
+//! `astro_core_shared::testutils`.
+//!
+//! ## Wiring
+//! - Fee distributor splits 50/30/20 to treasury, staking pool and burn
+//! - Staking pool stakes ASTRO and accepts ASTRO rewards from the distributor
+//! - Treasury and locker report to the fee distributor
+//! - Early-unlock penalties go to the treasury
+//!
+//! ## Usage
+//! ```rust,ignore
+//! let h = ProtocolHarness::new();
+//! let alice = h.funded_user(1_000 * ONE_TOKEN);
+//! h.staking.stake(&alice, &(100 * ONE_TOKEN), &None);
+//! h.collect_fees(&h.astro, 10 * ONE_TOKEN);
+//! h.fee_distributor.distribute(&h.astro.address, &None);
+//! ```
+
+use astro_core_shared::{
+    testutils::{self, TestToken},
+    types::{LockConfig, StakingConfig},
+};
+use astro_fee_distributor::{FeeDistributor, FeeDistributorClient};
+use astro_locker::{LiquidityLocker, LiquidityLockerClient};
+use astro_staking::{StakingPool, StakingPoolClient};
+use astro_treasury::{TreasuryVault, TreasuryVaultClient};
+use soroban_sdk::{Address, Env};
+
+pub use astro_core_shared::testutils::{
+    default_lock_config, default_staking_config, GENESIS_TIMESTAMP, ONE_TOKEN,
+};
+
+/// The core protocol deployed and wired in a test `Env`
+pub struct ProtocolHarness<'a> {
+    /// Env with every auth mocked
+    pub env: Env,
+    /// Admin of every contract and issuer of every harness token
+    pub admin: Address,
+    /// Burn recipient of the fee split
+    pub burn: Address,
+    /// Stake and reward token
+    pub astro: TestToken<'a>,
+    /// Treasury vault
+    pub treasury: TreasuryVaultClient<'a>,
+    /// Fee distributor
+    pub fee_distributor: FeeDistributorClient<'a>,
+    /// ASTRO staking pool
+    pub staking: StakingPoolClient<'a>,
+    /// Liquidity locker
+    pub locker: LiquidityLockerClient<'a>,
+}
+
+impl<'a> ProtocolHarness<'a> {
+    /// Deploy with the default staking and lock configs
+    pub fn new() -> Self {
+        Self::with_configs(default_staking_config(), default_lock_config())
+    }
+
+    /// Deploy with custom staking and lock configs
+    pub fn with_configs(staking_config: StakingConfig, lock_config: LockConfig) -> Self {
+        let env = testutils::env();
+        let admin = testutils::user(&env);
+        let burn = testutils::user(&env);
+        let astro = testutils::create_token(&env, &admin);
+
+        let treasury = TreasuryVaultClient::new(&env, &env.register(TreasuryVault, ()));
+        let fee_distributor = FeeDistributorClient::new(&env, &env.register(FeeDistributor, ()));
+        let staking = StakingPoolClient::new(&env, &env.register(StakingPool, ()));
+        let locker = LiquidityLockerClient::new(&env, &env.register(LiquidityLocker, ()));
+
+        treasury.initialize(&admin);
+        fee_distributor.initialize(&admin, &treasury.address, &staking.address, &burn);
+        staking.initialize(
+            &admin,
+            &astro.address,
+            &fee_distributor.address,
+            &staking_config,
+        );
+        locker.initialize(&admin, &treasury.address, &lock_config);
+
+        treasury.set_fee_distributor(&fee_distributor.address);
+        locker.set_fee_distributor(&fee_distributor.address);
+        staking.allow_reward_token(&astro.address);
+
+        Self {
+            env,
+            admin,
+            burn,
+            astro,
+            treasury,
+            fee_distributor,
+            staking,
+            locker,
+        }
+    }
+
+    /// A fresh account
+    pub fn user(&self) -> Address {
+        testutils::user(&self.env)
+    }
+
+    /// A fresh account holding `amount` ASTRO
+    pub fn funded_user(&self, amount: i128) -> Address {
+        let user = self.user();
+        self.astro.mint(&user, amount);
+        user
+    }
+
+    /// Deploy another token issued by the admin
+    pub fn token(&self) -> TestToken<'a> {
+        testutils::create_token(&self.env, &self.admin)
+    }
+
+    /// Move the ledger clock forward
+    pub fn advance_time(&self, secs: u64) {
+        testutils::advance_time(&self.env, secs);
+    }
+
+    /// Mint `amount` of `token` to a fee payer and hand it to the fee
+    /// distributor, as a pair's swap fee would be
+    pub fn collect_fees(&self, token: &TestToken, amount: i128) {
+        let payer = self.user();
+        token.mint(&payer, amount);
+        self.fee_distributor
+            .receive_fees(&payer, &token.address, &amount);
+    }
+}
+
+impl Default for ProtocolHarness<'_> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fees_reach_treasury_stakers_and_burn() {
+        let h = ProtocolHarness::new();
+        let alice = h.funded_user(1_000 * ONE_TOKEN);
+        h.staking.stake(&alice, &(100 * ONE_TOKEN), &None);
+
+        h.collect_fees(&h.astro, 10 * ONE_TOKEN);
+        let result = h.fee_distributor.distribute(&h.astro.address, &None);
+
+        assert_eq!(result.treasury_amount, 5 * ONE_TOKEN);
+        assert_eq!(result.staking_amount, 3 * ONE_TOKEN);
+        assert_eq!(result.burn_amount, 2 * ONE_TOKEN);
+        assert_eq!(h.treasury.balance(&h.astro.address), 5 * ONE_TOKEN);
+        assert_eq!(h.astro.balance(&h.burn), 2 * ONE_TOKEN);
+
+        h.staking.claim(&alice);
+        assert_eq!(h.astro.balance(&alice), 903 * ONE_TOKEN);
+    }
+
+    #[test]
+    fn test_early_unlock_penalty_reaches_treasury() {
+        let h = ProtocolHarness::new();
+        let lp = h.token();
+        let owner = h.user();
+        lp.mint(&owner, 100 * ONE_TOKEN);
+
+        let lock_id = h.locker.lock(
+            &owner,
+            &lp.address,
+            &(100 * ONE_TOKEN),
+            &(GENESIS_TIMESTAMP + 30 * 86_400),
+        );
+
+        // 25% default penalty
+        assert_eq!(h.locker.early_unlock(&owner, &lock_id), 75 * ONE_TOKEN);
+        assert_eq!(lp.balance(&h.treasury.address), 25 * ONE_TOKEN);
+    }
+}
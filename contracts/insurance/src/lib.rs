@@ -0,0 +1,616 @@
+#![no_std]
+
+//! # Insurance Contract
+//!
+//! Insurance fund fed by the fee distributor, paying out on approved
+//! incidents.
+//!
+//! ## Features
+//! - Funded by an `Insurance` recipient in the fee distributor (plain
+//!   transfers) or by direct deposits
+//! - Payouts only against an open incident, each with its own cap
+//! - Incident caps limited to a share of the fund balance
+//! - Coverage ratio of the fund against the value it covers
+//!
+//! The admin is expected to be the governance contract, so opening an
+//! incident and every payout go through a proposal.
+
+use astro_core_shared::{
+    events::EventBuilder,
+    math::{apply_bps, mul_div_down, safe_add, BPS_DENOMINATOR},
+    types::{
+        extend_instance_ttl, get_persistent, get_ttl_config, set_persistent, set_ttl_config,
+        ContractInfo, SharedError, TtlConfig,
+    },
+    upgrade::{self, PendingUpgrade},
+};
+use soroban_sdk::{contract, contractimpl, contracttype, token, Address, BytesN, Env, String};
+
+// ════════════════════════════════════════════════════════════════════════════
+// Constants
+// ════════════════════════════════════════════════════════════════════════════
+
+/// Storage layout version of this code (see `migrate`)
+const STORAGE_VERSION: u32 = 1;
+
+/// Default cap of one incident as a share of the fund balance (25%)
+const DEFAULT_MAX_INCIDENT_BPS: u32 = 2_500;
+
+/// Maximum incident description length
+const MAX_DESCRIPTION_LENGTH: u32 = 256;
+
+// ════════════════════════════════════════════════════════════════════════════
+// Storage Keys
+// ════════════════════════════════════════════════════════════════════════════
+
+#[contracttype]
+#[derive(Clone, Debug)]
+pub enum DataKey {
+    /// Admin address
+    Admin,
+    /// Whether contract is initialized
+    Initialized,
+    /// Whether contract is paused
+    Paused,
+    /// Maximum incident cap as bps of the fund balance
+    MaxIncidentBps,
+    /// Number of incidents opened (next incident id)
+    IncidentCount,
+    /// Incident by id (u64 -> Incident)
+    Incident(u64),
+    /// Value covered by the fund per token (Address -> i128)
+    CoveredValue(Address),
+    /// Cumulative payouts per token (Address -> i128)
+    TotalPaidOut(Address),
+    /// Protocol registry address (reported by `contract_info`)
+    Registry,
+}
+
+// ════════════════════════════════════════════════════════════════════════════
+// Types
+// ════════════════════════════════════════════════════════════════════════════
+
+/// An insured incident
+#[contracttype]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Incident {
+    /// Incident id
+    pub id: u64,
+    /// Token paid out for the incident
+    pub token: Address,
+    /// Maximum total payout
+    pub cap: i128,
+    /// Paid out so far
+    pub paid: i128,
+    /// Short description (post-mortem link, affected pool, ...)
+    pub description: String,
+    /// Opening timestamp
+    pub opened_at: u64,
+    /// Whether payouts are still allowed
+    pub open: bool,
+}
+
+// ════════════════════════════════════════════════════════════════════════════
+// Contract Implementation
+// ════════════════════════════════════════════════════════════════════════════
+
+#[contract]
+pub struct InsuranceFund;
+
+#[contractimpl]
+impl InsuranceFund {
+    // ────────────────────────────────────────────────────────────────────────
+    // Initialization
+    // ────────────────────────────────────────────────────────────────────────
+
+    /// Initialize the insurance fund
+    pub fn initialize(env: Env, admin: Address) -> Result<(), SharedError> {
+        if env.storage().instance().has(&DataKey::Initialized) {
+            return Err(SharedError::AlreadyInitialized);
+        }
+
+        env.storage().instance().set(&DataKey::Admin, &admin);
+        env.storage()
+            .instance()
+            .set(&DataKey::MaxIncidentBps, &DEFAULT_MAX_INCIDENT_BPS);
+        env.storage()
+            .instance()
+            .set(&DataKey::IncidentCount, &0_u64);
+        env.storage().instance().set(&DataKey::Initialized, &true);
+        upgrade::init_version(&env, STORAGE_VERSION);
+        env.storage().instance().set(&DataKey::Paused, &false);
+
+        extend_instance_ttl(&env);
+
+        let events = EventBuilder::new(&env);
+        events.publish(
+            "insurance",
+            "initialized",
+            (admin, env.ledger().timestamp()),
+        );
+
+        Ok(())
+    }
+
+    // ────────────────────────────────────────────────────────────────────────
+    // Deposits
+    // ────────────────────────────────────────────────────────────────────────
+
+    /// Top up the fund directly
+    pub fn deposit(
+        env: Env,
+        from: Address,
+        token: Address,
+        amount: i128,
+    ) -> Result<(), SharedError> {
+        Self::require_initialized(&env)?;
+        from.require_auth();
+
+        if amount <= 0 {
+            return Err(SharedError::InvalidAmount);
+        }
+
+        token::Client::new(&env, &token).transfer(&from, env.current_contract_address(), &amount);
+
+        let events = EventBuilder::new(&env);
+        events.publish(
+            "insurance",
+            "deposit",
+            (from, token, amount, env.ledger().timestamp()),
+        );
+
+        extend_instance_ttl(&env);
+        Ok(())
+    }
+
+    // ────────────────────────────────────────────────────────────────────────
+    // Incidents (admin only)
+    // ────────────────────────────────────────────────────────────────────────
+
+    /// Open an incident that may pay out up to `cap` of `token`.
+    /// Returns the incident id.
+    pub fn open_incident(
+        env: Env,
+        token: Address,
+        cap: i128,
+        description: String,
+    ) -> Result<u64, SharedError> {
+        Self::require_admin(&env)?;
+
+        if cap <= 0 {
+            return Err(SharedError::InvalidAmount);
+        }
+        if description.len() > MAX_DESCRIPTION_LENGTH {
+            return Err(SharedError::LimitExceeded);
+        }
+        let max_cap = apply_bps(
+            Self::balance(env.clone(), token.clone()),
+            Self::max_incident_bps(env.clone()),
+        )?;
+        if cap > max_cap {
+            return Err(SharedError::AmountExceedsMax);
+        }
+
+        let id: u64 = Self::incident_count(env.clone());
+        env.storage()
+            .instance()
+            .set(&DataKey::IncidentCount, &(id + 1));
+
+        let incident = Incident {
+            id,
+            token: token.clone(),
+            cap,
+            paid: 0,
+            description,
+            opened_at: env.ledger().timestamp(),
+            open: true,
+        };
+        Self::save_incident(&env, &incident);
+
+        let events = EventBuilder::new(&env);
+        events.publish(
+            "insurance",
+            "incident_opened",
+            (id, token, cap, env.ledger().timestamp()),
+        );
+
+        extend_instance_ttl(&env);
+        Ok(id)
+    }
+
+    /// Pay `amount` to `to` against an open incident
+    pub fn payout(
+        env: Env,
+        incident_id: u64,
+        to: Address,
+        amount: i128,
+    ) -> Result<(), SharedError> {
+        Self::require_admin(&env)?;
+        Self::require_not_paused(&env)?;
+
+        if amount <= 0 {
+            return Err(SharedError::InvalidAmount);
+        }
+
+        let mut incident = Self::load_open_incident(&env, incident_id)?;
+        let paid = safe_add(incident.paid, amount)?;
+        if paid > incident.cap {
+            return Err(SharedError::AmountExceedsMax);
+        }
+        incident.paid = paid;
+        Self::save_incident(&env, &incident);
+
+        let key = DataKey::TotalPaidOut(incident.token.clone());
+        let total: i128 = get_persistent(&env, &key).unwrap_or(0);
+        set_persistent(&env, &key, &safe_add(total, amount)?);
+
+        token::Client::new(&env, &incident.token).transfer(
+            &env.current_contract_address(),
+            &to,
+            &amount,
+        );
+
+        let events = EventBuilder::new(&env);
+        events.publish(
+            "insurance",
+            "payout",
+            (
+                incident_id,
+                to,
+                incident.token,
+                amount,
+                env.ledger().timestamp(),
+            ),
+        );
+
+        extend_instance_ttl(&env);
+        Ok(())
+    }
+
+    /// Close an incident; no further payouts are possible
+    pub fn close_incident(env: Env, incident_id: u64) -> Result<(), SharedError> {
+        Self::require_admin(&env)?;
+
+        let mut incident = Self::load_open_incident(&env, incident_id)?;
+        incident.open = false;
+        Self::save_incident(&env, &incident);
+
+        let events = EventBuilder::new(&env);
+        events.publish(
+            "insurance",
+            "incident_closed",
+            (incident_id, incident.paid, env.ledger().timestamp()),
+        );
+
+        extend_instance_ttl(&env);
+        Ok(())
+    }
+
+    // ────────────────────────────────────────────────────────────────────────
+    // Admin Functions
+    // ────────────────────────────────────────────────────────────────────────
+
+    /// Set the value covered by the fund for a token (denominator of the
+    /// coverage ratio)
+    pub fn set_covered_value(env: Env, token: Address, value: i128) -> Result<(), SharedError> {
+        Self::require_admin(&env)?;
+
+        if value < 0 {
+            return Err(SharedError::InvalidAmount);
+        }
+        let key = DataKey::CoveredValue(token.clone());
+        set_persistent(&env, &key, &value);
+
+        let events = EventBuilder::new(&env);
+        events.publish(
+            "insurance",
+            "covered_value_set",
+            (token, value, env.ledger().timestamp()),
+        );
+
+        extend_instance_ttl(&env);
+        Ok(())
+    }
+
+    /// Set the maximum incident cap as bps of the fund balance
+    pub fn set_max_incident_bps(env: Env, bps: u32) -> Result<(), SharedError> {
+        Self::require_admin(&env)?;
+
+        if bps == 0 || bps as i128 > BPS_DENOMINATOR {
+            return Err(SharedError::InvalidBps);
+        }
+        env.storage().instance().set(&DataKey::MaxIncidentBps, &bps);
+        extend_instance_ttl(&env);
+
+        Ok(())
+    }
+
+    /// Set the protocol registry address reported by `contract_info`
+    pub fn set_registry(env: Env, registry: Address) -> Result<(), SharedError> {
+        Self::require_admin(&env)?;
+        env.storage().instance().set(&DataKey::Registry, &registry);
+        extend_instance_ttl(&env);
+        Ok(())
+    }
+
+    /// Set admin address
+    pub fn set_admin(env: Env, new_admin: Address) -> Result<(), SharedError> {
+        Self::require_admin(&env)?;
+
+        env.storage().instance().set(&DataKey::Admin, &new_admin);
+        extend_instance_ttl(&env);
+
+        Ok(())
+    }
+
+    /// Pause/unpause payouts
+    pub fn set_paused(env: Env, paused: bool) -> Result<(), SharedError> {
+        Self::require_admin(&env)?;
+
+        env.storage().instance().set(&DataKey::Paused, &paused);
+        extend_instance_ttl(&env);
+
+        Ok(())
+    }
+
+    /// Set how far storage TTLs are extended (see `TtlConfig`)
+    pub fn set_ttl_config(env: Env, config: TtlConfig) -> Result<(), SharedError> {
+        Self::require_admin(&env)?;
+
+        set_ttl_config(&env, &config)?;
+        extend_instance_ttl(&env);
+
+        Ok(())
+    }
+
+    // ────────────────────────────────────────────────────────────────────────
+    // Upgrades (admin only)
+    // ────────────────────────────────────────────────────────────────────────
+
+    /// Queue a WASM upgrade behind the upgrade timelock. Returns its eta.
+    pub fn queue_upgrade(env: Env, new_wasm_hash: BytesN<32>) -> Result<u64, SharedError> {
+        Self::require_admin(&env)?;
+        upgrade::queue(&env, new_wasm_hash)
+    }
+
+    /// Cancel the queued upgrade
+    pub fn cancel_upgrade(env: Env) -> Result<(), SharedError> {
+        Self::require_admin(&env)?;
+        upgrade::cancel(&env)
+    }
+
+    /// Switch to the queued WASM once the timelock has elapsed
+    pub fn upgrade(env: Env, new_wasm_hash: BytesN<32>) -> Result<(), SharedError> {
+        Self::require_admin(&env)?;
+        upgrade::execute(&env, new_wasm_hash)
+    }
+
+    /// Migrate storage to `STORAGE_VERSION` after an upgrade
+    pub fn migrate(env: Env) -> Result<u32, SharedError> {
+        Self::require_admin(&env)?;
+        upgrade::migrate(&env, STORAGE_VERSION, |_, _| Ok(()))
+    }
+
+    // ────────────────────────────────────────────────────────────────────────
+    // Query Functions
+    // ────────────────────────────────────────────────────────────────────────
+
+    /// Get the storage layout version
+    pub fn version(env: Env) -> u32 {
+        upgrade::version(&env)
+    }
+
+    /// Get the queued upgrade, if any
+    pub fn get_pending_upgrade(env: Env) -> Option<PendingUpgrade> {
+        upgrade::pending(&env)
+    }
+
+    /// Get the fund balance of a token
+    pub fn balance(env: Env, token: Address) -> i128 {
+        token::Client::new(&env, &token).balance(&env.current_contract_address())
+    }
+
+    /// Get the fund balance as bps of the covered value (capped at 10,000;
+    /// 0 if no covered value is set)
+    pub fn coverage_ratio(env: Env, token: Address) -> Result<u32, SharedError> {
+        let covered = Self::covered_value(env.clone(), token.clone());
+        if covered == 0 {
+            return Ok(0);
+        }
+        let ratio = mul_div_down(Self::balance(env, token), BPS_DENOMINATOR, covered)?;
+        Ok(ratio.min(BPS_DENOMINATOR) as u32)
+    }
+
+    /// Get the value covered for a token
+    pub fn covered_value(env: Env, token: Address) -> i128 {
+        get_persistent(&env, &DataKey::CoveredValue(token)).unwrap_or(0)
+    }
+
+    /// Get an incident
+    pub fn get_incident(env: Env, incident_id: u64) -> Option<Incident> {
+        get_persistent(&env, &DataKey::Incident(incident_id))
+    }
+
+    /// Get the number of incidents opened
+    pub fn incident_count(env: Env) -> u64 {
+        env.storage()
+            .instance()
+            .get(&DataKey::IncidentCount)
+            .unwrap_or(0)
+    }
+
+    /// Get the cumulative payouts of a token
+    pub fn get_total_paid_out(env: Env, token: Address) -> i128 {
+        get_persistent(&env, &DataKey::TotalPaidOut(token)).unwrap_or(0)
+    }
+
+    /// Get the maximum incident cap as bps of the fund balance
+    pub fn max_incident_bps(env: Env) -> u32 {
+        env.storage()
+            .instance()
+            .get(&DataKey::MaxIncidentBps)
+            .unwrap_or(DEFAULT_MAX_INCIDENT_BPS)
+    }
+
+    /// Get admin address
+    pub fn admin(env: Env) -> Result<Address, SharedError> {
+        env.storage()
+            .instance()
+            .get(&DataKey::Admin)
+            .ok_or(SharedError::NotInitialized)
+    }
+
+    /// Get the storage TTL config
+    pub fn ttl_config(env: Env) -> TtlConfig {
+        get_ttl_config(&env)
+    }
+
+    /// Check if contract is paused
+    pub fn is_paused(env: Env) -> bool {
+        env.storage()
+            .instance()
+            .get(&DataKey::Paused)
+            .unwrap_or(false)
+    }
+
+    /// Describe this contract for explorers and wallets
+    pub fn contract_info(env: Env) -> Result<ContractInfo, SharedError> {
+        let admin = Self::admin(env.clone())?;
+        let info = ContractInfo::new(
+            &env,
+            "insurance",
+            env!("CARGO_PKG_VERSION"),
+            admin,
+            Self::is_paused(env.clone()),
+            env.storage().instance().get(&DataKey::Registry),
+        );
+        Ok(info
+            .with(
+                "max_incident_bps",
+                Self::max_incident_bps(env.clone()) as i128,
+            )
+            .with("incident_count", Self::incident_count(env) as i128))
+    }
+
+    // ────────────────────────────────────────────────────────────────────────
+    // Internal Functions
+    // ────────────────────────────────────────────────────────────────────────
+
+    fn require_initialized(env: &Env) -> Result<(), SharedError> {
+        let initialized: bool = env
+            .storage()
+            .instance()
+            .get(&DataKey::Initialized)
+            .unwrap_or(false);
+
+        if !initialized {
+            return Err(SharedError::NotInitialized);
+        }
+        Ok(())
+    }
+
+    fn require_not_paused(env: &Env) -> Result<(), SharedError> {
+        let paused: bool = env
+            .storage()
+            .instance()
+            .get(&DataKey::Paused)
+            .unwrap_or(false);
+
+        if paused {
+            return Err(SharedError::ContractPaused);
+        }
+        Ok(())
+    }
+
+    fn require_admin(env: &Env) -> Result<(), SharedError> {
+        let admin: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::Admin)
+            .ok_or(SharedError::NotInitialized)?;
+
+        admin.require_auth();
+        Ok(())
+    }
+
+    fn load_open_incident(env: &Env, incident_id: u64) -> Result<Incident, SharedError> {
+        let incident: Incident = get_persistent(env, &DataKey::Incident(incident_id))
+            .ok_or(SharedError::InvalidState)?;
+        if !incident.open {
+            return Err(SharedError::AlreadyExecuted);
+        }
+        Ok(incident)
+    }
+
+    fn save_incident(env: &Env, incident: &Incident) {
+        let key = DataKey::Incident(incident.id);
+        set_persistent(env, &key, incident);
+    }
+}
+
+// ════════════════════════════════════════════════════════════════════════════
+// Tests
+// ════════════════════════════════════════════════════════════════════════════
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use astro_core_shared::testutils::create_token;
+    use soroban_sdk::testutils::Address as _;
+
+    #[test]
+    fn test_incident_payouts() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let admin = Address::generate(&env);
+        let token = create_token(&env, &admin);
+        let client = InsuranceFundClient::new(&env, &env.register(InsuranceFund, ()));
+        client.initialize(&admin);
+
+        // Fed by the fee distributor with plain transfers
+        token.mint(&client.address, 1_000_000);
+        assert_eq!(client.balance(&token.address), 1_000_000);
+
+        // Caps are limited to 25% of the balance
+        let description = String::from_str(&env, "pair exploit");
+        assert!(client
+            .try_open_incident(&token.address, &250_001, &description)
+            .is_err());
+        let id = client.open_incident(&token.address, &200_000, &description);
+
+        let victim = Address::generate(&env);
+        client.payout(&id, &victim, &150_000);
+        assert!(client.try_payout(&id, &victim, &50_001).is_err());
+        client.payout(&id, &victim, &50_000);
+        assert_eq!(token.balance(&victim), 200_000);
+        assert_eq!(client.get_total_paid_out(&token.address), 200_000);
+
+        client.close_incident(&id);
+        assert!(!client.get_incident(&id).unwrap().open);
+        assert!(client.try_payout(&id, &victim, &1).is_err());
+    }
+
+    #[test]
+    fn test_coverage_ratio() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let admin = Address::generate(&env);
+        let depositor = Address::generate(&env);
+        let token = create_token(&env, &admin);
+        token.mint(&depositor, 1_000_000);
+        let client = InsuranceFundClient::new(&env, &env.register(InsuranceFund, ()));
+        client.initialize(&admin);
+
+        assert_eq!(client.coverage_ratio(&token.address), 0);
+        client.deposit(&depositor, &token.address, &50_000);
+        client.set_covered_value(&token.address, &1_000_000);
+        assert_eq!(client.coverage_ratio(&token.address), 500);
+
+        // Over-collateralized funds report full coverage
+        client.deposit(&depositor, &token.address, &950_000);
+        client.set_covered_value(&token.address, &800_000);
+        assert_eq!(client.coverage_ratio(&token.address), 10_000);
+    }
+}
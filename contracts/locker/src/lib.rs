@@ -11,21 +11,62 @@
 //! - Multiple locks per user
 //! - Lock extensions
 //! - Lock transfers (ownership)
+//! - Optional public-goods donation at lock creation
+//! - Unlock schedule per LP token (indexed by unlock month)
 
 use astro_core_shared::{
-    events::{emit_lock, emit_unlock, EventBuilder},
-    math::{apply_bps, safe_add, safe_sub},
-    types::{extend_instance_ttl, LockConfig, LockInfo, SharedError},
+    access_control::{self, CONFIG_ADMIN, PAUSER, UPGRADER},
+    events::{
+        emit_admin_changed, emit_config_updated, emit_lock, emit_lock_extended,
+        emit_lock_transferred, emit_penalty_routed, emit_treasury_changed, emit_unlock,
+        EventBuilder,
+    },
+    interfaces::{AmmPairClient, FeeDistributorClient, IntoSharedResult},
+    limits::{validate_amount, MIN_LOCK_AMOUNT, NO_MAX},
+    math::{apply_bps, apply_bps_round_up, safe_add, safe_sub},
+    ownership,
+    pausable::{self, PauseConfig, PauseGroup},
+    signature,
+    types::{
+        extend_instance_ttl, get_persistent, get_ttl_config, set_persistent, set_ttl_config, Bps,
+        BundleLockInfo, ContractInfo, LockConfig, LockInfo, PenaltyRouting, SharedError,
+        TokenSolvency, TtlConfig,
+    },
+    upgrade::{self, PendingUpgrade},
+};
+use soroban_sdk::{
+    auth::{ContractContext, InvokerContractAuthEntry, SubContractInvocation},
+    contract, contractimpl, contracttype, token, vec, Address, BytesN, Env, IntoVal, Symbol, Vec,
 };
-use soroban_sdk::{contract, contractimpl, contracttype, token, Address, Env, Vec};
 
 // ════════════════════════════════════════════════════════════════════════════
 // Constants
 // ════════════════════════════════════════════════════════════════════════════
 
+/// Storage layout version of this code (see `migrate`)
+const STORAGE_VERSION: u32 = 5;
+
 /// Maximum locks per user to prevent DoS (FIX #M4)
 const MAX_LOCKS_PER_USER: u32 = 1000;
 
+/// Number of past pause windows retained for unlock-buffer grace checks
+const MAX_PAUSE_WINDOWS: u32 = 20;
+
+/// Maximum number of tokens in a bundle lock
+const MAX_BUNDLE_ASSETS: u32 = 5;
+
+/// Maximum early unlock penalty (50%)
+const MAX_PENALTY: Bps = Bps::from_const(5000);
+
+/// Maximum share of a lock that can be donated (50%)
+const MAX_DONATION_BPS: u32 = 5000;
+
+/// Bucket width of the unlock index (30 days)
+const UNLOCK_MONTH: u64 = 30 * 24 * 60 * 60;
+
+/// Maximum index months scanned by `get_unlock_schedule` (~5 years)
+const MAX_SCHEDULE_MONTHS: u64 = 60;
+
 // ════════════════════════════════════════════════════════════════════════════
 // Storage Keys
 // ════════════════════════════════════════════════════════════════════════════
@@ -39,7 +80,7 @@ pub enum DataKey {
     Config,
     /// Whether contract is initialized
     Initialized,
-    /// Whether contract is paused
+    /// Legacy pause flag (storage version 1, see `migrate`)
     Paused,
     /// Next lock ID counter
     NextLockId,
@@ -51,8 +92,88 @@ pub enum DataKey {
     TokenLocks(Address),
     /// Total locked per token (Address -> i128)
     TotalLocked(Address),
+    /// LP tokens that have been locked (Vec<Address>)
+    LockedTokens,
+    /// Contract allowed to lock on behalf of others (Address -> bool)
+    ApprovedLocker(Address),
+    /// Unlocked locks dropped from a user's index (Address -> u32)
+    ArchivedCount(Address),
     /// Treasury for penalty fees
     Treasury,
+    /// Timestamp the current pause started
+    PausedAt,
+    /// Recent completed pause windows (Vec<PauseWindow>)
+    PauseWindows,
+    /// Public-goods address receiving lock donations
+    PublicGoods,
+    /// Total donated per token (Address -> i128)
+    TotalDonated(Address),
+    /// Protocol registry address (reported by `contract_info`)
+    Registry,
+    /// Timed locks per LP token by unlock month ((Address, u64) -> Vec<u64>)
+    UnlockMonth(Address, u64),
+    /// Bundle lock by ID (u64 -> BundleLockInfo)
+    BundleLock(u64),
+    /// User's bundle lock IDs (Address -> Vec<u64>)
+    UserBundleLocks(Address),
+    /// Fee distributor receiving the stakers' share of penalties
+    FeeDistributor,
+    /// Fallback destination of the burned share of penalties
+    BurnAddress,
+    /// Committed lock seconds plus bonus points per user (Address -> u64)
+    LoyaltyScore(Address),
+    /// Bonus on extensions and re-locks, as a share of the added seconds (Bps)
+    LoyaltyBonus,
+}
+
+// ════════════════════════════════════════════════════════════════════════════
+// Types
+// ════════════════════════════════════════════════════════════════════════════
+
+/// `LockConfig` as stored before storage version 3 (plain `u32` penalty)
+#[contracttype]
+#[derive(Clone, Debug)]
+struct LegacyLockConfig {
+    min_lock_duration: u64,
+    max_lock_duration: u64,
+    early_unlock_enabled: bool,
+    early_unlock_penalty_bps: u32,
+    unlock_buffer: u64,
+}
+
+/// `LockConfig` as stored in version 3 (whole penalty to the treasury)
+#[contracttype]
+#[derive(Clone, Debug)]
+struct LockConfigV3 {
+    min_lock_duration: u64,
+    max_lock_duration: u64,
+    early_unlock_enabled: bool,
+    early_unlock_penalty_bps: Bps,
+    unlock_buffer: u64,
+}
+
+/// `LockConfig` as stored in version 4 (stakers and burn shares, treasury
+/// takes the rest)
+#[contracttype]
+#[derive(Clone, Debug)]
+struct LockConfigV4 {
+    min_lock_duration: u64,
+    max_lock_duration: u64,
+    early_unlock_enabled: bool,
+    early_unlock_penalty_bps: Bps,
+    penalty_stakers_bps: Bps,
+    penalty_burn_bps: Bps,
+    unlock_buffer: u64,
+}
+
+/// A completed period during which the locker was paused
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PauseWindow {
+    /// When the pause started
+    pub start: u64,
+    /// When the contract was unpaused
+    pub end: u64,
 }
 
 // ════════════════════════════════════════════════════════════════════════════
@@ -80,22 +201,14 @@ impl LiquidityLocker {
             return Err(SharedError::AlreadyInitialized);
         }
 
-        // Validate config
-        if config.min_lock_duration > config.max_lock_duration {
-            return Err(SharedError::InvalidTimestamp);
-        }
-
-        if config.early_unlock_penalty_bps > 5000 {
-            // Max 50% penalty
-            return Err(SharedError::InvalidBps);
-        }
+        Self::validate_config(&env, &config)?;
 
         // Store initial state
         env.storage().instance().set(&DataKey::Admin, &admin);
         env.storage().instance().set(&DataKey::Treasury, &treasury);
         env.storage().instance().set(&DataKey::Config, &config);
         env.storage().instance().set(&DataKey::Initialized, &true);
-        env.storage().instance().set(&DataKey::Paused, &false);
+        upgrade::init_version(&env, STORAGE_VERSION);
         env.storage().instance().set(&DataKey::NextLockId, &1_u64);
 
         extend_instance_ttl(&env);
@@ -124,81 +237,79 @@ impl LiquidityLocker {
     ) -> Result<u64, SharedError> {
         owner.require_auth();
         Self::require_initialized(&env)?;
-        Self::require_not_paused(&env)?;
+        pausable::require_not_paused(&env, PauseGroup::Deposits)?;
 
-        if amount <= 0 {
-            return Err(SharedError::InvalidAmount);
-        }
-
-        let config: LockConfig = env
-            .storage()
-            .instance()
-            .get(&DataKey::Config)
-            .ok_or(SharedError::NotInitialized)?;
-
-        let current_time = env.ledger().timestamp();
-        let lock_duration = unlock_time.saturating_sub(current_time);
+        Self::create_lock(&env, &owner, &owner, &lp_token, amount, unlock_time, 0)
+    }
 
-        // Validate lock duration
-        if lock_duration < config.min_lock_duration {
-            return Err(SharedError::InvalidTimestamp);
-        }
+    /// Lock LP tokens pulled from an approved locker (e.g. the bridge or
+    /// token factory during graduation) on behalf of `beneficiary`.
+    /// Without a beneficiary the lock is protocol-owned (held by the treasury).
+    pub fn lock_for(
+        env: Env,
+        caller: Address,
+        beneficiary: Option<Address>,
+        lp_token: Address,
+        amount: i128,
+        unlock_time: u64,
+    ) -> Result<u64, SharedError> {
+        caller.require_auth();
+        Self::require_initialized(&env)?;
+        pausable::require_not_paused(&env, PauseGroup::Deposits)?;
 
-        if lock_duration > config.max_lock_duration && config.max_lock_duration > 0 {
-            return Err(SharedError::InvalidTimestamp);
+        if !Self::is_approved_locker(env.clone(), caller.clone()) {
+            return Err(SharedError::Unauthorized);
         }
 
-        // Transfer LP tokens to contract
-        let token_client = token::Client::new(&env, &lp_token);
-        token_client.transfer(&owner, &env.current_contract_address(), &amount);
-
-        // Create lock
-        let lock_id: u64 = env
-            .storage()
-            .instance()
-            .get(&DataKey::NextLockId)
-            .unwrap_or(1);
-
-        let lock_info = LockInfo {
-            id: lock_id,
-            owner: owner.clone(),
-            lp_token: lp_token.clone(),
-            amount,
-            lock_time: current_time,
-            unlock_time,
-            unlocked: false,
+        let protocol_owned = beneficiary.is_none();
+        let owner = match beneficiary {
+            Some(beneficiary) => beneficiary,
+            None => env
+                .storage()
+                .instance()
+                .get(&DataKey::Treasury)
+                .ok_or(SharedError::ExternalContractNotSet)?,
         };
 
-        // Store lock
-        env.storage()
-            .persistent()
-            .set(&DataKey::Lock(lock_id), &lock_info);
-
-        // Extend TTL for long-term locks (VULN #H2 fix)
-        Self::extend_lock_ttl(&env, lock_id, &lock_info);
-
-        // Update user's lock list
-        Self::add_lock_to_user(&env, &owner, lock_id)?;
+        let lock_id = Self::create_lock(&env, &caller, &owner, &lp_token, amount, unlock_time, 0)?;
 
-        // Update token's lock list
-        Self::add_lock_to_token(&env, &lp_token, lock_id);
+        let events = EventBuilder::new(&env);
+        events.publish(
+            "locker",
+            "lock_for",
+            (lock_id, caller, owner, lp_token, amount, protocol_owned),
+        );
 
-        // Update total locked
-        let total = Self::get_total_locked(&env, &lp_token);
-        let new_total = safe_add(total, amount)?;
-        env.storage()
-            .persistent()
-            .set(&DataKey::TotalLocked(lp_token.clone()), &new_total);
+        Ok(lock_id)
+    }
 
-        // Increment lock ID
-        env.storage()
-            .instance()
-            .set(&DataKey::NextLockId, &(lock_id + 1));
+    /// Lock LP tokens, donating `donation_bps` of `amount` to the configured
+    /// public-goods address. The remainder is locked.
+    pub fn lock_with_donation(
+        env: Env,
+        owner: Address,
+        lp_token: Address,
+        amount: i128,
+        unlock_time: u64,
+        donation_bps: u32,
+    ) -> Result<u64, SharedError> {
+        owner.require_auth();
+        Self::require_initialized(&env)?;
+        pausable::require_not_paused(&env, PauseGroup::Deposits)?;
 
-        emit_lock(&env, lock_id, &owner, &lp_token, amount, unlock_time);
-        extend_instance_ttl(&env);
+        if donation_bps == 0 || donation_bps > MAX_DONATION_BPS {
+            return Err(SharedError::InvalidBps);
+        }
 
-        Ok(lock_id)
+        Self::create_lock(
+            &env,
+            &owner,
+            &owner,
+            &lp_token,
+            amount,
+            unlock_time,
+            donation_bps,
+        )
     }
 
     /// Permanent lock (burn) - cannot be unlocked
@@ -210,7 +321,7 @@ impl LiquidityLocker {
     ) -> Result<u64, SharedError> {
         owner.require_auth();
         Self::require_initialized(&env)?;
-        Self::require_not_paused(&env)?;
+        pausable::require_not_paused(&env, PauseGroup::Deposits)?;
 
         if amount <= 0 {
             return Err(SharedError::InvalidAmount);
@@ -218,7 +329,7 @@ impl LiquidityLocker {
 
         // Transfer LP tokens to contract
         let token_client = token::Client::new(&env, &lp_token);
-        token_client.transfer(&owner, &env.current_contract_address(), &amount);
+        token_client.transfer(&owner, env.current_contract_address(), &amount);
 
         let current_time = env.ledger().timestamp();
         let lock_id: u64 = env
@@ -238,9 +349,7 @@ impl LiquidityLocker {
             unlocked: false,
         };
 
-        env.storage()
-            .persistent()
-            .set(&DataKey::Lock(lock_id), &lock_info);
+        set_persistent(&env, &DataKey::Lock(lock_id), &lock_info);
 
         // Extend TTL for permanent lock (VULN #H2 fix - critical for u64::MAX)
         Self::extend_lock_ttl(&env, lock_id, &lock_info);
@@ -248,11 +357,17 @@ impl LiquidityLocker {
         Self::add_lock_to_user(&env, &owner, lock_id)?;
         Self::add_lock_to_token(&env, &lp_token, lock_id);
 
+        // A permanent lock counts as the longest timed lock
+        let config: LockConfig = env
+            .storage()
+            .instance()
+            .get(&DataKey::Config)
+            .ok_or(SharedError::NotInitialized)?;
+        Self::credit_loyalty(&env, &owner, config.max_lock_duration, false);
+
         let total = Self::get_total_locked(&env, &lp_token);
         let new_total = safe_add(total, amount)?;
-        env.storage()
-            .persistent()
-            .set(&DataKey::TotalLocked(lp_token.clone()), &new_total);
+        set_persistent(&env, &DataKey::TotalLocked(lp_token.clone()), &new_total);
 
         env.storage()
             .instance()
@@ -270,82 +385,134 @@ impl LiquidityLocker {
         Ok(lock_id)
     }
 
-    /// Unlock LP tokens after lock period expires
-    pub fn unlock(env: Env, owner: Address, lock_id: u64) -> Result<i128, SharedError> {
+    /// Lock several tokens (at most `MAX_BUNDLE_ASSETS`, each at least
+    /// `MIN_LOCK_AMOUNT`) under one lock ID. They unlock together through
+    /// `unlock_bundle`; there is no early unlock.
+    pub fn lock_bundle(
+        env: Env,
+        owner: Address,
+        assets: Vec<(Address, i128)>,
+        unlock_time: u64,
+    ) -> Result<u64, SharedError> {
         owner.require_auth();
         Self::require_initialized(&env)?;
-        Self::require_not_paused(&env)?;
+        pausable::require_not_paused(&env, PauseGroup::Deposits)?;
+
+        if assets.is_empty() {
+            return Err(SharedError::InvalidAmount);
+        }
+        if assets.len() > MAX_BUNDLE_ASSETS {
+            return Err(SharedError::LimitExceeded);
+        }
+        for (i, (asset, amount)) in assets.iter().enumerate() {
+            validate_amount(amount, MIN_LOCK_AMOUNT, NO_MAX)?;
+            for (other, _) in assets.iter().skip(i + 1) {
+                if other == asset {
+                    return Err(SharedError::InvalidAddress);
+                }
+            }
+        }
+        Self::check_lock_duration(&env, unlock_time)?;
+
+        let this = env.current_contract_address();
+        for (asset, amount) in assets.iter() {
+            token::Client::new(&env, &asset).transfer(&owner, &this, &amount);
+            let total = safe_add(Self::get_total_locked(&env, &asset), amount)?;
+            set_persistent(&env, &DataKey::TotalLocked(asset.clone()), &total);
+            Self::track_locked_token(&env, &asset);
+        }
 
-        let mut lock_info: LockInfo = env
+        let lock_id: u64 = env
             .storage()
-            .persistent()
-            .get(&DataKey::Lock(lock_id))
-            .ok_or(SharedError::TokenNotFound)?;
+            .instance()
+            .get(&DataKey::NextLockId)
+            .unwrap_or(1);
+        env.storage()
+            .instance()
+            .set(&DataKey::NextLockId, &(lock_id + 1));
 
-        // Verify ownership
-        if lock_info.owner != owner {
-            return Err(SharedError::NotOwner);
-        }
+        let bundle = BundleLockInfo {
+            id: lock_id,
+            owner: owner.clone(),
+            assets: assets.clone(),
+            lock_time: env.ledger().timestamp(),
+            unlock_time,
+            unlocked: false,
+        };
+        set_persistent(&env, &DataKey::BundleLock(lock_id), &bundle);
 
-        if lock_info.unlocked {
-            return Err(SharedError::AlreadyExecuted);
+        let key = DataKey::UserBundleLocks(owner.clone());
+        let mut locks: Vec<u64> = get_persistent(&env, &key).unwrap_or(Vec::new(&env));
+        if locks.len() >= MAX_LOCKS_PER_USER {
+            return Err(SharedError::LimitExceeded);
         }
+        locks.push_back(lock_id);
+        set_persistent(&env, &key, &locks);
 
-        let current_time = env.ledger().timestamp();
+        let events = EventBuilder::new(&env);
+        events.publish(
+            "locker",
+            "bundle_lock",
+            (lock_id, owner, assets, unlock_time),
+        );
 
-        // Check if permanent lock
-        if lock_info.unlock_time == u64::MAX {
-            return Err(SharedError::InvalidState);
-        }
+        extend_instance_ttl(&env);
+        Ok(lock_id)
+    }
 
-        // Check if unlock time reached
-        if current_time < lock_info.unlock_time {
-            return Err(SharedError::DeadlineExpired);
-        }
+    /// Unlock LP tokens after lock period expires
+    pub fn unlock(env: Env, owner: Address, lock_id: u64) -> Result<i128, SharedError> {
+        owner.require_auth();
+        Self::unlock_internal(&env, &owner, lock_id)
+    }
 
-        // Check unlock buffer (H2 security measure - prevents front-running)
-        let config: LockConfig = env
-            .storage()
-            .instance()
-            .get(&DataKey::Config)
-            .ok_or(SharedError::NotInitialized)?;
+    /// Unlock every token of a bundle lock after its lock period expires.
+    /// Returns the tokens and amounts sent back to the owner.
+    pub fn unlock_bundle(
+        env: Env,
+        owner: Address,
+        lock_id: u64,
+    ) -> Result<Vec<(Address, i128)>, SharedError> {
+        owner.require_auth();
+        Self::require_initialized(&env)?;
+        pausable::require_not_paused(&env, PauseGroup::Withdrawals)?;
 
-        if config.unlock_buffer > 0 {
-            let unlock_with_buffer = lock_info.unlock_time.saturating_add(config.unlock_buffer);
-            if current_time < unlock_with_buffer {
-                return Err(SharedError::UnlockBufferNotElapsed);
-            }
+        let mut bundle: BundleLockInfo = get_persistent(&env, &DataKey::BundleLock(lock_id))
+            .ok_or(SharedError::TokenNotFound)?;
+        if bundle.owner != owner {
+            return Err(SharedError::NotOwner);
         }
+        if bundle.unlocked {
+            return Err(SharedError::AlreadyExecuted);
+        }
+        Self::require_unlock_time_reached(&env, bundle.unlock_time)?;
 
-        // Mark as unlocked
-        lock_info.unlocked = true;
-        env.storage()
-            .persistent()
-            .set(&DataKey::Lock(lock_id), &lock_info);
+        bundle.unlocked = true;
+        set_persistent(&env, &DataKey::BundleLock(lock_id), &bundle);
 
-        // Transfer LP tokens back to owner
-        let token_client = token::Client::new(&env, &lock_info.lp_token);
-        token_client.transfer(&env.current_contract_address(), &owner, &lock_info.amount);
+        let this = env.current_contract_address();
+        for (asset, amount) in bundle.assets.iter() {
+            token::Client::new(&env, &asset).transfer(&this, &owner, &amount);
+            let total = safe_sub(Self::get_total_locked(&env, &asset), amount)?;
+            set_persistent(&env, &DataKey::TotalLocked(asset.clone()), &total);
+        }
 
-        // Update total locked
-        let total = Self::get_total_locked(&env, &lock_info.lp_token);
-        let new_total = safe_sub(total, lock_info.amount)?;
-        env.storage().persistent().set(
-            &DataKey::TotalLocked(lock_info.lp_token.clone()),
-            &new_total,
+        let events = EventBuilder::new(&env);
+        events.publish(
+            "locker",
+            "bundle_unlock",
+            (lock_id, owner, bundle.assets.clone()),
         );
 
-        emit_unlock(&env, lock_id, &owner, &lock_info.lp_token, lock_info.amount);
         extend_instance_ttl(&env);
-
-        Ok(lock_info.amount)
+        Ok(bundle.assets)
     }
 
     /// Early unlock with penalty (if enabled)
     pub fn early_unlock(env: Env, owner: Address, lock_id: u64) -> Result<i128, SharedError> {
         owner.require_auth();
         Self::require_initialized(&env)?;
-        Self::require_not_paused(&env)?;
+        pausable::require_not_paused(&env, PauseGroup::Withdrawals)?;
 
         let config: LockConfig = env
             .storage()
@@ -357,11 +524,8 @@ impl LiquidityLocker {
             return Err(SharedError::InvalidState);
         }
 
-        let mut lock_info: LockInfo = env
-            .storage()
-            .persistent()
-            .get(&DataKey::Lock(lock_id))
-            .ok_or(SharedError::TokenNotFound)?;
+        let mut lock_info: LockInfo =
+            get_persistent(&env, &DataKey::Lock(lock_id)).ok_or(SharedError::TokenNotFound)?;
 
         if lock_info.owner != owner {
             return Err(SharedError::NotOwner);
@@ -378,24 +542,23 @@ impl LiquidityLocker {
 
         // Mark as unlocked
         lock_info.unlocked = true;
-        env.storage()
-            .persistent()
-            .set(&DataKey::Lock(lock_id), &lock_info);
+        set_persistent(&env, &DataKey::Lock(lock_id), &lock_info);
+        Self::unindex_unlock(&env, &lock_info);
+
+        // Time given up no longer counts towards loyalty
+        let forfeited = lock_info
+            .unlock_time
+            .saturating_sub(env.ledger().timestamp());
+        Self::debit_loyalty(&env, &owner, forfeited);
 
         // Calculate penalty
-        let penalty = apply_bps(lock_info.amount, config.early_unlock_penalty_bps)?;
+        let penalty = config.early_unlock_penalty_bps.apply(lock_info.amount)?;
         let amount_after_penalty = safe_sub(lock_info.amount, penalty)?;
 
         let token_client = token::Client::new(&env, &lock_info.lp_token);
 
-        // Transfer penalty to treasury
         if penalty > 0 {
-            let treasury: Address = env
-                .storage()
-                .instance()
-                .get(&DataKey::Treasury)
-                .ok_or(SharedError::NotInitialized)?;
-            token_client.transfer(&env.current_contract_address(), &treasury, &penalty);
+            Self::route_penalty(&env, &config, &owner, &lock_info.lp_token, penalty)?;
         }
 
         // Transfer remaining to owner
@@ -408,7 +571,8 @@ impl LiquidityLocker {
         // Update total locked
         let total = Self::get_total_locked(&env, &lock_info.lp_token);
         let new_total = safe_sub(total, lock_info.amount)?;
-        env.storage().persistent().set(
+        set_persistent(
+            &env,
             &DataKey::TotalLocked(lock_info.lp_token.clone()),
             &new_total,
         );
@@ -434,13 +598,10 @@ impl LiquidityLocker {
     ) -> Result<(), SharedError> {
         owner.require_auth();
         Self::require_initialized(&env)?;
-        Self::require_not_paused(&env)?;
+        pausable::require_not_paused(&env, PauseGroup::Deposits)?;
 
-        let mut lock_info: LockInfo = env
-            .storage()
-            .persistent()
-            .get(&DataKey::Lock(lock_id))
-            .ok_or(SharedError::TokenNotFound)?;
+        let mut lock_info: LockInfo =
+            get_persistent(&env, &DataKey::Lock(lock_id)).ok_or(SharedError::TokenNotFound)?;
 
         if lock_info.owner != owner {
             return Err(SharedError::NotOwner);
@@ -473,16 +634,24 @@ impl LiquidityLocker {
             return Err(SharedError::InvalidTimestamp);
         }
 
+        Self::unindex_unlock(&env, &lock_info);
+        Self::credit_loyalty(&env, &owner, new_unlock_time - lock_info.unlock_time, true);
         lock_info.unlock_time = new_unlock_time;
-        env.storage()
-            .persistent()
-            .set(&DataKey::Lock(lock_id), &lock_info);
+        set_persistent(&env, &DataKey::Lock(lock_id), &lock_info);
+        Self::index_unlock(&env, &lock_info);
 
         // Re-extend TTL with new unlock time (VULN #H2 fix)
         Self::extend_lock_ttl(&env, lock_id, &lock_info);
 
-        let events = EventBuilder::new(&env);
-        events.publish("locker", "lock_extended", (lock_id, new_unlock_time));
+        emit_lock_extended(
+            &env,
+            lock_id,
+            &owner,
+            &lock_info.lp_token,
+            new_unlock_time,
+            Self::get_total_locked(&env, &lock_info.lp_token),
+            Self::user_lock_count(&env, &owner),
+        );
 
         extend_instance_ttl(&env);
 
@@ -499,11 +668,8 @@ impl LiquidityLocker {
         owner.require_auth();
         Self::require_initialized(&env)?;
 
-        let mut lock_info: LockInfo = env
-            .storage()
-            .persistent()
-            .get(&DataKey::Lock(lock_id))
-            .ok_or(SharedError::TokenNotFound)?;
+        let mut lock_info: LockInfo =
+            get_persistent(&env, &DataKey::Lock(lock_id)).ok_or(SharedError::TokenNotFound)?;
 
         if lock_info.owner != owner {
             return Err(SharedError::NotOwner);
@@ -515,9 +681,7 @@ impl LiquidityLocker {
 
         // Update owner
         lock_info.owner = new_owner.clone();
-        env.storage()
-            .persistent()
-            .set(&DataKey::Lock(lock_id), &lock_info);
+        set_persistent(&env, &DataKey::Lock(lock_id), &lock_info);
 
         // Re-extend TTL on transfer (VULN #H2 fix)
         Self::extend_lock_ttl(&env, lock_id, &lock_info);
@@ -526,38 +690,156 @@ impl LiquidityLocker {
         Self::remove_lock_from_user(&env, &owner, lock_id);
         Self::add_lock_to_user(&env, &new_owner, lock_id)?;
 
-        let events = EventBuilder::new(&env);
-        events.publish("locker", "lock_transferred", (lock_id, owner, new_owner));
+        emit_lock_transferred(
+            &env,
+            lock_id,
+            &owner,
+            &new_owner,
+            &lock_info.lp_token,
+            lock_info.amount,
+            Self::get_total_locked(&env, &lock_info.lp_token),
+            Self::user_lock_count(&env, &owner),
+            Self::user_lock_count(&env, &new_owner),
+        );
 
         extend_instance_ttl(&env);
 
         Ok(())
     }
 
-    // ────────────────────────────────────────────────────────────────────────
-    // Admin Functions
-    // ────────────────────────────────────────────────────────────────────────
+    /// Drop up to `limit` unlocked locks from the user's index so
+    /// `get_user_locks` stays cheap and frees room under the per-user cap.
+    /// Lock records stay readable through `get_lock`. Returns the number
+    /// of locks archived.
+    pub fn archive_unlocked_locks(env: Env, user: Address, limit: u32) -> Result<u32, SharedError> {
+        user.require_auth();
+        Self::require_initialized(&env)?;
 
-    /// Update configuration
-    pub fn update_config(env: Env, new_config: LockConfig) -> Result<(), SharedError> {
-        Self::require_admin(&env)?;
+        let key = DataKey::UserLocks(user.clone());
+        let lock_ids: Vec<u64> = get_persistent(&env, &key).unwrap_or(Vec::new(&env));
 
-        if new_config.min_lock_duration > new_config.max_lock_duration {
-            return Err(SharedError::InvalidTimestamp);
+        let mut kept = Vec::new(&env);
+        let mut archived: u32 = 0;
+        for id in lock_ids.iter() {
+            // Ids without a lock record are left in place
+            let archive = archived < limit
+                && get_persistent::<_, LockInfo>(&env, &DataKey::Lock(id))
+                    .map(|lock| lock.unlocked)
+                    .unwrap_or(false);
+            if archive {
+                archived += 1;
+            } else {
+                kept.push_back(id);
+            }
         }
+        if archived == 0 {
+            return Ok(0);
+        }
+        set_persistent(&env, &key, &kept);
+
+        let count_key = DataKey::ArchivedCount(user.clone());
+        let total: u32 = get_persistent(&env, &count_key).unwrap_or(0);
+        let total = total.saturating_add(archived);
+        set_persistent(&env, &count_key, &total);
+
+        let events = EventBuilder::new(&env);
+        events.publish("locker", "locks_archived", (user, archived, total));
 
-        env.storage().instance().set(&DataKey::Config, &new_config);
         extend_instance_ttl(&env);
 
-        Ok(())
+        Ok(archived)
     }
 
-    /// Set admin address
-    pub fn set_admin(env: Env, new_admin: Address) -> Result<(), SharedError> {
-        Self::require_admin(&env)?;
-
-        env.storage().instance().set(&DataKey::Admin, &new_admin);
-        extend_instance_ttl(&env);
+    // ────────────────────────────────────────────────────────────────────────
+    // Relayed Transactions
+    // ────────────────────────────────────────────────────────────────────────
+
+    /// Register (or rotate) the ed25519 key `user` signs relayed calls with
+    pub fn set_signing_key(env: Env, user: Address, key: BytesN<32>) -> Result<(), SharedError> {
+        user.require_auth();
+        signature::set_signing_key(&env, &user, &key);
+        Ok(())
+    }
+
+    /// `unlock` submitted by a sponsor, authorized by `owner`'s signature over
+    /// `unlock(owner, lock_id)` and its next nonce
+    pub fn unlock_with_sig(
+        env: Env,
+        owner: Address,
+        lock_id: u64,
+        nonce: u64,
+        sig: BytesN<64>,
+    ) -> Result<i128, SharedError> {
+        signature::consume(
+            &env,
+            &owner,
+            &Symbol::new(&env, "unlock"),
+            (owner.clone(), lock_id),
+            nonce,
+            &sig,
+        )?;
+        Self::unlock_internal(&env, &owner, lock_id)
+    }
+
+    // ────────────────────────────────────────────────────────────────────────
+    // Admin Functions
+    // ────────────────────────────────────────────────────────────────────────
+
+    /// Set the protocol registry address reported by `contract_info`
+    pub fn set_registry(env: Env, registry: Address) -> Result<(), SharedError> {
+        Self::require_admin(&env)?;
+        env.storage().instance().set(&DataKey::Registry, &registry);
+        extend_instance_ttl(&env);
+        Ok(())
+    }
+
+    /// Update configuration
+    pub fn update_config(
+        env: Env,
+        caller: Address,
+        new_config: LockConfig,
+    ) -> Result<(), SharedError> {
+        Self::require_role(&env, &caller, &CONFIG_ADMIN)?;
+        pausable::require_not_paused(&env, PauseGroup::AdminOps)?;
+
+        Self::validate_config(&env, &new_config)?;
+
+        env.storage().instance().set(&DataKey::Config, &new_config);
+        emit_config_updated(&env, &caller);
+        extend_instance_ttl(&env);
+
+        Ok(())
+    }
+
+    /// Propose a new admin; it takes over once it calls `accept_admin`
+    pub fn propose_admin(env: Env, new_admin: Address) -> Result<(), SharedError> {
+        Self::require_admin(&env)?;
+
+        let admin = Self::admin(env.clone())?;
+        ownership::propose(&env, &admin, &new_admin)?;
+        extend_instance_ttl(&env);
+
+        Ok(())
+    }
+
+    /// Accept a pending admin proposal (proposed admin only)
+    pub fn accept_admin(env: Env) -> Result<(), SharedError> {
+        let old_admin = Self::admin(env.clone())?;
+        let new_admin = ownership::accept(&env, &old_admin)?;
+
+        env.storage().instance().set(&DataKey::Admin, &new_admin);
+        emit_admin_changed(&env, &old_admin, &new_admin);
+        extend_instance_ttl(&env);
+
+        Ok(())
+    }
+
+    /// Withdraw a pending admin proposal
+    pub fn cancel_admin_transfer(env: Env) -> Result<(), SharedError> {
+        Self::require_admin(&env)?;
+
+        ownership::cancel(&env)?;
+        extend_instance_ttl(&env);
 
         Ok(())
     }
@@ -566,44 +848,351 @@ impl LiquidityLocker {
     pub fn set_treasury(env: Env, new_treasury: Address) -> Result<(), SharedError> {
         Self::require_admin(&env)?;
 
+        let old_treasury = env.storage().instance().get(&DataKey::Treasury);
         env.storage()
             .instance()
             .set(&DataKey::Treasury, &new_treasury);
+        emit_treasury_changed(&env, old_treasury, &new_treasury);
         extend_instance_ttl(&env);
 
         Ok(())
     }
 
-    /// Pause/unpause the contract
-    pub fn set_paused(env: Env, paused: bool) -> Result<(), SharedError> {
+    /// Set the fee distributor receiving the stakers' share of penalties
+    pub fn set_fee_distributor(env: Env, fee_distributor: Address) -> Result<(), SharedError> {
         Self::require_admin(&env)?;
 
-        env.storage().instance().set(&DataKey::Paused, &paused);
+        env.storage()
+            .instance()
+            .set(&DataKey::FeeDistributor, &fee_distributor);
+
+        let events = EventBuilder::new(&env);
+        events.publish("locker", "fee_distributor_set", fee_distributor);
+
         extend_instance_ttl(&env);
+        Ok(())
+    }
+
+    /// Set where the burned share of penalties goes when the LP token
+    /// cannot be burned directly
+    pub fn set_burn_address(env: Env, burn_address: Address) -> Result<(), SharedError> {
+        Self::require_admin(&env)?;
 
+        env.storage()
+            .instance()
+            .set(&DataKey::BurnAddress, &burn_address);
+
+        let events = EventBuilder::new(&env);
+        events.publish("locker", "burn_address_set", burn_address);
+
+        extend_instance_ttl(&env);
+        Ok(())
+    }
+
+    /// Approve or revoke a contract (bridge, factory) allowed to `lock_for`
+    pub fn set_approved_locker(
+        env: Env,
+        locker: Address,
+        approved: bool,
+    ) -> Result<(), SharedError> {
+        Self::require_admin(&env)?;
+
+        let key = DataKey::ApprovedLocker(locker.clone());
+        if approved {
+            set_persistent(&env, &key, &true);
+        } else {
+            env.storage().persistent().remove(&key);
+        }
+
+        let events = EventBuilder::new(&env);
+        events.publish("locker", "locker_approved", (locker, approved));
+
+        extend_instance_ttl(&env);
         Ok(())
     }
 
+    /// Set the loyalty bonus: extensions and re-locks (new locks by a user
+    /// who already has a score) earn this share of the added seconds on top
+    pub fn set_loyalty_bonus(env: Env, bps: u32) -> Result<(), SharedError> {
+        Self::require_admin(&env)?;
+        let bps = Bps::new(bps)?;
+
+        env.storage().instance().set(&DataKey::LoyaltyBonus, &bps);
+
+        let events = EventBuilder::new(&env);
+        events.publish("locker", "loyalty_bonus_set", bps.get());
+
+        extend_instance_ttl(&env);
+        Ok(())
+    }
+
+    /// Set the public-goods address receiving lock donations
+    pub fn set_public_goods(env: Env, public_goods: Address) -> Result<(), SharedError> {
+        Self::require_admin(&env)?;
+
+        env.storage()
+            .instance()
+            .set(&DataKey::PublicGoods, &public_goods);
+        extend_instance_ttl(&env);
+
+        Ok(())
+    }
+
+    /// Move tokens sent here by mistake (admin, while withdrawals are paused).
+    ///
+    /// Only the balance above the token's total locked amount can be moved,
+    /// so locked LP stays untouched.
+    pub fn rescue_token(
+        env: Env,
+        token: Address,
+        to: Address,
+        amount: i128,
+    ) -> Result<(), SharedError> {
+        Self::require_admin(&env)?;
+        pausable::require_paused(&env, PauseGroup::Withdrawals)?;
+
+        if amount <= 0 {
+            return Err(SharedError::InvalidAmount);
+        }
+
+        let token_client = token::Client::new(&env, &token);
+        let this = env.current_contract_address();
+        let surplus = safe_sub(
+            token_client.balance(&this),
+            Self::get_total_locked(&env, &token),
+        )?;
+        if amount > surplus {
+            return Err(SharedError::InsufficientBalance);
+        }
+
+        token_client.transfer(&this, &to, &amount);
+
+        let events = EventBuilder::new(&env);
+        events.publish(
+            "locker",
+            "token_rescued",
+            (token, to, amount, env.ledger().timestamp()),
+        );
+
+        extend_instance_ttl(&env);
+        Ok(())
+    }
+
+    /// Pause/unpause every function group (admin or `PAUSER`).
+    /// Completed pause windows are recorded so matured locks aren't penalized.
+    pub fn set_paused(env: Env, caller: Address, paused: bool) -> Result<(), SharedError> {
+        Self::require_role(&env, &caller, &PAUSER)?;
+
+        Self::store_pause_config(&env, &PauseConfig::uniform(paused));
+        extend_instance_ttl(&env);
+
+        Ok(())
+    }
+
+    /// Pause individual function groups, e.g. halt new locks while unlocks
+    /// stay open (admin or `PAUSER`)
+    pub fn set_pause_config(
+        env: Env,
+        caller: Address,
+        config: PauseConfig,
+    ) -> Result<(), SharedError> {
+        Self::require_role(&env, &caller, &PAUSER)?;
+
+        Self::store_pause_config(&env, &config);
+
+        let events = EventBuilder::new(&env);
+        events.publish(
+            "locker",
+            "pause_config",
+            (
+                config.deposits,
+                config.withdrawals,
+                config.admin_ops,
+                env.ledger().timestamp(),
+            ),
+        );
+
+        extend_instance_ttl(&env);
+        Ok(())
+    }
+
+    /// Set how far storage TTLs are extended (see `TtlConfig`), e.g. shorter
+    /// lifetimes on testnet
+    pub fn set_ttl_config(env: Env, caller: Address, config: TtlConfig) -> Result<(), SharedError> {
+        Self::require_role(&env, &caller, &CONFIG_ADMIN)?;
+        pausable::require_not_paused(&env, PauseGroup::AdminOps)?;
+
+        set_ttl_config(&env, &config)?;
+        emit_config_updated(&env, &caller);
+        extend_instance_ttl(&env);
+
+        Ok(())
+    }
+
+    // ────────────────────────────────────────────────────────────────────────
+    // Roles (admin only)
+    // ────────────────────────────────────────────────────────────────────────
+
+    /// Grant a role (`PAUSER`, `TREASURER`, `CFG_ADMIN`, `UPGRADER`) to an account
+    pub fn grant_role(env: Env, role: Symbol, account: Address) -> Result<(), SharedError> {
+        Self::require_admin(&env)?;
+        access_control::grant_role(&env, role, account)?;
+        extend_instance_ttl(&env);
+        Ok(())
+    }
+
+    /// Revoke a role from an account
+    pub fn revoke_role(env: Env, role: Symbol, account: Address) -> Result<(), SharedError> {
+        Self::require_admin(&env)?;
+        access_control::revoke_role(&env, role, account)?;
+        extend_instance_ttl(&env);
+        Ok(())
+    }
+
+    // ────────────────────────────────────────────────────────────────────────
+    // Upgrades (admin or UPGRADER)
+    // ────────────────────────────────────────────────────────────────────────
+
+    /// Queue a WASM upgrade behind the upgrade timelock. Returns its eta.
+    pub fn queue_upgrade(
+        env: Env,
+        caller: Address,
+        new_wasm_hash: BytesN<32>,
+    ) -> Result<u64, SharedError> {
+        Self::require_role(&env, &caller, &UPGRADER)?;
+        upgrade::queue(&env, new_wasm_hash)
+    }
+
+    /// Cancel the queued upgrade
+    pub fn cancel_upgrade(env: Env, caller: Address) -> Result<(), SharedError> {
+        Self::require_role(&env, &caller, &UPGRADER)?;
+        upgrade::cancel(&env)
+    }
+
+    /// Switch to the queued WASM once the timelock has elapsed
+    pub fn upgrade(
+        env: Env,
+        caller: Address,
+        new_wasm_hash: BytesN<32>,
+    ) -> Result<(), SharedError> {
+        Self::require_role(&env, &caller, &UPGRADER)?;
+        upgrade::execute(&env, new_wasm_hash)
+    }
+
+    /// Migrate storage to `STORAGE_VERSION` after an upgrade
+    pub fn migrate(env: Env, caller: Address) -> Result<u32, SharedError> {
+        Self::require_role(&env, &caller, &UPGRADER)?;
+        upgrade::migrate(&env, STORAGE_VERSION, |env, from| {
+            if from < 2 {
+                // v2 moved the single pause flag to per-group `PauseConfig`
+                let paused: bool = env
+                    .storage()
+                    .instance()
+                    .get(&DataKey::Paused)
+                    .unwrap_or(false);
+                pausable::set(env, &PauseConfig::uniform(paused));
+                env.storage().instance().remove(&DataKey::Paused);
+            }
+            if from < 3 {
+                // v3 stores the early unlock penalty as `Bps`
+                let legacy: Option<LegacyLockConfig> =
+                    env.storage().instance().get(&DataKey::Config);
+                if let Some(legacy) = legacy {
+                    let config = LockConfigV3 {
+                        min_lock_duration: legacy.min_lock_duration,
+                        max_lock_duration: legacy.max_lock_duration,
+                        early_unlock_enabled: legacy.early_unlock_enabled,
+                        early_unlock_penalty_bps: Bps::new(legacy.early_unlock_penalty_bps)?,
+                        unlock_buffer: legacy.unlock_buffer,
+                    };
+                    env.storage().instance().set(&DataKey::Config, &config);
+                }
+            }
+            if from < 4 {
+                // v4 splits penalties; existing deployments keep 100% to treasury
+                let v3: Option<LockConfigV3> = env.storage().instance().get(&DataKey::Config);
+                if let Some(v3) = v3 {
+                    let config = LockConfigV4 {
+                        min_lock_duration: v3.min_lock_duration,
+                        max_lock_duration: v3.max_lock_duration,
+                        early_unlock_enabled: v3.early_unlock_enabled,
+                        early_unlock_penalty_bps: v3.early_unlock_penalty_bps,
+                        penalty_stakers_bps: Bps::ZERO,
+                        penalty_burn_bps: Bps::ZERO,
+                        unlock_buffer: v3.unlock_buffer,
+                    };
+                    env.storage().instance().set(&DataKey::Config, &config);
+                }
+            }
+            if from < 5 {
+                // v5 stores the split as a shared `PenaltyRouting`
+                let v4: Option<LockConfigV4> = env.storage().instance().get(&DataKey::Config);
+                if let Some(v4) = v4 {
+                    let shares = v4
+                        .penalty_stakers_bps
+                        .checked_add(v4.penalty_burn_bps)
+                        .ok_or(SharedError::InvalidBps)?;
+                    let config = LockConfig {
+                        min_lock_duration: v4.min_lock_duration,
+                        max_lock_duration: v4.max_lock_duration,
+                        early_unlock_enabled: v4.early_unlock_enabled,
+                        early_unlock_penalty_bps: v4.early_unlock_penalty_bps,
+                        penalty_routing: PenaltyRouting {
+                            treasury_bps: shares.complement(),
+                            burn_bps: v4.penalty_burn_bps,
+                            stakers_bps: v4.penalty_stakers_bps,
+                        },
+                        unlock_buffer: v4.unlock_buffer,
+                    };
+                    env.storage().instance().set(&DataKey::Config, &config);
+                }
+            }
+            Ok(())
+        })
+    }
+
     // ────────────────────────────────────────────────────────────────────────
     // Query Functions
     // ────────────────────────────────────────────────────────────────────────
 
+    /// Next nonce `user` must sign relayed calls with
+    pub fn signing_nonce(env: Env, user: Address) -> u64 {
+        signature::nonce(&env, &user)
+    }
+
+    /// Whether an account was granted a role (the admin implicitly holds all)
+    pub fn has_role(env: Env, role: Symbol, account: Address) -> bool {
+        access_control::has_role(&env, &role, &account)
+    }
+
+    /// Get the storage layout version
+    pub fn version(env: Env) -> u32 {
+        upgrade::version(&env)
+    }
+
+    /// Get the queued upgrade, if any
+    pub fn get_pending_upgrade(env: Env) -> Option<PendingUpgrade> {
+        upgrade::pending(&env)
+    }
+
     /// Get lock information
     pub fn get_lock(env: Env, lock_id: u64) -> Option<LockInfo> {
-        env.storage().persistent().get(&DataKey::Lock(lock_id))
+        get_persistent(&env, &DataKey::Lock(lock_id))
+    }
+
+    /// Get the number of unlocked locks archived from a user's index
+    pub fn archived_lock_count(env: Env, user: Address) -> u32 {
+        get_persistent(&env, &DataKey::ArchivedCount(user)).unwrap_or(0)
     }
 
     /// Get all locks for a user
     pub fn get_user_locks(env: Env, user: Address) -> Vec<LockInfo> {
-        let lock_ids: Vec<u64> = env
-            .storage()
-            .persistent()
-            .get(&DataKey::UserLocks(user))
-            .unwrap_or(Vec::new(&env));
+        let lock_ids: Vec<u64> =
+            get_persistent(&env, &DataKey::UserLocks(user)).unwrap_or(Vec::new(&env));
 
         let mut locks = Vec::new(&env);
         for id in lock_ids.iter() {
-            if let Some(lock) = env.storage().persistent().get(&DataKey::Lock(id)) {
+            if let Some(lock) = get_persistent(&env, &DataKey::Lock(id)) {
                 let lock_info: LockInfo = lock;
                 locks.push_back(lock_info);
             }
@@ -611,17 +1200,34 @@ impl LiquidityLocker {
         locks
     }
 
+    /// Get a bundle lock by ID
+    pub fn get_bundle_lock(env: Env, lock_id: u64) -> Option<BundleLockInfo> {
+        get_persistent(&env, &DataKey::BundleLock(lock_id))
+    }
+
+    /// Get all bundle locks for a user
+    pub fn get_user_bundle_locks(env: Env, user: Address) -> Vec<BundleLockInfo> {
+        let lock_ids: Vec<u64> =
+            get_persistent(&env, &DataKey::UserBundleLocks(user)).unwrap_or(Vec::new(&env));
+
+        let mut locks = Vec::new(&env);
+        for id in lock_ids.iter() {
+            if let Some(bundle) = get_persistent(&env, &DataKey::BundleLock(id)) {
+                let bundle: BundleLockInfo = bundle;
+                locks.push_back(bundle);
+            }
+        }
+        locks
+    }
+
     /// Get all locks for a token
     pub fn get_token_locks(env: Env, lp_token: Address) -> Vec<LockInfo> {
-        let lock_ids: Vec<u64> = env
-            .storage()
-            .persistent()
-            .get(&DataKey::TokenLocks(lp_token))
-            .unwrap_or(Vec::new(&env));
+        let lock_ids: Vec<u64> =
+            get_persistent(&env, &DataKey::TokenLocks(lp_token)).unwrap_or(Vec::new(&env));
 
         let mut locks = Vec::new(&env);
         for id in lock_ids.iter() {
-            if let Some(lock) = env.storage().persistent().get(&DataKey::Lock(id)) {
+            if let Some(lock) = get_persistent(&env, &DataKey::Lock(id)) {
                 let lock_info: LockInfo = lock;
                 locks.push_back(lock_info);
             }
@@ -629,11 +1235,87 @@ impl LiquidityLocker {
         locks
     }
 
+    /// Loyalty score of a user: seconds committed across their single-token
+    /// locks (a permanent lock counts as `max_lock_duration`), plus
+    /// extension and re-lock bonuses, minus time given up through early
+    /// unlocks
+    pub fn loyalty_score(env: Env, user: Address) -> u64 {
+        get_persistent(&env, &DataKey::LoyaltyScore(user)).unwrap_or(0)
+    }
+
+    /// Loyalty bonus on extensions and re-locks
+    pub fn loyalty_bonus(env: Env) -> Bps {
+        env.storage()
+            .instance()
+            .get(&DataKey::LoyaltyBonus)
+            .unwrap_or(Bps::ZERO)
+    }
+
     /// Get total locked for a token
     pub fn get_total_locked_amount(env: Env, lp_token: Address) -> i128 {
         Self::get_total_locked(&env, &lp_token)
     }
 
+    /// Whether at least `min_bps_of_supply` of `lp_token`'s total supply is
+    /// locked for at least `min_remaining_secs` more seconds.
+    ///
+    /// Lets third parties verify a "locked liquidity" claim in one call.
+    /// False for tokens without a `total_supply` (i.e. not a pair).
+    pub fn is_liquidity_locked(
+        env: Env,
+        lp_token: Address,
+        min_bps_of_supply: u32,
+        min_remaining_secs: u64,
+    ) -> bool {
+        let supply = match AmmPairClient::new(&env, &lp_token)
+            .try_total_supply()
+            .into_shared()
+        {
+            Ok(supply) if supply > 0 => supply,
+            _ => return false,
+        };
+        let required = match apply_bps_round_up(supply, min_bps_of_supply) {
+            Ok(required) => required,
+            Err(_) => return false,
+        };
+
+        let until = env.ledger().timestamp().saturating_add(min_remaining_secs);
+        let mut locked: i128 = 0;
+        for lock_info in Self::get_token_locks(env.clone(), lp_token).iter() {
+            if !lock_info.unlocked && lock_info.unlock_time >= until {
+                locked = locked.saturating_add(lock_info.amount);
+                if locked >= required {
+                    return true;
+                }
+            }
+        }
+        locked >= required
+    }
+
+    /// Check if an address may lock on behalf of others
+    pub fn is_approved_locker(env: Env, locker: Address) -> bool {
+        get_persistent(&env, &DataKey::ApprovedLocker(locker)).unwrap_or(false)
+    }
+
+    /// Get the LP tokens that have been locked
+    pub fn locked_tokens(env: Env) -> Vec<Address> {
+        Self::get_locked_tokens(&env)
+    }
+
+    /// Compare the balance of each locked LP token with its total locked
+    pub fn solvency_report(env: Env) -> Vec<TokenSolvency> {
+        let this = env.current_contract_address();
+        let mut report = Vec::new(&env);
+        for lp_token in Self::get_locked_tokens(&env).iter() {
+            report.push_back(TokenSolvency {
+                balance: token::Client::new(&env, &lp_token).balance(&this),
+                liabilities: Self::get_total_locked(&env, &lp_token),
+                token: lp_token,
+            });
+        }
+        report
+    }
+
     /// Get configuration
     pub fn get_config(env: Env) -> Result<LockConfig, SharedError> {
         env.storage()
@@ -650,12 +1332,125 @@ impl LiquidityLocker {
             .ok_or(SharedError::NotInitialized)
     }
 
-    /// Check if contract is paused
+    /// Get the storage TTL config
+    pub fn ttl_config(env: Env) -> TtlConfig {
+        get_ttl_config(&env)
+    }
+
+    /// Get the proposed admin awaiting acceptance
+    pub fn pending_admin(env: Env) -> Option<Address> {
+        ownership::pending(&env)
+    }
+
+    /// Whether any function group is paused
     pub fn is_paused(env: Env) -> bool {
-        env.storage()
-            .instance()
-            .get(&DataKey::Paused)
-            .unwrap_or(false)
+        pausable::get(&env).is_any()
+    }
+
+    /// Get the pause flags per function group
+    pub fn pause_config(env: Env) -> PauseConfig {
+        pausable::get(&env)
+    }
+
+    /// Describe this contract for explorers and wallets
+    pub fn contract_info(env: Env) -> Result<ContractInfo, SharedError> {
+        let admin = Self::admin(env.clone())?;
+        let config = Self::get_config(env.clone())?;
+        let info = ContractInfo::new(
+            &env,
+            "locker",
+            env!("CARGO_PKG_VERSION"),
+            admin,
+            Self::is_paused(env.clone()),
+            env.storage().instance().get(&DataKey::Registry),
+        );
+        Ok(info
+            .with("min_lock_duration", config.min_lock_duration as i128)
+            .with("max_lock_duration", config.max_lock_duration as i128)
+            .with("early_unlock_enabled", config.early_unlock_enabled as i128)
+            .with(
+                "early_unlock_penalty_bps",
+                config.early_unlock_penalty_bps.get() as i128,
+            )
+            .with(
+                "penalty_treasury_bps",
+                config.penalty_routing.treasury_bps.get() as i128,
+            )
+            .with(
+                "penalty_burn_bps",
+                config.penalty_routing.burn_bps.get() as i128,
+            )
+            .with(
+                "penalty_stakers_bps",
+                config.penalty_routing.stakers_bps.get() as i128,
+            )
+            .with("unlock_buffer", config.unlock_buffer as i128))
+    }
+
+    /// Get upcoming unlockable amounts for an LP token, bucketed by time.
+    /// Bucket `i` covers `[now + i * bucket_seconds, now + (i + 1) * bucket_seconds)`
+    /// (e.g. 12 weekly buckets). Permanent locks never appear.
+    pub fn get_unlock_schedule(
+        env: Env,
+        lp_token: Address,
+        buckets: u32,
+        bucket_seconds: u64,
+    ) -> Result<Vec<i128>, SharedError> {
+        if buckets == 0 || bucket_seconds == 0 {
+            return Err(SharedError::InvalidAmount);
+        }
+
+        let start = env.ledger().timestamp();
+        let end = (buckets as u64)
+            .checked_mul(bucket_seconds)
+            .and_then(|span| span.checked_add(start))
+            .ok_or(SharedError::LimitExceeded)?;
+        let first_month = start / UNLOCK_MONTH;
+        let last_month = (end - 1) / UNLOCK_MONTH;
+        if last_month - first_month >= MAX_SCHEDULE_MONTHS {
+            return Err(SharedError::LimitExceeded);
+        }
+
+        let mut schedule = Vec::new(&env);
+        for _ in 0..buckets {
+            schedule.push_back(0i128);
+        }
+
+        for month in first_month..=last_month {
+            for lock_id in Self::get_unlock_month(&env, &lp_token, month).iter() {
+                let lock_info: Option<LockInfo> = get_persistent(&env, &DataKey::Lock(lock_id));
+                let lock_info = match lock_info {
+                    Some(lock_info) => lock_info,
+                    None => continue,
+                };
+                if lock_info.unlocked
+                    || lock_info.unlock_time < start
+                    || lock_info.unlock_time >= end
+                {
+                    continue;
+                }
+                let bucket = ((lock_info.unlock_time - start) / bucket_seconds) as u32;
+                let amount = safe_add(schedule.get(bucket).unwrap(), lock_info.amount)?;
+                schedule.set(bucket, amount);
+            }
+        }
+
+        Ok(schedule)
+    }
+
+    /// Get recent completed pause windows
+    pub fn pause_windows(env: Env) -> Vec<PauseWindow> {
+        Self::get_pause_windows(&env)
+    }
+
+    /// Get the public-goods donation address
+    pub fn public_goods(env: Env) -> Option<Address> {
+        env.storage().instance().get(&DataKey::PublicGoods)
+    }
+
+    /// Get total donated to public goods for a token
+    pub fn get_total_donated(env: Env, lp_token: Address) -> i128 {
+        get_persistent(&env, &DataKey::TotalDonated(lp_token)).unwrap_or(0)
     }
 
     /// Get next lock ID
@@ -670,6 +1465,133 @@ impl LiquidityLocker {
     // Internal Helpers
     // ────────────────────────────────────────────────────────────────────────
 
+    /// Create a time lock for `owner` funded by `payer`, optionally donating
+    /// a share to public goods
+    fn create_lock(
+        env: &Env,
+        payer: &Address,
+        owner: &Address,
+        lp_token: &Address,
+        amount: i128,
+        unlock_time: u64,
+        donation_bps: u32,
+    ) -> Result<u64, SharedError> {
+        validate_amount(amount, MIN_LOCK_AMOUNT, NO_MAX)?;
+        Self::check_lock_duration(env, unlock_time)?;
+        let current_time = env.ledger().timestamp();
+
+        // Transfer LP tokens to contract
+        let token_client = token::Client::new(env, lp_token);
+        token_client.transfer(payer, env.current_contract_address(), &amount);
+
+        // Optional donation comes off the top
+        let donation = apply_bps(amount, donation_bps)?;
+        let amount = safe_sub(amount, donation)?;
+        if amount <= 0 {
+            return Err(SharedError::InvalidAmount);
+        }
+
+        // Create lock
+        let lock_id: u64 = env
+            .storage()
+            .instance()
+            .get(&DataKey::NextLockId)
+            .unwrap_or(1);
+
+        let lock_info = LockInfo {
+            id: lock_id,
+            owner: owner.clone(),
+            lp_token: lp_token.clone(),
+            amount,
+            lock_time: current_time,
+            unlock_time,
+            unlocked: false,
+        };
+
+        // Store lock
+        set_persistent(env, &DataKey::Lock(lock_id), &lock_info);
+
+        // Extend TTL for long-term locks (VULN #H2 fix)
+        Self::extend_lock_ttl(env, lock_id, &lock_info);
+
+        // Update user's lock list
+        Self::add_lock_to_user(env, owner, lock_id)?;
+        Self::credit_loyalty(env, owner, unlock_time.saturating_sub(current_time), false);
+
+        // Update token's lock list and unlock index
+        Self::add_lock_to_token(env, lp_token, lock_id);
+        Self::index_unlock(env, &lock_info);
+
+        // Update total locked
+        let total = Self::get_total_locked(env, lp_token);
+        let new_total = safe_add(total, amount)?;
+        set_persistent(env, &DataKey::TotalLocked(lp_token.clone()), &new_total);
+
+        // Increment lock ID
+        env.storage()
+            .instance()
+            .set(&DataKey::NextLockId, &(lock_id + 1));
+
+        if donation > 0 {
+            Self::donate(env, lock_id, owner, lp_token, donation)?;
+        }
+
+        emit_lock(
+            env,
+            lock_id,
+            owner,
+            lp_token,
+            amount,
+            unlock_time,
+            new_total,
+            Self::user_lock_count(env, owner),
+        );
+        extend_instance_ttl(env);
+
+        Ok(lock_id)
+    }
+
+    /// Send a lock donation to the public-goods address and record it
+    fn donate(
+        env: &Env,
+        lock_id: u64,
+        owner: &Address,
+        lp_token: &Address,
+        donation: i128,
+    ) -> Result<(), SharedError> {
+        let public_goods: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::PublicGoods)
+            .ok_or(SharedError::ExternalContractNotSet)?;
+
+        token::Client::new(env, lp_token).transfer(
+            &env.current_contract_address(),
+            &public_goods,
+            &donation,
+        );
+
+        let key = DataKey::TotalDonated(lp_token.clone());
+        let total: i128 = get_persistent(env, &key).unwrap_or(0);
+        set_persistent(env, &key, &safe_add(total, donation)?);
+
+        let events = EventBuilder::new(env);
+        events.publish(
+            "locker",
+            "donated",
+            (
+                lock_id,
+                owner.clone(),
+                lp_token.clone(),
+                donation,
+                public_goods,
+                env.ledger().timestamp(),
+            ),
+        );
+
+        Ok(())
+    }
+
     fn require_initialized(env: &Env) -> Result<(), SharedError> {
         let initialized: bool = env
             .storage()
@@ -683,17 +1605,55 @@ impl LiquidityLocker {
         Ok(())
     }
 
-    fn require_not_paused(env: &Env) -> Result<(), SharedError> {
-        let paused: bool = env
-            .storage()
-            .instance()
-            .get(&DataKey::Paused)
-            .unwrap_or(false);
+    /// Body of `unlock` once `owner` has authorized it
+    fn unlock_internal(env: &Env, owner: &Address, lock_id: u64) -> Result<i128, SharedError> {
+        Self::require_initialized(env)?;
+        pausable::require_not_paused(env, PauseGroup::Withdrawals)?;
 
-        if paused {
-            return Err(SharedError::ContractPaused);
+        let mut lock_info: LockInfo =
+            get_persistent(env, &DataKey::Lock(lock_id)).ok_or(SharedError::TokenNotFound)?;
+
+        // Verify ownership
+        if lock_info.owner != *owner {
+            return Err(SharedError::NotOwner);
         }
-        Ok(())
+
+        if lock_info.unlocked {
+            return Err(SharedError::AlreadyExecuted);
+        }
+
+        Self::require_unlock_time_reached(env, lock_info.unlock_time)?;
+
+        // Mark as unlocked
+        lock_info.unlocked = true;
+        set_persistent(env, &DataKey::Lock(lock_id), &lock_info);
+        Self::unindex_unlock(env, &lock_info);
+
+        // Transfer LP tokens back to owner
+        let token_client = token::Client::new(env, &lock_info.lp_token);
+        token_client.transfer(&env.current_contract_address(), owner, &lock_info.amount);
+
+        // Update total locked
+        let total = Self::get_total_locked(env, &lock_info.lp_token);
+        let new_total = safe_sub(total, lock_info.amount)?;
+        set_persistent(
+            env,
+            &DataKey::TotalLocked(lock_info.lp_token.clone()),
+            &new_total,
+        );
+
+        emit_unlock(
+            env,
+            lock_id,
+            owner,
+            &lock_info.lp_token,
+            lock_info.amount,
+            new_total,
+            Self::user_lock_count(env, owner),
+        );
+        extend_instance_ttl(env);
+
+        Ok(lock_info.amount)
     }
 
     fn require_admin(env: &Env) -> Result<(), SharedError> {
@@ -707,6 +1667,16 @@ impl LiquidityLocker {
         Ok(())
     }
 
+    /// Require `caller`'s auth and that it is the admin or holds `role`
+    fn require_role(env: &Env, caller: &Address, role: &Symbol) -> Result<(), SharedError> {
+        let admin: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::Admin)
+            .ok_or(SharedError::NotInitialized)?;
+        access_control::require_role(env, role, caller, &admin)
+    }
+
     /// Extend TTL for a lock based on its duration
     /// For permanent locks (u64::MAX), use maximum TTL and re-extend periodically
     /// Fixes VULN #H2: Prevents permanent loss of funds in long-term locks
@@ -763,19 +1733,257 @@ impl LiquidityLocker {
         }
     }
 
-    fn get_total_locked(env: &Env, lp_token: &Address) -> i128 {
+    fn get_pause_windows(env: &Env) -> Vec<PauseWindow> {
         env.storage()
-            .persistent()
-            .get(&DataKey::TotalLocked(lp_token.clone()))
-            .unwrap_or(0)
+            .instance()
+            .get(&DataKey::PauseWindows)
+            .unwrap_or(Vec::new(env))
     }
 
-    fn add_lock_to_user(env: &Env, user: &Address, lock_id: u64) -> Result<(), SharedError> {
-        let mut locks: Vec<u64> = env
+    /// Store pause flags, recording a pause window each time unlocks resume
+    fn store_pause_config(env: &Env, config: &PauseConfig) {
+        let was_paused = pausable::is_paused(env, PauseGroup::Withdrawals);
+        let paused = config.withdrawals;
+        let now = env.ledger().timestamp();
+
+        if paused && !was_paused {
+            env.storage().instance().set(&DataKey::PausedAt, &now);
+        } else if !paused && was_paused {
+            let start: u64 = env
+                .storage()
+                .instance()
+                .get(&DataKey::PausedAt)
+                .unwrap_or(now);
+            let mut windows = Self::get_pause_windows(env);
+            if windows.len() >= MAX_PAUSE_WINDOWS {
+                windows.pop_front();
+            }
+            windows.push_back(PauseWindow { start, end: now });
+            env.storage()
+                .instance()
+                .set(&DataKey::PauseWindows, &windows);
+            env.storage().instance().remove(&DataKey::PausedAt);
+        }
+
+        pausable::set(env, config);
+    }
+
+    /// Whether any recorded pause overlapped `[unlock_time, unlock_time + buffer)`
+    /// Fail unless a lock ending at `unlock_time` may be unlocked now:
+    /// not permanent, matured and past the unlock buffer
+    fn require_unlock_time_reached(env: &Env, unlock_time: u64) -> Result<(), SharedError> {
+        let current_time = env.ledger().timestamp();
+
+        // Check if permanent lock
+        if unlock_time == u64::MAX {
+            return Err(SharedError::InvalidState);
+        }
+
+        // Check if unlock time reached
+        if current_time < unlock_time {
+            return Err(SharedError::DeadlineExpired);
+        }
+
+        // Check unlock buffer (H2 security measure - prevents front-running)
+        let config: LockConfig = env
+            .storage()
+            .instance()
+            .get(&DataKey::Config)
+            .ok_or(SharedError::NotInitialized)?;
+
+        // Grace: if a pause overlapped the buffer window, the owner already lost
+        // that time, so the buffer is waived
+        if config.unlock_buffer > 0 && !Self::paused_during_buffer(env, unlock_time, &config) {
+            let unlock_with_buffer = unlock_time.saturating_add(config.unlock_buffer);
+            if current_time < unlock_with_buffer {
+                return Err(SharedError::UnlockBufferNotElapsed);
+            }
+        }
+        Ok(())
+    }
+
+    /// Check duration bounds, the penalty cap and the penalty split. A
+    /// stakers' or burn share needs its destination configured first.
+    fn validate_config(env: &Env, config: &LockConfig) -> Result<(), SharedError> {
+        if config.min_lock_duration > config.max_lock_duration {
+            return Err(SharedError::InvalidTimestamp);
+        }
+        if config.early_unlock_penalty_bps > MAX_PENALTY {
+            return Err(SharedError::InvalidBps);
+        }
+        let routing = &config.penalty_routing;
+        if !routing.is_valid() {
+            return Err(SharedError::InvalidBps);
+        }
+
+        let storage = env.storage().instance();
+        if routing.stakers_bps > Bps::ZERO && !storage.has(&DataKey::FeeDistributor) {
+            return Err(SharedError::NotInitialized);
+        }
+        if routing.burn_bps > Bps::ZERO && !storage.has(&DataKey::BurnAddress) {
+            return Err(SharedError::NotInitialized);
+        }
+        Ok(())
+    }
+
+    /// Split an early-unlock penalty between stakers (fee distributor),
+    /// burn and the treasury per the config's `penalty_routing`
+    fn route_penalty(
+        env: &Env,
+        config: &LockConfig,
+        owner: &Address,
+        lp_token: &Address,
+        penalty: i128,
+    ) -> Result<(), SharedError> {
+        let this = env.current_contract_address();
+        let token_client = token::Client::new(env, lp_token);
+
+        let split = config.penalty_routing.split(penalty)?;
+        let (to_stakers, to_burn, to_treasury) =
+            (split.to_stakers, split.to_burn, split.to_treasury);
+
+        if to_stakers > 0 {
+            let fee_distributor: Address = env
+                .storage()
+                .instance()
+                .get(&DataKey::FeeDistributor)
+                .ok_or(SharedError::NotInitialized)?;
+            // The distributor pulls the share from this contract
+            env.authorize_as_current_contract(vec![
+                env,
+                InvokerContractAuthEntry::Contract(SubContractInvocation {
+                    context: ContractContext {
+                        contract: lp_token.clone(),
+                        fn_name: Symbol::new(env, "transfer"),
+                        args: (this.clone(), fee_distributor.clone(), to_stakers).into_val(env),
+                    },
+                    sub_invocations: Vec::new(env),
+                }),
+            ]);
+            FeeDistributorClient::new(env, &fee_distributor)
+                .try_receive_fees(&this, lp_token, &to_stakers)
+                .into_shared()?;
+        }
+
+        let mut burned = true;
+        if to_burn > 0 {
+            burned = matches!(token_client.try_burn(&this, &to_burn), Ok(Ok(())));
+            if !burned {
+                let burn_address: Address = env
+                    .storage()
+                    .instance()
+                    .get(&DataKey::BurnAddress)
+                    .ok_or(SharedError::NotInitialized)?;
+                token_client.transfer(&this, &burn_address, &to_burn);
+            }
+        }
+
+        if to_treasury > 0 {
+            let treasury: Address = env
+                .storage()
+                .instance()
+                .get(&DataKey::Treasury)
+                .ok_or(SharedError::NotInitialized)?;
+            token_client.transfer(&this, &treasury, &to_treasury);
+        }
+
+        emit_penalty_routed(env, lp_token, owner, &split, burned);
+        Ok(())
+    }
+
+    /// Fail with `InvalidTimestamp` unless `unlock_time` is within the
+    /// configured lock duration bounds
+    fn check_lock_duration(env: &Env, unlock_time: u64) -> Result<(), SharedError> {
+        let config: LockConfig = env
             .storage()
-            .persistent()
-            .get(&DataKey::UserLocks(user.clone()))
-            .unwrap_or(Vec::new(env));
+            .instance()
+            .get(&DataKey::Config)
+            .ok_or(SharedError::NotInitialized)?;
+
+        let lock_duration = unlock_time.saturating_sub(env.ledger().timestamp());
+
+        // Validate lock duration
+        if lock_duration < config.min_lock_duration {
+            return Err(SharedError::InvalidTimestamp);
+        }
+
+        if lock_duration > config.max_lock_duration && config.max_lock_duration > 0 {
+            return Err(SharedError::InvalidTimestamp);
+        }
+        Ok(())
+    }
+
+    fn paused_during_buffer(env: &Env, unlock_time: u64, config: &LockConfig) -> bool {
+        let buffer_start = unlock_time;
+        let buffer_end = buffer_start.saturating_add(config.unlock_buffer);
+
+        for window in Self::get_pause_windows(env).iter() {
+            if window.start < buffer_end && window.end > buffer_start {
+                return true;
+            }
+        }
+        false
+    }
+
+    fn get_total_locked(env: &Env, lp_token: &Address) -> i128 {
+        get_persistent(env, &DataKey::TotalLocked(lp_token.clone())).unwrap_or(0)
+    }
+
+    /// Locks in the user's index, including unlocked ones not yet archived
+    fn user_lock_count(env: &Env, user: &Address) -> u32 {
+        let locks: Vec<u64> =
+            get_persistent(env, &DataKey::UserLocks(user.clone())).unwrap_or(Vec::new(env));
+        locks.len()
+    }
+
+    /// Add committed seconds to a user's loyalty score, plus the bonus on
+    /// extensions and on locks by users who already have a score
+    fn credit_loyalty(env: &Env, user: &Address, seconds: u64, extension: bool) {
+        if seconds == 0 {
+            return;
+        }
+        let score = Self::loyalty_score(env.clone(), user.clone());
+        let bonus = if extension || score > 0 {
+            let bonus_bps = Self::loyalty_bonus(env.clone()).get() as u128;
+            (seconds as u128 * bonus_bps / Bps::MAX_VALUE as u128) as u64
+        } else {
+            0
+        };
+        let new_score = score.saturating_add(seconds).saturating_add(bonus);
+        set_persistent(env, &DataKey::LoyaltyScore(user.clone()), &new_score);
+
+        let events = EventBuilder::new(env);
+        events.publish(
+            "locker",
+            "loyalty_credited",
+            (user.clone(), seconds, bonus, new_score),
+        );
+    }
+
+    /// Take forfeited seconds off a user's loyalty score (not below zero)
+    fn debit_loyalty(env: &Env, user: &Address, seconds: u64) {
+        let score = Self::loyalty_score(env.clone(), user.clone());
+        if seconds == 0 || score == 0 {
+            return;
+        }
+        let new_score = score.saturating_sub(seconds);
+        set_persistent(env, &DataKey::LoyaltyScore(user.clone()), &new_score);
+
+        let events = EventBuilder::new(env);
+        events.publish(
+            "locker",
+            "loyalty_debited",
+            (user.clone(), seconds, new_score),
+        );
+    }
+
+    fn get_locked_tokens(env: &Env) -> Vec<Address> {
+        get_persistent(env, &DataKey::LockedTokens).unwrap_or(Vec::new(env))
+    }
+
+    fn add_lock_to_user(env: &Env, user: &Address, lock_id: u64) -> Result<(), SharedError> {
+        let mut locks: Vec<u64> =
+            get_persistent(env, &DataKey::UserLocks(user.clone())).unwrap_or(Vec::new(env));
 
         // FIX #M4: Prevent DoS by limiting locks per user
         if locks.len() >= MAX_LOCKS_PER_USER {
@@ -783,19 +1991,14 @@ impl LiquidityLocker {
         }
 
         locks.push_back(lock_id);
-        env.storage()
-            .persistent()
-            .set(&DataKey::UserLocks(user.clone()), &locks);
+        set_persistent(env, &DataKey::UserLocks(user.clone()), &locks);
 
         Ok(())
     }
 
     fn remove_lock_from_user(env: &Env, user: &Address, lock_id: u64) {
-        let locks: Vec<u64> = env
-            .storage()
-            .persistent()
-            .get(&DataKey::UserLocks(user.clone()))
-            .unwrap_or(Vec::new(env));
+        let locks: Vec<u64> =
+            get_persistent(env, &DataKey::UserLocks(user.clone())).unwrap_or(Vec::new(env));
 
         let mut new_locks = Vec::new(env);
         for id in locks.iter() {
@@ -803,21 +2006,56 @@ impl LiquidityLocker {
                 new_locks.push_back(id);
             }
         }
-        env.storage()
-            .persistent()
-            .set(&DataKey::UserLocks(user.clone()), &new_locks);
+        set_persistent(env, &DataKey::UserLocks(user.clone()), &new_locks);
+    }
+
+    fn get_unlock_month(env: &Env, lp_token: &Address, month: u64) -> Vec<u64> {
+        get_persistent(env, &DataKey::UnlockMonth(lp_token.clone(), month)).unwrap_or(Vec::new(env))
+    }
+
+    /// Add a timed lock to the unlock-month index
+    fn index_unlock(env: &Env, lock_info: &LockInfo) {
+        let month = lock_info.unlock_time / UNLOCK_MONTH;
+        let mut locks = Self::get_unlock_month(env, &lock_info.lp_token, month);
+        locks.push_back(lock_info.id);
+        let key = DataKey::UnlockMonth(lock_info.lp_token.clone(), month);
+        set_persistent(env, &key, &locks);
+    }
+
+    /// Remove a lock from the unlock-month index
+    fn unindex_unlock(env: &Env, lock_info: &LockInfo) {
+        let month = lock_info.unlock_time / UNLOCK_MONTH;
+        let mut locks = Self::get_unlock_month(env, &lock_info.lp_token, month);
+        if let Some(index) = locks.first_index_of(lock_info.id) {
+            locks.remove(index);
+        }
+        let key = DataKey::UnlockMonth(lock_info.lp_token.clone(), month);
+        if locks.is_empty() {
+            env.storage().persistent().remove(&key);
+        } else {
+            set_persistent(env, &key, &locks);
+        }
+    }
+
+    /// Add `token` to `LockedTokens` if it isn't listed yet
+    fn track_locked_token(env: &Env, token: &Address) {
+        let mut tokens = Self::get_locked_tokens(env);
+        if !tokens.contains(token) {
+            tokens.push_back(token.clone());
+            set_persistent(env, &DataKey::LockedTokens, &tokens);
+        }
     }
 
     fn add_lock_to_token(env: &Env, token: &Address, lock_id: u64) {
-        let mut locks: Vec<u64> = env
-            .storage()
-            .persistent()
-            .get(&DataKey::TokenLocks(token.clone()))
-            .unwrap_or(Vec::new(env));
+        let mut locks: Vec<u64> =
+            get_persistent(env, &DataKey::TokenLocks(token.clone())).unwrap_or(Vec::new(env));
+        if locks.is_empty() {
+            let mut tokens = Self::get_locked_tokens(env);
+            tokens.push_back(token.clone());
+            set_persistent(env, &DataKey::LockedTokens, &tokens);
+        }
         locks.push_back(lock_id);
-        env.storage()
-            .persistent()
-            .set(&DataKey::TokenLocks(token.clone()), &locks);
+        set_persistent(env, &DataKey::TokenLocks(token.clone()), &locks);
     }
 }
 
@@ -828,29 +2066,9 @@ impl LiquidityLocker {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use astro_core_shared::testutils::{create_token, default_lock_config};
     use soroban_sdk::testutils::{Address as _, Ledger as _};
 
-    fn create_token<'a>(
-        env: &Env,
-        admin: &Address,
-    ) -> (token::Client<'a>, token::StellarAssetClient<'a>) {
-        let contract_id = env.register_stellar_asset_contract_v2(admin.clone());
-        (
-            token::Client::new(env, &contract_id.address()),
-            token::StellarAssetClient::new(env, &contract_id.address()),
-        )
-    }
-
-    fn default_config() -> LockConfig {
-        LockConfig {
-            min_lock_duration: 86400,    // 1 day
-            max_lock_duration: 31536000, // 1 year
-            early_unlock_enabled: true,
-            early_unlock_penalty_bps: 2500, // 25%
-            unlock_buffer: 0,               // No buffer for tests
-        }
-    }
-
     #[test]
     fn test_initialize() {
         let env = Env::default();
@@ -862,12 +2080,44 @@ mod tests {
         let admin = Address::generate(&env);
         let treasury = Address::generate(&env);
 
-        client.initialize(&admin, &treasury, &default_config());
+        client.initialize(&admin, &treasury, &default_lock_config());
 
         assert_eq!(client.admin(), admin);
         assert_eq!(client.next_lock_id(), 1);
     }
 
+    #[test]
+    fn test_ttl_config() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let contract_id = env.register(LiquidityLocker, ());
+        let client = LiquidityLockerClient::new(&env, &contract_id);
+
+        let admin = Address::generate(&env);
+        let treasury = Address::generate(&env);
+        client.initialize(&admin, &treasury, &default_lock_config());
+        assert_eq!(client.ttl_config(), TtlConfig::default());
+
+        // Shorter lifetimes, e.g. for a testnet deployment
+        let config = TtlConfig {
+            instance_threshold: 1_000,
+            instance_extend: 20_000,
+            persistent_threshold: 1_000,
+            persistent_extend: 20_000,
+        };
+        client.set_ttl_config(&admin, &config);
+        assert_eq!(client.ttl_config(), config);
+
+        let invalid = TtlConfig {
+            persistent_threshold: 30_000,
+            ..config
+        };
+        assert!(client.try_set_ttl_config(&admin, &invalid).is_err());
+        let outsider = Address::generate(&env);
+        assert!(client.try_set_ttl_config(&outsider, &config).is_err());
+    }
+
     #[test]
     fn test_lock_and_unlock() {
         let env = Env::default();
@@ -880,10 +2130,10 @@ mod tests {
         let treasury = Address::generate(&env);
         let user = Address::generate(&env);
 
-        let (lp_token, lp_admin) = create_token(&env, &admin);
-        lp_admin.mint(&user, &1_000_000_000_000);
+        let lp_token = create_token(&env, &admin);
+        lp_token.mint(&user, 1_000_000_000_000);
 
-        client.initialize(&admin, &treasury, &default_config());
+        client.initialize(&admin, &treasury, &default_lock_config());
 
         // Set current time
         env.ledger().set_timestamp(1000);
@@ -898,6 +2148,12 @@ mod tests {
             client.get_total_locked_amount(&lp_token.address),
             lock_amount
         );
+        let report = client.solvency_report();
+        assert_eq!(report.len(), 1);
+        let entry = report.get(0).unwrap();
+        assert_eq!(entry.token, lp_token.address);
+        assert_eq!(entry.liabilities, lock_amount);
+        assert!(entry.is_solvent());
 
         // Check lock info
         let lock_info = client.get_lock(&lock_id).unwrap();
@@ -917,6 +2173,151 @@ mod tests {
         assert_eq!(lp_token.balance(&user), 1_000_000_000_000);
     }
 
+    #[test]
+    fn test_archive_unlocked_locks() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let contract_id = env.register(LiquidityLocker, ());
+        let client = LiquidityLockerClient::new(&env, &contract_id);
+
+        let admin = Address::generate(&env);
+        let user = Address::generate(&env);
+        let lp_token = create_token(&env, &admin);
+        lp_token.mint(&user, 1_000_000_000_000);
+
+        client.initialize(&admin, &Address::generate(&env), &default_lock_config());
+        env.ledger().set_timestamp(1000);
+
+        let lock_amount = 100_000_000_000_i128;
+        let unlock_time = 1000 + 7 * 86400;
+        let lock_id = client.lock(&user, &lp_token.address, &lock_amount, &unlock_time);
+        env.ledger().set_timestamp(unlock_time + 1);
+        client.unlock(&user, &lock_id);
+
+        // The unlocked lock can be archived from the user's index
+        let open_id = client.lock(
+            &user,
+            &lp_token.address,
+            &lock_amount,
+            &(unlock_time + 7 * 86400),
+        );
+        assert_eq!(client.archive_unlocked_locks(&user, &0), 0);
+        assert_eq!(client.archive_unlocked_locks(&user, &10), 1);
+        assert_eq!(client.archived_lock_count(&user), 1);
+        let locks = client.get_user_locks(&user);
+        assert_eq!(locks.len(), 1);
+        assert_eq!(locks.get(0).unwrap().id, open_id);
+        assert!(client.get_lock(&lock_id).unwrap().unlocked);
+        assert_eq!(client.archive_unlocked_locks(&user, &10), 0);
+
+        // An id whose record is gone stays in the index
+        env.as_contract(&contract_id, || {
+            env.storage().persistent().remove(&DataKey::Lock(open_id));
+        });
+        assert_eq!(client.archive_unlocked_locks(&user, &10), 0);
+        assert_eq!(client.archived_lock_count(&user), 1);
+    }
+
+    #[test]
+    fn test_lock_for() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let client = LiquidityLockerClient::new(&env, &env.register(LiquidityLocker, ()));
+        let admin = Address::generate(&env);
+        let treasury = Address::generate(&env);
+        let bridge = Address::generate(&env);
+        let creator = Address::generate(&env);
+
+        let lp_token = create_token(&env, &admin);
+        lp_token.mint(&bridge, 10_000);
+        client.initialize(&admin, &treasury, &default_lock_config());
+        env.ledger().set_timestamp(1000);
+        let unlock_time = 1000 + 7 * 86400;
+
+        // Only approved lockers
+        assert!(client
+            .try_lock_for(
+                &bridge,
+                &Some(creator.clone()),
+                &lp_token.address,
+                &1_000,
+                &unlock_time
+            )
+            .is_err());
+        client.set_approved_locker(&bridge, &true);
+
+        let lock_id = client.lock_for(
+            &bridge,
+            &Some(creator.clone()),
+            &lp_token.address,
+            &1_000,
+            &unlock_time,
+        );
+        assert_eq!(client.get_lock(&lock_id).unwrap().owner, creator);
+        assert_eq!(lp_token.balance(&bridge), 9_000);
+
+        // Protocol-owned fallback
+        let lock_id = client.lock_for(&bridge, &None, &lp_token.address, &1_000, &unlock_time);
+        assert_eq!(client.get_lock(&lock_id).unwrap().owner, treasury);
+        assert_eq!(client.get_total_locked_amount(&lp_token.address), 2_000);
+    }
+
+    #[test]
+    fn test_lock_with_donation() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let contract_id = env.register(LiquidityLocker, ());
+        let client = LiquidityLockerClient::new(&env, &contract_id);
+
+        let admin = Address::generate(&env);
+        let treasury = Address::generate(&env);
+        let public_goods = Address::generate(&env);
+        let user = Address::generate(&env);
+
+        let lp_token = create_token(&env, &admin);
+        lp_token.mint(&user, 1_000_000_000_000);
+
+        client.initialize(&admin, &treasury, &default_lock_config());
+        env.ledger().set_timestamp(1000);
+        let unlock_time = 1000 + 7 * 86400;
+
+        // No public-goods address configured yet
+        let result =
+            client.try_lock_with_donation(&user, &lp_token.address, &1_000_000, &unlock_time, &100);
+        assert!(result.is_err());
+
+        client.set_public_goods(&public_goods);
+
+        // Donation above the cap is rejected
+        let result = client.try_lock_with_donation(
+            &user,
+            &lp_token.address,
+            &1_000_000,
+            &unlock_time,
+            &(MAX_DONATION_BPS + 1),
+        );
+        assert!(result.is_err());
+
+        // 1% donated, 99% locked
+        let lock_id = client.lock_with_donation(
+            &user,
+            &lp_token.address,
+            &100_000_000_000,
+            &unlock_time,
+            &100,
+        );
+        assert_eq!(client.get_lock(&lock_id).unwrap().amount, 99_000_000_000);
+        assert_eq!(lp_token.balance(&public_goods), 1_000_000_000);
+        assert_eq!(client.get_total_donated(&lp_token.address), 1_000_000_000);
+        assert_eq!(
+            client.get_total_locked_amount(&lp_token.address),
+            99_000_000_000
+        );
+    }
+
     #[test]
     fn test_permanent_lock() {
         let env = Env::default();
@@ -929,10 +2330,10 @@ mod tests {
         let treasury = Address::generate(&env);
         let user = Address::generate(&env);
 
-        let (lp_token, lp_admin) = create_token(&env, &admin);
-        lp_admin.mint(&user, &1_000_000_000_000);
+        let lp_token = create_token(&env, &admin);
+        lp_token.mint(&user, 1_000_000_000_000);
 
-        client.initialize(&admin, &treasury, &default_config());
+        client.initialize(&admin, &treasury, &default_lock_config());
 
         let lock_amount = 100_000_000_000_i128;
         let lock_id = client.permanent_lock(&user, &lp_token.address, &lock_amount);
@@ -958,10 +2359,10 @@ mod tests {
         let treasury = Address::generate(&env);
         let user = Address::generate(&env);
 
-        let (lp_token, lp_admin) = create_token(&env, &admin);
-        lp_admin.mint(&user, &1_000_000_000_000);
+        let lp_token = create_token(&env, &admin);
+        lp_token.mint(&user, 1_000_000_000_000);
 
-        client.initialize(&admin, &treasury, &default_config());
+        client.initialize(&admin, &treasury, &default_lock_config());
 
         env.ledger().set_timestamp(1000);
 
@@ -992,10 +2393,10 @@ mod tests {
         let treasury = Address::generate(&env);
         let user = Address::generate(&env);
 
-        let (lp_token, lp_admin) = create_token(&env, &admin);
-        lp_admin.mint(&user, &1_000_000_000_000);
+        let lp_token = create_token(&env, &admin);
+        lp_token.mint(&user, 1_000_000_000_000);
 
-        client.initialize(&admin, &treasury, &default_config());
+        client.initialize(&admin, &treasury, &default_lock_config());
 
         env.ledger().set_timestamp(1000);
 
@@ -1016,6 +2417,102 @@ mod tests {
         assert_eq!(lock_info.unlock_time, new_unlock_time);
     }
 
+    #[test]
+    fn test_loyalty_score() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let contract_id = env.register(LiquidityLocker, ());
+        let client = LiquidityLockerClient::new(&env, &contract_id);
+
+        let admin = Address::generate(&env);
+        let treasury = Address::generate(&env);
+        let user = Address::generate(&env);
+        let whale = Address::generate(&env);
+
+        let lp_token = create_token(&env, &admin);
+        lp_token.mint(&user, 1_000_000_000_000);
+        lp_token.mint(&whale, 1_000_000_000_000);
+
+        client.initialize(&admin, &treasury, &default_lock_config());
+        assert_eq!(
+            client.try_set_loyalty_bonus(&10_001),
+            Err(Ok(SharedError::InvalidBps))
+        );
+        client.set_loyalty_bonus(&1000); // 10%
+
+        env.ledger().set_timestamp(1000);
+        let day = 86400;
+        let amount = 100_000_000_000_i128;
+
+        // First lock: committed seconds, no bonus
+        let lock_id = client.lock(&user, &lp_token.address, &amount, &(1000 + 7 * day));
+        assert_eq!(client.loyalty_score(&user), 7 * day);
+
+        // Extension: added seconds plus 10%
+        client.extend_lock(&user, &lock_id, &(1000 + 30 * day));
+        let mut expected = 7 * day + 23 * day + 23 * day / 10;
+        assert_eq!(client.loyalty_score(&user), expected);
+
+        // Re-lock earns the bonus too
+        let relock_id = client.lock(&user, &lp_token.address, &amount, &(1000 + 10 * day));
+        expected += 10 * day + day;
+        assert_eq!(client.loyalty_score(&user), expected);
+
+        // Early unlock gives back the time not served
+        env.ledger().set_timestamp(1000 + 2 * day);
+        client.early_unlock(&user, &relock_id);
+        expected -= 8 * day;
+        assert_eq!(client.loyalty_score(&user), expected);
+
+        // A permanent lock counts as the maximum duration
+        client.permanent_lock(&whale, &lp_token.address, &amount);
+        assert_eq!(client.loyalty_score(&whale), 31_536_000);
+    }
+
+    #[test]
+    fn test_unlock_schedule() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let contract_id = env.register(LiquidityLocker, ());
+        let client = LiquidityLockerClient::new(&env, &contract_id);
+
+        let admin = Address::generate(&env);
+        let treasury = Address::generate(&env);
+        let user = Address::generate(&env);
+
+        let lp_token = create_token(&env, &admin);
+        lp_token.mint(&user, 1_000_000_000_000);
+
+        client.initialize(&admin, &treasury, &default_lock_config());
+
+        env.ledger().set_timestamp(1000);
+        let week = 7 * 86400;
+
+        let first = client.lock(&user, &lp_token.address, &100, &(1000 + week));
+        client.lock(&user, &lp_token.address, &200, &(1000 + 3 * week + 1));
+        client.lock(&user, &lp_token.address, &400, &(1000 + 20 * week));
+        client.permanent_lock(&user, &lp_token.address, &800);
+
+        let schedule = client.get_unlock_schedule(&lp_token.address, &12, &week);
+        assert_eq!(schedule.len(), 12);
+        assert_eq!(schedule.get(1), Some(100));
+        assert_eq!(schedule.get(3), Some(200));
+        assert_eq!(schedule.iter().sum::<i128>(), 300);
+
+        // Extending moves the lock to its new bucket
+        client.extend_lock(&user, &first, &(1000 + 5 * week));
+        let schedule = client.get_unlock_schedule(&lp_token.address, &12, &week);
+        assert_eq!(schedule.get(1), Some(0));
+        assert_eq!(schedule.get(5), Some(100));
+
+        // Window too far out
+        assert!(client
+            .try_get_unlock_schedule(&lp_token.address, &1000, &week)
+            .is_err());
+    }
+
     #[test]
     fn test_transfer_lock() {
         let env = Env::default();
@@ -1029,10 +2526,10 @@ mod tests {
         let user1 = Address::generate(&env);
         let user2 = Address::generate(&env);
 
-        let (lp_token, lp_admin) = create_token(&env, &admin);
-        lp_admin.mint(&user1, &1_000_000_000_000);
+        let lp_token = create_token(&env, &admin);
+        lp_token.mint(&user1, 1_000_000_000_000);
 
-        client.initialize(&admin, &treasury, &default_config());
+        client.initialize(&admin, &treasury, &default_lock_config());
 
         env.ledger().set_timestamp(1000);
 
@@ -1049,4 +2546,272 @@ mod tests {
         let result = client.unlock(&user2, &lock_id);
         assert_eq!(result, 100_000_000_000);
     }
+
+    #[test]
+    fn test_pause_waives_unlock_buffer() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let contract_id = env.register(LiquidityLocker, ());
+        let client = LiquidityLockerClient::new(&env, &contract_id);
+
+        let admin = Address::generate(&env);
+        let treasury = Address::generate(&env);
+        let user = Address::generate(&env);
+
+        let lp_token = create_token(&env, &admin);
+        lp_token.mint(&user, 1_000_000_000_000);
+
+        let mut config = default_lock_config();
+        config.unlock_buffer = 3600;
+        client.initialize(&admin, &treasury, &config);
+
+        env.ledger().set_timestamp(1000);
+        let unlock_time = 1000 + 86400;
+        let lock_id = client.lock(&user, &lp_token.address, &100_000_000_000, &unlock_time);
+
+        // Paused across maturity, unpaused before the buffer would have elapsed
+        env.ledger().set_timestamp(unlock_time - 100);
+        client.set_paused(&admin, &true);
+        env.ledger().set_timestamp(unlock_time + 600);
+        client.set_paused(&admin, &false);
+
+        assert_eq!(client.pause_windows().len(), 1);
+        assert_eq!(client.unlock(&user, &lock_id), 100_000_000_000);
+    }
+
+    #[test]
+    fn test_is_liquidity_locked() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let contract_id = env.register(LiquidityLocker, ());
+        let client = LiquidityLockerClient::new(&env, &contract_id);
+
+        let admin = Address::generate(&env);
+        let user = Address::generate(&env);
+        let token_0 = create_token(&env, &admin);
+        let token_1 = create_token(&env, &admin);
+        token_0.mint(&user, 1_000_000_000_000);
+        token_1.mint(&user, 1_000_000_000_000);
+
+        // Supply is 1_000_000_000_000, including the pair's minimum liquidity
+        let pair_id = env.register(astro_pair::AmmPair, ());
+        let pair = astro_pair::AmmPairClient::new(&env, &pair_id);
+        pair.initialize(&token_0.address, &token_1.address, &admin, &admin);
+        env.ledger().set_timestamp(1000);
+        pair.add_liquidity(&user, &1_000_000_000_000, &1_000_000_000_000, &0, &0, &1000);
+
+        client.initialize(&admin, &Address::generate(&env), &default_lock_config());
+        client.lock(&user, &pair_id, &600_000_000_000, &(1000 + 30 * 86400));
+        client.lock(&user, &pair_id, &100_000_000_000, &(1000 + 86400));
+
+        assert!(client.is_liquidity_locked(&pair_id, &6000, &(7 * 86400)));
+        assert!(!client.is_liquidity_locked(&pair_id, &7000, &(7 * 86400)));
+        assert!(client.is_liquidity_locked(&pair_id, &7000, &0));
+        assert!(!client.is_liquidity_locked(&pair_id, &7001, &0));
+
+        // Expiring locks stop counting
+        env.ledger().set_timestamp(1000 + 25 * 86400);
+        assert!(!client.is_liquidity_locked(&pair_id, &6000, &(7 * 86400)));
+
+        // Not an LP token
+        let lp_token = create_token(&env, &admin);
+        lp_token.mint(&user, 1_000_000_000_000);
+        client.lock(
+            &user,
+            &lp_token.address,
+            &100_000_000_000,
+            &(1000 + 30 * 86400),
+        );
+        assert!(!client.is_liquidity_locked(&lp_token.address, &1, &0));
+    }
+
+    #[test]
+    fn test_rescue_token() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let contract_id = env.register(LiquidityLocker, ());
+        let client = LiquidityLockerClient::new(&env, &contract_id);
+
+        let admin = Address::generate(&env);
+        let user = Address::generate(&env);
+        let recipient = Address::generate(&env);
+        let lp_token = create_token(&env, &admin);
+        let stray = create_token(&env, &admin);
+        lp_token.mint(&user, 1_000_000_000_000);
+
+        client.initialize(&admin, &Address::generate(&env), &default_lock_config());
+        env.ledger().set_timestamp(1000);
+        client.lock(&user, &lp_token.address, &100_000_000_000, &(1000 + 86400));
+
+        // Mistaken transfers
+        lp_token.transfer(&user, &contract_id, &5_000);
+        stray.mint(&contract_id, 7_000);
+
+        assert_eq!(
+            client.try_rescue_token(&stray.address, &recipient, &7_000),
+            Err(Ok(SharedError::ContractNotPaused))
+        );
+
+        client.set_paused(&admin, &true);
+        assert_eq!(
+            client.try_rescue_token(&lp_token.address, &recipient, &5_001),
+            Err(Ok(SharedError::InsufficientBalance))
+        );
+        client.rescue_token(&lp_token.address, &recipient, &5_000);
+        client.rescue_token(&stray.address, &recipient, &7_000);
+        assert_eq!(lp_token.balance(&recipient), 5_000);
+        assert_eq!(stray.balance(&recipient), 7_000);
+        assert_eq!(lp_token.balance(&contract_id), 100_000_000_000);
+    }
+
+    #[test]
+    fn test_lock_bundle() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let contract_id = env.register(LiquidityLocker, ());
+        let client = LiquidityLockerClient::new(&env, &contract_id);
+
+        let admin = Address::generate(&env);
+        let user = Address::generate(&env);
+        let other = Address::generate(&env);
+        let token_0 = create_token(&env, &admin);
+        let token_1 = create_token(&env, &admin);
+        token_0.mint(&user, 1_000_000_000_000);
+        token_1.mint(&user, 1_000_000_000_000);
+
+        client.initialize(&admin, &Address::generate(&env), &default_lock_config());
+        env.ledger().set_timestamp(1000);
+
+        let duplicate = Vec::from_array(
+            &env,
+            [
+                (token_0.address.clone(), 100_000_000_000),
+                (token_0.address.clone(), 100_000_000_000),
+            ],
+        );
+        assert_eq!(
+            client.try_lock_bundle(&user, &duplicate, &(1000 + 86400)),
+            Err(Ok(SharedError::InvalidAddress))
+        );
+
+        let assets = Vec::from_array(
+            &env,
+            [
+                (token_0.address.clone(), 100_000_000_000),
+                (token_1.address.clone(), 300_000_000_000),
+            ],
+        );
+        let single = client.lock(&user, &token_0.address, &100_000_000_000, &(1000 + 86400));
+        let bundle_id = client.lock_bundle(&user, &assets, &(1000 + 86400));
+        assert_eq!(bundle_id, single + 1);
+
+        let bundle = client.get_bundle_lock(&bundle_id).unwrap();
+        assert_eq!(bundle.assets, assets);
+        assert_eq!(client.get_user_bundle_locks(&user).len(), 1);
+        assert_eq!(client.get_user_locks(&user).len(), 1);
+        assert_eq!(
+            client.get_total_locked_amount(&token_0.address),
+            200_000_000_000
+        );
+        assert_eq!(
+            client.get_total_locked_amount(&token_1.address),
+            300_000_000_000
+        );
+        assert_eq!(client.locked_tokens().len(), 2);
+
+        assert_eq!(
+            client.try_unlock_bundle(&user, &bundle_id),
+            Err(Ok(SharedError::DeadlineExpired))
+        );
+        env.ledger().set_timestamp(1000 + 86400);
+        assert_eq!(
+            client.try_unlock_bundle(&other, &bundle_id),
+            Err(Ok(SharedError::NotOwner))
+        );
+        assert_eq!(client.unlock_bundle(&user, &bundle_id), assets);
+        assert_eq!(token_0.balance(&user), 900_000_000_000);
+        assert_eq!(token_1.balance(&user), 1_000_000_000_000);
+        assert_eq!(client.get_total_locked_amount(&token_1.address), 0);
+        assert!(client.get_bundle_lock(&bundle_id).unwrap().unlocked);
+        assert_eq!(
+            client.try_unlock_bundle(&user, &bundle_id),
+            Err(Ok(SharedError::AlreadyExecuted))
+        );
+    }
+
+    /// Fee distributor that pulls the fees it is sent
+    #[contract]
+    pub struct MockFeeDistributor;
+
+    #[contractimpl]
+    impl MockFeeDistributor {
+        pub fn receive_fees(env: Env, caller: Address, token: Address, amount: i128) {
+            caller.require_auth();
+            token::Client::new(&env, &token).transfer(
+                &caller,
+                env.current_contract_address(),
+                &amount,
+            );
+        }
+    }
+
+    #[test]
+    fn test_early_unlock_penalty_split() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let contract_id = env.register(LiquidityLocker, ());
+        let client = LiquidityLockerClient::new(&env, &contract_id);
+
+        let admin = Address::generate(&env);
+        let treasury = Address::generate(&env);
+        let burn_address = Address::generate(&env);
+        let user = Address::generate(&env);
+        let distributor = env.register(MockFeeDistributor, ());
+
+        let lp_token = create_token(&env, &admin);
+        lp_token.mint(&user, 1_000_000_000_000);
+
+        client.initialize(&admin, &treasury, &default_lock_config());
+
+        // A split needs its destinations first
+        let mut config = default_lock_config();
+        config.penalty_routing = PenaltyRouting::new(3000, 2000, 5000).unwrap();
+        assert_eq!(
+            client.try_update_config(&admin, &config),
+            Err(Ok(SharedError::NotInitialized))
+        );
+
+        client.set_fee_distributor(&distributor);
+        client.set_burn_address(&burn_address);
+
+        let mut too_much = config.clone();
+        too_much.penalty_routing.burn_bps = Bps::from_const(6000);
+        assert_eq!(
+            client.try_update_config(&admin, &too_much),
+            Err(Ok(SharedError::InvalidBps))
+        );
+
+        client.update_config(&admin, &config);
+
+        env.ledger().set_timestamp(1000);
+        let lock_id = client.lock(
+            &user,
+            &lp_token.address,
+            &100_000_000_000,
+            &(1000 + 30 * 86400),
+        );
+
+        // 25B penalty: 50% stakers, 20% burn, 30% treasury
+        assert_eq!(client.early_unlock(&user, &lock_id), 75_000_000_000);
+        assert_eq!(lp_token.balance(&distributor), 12_500_000_000);
+        assert_eq!(lp_token.balance(&treasury), 7_500_000_000);
+        // The SAC supports `burn`, so nothing reaches the fallback address
+        assert_eq!(lp_token.balance(&burn_address), 0);
+        assert_eq!(lp_token.balance(&contract_id), 0);
+    }
 }
@@ -0,0 +1,163 @@
+#![no_std]
+
+//! # Multicall Contract
+//!
+//! Runs several contract queries in one invocation, so a front-end can fetch
+//! config, balances and user positions with a single RPC simulation instead
+//! of one per value.
+//!
+//! ## Features
+//! - `aggregate`: all calls must succeed, results in call order
+//! - `aggregate_partial`: failing calls are reported instead of aborting
+//! - Stateless: no admin, no storage
+//!
+//! Intended for simulation. The calls are made as this contract, so a call
+//! that requires someone else's authorization fails.
+
+use astro_core_shared::types::SharedError;
+use soroban_sdk::{contract, contractimpl, contracttype, Address, Env, Error, Symbol, Val, Vec};
+
+// ════════════════════════════════════════════════════════════════════════════
+// Constants
+// ════════════════════════════════════════════════════════════════════════════
+
+/// Maximum number of calls per batch
+pub const MAX_CALLS: u32 = 32;
+
+// ════════════════════════════════════════════════════════════════════════════
+// Types
+// ════════════════════════════════════════════════════════════════════════════
+
+/// One query in a batch
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct CallSpec {
+    /// Contract to call
+    pub contract: Address,
+    /// Function name
+    pub function: Symbol,
+    /// Arguments, in order
+    pub args: Vec<Val>,
+}
+
+// ════════════════════════════════════════════════════════════════════════════
+// Contract Implementation
+// ════════════════════════════════════════════════════════════════════════════
+
+#[contract]
+pub struct Multicall;
+
+#[contractimpl]
+impl Multicall {
+    /// Run every call in order and return their results.
+    /// Fails as a whole if any call fails.
+    pub fn aggregate(env: Env, calls: Vec<CallSpec>) -> Result<Vec<Val>, SharedError> {
+        Self::require_batch_size(&calls)?;
+
+        let mut results = Vec::new(&env);
+        for call in calls.iter() {
+            let value: Val = env.invoke_contract(&call.contract, &call.function, call.args);
+            results.push_back(value);
+        }
+        Ok(results)
+    }
+
+    /// Run every call in order, reporting failed calls instead of failing.
+    /// Returns `(success, values)` in call order; a failed call's value is void.
+    pub fn aggregate_partial(
+        env: Env,
+        calls: Vec<CallSpec>,
+    ) -> Result<(Vec<bool>, Vec<Val>), SharedError> {
+        Self::require_batch_size(&calls)?;
+
+        let mut success = Vec::new(&env);
+        let mut values = Vec::new(&env);
+        for call in calls.iter() {
+            match env.try_invoke_contract::<Val, Error>(&call.contract, &call.function, call.args) {
+                Ok(Ok(value)) => {
+                    success.push_back(true);
+                    values.push_back(value);
+                }
+                _ => {
+                    success.push_back(false);
+                    values.push_back(Val::VOID.into());
+                }
+            }
+        }
+        Ok((success, values))
+    }
+
+    // ────────────────────────────────────────────────────────────────────────
+    // Internal Functions
+    // ────────────────────────────────────────────────────────────────────────
+
+    fn require_batch_size(calls: &Vec<CallSpec>) -> Result<(), SharedError> {
+        if calls.len() > MAX_CALLS {
+            return Err(SharedError::LimitExceeded);
+        }
+        Ok(())
+    }
+}
+
+// ════════════════════════════════════════════════════════════════════════════
+// Tests
+// ════════════════════════════════════════════════════════════════════════════
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use astro_core_shared::testutils::create_token;
+    use soroban_sdk::{testutils::Address as _, vec, IntoVal, TryFromVal};
+
+    #[test]
+    fn test_aggregate() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let client = MulticallClient::new(&env, &env.register(Multicall, ()));
+
+        let admin = Address::generate(&env);
+        let user = Address::generate(&env);
+        let sac = create_token(&env, &admin);
+        sac.mint(&user, 1_000);
+
+        let balance = CallSpec {
+            contract: sac.address.clone(),
+            function: Symbol::new(&env, "balance"),
+            args: vec![&env, user.into_val(&env)],
+        };
+        let decimals = CallSpec {
+            contract: sac.address.clone(),
+            function: Symbol::new(&env, "decimals"),
+            args: Vec::new(&env),
+        };
+        let missing = CallSpec {
+            contract: sac.address.clone(),
+            function: Symbol::new(&env, "no_such_fn"),
+            args: Vec::new(&env),
+        };
+
+        let results = client.aggregate(&vec![&env, balance.clone(), decimals.clone()]);
+        assert_eq!(
+            i128::try_from_val(&env, &results.get(0).unwrap()).unwrap(),
+            1_000
+        );
+        assert_eq!(
+            u32::try_from_val(&env, &results.get(1).unwrap()).unwrap(),
+            7
+        );
+
+        let (success, values) = client.aggregate_partial(&vec![&env, missing, balance]);
+        assert_eq!(success, vec![&env, false, true]);
+        assert!(values.get(0).unwrap().is_void());
+        assert_eq!(
+            i128::try_from_val(&env, &values.get(1).unwrap()).unwrap(),
+            1_000
+        );
+
+        let mut too_many = Vec::new(&env);
+        for _ in 0..=MAX_CALLS {
+            too_many.push_back(decimals.clone());
+        }
+        assert!(client.try_aggregate_partial(&too_many).is_err());
+    }
+}
@@ -0,0 +1,564 @@
+#![no_std]
+
+//! # Oracle Contract
+//!
+//! Time-weighted average prices for AMM pairs, for the treasury rate limiter
+//! and lending integrations that need manipulation-resistant prices.
+//!
+//! ## Features
+//! - Periodic observations of each tracked pair's price accumulators
+//! - `get_twap(pair, window)` over the newest observation at least `window`
+//!   seconds old
+//! - `consult` to value an amount of one pair token in the other
+//!
+//! ## Observations
+//! Anyone may call `update(pair)`; an observation is recorded at most once per
+//! `OBSERVATION_PERIOD` and the newest `MAX_OBSERVATIONS` are kept. A TWAP is
+//! refused if the observation it would use is more than twice the requested
+//! window old, so a stale history can't pass for a recent average.
+
+use astro_core_shared::{
+    events::EventBuilder,
+    interfaces::{AmmPairClient, IntoSharedResult},
+    math::{mul_div_down, PRECISION},
+    types::{
+        extend_instance_ttl, get_persistent, get_ttl_config, set_persistent, set_ttl_config,
+        ContractInfo, SharedError, TtlConfig,
+    },
+    upgrade::{self, PendingUpgrade},
+};
+use soroban_sdk::{contract, contractimpl, contracttype, Address, BytesN, Env, Vec};
+
+// ════════════════════════════════════════════════════════════════════════════
+// Constants
+// ════════════════════════════════════════════════════════════════════════════
+
+/// Storage layout version of this code (see `migrate`)
+const STORAGE_VERSION: u32 = 1;
+
+/// Minimum time between observations (15 minutes)
+const OBSERVATION_PERIOD: u64 = 15 * 60;
+
+/// Observations kept per pair (24 hours at one per period)
+const MAX_OBSERVATIONS: u32 = 96;
+
+// ════════════════════════════════════════════════════════════════════════════
+// Storage Keys
+// ════════════════════════════════════════════════════════════════════════════
+
+#[contracttype]
+#[derive(Clone, Debug)]
+pub enum DataKey {
+    /// Admin address
+    Admin,
+    /// Whether contract is initialized
+    Initialized,
+    /// Whether contract is paused
+    Paused,
+    /// Observations of a tracked pair, oldest first (Address -> Vec<Observation>)
+    Observations(Address),
+    /// Protocol registry address (reported by `contract_info`)
+    Registry,
+}
+
+// ════════════════════════════════════════════════════════════════════════════
+// Types
+// ════════════════════════════════════════════════════════════════════════════
+
+/// A sample of a pair's price accumulators
+#[contracttype]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Observation {
+    /// Ledger timestamp of the sample
+    pub timestamp: u64,
+    /// Cumulative price of token 0 in token 1
+    pub price_0_cumulative: i128,
+    /// Cumulative price of token 1 in token 0
+    pub price_1_cumulative: i128,
+}
+
+// ════════════════════════════════════════════════════════════════════════════
+// Contract Implementation
+// ════════════════════════════════════════════════════════════════════════════
+
+#[contract]
+pub struct Oracle;
+
+#[contractimpl]
+impl Oracle {
+    // ────────────────────────────────────────────────────────────────────────
+    // Initialization
+    // ────────────────────────────────────────────────────────────────────────
+
+    /// Initialize the oracle
+    pub fn initialize(env: Env, admin: Address) -> Result<(), SharedError> {
+        if env.storage().instance().has(&DataKey::Initialized) {
+            return Err(SharedError::AlreadyInitialized);
+        }
+
+        env.storage().instance().set(&DataKey::Admin, &admin);
+        env.storage().instance().set(&DataKey::Initialized, &true);
+        upgrade::init_version(&env, STORAGE_VERSION);
+        env.storage().instance().set(&DataKey::Paused, &false);
+
+        extend_instance_ttl(&env);
+
+        let events = EventBuilder::new(&env);
+        events.publish("oracle", "initialized", (admin, env.ledger().timestamp()));
+
+        Ok(())
+    }
+
+    // ────────────────────────────────────────────────────────────────────────
+    // Observations
+    // ────────────────────────────────────────────────────────────────────────
+
+    /// Record an observation of a tracked pair (callable by anyone).
+    /// Returns `false` if the last observation is younger than a period.
+    pub fn update(env: Env, pair: Address) -> Result<bool, SharedError> {
+        Self::require_initialized(&env)?;
+        Self::require_not_paused(&env)?;
+
+        let mut observations = Self::load_observations(&env, &pair)?;
+        let now = env.ledger().timestamp();
+        if let Some(last) = observations.last() {
+            if now < last.timestamp + OBSERVATION_PERIOD {
+                return Ok(false);
+            }
+        }
+
+        let observation = Self::observe(&env, &pair);
+        if observations.len() >= MAX_OBSERVATIONS {
+            observations.pop_front();
+        }
+        observations.push_back(observation.clone());
+        Self::save_observations(&env, &pair, &observations);
+
+        let events = EventBuilder::new(&env);
+        events.publish(
+            "oracle",
+            "observed",
+            (
+                pair,
+                observation.price_0_cumulative,
+                observation.price_1_cumulative,
+                now,
+            ),
+        );
+
+        extend_instance_ttl(&env);
+        Ok(true)
+    }
+
+    /// Get `(price_0, price_1)` averaged from the newest observation at least
+    /// `window` seconds old until now. Prices are `PRECISION`-scaled; `price_0`
+    /// is token 1 per token 0.
+    pub fn get_twap(env: Env, pair: Address, window: u64) -> Result<(i128, i128), SharedError> {
+        if window == 0 {
+            return Err(SharedError::InvalidTimestamp);
+        }
+
+        let observations = Self::load_observations(&env, &pair)?;
+        let now = env.ledger().timestamp();
+        let cutoff = now.checked_sub(window).ok_or(SharedError::InvalidState)?;
+
+        let mut start = None;
+        for observation in observations.iter().rev() {
+            if observation.timestamp <= cutoff {
+                start = Some(observation);
+                break;
+            }
+        }
+        let start = start.ok_or(SharedError::InvalidState)?;
+
+        let elapsed = now - start.timestamp;
+        if elapsed > window.saturating_mul(2) {
+            return Err(SharedError::InvalidState);
+        }
+
+        let current = Self::observe(&env, &pair);
+        let elapsed = elapsed as i128;
+        Ok((
+            current
+                .price_0_cumulative
+                .wrapping_sub(start.price_0_cumulative)
+                / elapsed,
+            current
+                .price_1_cumulative
+                .wrapping_sub(start.price_1_cumulative)
+                / elapsed,
+        ))
+    }
+
+    /// Value `amount_in` of `token_in` in the pair's other token at the TWAP
+    pub fn consult(
+        env: Env,
+        pair: Address,
+        token_in: Address,
+        amount_in: i128,
+        window: u64,
+    ) -> Result<i128, SharedError> {
+        if amount_in < 0 {
+            return Err(SharedError::InvalidAmount);
+        }
+
+        let (price_0, price_1) = Self::get_twap(env.clone(), pair.clone(), window)?;
+        let pair_client = AmmPairClient::new(&env, &pair);
+        let price = if token_in == pair_client.try_token_0().into_shared()? {
+            price_0
+        } else if token_in == pair_client.try_token_1().into_shared()? {
+            price_1
+        } else {
+            return Err(SharedError::TokenNotFound);
+        };
+
+        mul_div_down(amount_in, price, PRECISION)
+    }
+
+    // ────────────────────────────────────────────────────────────────────────
+    // Admin Functions
+    // ────────────────────────────────────────────────────────────────────────
+
+    /// Start tracking a pair, recording its first observation
+    pub fn add_pair(env: Env, pair: Address) -> Result<(), SharedError> {
+        Self::require_admin(&env)?;
+
+        let key = DataKey::Observations(pair.clone());
+        if env.storage().persistent().has(&key) {
+            return Err(SharedError::AlreadyInitialized);
+        }
+
+        let observations = Vec::from_array(&env, [Self::observe(&env, &pair)]);
+        Self::save_observations(&env, &pair, &observations);
+
+        let events = EventBuilder::new(&env);
+        events.publish("oracle", "pair_added", (pair, env.ledger().timestamp()));
+
+        extend_instance_ttl(&env);
+        Ok(())
+    }
+
+    /// Stop tracking a pair and drop its observations
+    pub fn remove_pair(env: Env, pair: Address) -> Result<(), SharedError> {
+        Self::require_admin(&env)?;
+
+        let key = DataKey::Observations(pair.clone());
+        if !env.storage().persistent().has(&key) {
+            return Err(SharedError::TokenNotFound);
+        }
+        env.storage().persistent().remove(&key);
+
+        let events = EventBuilder::new(&env);
+        events.publish("oracle", "pair_removed", (pair, env.ledger().timestamp()));
+
+        extend_instance_ttl(&env);
+        Ok(())
+    }
+
+    /// Set the protocol registry address reported by `contract_info`
+    pub fn set_registry(env: Env, registry: Address) -> Result<(), SharedError> {
+        Self::require_admin(&env)?;
+        env.storage().instance().set(&DataKey::Registry, &registry);
+        extend_instance_ttl(&env);
+        Ok(())
+    }
+
+    /// Set admin address
+    pub fn set_admin(env: Env, new_admin: Address) -> Result<(), SharedError> {
+        Self::require_admin(&env)?;
+
+        env.storage().instance().set(&DataKey::Admin, &new_admin);
+        extend_instance_ttl(&env);
+
+        Ok(())
+    }
+
+    /// Pause/unpause observations
+    pub fn set_paused(env: Env, paused: bool) -> Result<(), SharedError> {
+        Self::require_admin(&env)?;
+
+        env.storage().instance().set(&DataKey::Paused, &paused);
+        extend_instance_ttl(&env);
+
+        Ok(())
+    }
+
+    /// Set how far storage TTLs are extended (see `TtlConfig`)
+    pub fn set_ttl_config(env: Env, config: TtlConfig) -> Result<(), SharedError> {
+        Self::require_admin(&env)?;
+
+        set_ttl_config(&env, &config)?;
+        extend_instance_ttl(&env);
+
+        Ok(())
+    }
+
+    // ────────────────────────────────────────────────────────────────────────
+    // Upgrades (admin only)
+    // ────────────────────────────────────────────────────────────────────────
+
+    /// Queue a WASM upgrade behind the upgrade timelock. Returns its eta.
+    pub fn queue_upgrade(env: Env, new_wasm_hash: BytesN<32>) -> Result<u64, SharedError> {
+        Self::require_admin(&env)?;
+        upgrade::queue(&env, new_wasm_hash)
+    }
+
+    /// Cancel the queued upgrade
+    pub fn cancel_upgrade(env: Env) -> Result<(), SharedError> {
+        Self::require_admin(&env)?;
+        upgrade::cancel(&env)
+    }
+
+    /// Switch to the queued WASM once the timelock has elapsed
+    pub fn upgrade(env: Env, new_wasm_hash: BytesN<32>) -> Result<(), SharedError> {
+        Self::require_admin(&env)?;
+        upgrade::execute(&env, new_wasm_hash)
+    }
+
+    /// Migrate storage to `STORAGE_VERSION` after an upgrade
+    pub fn migrate(env: Env) -> Result<u32, SharedError> {
+        Self::require_admin(&env)?;
+        upgrade::migrate(&env, STORAGE_VERSION, |_, _| Ok(()))
+    }
+
+    // ────────────────────────────────────────────────────────────────────────
+    // Query Functions
+    // ────────────────────────────────────────────────────────────────────────
+
+    /// Get the storage layout version
+    pub fn version(env: Env) -> u32 {
+        upgrade::version(&env)
+    }
+
+    /// Get the queued upgrade, if any
+    pub fn get_pending_upgrade(env: Env) -> Option<PendingUpgrade> {
+        upgrade::pending(&env)
+    }
+
+    /// Get a pair's observations, oldest first
+    pub fn get_observations(env: Env, pair: Address) -> Vec<Observation> {
+        get_persistent(&env, &DataKey::Observations(pair)).unwrap_or(Vec::new(&env))
+    }
+
+    /// Check if a pair is tracked
+    pub fn is_tracked(env: Env, pair: Address) -> bool {
+        env.storage().persistent().has(&DataKey::Observations(pair))
+    }
+
+    /// Get admin address
+    pub fn admin(env: Env) -> Result<Address, SharedError> {
+        env.storage()
+            .instance()
+            .get(&DataKey::Admin)
+            .ok_or(SharedError::NotInitialized)
+    }
+
+    /// Get the storage TTL config
+    pub fn ttl_config(env: Env) -> TtlConfig {
+        get_ttl_config(&env)
+    }
+
+    /// Check if contract is paused
+    pub fn is_paused(env: Env) -> bool {
+        env.storage()
+            .instance()
+            .get(&DataKey::Paused)
+            .unwrap_or(false)
+    }
+
+    /// Describe this contract for explorers and wallets
+    pub fn contract_info(env: Env) -> Result<ContractInfo, SharedError> {
+        let admin = Self::admin(env.clone())?;
+        let info = ContractInfo::new(
+            &env,
+            "oracle",
+            env!("CARGO_PKG_VERSION"),
+            admin,
+            Self::is_paused(env.clone()),
+            env.storage().instance().get(&DataKey::Registry),
+        );
+        Ok(info
+            .with("observation_period", OBSERVATION_PERIOD as i128)
+            .with("max_observations", MAX_OBSERVATIONS as i128))
+    }
+
+    // ────────────────────────────────────────────────────────────────────────
+    // Internal Functions
+    // ────────────────────────────────────────────────────────────────────────
+
+    fn require_initialized(env: &Env) -> Result<(), SharedError> {
+        let initialized: bool = env
+            .storage()
+            .instance()
+            .get(&DataKey::Initialized)
+            .unwrap_or(false);
+
+        if !initialized {
+            return Err(SharedError::NotInitialized);
+        }
+        Ok(())
+    }
+
+    fn require_not_paused(env: &Env) -> Result<(), SharedError> {
+        let paused: bool = env
+            .storage()
+            .instance()
+            .get(&DataKey::Paused)
+            .unwrap_or(false);
+
+        if paused {
+            return Err(SharedError::ContractPaused);
+        }
+        Ok(())
+    }
+
+    fn require_admin(env: &Env) -> Result<(), SharedError> {
+        let admin: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::Admin)
+            .ok_or(SharedError::NotInitialized)?;
+
+        admin.require_auth();
+        Ok(())
+    }
+
+    fn observe(env: &Env, pair: &Address) -> Observation {
+        let (price_0_cumulative, price_1_cumulative, timestamp) =
+            AmmPairClient::new(env, pair).get_price_cumulatives();
+        Observation {
+            timestamp,
+            price_0_cumulative,
+            price_1_cumulative,
+        }
+    }
+
+    fn load_observations(env: &Env, pair: &Address) -> Result<Vec<Observation>, SharedError> {
+        get_persistent(env, &DataKey::Observations(pair.clone())).ok_or(SharedError::TokenNotFound)
+    }
+
+    fn save_observations(env: &Env, pair: &Address, observations: &Vec<Observation>) {
+        let key = DataKey::Observations(pair.clone());
+        set_persistent(env, &key, observations);
+    }
+}
+
+// ════════════════════════════════════════════════════════════════════════════
+// Tests
+// ════════════════════════════════════════════════════════════════════════════
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use astro_core_shared::testutils::create_token;
+    use astro_pair::{AmmPair, AmmPairClient as PairClient};
+    use soroban_sdk::testutils::{Address as _, Ledger as _};
+
+    struct Setup<'a> {
+        env: Env,
+        client: OracleClient<'a>,
+        pair: PairClient<'a>,
+        token_0: Address,
+        user: Address,
+    }
+
+    fn setup<'a>() -> Setup<'a> {
+        let env = Env::default();
+        env.mock_all_auths();
+        env.ledger().set_timestamp(10_000);
+
+        let admin = Address::generate(&env);
+        let user = Address::generate(&env);
+        let token_0 = create_token(&env, &admin);
+        let token_1 = create_token(&env, &admin);
+        token_0.mint(&user, 1_000_000_000_000);
+        token_1.mint(&user, 1_000_000_000_000);
+
+        // No fee distributor: pair is initialized with the admin as fee_to
+        // and a zero protocol fee
+        let pair = PairClient::new(&env, &env.register(AmmPair, ()));
+        pair.initialize(
+            &token_0.address,
+            &token_1.address,
+            &Address::generate(&env),
+            &admin,
+        );
+        pair.set_fees(&25, &0);
+        pair.add_liquidity(&user, &100_000_000_000, &200_000_000_000, &0, &0, &u64::MAX);
+
+        let client = OracleClient::new(&env, &env.register(Oracle, ()));
+        client.initialize(&admin);
+        client.add_pair(&pair.address);
+
+        Setup {
+            env,
+            client,
+            pair,
+            token_0: token_0.address.clone(),
+            user,
+        }
+    }
+
+    #[test]
+    fn test_update_respects_period() {
+        let s = setup();
+        assert!(s.client.is_tracked(&s.pair.address));
+        assert!(s.client.try_add_pair(&s.pair.address).is_err());
+
+        assert!(!s.client.update(&s.pair.address));
+        s.env.ledger().set_timestamp(10_000 + OBSERVATION_PERIOD);
+        assert!(s.client.update(&s.pair.address));
+        assert_eq!(s.client.get_observations(&s.pair.address).len(), 2);
+
+        // Only the newest observations are kept
+        for i in 2..=MAX_OBSERVATIONS as u64 + 5 {
+            s.env
+                .ledger()
+                .set_timestamp(10_000 + i * OBSERVATION_PERIOD);
+            s.client.update(&s.pair.address);
+        }
+        let observations = s.client.get_observations(&s.pair.address);
+        assert_eq!(observations.len(), MAX_OBSERVATIONS);
+        assert_eq!(
+            observations.last().unwrap().timestamp,
+            10_000 + (MAX_OBSERVATIONS as u64 + 5) * OBSERVATION_PERIOD
+        );
+
+        let untracked = Address::generate(&s.env);
+        assert!(s.client.try_update(&untracked).is_err());
+    }
+
+    #[test]
+    fn test_twap_resists_spot_moves() {
+        let s = setup();
+
+        // Not enough history yet
+        assert!(s.client.try_get_twap(&s.pair.address, &3_600).is_err());
+
+        // Price 2.0 held for an hour, then a large swap right before reading
+        s.env.ledger().set_timestamp(10_000 + 3_600);
+        s.pair.swap(&s.user, &s.token_0, &100_000_000_000, &0);
+        let (price_0, price_1) = s.client.get_twap(&s.pair.address, &3_600);
+        assert_eq!(price_0, 2 * PRECISION);
+        assert_eq!(price_1, PRECISION / 2);
+        assert_eq!(
+            s.client
+                .consult(&s.pair.address, &s.token_0, &1_000_000, &3_600),
+            2_000_000
+        );
+        let other = Address::generate(&s.env);
+        assert!(s
+            .client
+            .try_consult(&s.pair.address, &other, &1_000_000, &3_600)
+            .is_err());
+
+        // The swapped price moves the average only as it is held
+        s.env.ledger().set_timestamp(10_000 + 7_200);
+        let (price_0, _) = s.client.get_twap(&s.pair.address, &3_600);
+        assert!(price_0 < 2 * PRECISION && price_0 > PRECISION / 2);
+
+        // History older than twice the window is stale
+        s.env.ledger().set_timestamp(10_000 + 7_201);
+        assert!(s.client.try_get_twap(&s.pair.address, &3_600).is_err());
+    }
+}
@@ -0,0 +1,1393 @@
+#![no_std]
+
+//! # AMM Pair Contract
+//!
+//! Constant-product (x * y = k) pool for a single token pair.
+//!
+//! ## Features
+//! - Liquidity provision with LP share minting (the pair is its own LP token)
+//! - Swaps priced by the shared `get_amount_out` helper, checked with
+//!   `verify_k_invariant`
+//! - LP fee left in the pool for liquidity providers
+//! - Protocol fee routed to the fee distributor on every swap, less the
+//!   swapper's referral share when a referral registry is set; fees the
+//!   distributor rejects are held for `flush_protocol_fees`
+//! - Cumulative price accumulators for time-weighted average prices
+//!
+//! ## LP Shares
+//! The first deposit mints `sqrt(amount_0 * amount_1)` shares, of which
+//! `MINIMUM_LIQUIDITY` are locked in the pair forever so the share price can
+//! never be inflated from zero. LP shares expose `balance`/`transfer`, so the
+//! locker can hold them like any other token.
+//!
+//! ## Price Accumulators
+//! Before reserves change, each price (`PRECISION`-scaled) is multiplied by
+//! the seconds it was in effect and added to a wrapping accumulator. The
+//! oracle samples `get_price_cumulatives` and divides the difference between
+//! two samples by the elapsed time, so a price must be held for the whole
+//! window to move the average.
+
+use astro_core_shared::{
+    events::{emit_fee_distributor_changed, EventBuilder},
+    interfaces::{FeeDistributorClient, IntoSharedResult, ReferralClient},
+    limits::MIN_FEE_AMOUNT,
+    math::{
+        apply_bps_round_up, get_amount_out, min, mul_div_down, quote, safe_add, safe_mul, safe_sub,
+        sqrt, update_reserves_add, update_reserves_sub, update_reserves_swap, verify_k_invariant,
+        MIN_TRADE_AMOUNT, PRECISION, STELLAR_DECIMALS,
+    },
+    reentrancy::SimpleReentrancyGuard,
+    types::{
+        check_deadline, extend_instance_ttl, get_persistent, get_ttl_config, set_persistent,
+        set_ttl_config, ContractInfo, FeeConfig, SharedError, TtlConfig,
+    },
+    upgrade::{self, PendingUpgrade},
+};
+use soroban_sdk::{
+    auth::{ContractContext, InvokerContractAuthEntry, SubContractInvocation},
+    contract, contractimpl, contracttype, token, vec, Address, BytesN, Env, IntoVal, String,
+    Symbol, Vec,
+};
+
+// ════════════════════════════════════════════════════════════════════════════
+// Constants
+// ════════════════════════════════════════════════════════════════════════════
+
+/// Storage layout version of this code (see `migrate`)
+const STORAGE_VERSION: u32 = 1;
+
+/// LP shares locked in the pair on the first deposit
+const MINIMUM_LIQUIDITY: i128 = 1_000;
+
+/// Default LP fee (0.25%)
+const DEFAULT_LP_FEE_BPS: u32 = 25;
+
+/// Default protocol fee (0.05%)
+const DEFAULT_PROTOCOL_FEE_BPS: u32 = 5;
+
+// ════════════════════════════════════════════════════════════════════════════
+// Storage Keys
+// ════════════════════════════════════════════════════════════════════════════
+
+#[contracttype]
+#[derive(Clone, Debug)]
+pub enum DataKey {
+    /// Admin address (the factory)
+    Admin,
+    /// Whether contract is initialized
+    Initialized,
+    /// Whether contract is paused
+    Paused,
+    /// First token of the pair
+    Token0,
+    /// Second token of the pair
+    Token1,
+    /// Reserves of both tokens ((i128, i128))
+    Reserves,
+    /// Total LP shares outstanding
+    TotalSupply,
+    /// LP share balance (Address -> i128)
+    Balance(Address),
+    /// Fee distributor receiving protocol fees
+    FeeTo,
+    /// LP fee in basis points
+    LpFeeBps,
+    /// Protocol fee in basis points
+    ProtocolFeeBps,
+    /// Protocol fees paid out of the pair per token (Address -> i128)
+    TotalProtocolFees(Address),
+    /// Protocol registry address (reported by `contract_info`)
+    Registry,
+    /// Price accumulators ((price_0_cumulative, price_1_cumulative, timestamp))
+    PriceCumulative,
+    /// Referral registry taking referrers' shares of protocol fees
+    Referral,
+    /// Protocol fees held in the pair: dust below `MIN_FEE_AMOUNT` and fees
+    /// the fee distributor rejected (Address -> i128)
+    PendingProtocolFees(Address),
+}
+
+// ════════════════════════════════════════════════════════════════════════════
+// Contract Implementation
+// ════════════════════════════════════════════════════════════════════════════
+
+#[contract]
+pub struct AmmPair;
+
+#[contractimpl]
+impl AmmPair {
+    // ────────────────────────────────────────────────────────────────────────
+    // Initialization
+    // ────────────────────────────────────────────────────────────────────────
+
+    /// Initialize the pair. The factory becomes the admin; protocol fees are
+    /// routed to `fee_to` (the fee distributor).
+    pub fn initialize(
+        env: Env,
+        token_0: Address,
+        token_1: Address,
+        factory: Address,
+        fee_to: Address,
+    ) -> Result<(), SharedError> {
+        if env.storage().instance().has(&DataKey::Initialized) {
+            return Err(SharedError::AlreadyInitialized);
+        }
+        factory.require_auth();
+
+        if token_0 == token_1 {
+            return Err(SharedError::InvalidAddress);
+        }
+
+        env.storage().instance().set(&DataKey::Admin, &factory);
+        env.storage().instance().set(&DataKey::Token0, &token_0);
+        env.storage().instance().set(&DataKey::Token1, &token_1);
+        env.storage().instance().set(&DataKey::FeeTo, &fee_to);
+        env.storage()
+            .instance()
+            .set(&DataKey::LpFeeBps, &DEFAULT_LP_FEE_BPS);
+        env.storage()
+            .instance()
+            .set(&DataKey::ProtocolFeeBps, &DEFAULT_PROTOCOL_FEE_BPS);
+        env.storage()
+            .instance()
+            .set(&DataKey::Reserves, &(0_i128, 0_i128));
+        env.storage().instance().set(
+            &DataKey::PriceCumulative,
+            &(0_i128, 0_i128, env.ledger().timestamp()),
+        );
+        env.storage().instance().set(&DataKey::TotalSupply, &0_i128);
+        env.storage().instance().set(&DataKey::Initialized, &true);
+        upgrade::init_version(&env, STORAGE_VERSION);
+        env.storage().instance().set(&DataKey::Paused, &false);
+
+        extend_instance_ttl(&env);
+
+        let events = EventBuilder::new(&env);
+        events.publish(
+            "pair",
+            "initialized",
+            (token_0, token_1, factory, env.ledger().timestamp()),
+        );
+
+        Ok(())
+    }
+
+    // ────────────────────────────────────────────────────────────────────────
+    // Liquidity
+    // ────────────────────────────────────────────────────────────────────────
+
+    /// Deposit both tokens and mint LP shares.
+    /// Amounts are trimmed to the current pool ratio; returns
+    /// `(amount_0, amount_1, liquidity)` actually used and minted.
+    pub fn add_liquidity(
+        env: Env,
+        sender: Address,
+        amount_0: i128,
+        amount_1: i128,
+        min_0: i128,
+        min_1: i128,
+        deadline: u64,
+    ) -> Result<(i128, i128, i128), SharedError> {
+        sender.require_auth();
+        Self::require_initialized(&env)?;
+        Self::require_not_paused(&env)?;
+        check_deadline(&env, deadline)?;
+        let _guard = SimpleReentrancyGuard::acquire(&env)?;
+
+        if amount_0 <= 0 || amount_1 <= 0 {
+            return Err(SharedError::InvalidAmount);
+        }
+
+        let (reserve_0, reserve_1) = Self::load_reserves(&env);
+        let total_supply = Self::get_total_supply(&env);
+
+        // Match the pool ratio, keeping the side the sender over-supplied
+        let (used_0, used_1) = if reserve_0 == 0 && reserve_1 == 0 {
+            (amount_0, amount_1)
+        } else {
+            let optimal_1 = quote(amount_0, reserve_0, reserve_1)?;
+            if optimal_1 <= amount_1 {
+                (amount_0, optimal_1)
+            } else {
+                (quote(amount_1, reserve_1, reserve_0)?, amount_1)
+            }
+        };
+        if used_0 < min_0 || used_1 < min_1 {
+            return Err(SharedError::BelowMinimum);
+        }
+
+        let liquidity = if total_supply == 0 {
+            let initial = sqrt(safe_mul(used_0, used_1)?);
+            if initial <= MINIMUM_LIQUIDITY {
+                return Err(SharedError::BelowMinimum);
+            }
+            Self::mint_shares(&env, &env.current_contract_address(), MINIMUM_LIQUIDITY)?;
+            initial - MINIMUM_LIQUIDITY
+        } else {
+            min(
+                mul_div_down(used_0, total_supply, reserve_0)?,
+                mul_div_down(used_1, total_supply, reserve_1)?,
+            )
+        };
+        if liquidity <= 0 {
+            return Err(SharedError::BelowMinimum);
+        }
+
+        let this = env.current_contract_address();
+        token::Client::new(&env, &Self::load_token_0(&env)?).transfer(&sender, &this, &used_0);
+        token::Client::new(&env, &Self::load_token_1(&env)?).transfer(&sender, &this, &used_1);
+
+        let reserves = update_reserves_add(reserve_0, reserve_1, used_0, used_1)?;
+        Self::store_reserves(&env, reserves)?;
+        Self::mint_shares(&env, &sender, liquidity)?;
+
+        let events = EventBuilder::new(&env);
+        events.publish(
+            "pair",
+            "add_liquidity",
+            (sender, used_0, used_1, liquidity, env.ledger().timestamp()),
+        );
+
+        extend_instance_ttl(&env);
+        Ok((used_0, used_1, liquidity))
+    }
+
+    /// Burn LP shares and withdraw the proportional reserves.
+    /// Returns `(amount_0, amount_1)`.
+    pub fn remove_liquidity(
+        env: Env,
+        sender: Address,
+        liquidity: i128,
+        min_0: i128,
+        min_1: i128,
+        deadline: u64,
+    ) -> Result<(i128, i128), SharedError> {
+        sender.require_auth();
+        Self::require_initialized(&env)?;
+        check_deadline(&env, deadline)?;
+        let _guard = SimpleReentrancyGuard::acquire(&env)?;
+
+        if liquidity <= 0 {
+            return Err(SharedError::InvalidAmount);
+        }
+
+        let (reserve_0, reserve_1) = Self::load_reserves(&env);
+        let total_supply = Self::get_total_supply(&env);
+
+        let amount_0 = mul_div_down(liquidity, reserve_0, total_supply)?;
+        let amount_1 = mul_div_down(liquidity, reserve_1, total_supply)?;
+        if amount_0 <= 0 || amount_1 <= 0 {
+            return Err(SharedError::InvalidAmount);
+        }
+        if amount_0 < min_0 || amount_1 < min_1 {
+            return Err(SharedError::BelowMinimum);
+        }
+
+        Self::burn_shares(&env, &sender, liquidity)?;
+        let reserves = update_reserves_sub(reserve_0, reserve_1, amount_0, amount_1)?;
+        Self::store_reserves(&env, reserves)?;
+
+        let this = env.current_contract_address();
+        token::Client::new(&env, &Self::load_token_0(&env)?).transfer(&this, &sender, &amount_0);
+        token::Client::new(&env, &Self::load_token_1(&env)?).transfer(&this, &sender, &amount_1);
+
+        let events = EventBuilder::new(&env);
+        events.publish(
+            "pair",
+            "remove_liquidity",
+            (
+                sender,
+                amount_0,
+                amount_1,
+                liquidity,
+                env.ledger().timestamp(),
+            ),
+        );
+
+        extend_instance_ttl(&env);
+        Ok((amount_0, amount_1))
+    }
+
+    // ────────────────────────────────────────────────────────────────────────
+    // Swaps
+    // ────────────────────────────────────────────────────────────────────────
+
+    /// Swap `amount_in` of `token_in` for the other token.
+    /// The protocol fee is taken from the input and sent to the fee
+    /// distributor; the LP fee stays in the pool.
+    pub fn swap(
+        env: Env,
+        user: Address,
+        token_in: Address,
+        amount_in: i128,
+        min_out: i128,
+    ) -> Result<i128, SharedError> {
+        user.require_auth();
+        Self::require_initialized(&env)?;
+        Self::require_not_paused(&env)?;
+        let _guard = SimpleReentrancyGuard::acquire(&env)?;
+
+        if amount_in < MIN_TRADE_AMOUNT {
+            return Err(SharedError::BelowMinimum);
+        }
+
+        let token_0 = Self::load_token_0(&env)?;
+        let token_1 = Self::load_token_1(&env)?;
+        let is_token_0_in = if token_in == token_0 {
+            true
+        } else if token_in == token_1 {
+            false
+        } else {
+            return Err(SharedError::TokenNotFound);
+        };
+        let token_out = if is_token_0_in { token_1 } else { token_0 };
+
+        let (reserve_0, reserve_1) = Self::load_reserves(&env);
+        let (reserve_in, reserve_out) = if is_token_0_in {
+            (reserve_0, reserve_1)
+        } else {
+            (reserve_1, reserve_0)
+        };
+
+        // The fee distributor converting its own fees can't be re-entered
+        let protocol_fee = if Self::fee_to(env.clone()) == Some(user.clone()) {
+            0
+        } else {
+            Self::protocol_fee(&env, amount_in)?
+        };
+        let net_in = safe_sub(amount_in, protocol_fee)?;
+        let amount_out = get_amount_out(net_in, reserve_in, reserve_out, Self::load_lp_fee(&env))?;
+        if amount_out <= 0 || amount_out < min_out {
+            return Err(SharedError::BelowMinimum);
+        }
+
+        let (new_0, new_1) =
+            update_reserves_swap(reserve_in, reserve_out, net_in, amount_out, is_token_0_in)?;
+        if !verify_k_invariant(new_0, new_1, reserve_0, reserve_1)? {
+            return Err(SharedError::InvalidState);
+        }
+
+        let this = env.current_contract_address();
+        token::Client::new(&env, &token_in).transfer(&user, &this, &amount_in);
+        Self::store_reserves(&env, (new_0, new_1))?;
+        if protocol_fee > 0 {
+            Self::route_protocol_fee(&env, &user, &token_in, protocol_fee)?;
+        }
+        token::Client::new(&env, &token_out).transfer(&this, &user, &amount_out);
+
+        let events = EventBuilder::new(&env);
+        events.publish(
+            "pair",
+            "swap",
+            (
+                user,
+                token_in,
+                amount_in,
+                amount_out,
+                protocol_fee,
+                env.ledger().timestamp(),
+            ),
+        );
+
+        extend_instance_ttl(&env);
+        Ok(amount_out)
+    }
+
+    /// Retry handing protocol fees the fee distributor rejected earlier
+    /// (paused, source not allowlisted). Anyone can call it; fails while the
+    /// distributor still rejects them. Returns the amount sent (zero while
+    /// the held amount is below `MIN_FEE_AMOUNT`).
+    pub fn flush_protocol_fees(env: Env, token: Address) -> Result<i128, SharedError> {
+        Self::require_initialized(&env)?;
+        let _guard = SimpleReentrancyGuard::acquire(&env)?;
+
+        let fee_to: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::FeeTo)
+            .ok_or(SharedError::ExternalContractNotSet)?;
+        let key = DataKey::PendingProtocolFees(token.clone());
+        let pending: i128 = get_persistent(&env, &key).unwrap_or(0);
+        if pending < MIN_FEE_AMOUNT {
+            return Ok(0);
+        }
+
+        Self::authorize_transfer(&env, &token, &fee_to, pending);
+        FeeDistributorClient::new(&env, &fee_to)
+            .try_receive_fees(&env.current_contract_address(), &token, &pending)
+            .into_shared()?;
+        set_persistent(&env, &key, &0_i128);
+        Self::record_protocol_fees(&env, &token, pending)?;
+
+        let events = EventBuilder::new(&env);
+        events.publish(
+            "pair",
+            "fees_flushed",
+            (token, pending, env.ledger().timestamp()),
+        );
+
+        extend_instance_ttl(&env);
+        Ok(pending)
+    }
+
+    /// Output a swap of `amount_in` of `token_in` would receive right now
+    pub fn quote_swap(env: Env, token_in: Address, amount_in: i128) -> Result<i128, SharedError> {
+        let (reserve_0, reserve_1) = Self::load_reserves(&env);
+        let (reserve_in, reserve_out) = if token_in == Self::load_token_0(&env)? {
+            (reserve_0, reserve_1)
+        } else if token_in == Self::load_token_1(&env)? {
+            (reserve_1, reserve_0)
+        } else {
+            return Err(SharedError::TokenNotFound);
+        };
+
+        let net_in = safe_sub(amount_in, Self::protocol_fee(&env, amount_in)?)?;
+        get_amount_out(net_in, reserve_in, reserve_out, Self::load_lp_fee(&env))
+    }
+
+    // ────────────────────────────────────────────────────────────────────────
+    // LP Shares
+    // ────────────────────────────────────────────────────────────────────────
+
+    /// LP share balance of an address
+    pub fn balance(env: Env, id: Address) -> i128 {
+        Self::get_balance(&env, &id)
+    }
+
+    /// Transfer LP shares
+    pub fn transfer(env: Env, from: Address, to: Address, amount: i128) -> Result<(), SharedError> {
+        from.require_auth();
+        Self::require_initialized(&env)?;
+
+        if amount < 0 {
+            return Err(SharedError::InvalidAmount);
+        }
+
+        let from_balance = Self::get_balance(&env, &from);
+        if from_balance < amount {
+            return Err(SharedError::InsufficientBalance);
+        }
+        Self::set_balance(&env, &from, from_balance - amount);
+        let to_balance = safe_add(Self::get_balance(&env, &to), amount)?;
+        Self::set_balance(&env, &to, to_balance);
+
+        let events = EventBuilder::new(&env);
+        events.publish("pair", "transfer", (from, to, amount));
+
+        Ok(())
+    }
+
+    /// Total LP shares outstanding (including the locked minimum)
+    pub fn total_supply(env: Env) -> i128 {
+        Self::get_total_supply(&env)
+    }
+
+    /// LP share decimals
+    pub fn decimals(_env: Env) -> u32 {
+        STELLAR_DECIMALS
+    }
+
+    /// LP share name
+    pub fn name(env: Env) -> String {
+        String::from_str(&env, "Astro LP")
+    }
+
+    /// LP share symbol
+    pub fn symbol(env: Env) -> String {
+        String::from_str(&env, "ASTRO-LP")
+    }
+
+    // ────────────────────────────────────────────────────────────────────────
+    // Admin Functions
+    // ────────────────────────────────────────────────────────────────────────
+
+    /// Set the fee distributor receiving protocol fees
+    pub fn set_fee_to(env: Env, fee_to: Address) -> Result<(), SharedError> {
+        Self::require_admin(&env)?;
+
+        let old_fee_to = env.storage().instance().get(&DataKey::FeeTo);
+        env.storage().instance().set(&DataKey::FeeTo, &fee_to);
+        emit_fee_distributor_changed(&env, old_fee_to, &fee_to);
+        extend_instance_ttl(&env);
+
+        Ok(())
+    }
+
+    /// Set the referral registry paid from protocol fees
+    pub fn set_referral(env: Env, referral: Address) -> Result<(), SharedError> {
+        Self::require_admin(&env)?;
+
+        env.storage().instance().set(&DataKey::Referral, &referral);
+        extend_instance_ttl(&env);
+
+        Ok(())
+    }
+
+    /// Set LP and protocol fees (each at most 1%, 2% combined)
+    pub fn set_fees(env: Env, lp_fee_bps: u32, protocol_fee_bps: u32) -> Result<(), SharedError> {
+        Self::require_admin(&env)?;
+
+        if lp_fee_bps > FeeConfig::MAX_FEE_BPS
+            || protocol_fee_bps > FeeConfig::MAX_FEE_BPS
+            || lp_fee_bps + protocol_fee_bps > FeeConfig::MAX_TOTAL_FEE_BPS
+        {
+            return Err(SharedError::InvalidBps);
+        }
+
+        env.storage()
+            .instance()
+            .set(&DataKey::LpFeeBps, &lp_fee_bps);
+        env.storage()
+            .instance()
+            .set(&DataKey::ProtocolFeeBps, &protocol_fee_bps);
+
+        let events = EventBuilder::new(&env);
+        events.publish(
+            "pair",
+            "fees_set",
+            (lp_fee_bps, protocol_fee_bps, env.ledger().timestamp()),
+        );
+
+        extend_instance_ttl(&env);
+        Ok(())
+    }
+
+    /// Set the protocol registry address reported by `contract_info`
+    pub fn set_registry(env: Env, registry: Address) -> Result<(), SharedError> {
+        Self::require_admin(&env)?;
+        env.storage().instance().set(&DataKey::Registry, &registry);
+        extend_instance_ttl(&env);
+        Ok(())
+    }
+
+    /// Set admin address
+    pub fn set_admin(env: Env, new_admin: Address) -> Result<(), SharedError> {
+        Self::require_admin(&env)?;
+
+        env.storage().instance().set(&DataKey::Admin, &new_admin);
+        extend_instance_ttl(&env);
+
+        Ok(())
+    }
+
+    /// Pause/unpause swaps and deposits (withdrawals stay open)
+    pub fn set_paused(env: Env, paused: bool) -> Result<(), SharedError> {
+        Self::require_admin(&env)?;
+
+        env.storage().instance().set(&DataKey::Paused, &paused);
+        extend_instance_ttl(&env);
+
+        Ok(())
+    }
+
+    /// Set how far storage TTLs are extended (see `TtlConfig`)
+    pub fn set_ttl_config(env: Env, config: TtlConfig) -> Result<(), SharedError> {
+        Self::require_admin(&env)?;
+
+        set_ttl_config(&env, &config)?;
+        extend_instance_ttl(&env);
+
+        Ok(())
+    }
+
+    // ────────────────────────────────────────────────────────────────────────
+    // Upgrades (admin only)
+    // ────────────────────────────────────────────────────────────────────────
+
+    /// Queue a WASM upgrade behind the upgrade timelock. Returns its eta.
+    pub fn queue_upgrade(env: Env, new_wasm_hash: BytesN<32>) -> Result<u64, SharedError> {
+        Self::require_admin(&env)?;
+        upgrade::queue(&env, new_wasm_hash)
+    }
+
+    /// Cancel the queued upgrade
+    pub fn cancel_upgrade(env: Env) -> Result<(), SharedError> {
+        Self::require_admin(&env)?;
+        upgrade::cancel(&env)
+    }
+
+    /// Switch to the queued WASM once the timelock has elapsed
+    pub fn upgrade(env: Env, new_wasm_hash: BytesN<32>) -> Result<(), SharedError> {
+        Self::require_admin(&env)?;
+        upgrade::execute(&env, new_wasm_hash)
+    }
+
+    /// Migrate storage to `STORAGE_VERSION` after an upgrade
+    pub fn migrate(env: Env) -> Result<u32, SharedError> {
+        Self::require_admin(&env)?;
+        upgrade::migrate(&env, STORAGE_VERSION, |_, _| Ok(()))
+    }
+
+    // ────────────────────────────────────────────────────────────────────────
+    // Query Functions
+    // ────────────────────────────────────────────────────────────────────────
+
+    /// Get the storage layout version
+    pub fn version(env: Env) -> u32 {
+        upgrade::version(&env)
+    }
+
+    /// Get the queued upgrade, if any
+    pub fn get_pending_upgrade(env: Env) -> Option<PendingUpgrade> {
+        upgrade::pending(&env)
+    }
+
+    /// Get reserves `(reserve_0, reserve_1)`
+    pub fn get_reserves(env: Env) -> (i128, i128) {
+        Self::load_reserves(&env)
+    }
+
+    /// Get `(price_0_cumulative, price_1_cumulative, timestamp)` as of now.
+    /// `price_0` is token 1 per token 0; both are `PRECISION`-scaled and wrap
+    /// on overflow, so consumers must use wrapping subtraction.
+    pub fn get_price_cumulatives(env: Env) -> Result<(i128, i128, u64), SharedError> {
+        Self::accumulate_prices(&env)
+    }
+
+    /// Get token 0 address
+    pub fn token_0(env: Env) -> Result<Address, SharedError> {
+        Self::load_token_0(&env)
+    }
+
+    /// Get token 1 address
+    pub fn token_1(env: Env) -> Result<Address, SharedError> {
+        Self::load_token_1(&env)
+    }
+
+    /// Get `(lp_fee_bps, protocol_fee_bps)`
+    pub fn get_fees(env: Env) -> (u32, u32) {
+        (Self::load_lp_fee(&env), Self::load_protocol_fee_bps(&env))
+    }
+
+    /// Get the fee distributor address
+    pub fn fee_to(env: Env) -> Option<Address> {
+        env.storage().instance().get(&DataKey::FeeTo)
+    }
+
+    /// Get the referral registry, if set
+    pub fn referral(env: Env) -> Option<Address> {
+        env.storage().instance().get(&DataKey::Referral)
+    }
+
+    /// Total protocol fees paid out of the pair for a token (referral shares
+    /// and hand-offs to the fee distributor; held fees count once sent)
+    pub fn get_total_protocol_fees(env: Env, token: Address) -> i128 {
+        get_persistent(&env, &DataKey::TotalProtocolFees(token)).unwrap_or(0)
+    }
+
+    /// Protocol fees held in the pair until the next successful hand-off
+    pub fn get_pending_protocol_fees(env: Env, token: Address) -> i128 {
+        get_persistent(&env, &DataKey::PendingProtocolFees(token)).unwrap_or(0)
+    }
+
+    /// Get admin address
+    pub fn admin(env: Env) -> Result<Address, SharedError> {
+        env.storage()
+            .instance()
+            .get(&DataKey::Admin)
+            .ok_or(SharedError::NotInitialized)
+    }
+
+    /// Get the storage TTL config
+    pub fn ttl_config(env: Env) -> TtlConfig {
+        get_ttl_config(&env)
+    }
+
+    /// Check if contract is paused
+    pub fn is_paused(env: Env) -> bool {
+        env.storage()
+            .instance()
+            .get(&DataKey::Paused)
+            .unwrap_or(false)
+    }
+
+    /// Describe this contract for explorers and wallets
+    pub fn contract_info(env: Env) -> Result<ContractInfo, SharedError> {
+        let admin = Self::admin(env.clone())?;
+        let (reserve_0, reserve_1) = Self::load_reserves(&env);
+        let info = ContractInfo::new(
+            &env,
+            "pair",
+            env!("CARGO_PKG_VERSION"),
+            admin,
+            Self::is_paused(env.clone()),
+            env.storage().instance().get(&DataKey::Registry),
+        );
+        Ok(info
+            .with("lp_fee_bps", Self::load_lp_fee(&env) as i128)
+            .with(
+                "protocol_fee_bps",
+                Self::load_protocol_fee_bps(&env) as i128,
+            )
+            .with("reserve_0", reserve_0)
+            .with("reserve_1", reserve_1)
+            .with("total_supply", Self::get_total_supply(&env)))
+    }
+
+    // ────────────────────────────────────────────────────────────────────────
+    // Internal Functions
+    // ────────────────────────────────────────────────────────────────────────
+
+    fn require_initialized(env: &Env) -> Result<(), SharedError> {
+        let initialized: bool = env
+            .storage()
+            .instance()
+            .get(&DataKey::Initialized)
+            .unwrap_or(false);
+
+        if !initialized {
+            return Err(SharedError::NotInitialized);
+        }
+        Ok(())
+    }
+
+    fn require_not_paused(env: &Env) -> Result<(), SharedError> {
+        let paused: bool = env
+            .storage()
+            .instance()
+            .get(&DataKey::Paused)
+            .unwrap_or(false);
+
+        if paused {
+            return Err(SharedError::ContractPaused);
+        }
+        Ok(())
+    }
+
+    fn require_admin(env: &Env) -> Result<(), SharedError> {
+        let admin: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::Admin)
+            .ok_or(SharedError::NotInitialized)?;
+
+        admin.require_auth();
+        Ok(())
+    }
+
+    fn load_token_0(env: &Env) -> Result<Address, SharedError> {
+        env.storage()
+            .instance()
+            .get(&DataKey::Token0)
+            .ok_or(SharedError::NotInitialized)
+    }
+
+    fn load_token_1(env: &Env) -> Result<Address, SharedError> {
+        env.storage()
+            .instance()
+            .get(&DataKey::Token1)
+            .ok_or(SharedError::NotInitialized)
+    }
+
+    fn load_reserves(env: &Env) -> (i128, i128) {
+        env.storage()
+            .instance()
+            .get(&DataKey::Reserves)
+            .unwrap_or((0, 0))
+    }
+
+    /// Accumulators advanced to the current timestamp with stored reserves
+    fn accumulate_prices(env: &Env) -> Result<(i128, i128, u64), SharedError> {
+        let now = env.ledger().timestamp();
+        let (mut price_0_cumulative, mut price_1_cumulative, last): (i128, i128, u64) = env
+            .storage()
+            .instance()
+            .get(&DataKey::PriceCumulative)
+            .unwrap_or((0, 0, now));
+        let (reserve_0, reserve_1) = Self::load_reserves(env);
+
+        let elapsed = now.saturating_sub(last) as i128;
+        if elapsed > 0 && reserve_0 > 0 && reserve_1 > 0 {
+            let price_0 = mul_div_down(reserve_1, PRECISION, reserve_0)?;
+            let price_1 = mul_div_down(reserve_0, PRECISION, reserve_1)?;
+            price_0_cumulative = price_0_cumulative.wrapping_add(price_0.wrapping_mul(elapsed));
+            price_1_cumulative = price_1_cumulative.wrapping_add(price_1.wrapping_mul(elapsed));
+        }
+
+        Ok((price_0_cumulative, price_1_cumulative, now))
+    }
+
+    /// Update reserves, first crediting the old price to the accumulators
+    fn store_reserves(env: &Env, reserves: (i128, i128)) -> Result<(), SharedError> {
+        let cumulative = Self::accumulate_prices(env)?;
+        env.storage()
+            .instance()
+            .set(&DataKey::PriceCumulative, &cumulative);
+        env.storage().instance().set(&DataKey::Reserves, &reserves);
+        Ok(())
+    }
+
+    fn load_lp_fee(env: &Env) -> u32 {
+        env.storage()
+            .instance()
+            .get(&DataKey::LpFeeBps)
+            .unwrap_or(DEFAULT_LP_FEE_BPS)
+    }
+
+    fn load_protocol_fee_bps(env: &Env) -> u32 {
+        env.storage()
+            .instance()
+            .get(&DataKey::ProtocolFeeBps)
+            .unwrap_or(DEFAULT_PROTOCOL_FEE_BPS)
+    }
+
+    /// Protocol fee charged on `amount_in` (zero without a fee distributor)
+    fn protocol_fee(env: &Env, amount_in: i128) -> Result<i128, SharedError> {
+        if !env.storage().instance().has(&DataKey::FeeTo) {
+            return Ok(0);
+        }
+        apply_bps_round_up(amount_in, Self::load_protocol_fee_bps(env))
+    }
+
+    /// Pay `user`'s referrer their share of the protocol fee, then hand the
+    /// rest to the fee distributor. Both pull their part from the pair.
+    fn route_protocol_fee(
+        env: &Env,
+        user: &Address,
+        token: &Address,
+        amount: i128,
+    ) -> Result<(), SharedError> {
+        let fee_to: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::FeeTo)
+            .ok_or(SharedError::ExternalContractNotSet)?;
+        let this = env.current_contract_address();
+
+        let mut routed = 0;
+        let mut remaining = amount;
+        if let Some(referral) = Self::referral(env.clone()) {
+            let referral_client = ReferralClient::new(env, &referral);
+            // A failing referral registry must not block swaps
+            let share = referral_client
+                .try_referral_share(user, &amount)
+                .into_shared()
+                .unwrap_or(0);
+            if share > 0 {
+                Self::authorize_transfer(env, token, &referral, share);
+                let accrued = referral_client
+                    .try_accrue(&this, user, token, &amount)
+                    .into_shared();
+                if accrued.is_ok() {
+                    remaining = safe_sub(amount, share)?;
+                    routed = share;
+                }
+            }
+        }
+
+        // Dust below the distributor's minimum and fees a rejecting
+        // distributor sent back are held in the pair, and go out with the
+        // next hand-off that reaches the minimum (or `flush_protocol_fees`)
+        let key = DataKey::PendingProtocolFees(token.clone());
+        let held = safe_add(get_persistent(env, &key).unwrap_or(0), remaining)?;
+        let mut sent = false;
+        if held >= MIN_FEE_AMOUNT {
+            Self::authorize_transfer(env, token, &fee_to, held);
+            sent = FeeDistributorClient::new(env, &fee_to)
+                .try_receive_fees(&this, token, &held)
+                .into_shared()
+                .is_ok();
+        }
+        if sent {
+            set_persistent(env, &key, &0_i128);
+            routed = safe_add(routed, held)?;
+        } else {
+            set_persistent(env, &key, &held);
+        }
+
+        Self::record_protocol_fees(env, token, routed)
+    }
+
+    /// Add fees that left the pair to the per-token total
+    fn record_protocol_fees(env: &Env, token: &Address, amount: i128) -> Result<(), SharedError> {
+        if amount == 0 {
+            return Ok(());
+        }
+        let key = DataKey::TotalProtocolFees(token.clone());
+        let total: i128 = get_persistent(env, &key).unwrap_or(0);
+        set_persistent(env, &key, &safe_add(total, amount)?);
+        Ok(())
+    }
+
+    /// Let a contract called next pull `amount` of `token` from the pair
+    fn authorize_transfer(env: &Env, token: &Address, to: &Address, amount: i128) {
+        let this = env.current_contract_address();
+        env.authorize_as_current_contract(vec![
+            env,
+            InvokerContractAuthEntry::Contract(SubContractInvocation {
+                context: ContractContext {
+                    contract: token.clone(),
+                    fn_name: Symbol::new(env, "transfer"),
+                    args: (this, to.clone(), amount).into_val(env),
+                },
+                sub_invocations: Vec::new(env),
+            }),
+        ]);
+    }
+
+    fn get_total_supply(env: &Env) -> i128 {
+        env.storage()
+            .instance()
+            .get(&DataKey::TotalSupply)
+            .unwrap_or(0)
+    }
+
+    fn get_balance(env: &Env, id: &Address) -> i128 {
+        get_persistent(env, &DataKey::Balance(id.clone())).unwrap_or(0)
+    }
+
+    fn set_balance(env: &Env, id: &Address, amount: i128) {
+        let key = DataKey::Balance(id.clone());
+        set_persistent(env, &key, &amount);
+    }
+
+    fn mint_shares(env: &Env, to: &Address, amount: i128) -> Result<(), SharedError> {
+        let balance = safe_add(Self::get_balance(env, to), amount)?;
+        Self::set_balance(env, to, balance);
+        let total_supply = safe_add(Self::get_total_supply(env), amount)?;
+        env.storage()
+            .instance()
+            .set(&DataKey::TotalSupply, &total_supply);
+        Ok(())
+    }
+
+    fn burn_shares(env: &Env, from: &Address, amount: i128) -> Result<(), SharedError> {
+        let balance = Self::get_balance(env, from);
+        if balance < amount {
+            return Err(SharedError::InsufficientBalance);
+        }
+        Self::set_balance(env, from, balance - amount);
+        let total_supply = safe_sub(Self::get_total_supply(env), amount)?;
+        env.storage()
+            .instance()
+            .set(&DataKey::TotalSupply, &total_supply);
+        Ok(())
+    }
+}
+
+// ════════════════════════════════════════════════════════════════════════════
+// Tests
+// ════════════════════════════════════════════════════════════════════════════
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use astro_core_shared::testutils::{create_token, TestToken};
+    use soroban_sdk::testutils::{Address as _, Ledger as _};
+
+    /// Minimal fee distributor: pulls fees and tracks what it received
+    #[contract]
+    pub struct MockFeeDistributor;
+
+    #[contractimpl]
+    impl MockFeeDistributor {
+        pub fn set_paused(env: Env, paused: bool) {
+            env.storage().instance().set(&DataKey::Paused, &paused);
+        }
+
+        pub fn receive_fees(env: Env, caller: Address, token: Address, amount: i128) {
+            let paused: bool = env
+                .storage()
+                .instance()
+                .get(&DataKey::Paused)
+                .unwrap_or(false);
+            assert!(!paused, "paused");
+            caller.require_auth();
+            token::Client::new(&env, &token).transfer(
+                &caller,
+                env.current_contract_address(),
+                &amount,
+            );
+        }
+    }
+
+    /// Referral registry taking a flat 20% of every fee
+    #[contract]
+    pub struct MockReferral;
+
+    #[contractimpl]
+    impl MockReferral {
+        pub fn referral_share(_env: Env, _user: Address, fee: i128) -> i128 {
+            fee / 5
+        }
+
+        pub fn accrue(
+            env: Env,
+            source: Address,
+            _user: Address,
+            token: Address,
+            fee: i128,
+        ) -> i128 {
+            source.require_auth();
+            token::Client::new(&env, &token).transfer(
+                &source,
+                env.current_contract_address(),
+                &(fee / 5),
+            );
+            fee / 5
+        }
+    }
+
+    /// Referral registry that quotes a share but rejects the pair as a source
+    #[contract]
+    pub struct MockRejectingReferral;
+
+    #[contractimpl]
+    impl MockRejectingReferral {
+        pub fn referral_share(_env: Env, _user: Address, fee: i128) -> i128 {
+            fee / 5
+        }
+
+        pub fn accrue(_env: Env, _source: Address, _user: Address, _token: Address, _fee: i128) {
+            panic!("source not approved");
+        }
+    }
+
+    struct Setup<'a> {
+        env: Env,
+        client: AmmPairClient<'a>,
+        token_0: TestToken<'a>,
+        token_1: TestToken<'a>,
+        fee_to: Address,
+        user: Address,
+    }
+
+    fn setup<'a>() -> Setup<'a> {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let admin = Address::generate(&env);
+        let factory = Address::generate(&env);
+        let user = Address::generate(&env);
+        let token_0 = create_token(&env, &admin);
+        let token_1 = create_token(&env, &admin);
+        token_0.mint(&user, 1_000_000_000_000);
+        token_1.mint(&user, 1_000_000_000_000);
+
+        let fee_to = env.register(MockFeeDistributor, ());
+        let contract_id = env.register(AmmPair, ());
+        let client = AmmPairClient::new(&env, &contract_id);
+        client.initialize(&token_0.address, &token_1.address, &factory, &fee_to);
+
+        Setup {
+            env,
+            client,
+            token_0,
+            token_1,
+            fee_to,
+            user,
+        }
+    }
+
+    #[test]
+    fn test_initialize() {
+        let s = setup();
+
+        assert_eq!(s.client.token_0(), s.token_0.address);
+        assert_eq!(s.client.token_1(), s.token_1.address);
+        assert_eq!(s.client.get_reserves(), (0, 0));
+        assert_eq!(s.client.get_fees(), (25, 5));
+
+        let factory = Address::generate(&s.env);
+        assert!(s
+            .client
+            .try_initialize(&s.token_0.address, &s.token_1.address, &factory, &s.fee_to)
+            .is_err());
+    }
+
+    #[test]
+    fn test_add_and_remove_liquidity() {
+        let s = setup();
+
+        let (used_0, used_1, liquidity) = s.client.add_liquidity(
+            &s.user,
+            &100_000_000_000,
+            &400_000_000_000,
+            &0,
+            &0,
+            &u64::MAX,
+        );
+        assert_eq!((used_0, used_1), (100_000_000_000, 400_000_000_000));
+        assert_eq!(liquidity, 200_000_000_000 - MINIMUM_LIQUIDITY);
+        assert_eq!(s.client.balance(&s.user), liquidity);
+        assert_eq!(s.client.total_supply(), 200_000_000_000);
+
+        // Excess token_1 is not taken
+        let (used_0, used_1, minted) =
+            s.client
+                .add_liquidity(&s.user, &10_000_000_000, &50_000_000_000, &0, &0, &u64::MAX);
+        assert_eq!((used_0, used_1), (10_000_000_000, 40_000_000_000));
+        assert_eq!(minted, 20_000_000_000);
+
+        // Slippage protection
+        assert!(s
+            .client
+            .try_add_liquidity(
+                &s.user,
+                &10_000_000_000,
+                &50_000_000_000,
+                &0,
+                &45_000_000_000,
+                &u64::MAX,
+            )
+            .is_err());
+
+        let (out_0, out_1) = s
+            .client
+            .remove_liquidity(&s.user, &minted, &0, &0, &u64::MAX);
+        assert_eq!((out_0, out_1), (10_000_000_000, 40_000_000_000));
+        assert_eq!(s.client.get_reserves(), (100_000_000_000, 400_000_000_000));
+
+        // The locked minimum can never be withdrawn
+        let all = s.client.balance(&s.user);
+        s.client.remove_liquidity(&s.user, &all, &0, &0, &u64::MAX);
+        assert_eq!(s.client.total_supply(), MINIMUM_LIQUIDITY);
+        assert!(s.client.get_reserves().0 > 0);
+    }
+
+    #[test]
+    fn test_swap_routes_protocol_fee() {
+        let s = setup();
+        s.client.add_liquidity(
+            &s.user,
+            &100_000_000_000,
+            &100_000_000_000,
+            &0,
+            &0,
+            &u64::MAX,
+        );
+
+        let amount_in = 1_000_000_000;
+        let quoted = s.client.quote_swap(&s.token_0.address, &amount_in);
+        let balance_before = s.token_1.balance(&s.user);
+        let amount_out = s
+            .client
+            .swap(&s.user, &s.token_0.address, &amount_in, &quoted);
+        assert_eq!(amount_out, quoted);
+        assert_eq!(s.token_1.balance(&s.user) - balance_before, amount_out);
+
+        // 0.05% of the input goes to the fee distributor
+        let protocol_fee = 500_000;
+        assert_eq!(s.token_0.balance(&s.fee_to), protocol_fee);
+        assert_eq!(
+            s.client.get_total_protocol_fees(&s.token_0.address),
+            protocol_fee
+        );
+        assert_eq!(
+            s.client.get_reserves(),
+            (
+                100_000_000_000 + amount_in - protocol_fee,
+                100_000_000_000 - amount_out
+            )
+        );
+
+        // Slippage protection and unknown tokens
+        let quoted = s.client.quote_swap(&s.token_1.address, &amount_in);
+        assert!(s
+            .client
+            .try_swap(&s.user, &s.token_1.address, &amount_in, &(quoted + 1))
+            .is_err());
+        let other = Address::generate(&s.env);
+        assert!(s.client.try_swap(&s.user, &other, &amount_in, &0).is_err());
+    }
+
+    #[test]
+    fn test_swap_holds_rejected_protocol_fee() {
+        let s = setup();
+        s.client.add_liquidity(
+            &s.user,
+            &100_000_000_000,
+            &100_000_000_000,
+            &0,
+            &0,
+            &u64::MAX,
+        );
+        let distributor = MockFeeDistributorClient::new(&s.env, &s.fee_to);
+        distributor.set_paused(&true);
+
+        // The swap goes through; the fee waits in the pair
+        s.client
+            .swap(&s.user, &s.token_0.address, &1_000_000_000, &0);
+        s.client
+            .swap(&s.user, &s.token_0.address, &1_000_000_000, &0);
+        assert_eq!(s.token_0.balance(&s.fee_to), 0);
+        assert_eq!(
+            s.client.get_pending_protocol_fees(&s.token_0.address),
+            1_000_000
+        );
+        assert_eq!(s.client.get_total_protocol_fees(&s.token_0.address), 0);
+        assert!(s
+            .client
+            .try_flush_protocol_fees(&s.token_0.address)
+            .is_err());
+
+        distributor.set_paused(&false);
+        assert_eq!(s.client.flush_protocol_fees(&s.token_0.address), 1_000_000);
+        assert_eq!(s.token_0.balance(&s.fee_to), 1_000_000);
+        assert_eq!(s.client.get_pending_protocol_fees(&s.token_0.address), 0);
+        assert_eq!(
+            s.client.get_total_protocol_fees(&s.token_0.address),
+            1_000_000
+        );
+        assert_eq!(s.client.flush_protocol_fees(&s.token_0.address), 0);
+    }
+
+    #[test]
+    fn test_swap_pays_referral_share() {
+        let s = setup();
+        s.client.add_liquidity(
+            &s.user,
+            &100_000_000_000,
+            &100_000_000_000,
+            &0,
+            &0,
+            &u64::MAX,
+        );
+        let referral = s.env.register(MockReferral, ());
+        s.client.set_referral(&referral);
+
+        s.client
+            .swap(&s.user, &s.token_0.address, &1_000_000_000, &0);
+
+        // The 500_000 protocol fee is split 20/80
+        assert_eq!(s.token_0.balance(&referral), 100_000);
+        assert_eq!(s.token_0.balance(&s.fee_to), 400_000);
+        assert_eq!(
+            s.client.get_total_protocol_fees(&s.token_0.address),
+            500_000
+        );
+    }
+
+    #[test]
+    fn test_swap_holds_fee_dust() {
+        let s = setup();
+        s.client.add_liquidity(
+            &s.user,
+            &100_000_000_000,
+            &100_000_000_000,
+            &0,
+            &0,
+            &u64::MAX,
+        );
+        let referral = s.env.register(MockReferral, ());
+        s.client.set_referral(&referral);
+        s.client.set_fees(&25, &1);
+
+        // 100 fee: 20 to the referrer, 80 is below the distributor's minimum
+        s.client
+            .swap(&s.user, &s.token_0.address, &MIN_TRADE_AMOUNT, &0);
+        assert_eq!(s.token_0.balance(&s.fee_to), 0);
+        assert_eq!(s.client.get_pending_protocol_fees(&s.token_0.address), 80);
+        assert_eq!(s.client.get_total_protocol_fees(&s.token_0.address), 20);
+        assert_eq!(s.client.flush_protocol_fees(&s.token_0.address), 0);
+
+        // The next fee takes the held dust over the minimum
+        s.client
+            .swap(&s.user, &s.token_0.address, &MIN_TRADE_AMOUNT, &0);
+        assert_eq!(s.token_0.balance(&s.fee_to), 160);
+        assert_eq!(s.client.get_pending_protocol_fees(&s.token_0.address), 0);
+        assert_eq!(s.client.get_total_protocol_fees(&s.token_0.address), 200);
+    }
+
+    #[test]
+    fn test_swap_ignores_broken_referral() {
+        let s = setup();
+        s.client.add_liquidity(
+            &s.user,
+            &100_000_000_000,
+            &100_000_000_000,
+            &0,
+            &0,
+            &u64::MAX,
+        );
+        // Not a referral contract: the whole fee goes to the distributor
+        s.client.set_referral(&s.fee_to);
+
+        s.client
+            .swap(&s.user, &s.token_0.address, &1_000_000_000, &0);
+        assert_eq!(s.token_0.balance(&s.fee_to), 500_000);
+    }
+
+    #[test]
+    fn test_swap_ignores_rejected_referral_accrual() {
+        let s = setup();
+        s.client.add_liquidity(
+            &s.user,
+            &100_000_000_000,
+            &100_000_000_000,
+            &0,
+            &0,
+            &u64::MAX,
+        );
+        let referral = s.env.register(MockRejectingReferral, ());
+        s.client.set_referral(&referral);
+
+        // No referral payout: the whole fee goes to the distributor
+        s.client
+            .swap(&s.user, &s.token_0.address, &1_000_000_000, &0);
+        assert_eq!(s.token_0.balance(&referral), 0);
+        assert_eq!(s.token_0.balance(&s.fee_to), 500_000);
+    }
+
+    #[test]
+    fn test_lp_transfer_and_fees() {
+        let s = setup();
+        let (_, _, liquidity) = s.client.add_liquidity(
+            &s.user,
+            &100_000_000_000,
+            &100_000_000_000,
+            &0,
+            &0,
+            &u64::MAX,
+        );
+
+        let other = Address::generate(&s.env);
+        s.client.transfer(&s.user, &other, &1_000);
+        assert_eq!(s.client.balance(&other), 1_000);
+        assert_eq!(s.client.balance(&s.user), liquidity - 1_000);
+        assert!(s.client.try_transfer(&other, &s.user, &1_001).is_err());
+
+        assert!(s.client.try_set_fees(&101, &0).is_err());
+        s.client.set_fees(&30, &0);
+        assert_eq!(s.client.get_fees(), (30, 0));
+
+        // No protocol fee routed at 0 bps
+        s.client
+            .swap(&s.user, &s.token_0.address, &1_000_000_000, &0);
+        assert_eq!(s.token_0.balance(&s.fee_to), 0);
+    }
+
+    #[test]
+    fn test_price_cumulatives() {
+        let s = setup();
+        s.env.ledger().set_timestamp(1_000);
+        assert_eq!(s.client.get_price_cumulatives(), (0, 0, 1_000));
+
+        s.client.add_liquidity(
+            &s.user,
+            &100_000_000_000,
+            &400_000_000_000,
+            &0,
+            &0,
+            &u64::MAX,
+        );
+
+        // A price of 4.0 (and 0.25) held for 100 seconds
+        s.env.ledger().set_timestamp(1_100);
+        let (price_0_cumulative, price_1_cumulative, timestamp) = s.client.get_price_cumulatives();
+        assert_eq!(timestamp, 1_100);
+        assert_eq!(price_0_cumulative, 4 * PRECISION * 100);
+        assert_eq!(price_1_cumulative, PRECISION / 4 * 100);
+
+        // The pre-swap price is credited for the time it was in effect
+        s.client
+            .swap(&s.user, &s.token_0.address, &10_000_000_000, &0);
+        s.env.ledger().set_timestamp(1_200);
+        let (after_0, _, _) = s.client.get_price_cumulatives();
+        let (reserve_0, reserve_1) = s.client.get_reserves();
+        assert_eq!(
+            after_0,
+            price_0_cumulative + reserve_1 * PRECISION / reserve_0 * 100
+        );
+    }
+}
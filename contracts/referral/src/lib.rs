@@ -0,0 +1,578 @@
+#![no_std]
+
+//! # Referral Contract
+//!
+//! Registry of referrer relationships that accrues referral shares of
+//! protocol fees, so launch partners earn a cut of the fees their users pay.
+//!
+//! ## Features
+//! - Users register their referrer once (no self-referral)
+//! - Fee sources (pairs, escrow, ...) approved by the admin call `accrue`
+//!   on their fee path; the referrer's share is pulled from the source
+//! - Referrers claim accrued rewards per token
+//!
+//! ## Fee Path Hook
+//! A source quotes `referral_share(user, fee)`, authorizes the transfer of
+//! that amount to this contract and calls `accrue`; the rest of the fee
+//! goes to the fee distributor as before.
+
+use astro_core_shared::{
+    events::EventBuilder,
+    math::{apply_bps, safe_add},
+    types::{
+        extend_instance_ttl, get_persistent, get_ttl_config, set_persistent, set_ttl_config,
+        ContractInfo, ReferralConfig, SharedError, TtlConfig,
+    },
+    upgrade::{self, PendingUpgrade},
+};
+use soroban_sdk::{contract, contractimpl, contracttype, token, Address, BytesN, Env};
+
+// ════════════════════════════════════════════════════════════════════════════
+// Constants
+// ════════════════════════════════════════════════════════════════════════════
+
+/// Storage layout version of this code (see `migrate`)
+const STORAGE_VERSION: u32 = 1;
+
+// ════════════════════════════════════════════════════════════════════════════
+// Storage Keys
+// ════════════════════════════════════════════════════════════════════════════
+
+#[contracttype]
+#[derive(Clone, Debug)]
+pub enum DataKey {
+    /// Admin address
+    Admin,
+    /// Whether contract is initialized
+    Initialized,
+    /// Whether contract is paused
+    Paused,
+    /// Referral configuration
+    Config,
+    /// Approved fee source (Address -> bool)
+    Source(Address),
+    /// Referrer of a user (Address -> Address)
+    Referrer(Address),
+    /// Users referred by a referrer (Address -> u32)
+    ReferralCount(Address),
+    /// Unclaimed rewards ((referrer, token) -> i128)
+    Rewards(Address, Address),
+    /// Rewards accrued all-time ((referrer, token) -> i128)
+    TotalEarned(Address, Address),
+    /// Protocol registry address (reported by `contract_info`)
+    Registry,
+}
+
+// ════════════════════════════════════════════════════════════════════════════
+// Contract Implementation
+// ════════════════════════════════════════════════════════════════════════════
+
+#[contract]
+pub struct Referral;
+
+#[contractimpl]
+impl Referral {
+    // ────────────────────────────────────────────────────────────────────────
+    // Initialization
+    // ────────────────────────────────────────────────────────────────────────
+
+    /// Initialize the referral registry
+    pub fn initialize(env: Env, admin: Address, config: ReferralConfig) -> Result<(), SharedError> {
+        if env.storage().instance().has(&DataKey::Initialized) {
+            return Err(SharedError::AlreadyInitialized);
+        }
+        if !config.is_valid() {
+            return Err(SharedError::InvalidInitParams);
+        }
+
+        env.storage().instance().set(&DataKey::Admin, &admin);
+        env.storage().instance().set(&DataKey::Config, &config);
+        env.storage().instance().set(&DataKey::Initialized, &true);
+        upgrade::init_version(&env, STORAGE_VERSION);
+        env.storage().instance().set(&DataKey::Paused, &false);
+
+        extend_instance_ttl(&env);
+
+        let events = EventBuilder::new(&env);
+        events.publish(
+            "referral",
+            "initialized",
+            (admin, config.share_bps, env.ledger().timestamp()),
+        );
+
+        Ok(())
+    }
+
+    // ────────────────────────────────────────────────────────────────────────
+    // Referrers
+    // ────────────────────────────────────────────────────────────────────────
+
+    /// Register `referrer` as the referrer of `user` (once per user)
+    pub fn register_referrer(
+        env: Env,
+        user: Address,
+        referrer: Address,
+    ) -> Result<(), SharedError> {
+        Self::require_initialized(&env)?;
+        Self::require_not_paused(&env)?;
+        user.require_auth();
+
+        if user == referrer {
+            return Err(SharedError::InvalidAddress);
+        }
+        let key = DataKey::Referrer(user.clone());
+        if env.storage().persistent().has(&key) {
+            return Err(SharedError::AlreadyInitialized);
+        }
+
+        set_persistent(&env, &key, &referrer);
+
+        let count_key = DataKey::ReferralCount(referrer.clone());
+        let count: u32 = get_persistent(&env, &count_key).unwrap_or(0);
+        set_persistent(&env, &count_key, &(count + 1));
+
+        let events = EventBuilder::new(&env);
+        events.publish(
+            "referral",
+            "registered",
+            (user, referrer, env.ledger().timestamp()),
+        );
+
+        extend_instance_ttl(&env);
+        Ok(())
+    }
+
+    /// Claim a referrer's accrued rewards in `token`
+    pub fn claim_referral_rewards(
+        env: Env,
+        referrer: Address,
+        token: Address,
+    ) -> Result<i128, SharedError> {
+        Self::require_initialized(&env)?;
+        Self::require_not_paused(&env)?;
+        referrer.require_auth();
+
+        let key = DataKey::Rewards(referrer.clone(), token.clone());
+        let amount: i128 = get_persistent(&env, &key).unwrap_or(0);
+        if amount <= 0 {
+            return Err(SharedError::InvalidAmount);
+        }
+        if amount < Self::load_config(&env)?.min_claim {
+            return Err(SharedError::BelowMinimum);
+        }
+
+        env.storage().persistent().remove(&key);
+        token::Client::new(&env, &token).transfer(
+            &env.current_contract_address(),
+            &referrer,
+            &amount,
+        );
+
+        let events = EventBuilder::new(&env);
+        events.publish(
+            "referral",
+            "claimed",
+            (referrer, token, amount, env.ledger().timestamp()),
+        );
+
+        extend_instance_ttl(&env);
+        Ok(amount)
+    }
+
+    // ────────────────────────────────────────────────────────────────────────
+    // Fee Path Hook
+    // ────────────────────────────────────────────────────────────────────────
+
+    /// Credit `user`'s referrer with their share of a protocol `fee`, pulling
+    /// the share from `source`. Returns the share (0 without a referrer).
+    pub fn accrue(
+        env: Env,
+        source: Address,
+        user: Address,
+        token: Address,
+        fee: i128,
+    ) -> Result<i128, SharedError> {
+        Self::require_initialized(&env)?;
+        source.require_auth();
+
+        if !Self::is_source(env.clone(), source.clone()) {
+            return Err(SharedError::Unauthorized);
+        }
+        if fee < 0 {
+            return Err(SharedError::InvalidAmount);
+        }
+
+        let referrer = match Self::referrer_of(env.clone(), user.clone()) {
+            Some(referrer) => referrer,
+            None => return Ok(0),
+        };
+        let share = Self::referral_share(env.clone(), user.clone(), fee)?;
+        if share == 0 {
+            return Ok(0);
+        }
+
+        token::Client::new(&env, &token).transfer(&source, env.current_contract_address(), &share);
+
+        for key in [
+            DataKey::Rewards(referrer.clone(), token.clone()),
+            DataKey::TotalEarned(referrer.clone(), token.clone()),
+        ] {
+            let total: i128 = get_persistent(&env, &key).unwrap_or(0);
+            set_persistent(&env, &key, &safe_add(total, share)?);
+        }
+
+        let events = EventBuilder::new(&env);
+        events.publish(
+            "referral",
+            "accrued",
+            (referrer, user, token, share, env.ledger().timestamp()),
+        );
+
+        extend_instance_ttl(&env);
+        Ok(share)
+    }
+
+    /// Referral share of a protocol fee paid by `user` (0 without a referrer)
+    pub fn referral_share(env: Env, user: Address, fee: i128) -> Result<i128, SharedError> {
+        if fee <= 0
+            || Self::is_paused(env.clone())
+            || Self::referrer_of(env.clone(), user).is_none()
+        {
+            return Ok(0);
+        }
+        apply_bps(fee, Self::load_config(&env)?.share_bps)
+    }
+
+    // ────────────────────────────────────────────────────────────────────────
+    // Admin Functions
+    // ────────────────────────────────────────────────────────────────────────
+
+    /// Update referral configuration
+    pub fn set_config(env: Env, config: ReferralConfig) -> Result<(), SharedError> {
+        Self::require_admin(&env)?;
+
+        if !config.is_valid() {
+            return Err(SharedError::InvalidBps);
+        }
+        env.storage().instance().set(&DataKey::Config, &config);
+
+        let events = EventBuilder::new(&env);
+        events.publish(
+            "referral",
+            "config_updated",
+            (config.share_bps, config.min_claim, env.ledger().timestamp()),
+        );
+
+        extend_instance_ttl(&env);
+        Ok(())
+    }
+
+    /// Approve or revoke a fee source
+    pub fn set_source(env: Env, source: Address, approved: bool) -> Result<(), SharedError> {
+        Self::require_admin(&env)?;
+
+        let key = DataKey::Source(source.clone());
+        if approved {
+            set_persistent(&env, &key, &true);
+        } else {
+            env.storage().persistent().remove(&key);
+        }
+
+        let events = EventBuilder::new(&env);
+        events.publish(
+            "referral",
+            "source_set",
+            (source, approved, env.ledger().timestamp()),
+        );
+
+        extend_instance_ttl(&env);
+        Ok(())
+    }
+
+    /// Set the protocol registry address reported by `contract_info`
+    pub fn set_registry(env: Env, registry: Address) -> Result<(), SharedError> {
+        Self::require_admin(&env)?;
+        env.storage().instance().set(&DataKey::Registry, &registry);
+        extend_instance_ttl(&env);
+        Ok(())
+    }
+
+    /// Set admin address
+    pub fn set_admin(env: Env, new_admin: Address) -> Result<(), SharedError> {
+        Self::require_admin(&env)?;
+
+        env.storage().instance().set(&DataKey::Admin, &new_admin);
+        extend_instance_ttl(&env);
+
+        Ok(())
+    }
+
+    /// Pause/unpause (paused: no shares are taken from fees)
+    pub fn set_paused(env: Env, paused: bool) -> Result<(), SharedError> {
+        Self::require_admin(&env)?;
+
+        env.storage().instance().set(&DataKey::Paused, &paused);
+        extend_instance_ttl(&env);
+
+        Ok(())
+    }
+
+    /// Set how far storage TTLs are extended (see `TtlConfig`)
+    pub fn set_ttl_config(env: Env, config: TtlConfig) -> Result<(), SharedError> {
+        Self::require_admin(&env)?;
+
+        set_ttl_config(&env, &config)?;
+        extend_instance_ttl(&env);
+
+        Ok(())
+    }
+
+    // ────────────────────────────────────────────────────────────────────────
+    // Upgrades (admin only)
+    // ────────────────────────────────────────────────────────────────────────
+
+    /// Queue a WASM upgrade behind the upgrade timelock. Returns its eta.
+    pub fn queue_upgrade(env: Env, new_wasm_hash: BytesN<32>) -> Result<u64, SharedError> {
+        Self::require_admin(&env)?;
+        upgrade::queue(&env, new_wasm_hash)
+    }
+
+    /// Cancel the queued upgrade
+    pub fn cancel_upgrade(env: Env) -> Result<(), SharedError> {
+        Self::require_admin(&env)?;
+        upgrade::cancel(&env)
+    }
+
+    /// Switch to the queued WASM once the timelock has elapsed
+    pub fn upgrade(env: Env, new_wasm_hash: BytesN<32>) -> Result<(), SharedError> {
+        Self::require_admin(&env)?;
+        upgrade::execute(&env, new_wasm_hash)
+    }
+
+    /// Migrate storage to `STORAGE_VERSION` after an upgrade
+    pub fn migrate(env: Env) -> Result<u32, SharedError> {
+        Self::require_admin(&env)?;
+        upgrade::migrate(&env, STORAGE_VERSION, |_, _| Ok(()))
+    }
+
+    // ────────────────────────────────────────────────────────────────────────
+    // Query Functions
+    // ────────────────────────────────────────────────────────────────────────
+
+    /// Get the storage layout version
+    pub fn version(env: Env) -> u32 {
+        upgrade::version(&env)
+    }
+
+    /// Get the queued upgrade, if any
+    pub fn get_pending_upgrade(env: Env) -> Option<PendingUpgrade> {
+        upgrade::pending(&env)
+    }
+
+    /// Get a user's referrer
+    pub fn referrer_of(env: Env, user: Address) -> Option<Address> {
+        get_persistent(&env, &DataKey::Referrer(user))
+    }
+
+    /// Get the number of users a referrer has referred
+    pub fn referral_count(env: Env, referrer: Address) -> u32 {
+        get_persistent(&env, &DataKey::ReferralCount(referrer)).unwrap_or(0)
+    }
+
+    /// Get a referrer's unclaimed rewards in `token`
+    pub fn get_rewards(env: Env, referrer: Address, token: Address) -> i128 {
+        get_persistent(&env, &DataKey::Rewards(referrer, token)).unwrap_or(0)
+    }
+
+    /// Get a referrer's all-time rewards in `token`
+    pub fn get_total_earned(env: Env, referrer: Address, token: Address) -> i128 {
+        get_persistent(&env, &DataKey::TotalEarned(referrer, token)).unwrap_or(0)
+    }
+
+    /// Check if an address is an approved fee source
+    pub fn is_source(env: Env, source: Address) -> bool {
+        env.storage().persistent().has(&DataKey::Source(source))
+    }
+
+    /// Get referral configuration
+    pub fn get_config(env: Env) -> Result<ReferralConfig, SharedError> {
+        Self::load_config(&env)
+    }
+
+    /// Get admin address
+    pub fn admin(env: Env) -> Result<Address, SharedError> {
+        env.storage()
+            .instance()
+            .get(&DataKey::Admin)
+            .ok_or(SharedError::NotInitialized)
+    }
+
+    /// Get the storage TTL config
+    pub fn ttl_config(env: Env) -> TtlConfig {
+        get_ttl_config(&env)
+    }
+
+    /// Check if contract is paused
+    pub fn is_paused(env: Env) -> bool {
+        env.storage()
+            .instance()
+            .get(&DataKey::Paused)
+            .unwrap_or(false)
+    }
+
+    /// Describe this contract for explorers and wallets
+    pub fn contract_info(env: Env) -> Result<ContractInfo, SharedError> {
+        let admin = Self::admin(env.clone())?;
+        let config = Self::load_config(&env)?;
+        let info = ContractInfo::new(
+            &env,
+            "referral",
+            env!("CARGO_PKG_VERSION"),
+            admin,
+            Self::is_paused(env.clone()),
+            env.storage().instance().get(&DataKey::Registry),
+        );
+        Ok(info
+            .with("share_bps", config.share_bps as i128)
+            .with("min_claim", config.min_claim))
+    }
+
+    // ────────────────────────────────────────────────────────────────────────
+    // Internal Functions
+    // ────────────────────────────────────────────────────────────────────────
+
+    fn require_initialized(env: &Env) -> Result<(), SharedError> {
+        let initialized: bool = env
+            .storage()
+            .instance()
+            .get(&DataKey::Initialized)
+            .unwrap_or(false);
+
+        if !initialized {
+            return Err(SharedError::NotInitialized);
+        }
+        Ok(())
+    }
+
+    fn require_not_paused(env: &Env) -> Result<(), SharedError> {
+        let paused: bool = env
+            .storage()
+            .instance()
+            .get(&DataKey::Paused)
+            .unwrap_or(false);
+
+        if paused {
+            return Err(SharedError::ContractPaused);
+        }
+        Ok(())
+    }
+
+    fn require_admin(env: &Env) -> Result<(), SharedError> {
+        let admin: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::Admin)
+            .ok_or(SharedError::NotInitialized)?;
+
+        admin.require_auth();
+        Ok(())
+    }
+
+    fn load_config(env: &Env) -> Result<ReferralConfig, SharedError> {
+        env.storage()
+            .instance()
+            .get(&DataKey::Config)
+            .ok_or(SharedError::NotInitialized)
+    }
+}
+
+// ════════════════════════════════════════════════════════════════════════════
+// Tests
+// ════════════════════════════════════════════════════════════════════════════
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use astro_core_shared::testutils::create_token;
+    use soroban_sdk::testutils::Address as _;
+
+    fn setup<'a>(env: &Env) -> ReferralClient<'a> {
+        let client = ReferralClient::new(env, &env.register(Referral, ()));
+        let config = ReferralConfig {
+            share_bps: 2_000,
+            min_claim: 100,
+        };
+        client.initialize(&Address::generate(env), &config);
+        client
+    }
+
+    #[test]
+    fn test_register_referrer() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let client = setup(&env);
+
+        let user = Address::generate(&env);
+        let referrer = Address::generate(&env);
+        assert!(client.try_register_referrer(&user, &user).is_err());
+
+        client.register_referrer(&user, &referrer);
+        assert_eq!(client.referrer_of(&user), Some(referrer.clone()));
+        assert_eq!(client.referral_count(&referrer), 1);
+
+        // A referrer is set once
+        let other = Address::generate(&env);
+        assert!(client.try_register_referrer(&user, &other).is_err());
+
+        assert!(client
+            .try_set_config(&ReferralConfig {
+                share_bps: 5_001,
+                min_claim: 0,
+            })
+            .is_err());
+    }
+
+    #[test]
+    fn test_accrue_and_claim() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let client = setup(&env);
+
+        let admin = Address::generate(&env);
+        let token = create_token(&env, &admin);
+        let source = Address::generate(&env);
+        token.mint(&source, 10_000);
+
+        let user = Address::generate(&env);
+        let referrer = Address::generate(&env);
+        let stranger = Address::generate(&env);
+        client.register_referrer(&user, &referrer);
+
+        // Only approved sources
+        assert!(client
+            .try_accrue(&source, &user, &token.address, &1_000)
+            .is_err());
+        client.set_source(&source, &true);
+
+        // No referrer, no share
+        assert_eq!(client.accrue(&source, &stranger, &token.address, &1_000), 0);
+
+        assert_eq!(client.referral_share(&user, &250), 50);
+        assert_eq!(client.accrue(&source, &user, &token.address, &250), 50);
+        assert_eq!(token.balance(&source), 9_950);
+
+        // Below the minimum claim
+        assert!(client
+            .try_claim_referral_rewards(&referrer, &token.address)
+            .is_err());
+
+        client.accrue(&source, &user, &token.address, &1_000);
+        assert_eq!(
+            client.claim_referral_rewards(&referrer, &token.address),
+            250
+        );
+        assert_eq!(token.balance(&referrer), 250);
+        assert_eq!(client.get_rewards(&referrer, &token.address), 0);
+        assert_eq!(client.get_total_earned(&referrer, &token.address), 250);
+    }
+}
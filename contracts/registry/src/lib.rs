@@ -0,0 +1,377 @@
+#![no_std]
+
+//! # Token Registry Contract
+//!
+//! Protocol-wide list of allowlisted and denylisted token addresses, so
+//! known scam tokens can be kept out of every contract with one admin call.
+//!
+//! ## Features
+//! - Admin lists a token as `Allowed` or `Denied`, or clears its listing
+//! - Denylisted tokens are never accepted
+//! - In allowlist-only mode, unlisted tokens are rejected as well
+//!
+//! ## Consumers
+//! The treasury, staking pool and fee distributor take an optional registry
+//! address (`set_token_registry`). When set, they call `is_token_allowed`
+//! before accepting a token and fail with `TokenNotAllowed` otherwise.
+
+use astro_core_shared::{
+    events::EventBuilder,
+    types::{
+        extend_instance_ttl, get_persistent, get_ttl_config, set_persistent, set_ttl_config,
+        ContractInfo, SharedError, TokenStatus, TtlConfig,
+    },
+    upgrade::{self, PendingUpgrade},
+};
+use soroban_sdk::{contract, contractimpl, contracttype, Address, BytesN, Env};
+
+// ════════════════════════════════════════════════════════════════════════════
+// Constants
+// ════════════════════════════════════════════════════════════════════════════
+
+/// Storage layout version of this code (see `migrate`)
+const STORAGE_VERSION: u32 = 1;
+
+// ════════════════════════════════════════════════════════════════════════════
+// Storage Keys
+// ════════════════════════════════════════════════════════════════════════════
+
+#[contracttype]
+#[derive(Clone, Debug)]
+pub enum DataKey {
+    /// Admin address
+    Admin,
+    /// Whether contract is initialized
+    Initialized,
+    /// Whether only allowlisted tokens are accepted
+    AllowlistOnly,
+    /// Listing of a token (Address -> TokenStatus)
+    Status(Address),
+    /// Number of allowlisted tokens
+    AllowedCount,
+    /// Number of denylisted tokens
+    DeniedCount,
+    /// Protocol registry address (reported by `contract_info`)
+    Registry,
+}
+
+// ════════════════════════════════════════════════════════════════════════════
+// Contract Implementation
+// ════════════════════════════════════════════════════════════════════════════
+
+#[contract]
+pub struct TokenRegistry;
+
+#[contractimpl]
+impl TokenRegistry {
+    // ────────────────────────────────────────────────────────────────────────
+    // Initialization
+    // ────────────────────────────────────────────────────────────────────────
+
+    /// Initialize the token registry
+    pub fn initialize(env: Env, admin: Address, allowlist_only: bool) -> Result<(), SharedError> {
+        if env.storage().instance().has(&DataKey::Initialized) {
+            return Err(SharedError::AlreadyInitialized);
+        }
+
+        env.storage().instance().set(&DataKey::Admin, &admin);
+        env.storage()
+            .instance()
+            .set(&DataKey::AllowlistOnly, &allowlist_only);
+        env.storage().instance().set(&DataKey::Initialized, &true);
+        upgrade::init_version(&env, STORAGE_VERSION);
+
+        extend_instance_ttl(&env);
+
+        let events = EventBuilder::new(&env);
+        events.publish(
+            "registry",
+            "initialized",
+            (admin, allowlist_only, env.ledger().timestamp()),
+        );
+
+        Ok(())
+    }
+
+    // ────────────────────────────────────────────────────────────────────────
+    // Admin Functions
+    // ────────────────────────────────────────────────────────────────────────
+
+    /// List `token` as allowed or denied, or clear its listing with `Unlisted`
+    pub fn set_token_status(
+        env: Env,
+        token: Address,
+        status: TokenStatus,
+    ) -> Result<(), SharedError> {
+        Self::require_admin(&env)?;
+
+        let key = DataKey::Status(token.clone());
+        let previous = Self::token_status(env.clone(), token.clone());
+        if previous == status {
+            return Ok(());
+        }
+
+        Self::adjust_count(&env, previous, -1);
+        Self::adjust_count(&env, status, 1);
+        if status == TokenStatus::Unlisted {
+            env.storage().persistent().remove(&key);
+        } else {
+            set_persistent(&env, &key, &status);
+        }
+
+        let events = EventBuilder::new(&env);
+        events.publish(
+            "registry",
+            "token_status",
+            (token, status as u32, env.ledger().timestamp()),
+        );
+
+        extend_instance_ttl(&env);
+        Ok(())
+    }
+
+    /// Accept only allowlisted tokens (`true`) or every token that is not
+    /// denylisted (`false`)
+    pub fn set_allowlist_only(env: Env, allowlist_only: bool) -> Result<(), SharedError> {
+        Self::require_admin(&env)?;
+
+        env.storage()
+            .instance()
+            .set(&DataKey::AllowlistOnly, &allowlist_only);
+
+        let events = EventBuilder::new(&env);
+        events.publish(
+            "registry",
+            "allowlist_only",
+            (allowlist_only, env.ledger().timestamp()),
+        );
+
+        extend_instance_ttl(&env);
+        Ok(())
+    }
+
+    /// Set the protocol registry address reported by `contract_info`
+    pub fn set_registry(env: Env, registry: Address) -> Result<(), SharedError> {
+        Self::require_admin(&env)?;
+        env.storage().instance().set(&DataKey::Registry, &registry);
+        extend_instance_ttl(&env);
+        Ok(())
+    }
+
+    /// Set admin address
+    pub fn set_admin(env: Env, new_admin: Address) -> Result<(), SharedError> {
+        Self::require_admin(&env)?;
+
+        env.storage().instance().set(&DataKey::Admin, &new_admin);
+        extend_instance_ttl(&env);
+
+        Ok(())
+    }
+
+    /// Set how far storage TTLs are extended (see `TtlConfig`)
+    pub fn set_ttl_config(env: Env, config: TtlConfig) -> Result<(), SharedError> {
+        Self::require_admin(&env)?;
+
+        set_ttl_config(&env, &config)?;
+        extend_instance_ttl(&env);
+
+        Ok(())
+    }
+
+    // ────────────────────────────────────────────────────────────────────────
+    // Upgrades (admin only)
+    // ────────────────────────────────────────────────────────────────────────
+
+    /// Queue a WASM upgrade behind the upgrade timelock. Returns its eta.
+    pub fn queue_upgrade(env: Env, new_wasm_hash: BytesN<32>) -> Result<u64, SharedError> {
+        Self::require_admin(&env)?;
+        upgrade::queue(&env, new_wasm_hash)
+    }
+
+    /// Cancel the queued upgrade
+    pub fn cancel_upgrade(env: Env) -> Result<(), SharedError> {
+        Self::require_admin(&env)?;
+        upgrade::cancel(&env)
+    }
+
+    /// Switch to the queued WASM once the timelock has elapsed
+    pub fn upgrade(env: Env, new_wasm_hash: BytesN<32>) -> Result<(), SharedError> {
+        Self::require_admin(&env)?;
+        upgrade::execute(&env, new_wasm_hash)
+    }
+
+    /// Migrate storage to `STORAGE_VERSION` after an upgrade
+    pub fn migrate(env: Env) -> Result<u32, SharedError> {
+        Self::require_admin(&env)?;
+        upgrade::migrate(&env, STORAGE_VERSION, |_, _| Ok(()))
+    }
+
+    // ────────────────────────────────────────────────────────────────────────
+    // Query Functions
+    // ────────────────────────────────────────────────────────────────────────
+
+    /// Get the storage layout version
+    pub fn version(env: Env) -> u32 {
+        upgrade::version(&env)
+    }
+
+    /// Get the queued upgrade, if any
+    pub fn get_pending_upgrade(env: Env) -> Option<PendingUpgrade> {
+        upgrade::pending(&env)
+    }
+
+    /// Whether contracts should accept `token`
+    pub fn is_token_allowed(env: Env, token: Address) -> bool {
+        match Self::token_status(env.clone(), token) {
+            TokenStatus::Allowed => true,
+            TokenStatus::Denied => false,
+            TokenStatus::Unlisted => !Self::is_allowlist_only(env),
+        }
+    }
+
+    /// Get the listing of `token`
+    pub fn token_status(env: Env, token: Address) -> TokenStatus {
+        get_persistent(&env, &DataKey::Status(token)).unwrap_or(TokenStatus::Unlisted)
+    }
+
+    /// Check if only allowlisted tokens are accepted
+    pub fn is_allowlist_only(env: Env) -> bool {
+        env.storage()
+            .instance()
+            .get(&DataKey::AllowlistOnly)
+            .unwrap_or(false)
+    }
+
+    /// Get `(allowlisted, denylisted)` token counts
+    pub fn get_counts(env: Env) -> (u32, u32) {
+        (
+            env.storage()
+                .instance()
+                .get(&DataKey::AllowedCount)
+                .unwrap_or(0),
+            env.storage()
+                .instance()
+                .get(&DataKey::DeniedCount)
+                .unwrap_or(0),
+        )
+    }
+
+    /// Get admin address
+    pub fn admin(env: Env) -> Result<Address, SharedError> {
+        env.storage()
+            .instance()
+            .get(&DataKey::Admin)
+            .ok_or(SharedError::NotInitialized)
+    }
+
+    /// Get the storage TTL config
+    pub fn ttl_config(env: Env) -> TtlConfig {
+        get_ttl_config(&env)
+    }
+
+    /// Describe this contract for explorers and wallets
+    pub fn contract_info(env: Env) -> Result<ContractInfo, SharedError> {
+        let admin = Self::admin(env.clone())?;
+        let (allowed, denied) = Self::get_counts(env.clone());
+        let info = ContractInfo::new(
+            &env,
+            "registry",
+            env!("CARGO_PKG_VERSION"),
+            admin,
+            false,
+            env.storage().instance().get(&DataKey::Registry),
+        );
+        Ok(info
+            .with(
+                "allowlist_only",
+                Self::is_allowlist_only(env.clone()) as i128,
+            )
+            .with("allowed_tokens", allowed as i128)
+            .with("denied_tokens", denied as i128))
+    }
+
+    // ────────────────────────────────────────────────────────────────────────
+    // Internal Functions
+    // ────────────────────────────────────────────────────────────────────────
+
+    fn require_admin(env: &Env) -> Result<(), SharedError> {
+        let admin: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::Admin)
+            .ok_or(SharedError::NotInitialized)?;
+
+        admin.require_auth();
+        Ok(())
+    }
+
+    fn adjust_count(env: &Env, status: TokenStatus, delta: i32) {
+        let key = match status {
+            TokenStatus::Allowed => DataKey::AllowedCount,
+            TokenStatus::Denied => DataKey::DeniedCount,
+            TokenStatus::Unlisted => return,
+        };
+        let count: u32 = env.storage().instance().get(&key).unwrap_or(0);
+        env.storage()
+            .instance()
+            .set(&key, &count.saturating_add_signed(delta));
+    }
+}
+
+// ════════════════════════════════════════════════════════════════════════════
+// Tests
+// ════════════════════════════════════════════════════════════════════════════
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use soroban_sdk::testutils::Address as _;
+
+    fn setup<'a>(env: &Env, allowlist_only: bool) -> TokenRegistryClient<'a> {
+        let client = TokenRegistryClient::new(env, &env.register(TokenRegistry, ()));
+        client.initialize(&Address::generate(env), &allowlist_only);
+        client
+    }
+
+    #[test]
+    fn test_denylist() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let client = setup(&env, false);
+
+        let token = Address::generate(&env);
+        let scam = Address::generate(&env);
+        assert!(client.is_token_allowed(&token));
+
+        client.set_token_status(&scam, &TokenStatus::Denied);
+        assert_eq!(client.token_status(&scam), TokenStatus::Denied);
+        assert!(!client.is_token_allowed(&scam));
+        assert!(client.is_token_allowed(&token));
+        assert_eq!(client.get_counts(), (0, 1));
+
+        client.set_token_status(&scam, &TokenStatus::Unlisted);
+        assert!(client.is_token_allowed(&scam));
+        assert_eq!(client.get_counts(), (0, 0));
+    }
+
+    #[test]
+    fn test_allowlist_only() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let client = setup(&env, true);
+
+        let token = Address::generate(&env);
+        assert!(!client.is_token_allowed(&token));
+
+        client.set_token_status(&token, &TokenStatus::Allowed);
+        assert!(client.is_token_allowed(&token));
+        assert_eq!(client.get_counts(), (1, 0));
+
+        client.set_token_status(&token, &TokenStatus::Denied);
+        assert!(!client.is_token_allowed(&token));
+        assert_eq!(client.get_counts(), (0, 1));
+
+        client.set_allowlist_only(&false);
+        assert!(client.is_token_allowed(&Address::generate(&env)));
+    }
+}
@@ -0,0 +1,857 @@
+#![no_std]
+
+//! # Router Contract
+//!
+//! Entry point for swaps and liquidity so users don't interact with pairs
+//! directly.
+//!
+//! ## Features
+//! - Multi-hop swaps along a token path (`swap_exact_in`, `swap_exact_out`)
+//! - Slippage bounds on the final amount and a deadline on every call
+//! - `add_liquidity`/`remove_liquidity` wrappers taking tokens in any order
+//! - Quotes for a path (`get_amounts_out`, `get_amounts_in`)
+//!
+//! Pairs are registered by the admin; each hop of a path must have one.
+//! During a swap the router holds the intermediate tokens and pays the final
+//! output to `to`.
+
+// The liquidity wrappers take eight arguments besides `env`, which the
+// generated client and args helpers repeat outside the `contractimpl`
+#![allow(clippy::too_many_arguments)]
+
+use astro_core_shared::{
+    events::EventBuilder,
+    interfaces::AmmPairClient,
+    math::{
+        apply_bps_round_up, get_amount_in, get_amount_out, mul_div_up, safe_sub, BPS_DENOMINATOR,
+    },
+    types::{
+        check_deadline, extend_instance_ttl, get_persistent, get_ttl_config, set_persistent,
+        set_ttl_config, ContractInfo, SharedError, TtlConfig,
+    },
+    upgrade::{self, PendingUpgrade},
+};
+use soroban_sdk::{
+    auth::{ContractContext, InvokerContractAuthEntry, SubContractInvocation},
+    contract, contractimpl, contracttype, token, vec, Address, BytesN, Env, IntoVal, Symbol, Vec,
+};
+
+// ════════════════════════════════════════════════════════════════════════════
+// Constants
+// ════════════════════════════════════════════════════════════════════════════
+
+/// Storage layout version of this code (see `migrate`)
+const STORAGE_VERSION: u32 = 1;
+
+/// Maximum tokens in a swap path (4 hops)
+const MAX_PATH_LENGTH: u32 = 5;
+
+// ════════════════════════════════════════════════════════════════════════════
+// Storage Keys
+// ════════════════════════════════════════════════════════════════════════════
+
+#[contracttype]
+#[derive(Clone, Debug)]
+pub enum DataKey {
+    /// Admin address
+    Admin,
+    /// Whether contract is initialized
+    Initialized,
+    /// Pair for a token pair, stored under both orders ((Address, Address) -> Address)
+    Pair(Address, Address),
+    /// Protocol registry address (reported by `contract_info`)
+    Registry,
+}
+
+/// One hop of a swap path, oriented from the hop's input token
+struct Hop {
+    /// Reserve of the input token
+    reserve_in: i128,
+    /// Reserve of the output token
+    reserve_out: i128,
+    /// LP fee in basis points
+    lp_fee_bps: u32,
+    /// Protocol fee taken from the input, in basis points
+    protocol_fee_bps: u32,
+}
+
+// ════════════════════════════════════════════════════════════════════════════
+// Contract Implementation
+// ════════════════════════════════════════════════════════════════════════════
+
+#[contract]
+pub struct Router;
+
+#[contractimpl]
+impl Router {
+    // ────────────────────────────────────────────────────────────────────────
+    // Initialization
+    // ────────────────────────────────────────────────────────────────────────
+
+    /// Initialize the router
+    pub fn initialize(env: Env, admin: Address) -> Result<(), SharedError> {
+        if env.storage().instance().has(&DataKey::Initialized) {
+            return Err(SharedError::AlreadyInitialized);
+        }
+
+        env.storage().instance().set(&DataKey::Admin, &admin);
+        env.storage().instance().set(&DataKey::Initialized, &true);
+        upgrade::init_version(&env, STORAGE_VERSION);
+
+        extend_instance_ttl(&env);
+
+        let events = EventBuilder::new(&env);
+        events.publish("router", "initialized", (admin, env.ledger().timestamp()));
+
+        Ok(())
+    }
+
+    // ────────────────────────────────────────────────────────────────────────
+    // Swaps
+    // ────────────────────────────────────────────────────────────────────────
+
+    /// Swap exactly `amount_in` of `path[0]` for at least `min_out` of the
+    /// last token in `path`, paid to `to`. Returns the amount at each step.
+    pub fn swap_exact_in(
+        env: Env,
+        sender: Address,
+        path: Vec<Address>,
+        amount_in: i128,
+        min_out: i128,
+        to: Address,
+        deadline: u64,
+    ) -> Result<Vec<i128>, SharedError> {
+        sender.require_auth();
+        Self::require_initialized(&env)?;
+        check_deadline(&env, deadline)?;
+
+        let amounts = Self::amounts_out(&env, amount_in, &path)?;
+        if amounts.last().unwrap() < min_out {
+            return Err(SharedError::BelowMinimum);
+        }
+
+        let amounts = Self::execute_path(&env, &sender, &path, &amounts, &to)?;
+        Self::emit_swap(&env, &sender, &path, &amounts, &to);
+
+        extend_instance_ttl(&env);
+        Ok(amounts)
+    }
+
+    /// Swap at most `max_in` of `path[0]` for exactly `amount_out` of the
+    /// last token in `path`, paid to `to`. Returns the amount at each step.
+    pub fn swap_exact_out(
+        env: Env,
+        sender: Address,
+        path: Vec<Address>,
+        amount_out: i128,
+        max_in: i128,
+        to: Address,
+        deadline: u64,
+    ) -> Result<Vec<i128>, SharedError> {
+        sender.require_auth();
+        Self::require_initialized(&env)?;
+        check_deadline(&env, deadline)?;
+
+        let amounts = Self::amounts_in(&env, amount_out, &path)?;
+        if amounts.get(0).unwrap() > max_in {
+            return Err(SharedError::AmountExceedsMax);
+        }
+
+        let amounts = Self::execute_path(&env, &sender, &path, &amounts, &to)?;
+        Self::emit_swap(&env, &sender, &path, &amounts, &to);
+
+        extend_instance_ttl(&env);
+        Ok(amounts)
+    }
+
+    /// Quote each step of swapping `amount_in` along `path`
+    pub fn get_amounts_out(
+        env: Env,
+        amount_in: i128,
+        path: Vec<Address>,
+    ) -> Result<Vec<i128>, SharedError> {
+        Self::amounts_out(&env, amount_in, &path)
+    }
+
+    /// Quote the input needed at each step to receive `amount_out` along `path`
+    pub fn get_amounts_in(
+        env: Env,
+        amount_out: i128,
+        path: Vec<Address>,
+    ) -> Result<Vec<i128>, SharedError> {
+        Self::amounts_in(&env, amount_out, &path)
+    }
+
+    // ────────────────────────────────────────────────────────────────────────
+    // Liquidity
+    // ────────────────────────────────────────────────────────────────────────
+
+    /// Add liquidity to the `token_a`/`token_b` pair.
+    /// Returns `(amount_a, amount_b, liquidity)`; LP shares go to `sender`.
+    pub fn add_liquidity(
+        env: Env,
+        sender: Address,
+        token_a: Address,
+        token_b: Address,
+        amount_a: i128,
+        amount_b: i128,
+        min_a: i128,
+        min_b: i128,
+        deadline: u64,
+    ) -> Result<(i128, i128, i128), SharedError> {
+        sender.require_auth();
+        Self::require_initialized(&env)?;
+        check_deadline(&env, deadline)?;
+
+        let pair_address = Self::load_pair(&env, &token_a, &token_b)?;
+        let pair = AmmPairClient::new(&env, &pair_address);
+
+        let (used_a, used_b, liquidity) = if pair.token_0() == token_a {
+            pair.add_liquidity(&sender, &amount_a, &amount_b, &min_a, &min_b, &deadline)
+        } else {
+            let (used_b, used_a, liquidity) =
+                pair.add_liquidity(&sender, &amount_b, &amount_a, &min_b, &min_a, &deadline);
+            (used_a, used_b, liquidity)
+        };
+
+        extend_instance_ttl(&env);
+        Ok((used_a, used_b, liquidity))
+    }
+
+    /// Burn `liquidity` LP shares of the `token_a`/`token_b` pair.
+    /// Returns `(amount_a, amount_b)`.
+    pub fn remove_liquidity(
+        env: Env,
+        sender: Address,
+        token_a: Address,
+        token_b: Address,
+        liquidity: i128,
+        min_a: i128,
+        min_b: i128,
+        deadline: u64,
+    ) -> Result<(i128, i128), SharedError> {
+        sender.require_auth();
+        Self::require_initialized(&env)?;
+        check_deadline(&env, deadline)?;
+
+        let pair_address = Self::load_pair(&env, &token_a, &token_b)?;
+        let pair = AmmPairClient::new(&env, &pair_address);
+
+        let amounts = if pair.token_0() == token_a {
+            pair.remove_liquidity(&sender, &liquidity, &min_a, &min_b, &deadline)
+        } else {
+            let (amount_b, amount_a) =
+                pair.remove_liquidity(&sender, &liquidity, &min_b, &min_a, &deadline);
+            (amount_a, amount_b)
+        };
+
+        extend_instance_ttl(&env);
+        Ok(amounts)
+    }
+
+    // ────────────────────────────────────────────────────────────────────────
+    // Admin Functions
+    // ────────────────────────────────────────────────────────────────────────
+
+    /// Register a pair for routing
+    pub fn register_pair(env: Env, pair: Address) -> Result<(), SharedError> {
+        Self::require_admin(&env)?;
+
+        let client = AmmPairClient::new(&env, &pair);
+        let token_0 = client.token_0();
+        let token_1 = client.token_1();
+        for key in [
+            DataKey::Pair(token_0.clone(), token_1.clone()),
+            DataKey::Pair(token_1.clone(), token_0.clone()),
+        ] {
+            set_persistent(&env, &key, &pair);
+        }
+
+        let events = EventBuilder::new(&env);
+        events.publish(
+            "router",
+            "pair_registered",
+            (pair, token_0, token_1, env.ledger().timestamp()),
+        );
+
+        extend_instance_ttl(&env);
+        Ok(())
+    }
+
+    /// Stop routing through the pair for two tokens
+    pub fn remove_pair(env: Env, token_a: Address, token_b: Address) -> Result<(), SharedError> {
+        Self::require_admin(&env)?;
+
+        let pair = Self::load_pair(&env, &token_a, &token_b)?;
+        env.storage()
+            .persistent()
+            .remove(&DataKey::Pair(token_a.clone(), token_b.clone()));
+        env.storage()
+            .persistent()
+            .remove(&DataKey::Pair(token_b.clone(), token_a.clone()));
+
+        let events = EventBuilder::new(&env);
+        events.publish(
+            "router",
+            "pair_removed",
+            (pair, token_a, token_b, env.ledger().timestamp()),
+        );
+
+        extend_instance_ttl(&env);
+        Ok(())
+    }
+
+    /// Set the protocol registry address reported by `contract_info`
+    pub fn set_registry(env: Env, registry: Address) -> Result<(), SharedError> {
+        Self::require_admin(&env)?;
+        env.storage().instance().set(&DataKey::Registry, &registry);
+        extend_instance_ttl(&env);
+        Ok(())
+    }
+
+    /// Set admin address
+    pub fn set_admin(env: Env, new_admin: Address) -> Result<(), SharedError> {
+        Self::require_admin(&env)?;
+
+        env.storage().instance().set(&DataKey::Admin, &new_admin);
+        extend_instance_ttl(&env);
+
+        Ok(())
+    }
+
+    /// Set how far storage TTLs are extended (see `TtlConfig`)
+    pub fn set_ttl_config(env: Env, config: TtlConfig) -> Result<(), SharedError> {
+        Self::require_admin(&env)?;
+
+        set_ttl_config(&env, &config)?;
+        extend_instance_ttl(&env);
+
+        Ok(())
+    }
+
+    // ────────────────────────────────────────────────────────────────────────
+    // Upgrades (admin only)
+    // ────────────────────────────────────────────────────────────────────────
+
+    /// Queue a WASM upgrade behind the upgrade timelock. Returns its eta.
+    pub fn queue_upgrade(env: Env, new_wasm_hash: BytesN<32>) -> Result<u64, SharedError> {
+        Self::require_admin(&env)?;
+        upgrade::queue(&env, new_wasm_hash)
+    }
+
+    /// Cancel the queued upgrade
+    pub fn cancel_upgrade(env: Env) -> Result<(), SharedError> {
+        Self::require_admin(&env)?;
+        upgrade::cancel(&env)
+    }
+
+    /// Switch to the queued WASM once the timelock has elapsed
+    pub fn upgrade(env: Env, new_wasm_hash: BytesN<32>) -> Result<(), SharedError> {
+        Self::require_admin(&env)?;
+        upgrade::execute(&env, new_wasm_hash)
+    }
+
+    /// Migrate storage to `STORAGE_VERSION` after an upgrade
+    pub fn migrate(env: Env) -> Result<u32, SharedError> {
+        Self::require_admin(&env)?;
+        upgrade::migrate(&env, STORAGE_VERSION, |_, _| Ok(()))
+    }
+
+    // ────────────────────────────────────────────────────────────────────────
+    // Query Functions
+    // ────────────────────────────────────────────────────────────────────────
+
+    /// Get the storage layout version
+    pub fn version(env: Env) -> u32 {
+        upgrade::version(&env)
+    }
+
+    /// Get the queued upgrade, if any
+    pub fn get_pending_upgrade(env: Env) -> Option<PendingUpgrade> {
+        upgrade::pending(&env)
+    }
+
+    /// Get the pair registered for two tokens (either order)
+    pub fn get_pair(env: Env, token_a: Address, token_b: Address) -> Option<Address> {
+        get_persistent(&env, &DataKey::Pair(token_a, token_b))
+    }
+
+    /// Get admin address
+    pub fn admin(env: Env) -> Result<Address, SharedError> {
+        env.storage()
+            .instance()
+            .get(&DataKey::Admin)
+            .ok_or(SharedError::NotInitialized)
+    }
+
+    /// Get the storage TTL config
+    pub fn ttl_config(env: Env) -> TtlConfig {
+        get_ttl_config(&env)
+    }
+
+    /// Describe this contract for explorers and wallets
+    pub fn contract_info(env: Env) -> Result<ContractInfo, SharedError> {
+        let admin = Self::admin(env.clone())?;
+        let info = ContractInfo::new(
+            &env,
+            "router",
+            env!("CARGO_PKG_VERSION"),
+            admin,
+            false,
+            env.storage().instance().get(&DataKey::Registry),
+        );
+        Ok(info.with("max_path_length", MAX_PATH_LENGTH as i128))
+    }
+
+    // ────────────────────────────────────────────────────────────────────────
+    // Internal Functions
+    // ────────────────────────────────────────────────────────────────────────
+
+    fn require_initialized(env: &Env) -> Result<(), SharedError> {
+        let initialized: bool = env
+            .storage()
+            .instance()
+            .get(&DataKey::Initialized)
+            .unwrap_or(false);
+
+        if !initialized {
+            return Err(SharedError::NotInitialized);
+        }
+        Ok(())
+    }
+
+    fn require_admin(env: &Env) -> Result<(), SharedError> {
+        let admin: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::Admin)
+            .ok_or(SharedError::NotInitialized)?;
+
+        admin.require_auth();
+        Ok(())
+    }
+
+    fn require_path(path: &Vec<Address>) -> Result<(), SharedError> {
+        if path.len() < 2 {
+            return Err(SharedError::InvalidState);
+        }
+        if path.len() > MAX_PATH_LENGTH {
+            return Err(SharedError::LimitExceeded);
+        }
+        Ok(())
+    }
+
+    fn load_pair(env: &Env, token_a: &Address, token_b: &Address) -> Result<Address, SharedError> {
+        get_persistent(env, &DataKey::Pair(token_a.clone(), token_b.clone()))
+            .ok_or(SharedError::TokenNotFound)
+    }
+
+    /// Reserves and fees of the pair trading one hop
+    fn load_hop(env: &Env, token_in: &Address, token_out: &Address) -> Result<Hop, SharedError> {
+        let pair = AmmPairClient::new(env, &Self::load_pair(env, token_in, token_out)?);
+        let (reserve_0, reserve_1) = pair.get_reserves();
+        let (reserve_in, reserve_out) = if pair.token_0() == *token_in {
+            (reserve_0, reserve_1)
+        } else {
+            (reserve_1, reserve_0)
+        };
+        let (lp_fee_bps, protocol_fee_bps) = pair.get_fees();
+        Ok(Hop {
+            reserve_in,
+            reserve_out,
+            lp_fee_bps,
+            protocol_fee_bps,
+        })
+    }
+
+    fn amounts_out(
+        env: &Env,
+        amount_in: i128,
+        path: &Vec<Address>,
+    ) -> Result<Vec<i128>, SharedError> {
+        Self::require_path(path)?;
+
+        let mut amounts = Vec::new(env);
+        amounts.push_back(amount_in);
+        let mut amount = amount_in;
+        for i in 0..path.len() - 1 {
+            let token_in = path.get(i).unwrap();
+            let token_out = path.get(i + 1).unwrap();
+            let hop = Self::load_hop(env, &token_in, &token_out)?;
+
+            // The pair takes its protocol fee from the input first
+            let net_in = safe_sub(amount, apply_bps_round_up(amount, hop.protocol_fee_bps)?)?;
+            amount = get_amount_out(net_in, hop.reserve_in, hop.reserve_out, hop.lp_fee_bps)?;
+            amounts.push_back(amount);
+        }
+        Ok(amounts)
+    }
+
+    fn amounts_in(
+        env: &Env,
+        amount_out: i128,
+        path: &Vec<Address>,
+    ) -> Result<Vec<i128>, SharedError> {
+        Self::require_path(path)?;
+
+        let mut amounts = Vec::new(env);
+        amounts.push_front(amount_out);
+        let mut amount = amount_out;
+        for i in (1..path.len()).rev() {
+            let token_in = path.get(i - 1).unwrap();
+            let token_out = path.get(i).unwrap();
+            let hop = Self::load_hop(env, &token_in, &token_out)?;
+
+            let net_in = get_amount_in(amount, hop.reserve_in, hop.reserve_out, hop.lp_fee_bps)?;
+            amount = Self::gross_up(net_in, hop.protocol_fee_bps)?;
+            amounts.push_front(amount);
+        }
+        Ok(amounts)
+    }
+
+    /// Smallest input that still leaves `net_in` after the protocol fee
+    fn gross_up(net_in: i128, protocol_fee_bps: u32) -> Result<i128, SharedError> {
+        let fee_factor = BPS_DENOMINATOR - protocol_fee_bps as i128;
+        let mut gross = mul_div_up(net_in, BPS_DENOMINATOR, fee_factor)?;
+        while safe_sub(gross, apply_bps_round_up(gross, protocol_fee_bps)?)? < net_in {
+            gross += 1;
+        }
+        Ok(gross)
+    }
+
+    /// Pull `amounts[0]` from `sender`, swap hop by hop (each hop must return
+    /// at least its quoted amount) and pay the final output to `to`.
+    /// Returns the amounts actually swapped.
+    fn execute_path(
+        env: &Env,
+        sender: &Address,
+        path: &Vec<Address>,
+        amounts: &Vec<i128>,
+        to: &Address,
+    ) -> Result<Vec<i128>, SharedError> {
+        let this = env.current_contract_address();
+        let mut amount = amounts.get(0).unwrap();
+        token::Client::new(env, &path.get(0).unwrap()).transfer(sender, &this, &amount);
+
+        let mut actual = Vec::new(env);
+        actual.push_back(amount);
+        for i in 0..path.len() - 1 {
+            let token_in = path.get(i).unwrap();
+            let pair = Self::load_pair(env, &token_in, &path.get(i + 1).unwrap())?;
+
+            // The pair pulls the input from this contract
+            env.authorize_as_current_contract(vec![
+                env,
+                InvokerContractAuthEntry::Contract(SubContractInvocation {
+                    context: ContractContext {
+                        contract: token_in.clone(),
+                        fn_name: Symbol::new(env, "transfer"),
+                        args: (this.clone(), pair.clone(), amount).into_val(env),
+                    },
+                    sub_invocations: Vec::new(env),
+                }),
+            ]);
+            amount = AmmPairClient::new(env, &pair).swap(
+                &this,
+                &token_in,
+                &amount,
+                &amounts.get(i + 1).unwrap(),
+            );
+            actual.push_back(amount);
+        }
+
+        token::Client::new(env, &path.last().unwrap()).transfer(&this, to, &amount);
+        Ok(actual)
+    }
+
+    fn emit_swap(
+        env: &Env,
+        sender: &Address,
+        path: &Vec<Address>,
+        amounts: &Vec<i128>,
+        to: &Address,
+    ) {
+        let events = EventBuilder::new(env);
+        events.publish(
+            "router",
+            "swap",
+            (
+                sender.clone(),
+                path.get(0).unwrap(),
+                path.last().unwrap(),
+                amounts.get(0).unwrap(),
+                amounts.last().unwrap(),
+                to.clone(),
+                env.ledger().timestamp(),
+            ),
+        );
+    }
+}
+
+// ════════════════════════════════════════════════════════════════════════════
+// Tests
+// ════════════════════════════════════════════════════════════════════════════
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use astro_core_shared::testutils::{create_token, TestToken};
+    use astro_pair::{AmmPair, AmmPairClient as PairClient};
+    use soroban_sdk::testutils::{Address as _, Ledger as _};
+
+    /// Minimal fee distributor: pulls fees from the pair
+    #[contract]
+    pub struct MockFeeDistributor;
+
+    #[contractimpl]
+    impl MockFeeDistributor {
+        pub fn receive_fees(env: Env, caller: Address, token: Address, amount: i128) {
+            caller.require_auth();
+            token::Client::new(&env, &token).transfer(
+                &caller,
+                env.current_contract_address(),
+                &amount,
+            );
+        }
+    }
+
+    fn create_pair<'a>(
+        env: &Env,
+        token_0: &Address,
+        token_1: &Address,
+        fee_to: &Address,
+    ) -> PairClient<'a> {
+        let pair = PairClient::new(env, &env.register(AmmPair, ()));
+        pair.initialize(token_0, token_1, &Address::generate(env), fee_to);
+        pair
+    }
+
+    struct Setup<'a> {
+        env: Env,
+        client: RouterClient<'a>,
+        tokens: [TestToken<'a>; 3],
+        pairs: [PairClient<'a>; 2],
+        user: Address,
+    }
+
+    /// Two pools: A/B and B/C, 100k each side
+    fn setup<'a>() -> Setup<'a> {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let admin = Address::generate(&env);
+        let user = Address::generate(&env);
+        let fee_to = env.register(MockFeeDistributor, ());
+
+        let token_a = create_token(&env, &admin);
+        let token_b = create_token(&env, &admin);
+        let token_c = create_token(&env, &admin);
+        for token in [&token_a, &token_b, &token_c] {
+            token.mint(&user, 10_000_000_000_000);
+        }
+
+        let pair_ab = create_pair(&env, &token_a.address, &token_b.address, &fee_to);
+        let pair_bc = create_pair(&env, &token_b.address, &token_c.address, &fee_to);
+
+        let client = RouterClient::new(&env, &env.register(Router, ()));
+        client.initialize(&admin);
+        client.register_pair(&pair_ab.address);
+        client.register_pair(&pair_bc.address);
+
+        for (pair, a, b) in [
+            (&pair_ab, &token_a, &token_b),
+            (&pair_bc, &token_b, &token_c),
+        ] {
+            client.add_liquidity(
+                &user,
+                &a.address,
+                &b.address,
+                &1_000_000_000_000,
+                &1_000_000_000_000,
+                &0,
+                &0,
+                &u64::MAX,
+            );
+            assert!(pair.balance(&user) > 0);
+        }
+
+        Setup {
+            env,
+            client,
+            tokens: [token_a, token_b, token_c],
+            pairs: [pair_ab, pair_bc],
+            user,
+        }
+    }
+
+    fn path(s: &Setup) -> Vec<Address> {
+        Vec::from_array(
+            &s.env,
+            [
+                s.tokens[0].address.clone(),
+                s.tokens[1].address.clone(),
+                s.tokens[2].address.clone(),
+            ],
+        )
+    }
+
+    #[test]
+    fn test_swap_exact_in_multi_hop() {
+        let s = setup();
+        let to = Address::generate(&s.env);
+        let path = path(&s);
+
+        let quoted = s.client.get_amounts_out(&1_000_000_000, &path);
+        assert_eq!(quoted.len(), 3);
+
+        let amounts = s.client.swap_exact_in(
+            &s.user,
+            &path,
+            &1_000_000_000,
+            &quoted.get(2).unwrap(),
+            &to,
+            &u64::MAX,
+        );
+        assert_eq!(amounts, quoted);
+        assert_eq!(s.tokens[2].balance(&to), quoted.get(2).unwrap());
+
+        // Nothing is left in the router
+        let router = s.client.address.clone();
+        for token in s.tokens.iter() {
+            assert_eq!(token.balance(&router), 0);
+        }
+
+        // Slippage bound on the final amount
+        assert!(s
+            .client
+            .try_swap_exact_in(
+                &s.user,
+                &path,
+                &1_000_000_000,
+                &1_000_000_000,
+                &to,
+                &u64::MAX
+            )
+            .is_err());
+    }
+
+    #[test]
+    fn test_swap_exact_out_multi_hop() {
+        let s = setup();
+        let to = Address::generate(&s.env);
+        let path = path(&s);
+
+        let quoted = s.client.get_amounts_in(&500_000_000, &path);
+        let amounts = s.client.swap_exact_out(
+            &s.user,
+            &path,
+            &500_000_000,
+            &quoted.get(0).unwrap(),
+            &to,
+            &u64::MAX,
+        );
+        assert_eq!(amounts.get(0).unwrap(), quoted.get(0).unwrap());
+        assert!(s.tokens[2].balance(&to) >= 500_000_000);
+
+        // Input bound
+        assert!(s
+            .client
+            .try_swap_exact_out(&s.user, &path, &500_000_000, &500_000_000, &to, &u64::MAX)
+            .is_err());
+    }
+
+    #[test]
+    fn test_deadline_and_path_checks() {
+        let s = setup();
+        s.env.ledger().set_timestamp(1_000);
+        let to = Address::generate(&s.env);
+
+        assert!(s
+            .client
+            .try_swap_exact_in(&s.user, &path(&s), &1_000_000_000, &0, &to, &999)
+            .is_err());
+
+        let single = Vec::from_array(&s.env, [s.tokens[0].address.clone()]);
+        assert!(s
+            .client
+            .try_swap_exact_in(&s.user, &single, &1_000_000_000, &0, &to, &u64::MAX)
+            .is_err());
+
+        // No A/C pair registered
+        let direct = Vec::from_array(
+            &s.env,
+            [s.tokens[0].address.clone(), s.tokens[2].address.clone()],
+        );
+        assert!(s
+            .client
+            .try_swap_exact_in(&s.user, &direct, &1_000_000_000, &0, &to, &u64::MAX)
+            .is_err());
+    }
+
+    #[test]
+    fn test_liquidity_wrappers() {
+        let s = setup();
+        let pair = &s.pairs[0];
+
+        // Tokens in reverse order are mapped onto the pair's order
+        let (used_b, used_a, liquidity) = s.client.add_liquidity(
+            &s.user,
+            &s.tokens[1].address,
+            &s.tokens[0].address,
+            &2_000_000_000,
+            &1_000_000_000,
+            &0,
+            &0,
+            &u64::MAX,
+        );
+        assert_eq!((used_a, used_b), (1_000_000_000, 1_000_000_000));
+
+        let (out_b, out_a) = s.client.remove_liquidity(
+            &s.user,
+            &s.tokens[1].address,
+            &s.tokens[0].address,
+            &liquidity,
+            &0,
+            &0,
+            &u64::MAX,
+        );
+        assert_eq!((out_a, out_b), (1_000_000_000, 1_000_000_000));
+        assert!(pair.balance(&s.user) > 0);
+
+        // Min amount checks
+        assert!(s
+            .client
+            .try_remove_liquidity(
+                &s.user,
+                &s.tokens[0].address,
+                &s.tokens[1].address,
+                &liquidity,
+                &2_000_000_000,
+                &0,
+                &u64::MAX,
+            )
+            .is_err());
+    }
+
+    #[test]
+    fn test_upgrade_timelock() {
+        let s = setup();
+        s.env.ledger().set_timestamp(1_000);
+        assert_eq!(s.client.version(), STORAGE_VERSION);
+
+        let hash = BytesN::from_array(&s.env, &[1; 32]);
+        let eta = s.client.queue_upgrade(&hash);
+        assert_eq!(eta, 1_000 + upgrade::UPGRADE_TIMELOCK);
+        assert_eq!(s.client.get_pending_upgrade().unwrap().wasm_hash, hash);
+
+        // Too early, then cancelled
+        assert!(s.client.try_upgrade(&hash).is_err());
+        s.client.cancel_upgrade();
+        assert!(s.client.get_pending_upgrade().is_none());
+        s.env.ledger().set_timestamp(eta);
+        assert!(s.client.try_upgrade(&hash).is_err());
+
+        // Already at this code's storage version
+        assert!(s.client.try_migrate().is_err());
+    }
+}
@@ -0,0 +1,160 @@
+//! # Access Control Module
+//!
+//! Role-based access control layered on top of the single admin, so duties
+//! such as pausing, moving funds and upgrading can sit on separate keys.
+//!
+//! The admin implicitly holds every role and is the only one who can grant
+//! or revoke them; an admin that never grants a role behaves exactly like
+//! the old single-admin contracts.
+//!
+//! ## Usage
+//! ```rust,ignore
+//! use astro_core_shared::access_control::{self, PAUSER};
+//!
+//! pub fn set_paused(env: Env, caller: Address, paused: bool) -> Result<(), SharedError> {
+//!     let admin = Self::get_admin(&env)?;
+//!     access_control::require_role(&env, &PAUSER, &caller, &admin)?;
+//!     // ...
+//! }
+//! ```
+
+use soroban_sdk::{symbol_short, Address, Env, Symbol};
+
+use crate::events::EventBuilder;
+use crate::types::{get_persistent, set_persistent, CommonPersistentKey, SharedError};
+
+/// Can pause and unpause the contract
+pub const PAUSER: Symbol = symbol_short!("PAUSER");
+
+/// Can move funds out of the contract
+pub const TREASURER: Symbol = symbol_short!("TREASURER");
+
+/// Can change contract configuration
+pub const CONFIG_ADMIN: Symbol = symbol_short!("CFG_ADMIN");
+
+/// Can queue, execute and cancel WASM upgrades
+pub const UPGRADER: Symbol = symbol_short!("UPGRADER");
+
+/// Can trigger maintenance that moves no funds out (e.g. redistributions)
+pub const KEEPER: Symbol = symbol_short!("KEEPER");
+
+/// Can pause and unpause every contract registered with the guardian
+pub const GUARDIAN: Symbol = symbol_short!("GUARDIAN");
+
+/// Whether `account` has been granted `role`
+pub fn has_role(env: &Env, role: &Symbol, account: &Address) -> bool {
+    get_persistent(
+        env,
+        &CommonPersistentKey::Role(role.clone(), account.clone()),
+    )
+    .unwrap_or(false)
+}
+
+/// Grant `role` to `account`. The caller checks admin auth.
+pub fn grant_role(env: &Env, role: Symbol, account: Address) -> Result<(), SharedError> {
+    if has_role(env, &role, &account) {
+        return Err(SharedError::InvalidState);
+    }
+
+    let key = CommonPersistentKey::Role(role.clone(), account.clone());
+    set_persistent(env, &key, &true);
+
+    let events = EventBuilder::new(env);
+    events.publish(
+        "access",
+        "role_granted",
+        (role, account, env.ledger().timestamp()),
+    );
+
+    Ok(())
+}
+
+/// Revoke `role` from `account`. The caller checks admin auth.
+pub fn revoke_role(env: &Env, role: Symbol, account: Address) -> Result<(), SharedError> {
+    if !has_role(env, &role, &account) {
+        return Err(SharedError::InvalidState);
+    }
+
+    env.storage()
+        .persistent()
+        .remove(&CommonPersistentKey::Role(role.clone(), account.clone()));
+
+    let events = EventBuilder::new(env);
+    events.publish(
+        "access",
+        "role_revoked",
+        (role, account, env.ledger().timestamp()),
+    );
+
+    Ok(())
+}
+
+/// Require `caller`'s auth and that it is `admin` or holds `role`
+pub fn require_role(
+    env: &Env,
+    role: &Symbol,
+    caller: &Address,
+    admin: &Address,
+) -> Result<(), SharedError> {
+    caller.require_auth();
+    if caller == admin || has_role(env, role, caller) {
+        Ok(())
+    } else {
+        Err(SharedError::Unauthorized)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use soroban_sdk::testutils::Address as _;
+    use soroban_sdk::{contract, contractimpl};
+
+    #[contract]
+    pub struct Guarded;
+
+    #[contractimpl]
+    impl Guarded {}
+
+    #[test]
+    fn test_grant_revoke_require() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let contract_id = env.register(Guarded, ());
+        let admin = Address::generate(&env);
+        let pauser = Address::generate(&env);
+
+        // Each check runs in its own frame: `require_auth` may only be
+        // called once per address per invocation
+        let require = |role: Symbol, caller: &Address| {
+            env.as_contract(&contract_id, || require_role(&env, &role, caller, &admin))
+        };
+
+        // Admin holds every role implicitly
+        assert!(require(UPGRADER, &admin).is_ok());
+        assert_eq!(require(PAUSER, &pauser), Err(SharedError::Unauthorized));
+
+        env.as_contract(&contract_id, || {
+            grant_role(&env, PAUSER, pauser.clone()).unwrap();
+            assert!(has_role(&env, &PAUSER, &pauser));
+            assert_eq!(
+                grant_role(&env, PAUSER, pauser.clone()),
+                Err(SharedError::InvalidState)
+            );
+        });
+        assert!(require(PAUSER, &pauser).is_ok());
+
+        // Roles don't imply each other
+        assert_eq!(require(TREASURER, &pauser), Err(SharedError::Unauthorized));
+
+        env.as_contract(&contract_id, || {
+            revoke_role(&env, PAUSER, pauser.clone()).unwrap();
+            assert!(!has_role(&env, &PAUSER, &pauser));
+            assert_eq!(
+                revoke_role(&env, PAUSER, pauser.clone()),
+                Err(SharedError::InvalidState)
+            );
+        });
+        assert_eq!(require(PAUSER, &pauser), Err(SharedError::Unauthorized));
+    }
+}
@@ -0,0 +1,244 @@
+//! # Compact Event Encoding
+//!
+//! Alternative encoding for high-frequency events (swaps, fee receipts, stakes).
+//! Instead of a structured `#[contractevent]` map, numeric fields are packed into
+//! a single fixed-width `Bytes` payload, which is considerably smaller on ledger.
+//!
+//! Encoding is selected per event kind by a bitmask stored in instance storage,
+//! so each contract can opt in independently via its admin.
+//!
+//! ## Layout
+//!
+//! ```text
+//! Topics: (Symbol("compact"), kind: u32, subject: Address[, counterparty: Address])
+//! Data:   Bytes
+//!   [0]       layout version (COMPACT_LAYOUT_VERSION)
+//!   [1..9]    ledger timestamp, u64 big-endian
+//!   [9..]     kind-specific i128 fields, 16 bytes big-endian each:
+//!               Swap          amount_in, amount_out
+//!               FeeReceived   amount
+//!               Distribution  total, treasury, staking, burn
+//!               Stake         amount, total_staked, fee
+//!               Unstake       amount, remaining, fee
+//!               Claim         amount
+//! ```
+//!
+//! Version 1 payloads carried no `fee` field on Stake and Unstake; decoders
+//! should branch on the version byte.
+
+use soroban_sdk::{contracttype, Address, Bytes, Env, Symbol};
+
+/// Current compact payload layout version (2: `fee` on Stake and Unstake)
+pub const COMPACT_LAYOUT_VERSION: u8 = 2;
+
+/// Size of the payload header (version + timestamp)
+pub const COMPACT_HEADER_LEN: u32 = 9;
+
+/// High-frequency event kinds that support compact encoding
+#[contracttype]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[repr(u32)]
+pub enum CompactEventKind {
+    Swap = 0,
+    FeeReceived = 1,
+    Distribution = 2,
+    Stake = 3,
+    Unstake = 4,
+    Claim = 5,
+}
+
+impl CompactEventKind {
+    /// Bit used for this kind in the compact mask
+    pub fn bit(self) -> u32 {
+        1 << (self as u32)
+    }
+}
+
+/// Storage key for the compact encoding mask
+#[contracttype]
+#[derive(Clone)]
+pub enum CompactEventKey {
+    /// Bitmask of `CompactEventKind`s emitted in compact form
+    Mask,
+}
+
+// ════════════════════════════════════════════════════════════════════════════
+// Configuration
+// ════════════════════════════════════════════════════════════════════════════
+
+/// Set which event kinds are emitted compactly (bit = `CompactEventKind::bit`)
+pub fn set_compact_mask(env: &Env, mask: u32) {
+    env.storage().instance().set(&CompactEventKey::Mask, &mask);
+}
+
+/// Get the compact encoding mask (0 = all structured)
+pub fn compact_mask(env: &Env) -> u32 {
+    env.storage()
+        .instance()
+        .get(&CompactEventKey::Mask)
+        .unwrap_or(0)
+}
+
+/// Whether an event kind should be emitted compactly
+pub fn is_compact(env: &Env, kind: CompactEventKind) -> bool {
+    compact_mask(env) & kind.bit() != 0
+}
+
+// ════════════════════════════════════════════════════════════════════════════
+// Bit-packing Helpers
+// ════════════════════════════════════════════════════════════════════════════
+
+/// Append a u64 in big-endian order
+pub fn pack_u64(buf: &mut Bytes, value: u64) {
+    buf.extend_from_array(&value.to_be_bytes());
+}
+
+/// Append an i128 in big-endian order
+pub fn pack_i128(buf: &mut Bytes, value: i128) {
+    buf.extend_from_array(&value.to_be_bytes());
+}
+
+/// Read a big-endian u64 at `offset`
+pub fn unpack_u64(buf: &Bytes, offset: u32) -> Option<u64> {
+    if buf.len() < offset.checked_add(8)? {
+        return None;
+    }
+    let mut raw = [0u8; 8];
+    buf.slice(offset..offset + 8).copy_into_slice(&mut raw);
+    Some(u64::from_be_bytes(raw))
+}
+
+/// Read a big-endian i128 at `offset`
+pub fn unpack_i128(buf: &Bytes, offset: u32) -> Option<i128> {
+    if buf.len() < offset.checked_add(16)? {
+        return None;
+    }
+    let mut raw = [0u8; 16];
+    buf.slice(offset..offset + 16).copy_into_slice(&mut raw);
+    Some(i128::from_be_bytes(raw))
+}
+
+/// Build a payload header plus the given i128 fields
+pub fn pack_payload(env: &Env, fields: &[i128]) -> Bytes {
+    let mut buf = Bytes::new(env);
+    buf.push_back(COMPACT_LAYOUT_VERSION);
+    pack_u64(&mut buf, env.ledger().timestamp());
+    for field in fields {
+        pack_i128(&mut buf, *field);
+    }
+    buf
+}
+
+/// Read the i128 field at `index` from a compact payload
+pub fn unpack_field(buf: &Bytes, index: u32) -> Option<i128> {
+    unpack_i128(buf, COMPACT_HEADER_LEN + index * 16)
+}
+
+// ════════════════════════════════════════════════════════════════════════════
+// Compact Emitters
+// ════════════════════════════════════════════════════════════════════════════
+
+/// Publish a compact event with a single subject address
+#[allow(deprecated)]
+pub fn publish_compact(env: &Env, kind: CompactEventKind, subject: &Address, fields: &[i128]) {
+    let topics = (Symbol::new(env, "compact"), kind as u32, subject.clone());
+    env.events().publish(topics, pack_payload(env, fields));
+}
+
+/// Publish a compact event with a subject and a counterparty address
+#[allow(deprecated)]
+pub fn publish_compact_pair(
+    env: &Env,
+    kind: CompactEventKind,
+    subject: &Address,
+    counterparty: &Address,
+    fields: &[i128],
+) {
+    let topics = (
+        Symbol::new(env, "compact"),
+        kind as u32,
+        subject.clone(),
+        counterparty.clone(),
+    );
+    env.events().publish(topics, pack_payload(env, fields));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pack_roundtrip() {
+        let env = Env::default();
+        let mut buf = Bytes::new(&env);
+        pack_u64(&mut buf, 1_700_000_000);
+        pack_i128(&mut buf, -42);
+        pack_i128(&mut buf, i128::MAX);
+
+        assert_eq!(buf.len(), 8 + 16 + 16);
+        assert_eq!(unpack_u64(&buf, 0), Some(1_700_000_000));
+        assert_eq!(unpack_i128(&buf, 8), Some(-42));
+        assert_eq!(unpack_i128(&buf, 24), Some(i128::MAX));
+        assert_eq!(unpack_i128(&buf, 32), None);
+    }
+
+    #[test]
+    fn test_payload_layout() {
+        let env = Env::default();
+        let payload = pack_payload(&env, &[100, 50, 30, 20]);
+
+        assert_eq!(payload.len(), COMPACT_HEADER_LEN + 4 * 16);
+        assert_eq!(payload.get(0), Some(COMPACT_LAYOUT_VERSION));
+        assert_eq!(unpack_field(&payload, 0), Some(100));
+        assert_eq!(unpack_field(&payload, 3), Some(20));
+        assert_eq!(unpack_field(&payload, 4), None);
+    }
+
+    #[test]
+    fn test_stake_payload_carries_fee() {
+        use crate::events::emit_stake;
+        use soroban_sdk::testutils::{Address as _, Events as _};
+        use soroban_sdk::{contract, contractimpl, vec, IntoVal};
+
+        #[contract]
+        pub struct Emitter;
+
+        #[contractimpl]
+        impl Emitter {}
+
+        let env = Env::default();
+        let contract_id = env.register(Emitter, ());
+        let user = Address::generate(&env);
+
+        env.as_contract(&contract_id, || {
+            set_compact_mask(&env, CompactEventKind::Stake.bit());
+            emit_stake(&env, &user, 300, 1_000, 3);
+        });
+
+        let payload = pack_payload(&env, &[300, 1_000, 3]);
+        assert_eq!(payload.get(0), Some(2));
+        assert_eq!(unpack_field(&payload, 2), Some(3));
+        assert_eq!(
+            env.events().all(),
+            vec![
+                &env,
+                (
+                    contract_id.clone(),
+                    (
+                        Symbol::new(&env, "compact"),
+                        CompactEventKind::Stake as u32,
+                        user.clone()
+                    )
+                        .into_val(&env),
+                    payload.into_val(&env),
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_kind_bits() {
+        assert_eq!(CompactEventKind::Swap.bit(), 1);
+        assert_eq!(CompactEventKind::Claim.bit(), 1 << 5);
+    }
+}
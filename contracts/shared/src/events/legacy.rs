@@ -0,0 +1,334 @@
+//! Positional tuple emitters (`legacy-events` feature).
+//!
+//! Same signatures as the structured emitters, publishing
+//! `(name, subject) -> (fields..., timestamp)` for indexers that have not
+//! moved to the versioned structs yet.
+
+use soroban_sdk::{Address, Env};
+
+use super::*;
+use crate::types::PenaltySplit;
+
+/// Emit initialization event
+pub fn emit_initialized(env: &Env, admin: &Address) {
+    EventBuilder::new(env).publish("initialized", admin.clone(), env.ledger().timestamp());
+}
+
+/// Emit deposit event
+pub fn emit_deposit(env: &Env, token: &Address, from: &Address, amount: i128) {
+    EventBuilder::new(env).publish(
+        "deposit",
+        token.clone(),
+        (from.clone(), amount, env.ledger().timestamp()),
+    );
+}
+
+/// Emit withdraw event
+pub fn emit_withdraw(env: &Env, token: &Address, to: &Address, amount: i128) {
+    EventBuilder::new(env).publish(
+        "withdraw",
+        token.clone(),
+        (to.clone(), amount, env.ledger().timestamp()),
+    );
+}
+
+/// Emit stake event
+pub fn emit_stake(env: &Env, user: &Address, amount: i128, total_staked: i128, fee: i128) {
+    if is_compact(env, CompactEventKind::Stake) {
+        publish_compact(
+            env,
+            CompactEventKind::Stake,
+            user,
+            &[amount, total_staked, fee],
+        );
+        return;
+    }
+    EventBuilder::new(env).publish(
+        "stake",
+        user.clone(),
+        (amount, total_staked, fee, env.ledger().timestamp()),
+    );
+}
+
+/// Emit unstake event
+pub fn emit_unstake(env: &Env, user: &Address, amount: i128, remaining: i128, fee: i128) {
+    if is_compact(env, CompactEventKind::Unstake) {
+        publish_compact(
+            env,
+            CompactEventKind::Unstake,
+            user,
+            &[amount, remaining, fee],
+        );
+        return;
+    }
+    EventBuilder::new(env).publish(
+        "unstake",
+        user.clone(),
+        (amount, remaining, fee, env.ledger().timestamp()),
+    );
+}
+
+/// Emit claim event
+pub fn emit_claim(env: &Env, user: &Address, token: &Address, amount: i128) {
+    if is_compact(env, CompactEventKind::Claim) {
+        publish_compact_pair(env, CompactEventKind::Claim, user, token, &[amount]);
+        return;
+    }
+    EventBuilder::new(env).publish(
+        "claim",
+        user.clone(),
+        (token.clone(), amount, env.ledger().timestamp()),
+    );
+}
+
+/// Emit lock event
+#[allow(clippy::too_many_arguments)]
+pub fn emit_lock(
+    env: &Env,
+    lock_id: u64,
+    owner: &Address,
+    token: &Address,
+    amount: i128,
+    unlock_time: u64,
+    total_locked: i128,
+    lock_count: u32,
+) {
+    EventBuilder::new(env).publish(
+        "lock",
+        owner.clone(),
+        (
+            lock_id,
+            token.clone(),
+            amount,
+            unlock_time,
+            total_locked,
+            lock_count,
+        ),
+    );
+}
+
+/// Emit unlock event
+pub fn emit_unlock(
+    env: &Env,
+    lock_id: u64,
+    owner: &Address,
+    token: &Address,
+    amount: i128,
+    total_locked: i128,
+    lock_count: u32,
+) {
+    EventBuilder::new(env).publish(
+        "unlock",
+        owner.clone(),
+        (
+            lock_id,
+            token.clone(),
+            amount,
+            total_locked,
+            lock_count,
+            env.ledger().timestamp(),
+        ),
+    );
+}
+
+/// Emit lock extended event
+pub fn emit_lock_extended(
+    env: &Env,
+    lock_id: u64,
+    owner: &Address,
+    token: &Address,
+    unlock_time: u64,
+    total_locked: i128,
+    lock_count: u32,
+) {
+    EventBuilder::new(env).publish(
+        "lock_extended",
+        owner.clone(),
+        (
+            lock_id,
+            token.clone(),
+            unlock_time,
+            total_locked,
+            lock_count,
+            env.ledger().timestamp(),
+        ),
+    );
+}
+
+/// Emit lock transferred event
+#[allow(clippy::too_many_arguments)]
+pub fn emit_lock_transferred(
+    env: &Env,
+    lock_id: u64,
+    from: &Address,
+    to: &Address,
+    token: &Address,
+    amount: i128,
+    total_locked: i128,
+    from_lock_count: u32,
+    to_lock_count: u32,
+) {
+    EventBuilder::new(env).publish(
+        "lock_transferred",
+        from.clone(),
+        (
+            lock_id,
+            to.clone(),
+            token.clone(),
+            amount,
+            total_locked,
+            from_lock_count,
+            to_lock_count,
+            env.ledger().timestamp(),
+        ),
+    );
+}
+
+/// Emit penalty routed event
+pub fn emit_penalty_routed(
+    env: &Env,
+    token: &Address,
+    payer: &Address,
+    split: &PenaltySplit,
+    burned: bool,
+) {
+    EventBuilder::new(env).publish(
+        "penalty_routed",
+        token.clone(),
+        (
+            payer.clone(),
+            split.to_treasury,
+            split.to_burn,
+            split.to_stakers,
+            burned,
+            env.ledger().timestamp(),
+        ),
+    );
+}
+
+/// Emit distribution event
+pub fn emit_distribution(
+    env: &Env,
+    token: &Address,
+    total: i128,
+    treasury: i128,
+    staking: i128,
+    burn: i128,
+) {
+    if is_compact(env, CompactEventKind::Distribution) {
+        publish_compact(
+            env,
+            CompactEventKind::Distribution,
+            token,
+            &[total, treasury, staking, burn],
+        );
+        return;
+    }
+    EventBuilder::new(env).publish(
+        "distribution",
+        token.clone(),
+        (total, treasury, staking, burn, env.ledger().timestamp()),
+    );
+}
+
+/// Emit fee received event
+pub fn emit_fee_received(env: &Env, token: &Address, from: &Address, amount: i128) {
+    if is_compact(env, CompactEventKind::FeeReceived) {
+        publish_compact_pair(env, CompactEventKind::FeeReceived, token, from, &[amount]);
+        return;
+    }
+    EventBuilder::new(env).publish(
+        "fee_received",
+        token.clone(),
+        (from.clone(), amount, env.ledger().timestamp()),
+    );
+}
+
+/// Emit admin change event
+pub fn emit_admin_changed(env: &Env, old_admin: &Address, new_admin: &Address) {
+    EventBuilder::new(env).publish(
+        "admin_changed",
+        new_admin.clone(),
+        (old_admin.clone(), env.ledger().timestamp()),
+    );
+}
+
+/// Emit pause event
+pub fn emit_paused(env: &Env, paused: bool, by: &Address) {
+    EventBuilder::new(env).publish("paused", by.clone(), (paused, env.ledger().timestamp()));
+}
+
+/// Emit config updated event
+pub fn emit_config_updated(env: &Env, by: &Address) {
+    EventBuilder::new(env).publish("config_updated", by.clone(), env.ledger().timestamp());
+}
+
+/// Emit treasury changed event
+pub fn emit_treasury_changed(env: &Env, old_treasury: Option<Address>, new_treasury: &Address) {
+    EventBuilder::new(env).publish(
+        "treasury_changed",
+        new_treasury.clone(),
+        (old_treasury, env.ledger().timestamp()),
+    );
+}
+
+/// Emit fee distributor changed event
+pub fn emit_fee_distributor_changed(
+    env: &Env,
+    old_fee_distributor: Option<Address>,
+    new_fee_distributor: &Address,
+) {
+    EventBuilder::new(env).publish(
+        "fee_distributor_changed",
+        new_fee_distributor.clone(),
+        (old_fee_distributor, env.ledger().timestamp()),
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use soroban_sdk::testutils::{Address as _, Events as _};
+    use soroban_sdk::{contract, contractimpl, vec, IntoVal, Symbol};
+
+    #[contract]
+    pub struct Emitter;
+
+    #[contractimpl]
+    impl Emitter {}
+
+    #[test]
+    fn test_tuple_payloads() {
+        let env = Env::default();
+        let contract_id = env.register(Emitter, ());
+        let token = Address::generate(&env);
+        let user = Address::generate(&env);
+        let now = env.ledger().timestamp();
+
+        env.as_contract(&contract_id, || emit_deposit(&env, &token, &user, 500));
+        assert_eq!(
+            env.events().all(),
+            vec![
+                &env,
+                (
+                    contract_id.clone(),
+                    (Symbol::new(&env, "deposit"), token.clone()).into_val(&env),
+                    (user.clone(), 500_i128, now).into_val(&env),
+                ),
+            ]
+        );
+
+        env.as_contract(&contract_id, || emit_stake(&env, &user, 300, 1_000, 3));
+        assert_eq!(
+            env.events().all(),
+            vec![
+                &env,
+                (
+                    contract_id.clone(),
+                    (Symbol::new(&env, "stake"), user.clone()).into_val(&env),
+                    (300_i128, 1_000_i128, 3_i128, now).into_val(&env),
+                ),
+            ]
+        );
+    }
+}
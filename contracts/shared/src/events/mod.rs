@@ -2,9 +2,34 @@
 //!
 //! Common event emission helpers for the Astro ecosystem.
 //! Using `#[contractevent]` macro for better type safety and indexing.
+//!
+//! High-frequency events (stakes, claims, fee receipts, distributions) can be
+//! switched to a packed `Bytes` payload per kind; see [`compact`].
+//!
+//! ## Schema Versioning
+//! Every standard event carries a `version` field (`EVENT_SCHEMA_VERSION`),
+//! bumped whenever a struct's fields change so indexers can decode old and
+//! new payloads side by side. Indexers still reading the positional tuple
+//! payloads can build with the `legacy-events` feature, which swaps the
+//! `emit_*` helpers for the tuple emitters.
+
+mod compact;
+#[cfg(feature = "legacy-events")]
+mod legacy;
+#[cfg(not(feature = "legacy-events"))]
+mod structured;
+
+pub use compact::*;
+#[cfg(feature = "legacy-events")]
+pub use legacy::*;
+#[cfg(not(feature = "legacy-events"))]
+pub use structured::*;
 
 use soroban_sdk::{contractevent, Address, Env};
 
+/// Version of the standard event structs below
+pub const EVENT_SCHEMA_VERSION: u32 = 2;
+
 // ════════════════════════════════════════════════════════════════════════════
 // Contract Events (SDK 25.x pattern)
 // ════════════════════════════════════════════════════════════════════════════
@@ -16,6 +41,7 @@ pub struct InitializedEvent {
     #[topic]
     pub admin: Address,
     pub timestamp: u64,
+    pub version: u32,
 }
 
 /// Deposit event
@@ -27,6 +53,7 @@ pub struct DepositEvent {
     pub from: Address,
     pub amount: i128,
     pub timestamp: u64,
+    pub version: u32,
 }
 
 /// Withdraw event
@@ -38,6 +65,7 @@ pub struct WithdrawEvent {
     pub to: Address,
     pub amount: i128,
     pub timestamp: u64,
+    pub version: u32,
 }
 
 /// Stake event
@@ -48,7 +76,9 @@ pub struct StakeEvent {
     pub user: Address,
     pub amount: i128,
     pub total_staked: i128,
+    pub fee: i128,
     pub timestamp: u64,
+    pub version: u32,
 }
 
 /// Unstake event
@@ -59,7 +89,9 @@ pub struct UnstakeEvent {
     pub user: Address,
     pub amount: i128,
     pub remaining: i128,
+    pub fee: i128,
     pub timestamp: u64,
+    pub version: u32,
 }
 
 /// Claim rewards event
@@ -71,9 +103,14 @@ pub struct ClaimEvent {
     pub token: Address,
     pub amount: i128,
     pub timestamp: u64,
+    pub version: u32,
 }
 
 /// Lock event
+///
+/// `total_locked` is the token's locked total after the lock and
+/// `lock_count` the number of locks in the owner's index, so indexers can
+/// keep aggregates without reading storage.
 #[contractevent]
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct LockEvent {
@@ -83,9 +120,12 @@ pub struct LockEvent {
     pub token: Address,
     pub amount: i128,
     pub unlock_time: u64,
+    pub total_locked: i128,
+    pub lock_count: u32,
+    pub version: u32,
 }
 
-/// Unlock event
+/// Unlock event (totals as in `LockEvent`, after the unlock)
 #[contractevent]
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct UnlockEvent {
@@ -94,7 +134,60 @@ pub struct UnlockEvent {
     pub lock_id: u64,
     pub token: Address,
     pub amount: i128,
+    pub total_locked: i128,
+    pub lock_count: u32,
+    pub timestamp: u64,
+    pub version: u32,
+}
+
+/// Lock extended event
+#[contractevent]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct LockExtendedEvent {
+    #[topic]
+    pub owner: Address,
+    pub lock_id: u64,
+    pub token: Address,
+    pub unlock_time: u64,
+    pub total_locked: i128,
+    pub lock_count: u32,
     pub timestamp: u64,
+    pub version: u32,
+}
+
+/// Lock transferred event, with both owners' lock counts after the move
+#[contractevent]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct LockTransferredEvent {
+    #[topic]
+    pub from: Address,
+    #[topic]
+    pub to: Address,
+    pub lock_id: u64,
+    pub token: Address,
+    pub amount: i128,
+    pub total_locked: i128,
+    pub from_lock_count: u32,
+    pub to_lock_count: u32,
+    pub timestamp: u64,
+    pub version: u32,
+}
+
+/// Penalty routed event (locker early unlocks, staking early exits).
+/// `burned` is false when the token couldn't be burned and the burn share
+/// went to the fallback burn address instead.
+#[contractevent]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PenaltyRoutedEvent {
+    #[topic]
+    pub token: Address,
+    pub payer: Address,
+    pub to_treasury: i128,
+    pub to_burn: i128,
+    pub to_stakers: i128,
+    pub burned: bool,
+    pub timestamp: u64,
+    pub version: u32,
 }
 
 /// Fee distribution event
@@ -108,6 +201,19 @@ pub struct DistributionEvent {
     pub staking: i128,
     pub burn: i128,
     pub timestamp: u64,
+    pub version: u32,
+}
+
+/// Fee received event
+#[contractevent]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct FeeReceivedEvent {
+    #[topic]
+    pub token: Address,
+    pub from: Address,
+    pub amount: i128,
+    pub timestamp: u64,
+    pub version: u32,
 }
 
 /// Admin changed event
@@ -118,6 +224,7 @@ pub struct AdminChangedEvent {
     pub new_admin: Address,
     pub old_admin: Address,
     pub timestamp: u64,
+    pub version: u32,
 }
 
 /// Contract paused/unpaused event
@@ -128,145 +235,39 @@ pub struct PausedEvent {
     pub by: Address,
     pub paused: bool,
     pub timestamp: u64,
+    pub version: u32,
 }
 
-// ════════════════════════════════════════════════════════════════════════════
-// Helper Functions (backwards compatible API)
-// ════════════════════════════════════════════════════════════════════════════
-
-/// Emit initialization event
-pub fn emit_initialized(env: &Env, admin: &Address) {
-    InitializedEvent {
-        admin: admin.clone(),
-        timestamp: env.ledger().timestamp(),
-    }
-    .publish(env);
-}
-
-/// Emit deposit event
-pub fn emit_deposit(env: &Env, token: &Address, from: &Address, amount: i128) {
-    DepositEvent {
-        token: token.clone(),
-        from: from.clone(),
-        amount,
-        timestamp: env.ledger().timestamp(),
-    }
-    .publish(env);
-}
-
-/// Emit withdraw event
-pub fn emit_withdraw(env: &Env, token: &Address, to: &Address, amount: i128) {
-    WithdrawEvent {
-        token: token.clone(),
-        to: to.clone(),
-        amount,
-        timestamp: env.ledger().timestamp(),
-    }
-    .publish(env);
-}
-
-/// Emit stake event
-pub fn emit_stake(env: &Env, user: &Address, amount: i128, total_staked: i128) {
-    StakeEvent {
-        user: user.clone(),
-        amount,
-        total_staked,
-        timestamp: env.ledger().timestamp(),
-    }
-    .publish(env);
-}
-
-/// Emit unstake event
-pub fn emit_unstake(env: &Env, user: &Address, amount: i128, remaining: i128) {
-    UnstakeEvent {
-        user: user.clone(),
-        amount,
-        remaining,
-        timestamp: env.ledger().timestamp(),
-    }
-    .publish(env);
-}
-
-/// Emit claim event
-pub fn emit_claim(env: &Env, user: &Address, token: &Address, amount: i128) {
-    ClaimEvent {
-        user: user.clone(),
-        token: token.clone(),
-        amount,
-        timestamp: env.ledger().timestamp(),
-    }
-    .publish(env);
-}
-
-/// Emit lock event
-pub fn emit_lock(
-    env: &Env,
-    lock_id: u64,
-    owner: &Address,
-    token: &Address,
-    amount: i128,
-    unlock_time: u64,
-) {
-    LockEvent {
-        lock_id,
-        owner: owner.clone(),
-        token: token.clone(),
-        amount,
-        unlock_time,
-    }
-    .publish(env);
-}
-
-/// Emit unlock event
-pub fn emit_unlock(env: &Env, lock_id: u64, owner: &Address, token: &Address, amount: i128) {
-    UnlockEvent {
-        lock_id,
-        owner: owner.clone(),
-        token: token.clone(),
-        amount,
-        timestamp: env.ledger().timestamp(),
-    }
-    .publish(env);
-}
-
-/// Emit distribution event
-pub fn emit_distribution(
-    env: &Env,
-    token: &Address,
-    total: i128,
-    treasury: i128,
-    staking: i128,
-    burn: i128,
-) {
-    DistributionEvent {
-        token: token.clone(),
-        total,
-        treasury,
-        staking,
-        burn,
-        timestamp: env.ledger().timestamp(),
-    }
-    .publish(env);
+/// Contract configuration updated event
+#[contractevent]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ConfigUpdatedEvent {
+    #[topic]
+    pub by: Address,
+    pub timestamp: u64,
+    pub version: u32,
 }
 
-/// Emit admin change event
-pub fn emit_admin_changed(env: &Env, old_admin: &Address, new_admin: &Address) {
-    AdminChangedEvent {
-        old_admin: old_admin.clone(),
-        new_admin: new_admin.clone(),
-        timestamp: env.ledger().timestamp(),
-    }
-    .publish(env);
+/// Treasury address changed event
+#[contractevent]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TreasuryChangedEvent {
+    #[topic]
+    pub new_treasury: Address,
+    pub old_treasury: Option<Address>,
+    pub timestamp: u64,
+    pub version: u32,
 }
 
-/// Emit pause event
-pub fn emit_paused(env: &Env, paused: bool, by: &Address) {
-    PausedEvent {
-        paused,
-        by: by.clone(),
-        timestamp: env.ledger().timestamp(),
-    }
-    .publish(env);
+/// Fee distributor address changed event
+#[contractevent]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct FeeDistributorChangedEvent {
+    #[topic]
+    pub new_fee_distributor: Address,
+    pub old_fee_distributor: Option<Address>,
+    pub timestamp: u64,
+    pub version: u32,
 }
 
 // ════════════════════════════════════════════════════════════════════════════
@@ -0,0 +1,366 @@
+//! Standard event emitters publishing the versioned event structs.
+
+use soroban_sdk::{Address, Env};
+
+use super::*;
+use crate::types::PenaltySplit;
+
+/// Emit initialization event
+pub fn emit_initialized(env: &Env, admin: &Address) {
+    InitializedEvent {
+        admin: admin.clone(),
+        timestamp: env.ledger().timestamp(),
+        version: EVENT_SCHEMA_VERSION,
+    }
+    .publish(env);
+}
+
+/// Emit deposit event
+pub fn emit_deposit(env: &Env, token: &Address, from: &Address, amount: i128) {
+    DepositEvent {
+        token: token.clone(),
+        from: from.clone(),
+        amount,
+        timestamp: env.ledger().timestamp(),
+        version: EVENT_SCHEMA_VERSION,
+    }
+    .publish(env);
+}
+
+/// Emit withdraw event
+pub fn emit_withdraw(env: &Env, token: &Address, to: &Address, amount: i128) {
+    WithdrawEvent {
+        token: token.clone(),
+        to: to.clone(),
+        amount,
+        timestamp: env.ledger().timestamp(),
+        version: EVENT_SCHEMA_VERSION,
+    }
+    .publish(env);
+}
+
+/// Emit stake event
+pub fn emit_stake(env: &Env, user: &Address, amount: i128, total_staked: i128, fee: i128) {
+    if is_compact(env, CompactEventKind::Stake) {
+        publish_compact(
+            env,
+            CompactEventKind::Stake,
+            user,
+            &[amount, total_staked, fee],
+        );
+        return;
+    }
+    StakeEvent {
+        user: user.clone(),
+        amount,
+        total_staked,
+        fee,
+        timestamp: env.ledger().timestamp(),
+        version: EVENT_SCHEMA_VERSION,
+    }
+    .publish(env);
+}
+
+/// Emit unstake event
+pub fn emit_unstake(env: &Env, user: &Address, amount: i128, remaining: i128, fee: i128) {
+    if is_compact(env, CompactEventKind::Unstake) {
+        publish_compact(
+            env,
+            CompactEventKind::Unstake,
+            user,
+            &[amount, remaining, fee],
+        );
+        return;
+    }
+    UnstakeEvent {
+        user: user.clone(),
+        amount,
+        remaining,
+        fee,
+        timestamp: env.ledger().timestamp(),
+        version: EVENT_SCHEMA_VERSION,
+    }
+    .publish(env);
+}
+
+/// Emit claim event
+pub fn emit_claim(env: &Env, user: &Address, token: &Address, amount: i128) {
+    if is_compact(env, CompactEventKind::Claim) {
+        publish_compact_pair(env, CompactEventKind::Claim, user, token, &[amount]);
+        return;
+    }
+    ClaimEvent {
+        user: user.clone(),
+        token: token.clone(),
+        amount,
+        timestamp: env.ledger().timestamp(),
+        version: EVENT_SCHEMA_VERSION,
+    }
+    .publish(env);
+}
+
+/// Emit lock event
+#[allow(clippy::too_many_arguments)]
+pub fn emit_lock(
+    env: &Env,
+    lock_id: u64,
+    owner: &Address,
+    token: &Address,
+    amount: i128,
+    unlock_time: u64,
+    total_locked: i128,
+    lock_count: u32,
+) {
+    LockEvent {
+        lock_id,
+        owner: owner.clone(),
+        token: token.clone(),
+        amount,
+        unlock_time,
+        total_locked,
+        lock_count,
+        version: EVENT_SCHEMA_VERSION,
+    }
+    .publish(env);
+}
+
+/// Emit unlock event
+pub fn emit_unlock(
+    env: &Env,
+    lock_id: u64,
+    owner: &Address,
+    token: &Address,
+    amount: i128,
+    total_locked: i128,
+    lock_count: u32,
+) {
+    UnlockEvent {
+        lock_id,
+        owner: owner.clone(),
+        token: token.clone(),
+        amount,
+        total_locked,
+        lock_count,
+        timestamp: env.ledger().timestamp(),
+        version: EVENT_SCHEMA_VERSION,
+    }
+    .publish(env);
+}
+
+/// Emit lock extended event
+pub fn emit_lock_extended(
+    env: &Env,
+    lock_id: u64,
+    owner: &Address,
+    token: &Address,
+    unlock_time: u64,
+    total_locked: i128,
+    lock_count: u32,
+) {
+    LockExtendedEvent {
+        lock_id,
+        owner: owner.clone(),
+        token: token.clone(),
+        unlock_time,
+        total_locked,
+        lock_count,
+        timestamp: env.ledger().timestamp(),
+        version: EVENT_SCHEMA_VERSION,
+    }
+    .publish(env);
+}
+
+/// Emit lock transferred event
+#[allow(clippy::too_many_arguments)]
+pub fn emit_lock_transferred(
+    env: &Env,
+    lock_id: u64,
+    from: &Address,
+    to: &Address,
+    token: &Address,
+    amount: i128,
+    total_locked: i128,
+    from_lock_count: u32,
+    to_lock_count: u32,
+) {
+    LockTransferredEvent {
+        lock_id,
+        from: from.clone(),
+        to: to.clone(),
+        token: token.clone(),
+        amount,
+        total_locked,
+        from_lock_count,
+        to_lock_count,
+        timestamp: env.ledger().timestamp(),
+        version: EVENT_SCHEMA_VERSION,
+    }
+    .publish(env);
+}
+
+/// Emit penalty routed event
+pub fn emit_penalty_routed(
+    env: &Env,
+    token: &Address,
+    payer: &Address,
+    split: &PenaltySplit,
+    burned: bool,
+) {
+    PenaltyRoutedEvent {
+        token: token.clone(),
+        payer: payer.clone(),
+        to_treasury: split.to_treasury,
+        to_burn: split.to_burn,
+        to_stakers: split.to_stakers,
+        burned,
+        timestamp: env.ledger().timestamp(),
+        version: EVENT_SCHEMA_VERSION,
+    }
+    .publish(env);
+}
+
+/// Emit distribution event
+pub fn emit_distribution(
+    env: &Env,
+    token: &Address,
+    total: i128,
+    treasury: i128,
+    staking: i128,
+    burn: i128,
+) {
+    if is_compact(env, CompactEventKind::Distribution) {
+        publish_compact(
+            env,
+            CompactEventKind::Distribution,
+            token,
+            &[total, treasury, staking, burn],
+        );
+        return;
+    }
+    DistributionEvent {
+        token: token.clone(),
+        total,
+        treasury,
+        staking,
+        burn,
+        timestamp: env.ledger().timestamp(),
+        version: EVENT_SCHEMA_VERSION,
+    }
+    .publish(env);
+}
+
+/// Emit fee received event
+pub fn emit_fee_received(env: &Env, token: &Address, from: &Address, amount: i128) {
+    if is_compact(env, CompactEventKind::FeeReceived) {
+        publish_compact_pair(env, CompactEventKind::FeeReceived, token, from, &[amount]);
+        return;
+    }
+    FeeReceivedEvent {
+        token: token.clone(),
+        from: from.clone(),
+        amount,
+        timestamp: env.ledger().timestamp(),
+        version: EVENT_SCHEMA_VERSION,
+    }
+    .publish(env);
+}
+
+/// Emit admin change event
+pub fn emit_admin_changed(env: &Env, old_admin: &Address, new_admin: &Address) {
+    AdminChangedEvent {
+        old_admin: old_admin.clone(),
+        new_admin: new_admin.clone(),
+        timestamp: env.ledger().timestamp(),
+        version: EVENT_SCHEMA_VERSION,
+    }
+    .publish(env);
+}
+
+/// Emit pause event
+pub fn emit_paused(env: &Env, paused: bool, by: &Address) {
+    PausedEvent {
+        paused,
+        by: by.clone(),
+        timestamp: env.ledger().timestamp(),
+        version: EVENT_SCHEMA_VERSION,
+    }
+    .publish(env);
+}
+
+/// Emit config updated event
+pub fn emit_config_updated(env: &Env, by: &Address) {
+    ConfigUpdatedEvent {
+        by: by.clone(),
+        timestamp: env.ledger().timestamp(),
+        version: EVENT_SCHEMA_VERSION,
+    }
+    .publish(env);
+}
+
+/// Emit treasury changed event
+pub fn emit_treasury_changed(env: &Env, old_treasury: Option<Address>, new_treasury: &Address) {
+    TreasuryChangedEvent {
+        old_treasury,
+        new_treasury: new_treasury.clone(),
+        timestamp: env.ledger().timestamp(),
+        version: EVENT_SCHEMA_VERSION,
+    }
+    .publish(env);
+}
+
+/// Emit fee distributor changed event
+pub fn emit_fee_distributor_changed(
+    env: &Env,
+    old_fee_distributor: Option<Address>,
+    new_fee_distributor: &Address,
+) {
+    FeeDistributorChangedEvent {
+        old_fee_distributor,
+        new_fee_distributor: new_fee_distributor.clone(),
+        timestamp: env.ledger().timestamp(),
+        version: EVENT_SCHEMA_VERSION,
+    }
+    .publish(env);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use soroban_sdk::testutils::{Address as _, Events as _};
+    use soroban_sdk::{contract, contractimpl, Event as _};
+
+    #[contract]
+    pub struct Emitter;
+
+    #[contractimpl]
+    impl Emitter {}
+
+    #[test]
+    fn test_versioned_payloads() {
+        let env = Env::default();
+        let contract_id = env.register(Emitter, ());
+        let token = Address::generate(&env);
+        let user = Address::generate(&env);
+
+        env.as_contract(&contract_id, || emit_deposit(&env, &token, &user, 500));
+        let deposit = DepositEvent {
+            token: token.clone(),
+            from: user.clone(),
+            amount: 500,
+            timestamp: env.ledger().timestamp(),
+            version: EVENT_SCHEMA_VERSION,
+        };
+        assert_eq!(env.events().all(), [deposit.to_xdr(&env, &contract_id)]);
+
+        env.as_contract(&contract_id, || emit_stake(&env, &user, 300, 1_000, 3));
+        let stake = StakeEvent {
+            user: user.clone(),
+            amount: 300,
+            total_staked: 1_000,
+            fee: 3,
+            timestamp: env.ledger().timestamp(),
+            version: EVENT_SCHEMA_VERSION,
+        };
+        assert_eq!(env.events().all(), [stake.to_xdr(&env, &contract_id)]);
+    }
+}
@@ -1,385 +1,421 @@
 //! # Cross-Contract Interfaces
 //!
-//! Type-safe client wrappers for cross-contract calls.
-//! These avoid the need to import WASM files directly.
-
-use crate::types::{DistributionResult, GraduationInfo, LockInfo, TokenMetadata, UserStake};
-use soroban_sdk::{Address, Env, IntoVal, Symbol, Vec};
+//! Interface traits for cross-contract calls. Each trait mirrors the public
+//! entrypoints of a contract and `#[contractclient]` generates its client,
+//! so call sites are type-checked against these signatures and every method
+//! has a `try_` variant that returns the callee's `SharedError` instead of
+//! trapping. These avoid the need to import WASM files directly.
+//!
+//! Arguments are passed by reference, as with any generated client. The
+//! `try_` variants return a nested result; `into_shared` flattens it into a
+//! `SharedError` so a caller can recover from a trapping callee:
+//! ```rust,ignore
+//! let staked = StakingPoolClient::new(&env, &pool).stake(&user, &amount, &None);
+//!
+//! let graduated = BridgeClient::new(&env, &bridge)
+//!     .try_is_graduated(&token)
+//!     .into_shared()
+//!     .unwrap_or(false);
+//! ```
+
+use crate::types::{
+    DistributionResult, GraduationInfo, GraduationInfoV2, LockInfo, SharedError, TokenMetadata,
+    TokenStatus, UserStake,
+};
+use soroban_sdk::{contractclient, Address, Env, InvokeError, String, Vec};
 
 // ════════════════════════════════════════════════════════════════════════════
-// Fee Distributor Client
+// Fallible Calls
 // ════════════════════════════════════════════════════════════════════════════
 
-/// Client for Fee Distributor contract
-pub struct FeeDistributorClient<'a> {
-    env: &'a Env,
-    contract_id: Address,
+/// Flattens the result of a generated `try_` client method into
+/// `Result<T, SharedError>`
+///
+/// In `Result<Result<T, C>, Result<E, InvokeError>>` the outer error is the
+/// callee's failure and the inner one a return value that failed to decode.
+/// `E` is `SharedError` for entrypoints that return a `Result`, and
+/// `soroban_sdk::Error` for the rest.
+pub trait IntoSharedResult<T> {
+    /// The callee's own `SharedError` is passed through; a trap, an unknown
+    /// error code or an undecodable return value become
+    /// `CrossContractCallFailed`
+    fn into_shared(self) -> Result<T, SharedError>;
 }
 
-impl<'a> FeeDistributorClient<'a> {
-    pub fn new(env: &'a Env, contract_id: &Address) -> Self {
-        Self {
-            env,
-            contract_id: contract_id.clone(),
+impl<T, C, E> IntoSharedResult<T> for Result<Result<T, C>, Result<E, InvokeError>>
+where
+    E: TryInto<SharedError>,
+{
+    fn into_shared(self) -> Result<T, SharedError> {
+        match self {
+            Ok(Ok(value)) => Ok(value),
+            Err(Ok(error)) => Err(error
+                .try_into()
+                .unwrap_or(SharedError::CrossContractCallFailed)),
+            Ok(Err(_)) | Err(Err(_)) => Err(SharedError::CrossContractCallFailed),
         }
     }
+}
 
+// ════════════════════════════════════════════════════════════════════════════
+// Fee Distributor Client
+// ════════════════════════════════════════════════════════════════════════════
+
+/// Interface of the Fee Distributor contract
+#[contractclient(name = "FeeDistributorClient")]
+pub trait FeeDistributorInterface {
     /// Distribute fees for a token
-    pub fn distribute(&self, token: &Address, amount: i128) -> DistributionResult {
-        self.env.invoke_contract(
-            &self.contract_id,
-            &Symbol::new(self.env, "distribute"),
-            Vec::from_array(
-                self.env,
-                [token.into_val(self.env), amount.into_val(self.env)],
-            ),
-        )
-    }
+    fn distribute(
+        env: Env,
+        token: Address,
+        deadline: Option<u64>,
+    ) -> Result<DistributionResult, SharedError>;
+
+    /// Send fees to the distributor (pulled from `caller`)
+    fn receive_fees(
+        env: Env,
+        caller: Address,
+        token: Address,
+        amount: i128,
+    ) -> Result<(), SharedError>;
 
     /// Get pending distribution for a token
-    pub fn get_pending(&self, token: &Address) -> i128 {
-        self.env.invoke_contract(
-            &self.contract_id,
-            &Symbol::new(self.env, "get_pending_distribution"),
-            Vec::from_array(self.env, [token.into_val(self.env)]),
-        )
-    }
+    fn get_pending_distribution(env: Env, token: Address) -> i128;
 }
 
 // ════════════════════════════════════════════════════════════════════════════
 // Staking Pool Client
 // ════════════════════════════════════════════════════════════════════════════
 
-/// Client for Staking Pool contract
-pub struct StakingPoolClient<'a> {
-    env: &'a Env,
-    contract_id: Address,
-}
+/// Interface of the Staking Pool contract
+#[contractclient(name = "StakingPoolClient")]
+pub trait StakingPoolInterface {
+    /// Stake tokens, returning the new staked balance
+    fn stake(
+        env: Env,
+        user: Address,
+        amount: i128,
+        deadline: Option<u64>,
+    ) -> Result<i128, SharedError>;
+
+    /// Unstake tokens, returning the remaining staked balance
+    fn unstake(
+        env: Env,
+        user: Address,
+        amount: i128,
+        deadline: Option<u64>,
+    ) -> Result<i128, SharedError>;
+
+    /// Add rewards to pool (called by Fee Distributor).
+    /// Tokens are pulled from `caller`; returns the amount actually received.
+    fn add_rewards(
+        env: Env,
+        caller: Address,
+        reward_token: Address,
+        amount: i128,
+    ) -> Result<i128, SharedError>;
+
+    /// Claim pending rewards without unstaking
+    fn claim(env: Env, user: Address) -> Result<Vec<(Address, i128)>, SharedError>;
 
-impl<'a> StakingPoolClient<'a> {
-    pub fn new(env: &'a Env, contract_id: &Address) -> Self {
-        Self {
-            env,
-            contract_id: contract_id.clone(),
-        }
-    }
+    /// Get user stake info
+    fn get_stake(env: Env, user: Address) -> UserStake;
 
-    /// Stake tokens
-    pub fn stake(&self, user: &Address, amount: i128) {
-        self.env.invoke_contract::<()>(
-            &self.contract_id,
-            &Symbol::new(self.env, "stake"),
-            Vec::from_array(
-                self.env,
-                [user.into_val(self.env), amount.into_val(self.env)],
-            ),
-        );
-    }
+    /// Get total staked
+    fn total_staked(env: Env) -> i128;
 
-    /// Unstake tokens
-    pub fn unstake(&self, user: &Address, amount: i128) {
-        self.env.invoke_contract::<()>(
-            &self.contract_id,
-            &Symbol::new(self.env, "unstake"),
-            Vec::from_array(
-                self.env,
-                [user.into_val(self.env), amount.into_val(self.env)],
-            ),
-        );
-    }
+    /// Get the token being staked
+    fn stake_token(env: Env) -> Result<Address, SharedError>;
 
-    /// Add rewards to pool (called by Fee Distributor)
-    pub fn add_rewards(&self, token: &Address, amount: i128) {
-        self.env.invoke_contract::<()>(
-            &self.contract_id,
-            &Symbol::new(self.env, "add_rewards"),
-            Vec::from_array(
-                self.env,
-                [token.into_val(self.env), amount.into_val(self.env)],
-            ),
-        );
-    }
+    /// Stake tokens `shares` are worth now
+    fn convert_to_assets(env: Env, shares: i128) -> Result<i128, SharedError>;
 
-    /// Get user stake info
-    pub fn get_stake(&self, user: &Address) -> UserStake {
-        self.env.invoke_contract(
-            &self.contract_id,
-            &Symbol::new(self.env, "get_stake"),
-            Vec::from_array(self.env, [user.into_val(self.env)]),
-        )
-    }
+    /// Get a user's staked amount as of `timestamp`
+    fn get_stake_at(env: Env, user: Address, timestamp: u64) -> i128;
 
-    /// Get total staked
-    pub fn total_staked(&self) -> i128 {
-        self.env.invoke_contract(
-            &self.contract_id,
-            &Symbol::new(self.env, "total_staked"),
-            Vec::new(self.env),
-        )
-    }
+    /// Get the total staked amount as of `timestamp`
+    fn total_staked_at(env: Env, timestamp: u64) -> i128;
+
+    /// Pin a voter's stake until `until` (called by the governor)
+    fn lock_stake_for_vote(
+        env: Env,
+        user: Address,
+        proposal_id: u64,
+        until: u64,
+    ) -> Result<i128, SharedError>;
 }
 
 // ════════════════════════════════════════════════════════════════════════════
 // Liquidity Locker Client
 // ════════════════════════════════════════════════════════════════════════════
 
-/// Client for Liquidity Locker contract
-pub struct LiquidityLockerClient<'a> {
-    env: &'a Env,
-    contract_id: Address,
-}
-
-impl<'a> LiquidityLockerClient<'a> {
-    pub fn new(env: &'a Env, contract_id: &Address) -> Self {
-        Self {
-            env,
-            contract_id: contract_id.clone(),
-        }
-    }
-
+/// Interface of the Liquidity Locker contract
+#[contractclient(name = "LiquidityLockerClient")]
+pub trait LiquidityLockerInterface {
     /// Lock LP tokens
-    pub fn lock(&self, owner: &Address, lp_token: &Address, amount: i128, unlock_time: u64) -> u64 {
-        self.env.invoke_contract(
-            &self.contract_id,
-            &Symbol::new(self.env, "lock"),
-            Vec::from_array(
-                self.env,
-                [
-                    owner.into_val(self.env),
-                    lp_token.into_val(self.env),
-                    amount.into_val(self.env),
-                    unlock_time.into_val(self.env),
-                ],
-            ),
-        )
-    }
+    fn lock(
+        env: Env,
+        owner: Address,
+        lp_token: Address,
+        amount: i128,
+        unlock_time: u64,
+    ) -> Result<u64, SharedError>;
+
+    /// Lock LP tokens pulled from an approved `caller` for `beneficiary`
+    /// (protocol-owned without one)
+    fn lock_for(
+        env: Env,
+        caller: Address,
+        beneficiary: Option<Address>,
+        lp_token: Address,
+        amount: i128,
+        unlock_time: u64,
+    ) -> Result<u64, SharedError>;
 
     /// Unlock LP tokens
-    pub fn unlock(&self, owner: &Address, lock_id: u64) -> i128 {
-        self.env.invoke_contract(
-            &self.contract_id,
-            &Symbol::new(self.env, "unlock"),
-            Vec::from_array(
-                self.env,
-                [owner.into_val(self.env), lock_id.into_val(self.env)],
-            ),
-        )
-    }
+    fn unlock(env: Env, owner: Address, lock_id: u64) -> Result<i128, SharedError>;
 
     /// Get lock info
-    pub fn get_lock(&self, lock_id: u64) -> Option<LockInfo> {
-        self.env.invoke_contract(
-            &self.contract_id,
-            &Symbol::new(self.env, "get_lock"),
-            Vec::from_array(self.env, [lock_id.into_val(self.env)]),
-        )
-    }
+    fn get_lock(env: Env, lock_id: u64) -> Option<LockInfo>;
+
+    /// Get all of a user's locks
+    fn get_user_locks(env: Env, user: Address) -> Vec<LockInfo>;
 }
 
 // ════════════════════════════════════════════════════════════════════════════
 // Treasury Vault Client
 // ════════════════════════════════════════════════════════════════════════════
 
-/// Client for Treasury Vault contract
-pub struct TreasuryVaultClient<'a> {
-    env: &'a Env,
-    contract_id: Address,
-}
-
-impl<'a> TreasuryVaultClient<'a> {
-    pub fn new(env: &'a Env, contract_id: &Address) -> Self {
-        Self {
-            env,
-            contract_id: contract_id.clone(),
-        }
-    }
-
+/// Interface of the Treasury Vault contract
+#[contractclient(name = "TreasuryVaultClient")]
+pub trait TreasuryVaultInterface {
     /// Notify deposit (for tracking)
-    pub fn notify_deposit(&self, token: &Address, from: &Address, amount: i128) {
-        self.env.invoke_contract::<()>(
-            &self.contract_id,
-            &Symbol::new(self.env, "notify_deposit"),
-            Vec::from_array(
-                self.env,
-                [
-                    token.into_val(self.env),
-                    from.into_val(self.env),
-                    amount.into_val(self.env),
-                ],
-            ),
-        );
-    }
+    fn notify_deposit(
+        env: Env,
+        token: Address,
+        from: Address,
+        amount: i128,
+    ) -> Result<(), SharedError>;
 
     /// Get balance of a token
-    pub fn balance(&self, token: &Address) -> i128 {
-        self.env.invoke_contract(
-            &self.contract_id,
-            &Symbol::new(self.env, "balance"),
-            Vec::from_array(self.env, [token.into_val(self.env)]),
-        )
-    }
+    fn balance(env: Env, token: Address) -> i128;
 }
 
 // ════════════════════════════════════════════════════════════════════════════
 // AstroSwap Bridge Client
 // ════════════════════════════════════════════════════════════════════════════
 
-/// Client for AstroSwap Bridge contract
-pub struct BridgeClient<'a> {
-    env: &'a Env,
-    contract_id: Address,
-}
-
-impl<'a> BridgeClient<'a> {
-    pub fn new(env: &'a Env, contract_id: &Address) -> Self {
-        Self {
-            env,
-            contract_id: contract_id.clone(),
-        }
-    }
-
+/// Interface of the AstroSwap Bridge contract
+#[contractclient(name = "BridgeClient")]
+pub trait BridgeInterface {
     /// Graduate a token from launchpad to DEX
-    pub fn graduate_token(
-        &self,
-        token: &Address,
+    fn graduate_token(
+        env: Env,
+        token: Address,
         token_amount: i128,
         quote_amount: i128,
-        metadata: &TokenMetadata,
-    ) -> GraduationInfo {
-        self.env.invoke_contract(
-            &self.contract_id,
-            &Symbol::new(self.env, "graduate_token"),
-            Vec::from_array(
-                self.env,
-                [
-                    token.into_val(self.env),
-                    token_amount.into_val(self.env),
-                    quote_amount.into_val(self.env),
-                    metadata.into_val(self.env),
-                ],
-            ),
-        )
-    }
+        metadata: TokenMetadata,
+    ) -> GraduationInfo;
+
+    /// Get the full graduation provenance of a token, if graduated
+    fn get_graduation_info_v2(env: Env, token: Address) -> Option<GraduationInfoV2>;
 
     /// Check if token is graduated
-    pub fn is_graduated(&self, token: &Address) -> bool {
-        self.env.invoke_contract(
-            &self.contract_id,
-            &Symbol::new(self.env, "is_graduated"),
-            Vec::from_array(self.env, [token.into_val(self.env)]),
-        )
-    }
+    fn is_graduated(env: Env, token: Address) -> bool;
 }
 
 // ════════════════════════════════════════════════════════════════════════════
-// AMM Pair Client (generic for both internal and AstroSwap)
+// Token Factory Client
 // ════════════════════════════════════════════════════════════════════════════
 
-/// Client for AMM Pair contract
-pub struct AmmPairClient<'a> {
-    env: &'a Env,
-    contract_id: Address,
+/// Interface of the Token Factory contract
+#[contractclient(name = "TokenFactoryClient")]
+pub trait TokenFactoryInterface {
+    /// Deploy a new token and mint `supply` to `creator`
+    fn create_token(
+        env: Env,
+        creator: Address,
+        name: String,
+        symbol: String,
+        decimals: u32,
+        supply: i128,
+    ) -> Result<Address, SharedError>;
+
+    /// Get the metadata recorded for a factory token
+    fn get_metadata(env: Env, token: Address) -> Option<TokenMetadata>;
+
+    /// Get the tokens deployed by a creator
+    fn tokens_by_creator(env: Env, creator: Address) -> Vec<Address>;
+
+    /// Check if a token was deployed by the factory
+    fn is_factory_token(env: Env, token: Address) -> bool;
+
+    /// Number of tokens deployed
+    fn token_count(env: Env) -> u64;
+
+    /// Supply cap for new tokens
+    fn max_supply(env: Env) -> i128;
 }
 
-impl<'a> AmmPairClient<'a> {
-    pub fn new(env: &'a Env, contract_id: &Address) -> Self {
-        Self {
-            env,
-            contract_id: contract_id.clone(),
-        }
-    }
+// ════════════════════════════════════════════════════════════════════════════
+// AMM Pair Client (generic for both internal and AstroSwap)
+// ════════════════════════════════════════════════════════════════════════════
 
+/// Interface of the AMM Pair contract
+#[contractclient(name = "AmmPairClient")]
+pub trait AmmPairInterface {
     /// Initialize pair
-    pub fn initialize(
-        &self,
-        token_0: &Address,
-        token_1: &Address,
-        factory: &Address,
-        fee_to: &Address,
-    ) {
-        self.env.invoke_contract::<()>(
-            &self.contract_id,
-            &Symbol::new(self.env, "initialize"),
-            Vec::from_array(
-                self.env,
-                [
-                    token_0.into_val(self.env),
-                    token_1.into_val(self.env),
-                    factory.into_val(self.env),
-                    fee_to.into_val(self.env),
-                ],
-            ),
-        );
-    }
+    fn initialize(
+        env: Env,
+        token_0: Address,
+        token_1: Address,
+        factory: Address,
+        fee_to: Address,
+    ) -> Result<(), SharedError>;
 
     /// Get reserves
-    pub fn get_reserves(&self) -> (i128, i128) {
-        self.env.invoke_contract(
-            &self.contract_id,
-            &Symbol::new(self.env, "get_reserves"),
-            Vec::new(self.env),
-        )
-    }
+    fn get_reserves(env: Env) -> (i128, i128);
 
-    /// Add liquidity
-    pub fn add_liquidity(
-        &self,
-        sender: &Address,
+    /// Add liquidity, returning `(amount_0, amount_1, liquidity)`
+    fn add_liquidity(
+        env: Env,
+        sender: Address,
         amount_0: i128,
         amount_1: i128,
         min_0: i128,
         min_1: i128,
         deadline: u64,
-    ) -> (i128, i128, i128) {
-        self.env.invoke_contract(
-            &self.contract_id,
-            &Symbol::new(self.env, "add_liquidity"),
-            Vec::from_array(
-                self.env,
-                [
-                    sender.into_val(self.env),
-                    amount_0.into_val(self.env),
-                    amount_1.into_val(self.env),
-                    min_0.into_val(self.env),
-                    min_1.into_val(self.env),
-                    deadline.into_val(self.env),
-                ],
-            ),
-        )
-    }
+    ) -> Result<(i128, i128, i128), SharedError>;
+
+    /// Remove liquidity, returning `(amount_0, amount_1)`
+    fn remove_liquidity(
+        env: Env,
+        sender: Address,
+        liquidity: i128,
+        min_0: i128,
+        min_1: i128,
+        deadline: u64,
+    ) -> Result<(i128, i128), SharedError>;
+
+    /// Get `(lp_fee_bps, protocol_fee_bps)`
+    fn get_fees(env: Env) -> (u32, u32);
+
+    /// Get `(price_0_cumulative, price_1_cumulative, timestamp)`
+    fn get_price_cumulatives(env: Env) -> Result<(i128, i128, u64), SharedError>;
 
     /// Swap tokens
-    pub fn swap(&self, user: &Address, token_in: &Address, amount_in: i128, min_out: i128) -> i128 {
-        self.env.invoke_contract(
-            &self.contract_id,
-            &Symbol::new(self.env, "swap"),
-            Vec::from_array(
-                self.env,
-                [
-                    user.into_val(self.env),
-                    token_in.into_val(self.env),
-                    amount_in.into_val(self.env),
-                    min_out.into_val(self.env),
-                ],
-            ),
-        )
-    }
+    fn swap(
+        env: Env,
+        user: Address,
+        token_in: Address,
+        amount_in: i128,
+        min_out: i128,
+    ) -> Result<i128, SharedError>;
 
     /// Get token 0 address
-    pub fn token_0(&self) -> Address {
-        self.env.invoke_contract(
-            &self.contract_id,
-            &Symbol::new(self.env, "token_0"),
-            Vec::new(self.env),
-        )
-    }
+    fn token_0(env: Env) -> Result<Address, SharedError>;
 
     /// Get token 1 address
-    pub fn token_1(&self) -> Address {
-        self.env.invoke_contract(
-            &self.contract_id,
-            &Symbol::new(self.env, "token_1"),
-            Vec::new(self.env),
-        )
+    fn token_1(env: Env) -> Result<Address, SharedError>;
+
+    /// Get total LP share supply
+    fn total_supply(env: Env) -> i128;
+}
+
+// ════════════════════════════════════════════════════════════════════════════
+// Oracle Client
+// ════════════════════════════════════════════════════════════════════════════
+
+/// Interface of the TWAP Oracle contract
+#[contractclient(name = "OracleClient")]
+pub trait OracleInterface {
+    /// Get `(price_0, price_1)` averaged over at least `window` seconds
+    fn get_twap(env: Env, pair: Address, window: u64) -> Result<(i128, i128), SharedError>;
+
+    /// Value `amount_in` of `token_in` in the pair's other token at the TWAP
+    fn consult(
+        env: Env,
+        pair: Address,
+        token_in: Address,
+        amount_in: i128,
+        window: u64,
+    ) -> Result<i128, SharedError>;
+}
+
+// ════════════════════════════════════════════════════════════════════════════
+// Referral Client
+// ════════════════════════════════════════════════════════════════════════════
+
+/// Interface of the Referral registry contract
+#[contractclient(name = "ReferralClient")]
+pub trait ReferralInterface {
+    /// Referral share of a protocol fee paid by `user` (0 without a referrer)
+    fn referral_share(env: Env, user: Address, fee: i128) -> Result<i128, SharedError>;
+
+    /// Credit `user`'s referrer with their share of `fee` (pulled from `source`)
+    fn accrue(
+        env: Env,
+        source: Address,
+        user: Address,
+        token: Address,
+        fee: i128,
+    ) -> Result<i128, SharedError>;
+}
+
+// ════════════════════════════════════════════════════════════════════════════
+// Token Registry Client
+// ════════════════════════════════════════════════════════════════════════════
+
+/// Interface of the Token Registry contract
+#[contractclient(name = "TokenRegistryClient")]
+pub trait TokenRegistryInterface {
+    /// Whether contracts should accept `token`
+    fn is_token_allowed(env: Env, token: Address) -> bool;
+
+    /// Get the listing of `token`
+    fn token_status(env: Env, token: Address) -> TokenStatus;
+}
+
+// ════════════════════════════════════════════════════════════════════════════
+// Pausable Client
+// ════════════════════════════════════════════════════════════════════════════
+
+/// Pause entrypoints shared by the role-gated contracts (treasury, fee
+/// distributor, staking pool, locker)
+#[contractclient(name = "PausableClient")]
+pub trait PausableInterface {
+    /// Pause/unpause every function group (admin or `PAUSER`)
+    fn set_paused(env: Env, caller: Address, paused: bool) -> Result<(), SharedError>;
+
+    /// Whether any function group is paused
+    fn is_paused(env: Env) -> bool;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use soroban_sdk::ConversionError;
+
+    type Call<T, E> = Result<Result<T, ConversionError>, Result<E, InvokeError>>;
+
+    #[test]
+    fn test_into_shared() {
+        let ok: Call<i128, SharedError> = Ok(Ok(7));
+        assert_eq!(ok.into_shared(), Ok(7));
+
+        let failed: Call<i128, SharedError> = Err(Ok(SharedError::DeadlineExpired));
+        assert_eq!(failed.into_shared(), Err(SharedError::DeadlineExpired));
+
+        let trapped: Call<bool, soroban_sdk::Error> = Err(Err(InvokeError::Abort));
+        assert_eq!(
+            trapped.into_shared(),
+            Err(SharedError::CrossContractCallFailed)
+        );
+
+        let undecodable: Call<bool, soroban_sdk::Error> = Ok(Err(ConversionError));
+        assert_eq!(
+            undecodable.into_shared(),
+            Err(SharedError::CrossContractCallFailed)
+        );
     }
 }
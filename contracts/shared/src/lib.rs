@@ -3,12 +3,20 @@
 //! Shared types, interfaces, math utilities, and events for the Astro ecosystem.
 //!
 //! ## Modules
+//! - `access_control` - Role-based access control on top of the admin
 //! - `types` - Common data structures and enums
 //! - `math` - Safe arithmetic operations
 //! - `interfaces` - Cross-contract call interfaces
+//! - `limits` - Minimum amounts and `validate_amount` against dust entries
 //! - `events` - Standard event definitions (SDK 25.x #[contractevent])
+//! - `ownership` - Two-step admin transfer (propose/accept/cancel)
+//! - `pausable` - Pause flags per function group (deposits, withdrawals, admin)
+//! - `prf` - Ledger-seeded pseudo-random tie-breaking (not for lotteries)
 //! - `reentrancy` - RAII-based reentrancy protection
+//! - `signature` - Signed-message authorization for relayed transactions
+//! - `testutils` - Deterministic test fixtures (`testutils` feature only)
 //! - `ttl` - Lazy TTL refresh pattern for storage efficiency
+//! - `upgrade` - Timelocked WASM upgrades and storage migrations
 //! - `zk` - Zero-knowledge primitives (Protocol 25: BN254, Poseidon)
 //!
 //! ## Usage
@@ -21,12 +29,21 @@
 
 #![no_std]
 
+pub mod access_control;
 pub mod events;
 pub mod interfaces;
+pub mod limits;
 pub mod math;
+pub mod ownership;
+pub mod pausable;
+pub mod prf;
 pub mod reentrancy;
+pub mod signature;
+#[cfg(feature = "testutils")]
+pub mod testutils;
 pub mod ttl;
 pub mod types;
+pub mod upgrade;
 pub mod zk;
 
 // Re-export commonly used items
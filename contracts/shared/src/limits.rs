@@ -0,0 +1,83 @@
+//! # Amount Limits
+//!
+//! Minimum amounts for value-moving entrypoints, in one place so every
+//! contract rejects dust the same way. Entries that create storage (deposit
+//! receipts, locks, fee buckets) would otherwise let anyone fill the ledger
+//! with 1-stroop records.
+//!
+//! The fee distributor rejects fees below `MIN_FEE_AMOUNT`. The pair and the
+//! escrow hold such dust per token and add it to their next fee, so it is
+//! forwarded once the total reaches the minimum instead of being stranded.
+//!
+//! ## Usage
+//! ```rust,ignore
+//! use astro_core_shared::limits::{validate_amount, MIN_DEPOSIT_AMOUNT, NO_MAX};
+//!
+//! validate_amount(amount, MIN_DEPOSIT_AMOUNT, NO_MAX)?;
+//! ```
+
+use crate::types::SharedError;
+
+/// Minimum trade amount: 0.1 XLM = 1,000,000 stroops
+/// Prevents dust attacks and ensures fees don't round to 0
+pub const MIN_TRADE_AMOUNT: i128 = 1_000_000;
+
+/// Minimum treasury deposit (each deposit issues a receipt)
+pub const MIN_DEPOSIT_AMOUNT: i128 = 100;
+
+/// Floor for the staking pool's configurable `min_stake_amount`
+pub const MIN_STAKE_AMOUNT: i128 = 100;
+
+/// Minimum LP amount per lock (each lock is its own entry)
+pub const MIN_LOCK_AMOUNT: i128 = 100;
+
+/// Minimum fee accepted by the fee distributor
+pub const MIN_FEE_AMOUNT: i128 = 100;
+
+/// Upper bound for `validate_amount` when only a minimum applies
+pub const NO_MAX: i128 = i128::MAX;
+
+/// Check `min <= amount <= max` for a positive `amount`.
+/// Fails with `InvalidAmount` for zero or negative amounts, `AmountBelowMin`
+/// below `min` and `AmountExceedsMax` above `max`.
+pub fn validate_amount(amount: i128, min: i128, max: i128) -> Result<(), SharedError> {
+    if amount <= 0 {
+        return Err(SharedError::InvalidAmount);
+    }
+    if amount < min {
+        return Err(SharedError::AmountBelowMin);
+    }
+    if amount > max {
+        return Err(SharedError::AmountExceedsMax);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_amount() {
+        assert_eq!(
+            validate_amount(0, 0, NO_MAX),
+            Err(SharedError::InvalidAmount)
+        );
+        assert_eq!(
+            validate_amount(-5, 0, NO_MAX),
+            Err(SharedError::InvalidAmount)
+        );
+        assert_eq!(
+            validate_amount(MIN_DEPOSIT_AMOUNT - 1, MIN_DEPOSIT_AMOUNT, NO_MAX),
+            Err(SharedError::AmountBelowMin)
+        );
+        assert_eq!(
+            validate_amount(MIN_DEPOSIT_AMOUNT, MIN_DEPOSIT_AMOUNT, NO_MAX),
+            Ok(())
+        );
+        assert_eq!(
+            validate_amount(1_001, 1, 1_000),
+            Err(SharedError::AmountExceedsMax)
+        );
+    }
+}
@@ -17,9 +17,14 @@ pub const STELLAR_DECIMALS: u32 = 7;
 /// One token with 7 decimals
 pub const ONE_TOKEN: i128 = 10_000_000;
 
-/// Minimum trade amount: 0.1 XLM = 1,000,000 stroops
-/// Prevents dust attacks and ensures fees don't round to 0
-pub const MIN_TRADE_AMOUNT: i128 = 1_000_000;
+/// Decimals wallets and explorers commonly display XLM with
+pub const XLM_DISPLAY_DECIMALS: u32 = 5;
+
+/// Seconds in a 365-day year
+pub const SECONDS_PER_YEAR: u64 = 31_536_000;
+
+/// Minimum trade amount (defined with the other minimums in `limits`)
+pub use crate::limits::MIN_TRADE_AMOUNT;
 
 // ════════════════════════════════════════════════════════════════════════════
 // Safe Arithmetic Operations
@@ -60,13 +65,12 @@ pub fn safe_div(a: i128, b: i128) -> Result<i128, SharedError> {
 // Phantom Overflow Safe Arithmetic
 // ════════════════════════════════════════════════════════════════════════════
 
-/// Multiply then divide with phantom overflow protection: (a * b) / c
-/// Rounds DOWN (floor) - favors the protocol
+/// Multiply then divide with explicit rounding: (a * b) / c
 ///
-/// Handles phantom overflow where a*b overflows but (a*b)/c fits in i128.
-/// Uses u128 intermediate calculation to prevent overflow.
-#[inline]
-pub fn mul_div_down(a: i128, b: i128, c: i128) -> Result<i128, SharedError> {
+/// The product is taken as a full 256-bit value, so any inputs whose quotient
+/// fits in i128 succeed, even when a*b exceeds u128. Negative inputs are
+/// rejected (token math only).
+pub fn mul_div(a: i128, b: i128, c: i128, rounding: Rounding) -> Result<i128, SharedError> {
     if c == 0 {
         return Err(SharedError::DivisionByZero);
     }
@@ -79,32 +83,58 @@ pub fn mul_div_down(a: i128, b: i128, c: i128) -> Result<i128, SharedError> {
         return Err(SharedError::InvalidAmount);
     }
 
-    // Try direct calculation first (most common case)
-    if let Some(product) = a.checked_mul(b) {
-        return product.checked_div(c).ok_or(SharedError::Overflow);
+    let (high, low) = full_mul(a as u128, b as u128);
+    let c = c as u128;
+
+    // Quotient would need more than 128 bits
+    if high >= c {
+        return Err(SharedError::Overflow);
     }
 
-    // Phantom overflow: use u128 for intermediate calculation
-    let a_u = a as u128;
-    let b_u = b as u128;
-    let c_u = c as u128;
+    let (mut quotient, remainder) = if high == 0 {
+        (low / c, low % c)
+    } else {
+        // Binary long division of high:low by c. remainder < c < 2^127,
+        // so shifting it left never overflows.
+        let mut quotient = 0u128;
+        let mut remainder = high;
+        for i in (0..128).rev() {
+            remainder = (remainder << 1) | ((low >> i) & 1);
+            quotient <<= 1;
+            if remainder >= c {
+                remainder -= c;
+                quotient |= 1;
+            }
+        }
+        (quotient, remainder)
+    };
+
+    if rounding == Rounding::Up && remainder > 0 {
+        quotient = quotient.checked_add(1).ok_or(SharedError::Overflow)?;
+    }
 
-    // For very large numbers, use decomposition: a*b/c = (a/c)*b + (a%c)*b/c
-    let quotient = a_u / c_u;
-    let remainder = a_u % c_u;
+    checked_u128_to_i128(quotient)
+}
 
-    // result = quotient * b + (remainder * b) / c
-    let term1 = quotient.checked_mul(b_u).ok_or(SharedError::Overflow)?;
-    let term2_num = remainder.checked_mul(b_u).ok_or(SharedError::Overflow)?;
-    let term2 = term2_num / c_u;
+/// Full 256-bit product of two u128 values as (high, low) halves
+#[inline]
+fn full_mul(a: u128, b: u128) -> (u128, u128) {
+    const MASK: u128 = u64::MAX as u128;
+    let (a_high, a_low) = (a >> 64, a & MASK);
+    let (b_high, b_low) = (b >> 64, b & MASK);
 
-    let result = term1.checked_add(term2).ok_or(SharedError::Overflow)?;
+    let (mid, mid_carry) = (a_low * b_high).overflowing_add(a_high * b_low);
+    let (low, low_carry) = (a_low * b_low).overflowing_add(mid << 64);
+    let high = a_high * b_high + (mid >> 64) + ((mid_carry as u128) << 64) + low_carry as u128;
 
-    if result > i128::MAX as u128 {
-        return Err(SharedError::Overflow);
-    }
+    (high, low)
+}
 
-    Ok(result as i128)
+/// Multiply then divide with phantom overflow protection: (a * b) / c
+/// Rounds DOWN (floor) - favors the protocol
+#[inline]
+pub fn mul_div_down(a: i128, b: i128, c: i128) -> Result<i128, SharedError> {
+    mul_div(a, b, c, Rounding::Down)
 }
 
 /// Multiply then divide with phantom overflow protection: (a * b) / c
@@ -112,39 +142,7 @@ pub fn mul_div_down(a: i128, b: i128, c: i128) -> Result<i128, SharedError> {
 /// Used for get_amount_in calculations
 #[inline]
 pub fn mul_div_up(a: i128, b: i128, c: i128) -> Result<i128, SharedError> {
-    if c == 0 {
-        return Err(SharedError::DivisionByZero);
-    }
-    if a == 0 || b == 0 {
-        return Ok(0);
-    }
-
-    // Handle negative numbers
-    if a < 0 || b < 0 || c < 0 {
-        return Err(SharedError::InvalidAmount);
-    }
-
-    // floor((a * b + c - 1) / c) = ceil(a * b / c)
-    let floor_result = mul_div_down(a, b, c)?;
-
-    // Check if there's a remainder
-    let a_u = a as u128;
-    let b_u = b as u128;
-    let c_u = c as u128;
-
-    // Check remainder without overflow
-    let quotient = a_u / c_u;
-    let remainder_a = a_u % c_u;
-    let term1_remainder = (quotient * b_u) % c_u;
-    let term2_product = remainder_a * b_u;
-    let term2_remainder = term2_product % c_u;
-
-    // If there's any remainder, round up
-    if term1_remainder > 0 || term2_remainder > 0 {
-        safe_add(floor_result, 1)
-    } else {
-        Ok(floor_result)
-    }
+    mul_div(a, b, c, Rounding::Up)
 }
 
 /// Calculate k = reserve_0 * reserve_1 with overflow protection
@@ -384,6 +382,131 @@ pub fn quote(amount_a: i128, reserve_a: i128, reserve_b: i128) -> Result<i128, S
     mul_div_down(amount_a, reserve_b, reserve_a)
 }
 
+// ════════════════════════════════════════════════════════════════════════════
+// Weighted Pool Calculations
+// ════════════════════════════════════════════════════════════════════════════
+
+/// Safety margin added to fractional powers in weighted math (1e-14),
+/// above the combined `ln_fixed`/`exp_fixed` error
+pub const WEIGHTED_POW_MARGIN: i128 = 10_000;
+
+/// Calculate output amount for a weighted pool swap (Balancer-style constant value)
+/// amount_out = balance_out * (1 - (balance_in / new_balance_in)^(weight_in / weight_out))
+///
+/// Weights only matter as a ratio (8000/2000 for an 80/20 pool). Equal weights reduce
+/// to the constant product formula; otherwise the power is taken via `ln_fixed` and
+/// `exp_fixed` and padded by `WEIGHTED_POW_MARGIN`, so the result rounds down.
+pub fn get_amount_out_weighted(
+    amount_in: i128,
+    balance_in: i128,
+    weight_in: u32,
+    balance_out: i128,
+    weight_out: u32,
+    fee_bps: u32,
+) -> Result<i128, SharedError> {
+    if amount_in <= 0 || weight_in == 0 || weight_out == 0 {
+        return Err(SharedError::InvalidAmount);
+    }
+    if balance_in <= 0 || balance_out <= 0 {
+        return Err(SharedError::InsufficientBalance);
+    }
+
+    let fee_factor = BPS_DENOMINATOR - (fee_bps as i128);
+    let amount_in_with_fee = mul_div_down(amount_in, fee_factor, BPS_DENOMINATOR)?;
+    let new_balance_in = safe_add(balance_in, amount_in_with_fee)?;
+
+    // base = balance_in / new_balance_in <= 1, rounded up (pool keeps more)
+    let base = mul_div_up(balance_in, PRECISION, new_balance_in)?;
+    let power = if weight_in == weight_out {
+        base
+    } else {
+        // base^(w_in / w_out) = e^(-(w_in / w_out) * -ln(base))
+        let exponent = mul_div_down(-ln_fixed(base)?, weight_in as i128, weight_out as i128)?;
+        min(
+            safe_add(exp_fixed(-exponent)?, WEIGHTED_POW_MARGIN)?,
+            PRECISION,
+        )
+    };
+
+    mul_div_down(balance_out, PRECISION - power, PRECISION)
+}
+
+/// Calculate weighted pool price of base in quote with precision
+/// price = ((balance_quote / weight_quote) / (balance_base / weight_base)) * PRECISION
+#[inline]
+pub fn calculate_price_weighted(
+    balance_base: i128,
+    weight_base: u32,
+    balance_quote: i128,
+    weight_quote: u32,
+) -> Result<i128, SharedError> {
+    let numerator = safe_mul(balance_quote, weight_base as i128)?;
+    let denominator = safe_mul(balance_base, weight_quote as i128)?;
+    mul_div_down(numerator, PRECISION, denominator)
+}
+
+/// Calculate weighted pool spot price: amount in paid per unit out, fee included, with precision
+/// spot = ((balance_in / weight_in) / (balance_out / weight_out)) * 10000 / (10000 - fee)
+pub fn get_spot_price_weighted(
+    balance_in: i128,
+    weight_in: u32,
+    balance_out: i128,
+    weight_out: u32,
+    fee_bps: u32,
+) -> Result<i128, SharedError> {
+    let price = calculate_price_weighted(balance_out, weight_out, balance_in, weight_in)?;
+    let fee_factor = BPS_DENOMINATOR - (fee_bps as i128);
+    mul_div_up(price, BPS_DENOMINATOR, fee_factor)
+}
+
+// ════════════════════════════════════════════════════════════════════════════
+// Interest & APR
+// ════════════════════════════════════════════════════════════════════════════
+
+/// Simple interest accrued on `principal` at `rate_bps` per year over `elapsed` seconds
+/// interest = principal * rate_bps * elapsed / (10000 * year_secs), rounded down
+#[inline]
+pub fn accrue_linear(
+    principal: i128,
+    rate_bps: u32,
+    elapsed: u64,
+    year_secs: u64,
+) -> Result<i128, SharedError> {
+    let rate_time = (rate_bps as i128) * (elapsed as i128);
+    let denominator = safe_mul(BPS_DENOMINATOR, year_secs as i128)?;
+    mul_div_down(principal, rate_time, denominator)
+}
+
+/// Annualized simple rate in basis points of `gain` on `principal` over `elapsed` seconds
+/// rate_bps = gain * 10000 * year_secs / (principal * elapsed), rounded down
+///
+/// Inverse of `accrue_linear`.
+pub fn annualized_rate_bps(
+    gain: i128,
+    principal: i128,
+    elapsed: u64,
+    year_secs: u64,
+) -> Result<i128, SharedError> {
+    let numerator = safe_mul(gain, BPS_DENOMINATOR)?;
+    let denominator = safe_mul(principal, elapsed as i128)?;
+    mul_div_down(numerator, year_secs as i128, denominator)
+}
+
+/// Convert APR to APY (both in basis points) for `compounds_per_year` periods
+/// apy = (1 + apr / n)^n - 1, rounded down
+/// Example: apr_to_apy(1000, 365) = 1051 (10% daily compounded = 10.51%)
+pub fn apr_to_apy(apr_bps: u32, compounds_per_year: u32) -> Result<i128, SharedError> {
+    if compounds_per_year == 0 {
+        return Err(SharedError::InvalidAmount);
+    }
+
+    let period_denominator = safe_mul(BPS_DENOMINATOR, compounds_per_year as i128)?;
+    let period_rate = mul_div_down(apr_bps as i128, PRECISION, period_denominator)?;
+    let growth = pow_fixed(PRECISION + period_rate, compounds_per_year, PRECISION)?;
+
+    mul_div_down(growth - PRECISION, BPS_DENOMINATOR, PRECISION)
+}
+
 // ════════════════════════════════════════════════════════════════════════════
 // Square Root (Newton's Method)
 // ════════════════════════════════════════════════════════════════════════════
@@ -406,6 +529,271 @@ pub fn sqrt(value: i128) -> i128 {
     x
 }
 
+// ════════════════════════════════════════════════════════════════════════════
+// Fixed-Point Powers & Logarithms
+// ════════════════════════════════════════════════════════════════════════════
+
+/// ln(2) scaled by PRECISION (truncated)
+pub const LN_2: i128 = 693_147_180_559_945_309;
+
+/// Raise a fixed-point `base` to an integer power: base^exp, both scaled by `precision`
+/// Example: pow_fixed(1.01e18, 365, PRECISION) = 37.783434332887158072e18
+///
+/// Exponentiation by squaring over `mul_div_down`, so the result never exceeds
+/// the exact value. Error bound: below `exp` units for `base < precision`, and
+/// below `exp / precision` relative for `base >= precision`.
+pub fn pow_fixed(base: i128, exp: u32, precision: i128) -> Result<i128, SharedError> {
+    if base < 0 || precision <= 0 {
+        return Err(SharedError::InvalidAmount);
+    }
+
+    let mut result = precision;
+    let mut base = base;
+    let mut exp = exp;
+    while exp > 0 {
+        if exp & 1 == 1 {
+            result = mul_div_down(result, base, precision)?;
+        }
+        exp >>= 1;
+        if exp > 0 {
+            base = mul_div_down(base, base, precision)?;
+        }
+    }
+
+    Ok(result)
+}
+
+/// Natural logarithm of a PRECISION-scaled value, scaled by PRECISION
+/// Example: ln_fixed(2e18) = 0.693147180559945309e18
+///
+/// Splits x = m * 2^k with m in [1, 2), then ln(x) = k*ln(2) + 2*atanh((m-1)/(m+1))
+/// via the atanh series. Absolute error is below 100 units (1e-16) over the
+/// whole positive i128 range. Non-positive inputs are rejected.
+pub fn ln_fixed(x: i128) -> Result<i128, SharedError> {
+    if x <= 0 {
+        return Err(SharedError::InvalidAmount);
+    }
+
+    // Normalize to m in [PRECISION, 2 * PRECISION)
+    let mut k: i128 = 0;
+    while (x >> (k + 1)) >= PRECISION {
+        k += 1;
+    }
+    let mut m = x >> k;
+    while m < PRECISION {
+        m *= 2;
+        k -= 1;
+    }
+
+    // z < 1/3, so each term shrinks ~9x and the series stops within ~20 terms
+    let z = mul_div_down(m - PRECISION, PRECISION, m + PRECISION)?;
+    let z_squared = mul_div_down(z, z, PRECISION)?;
+    let mut term = z;
+    let mut sum = 0;
+    let mut n = 1;
+    while term > 0 {
+        sum += term / n;
+        term = mul_div_down(term, z_squared, PRECISION)?;
+        n += 2;
+    }
+
+    safe_add(safe_mul(k, LN_2)?, 2 * sum)
+}
+
+/// e^x for a PRECISION-scaled x, scaled by PRECISION
+/// Example: exp_fixed(-1e18) = 0.367879441171442318e18
+///
+/// Splits x = k*ln(2) + r with r in [0, ln(2)), sums the Taylor series for e^r
+/// and shifts by k. Relative error is below 100 units (1e-16) for x >= 0 and
+/// absolute error below 10 units for x < 0. Fails with `Overflow` above ~46e18.
+pub fn exp_fixed(x: i128) -> Result<i128, SharedError> {
+    let k = x.div_euclid(LN_2);
+    let r = x.rem_euclid(LN_2);
+
+    let mut term = PRECISION;
+    let mut sum = PRECISION;
+    let mut n = 1;
+    while term > 0 {
+        term = mul_div_down(term, r, PRECISION)? / n;
+        sum += term;
+        n += 1;
+    }
+
+    if k >= 0 {
+        if k >= sum.leading_zeros() as i128 {
+            return Err(SharedError::Overflow);
+        }
+        Ok(sum << k)
+    } else if k <= -127 {
+        Ok(0)
+    } else {
+        Ok(sum >> -k)
+    }
+}
+
+// ════════════════════════════════════════════════════════════════════════════
+// Conversions
+// ════════════════════════════════════════════════════════════════════════════
+
+/// Rounding direction for operations that drop precision
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Rounding {
+    /// Toward zero - favors the protocol when paying out
+    Down,
+    /// Away from zero - favors the protocol when charging
+    Up,
+}
+
+/// What `rescale_with` does when scaling up overflows i128
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OverflowMode {
+    /// Fail with `Overflow`
+    Error,
+    /// Clamp to `i128::MAX` (for display and estimates only)
+    Saturate,
+}
+
+/// Convert a non-negative i128 to u128
+#[inline]
+pub fn checked_i128_to_u128(value: i128) -> Result<u128, SharedError> {
+    u128::try_from(value).map_err(|_| SharedError::InvalidAmount)
+}
+
+/// Convert a u128 back to i128, failing if it exceeds i128::MAX
+#[inline]
+pub fn checked_u128_to_i128(value: u128) -> Result<i128, SharedError> {
+    i128::try_from(value).map_err(|_| SharedError::Overflow)
+}
+
+/// Clamp an i128 into u64 range (negatives become 0)
+#[inline]
+pub fn to_u64_saturating(value: i128) -> u64 {
+    if value <= 0 {
+        0
+    } else if value >= u64::MAX as i128 {
+        u64::MAX
+    } else {
+        value as u64
+    }
+}
+
+/// Convert `amount` between token decimal precisions
+/// Example: rescale(1_234_567, 7, 6, Rounding::Up) = 123_457
+///
+/// Scaling up is exact (or fails with `Overflow`); scaling down drops digits
+/// according to `rounding`. Negative amounts are rejected.
+pub fn rescale(
+    amount: i128,
+    from_decimals: u32,
+    to_decimals: u32,
+    rounding: Rounding,
+) -> Result<i128, SharedError> {
+    rescale_with(
+        amount,
+        from_decimals,
+        to_decimals,
+        rounding,
+        OverflowMode::Error,
+    )
+}
+
+/// `rescale` with a choice of what happens when scaling up overflows
+pub fn rescale_with(
+    amount: i128,
+    from_decimals: u32,
+    to_decimals: u32,
+    rounding: Rounding,
+    overflow: OverflowMode,
+) -> Result<i128, SharedError> {
+    if amount < 0 {
+        return Err(SharedError::InvalidAmount);
+    }
+    if from_decimals == to_decimals || amount == 0 {
+        return Ok(amount);
+    }
+
+    if to_decimals > from_decimals {
+        let scaled = 10_i128
+            .checked_pow(to_decimals - from_decimals)
+            .and_then(|factor| amount.checked_mul(factor));
+        return match (scaled, overflow) {
+            (Some(scaled), _) => Ok(scaled),
+            (None, OverflowMode::Saturate) => Ok(i128::MAX),
+            (None, OverflowMode::Error) => Err(SharedError::Overflow),
+        };
+    }
+
+    let factor = match 10_i128.checked_pow(from_decimals - to_decimals) {
+        Some(factor) => factor,
+        // More digits dropped than an i128 can hold
+        None => {
+            return Ok(match rounding {
+                Rounding::Down => 0,
+                Rounding::Up => 1,
+            })
+        }
+    };
+    let quotient = amount / factor;
+    match rounding {
+        Rounding::Up if amount % factor != 0 => safe_add(quotient, 1),
+        _ => Ok(quotient),
+    }
+}
+
+// ════════════════════════════════════════════════════════════════════════════
+// Unit-Tagged Amounts
+// ════════════════════════════════════════════════════════════════════════════
+
+/// An amount tagged with its number of decimals, so 5- and 7-decimal
+/// values can't be mixed up as bare i128s
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Scaled<const DECIMALS: u32>(pub i128);
+
+/// XLM in stroops (7 decimals)
+pub type Stroops = Scaled<STELLAR_DECIMALS>;
+
+/// XLM at display precision (5 decimals)
+pub type XlmDisplay = Scaled<XLM_DISPLAY_DECIMALS>;
+
+impl<const D: u32> Scaled<D> {
+    /// Decimals of this unit
+    pub const DECIMALS: u32 = D;
+
+    /// The untagged amount
+    pub const fn raw(self) -> i128 {
+        self.0
+    }
+
+    /// Convert to another precision (see `rescale_with`)
+    pub fn rescale<const TO: u32>(
+        self,
+        rounding: Rounding,
+        overflow: OverflowMode,
+    ) -> Result<Scaled<TO>, SharedError> {
+        rescale_with(self.0, D, TO, rounding, overflow).map(Scaled)
+    }
+
+    /// Sum of two amounts in the same unit
+    pub fn checked_add(self, other: Self) -> Result<Self, SharedError> {
+        safe_add(self.0, other.0).map(Scaled)
+    }
+
+    /// Difference of two amounts in the same unit
+    pub fn checked_sub(self, other: Self) -> Result<Self, SharedError> {
+        safe_sub(self.0, other.0).map(Scaled)
+    }
+}
+
+/// Whole XLM to stroops
+pub fn xlm_to_stroops(xlm: i128) -> Result<Stroops, SharedError> {
+    safe_mul(xlm, ONE_TOKEN).map(Scaled)
+}
+
+/// Stroops at display precision, rounded down (never shows more than held)
+pub fn stroops_to_xlm_display(stroops: Stroops) -> Result<XlmDisplay, SharedError> {
+    stroops.rescale(Rounding::Down, OverflowMode::Error)
+}
+
 // ════════════════════════════════════════════════════════════════════════════
 // Min/Max Helpers
 // ════════════════════════════════════════════════════════════════════════════
@@ -498,6 +886,42 @@ mod tests {
         assert_eq!(result, large);
     }
 
+    #[test]
+    fn test_mul_div_256_bit() {
+        // Remainder * b exceeds u128 here, which the old decomposition rejected
+        let a = i128::MAX - 1;
+        let b = i128::MAX / 3;
+        let c = i128::MAX / 2 + 1;
+        assert_eq!(
+            mul_div(a, b, c, Rounding::Down).unwrap(),
+            113_427_455_640_312_821_154_458_202_477_256_070_482
+        );
+        assert_eq!(
+            mul_div(a, b, c, Rounding::Up).unwrap(),
+            113_427_455_640_312_821_154_458_202_477_256_070_483
+        );
+
+        let a = 10_i128.pow(30);
+        let b = 3 * 10_i128.pow(30) + 1;
+        let c = 7 * 10_i128.pow(24);
+        assert_eq!(
+            mul_div(a, b, c, Rounding::Down).unwrap(),
+            428_571_428_571_428_571_428_571_428_571_571_428
+        );
+        assert_eq!(
+            mul_div(a, b, c, Rounding::Up).unwrap(),
+            428_571_428_571_428_571_428_571_428_571_571_429
+        );
+
+        // Extremes
+        assert_eq!(
+            mul_div(i128::MAX, i128::MAX, i128::MAX, Rounding::Up).unwrap(),
+            i128::MAX
+        );
+        assert!(mul_div(i128::MAX, 2, 1, Rounding::Down).is_err());
+        assert!(mul_div(i128::MAX, i128::MAX, 1, Rounding::Down).is_err());
+    }
+
     #[test]
     fn test_calculate_k() {
         assert_eq!(calculate_k(1000, 2000).unwrap(), 2_000_000);
@@ -645,6 +1069,287 @@ mod tests {
         assert!(get_amount_in(1001, 1000, 1000, 30).is_err());
     }
 
+    #[test]
+    fn test_pow_fixed() {
+        // Integer bases are exact
+        for base in 0..=10i128 {
+            for exp in 0..=12u32 {
+                assert_eq!(
+                    pow_fixed(base * PRECISION, exp, PRECISION).unwrap(),
+                    base.pow(exp) * PRECISION
+                );
+            }
+        }
+        // Any precision works
+        assert_eq!(pow_fixed(15_000, 2, 10_000).unwrap(), 22_500);
+        assert_eq!(
+            pow_fixed(PRECISION / 2, 10, PRECISION).unwrap(),
+            976_562_500_000_000
+        );
+
+        // 1.01^365 = 37.783434332887158877...
+        let exact = 37_783_434_332_887_158_877;
+        let result = pow_fixed(PRECISION + PRECISION / 100, 365, PRECISION).unwrap();
+        assert!(result <= exact);
+        assert!(exact - result < 365 * exact / PRECISION);
+
+        // Invalid inputs
+        assert!(pow_fixed(-1, 2, PRECISION).is_err());
+        assert!(pow_fixed(PRECISION, 2, 0).is_err());
+        assert!(pow_fixed(i128::MAX, 2, PRECISION).is_err());
+    }
+
+    #[test]
+    fn test_ln_fixed() {
+        // Reference values, truncated at 1e-18
+        let cases = [
+            (PRECISION, 0),
+            (2 * PRECISION, 693_147_180_559_945_309),
+            (3 * PRECISION, 1_098_612_288_668_109_691),
+            (10 * PRECISION, 2_302_585_092_994_045_684),
+            (PRECISION / 10, -2_302_585_092_994_045_684),
+            (2_718_281_828_459_045_235, 999_999_999_999_999_999),
+            (1_000_000, -27_631_021_115_928_548_208),
+            (10_i128.pow(38), 46_051_701_859_880_913_680),
+        ];
+        for (x, expected) in cases {
+            assert!((ln_fixed(x).unwrap() - expected).abs() < 100);
+        }
+
+        // Exact on powers of two
+        for k in 0..60 {
+            assert_eq!(ln_fixed(PRECISION << k).unwrap(), k as i128 * LN_2);
+        }
+        for k in 1..=18 {
+            assert_eq!(ln_fixed(PRECISION >> k).unwrap(), -(k as i128) * LN_2);
+        }
+
+        // Strictly increasing over 0.001..10
+        let mut previous = i128::MIN;
+        for i in 1..=10_000 {
+            let value = ln_fixed(i * PRECISION / 1_000).unwrap();
+            assert!(value > previous);
+            previous = value;
+        }
+
+        // ln(a * b) = ln(a) + ln(b)
+        for i in 1..=100 {
+            for j in 1..=100 {
+                let a = i * PRECISION / 13;
+                let b = j * PRECISION / 7;
+                let product = mul_div_down(a, b, PRECISION).unwrap();
+                let sum = ln_fixed(a).unwrap() + ln_fixed(b).unwrap();
+                assert!((ln_fixed(product).unwrap() - sum).abs() < 200);
+            }
+        }
+
+        // ln(b^n) = n * ln(b), tolerance covers pow_fixed rounding too
+        for i in 1..100 {
+            let base = PRECISION + i * PRECISION / 1_000;
+            for n in 1..50u32 {
+                let lhs = ln_fixed(pow_fixed(base, n, PRECISION).unwrap()).unwrap();
+                let rhs = n as i128 * ln_fixed(base).unwrap();
+                assert!((lhs - rhs).abs() < 1_000);
+            }
+        }
+
+        assert!(ln_fixed(0).is_err());
+        assert!(ln_fixed(-PRECISION).is_err());
+    }
+
+    #[test]
+    fn test_exp_fixed() {
+        assert_eq!(exp_fixed(0).unwrap(), PRECISION);
+        // e = 2.718281828459045235...
+        assert!((exp_fixed(PRECISION).unwrap() - 2_718_281_828_459_045_235).abs() < 100);
+        // 1/e = 0.367879441171442321...
+        assert!((exp_fixed(-PRECISION).unwrap() - 367_879_441_171_442_321).abs() < 10);
+        // Exact on multiples of ln(2)
+        for k in 0..60 {
+            assert_eq!(exp_fixed(k * LN_2).unwrap(), PRECISION << k);
+        }
+        // Inverse of ln_fixed
+        for i in 1..=1_000 {
+            let x = i * PRECISION / 37;
+            let round_trip = exp_fixed(ln_fixed(x).unwrap()).unwrap();
+            assert!((round_trip - x).abs() <= x / 10_000_000_000_000 + 10);
+        }
+        // Underflow to zero, overflow errors
+        assert_eq!(exp_fixed(-100 * PRECISION).unwrap(), 0);
+        assert!(exp_fixed(47 * PRECISION).is_err());
+    }
+
+    #[test]
+    fn test_get_amount_out_weighted() {
+        // Equal weights match the constant product formula
+        for amount_in in [ONE_TOKEN, 1_000 * ONE_TOKEN, 500_000 * ONE_TOKEN] {
+            let reserve = 1_000_000 * ONE_TOKEN;
+            let weighted =
+                get_amount_out_weighted(amount_in, reserve, 5_000, reserve, 5_000, 30).unwrap();
+            let constant_product = get_amount_out(amount_in, reserve, reserve, 30).unwrap();
+            assert!((weighted - constant_product).abs() <= 1);
+        }
+
+        // 80/20 pool, reference values floored from the exact formula
+        let cases = [
+            (ONE_TOKEN, 8_000, 2_000, 9_969_968),
+            (ONE_TOKEN, 2_000, 8_000, 9_969_968),
+            (1_000 * ONE_TOKEN, 8_000, 2_000, 9_939_014_474),
+            (1_000 * ONE_TOKEN, 2_000, 8_000, 9_939_052_886),
+            (100_000 * ONE_TOKEN, 8_000, 2_000, 749_743_670_940),
+            (100_000 * ONE_TOKEN, 2_000, 8_000, 769_375_636_763),
+        ];
+        for (amount_in, weight_in, weight_out, exact) in cases {
+            let (balance_in, balance_out) = if weight_in == 8_000 {
+                (800_000 * ONE_TOKEN, 200_000 * ONE_TOKEN)
+            } else {
+                (200_000 * ONE_TOKEN, 800_000 * ONE_TOKEN)
+            };
+            let out = get_amount_out_weighted(
+                amount_in,
+                balance_in,
+                weight_in,
+                balance_out,
+                weight_out,
+                30,
+            )
+            .unwrap();
+            assert!(out <= exact && exact - out <= 2);
+        }
+
+        // Invalid inputs
+        assert!(get_amount_out_weighted(0, 1000, 8_000, 1000, 2_000, 30).is_err());
+        assert!(get_amount_out_weighted(100, 0, 8_000, 1000, 2_000, 30).is_err());
+        assert!(get_amount_out_weighted(100, 1000, 0, 1000, 2_000, 30).is_err());
+        assert!(get_amount_out_weighted(100, 1000, 8_000, 1000, 2_000, 10_001).is_err());
+    }
+
+    #[test]
+    fn test_weighted_prices() {
+        // 800 base at 80% vs 200 quote at 20% is priced 1:1
+        assert_eq!(
+            calculate_price_weighted(800, 8_000, 200, 2_000).unwrap(),
+            PRECISION
+        );
+        // Equal weights reduce to calculate_price
+        assert_eq!(
+            calculate_price_weighted(1000, 5_000, 2000, 5_000).unwrap(),
+            calculate_price(1000, 2000).unwrap()
+        );
+        assert!(calculate_price_weighted(0, 8_000, 200, 2_000).is_err());
+
+        // Spot price with a 0.3% fee
+        let spot = get_spot_price_weighted(200, 2_000, 800, 8_000, 30).unwrap();
+        assert_eq!(spot, mul_div_up(PRECISION, 10_000, 9_970).unwrap());
+        assert!(get_spot_price_weighted(200, 2_000, 800, 8_000, 10_000).is_err());
+    }
+
+    #[test]
+    fn test_accrue_linear() {
+        // 10% for a full year
+        let year = SECONDS_PER_YEAR;
+        assert_eq!(
+            accrue_linear(1_000 * ONE_TOKEN, 1_000, year, year).unwrap(),
+            100 * ONE_TOKEN
+        );
+        // 10% for half a year
+        assert_eq!(
+            accrue_linear(1_000 * ONE_TOKEN, 1_000, year / 2, year).unwrap(),
+            50 * ONE_TOKEN
+        );
+        // Rounds down
+        assert_eq!(accrue_linear(1, 1_000, year, year).unwrap(), 0);
+        // No overflow with large principals and long periods
+        assert!(accrue_linear(i128::MAX / 2, 10_000, year, year).is_ok());
+        assert!(accrue_linear(1_000, 1_000, year, 0).is_err());
+
+        // Annualizing recovers the rate
+        let interest = accrue_linear(1_000 * ONE_TOKEN, 750, 86_400 * 30, year).unwrap();
+        let rate = annualized_rate_bps(interest, 1_000 * ONE_TOKEN, 86_400 * 30, year).unwrap();
+        assert_eq!(rate, 749); // 750 less rounding
+        assert!(annualized_rate_bps(interest, 1_000 * ONE_TOKEN, 0, year).is_err());
+    }
+
+    #[test]
+    fn test_apr_to_apy() {
+        // Compounding once is the APR itself
+        assert_eq!(apr_to_apy(1_000, 1).unwrap(), 1_000);
+        // 10% APR: monthly 10.47%, daily 10.51%
+        assert_eq!(apr_to_apy(1_000, 12).unwrap(), 1_047);
+        assert_eq!(apr_to_apy(1_000, 365).unwrap(), 1_051);
+        // 100% APR compounded daily = 171.45%
+        assert_eq!(apr_to_apy(10_000, 365).unwrap(), 17_145);
+        assert_eq!(apr_to_apy(0, 365).unwrap(), 0);
+        assert!(apr_to_apy(1_000, 0).is_err());
+    }
+
+    #[test]
+    fn test_conversions() {
+        assert_eq!(checked_i128_to_u128(42).unwrap(), 42);
+        assert!(checked_i128_to_u128(-1).is_err());
+        assert_eq!(checked_u128_to_i128(42).unwrap(), 42);
+        assert!(checked_u128_to_i128(u128::MAX).is_err());
+
+        assert_eq!(to_u64_saturating(-5), 0);
+        assert_eq!(to_u64_saturating(1_000), 1_000);
+        assert_eq!(to_u64_saturating(i128::MAX), u64::MAX);
+    }
+
+    #[test]
+    fn test_rescale() {
+        // 7 -> 18 decimals is exact
+        assert_eq!(
+            rescale(ONE_TOKEN, 7, 18, Rounding::Down).unwrap(),
+            PRECISION
+        );
+        // 7 -> 6 decimals drops a digit
+        assert_eq!(rescale(1_234_567, 7, 6, Rounding::Down).unwrap(), 123_456);
+        assert_eq!(rescale(1_234_567, 7, 6, Rounding::Up).unwrap(), 123_457);
+        // Exact results don't round up
+        assert_eq!(rescale(1_234_560, 7, 6, Rounding::Up).unwrap(), 123_456);
+        // Same decimals is a no-op
+        assert_eq!(rescale(777, 7, 7, Rounding::Up).unwrap(), 777);
+        // Dust below the target precision
+        assert_eq!(rescale(5, 18, 7, Rounding::Down).unwrap(), 0);
+        assert_eq!(rescale(5, 18, 7, Rounding::Up).unwrap(), 1);
+        assert_eq!(rescale(5, 60, 0, Rounding::Up).unwrap(), 1);
+        // Overflow and negatives
+        assert!(rescale(i128::MAX, 0, 1, Rounding::Down).is_err());
+        assert!(rescale(1, 0, 60, Rounding::Down).is_err());
+        assert!(rescale(-1, 7, 6, Rounding::Down).is_err());
+
+        // Saturating mode clamps instead of failing
+        assert_eq!(
+            rescale_with(i128::MAX, 0, 1, Rounding::Down, OverflowMode::Saturate),
+            Ok(i128::MAX)
+        );
+        assert_eq!(
+            rescale_with(1, 0, 60, Rounding::Down, OverflowMode::Error),
+            Err(SharedError::Overflow)
+        );
+    }
+
+    #[test]
+    fn test_stroop_helpers() {
+        assert_eq!(xlm_to_stroops(3), Ok(Scaled(30_000_000)));
+        assert!(xlm_to_stroops(i128::MAX).is_err());
+
+        // 1.2345678 XLM displays as 1.23456
+        let stroops: Stroops = Scaled(12_345_678);
+        assert_eq!(stroops_to_xlm_display(stroops), Ok(Scaled(123_456)));
+
+        let display: XlmDisplay = Scaled(123_456);
+        assert_eq!(
+            display.rescale::<7>(Rounding::Down, OverflowMode::Error),
+            Ok(Scaled(12_345_600))
+        );
+        assert_eq!(Stroops::DECIMALS, 7);
+        assert_eq!(
+            stroops.checked_add(Scaled(22)).map(Scaled::raw),
+            Ok(12_345_700)
+        );
+    }
+
     #[test]
     fn test_min_max() {
         assert_eq!(min(100, 200), 100);
@@ -653,3 +1358,100 @@ mod tests {
         assert_eq!(max(200, 100), 200);
     }
 }
+
+#[cfg(all(test, feature = "fuzz"))]
+mod proptests {
+    extern crate std;
+
+    use super::*;
+    use proptest::prelude::*;
+
+    /// Reserves a live pool can hold without `calculate_k` overflowing
+    const MAX_RESERVE: i128 = 1_000_000_000_000_000_000;
+
+    proptest! {
+        #[test]
+        fn prop_mul_div_down_le_up(
+            a in 0..i64::MAX as i128,
+            b in 0..i64::MAX as i128,
+            c in 1..i64::MAX as i128,
+        ) {
+            let down = mul_div_down(a, b, c).unwrap();
+            let up = mul_div_up(a, b, c).unwrap();
+            prop_assert!(down <= up);
+            prop_assert!(up - down <= 1);
+        }
+
+        #[test]
+        fn prop_apply_bps_monotonic(
+            a in 0..MAX_RESERVE,
+            b in 0..MAX_RESERVE,
+            bps_a in 0..=10_000u32,
+            bps_b in 0..=10_000u32,
+        ) {
+            let (lo, hi) = (a.min(b), a.max(b));
+            let (bps_lo, bps_hi) = (bps_a.min(bps_b), bps_a.max(bps_b));
+
+            prop_assert!(apply_bps(lo, bps_a).unwrap() <= apply_bps(hi, bps_a).unwrap());
+            prop_assert!(apply_bps(a, bps_lo).unwrap() <= apply_bps(a, bps_hi).unwrap());
+            prop_assert!(apply_bps(a, bps_a).unwrap() <= a);
+
+            let down = apply_bps(a, bps_a).unwrap();
+            let up = apply_bps_round_up(a, bps_a).unwrap();
+            prop_assert!(down <= up && up - down <= 1);
+        }
+
+        /// The quoted input always buys at least the requested output
+        #[test]
+        fn prop_amount_in_covers_amount_out(
+            reserve_in in 1_000..MAX_RESERVE,
+            reserve_out in 1_000..MAX_RESERVE,
+            amount_out_bps in 1..10_000i128,
+            fee_bps in 0..=1_000u32,
+        ) {
+            let amount_out = (reserve_out * amount_out_bps / BPS_DENOMINATOR).max(1);
+            let amount_in = get_amount_in(amount_out, reserve_in, reserve_out, fee_bps).unwrap();
+            let received = get_amount_out(amount_in, reserve_in, reserve_out, fee_bps).unwrap();
+            prop_assert!(received >= amount_out);
+        }
+
+        /// Quoting the output of a swap back never asks for more than was
+        /// paid plus the 1-stroop safety margin. The output is rounded down,
+        /// so the re-quote can be below what was paid.
+        #[test]
+        fn prop_amount_out_round_trip(
+            reserve_in in 1_000..MAX_RESERVE,
+            reserve_out in 1_000..MAX_RESERVE,
+            amount_in in 1..MAX_RESERVE,
+            fee_bps in 0..=1_000u32,
+        ) {
+            let amount_out = get_amount_out(amount_in, reserve_in, reserve_out, fee_bps).unwrap();
+            prop_assume!(amount_out > 0);
+            let requoted = get_amount_in(amount_out, reserve_in, reserve_out, fee_bps).unwrap();
+            prop_assert!(requoted <= amount_in + 1);
+        }
+
+        /// A swap priced by `get_amount_out` never shrinks k
+        #[test]
+        fn prop_swap_preserves_k(
+            reserve_in in 1_000..MAX_RESERVE,
+            reserve_out in 1_000..MAX_RESERVE,
+            amount_in in 1..MAX_RESERVE,
+            fee_bps in 0..=1_000u32,
+            is_token_0_in: bool,
+        ) {
+            let amount_out = get_amount_out(amount_in, reserve_in, reserve_out, fee_bps).unwrap();
+            prop_assert!(amount_out < reserve_out);
+
+            let (new_0, new_1) =
+                update_reserves_swap(reserve_in, reserve_out, amount_in, amount_out, is_token_0_in)
+                    .unwrap();
+            let (old_0, old_1) = if is_token_0_in {
+                (reserve_in, reserve_out)
+            } else {
+                (reserve_out, reserve_in)
+            };
+            prop_assert!(verify_k_invariant(new_0, new_1, old_0, old_1).unwrap());
+        }
+    }
+}
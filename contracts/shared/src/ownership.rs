@@ -0,0 +1,130 @@
+//! # Ownership Module
+//!
+//! Two-step admin transfer: the current admin proposes a successor, who must
+//! accept with its own auth before it takes over. A typo'd or unreachable
+//! address never ends up holding the contract.
+//!
+//! The contract keeps storing its admin under its own key; this module only
+//! tracks the pending successor.
+//!
+//! ## Usage
+//! ```rust,ignore
+//! use astro_core_shared::ownership;
+//!
+//! pub fn accept_admin(env: Env) -> Result<(), SharedError> {
+//!     let old_admin = Self::get_admin(&env)?;
+//!     let new_admin = ownership::accept(&env, &old_admin)?;
+//!     env.storage().instance().set(&DataKey::Admin, &new_admin);
+//!     Ok(())
+//! }
+//! ```
+
+use soroban_sdk::{Address, Env};
+
+use crate::events::EventBuilder;
+use crate::types::{CommonInstanceKey, SharedError};
+
+/// Proposed successor, if any
+pub fn pending(env: &Env) -> Option<Address> {
+    env.storage()
+        .instance()
+        .get(&CommonInstanceKey::PendingAdmin)
+}
+
+/// Propose `new_admin` as successor of `admin`, replacing any earlier
+/// proposal. The caller checks the current admin's auth.
+pub fn propose(env: &Env, admin: &Address, new_admin: &Address) -> Result<(), SharedError> {
+    if new_admin == admin {
+        return Err(SharedError::InvalidAddress);
+    }
+
+    env.storage()
+        .instance()
+        .set(&CommonInstanceKey::PendingAdmin, new_admin);
+
+    let events = EventBuilder::new(env);
+    events.publish(
+        "ownership",
+        "proposed",
+        (admin.clone(), new_admin.clone(), env.ledger().timestamp()),
+    );
+
+    Ok(())
+}
+
+/// Complete the transfer away from `admin`: requires the proposed
+/// successor's auth and returns it for the caller to store as admin.
+pub fn accept(env: &Env, admin: &Address) -> Result<Address, SharedError> {
+    let new_admin = pending(env).ok_or(SharedError::InvalidState)?;
+    new_admin.require_auth();
+
+    env.storage()
+        .instance()
+        .remove(&CommonInstanceKey::PendingAdmin);
+
+    let events = EventBuilder::new(env);
+    events.publish(
+        "ownership",
+        "accepted",
+        (admin.clone(), new_admin.clone(), env.ledger().timestamp()),
+    );
+
+    Ok(new_admin)
+}
+
+/// Withdraw the pending proposal. The caller checks the current admin's auth.
+pub fn cancel(env: &Env) -> Result<(), SharedError> {
+    let new_admin = pending(env).ok_or(SharedError::InvalidState)?;
+    env.storage()
+        .instance()
+        .remove(&CommonInstanceKey::PendingAdmin);
+
+    let events = EventBuilder::new(env);
+    events.publish(
+        "ownership",
+        "cancelled",
+        (new_admin, env.ledger().timestamp()),
+    );
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use soroban_sdk::testutils::Address as _;
+    use soroban_sdk::{contract, contractimpl};
+
+    #[contract]
+    pub struct Owned;
+
+    #[contractimpl]
+    impl Owned {}
+
+    #[test]
+    fn test_propose_accept_cancel() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let contract_id = env.register(Owned, ());
+        let admin = Address::generate(&env);
+        let successor = Address::generate(&env);
+
+        env.as_contract(&contract_id, || {
+            assert_eq!(accept(&env, &admin), Err(SharedError::InvalidState));
+            assert_eq!(
+                propose(&env, &admin, &admin),
+                Err(SharedError::InvalidAddress)
+            );
+
+            propose(&env, &admin, &successor).unwrap();
+            assert_eq!(pending(&env), Some(successor.clone()));
+            cancel(&env).unwrap();
+            assert_eq!(pending(&env), None);
+            assert_eq!(cancel(&env), Err(SharedError::InvalidState));
+
+            propose(&env, &admin, &successor).unwrap();
+            assert_eq!(accept(&env, &admin), Ok(successor));
+            assert_eq!(pending(&env), None);
+        });
+    }
+}
@@ -0,0 +1,154 @@
+//! # Pausable Module
+//!
+//! Pause flags shared by every contract, split into function groups so an
+//! incident can halt deposits while users keep withdrawing.
+//!
+//! ## Groups
+//! - `Deposits` - functions that take funds in (deposit, stake, lock, ...)
+//! - `Withdrawals` - functions that send funds out (withdraw, unstake, ...)
+//! - `AdminOps` - configuration changes by the admin and its roles
+//!
+//! Pausing itself is never paused, so a paused contract can always resume.
+//!
+//! ## Usage
+//! ```rust,ignore
+//! use astro_core_shared::pausable::{self, PauseGroup};
+//!
+//! pub fn deposit(env: Env, from: Address, amount: i128) -> Result<(), SharedError> {
+//!     pausable::require_not_paused(&env, PauseGroup::Deposits)?;
+//!     // ...
+//! }
+//! ```
+
+use soroban_sdk::{contracttype, Env};
+
+use crate::types::{CommonInstanceKey, SharedError};
+
+/// Function group a pause flag applies to
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PauseGroup {
+    Deposits,
+    Withdrawals,
+    AdminOps,
+}
+
+/// Pause flags per function group
+#[contracttype]
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct PauseConfig {
+    /// Functions that take funds in
+    pub deposits: bool,
+    /// Functions that send funds out
+    pub withdrawals: bool,
+    /// Configuration changes
+    pub admin_ops: bool,
+}
+
+impl PauseConfig {
+    /// Every group set to `paused`
+    pub fn uniform(paused: bool) -> Self {
+        Self {
+            deposits: paused,
+            withdrawals: paused,
+            admin_ops: paused,
+        }
+    }
+
+    /// Whether `group` is paused
+    pub fn is_paused(&self, group: PauseGroup) -> bool {
+        match group {
+            PauseGroup::Deposits => self.deposits,
+            PauseGroup::Withdrawals => self.withdrawals,
+            PauseGroup::AdminOps => self.admin_ops,
+        }
+    }
+
+    /// Whether any group is paused
+    pub fn is_any(&self) -> bool {
+        self.deposits || self.withdrawals || self.admin_ops
+    }
+}
+
+/// Current pause flags (nothing paused if never set)
+pub fn get(env: &Env) -> PauseConfig {
+    env.storage()
+        .instance()
+        .get(&CommonInstanceKey::Paused)
+        .unwrap_or_default()
+}
+
+/// Store new pause flags. The caller checks auth and emits its own event.
+pub fn set(env: &Env, config: &PauseConfig) {
+    env.storage()
+        .instance()
+        .set(&CommonInstanceKey::Paused, config);
+}
+
+/// Whether `group` is paused
+pub fn is_paused(env: &Env, group: PauseGroup) -> bool {
+    get(env).is_paused(group)
+}
+
+/// Fail with `ContractPaused` if `group` is paused
+pub fn require_not_paused(env: &Env, group: PauseGroup) -> Result<(), SharedError> {
+    if is_paused(env, group) {
+        return Err(SharedError::ContractPaused);
+    }
+    Ok(())
+}
+
+/// Fail with `ContractNotPaused` unless `group` is paused (emergency paths)
+pub fn require_paused(env: &Env, group: PauseGroup) -> Result<(), SharedError> {
+    if !is_paused(env, group) {
+        return Err(SharedError::ContractNotPaused);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use soroban_sdk::{contract, contractimpl};
+
+    #[contract]
+    pub struct Pausable;
+
+    #[contractimpl]
+    impl Pausable {}
+
+    #[test]
+    fn test_groups_pause_independently() {
+        let env = Env::default();
+        let contract_id = env.register(Pausable, ());
+
+        env.as_contract(&contract_id, || {
+            assert_eq!(get(&env), PauseConfig::default());
+            assert!(require_not_paused(&env, PauseGroup::Deposits).is_ok());
+            assert_eq!(
+                require_paused(&env, PauseGroup::Withdrawals),
+                Err(SharedError::ContractNotPaused)
+            );
+
+            // Halt deposits, keep withdrawals open
+            set(
+                &env,
+                &PauseConfig {
+                    deposits: true,
+                    ..PauseConfig::default()
+                },
+            );
+            assert_eq!(
+                require_not_paused(&env, PauseGroup::Deposits),
+                Err(SharedError::ContractPaused)
+            );
+            assert!(require_not_paused(&env, PauseGroup::Withdrawals).is_ok());
+            assert!(require_not_paused(&env, PauseGroup::AdminOps).is_ok());
+            assert!(get(&env).is_any());
+
+            set(&env, &PauseConfig::uniform(true));
+            assert!(require_paused(&env, PauseGroup::Withdrawals).is_ok());
+            set(&env, &PauseConfig::uniform(false));
+            assert!(!get(&env).is_any());
+        });
+    }
+}
@@ -0,0 +1,117 @@
+//! # Ledger-Seeded Pseudo-Random Function
+//!
+//! Deterministic pseudo-random values derived from ledger data (sequence,
+//! timestamp) and the calling address, for non-security-critical tie-breaking —
+//! e.g. choosing which queued exit requests fill first when capacity is limited,
+//! instead of always favouring the lowest index.
+//!
+//! ## NOT suitable for lotteries
+//!
+//! Every input is public and predictable before the transaction lands:
+//! validators choose the ledger, and callers can simulate the outcome and
+//! only submit when it favours them. Never use this where the result carries
+//! value on its own (raffles, prize draws, random mints, leader election).
+//! Use a commit-reveal scheme or an oracle for those.
+//!
+//! ## Usage
+//!
+//! ```rust,ignore
+//! use astro_core_shared::prf::LedgerPrf;
+//!
+//! let mut prf = LedgerPrf::new(&env, &caller, symbol_short!("exits"));
+//! let first = prf.next_below(queue.len());
+//! ```
+
+use soroban_sdk::{xdr::ToXdr, Address, Bytes, BytesN, Env, Symbol};
+
+/// Pseudo-random stream seeded from ledger data. See module docs before using.
+pub struct LedgerPrf<'a> {
+    env: &'a Env,
+    seed: BytesN<32>,
+    counter: u32,
+}
+
+impl<'a> LedgerPrf<'a> {
+    /// Seed a stream from the current ledger, `caller` and a domain tag.
+    /// Different domains yield independent streams within one transaction.
+    pub fn new(env: &'a Env, caller: &Address, domain: Symbol) -> Self {
+        let mut data = Bytes::new(env);
+        data.extend_from_array(&env.ledger().sequence().to_be_bytes());
+        data.extend_from_array(&env.ledger().timestamp().to_be_bytes());
+        data.append(&caller.clone().to_xdr(env));
+        data.append(&domain.to_xdr(env));
+
+        Self {
+            env,
+            seed: env.crypto().sha256(&data).to_bytes(),
+            counter: 0,
+        }
+    }
+
+    /// Next value in the stream
+    pub fn next_u64(&mut self) -> u64 {
+        let mut data = Bytes::from_array(self.env, &self.seed.to_array());
+        data.extend_from_array(&self.counter.to_be_bytes());
+        self.counter = self.counter.wrapping_add(1);
+
+        let digest = self.env.crypto().sha256(&data).to_array();
+        let mut raw = [0u8; 8];
+        raw.copy_from_slice(&digest[..8]);
+        u64::from_be_bytes(raw)
+    }
+
+    /// Next value in `[0, bound)`; returns 0 when `bound` is 0.
+    /// Uses rejection sampling so results are unbiased.
+    pub fn next_below(&mut self, bound: u32) -> u32 {
+        if bound <= 1 {
+            return 0;
+        }
+        let bound = bound as u64;
+        let zone = u64::MAX - (u64::MAX % bound);
+        loop {
+            let value = self.next_u64();
+            if value < zone {
+                return (value % bound) as u32;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use soroban_sdk::{
+        symbol_short,
+        testutils::{Address as _, Ledger as _},
+    };
+
+    #[test]
+    fn test_deterministic_per_ledger() {
+        let env = Env::default();
+        let caller = Address::generate(&env);
+
+        let a = LedgerPrf::new(&env, &caller, symbol_short!("exits")).next_u64();
+        let b = LedgerPrf::new(&env, &caller, symbol_short!("exits")).next_u64();
+        assert_eq!(a, b);
+
+        let other = LedgerPrf::new(&env, &caller, symbol_short!("other")).next_u64();
+        assert_ne!(a, other);
+
+        env.ledger().with_mut(|li| li.sequence_number += 1);
+        let c = LedgerPrf::new(&env, &caller, symbol_short!("exits")).next_u64();
+        assert_ne!(a, c);
+    }
+
+    #[test]
+    fn test_next_below_range() {
+        let env = Env::default();
+        let caller = Address::generate(&env);
+        let mut prf = LedgerPrf::new(&env, &caller, symbol_short!("exits"));
+
+        assert_eq!(prf.next_below(0), 0);
+        assert_eq!(prf.next_below(1), 0);
+        for _ in 0..50 {
+            assert!(prf.next_below(7) < 7);
+        }
+    }
+}
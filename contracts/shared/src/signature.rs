@@ -0,0 +1,152 @@
+//! # Signature Module
+//!
+//! Signed-message authorization for relayed transactions: the user signs a
+//! payload off-chain with a registered ed25519 key and a sponsor submits it,
+//! paying the fees. Contracts expose these as `*_with_sig` variants next to
+//! the `require_auth` entrypoints.
+//!
+//! ## Payload
+//! `sha256(DOMAIN || network_id || contract (XDR) || function (XDR) ||
+//! args (XDR) || nonce (u64 BE))`. The network, contract and function make a
+//! signature useless anywhere else; the per-account nonce makes it single use.
+//!
+//! ## Usage
+//! ```rust,ignore
+//! use astro_core_shared::signature;
+//!
+//! pub fn claim_with_sig(
+//!     env: Env,
+//!     user: Address,
+//!     nonce: u64,
+//!     sig: BytesN<64>,
+//! ) -> Result<i128, SharedError> {
+//!     let function = Symbol::new(&env, "claim");
+//!     signature::consume(&env, &user, &function, (user.clone(),), nonce, &sig)?;
+//!     Self::claim_internal(&env, &user)
+//! }
+//! ```
+
+use soroban_sdk::{xdr::ToXdr, Address, Bytes, BytesN, Env, IntoVal, Symbol, Val};
+
+use crate::events::EventBuilder;
+use crate::types::{get_persistent, set_persistent, CommonPersistentKey, SharedError};
+
+/// Domain tag prefixed to every signed payload
+pub const DOMAIN: &[u8] = b"astro-core:sig:v1";
+
+/// Registered ed25519 key of `account`, if any
+pub fn signing_key(env: &Env, account: &Address) -> Option<BytesN<32>> {
+    get_persistent(env, &CommonPersistentKey::SigningKey(account.clone()))
+}
+
+/// Register (or rotate) the key `account` signs payloads with.
+/// The caller checks `account`'s auth.
+pub fn set_signing_key(env: &Env, account: &Address, key: &BytesN<32>) {
+    let storage_key = CommonPersistentKey::SigningKey(account.clone());
+    set_persistent(env, &storage_key, key);
+
+    let events = EventBuilder::new(env);
+    events.publish(
+        "signature",
+        "key_set",
+        (account.clone(), key.clone(), env.ledger().timestamp()),
+    );
+}
+
+/// Next nonce `account` must sign with
+pub fn nonce(env: &Env, account: &Address) -> u64 {
+    get_persistent(env, &CommonPersistentKey::Nonce(account.clone())).unwrap_or(0)
+}
+
+/// Hash a call to `function` with `args` on the current contract
+pub fn payload_hash<A>(env: &Env, function: &Symbol, args: A, nonce: u64) -> BytesN<32>
+where
+    A: IntoVal<Env, Val>,
+{
+    let mut data = Bytes::from_slice(env, DOMAIN);
+    data.append(&env.ledger().network_id().into());
+    data.append(&env.current_contract_address().to_xdr(env));
+    data.append(&function.clone().to_xdr(env));
+    data.append(&args.to_xdr(env));
+    data.extend_from_array(&nonce.to_be_bytes());
+    env.crypto().sha256(&data).into()
+}
+
+/// Verify `signature` by `account`'s registered key over the payload for
+/// `function(args)` and consume `nonce`. Panics if the signature is invalid.
+pub fn consume<A>(
+    env: &Env,
+    account: &Address,
+    function: &Symbol,
+    args: A,
+    nonce: u64,
+    signature: &BytesN<64>,
+) -> Result<(), SharedError>
+where
+    A: IntoVal<Env, Val>,
+{
+    let key = signing_key(env, account).ok_or(SharedError::Unauthorized)?;
+    if nonce != self::nonce(env, account) {
+        return Err(SharedError::InvalidState);
+    }
+
+    let hash = payload_hash(env, function, args, nonce);
+    env.crypto().ed25519_verify(&key, &hash.into(), signature);
+
+    let nonce_key = CommonPersistentKey::Nonce(account.clone());
+    set_persistent(env, &nonce_key, &(nonce + 1));
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ed25519_dalek::{Signer, SigningKey};
+    use soroban_sdk::testutils::Address as _;
+    use soroban_sdk::{contract, contractimpl};
+
+    #[contract]
+    pub struct Relayed;
+
+    #[contractimpl]
+    impl Relayed {}
+
+    #[test]
+    fn test_signed_payload_is_single_use() {
+        let env = Env::default();
+        let contract_id = env.register(Relayed, ());
+        let user = Address::generate(&env);
+        let signer = SigningKey::from_bytes(&[7; 32]);
+        let public = BytesN::from_array(&env, &signer.verifying_key().to_bytes());
+        let function = Symbol::new(&env, "claim");
+
+        env.as_contract(&contract_id, || {
+            let hash = payload_hash(&env, &function, (user.clone(),), 0);
+            let sig = BytesN::from_array(&env, &signer.sign(&hash.to_array()).to_bytes());
+
+            // No key registered yet
+            assert_eq!(
+                consume(&env, &user, &function, (user.clone(),), 0, &sig),
+                Err(SharedError::Unauthorized)
+            );
+
+            set_signing_key(&env, &user, &public);
+            consume(&env, &user, &function, (user.clone(),), 0, &sig).unwrap();
+            assert_eq!(nonce(&env, &user), 1);
+
+            // Replays are rejected
+            assert_eq!(
+                consume(&env, &user, &function, (user.clone(),), 0, &sig),
+                Err(SharedError::InvalidState)
+            );
+
+            // The payload binds the function and the nonce
+            let other = Symbol::new(&env, "unlock");
+            assert_ne!(
+                payload_hash(&env, &other, (user.clone(),), 1),
+                payload_hash(&env, &function, (user.clone(),), 1)
+            );
+        });
+    }
+}
@@ -0,0 +1,156 @@
+//! # Test Fixtures
+//!
+//! Deterministic building blocks for contract tests, behind the `testutils`
+//! feature: an `Env` on a fixed ledger, tokens, funded users and the default
+//! configs the contract tests share. Each contract pulls them in through a
+//! dev-dependency on this crate with the feature on. The protocol-wide
+//! `ProtocolHarness` built on these lives in the `astro-harness` crate,
+//! since it has to depend on the contracts themselves.
+//!
+//! ## Usage
+//! ```rust,ignore
+//! use astro_core_shared::testutils;
+//!
+//! let env = testutils::env();
+//! let admin = testutils::user(&env);
+//! let astro = testutils::create_token(&env, &admin);
+//! let [alice, bob] = testutils::funded_users(&env, &astro, 1_000_000_000);
+//! testutils::advance_time(&env, 86_400);
+//! ```
+
+use core::ops::Deref;
+
+use soroban_sdk::{
+    testutils::{Address as _, Ledger, LedgerInfo},
+    token, Address, Env,
+};
+
+use crate::types::{Bps, LockConfig, PenaltyRouting, StakingConfig};
+
+/// Ledger timestamp every fixture `Env` starts at
+pub const GENESIS_TIMESTAMP: u64 = 1_700_000_000;
+
+/// Ledger sequence every fixture `Env` starts at
+pub const GENESIS_SEQUENCE: u32 = 1_000;
+
+/// One token with 7 decimals
+pub const ONE_TOKEN: i128 = 10_000_000;
+
+/// Env on a fixed ledger with every auth mocked
+pub fn env() -> Env {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().set(LedgerInfo {
+        timestamp: GENESIS_TIMESTAMP,
+        sequence_number: GENESIS_SEQUENCE,
+        ..env.ledger().get()
+    });
+    env
+}
+
+/// Move the ledger clock forward, one ledger per 5 seconds
+pub fn advance_time(env: &Env, secs: u64) {
+    env.ledger().with_mut(|ledger| {
+        ledger.timestamp += secs;
+        ledger.sequence_number += (secs / 5) as u32;
+    });
+}
+
+/// A fresh account
+pub fn user(env: &Env) -> Address {
+    Address::generate(env)
+}
+
+/// A Stellar asset contract with clients for holders and its issuer.
+/// Derefs to its SEP-41 client.
+pub struct TestToken<'a> {
+    /// Token contract address
+    pub address: Address,
+    /// SEP-41 client
+    pub client: token::Client<'a>,
+    /// Issuer client, for minting
+    pub admin: token::StellarAssetClient<'a>,
+}
+
+impl TestToken<'_> {
+    /// Mint `amount` to `to`
+    pub fn mint(&self, to: &Address, amount: i128) {
+        self.admin.mint(to, &amount);
+    }
+}
+
+impl<'a> Deref for TestToken<'a> {
+    type Target = token::Client<'a>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.client
+    }
+}
+
+/// Deploy a Stellar asset contract issued by `admin`
+pub fn create_token<'a>(env: &Env, admin: &Address) -> TestToken<'a> {
+    let address = env
+        .register_stellar_asset_contract_v2(admin.clone())
+        .address();
+    TestToken {
+        client: token::Client::new(env, &address),
+        admin: token::StellarAssetClient::new(env, &address),
+        address,
+    }
+}
+
+/// `N` fresh accounts holding `amount` of `token` each
+pub fn funded_users<const N: usize>(env: &Env, token: &TestToken, amount: i128) -> [Address; N] {
+    core::array::from_fn(|_| {
+        let user = user(env);
+        token.mint(&user, amount);
+        user
+    })
+}
+
+/// Staking config with no fees, cooldown or caps
+pub fn default_staking_config() -> StakingConfig {
+    StakingConfig {
+        min_stake_amount: ONE_TOKEN,
+        cooldown_period: 0,
+        max_stake_per_user: 0,
+        emergency_unlock: false,
+        slashing_enabled: false,
+        deposit_fee_bps: 0,
+        withdraw_fee_bps: 0,
+    }
+}
+
+/// Lock config from 1 day to 1 year, 25% early-unlock penalty to the treasury
+pub fn default_lock_config() -> LockConfig {
+    LockConfig {
+        min_lock_duration: 86_400,
+        max_lock_duration: 31_536_000,
+        early_unlock_enabled: true,
+        early_unlock_penalty_bps: Bps::from_const(2500),
+        penalty_routing: PenaltyRouting::TREASURY_ONLY,
+        unlock_buffer: 0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fixtures_are_deterministic() {
+        let env = env();
+        assert_eq!(env.ledger().timestamp(), GENESIS_TIMESTAMP);
+        assert_eq!(env.ledger().sequence(), GENESIS_SEQUENCE);
+
+        advance_time(&env, 50);
+        assert_eq!(env.ledger().timestamp(), GENESIS_TIMESTAMP + 50);
+        assert_eq!(env.ledger().sequence(), GENESIS_SEQUENCE + 10);
+
+        let admin = user(&env);
+        let token = create_token(&env, &admin);
+        let [alice, bob] = funded_users(&env, &token, 5 * ONE_TOKEN);
+        assert_eq!(token.balance(&alice), 5 * ONE_TOKEN);
+        assert_eq!(token.balance(&bob), 5 * ONE_TOKEN);
+    }
+}
@@ -0,0 +1,156 @@
+//! # Basis Points
+//!
+//! `Bps` is a share between 0 and 10,000 basis points (0% - 100%). Config
+//! fields use it instead of a bare `u32` so an out-of-range share fails where
+//! the value is built, not later in fee math.
+//!
+//! Constants are checked at compile time through `Bps::from_const`; runtime
+//! values (entrypoint arguments) go through `Bps::new`.
+//!
+//! ## Usage
+//! ```rust,ignore
+//! use astro_core_shared::Bps;
+//!
+//! const DEFAULT_PENALTY: Bps = Bps::from_const(2_500); // 25%
+//!
+//! let share = Bps::new(bps)?;
+//! let fee = share.apply(amount)?;
+//! ```
+
+use core::ops::{Add, Sub};
+
+use soroban_sdk::contracttype;
+
+use super::SharedError;
+use crate::math::apply_bps;
+
+/// Share in basis points, always within `0..=10_000`
+#[contracttype]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Bps(u32);
+
+impl Bps {
+    /// Largest valid value (100%)
+    pub const MAX_VALUE: u32 = 10_000;
+    /// 0%
+    pub const ZERO: Bps = Bps(0);
+    /// 100%
+    pub const MAX: Bps = Bps(Self::MAX_VALUE);
+
+    /// Checked constructor, `InvalidBps` above 10,000
+    pub const fn new(value: u32) -> Result<Self, SharedError> {
+        if value > Self::MAX_VALUE {
+            Err(SharedError::InvalidBps)
+        } else {
+            Ok(Bps(value))
+        }
+    }
+
+    /// Constructor for constants: an out-of-range value fails to compile in
+    /// a `const` context and panics otherwise
+    pub const fn from_const(value: u32) -> Self {
+        assert!(value <= Self::MAX_VALUE, "bps above 10,000");
+        Bps(value)
+    }
+
+    /// Raw basis points
+    pub const fn get(self) -> u32 {
+        self.0
+    }
+
+    /// Whether the value is in range. Always true for values built through
+    /// the constructors; decoded contract arguments should be checked.
+    pub const fn is_valid(self) -> bool {
+        self.0 <= Self::MAX_VALUE
+    }
+
+    /// Sum of two shares, `None` above 100%
+    pub fn checked_add(self, other: Bps) -> Option<Bps> {
+        Bps::new(self.0.checked_add(other.0)?).ok()
+    }
+
+    /// Difference of two shares, `None` below 0%
+    pub fn checked_sub(self, other: Bps) -> Option<Bps> {
+        Some(Bps(self.0.checked_sub(other.0)?))
+    }
+
+    /// Remaining share: 10,000 - self
+    pub fn complement(self) -> Bps {
+        Bps(Self::MAX_VALUE.saturating_sub(self.0))
+    }
+
+    /// This share of `amount`, rounded down
+    pub fn apply(self, amount: i128) -> Result<i128, SharedError> {
+        apply_bps(amount, self.0)
+    }
+}
+
+/// Panics above 100%, like integer overflow; use `checked_add` where the
+/// sum isn't already bounded
+impl Add for Bps {
+    type Output = Bps;
+
+    fn add(self, other: Bps) -> Bps {
+        self.checked_add(other).expect("bps above 10,000")
+    }
+}
+
+/// Panics below 0%; use `checked_sub` where the order isn't already known
+impl Sub for Bps {
+    type Output = Bps;
+
+    fn sub(self, other: Bps) -> Bps {
+        self.checked_sub(other).expect("bps below 0")
+    }
+}
+
+impl TryFrom<u32> for Bps {
+    type Error = SharedError;
+
+    fn try_from(value: u32) -> Result<Self, SharedError> {
+        Bps::new(value)
+    }
+}
+
+impl From<Bps> for u32 {
+    fn from(bps: Bps) -> u32 {
+        bps.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use soroban_sdk::{Env, IntoVal, TryFromVal, Val};
+
+    const QUARTER: Bps = Bps::from_const(2_500);
+
+    #[test]
+    fn test_range_and_arithmetic() {
+        assert_eq!(Bps::new(10_000), Ok(Bps::MAX));
+        assert_eq!(Bps::new(10_001), Err(SharedError::InvalidBps));
+        assert_eq!(Bps::try_from(0u32), Ok(Bps::ZERO));
+
+        assert_eq!(QUARTER + QUARTER, Bps::from_const(5_000));
+        assert_eq!(Bps::MAX - QUARTER, Bps::from_const(7_500));
+        assert_eq!(QUARTER.complement(), Bps::from_const(7_500));
+        assert_eq!(Bps::MAX.checked_add(QUARTER), None);
+        assert_eq!(QUARTER.checked_sub(Bps::MAX), None);
+
+        assert_eq!(QUARTER.apply(1_000).unwrap(), 250);
+        assert_eq!(u32::from(QUARTER), 2_500);
+    }
+
+    #[test]
+    #[should_panic(expected = "bps above 10,000")]
+    fn test_add_overflow_panics() {
+        let _ = Bps::MAX + QUARTER;
+    }
+
+    #[test]
+    fn test_val_round_trip() {
+        let env = Env::default();
+        let val: Val = QUARTER.into_val(&env);
+        assert_eq!(Bps::try_from_val(&env, &val).unwrap(), QUARTER);
+    }
+}
@@ -2,16 +2,18 @@
 //!
 //! Common configuration structures used across contracts.
 
-use soroban_sdk::{contracttype, Address};
+use soroban_sdk::{contracttype, Address, Vec};
+
+use super::{Bps, PenaltyRouting};
 
 /// Fee configuration for trading
 #[contracttype]
 #[derive(Clone, Debug)]
 pub struct FeeConfig {
     /// Protocol fee in basis points (max 100 = 1%)
-    pub protocol_fee_bps: u32,
+    pub protocol_fee_bps: Bps,
     /// LP fee in basis points (max 100 = 1%)
-    pub lp_fee_bps: u32,
+    pub lp_fee_bps: Bps,
     /// Treasury address to receive protocol fees
     pub treasury: Address,
 }
@@ -26,41 +28,136 @@ impl FeeConfig {
 
     /// Validate fee configuration
     pub fn is_valid(&self) -> bool {
-        self.protocol_fee_bps <= Self::MAX_FEE_BPS
-            && self.lp_fee_bps <= Self::MAX_FEE_BPS
-            && (self.protocol_fee_bps + self.lp_fee_bps) <= Self::MAX_TOTAL_FEE_BPS
+        self.protocol_fee_bps.get() <= Self::MAX_FEE_BPS
+            && self.lp_fee_bps.get() <= Self::MAX_FEE_BPS
+            && self.total_fee_bps() <= Self::MAX_TOTAL_FEE_BPS
     }
 
     /// Calculate total fee in basis points
     pub fn total_fee_bps(&self) -> u32 {
-        self.protocol_fee_bps + self.lp_fee_bps
+        self.protocol_fee_bps.get() + self.lp_fee_bps.get()
+    }
+}
+
+/// Referral configuration: the cut of protocol fees paid to referrers
+#[contracttype]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ReferralConfig {
+    /// Share of a referred user's protocol fee credited to their referrer (bps)
+    pub share_bps: u32,
+    /// Minimum accrued amount a referrer can claim
+    pub min_claim: i128,
+}
+
+impl ReferralConfig {
+    /// Maximum referral share of a protocol fee (50%)
+    pub const MAX_SHARE_BPS: u32 = 5_000;
+
+    /// Validate referral configuration
+    pub fn is_valid(&self) -> bool {
+        self.share_bps <= Self::MAX_SHARE_BPS && self.min_claim >= 0
     }
 }
 
+/// Role of a fee distribution recipient
+#[contracttype]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[repr(u32)]
+pub enum RecipientKind {
+    /// Treasury vault (also receives rounding dust)
+    Treasury = 0,
+    /// Staking pool
+    Staking = 1,
+    /// Burn address or dead address
+    Burn = 2,
+    /// Any other wallet (LP incentives, dev fund, ...)
+    Other = 3,
+    /// Insurance fund
+    Insurance = 4,
+}
+
+/// A single fee distribution recipient
+#[contracttype]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DistributionRecipient {
+    /// Recipient address
+    pub address: Address,
+    /// Share in basis points (e.g., 5000 = 50%)
+    pub bps: Bps,
+    /// Recipient role
+    pub kind: RecipientKind,
+}
+
+/// How the burn share of a distribution is destroyed
+#[contracttype]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[repr(u32)]
+pub enum BurnMode {
+    /// Transfer to the burn recipient address (dead address)
+    Transfer = 0,
+    /// Call `burn` on the token, falling back to `Transfer` if unsupported
+    Burn = 1,
+}
+
 /// Distribution configuration for Fee Distributor
 #[contracttype]
 #[derive(Clone, Debug)]
 pub struct DistributionConfig {
-    /// Treasury vault address (receives treasury_bps)
-    pub treasury_vault: Address,
-    /// Staking pool address (receives staking_bps)
-    pub staking_pool: Address,
-    /// Burn address or dead address (receives burn_bps)
-    pub burn_address: Address,
-    /// Treasury percentage in basis points (e.g., 5000 = 50%)
-    pub treasury_bps: u32,
-    /// Staking percentage in basis points (e.g., 3000 = 30%)
-    pub staking_bps: u32,
-    /// Burn percentage in basis points (e.g., 2000 = 20%)
-    pub burn_bps: u32,
+    /// Recipients and their shares (must sum to 10,000 bps)
+    pub recipients: Vec<DistributionRecipient>,
     /// Minimum amount to trigger distribution
     pub min_distribution: i128,
+    /// How `Burn` recipients are paid
+    pub burn_mode: BurnMode,
+    /// Pay `Staking` recipients with a plain transfer instead of `add_rewards`
+    pub staking_raw_transfer: bool,
+    /// Circuit breaker: largest amount one distribution may move (0 = no cap)
+    pub max_distribution_per_call: i128,
+    /// Circuit breaker: largest amount distributed per token per day (0 = no cap)
+    pub max_distribution_per_day: i128,
 }
 
 impl DistributionConfig {
-    /// Validate that percentages sum to 100%
+    /// Maximum number of recipients (bounds distribution cost)
+    pub const MAX_RECIPIENTS: u32 = 10;
+
+    /// Validate that percentages sum to 100% and addresses are unique
     pub fn is_valid(&self) -> bool {
-        self.treasury_bps + self.staking_bps + self.burn_bps == 10_000 && self.min_distribution >= 0
+        if self.recipients.is_empty()
+            || self.recipients.len() > Self::MAX_RECIPIENTS
+            || self.min_distribution < 0
+            || self.max_distribution_per_call < 0
+            || self.max_distribution_per_day < 0
+        {
+            return false;
+        }
+
+        let mut total = Bps::ZERO;
+        for (i, recipient) in self.recipients.iter().enumerate() {
+            total = match total.checked_add(recipient.bps) {
+                Some(total) => total,
+                None => return false,
+            };
+            for other in self.recipients.iter().skip(i + 1) {
+                if other.address == recipient.address {
+                    return false;
+                }
+            }
+        }
+        total == Bps::MAX
+    }
+
+    /// Index of a recipient by address
+    pub fn recipient_index(&self, address: &Address) -> Option<u32> {
+        self.recipients
+            .iter()
+            .position(|r| r.address == *address)
+            .map(|i| i as u32)
+    }
+
+    /// First recipient of a given kind
+    pub fn recipient_of(&self, kind: RecipientKind) -> Option<DistributionRecipient> {
+        self.recipients.iter().find(|r| r.kind == kind)
     }
 }
 
@@ -76,6 +173,24 @@ pub struct StakingConfig {
     pub max_stake_per_user: i128,
     /// Whether emergency unlock is enabled
     pub emergency_unlock: bool,
+    /// Whether stakes can be slashed by the admin or an authorized slasher
+    pub slashing_enabled: bool,
+    /// Fee charged on stake, in basis points (sent to fee distributor)
+    pub deposit_fee_bps: u32,
+    /// Fee charged on unstake, in basis points (sent to fee distributor)
+    pub withdraw_fee_bps: u32,
+}
+
+impl StakingConfig {
+    /// Maximum deposit or withdraw fee in basis points (5%)
+    pub const MAX_FEE_BPS: u32 = 500;
+
+    /// Validate configuration
+    pub fn is_valid(&self) -> bool {
+        self.min_stake_amount > 0
+            && self.deposit_fee_bps <= Self::MAX_FEE_BPS
+            && self.withdraw_fee_bps <= Self::MAX_FEE_BPS
+    }
 }
 
 /// Lock configuration for Liquidity Locker
@@ -89,7 +204,9 @@ pub struct LockConfig {
     /// Whether early unlock is allowed (with penalty)
     pub early_unlock_enabled: bool,
     /// Early unlock penalty in basis points
-    pub early_unlock_penalty_bps: u32,
+    pub early_unlock_penalty_bps: Bps,
+    /// Where early unlock penalties go
+    pub penalty_routing: PenaltyRouting,
     /// Buffer time after unlock_time before unlock can execute (security measure)
     pub unlock_buffer: u64,
 }
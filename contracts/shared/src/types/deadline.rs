@@ -0,0 +1,26 @@
+//! # Deadlines
+//!
+//! Expiry for pre-signed transactions: a call carrying a deadline fails once
+//! the ledger time passes it, instead of executing much later under changed
+//! prices or config.
+
+use soroban_sdk::Env;
+
+use super::SharedError;
+
+/// Fail with `DeadlineExpired` once the ledger time is past `deadline`
+pub fn check_deadline(env: &Env, deadline: u64) -> Result<(), SharedError> {
+    if env.ledger().timestamp() > deadline {
+        return Err(SharedError::DeadlineExpired);
+    }
+    Ok(())
+}
+
+/// `check_deadline` for entrypoints where the deadline is optional
+/// (`None` never expires)
+pub fn check_optional_deadline(env: &Env, deadline: Option<u64>) -> Result<(), SharedError> {
+    match deadline {
+        Some(deadline) => check_deadline(env, deadline),
+        None => Ok(()),
+    }
+}
@@ -81,6 +81,8 @@ pub enum SharedError {
     TransferFailed = 402,
     /// Insufficient allowance
     InsufficientAllowance = 403,
+    /// Token is denylisted, or not allowlisted, in the token registry
+    TokenNotAllowed = 404,
 
     // ════════════════════════════════════════════════════════════════
     // Math Errors (500-599)
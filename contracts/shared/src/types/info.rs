@@ -0,0 +1,77 @@
+//! # Contract Info
+//!
+//! Standard self-description returned by every contract's `contract_info()`,
+//! so explorers and wallets can render protocol components generically.
+
+use soroban_sdk::{contracttype, Address, Env, Map, String, Symbol};
+
+/// Name, version, admin and key settings of a protocol contract
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct ContractInfo {
+    /// Short contract name (same as its event namespace, e.g. "fee_dist")
+    pub name: Symbol,
+    /// Crate version the contract was built from
+    pub version: String,
+    /// Current admin
+    pub admin: Address,
+    /// Whether the contract is paused
+    pub paused: bool,
+    /// Key config values (bps, durations, amounts, counts)
+    pub config: Map<Symbol, i128>,
+    /// Protocol registry, if one has been set
+    pub registry: Option<Address>,
+}
+
+impl ContractInfo {
+    /// Create info with an empty config summary
+    pub fn new(
+        env: &Env,
+        name: &str,
+        version: &str,
+        admin: Address,
+        paused: bool,
+        registry: Option<Address>,
+    ) -> Self {
+        Self {
+            name: Symbol::new(env, name),
+            version: String::from_str(env, version),
+            admin,
+            paused,
+            config: Map::new(env),
+            registry,
+        }
+    }
+
+    /// Add a config summary entry
+    pub fn with(mut self, key: &str, value: i128) -> Self {
+        let key = Symbol::new(self.config.env(), key);
+        self.config.set(key, value);
+        self
+    }
+}
+
+/// Token balance held by a contract against what it owes, returned by
+/// `solvency_report()` so monitoring can detect accounting drift
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TokenSolvency {
+    /// Token the figures are in
+    pub token: Address,
+    /// Token balance of the contract
+    pub balance: i128,
+    /// Amount the contract's accounting says it owes (stakes, locks, fees)
+    pub liabilities: i128,
+}
+
+impl TokenSolvency {
+    /// Balance minus liabilities (negative when the contract is short)
+    pub fn surplus(&self) -> i128 {
+        self.balance.saturating_sub(self.liabilities)
+    }
+
+    /// Whether the balance covers the liabilities
+    pub fn is_solvent(&self) -> bool {
+        self.balance >= self.liabilities
+    }
+}
@@ -2,12 +2,22 @@
 //!
 //! Common data structures used across all Astro ecosystem contracts.
 
+mod bps;
 mod config;
+mod deadline;
 mod errors;
+mod info;
+mod penalty;
 mod storage;
 mod token;
+mod validation;
 
+pub use bps::*;
 pub use config::*;
+pub use deadline::*;
 pub use errors::*;
+pub use info::*;
+pub use penalty::*;
 pub use storage::*;
 pub use token::*;
+pub use validation::*;
@@ -0,0 +1,118 @@
+//! # Penalty Routing
+//!
+//! `PenaltyRouting` says where a penalty goes (locker early unlocks, staking
+//! early exits): a share to the treasury, a share burned and a share to
+//! stakers through the fee distributor. The three shares sum to 100%, and
+//! `split` turns a penalty into amounts per destination, with rounding dust
+//! going to the treasury.
+//!
+//! ## Usage
+//! ```rust,ignore
+//! use astro_core_shared::PenaltyRouting;
+//!
+//! let routing = PenaltyRouting::new(5_000, 2_000, 3_000)?;
+//! let split = routing.split(penalty)?;
+//! ```
+
+use soroban_sdk::contracttype;
+
+use super::{Bps, SharedError};
+use crate::math::safe_sub;
+
+/// Shares of a penalty sent to the treasury, burned and sent to stakers
+#[contracttype]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PenaltyRouting {
+    /// Share sent to the treasury
+    pub treasury_bps: Bps,
+    /// Share burned
+    pub burn_bps: Bps,
+    /// Share sent to stakers through the fee distributor
+    pub stakers_bps: Bps,
+}
+
+/// A penalty split by `PenaltyRouting`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PenaltySplit {
+    /// Amount for the treasury, including rounding dust
+    pub to_treasury: i128,
+    /// Amount to burn
+    pub to_burn: i128,
+    /// Amount for stakers
+    pub to_stakers: i128,
+}
+
+impl PenaltyRouting {
+    /// Whole penalty to the treasury
+    pub const TREASURY_ONLY: PenaltyRouting = PenaltyRouting {
+        treasury_bps: Bps::MAX,
+        burn_bps: Bps::ZERO,
+        stakers_bps: Bps::ZERO,
+    };
+
+    /// Checked constructor, `InvalidBps` unless the shares sum to 10,000
+    pub fn new(treasury_bps: u32, burn_bps: u32, stakers_bps: u32) -> Result<Self, SharedError> {
+        let routing = PenaltyRouting {
+            treasury_bps: Bps::new(treasury_bps)?,
+            burn_bps: Bps::new(burn_bps)?,
+            stakers_bps: Bps::new(stakers_bps)?,
+        };
+        if routing.is_valid() {
+            Ok(routing)
+        } else {
+            Err(SharedError::InvalidBps)
+        }
+    }
+
+    /// Whether the shares are in range and sum to exactly 100%
+    pub fn is_valid(&self) -> bool {
+        self.treasury_bps
+            .checked_add(self.burn_bps)
+            .and_then(|sum| sum.checked_add(self.stakers_bps))
+            == Some(Bps::MAX)
+    }
+
+    /// Split `penalty`; burn and stakers round down, the treasury takes the
+    /// remainder
+    pub fn split(&self, penalty: i128) -> Result<PenaltySplit, SharedError> {
+        let to_burn = self.burn_bps.apply(penalty)?;
+        let to_stakers = self.stakers_bps.apply(penalty)?;
+        Ok(PenaltySplit {
+            to_treasury: safe_sub(safe_sub(penalty, to_burn)?, to_stakers)?,
+            to_burn,
+            to_stakers,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_penalty_routing() {
+        assert_eq!(
+            PenaltyRouting::new(5_000, 2_000, 2_000),
+            Err(SharedError::InvalidBps)
+        );
+        assert_eq!(
+            PenaltyRouting::new(10_001, 0, 0),
+            Err(SharedError::InvalidBps)
+        );
+        assert!(PenaltyRouting::TREASURY_ONLY.is_valid());
+
+        let routing = PenaltyRouting::new(5_000, 2_000, 3_000).unwrap();
+        assert_eq!(
+            routing.split(1_001).unwrap(),
+            PenaltySplit {
+                to_treasury: 501,
+                to_burn: 200,
+                to_stakers: 300,
+            }
+        );
+        assert_eq!(
+            PenaltyRouting::TREASURY_ONLY.split(77).unwrap().to_treasury,
+            77
+        );
+    }
+}
@@ -1,8 +1,19 @@
 //! # Storage Key Types
 //!
-//! Common storage key patterns for contracts.
+//! Common storage key patterns for contracts, and persistent storage
+//! accessors that keep entries alive.
+//!
+//! Persistent entries are archived once their TTL runs out. Contracts read
+//! and write them through `get_persistent` / `set_persistent`, which extend
+//! the entry's TTL on every access, so live locks, stakes and trackers are
+//! never archived while in use.
+//!
+//! How far entries are extended comes from the contract's `TtlConfig`, which
+//! the admin can tune per deployment; the `ttl` constants are its defaults.
+
+use soroban_sdk::{contracttype, Address, Env, IntoVal, Symbol, TryFromVal, Val};
 
-use soroban_sdk::{contracttype, Address};
+use super::SharedError;
 
 /// Common instance storage keys
 #[contracttype]
@@ -12,12 +23,18 @@ pub enum CommonInstanceKey {
     Admin,
     /// Owner address (if different from admin)
     Owner,
+    /// Proposed admin awaiting acceptance (see `ownership`)
+    PendingAdmin,
     /// Whether contract is initialized
     Initialized,
-    /// Whether contract is paused
+    /// Pause flags per function group (see `pausable`)
     Paused,
     /// Contract version for upgrades
     Version,
+    /// WASM upgrade waiting for its timelock
+    PendingUpgrade,
+    /// TTL thresholds and extensions (see `TtlConfig`)
+    TtlConfig,
 }
 
 /// Common persistent storage keys
@@ -30,9 +47,13 @@ pub enum CommonPersistentKey {
     TokenData(Address),
     /// Nonce for replay protection
     Nonce(Address),
+    /// ed25519 key an account signs relayed payloads with (see `signature`)
+    SigningKey(Address),
+    /// Whether an account holds a role (see `access_control`)
+    Role(Symbol, Address),
 }
 
-/// TTL constants for storage management
+/// Default TTL values for storage management (see `TtlConfig`)
 pub mod ttl {
     /// Threshold to trigger TTL extension for instance storage
     pub const INSTANCE_TTL_THRESHOLD: u32 = 100;
@@ -50,24 +71,191 @@ pub mod ttl {
     pub const TEMPORARY_TTL_EXTEND: u32 = 1_000;
 }
 
+/// Ledger lifetimes used when extending storage, in ledgers. An entry is
+/// extended to `*_extend` once its TTL drops below `*_threshold`.
+#[contracttype]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TtlConfig {
+    pub instance_threshold: u32,
+    pub instance_extend: u32,
+    pub persistent_threshold: u32,
+    pub persistent_extend: u32,
+}
+
+impl Default for TtlConfig {
+    fn default() -> Self {
+        Self {
+            instance_threshold: ttl::INSTANCE_TTL_THRESHOLD,
+            instance_extend: ttl::INSTANCE_TTL_EXTEND,
+            persistent_threshold: ttl::PERSISTENT_TTL_THRESHOLD,
+            persistent_extend: ttl::PERSISTENT_TTL_EXTEND,
+        }
+    }
+}
+
+impl TtlConfig {
+    /// Extensions are non-zero and not below their thresholds
+    pub fn is_valid(&self) -> bool {
+        self.instance_extend > 0
+            && self.persistent_extend > 0
+            && self.instance_threshold <= self.instance_extend
+            && self.persistent_threshold <= self.persistent_extend
+    }
+}
+
+/// TTL config of the current contract (defaults until one is set)
+pub fn get_ttl_config(env: &Env) -> TtlConfig {
+    env.storage()
+        .instance()
+        .get(&CommonInstanceKey::TtlConfig)
+        .unwrap_or_default()
+}
+
+/// Store the current contract's TTL config. The caller checks admin auth.
+/// Fails with `InvalidAmount` for an invalid config and `AmountExceedsMax`
+/// if an extension is above the network's maximum entry TTL.
+pub fn set_ttl_config(env: &Env, config: &TtlConfig) -> Result<(), SharedError> {
+    if !config.is_valid() {
+        return Err(SharedError::InvalidAmount);
+    }
+    let max_ttl = env.storage().max_ttl();
+    if config.instance_extend > max_ttl || config.persistent_extend > max_ttl {
+        return Err(SharedError::AmountExceedsMax);
+    }
+
+    env.storage()
+        .instance()
+        .set(&CommonInstanceKey::TtlConfig, config);
+    Ok(())
+}
+
 /// Helper to extend instance storage TTL
-pub fn extend_instance_ttl(env: &soroban_sdk::Env) {
+pub fn extend_instance_ttl(env: &Env) {
+    let config = get_ttl_config(env);
     env.storage()
         .instance()
-        .extend_ttl(ttl::INSTANCE_TTL_THRESHOLD, ttl::INSTANCE_TTL_EXTEND);
+        .extend_ttl(config.instance_threshold, config.instance_extend);
 }
 
 /// Helper to extend persistent storage TTL
-pub fn extend_persistent_ttl<
-    K: soroban_sdk::TryFromVal<soroban_sdk::Env, soroban_sdk::Val>
-        + soroban_sdk::IntoVal<soroban_sdk::Env, soroban_sdk::Val>,
->(
-    env: &soroban_sdk::Env,
-    key: &K,
-) {
+pub fn extend_persistent_ttl<K: IntoVal<Env, Val>>(env: &Env, key: &K) {
+    let config = get_ttl_config(env);
     env.storage().persistent().extend_ttl(
         key,
-        ttl::PERSISTENT_TTL_THRESHOLD,
-        ttl::PERSISTENT_TTL_EXTEND,
+        config.persistent_threshold,
+        config.persistent_extend,
     );
 }
+
+/// Read a persistent entry, extending its TTL if it exists
+pub fn get_persistent<K, V>(env: &Env, key: &K) -> Option<V>
+where
+    K: IntoVal<Env, Val>,
+    V: TryFromVal<Env, Val>,
+{
+    let value = env.storage().persistent().get(key);
+    if value.is_some() {
+        extend_persistent_ttl(env, key);
+    }
+    value
+}
+
+/// Write a persistent entry and extend its TTL
+pub fn set_persistent<K, V>(env: &Env, key: &K, value: &V)
+where
+    K: IntoVal<Env, Val>,
+    V: IntoVal<Env, Val>,
+{
+    env.storage().persistent().set(key, value);
+    extend_persistent_ttl(env, key);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use soroban_sdk::testutils::{
+        storage::{Instance as _, Persistent as _},
+        Address as _, Ledger as _,
+    };
+    use soroban_sdk::{contract, contractimpl};
+
+    #[contract]
+    pub struct Storage;
+
+    #[contractimpl]
+    impl Storage {}
+
+    #[test]
+    fn test_persistent_accessors_extend_ttl() {
+        let env = Env::default();
+        let contract_id = env.register(Storage, ());
+
+        env.as_contract(&contract_id, || {
+            let key = CommonPersistentKey::Nonce(Address::generate(&env));
+            assert_eq!(get_persistent::<_, u64>(&env, &key), None);
+
+            // A fresh entry already lives past the threshold
+            set_persistent(&env, &key, &7u64);
+            let written_ttl = env.storage().persistent().get_ttl(&key);
+            assert!(written_ttl >= ttl::PERSISTENT_TTL_THRESHOLD);
+
+            // A read once the TTL drops below the threshold pushes it back
+            // out (the instance is extended too so the contract outlives the
+            // jump)
+            env.storage()
+                .instance()
+                .extend_ttl(ttl::PERSISTENT_TTL_EXTEND, ttl::PERSISTENT_TTL_EXTEND);
+            let start = env.ledger().sequence();
+            env.ledger()
+                .set_sequence_number(start + written_ttl - ttl::PERSISTENT_TTL_THRESHOLD / 2);
+            assert_eq!(get_persistent::<_, u64>(&env, &key), Some(7));
+            assert_eq!(
+                env.storage().persistent().get_ttl(&key),
+                ttl::PERSISTENT_TTL_EXTEND
+            );
+        });
+    }
+
+    #[test]
+    fn test_ttl_config() {
+        let env = Env::default();
+        let contract_id = env.register(Storage, ());
+
+        env.as_contract(&contract_id, || {
+            assert_eq!(get_ttl_config(&env), TtlConfig::default());
+
+            let config = TtlConfig {
+                instance_threshold: 5_000,
+                instance_extend: 50_000,
+                persistent_threshold: 10_000,
+                persistent_extend: 300_000,
+            };
+            let inverted = TtlConfig {
+                instance_threshold: 60_000,
+                ..config
+            };
+            assert_eq!(
+                set_ttl_config(&env, &inverted),
+                Err(SharedError::InvalidAmount)
+            );
+            let too_long = TtlConfig {
+                persistent_extend: u32::MAX,
+                ..config
+            };
+            assert_eq!(
+                set_ttl_config(&env, &too_long),
+                Err(SharedError::AmountExceedsMax)
+            );
+
+            set_ttl_config(&env, &config).unwrap();
+            assert_eq!(get_ttl_config(&env), config);
+
+            extend_instance_ttl(&env);
+            assert_eq!(env.storage().instance().get_ttl(), 50_000);
+
+            let key = CommonPersistentKey::Nonce(Address::generate(&env));
+            set_persistent(&env, &key, &1u64);
+            assert_eq!(env.storage().persistent().get_ttl(&key), 300_000);
+        });
+    }
+}
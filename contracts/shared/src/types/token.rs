@@ -2,7 +2,7 @@
 //!
 //! Types related to tokens in the Astro ecosystem.
 
-use soroban_sdk::{contracttype, Address, Map, String};
+use soroban_sdk::{contracttype, Address, Map, String, Vec};
 
 /// Token metadata shared between projects
 #[contracttype]
@@ -37,6 +37,19 @@ pub enum TokenLifecycle {
     Deprecated = 4,
 }
 
+/// Listing of a token in the token registry
+#[contracttype]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[repr(u32)]
+pub enum TokenStatus {
+    /// Not listed; accepted unless the registry is in allowlist-only mode
+    Unlisted = 0,
+    /// Allowlisted (known good token)
+    Allowed = 1,
+    /// Denylisted (e.g. known scam token), never accepted
+    Denied = 2,
+}
+
 /// Graduation information
 #[contracttype]
 #[derive(Clone, Debug)]
@@ -59,6 +72,55 @@ pub struct GraduationInfo {
     pub destination: TokenLifecycle,
 }
 
+/// Graduation information with full provenance.
+///
+/// Superset of `GraduationInfo`, kept as a separate type so existing
+/// consumers of `GraduationInfo` keep decoding unchanged.
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct GraduationInfoV2 {
+    /// Token address
+    pub token: Address,
+    /// AMM pair address
+    pub pair_address: Address,
+    /// Staking pool ID (if created)
+    pub staking_pool_id: u32,
+    /// Initial price at graduation
+    pub initial_price: i128,
+    /// Graduation timestamp
+    pub graduation_time: u64,
+    /// XLM locked in pool
+    pub xlm_locked: i128,
+    /// Tokens locked in pool
+    pub tokens_locked: i128,
+    /// Destination (internal or DEX)
+    pub destination: TokenLifecycle,
+    /// Liquidity locker lock ID of the permanently locked LP
+    pub lp_lock_id: u64,
+    /// Fee distributor the pair's fees are routed to
+    pub fee_distributor: Address,
+    /// Pair used to convert the token's fees into the reward token, if any
+    pub fee_route_pair: Option<Address>,
+    /// Router path seeded at graduation (token -> ... -> quote)
+    pub router_path: Vec<Address>,
+}
+
+impl GraduationInfoV2 {
+    /// The `GraduationInfo` subset, for consumers of the original type
+    pub fn to_v1(&self) -> GraduationInfo {
+        GraduationInfo {
+            token: self.token.clone(),
+            pair_address: self.pair_address.clone(),
+            staking_pool_id: self.staking_pool_id,
+            initial_price: self.initial_price,
+            graduation_time: self.graduation_time,
+            xlm_locked: self.xlm_locked,
+            tokens_locked: self.tokens_locked,
+            destination: self.destination,
+        }
+    }
+}
+
 /// Distribution result after fee split
 #[contracttype]
 #[derive(Clone, Debug)]
@@ -73,6 +135,10 @@ pub struct DistributionResult {
     pub staking_amount: i128,
     /// Amount burned
     pub burn_amount: i128,
+    /// Amount sent to other recipients
+    pub other_amount: i128,
+    /// Amount sent to the insurance fund
+    pub insurance_amount: i128,
     /// Timestamp of distribution
     pub timestamp: u64,
 }
@@ -81,7 +147,7 @@ pub struct DistributionResult {
 #[contracttype]
 #[derive(Clone, Debug)]
 pub struct UserStake {
-    /// Amount staked
+    /// Stake shares (1:1 with tokens unless the pool balance has drifted)
     pub amount: i128,
     /// Timestamp when staked
     pub stake_time: u64,
@@ -139,6 +205,31 @@ impl LockInfo {
     }
 }
 
+/// Lock holding several tokens that unlock together (e.g. both sides of a
+/// withdrawn LP position)
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct BundleLockInfo {
+    /// Unique lock ID (shared with `LockInfo` IDs)
+    pub id: u64,
+    /// Lock owner
+    pub owner: Address,
+    /// Locked tokens and amounts
+    pub assets: Vec<(Address, i128)>,
+    /// When locked
+    pub lock_time: u64,
+    /// When can unlock
+    pub unlock_time: u64,
+    /// Whether already unlocked
+    pub unlocked: bool,
+}
+
+impl BundleLockInfo {
+    pub fn is_unlockable(&self, current_time: u64) -> bool {
+        !self.unlocked && current_time >= self.unlock_time
+    }
+}
+
 /// Pending reward for a user
 #[contracttype]
 #[derive(Clone, Debug)]
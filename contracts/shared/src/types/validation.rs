@@ -0,0 +1,104 @@
+//! # Input Validation
+//!
+//! Checks on caller-supplied metadata and address lists, shared so every
+//! contract rejects the same inputs.
+
+use soroban_sdk::Address;
+
+use super::{SharedError, TokenMetadata};
+
+/// Maximum token decimals
+pub const MAX_DECIMALS: u32 = 18;
+
+/// Maximum length of a token name
+pub const MAX_NAME_LENGTH: u32 = 32;
+
+/// Maximum length of a token symbol (the Stellar asset code limit)
+pub const MAX_SYMBOL_LENGTH: u32 = 12;
+
+/// Fail with `InvalidInitParams` unless the name and symbol are non-empty
+/// and within their length limits and decimals are at most `MAX_DECIMALS`
+pub fn validate_metadata(metadata: &TokenMetadata) -> Result<(), SharedError> {
+    if metadata.name.is_empty() || metadata.name.len() > MAX_NAME_LENGTH {
+        return Err(SharedError::InvalidInitParams);
+    }
+    if metadata.symbol.is_empty() || metadata.symbol.len() > MAX_SYMBOL_LENGTH {
+        return Err(SharedError::InvalidInitParams);
+    }
+    if metadata.decimals > MAX_DECIMALS {
+        return Err(SharedError::InvalidInitParams);
+    }
+    Ok(())
+}
+
+/// Fail with `InvalidAddress` if any address appears twice
+pub fn require_distinct(addresses: &[Address]) -> Result<(), SharedError> {
+    for (i, address) in addresses.iter().enumerate() {
+        if addresses[i + 1..].contains(address) {
+            return Err(SharedError::InvalidAddress);
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use soroban_sdk::{testutils::Address as _, Env, String};
+
+    fn metadata(env: &Env, name: &str, symbol: &str, decimals: u32) -> TokenMetadata {
+        TokenMetadata {
+            name: String::from_str(env, name),
+            symbol: String::from_str(env, symbol),
+            decimals,
+            creator: Address::generate(env),
+            total_supply: 1_000,
+        }
+    }
+
+    #[test]
+    fn test_validate_metadata() {
+        let env = Env::default();
+
+        assert_eq!(
+            validate_metadata(&metadata(&env, "Astro Shiba", "ASHIB", 7)),
+            Ok(())
+        );
+        assert_eq!(
+            validate_metadata(&metadata(&env, "Astro", "ASTROSHIBA12", 18)),
+            Ok(())
+        );
+
+        for invalid in [
+            metadata(&env, "", "ASHIB", 7),
+            metadata(&env, "Astro Shiba", "", 7),
+            metadata(&env, "Astro Shiba", "ASTROSHIBA123", 7),
+            metadata(&env, "Astro Shiba Inu Token Of The Stars", "ASHIB", 7),
+            metadata(&env, "Astro Shiba", "ASHIB", 19),
+        ] {
+            assert_eq!(
+                validate_metadata(&invalid),
+                Err(SharedError::InvalidInitParams)
+            );
+        }
+    }
+
+    #[test]
+    fn test_require_distinct() {
+        let env = Env::default();
+        let a = Address::generate(&env);
+        let b = Address::generate(&env);
+        let c = Address::generate(&env);
+
+        assert_eq!(require_distinct(&[]), Ok(()));
+        assert_eq!(require_distinct(&[a.clone(), b.clone(), c.clone()]), Ok(()));
+        assert_eq!(
+            require_distinct(&[a.clone(), b.clone(), a.clone()]),
+            Err(SharedError::InvalidAddress)
+        );
+        assert_eq!(
+            require_distinct(&[a, c.clone(), c]),
+            Err(SharedError::InvalidAddress)
+        );
+    }
+}
@@ -0,0 +1,231 @@
+//! # Upgrade Module
+//!
+//! Timelocked WASM upgrades and versioned state migrations, shared by every
+//! contract so upgrades look the same across the protocol.
+//!
+//! ## Flow
+//! 1. `queue_upgrade(new_wasm_hash)` - admin queues the new code behind
+//!    `UPGRADE_TIMELOCK`
+//! 2. `upgrade(new_wasm_hash)` - admin swaps the code once the timelock has
+//!    elapsed; the hash must match the queued one
+//! 3. `migrate()` - admin runs the new code's storage migration (a separate
+//!    transaction: the new code only runs from the next invocation on)
+//!
+//! ## Usage
+//! ```rust,ignore
+//! use astro_core_shared::upgrade;
+//!
+//! /// Storage layout version of this code
+//! const STORAGE_VERSION: u32 = 2;
+//!
+//! pub fn upgrade(env: Env, new_wasm_hash: BytesN<32>) -> Result<(), SharedError> {
+//!     Self::require_admin(&env)?;
+//!     upgrade::execute(&env, new_wasm_hash)
+//! }
+//!
+//! pub fn migrate(env: Env) -> Result<u32, SharedError> {
+//!     Self::require_admin(&env)?;
+//!     upgrade::migrate(&env, STORAGE_VERSION, |env, from| {
+//!         if from < 2 {
+//!             // rewrite v1 entries ...
+//!         }
+//!         Ok(())
+//!     })
+//! }
+//! ```
+
+use soroban_sdk::{contracttype, BytesN, Env};
+
+use crate::events::EventBuilder;
+use crate::types::{CommonInstanceKey, SharedError};
+
+/// Delay between queuing an upgrade and executing it (2 days)
+pub const UPGRADE_TIMELOCK: u64 = 2 * 24 * 60 * 60;
+
+/// Version assumed for contracts deployed before versions were recorded
+pub const INITIAL_VERSION: u32 = 1;
+
+/// A WASM upgrade waiting for its timelock
+#[contracttype]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PendingUpgrade {
+    /// Hash of the uploaded WASM to switch to
+    pub wasm_hash: BytesN<32>,
+    /// Earliest execution timestamp
+    pub eta: u64,
+}
+
+/// Record the storage version written by `initialize`
+pub fn init_version(env: &Env, version: u32) {
+    env.storage()
+        .instance()
+        .set(&CommonInstanceKey::Version, &version);
+}
+
+/// Storage version of the contract's state
+pub fn version(env: &Env) -> u32 {
+    env.storage()
+        .instance()
+        .get(&CommonInstanceKey::Version)
+        .unwrap_or(INITIAL_VERSION)
+}
+
+/// Queued upgrade, if any
+pub fn pending(env: &Env) -> Option<PendingUpgrade> {
+    env.storage()
+        .instance()
+        .get(&CommonInstanceKey::PendingUpgrade)
+}
+
+/// Queue `wasm_hash` behind the timelock, replacing any queued upgrade.
+/// Returns the earliest execution timestamp. The caller checks auth.
+pub fn queue(env: &Env, wasm_hash: BytesN<32>) -> Result<u64, SharedError> {
+    let eta = env
+        .ledger()
+        .timestamp()
+        .checked_add(UPGRADE_TIMELOCK)
+        .ok_or(SharedError::Overflow)?;
+    let upgrade = PendingUpgrade {
+        wasm_hash: wasm_hash.clone(),
+        eta,
+    };
+    env.storage()
+        .instance()
+        .set(&CommonInstanceKey::PendingUpgrade, &upgrade);
+
+    let events = EventBuilder::new(env);
+    events.publish(
+        "upgrade",
+        "queued",
+        (wasm_hash, eta, env.ledger().timestamp()),
+    );
+
+    Ok(eta)
+}
+
+/// Drop the queued upgrade. The caller checks auth.
+pub fn cancel(env: &Env) -> Result<(), SharedError> {
+    let upgrade = pending(env).ok_or(SharedError::InvalidState)?;
+    env.storage()
+        .instance()
+        .remove(&CommonInstanceKey::PendingUpgrade);
+
+    let events = EventBuilder::new(env);
+    events.publish(
+        "upgrade",
+        "cancelled",
+        (upgrade.wasm_hash, env.ledger().timestamp()),
+    );
+
+    Ok(())
+}
+
+/// Switch to the queued `wasm_hash` once its timelock has elapsed.
+/// The caller checks auth.
+pub fn execute(env: &Env, wasm_hash: BytesN<32>) -> Result<(), SharedError> {
+    let upgrade = pending(env).ok_or(SharedError::InvalidState)?;
+    if upgrade.wasm_hash != wasm_hash {
+        return Err(SharedError::InvalidState);
+    }
+    if env.ledger().timestamp() < upgrade.eta {
+        return Err(SharedError::CooldownNotElapsed);
+    }
+
+    env.storage()
+        .instance()
+        .remove(&CommonInstanceKey::PendingUpgrade);
+
+    let events = EventBuilder::new(env);
+    events.publish(
+        "upgrade",
+        "executed",
+        (wasm_hash.clone(), version(env), env.ledger().timestamp()),
+    );
+
+    env.deployer().update_current_contract_wasm(wasm_hash);
+    Ok(())
+}
+
+/// Run `migration(env, from_version)` if the stored version is below
+/// `target`, then record `target`. Returns the new version.
+/// The caller checks auth.
+pub fn migrate<F>(env: &Env, target: u32, migration: F) -> Result<u32, SharedError>
+where
+    F: FnOnce(&Env, u32) -> Result<(), SharedError>,
+{
+    let from = version(env);
+    if from >= target {
+        return Err(SharedError::AlreadyExecuted);
+    }
+
+    migration(env, from)?;
+    env.storage()
+        .instance()
+        .set(&CommonInstanceKey::Version, &target);
+
+    let events = EventBuilder::new(env);
+    events.publish(
+        "upgrade",
+        "migrated",
+        (from, target, env.ledger().timestamp()),
+    );
+
+    Ok(target)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use soroban_sdk::testutils::Ledger as _;
+    use soroban_sdk::{contract, contractimpl};
+
+    #[contract]
+    pub struct Upgradable;
+
+    #[contractimpl]
+    impl Upgradable {}
+
+    #[test]
+    fn test_queue_and_migrate() {
+        let env = Env::default();
+        let contract_id = env.register(Upgradable, ());
+
+        env.as_contract(&contract_id, || {
+            assert_eq!(version(&env), INITIAL_VERSION);
+
+            let hash = BytesN::from_array(&env, &[7; 32]);
+            let eta = queue(&env, hash.clone()).unwrap();
+            assert_eq!(eta, UPGRADE_TIMELOCK);
+            assert_eq!(pending(&env).unwrap().wasm_hash, hash);
+
+            // Timelock and hash are enforced
+            assert_eq!(
+                execute(&env, hash.clone()),
+                Err(SharedError::CooldownNotElapsed)
+            );
+            env.ledger().set_timestamp(eta);
+            let other = BytesN::from_array(&env, &[8; 32]);
+            assert_eq!(execute(&env, other), Err(SharedError::InvalidState));
+
+            cancel(&env).unwrap();
+            assert!(pending(&env).is_none());
+            assert_eq!(cancel(&env), Err(SharedError::InvalidState));
+
+            // Migrations run once per version
+            let mut seen = 0;
+            assert_eq!(
+                migrate(&env, 2, |_, from| {
+                    seen = from;
+                    Ok(())
+                }),
+                Ok(2)
+            );
+            assert_eq!(seen, 1);
+            assert_eq!(version(&env), 2);
+            assert_eq!(
+                migrate(&env, 2, |_, _| Ok(())),
+                Err(SharedError::AlreadyExecuted)
+            );
+        });
+    }
+}
@@ -12,11 +12,75 @@
 //! - Time-weighted reward distribution
 
 use astro_core_shared::{
-    events::{emit_claim, emit_stake, emit_unstake, EventBuilder},
-    math::{safe_add, safe_div, safe_mul, safe_sub, PRECISION},
-    types::{extend_instance_ttl, SharedError, StakingConfig, UserStake},
+    access_control::{self, CONFIG_ADMIN, KEEPER, PAUSER, TREASURER, UPGRADER},
+    events::{
+        emit_admin_changed, emit_claim, emit_config_updated, emit_fee_distributor_changed,
+        emit_penalty_routed, emit_stake, emit_treasury_changed, emit_unstake, set_compact_mask,
+        EventBuilder,
+    },
+    interfaces::{FeeDistributorClient, IntoSharedResult, TokenRegistryClient},
+    limits::{MIN_FEE_AMOUNT, MIN_STAKE_AMOUNT},
+    math::{
+        annualized_rate_bps, apply_bps, mul_div_down, mul_div_up, safe_add, safe_div, safe_mul,
+        safe_sub, BPS_DENOMINATOR, PRECISION, SECONDS_PER_YEAR,
+    },
+    ownership,
+    pausable::{self, PauseConfig, PauseGroup},
+    signature,
+    types::{
+        check_optional_deadline, extend_instance_ttl, get_persistent, get_ttl_config,
+        set_persistent, set_ttl_config, Bps, ContractInfo, PenaltyRouting, SharedError,
+        StakingConfig, TokenSolvency, TtlConfig, UserStake,
+    },
+    upgrade::{self, PendingUpgrade},
 };
-use soroban_sdk::{contract, contractimpl, contracttype, token, Address, Env, Vec};
+use soroban_sdk::{
+    auth::{ContractContext, InvokerContractAuthEntry, SubContractInvocation},
+    contract, contractimpl, contracttype, token, vec, Address, BytesN, Env, IntoVal, Symbol, Vec,
+};
+
+// ════════════════════════════════════════════════════════════════════════════
+// Constants
+// ════════════════════════════════════════════════════════════════════════════
+
+/// Storage layout version of this code (see `migrate`)
+const STORAGE_VERSION: u32 = 2;
+
+/// Default cap on registered reward tokens (bounds harvest iteration cost)
+const DEFAULT_MAX_REWARD_TOKENS: u32 = 10;
+
+/// Residual liability tolerated when removing a reward token.
+/// Reward-per-share math floors per user, so a few stroops per distribution
+/// can never be claimed.
+const MAX_REWARD_DUST: i128 = 10_000;
+
+/// Maximum stakers returned by one `get_stakers_page` call
+const MAX_PAGE_SIZE: u32 = 100;
+
+/// Maximum concurrent vote locks per user
+const MAX_VOTE_LOCKS: u32 = 20;
+
+/// Trailing window `get_apr` measures reward-per-share growth over (30 days)
+const APR_WINDOW: u64 = 30 * 24 * 60 * 60;
+
+/// Longest reward vesting period (1 year)
+const MAX_REWARD_VESTING_PERIOD: u64 = 365 * 24 * 60 * 60;
+
+/// Largest haircut on early claims of vesting rewards (50%)
+const MAX_EARLY_CLAIM_HAIRCUT: Bps = Bps::from_const(5000);
+
+/// Longest early-exit window (1 year)
+const MAX_EARLY_EXIT_WINDOW: u64 = 365 * 24 * 60 * 60;
+
+/// Largest early-exit penalty (50%)
+const MAX_EARLY_EXIT_PENALTY: Bps = Bps::from_const(5000);
+
+/// Virtual shares and stake tokens added to both sides of every share
+/// conversion. Shares stay 1:1 with tokens until the pool gains value, but
+/// a first staker can no longer mint a single share and donate stake tokens
+/// to round later deposits down to zero: the donation is mostly credited to
+/// the virtual shares.
+const VIRTUAL_OFFSET: i128 = 1_000;
 
 // ════════════════════════════════════════════════════════════════════════════
 // Storage Keys
@@ -31,7 +95,7 @@ pub enum DataKey {
     Config,
     /// Whether contract is initialized
     Initialized,
-    /// Whether contract is paused
+    /// Legacy pause flag (storage version 1, see `migrate`)
     Paused,
     /// Token being staked
     StakeToken,
@@ -47,6 +111,116 @@ pub enum DataKey {
     RewardTokens,
     /// Fee distributor address
     FeeDistributor,
+    /// Reward tokens the admin has approved for registration
+    AllowedRewardTokens,
+    /// Maximum number of registered reward tokens
+    MaxRewardTokens,
+    /// Rewards added but not yet claimed for a token (Address -> i128)
+    RewardLiability(Address),
+    /// User stake checkpoint by index ((Address, u32) -> Checkpoint)
+    UserCheckpoint(Address, u32),
+    /// Number of checkpoints written for a user (Address -> u32)
+    UserCheckpointCount(Address),
+    /// Total staked checkpoint by index (u32 -> Checkpoint)
+    TotalCheckpoint(u32),
+    /// Number of total staked checkpoints
+    TotalCheckpointCount,
+    /// Address authorized to slash stakes (besides admin)
+    Slasher,
+    /// Staker registry entry by index (u32 -> Address)
+    StakerAt(u32),
+    /// Registry index of a staker (Address -> u32)
+    StakerIndex(Address),
+    /// Number of addresses with a non-zero stake
+    StakerCount,
+    /// Reward tokens skipped by harvest until unfrozen
+    FrozenRewardTokens,
+    /// Rewards accrued to a user while a token was frozen ((user, token) -> i128)
+    FrozenRewards(Address, Address),
+    /// Governance contract allowed to place vote locks
+    Governor,
+    /// Active vote locks for a user (Address -> Vec<VoteLock>)
+    VoteLocks(Address),
+    /// Protocol registry address (reported by `contract_info`)
+    Registry,
+    /// Token registry consulted before accepting a token (optional)
+    TokenRegistry,
+    /// Seconds after accrual before unclaimed rewards can be forfeited (0 = off)
+    RewardExpiry,
+    /// Reward-per-share history per reward token ((token, index) -> Checkpoint)
+    AccCheckpoint(Address, u32),
+    /// Number of reward-per-share checkpoints per reward token
+    AccCheckpointCount(Address),
+    /// Rewards paid out to a user all-time ((user, token) -> i128)
+    TotalClaimed(Address, Address),
+    /// Reward tokens a user has been paid in (Address -> Vec<Address>)
+    ClaimedTokens(Address),
+    /// Rewards received while nobody was staked (Address -> i128)
+    OrphanedRewards(Address),
+    /// Stake fees the fee distributor rejected, held until the next hand-off
+    PendingFees,
+    /// Seconds harvested rewards vest over before payout (0 = off)
+    RewardVestingPeriod,
+    /// Haircut on unvested rewards claimed early (Bps)
+    EarlyClaimHaircut,
+    /// Harvested rewards still vesting ((user, token) -> RewardVest)
+    RewardVest(Address, Address),
+    /// Treasury receiving its share of early-exit penalties
+    Treasury,
+    /// Fallback recipient of burned penalties when the token can't burn
+    BurnAddress,
+    /// Penalty on unstaking soon after staking (EarlyExit, absent = off)
+    EarlyExit,
+}
+
+// ════════════════════════════════════════════════════════════════════════════
+// Types
+// ════════════════════════════════════════════════════════════════════════════
+
+/// Staked balance (or reward-per-share) as of a point in time
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Checkpoint {
+    /// Ledger timestamp the balance took effect
+    pub timestamp: u64,
+    /// Staked amount from `timestamp` until the next checkpoint
+    pub amount: i128,
+}
+
+/// Harvested rewards vesting linearly until `end`
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RewardVest {
+    /// Rewards not yet paid out
+    pub amount: i128,
+    /// Timestamp vesting of `amount` (re)started
+    pub start: u64,
+    /// Timestamp `amount` is fully vested
+    pub end: u64,
+}
+
+/// Penalty on unstaking within `window` seconds of a user's last stake
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct EarlyExit {
+    /// Seconds after a stake during which unstaking is penalized
+    pub window: u64,
+    /// Share of the payout (after the withdraw fee) taken as penalty
+    pub penalty_bps: Bps,
+    /// Where the penalty goes
+    pub routing: PenaltyRouting,
+}
+
+/// Stake pinned by a governance vote until the proposal concludes
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct VoteLock {
+    /// Proposal the stake voted on
+    pub proposal_id: u64,
+    /// Stake that cannot be unstaked while the lock is active
+    pub amount: i128,
+    /// Timestamp the lock expires
+    pub until: u64,
 }
 
 // ════════════════════════════════════════════════════════════════════════════
@@ -76,9 +250,12 @@ impl StakingPool {
         }
 
         // Validate config
-        if config.min_stake_amount <= 0 {
+        if config.min_stake_amount < MIN_STAKE_AMOUNT {
             return Err(SharedError::InvalidAmount);
         }
+        if !config.is_valid() {
+            return Err(SharedError::InvalidBps);
+        }
 
         // Store initial state
         env.storage().instance().set(&DataKey::Admin, &admin);
@@ -90,7 +267,7 @@ impl StakingPool {
             .set(&DataKey::FeeDistributor, &fee_distributor);
         env.storage().instance().set(&DataKey::Config, &config);
         env.storage().instance().set(&DataKey::Initialized, &true);
-        env.storage().instance().set(&DataKey::Paused, &false);
+        upgrade::init_version(&env, STORAGE_VERSION);
         env.storage().instance().set(&DataKey::TotalStaked, &0_i128);
         env.storage()
             .instance()
@@ -112,187 +289,496 @@ impl StakingPool {
     // Staking Functions
     // ────────────────────────────────────────────────────────────────────────
 
-    /// Stake tokens
-    pub fn stake(env: Env, user: Address, amount: i128) -> Result<i128, SharedError> {
+    /// Stake tokens. The deposit fee is deducted from the amount received
+    /// before crediting shares at the current `convert_to_shares` rate.
+    /// Returns the user's new share balance.
+    /// Fails with `DeadlineExpired` after `deadline`, if given.
+    pub fn stake(
+        env: Env,
+        user: Address,
+        amount: i128,
+        deadline: Option<u64>,
+    ) -> Result<i128, SharedError> {
+        user.require_auth();
+        Self::require_initialized(&env)?;
+        check_optional_deadline(&env, deadline)?;
+        let (_, new_amount) = Self::stake_internal(&env, &user, amount)?;
+        Ok(new_amount)
+    }
+
+    /// Unstake `amount` shares, paid out at the current `convert_to_assets`
+    /// rate. The withdraw fee is deducted from the amount paid out.
+    /// Returns the user's remaining share balance.
+    /// Fails with `DeadlineExpired` after `deadline`, if given.
+    pub fn unstake(
+        env: Env,
+        user: Address,
+        amount: i128,
+        deadline: Option<u64>,
+    ) -> Result<i128, SharedError> {
         user.require_auth();
         Self::require_initialized(&env)?;
-        Self::require_not_paused(&env)?;
+        check_optional_deadline(&env, deadline)?;
+        let (_, remaining) = Self::unstake_internal(&env, &user, amount)?;
+        Ok(remaining)
+    }
 
-        let config: StakingConfig = env
-            .storage()
-            .instance()
-            .get(&DataKey::Config)
-            .ok_or(SharedError::NotInitialized)?;
+    /// Leave the pool at once with the full principal (no withdraw fee),
+    /// forfeiting all pending rewards to the other stakers. Only available
+    /// while `emergency_unlock` is set, and works even while paused. Stake
+    /// pinned by an active vote cannot leave. Returns the tokens paid out.
+    pub fn emergency_unstake(env: Env, user: Address) -> Result<i128, SharedError> {
+        user.require_auth();
+        Self::require_initialized(&env)?;
 
-        if amount < config.min_stake_amount {
-            return Err(SharedError::AmountBelowMin);
+        if !Self::get_config(env.clone())?.emergency_unlock {
+            return Err(SharedError::InvalidState);
         }
 
-        let stake_token: Address = env
-            .storage()
-            .instance()
-            .get(&DataKey::StakeToken)
-            .ok_or(SharedError::NotInitialized)?;
-
-        // Transfer tokens to contract
-        let token_client = token::Client::new(&env, &stake_token);
-        token_client.transfer(&user, &env.current_contract_address(), &amount);
-
-        // Get or create user stake
         let mut user_stake = Self::get_user_stake(&env, &user);
-        let reward_tokens = Self::get_reward_tokens(&env);
-
-        // Harvest pending rewards before updating stake
-        Self::internal_harvest(&env, &user, &mut user_stake, &reward_tokens)?;
+        let shares = user_stake.amount;
+        if shares <= 0 || Self::get_vote_locked_amount(&env, &user) > 0 {
+            return Err(SharedError::InsufficientBalance);
+        }
 
-        // Update user stake
-        let new_amount = safe_add(user_stake.amount, amount)?;
-        user_stake.amount = new_amount;
-        user_stake.stake_time = env.ledger().timestamp();
+        let stake_token = Self::stake_token(env.clone())?;
+        let assets = Self::shares_to_assets(&env, &stake_token, shares)?;
+        let others = safe_sub(Self::get_total_staked(&env), shares)?;
+        let now = env.ledger().timestamp();
 
-        // Update reward debts for all reward tokens
-        // FIX #H1: Use set_reward_debt() to properly track per-token debt
-        for reward_token in reward_tokens.iter() {
+        for reward_token in Self::get_reward_tokens(&env).iter() {
             let acc_per_share = Self::get_acc_reward_per_share(&env, &reward_token);
-            let new_debt = safe_div(safe_mul(new_amount, acc_per_share)?, PRECISION)?;
-            user_stake.set_reward_debt(&reward_token, new_debt);
+            let debt = user_stake.get_reward_debt(&reward_token);
+            let mut forfeited = Self::calculate_pending(&shares, acc_per_share, debt).unwrap_or(0);
+            let carried_key = DataKey::FrozenRewards(user.clone(), reward_token.clone());
+            let carried: i128 = get_persistent(&env, &carried_key).unwrap_or(0);
+            if carried > 0 {
+                forfeited = safe_add(forfeited, carried)?;
+                env.storage().persistent().remove(&carried_key);
+            }
+            user_stake.set_reward_debt(&reward_token, 0);
+            if forfeited <= 0 {
+                continue;
+            }
+
+            if others > 0 {
+                // Fold back into reward-per-share for everyone who stays
+                let bump = safe_div(safe_mul(forfeited, PRECISION)?, others)?;
+                let new_acc = safe_add(acc_per_share, bump)?;
+                set_persistent(
+                    &env,
+                    &DataKey::AccRewardPerShare(reward_token.clone()),
+                    &new_acc,
+                );
+                Self::write_acc_checkpoint(&env, &reward_token, new_acc);
+            } else {
+                // Nobody left to credit; hold as orphaned like `add_rewards`
+                let liability = Self::get_reward_liability(&env, &reward_token);
+                set_persistent(
+                    &env,
+                    &DataKey::RewardLiability(reward_token.clone()),
+                    &safe_sub(liability, forfeited)?,
+                );
+                let orphaned = Self::get_orphaned_rewards(&env, &reward_token);
+                set_persistent(
+                    &env,
+                    &DataKey::OrphanedRewards(reward_token.clone()),
+                    &safe_add(orphaned, forfeited)?,
+                );
+            }
+
+            let events = EventBuilder::new(&env);
+            events.publish(
+                "staking",
+                "rewards_forfeited",
+                (user.clone(), reward_token.clone(), forfeited, now),
+            );
         }
 
-        // Save user stake
-        let user_stake_key = DataKey::UserStake(user.clone());
-        env.storage()
-            .persistent()
-            .set(&user_stake_key, &user_stake);
+        user_stake.amount = 0;
+        set_persistent(&env, &DataKey::UserStake(user.clone()), &user_stake);
+        env.storage().instance().set(&DataKey::TotalStaked, &others);
+        Self::record_checkpoints(&env, &user, 0, others);
+        Self::sync_staker_registry(&env, &user, 0);
 
-        // FIX #M3: Extend TTL for UserStake to prevent expiration
-        env.storage()
-            .persistent()
-            .extend_ttl(&user_stake_key, 200_000, 200_000);
+        token::Client::new(&env, &stake_token).transfer(
+            &env.current_contract_address(),
+            &user,
+            &assets,
+        );
 
-        // Update total staked
-        let total_staked = Self::get_total_staked(&env);
-        let new_total = safe_add(total_staked, amount)?;
-        env.storage()
-            .instance()
-            .set(&DataKey::TotalStaked, &new_total);
+        let events = EventBuilder::new(&env);
+        events.publish("staking", "emergency_unstake", (user, shares, assets, now));
 
-        emit_stake(&env, &user, amount, new_total);
         extend_instance_ttl(&env);
 
-        Ok(new_amount)
+        Ok(assets)
+    }
+
+    /// Claim pending rewards without unstaking. Under reward vesting only
+    /// the vested part is paid; the rest keeps vesting.
+    pub fn claim(env: Env, user: Address) -> Result<Vec<(Address, i128)>, SharedError> {
+        user.require_auth();
+        Self::claim_internal(&env, &user)
+    }
+
+    /// Claim all vesting rewards now. The vested part is paid as by `claim`;
+    /// the unvested remainder is paid minus the early-claim haircut, which is
+    /// credited to the other stakers. Returns the early payouts per token.
+    pub fn claim_vested_early(
+        env: Env,
+        user: Address,
+    ) -> Result<Vec<(Address, i128)>, SharedError> {
+        user.require_auth();
+        Self::claim_internal(&env, &user)?;
+
+        let haircut_bps = Self::get_early_claim_haircut(&env);
+        let frozen = Self::get_frozen_reward_tokens(&env);
+        let this = env.current_contract_address();
+        let mut payouts = Vec::new(&env);
+
+        for reward_token in Self::get_reward_tokens(&env).iter() {
+            if frozen.contains(&reward_token) {
+                continue;
+            }
+            let key = DataKey::RewardVest(user.clone(), reward_token.clone());
+            let vest: RewardVest = match get_persistent(&env, &key) {
+                Some(vest) => vest,
+                None => continue,
+            };
+            env.storage().persistent().remove(&key);
+
+            let haircut = haircut_bps.apply(vest.amount)?;
+            let payout = safe_sub(vest.amount, haircut)?;
+
+            // The whole remainder leaves the user's claim; the haircut comes
+            // back in as new rewards for everyone else
+            let liability = Self::get_reward_liability(&env, &reward_token);
+            set_persistent(
+                &env,
+                &DataKey::RewardLiability(reward_token.clone()),
+                &safe_sub(liability, vest.amount)?,
+            );
+            if haircut > 0 {
+                Self::credit_other_stakers(&env, &user, &reward_token, haircut)?;
+            }
+
+            if payout > 0 {
+                Self::record_claimed(&env, &user, &reward_token, payout)?;
+                token::Client::new(&env, &reward_token).transfer(&this, &user, &payout);
+                emit_claim(&env, &user, &reward_token, payout);
+                payouts.push_back((reward_token.clone(), payout));
+            }
+
+            let events = EventBuilder::new(&env);
+            events.publish(
+                "staking",
+                "vest_claimed_early",
+                (user.clone(), reward_token, payout, haircut),
+            );
+        }
+
+        extend_instance_ttl(&env);
+        Ok(payouts)
+    }
+
+    // ────────────────────────────────────────────────────────────────────────
+    // Vault Interface (ERC-4626 style)
+    // ────────────────────────────────────────────────────────────────────────
+
+    /// Stake tokens held for stakers, excluding rewards owed in the stake token
+    pub fn total_assets(env: Env) -> Result<i128, SharedError> {
+        let stake_token = Self::stake_token(env.clone())?;
+        Self::pool_assets(&env, &stake_token)
     }
 
-    /// Unstake tokens
-    pub fn unstake(env: Env, user: Address, amount: i128) -> Result<i128, SharedError> {
+    /// Stake `assets` tokens; returns the shares minted
+    pub fn deposit(env: Env, user: Address, assets: i128) -> Result<i128, SharedError> {
         user.require_auth();
         Self::require_initialized(&env)?;
-        Self::require_not_paused(&env)?;
+        let (shares, _) = Self::stake_internal(&env, &user, assets)?;
+        Ok(shares)
+    }
 
-        if amount <= 0 {
-            return Err(SharedError::InvalidAmount);
+    /// Stake the tokens needed to mint `shares` (`preview_mint`); returns the
+    /// tokens pulled. Fails with `BelowMinimum` if fewer shares arrive, e.g.
+    /// for fee-on-transfer stake tokens.
+    pub fn mint(env: Env, user: Address, shares: i128) -> Result<i128, SharedError> {
+        user.require_auth();
+        Self::require_initialized(&env)?;
+        let assets = Self::preview_mint(env.clone(), shares)?;
+        let (minted, _) = Self::stake_internal(&env, &user, assets)?;
+        if minted < shares {
+            return Err(SharedError::BelowMinimum);
         }
+        Ok(assets)
+    }
 
-        let mut user_stake = Self::get_user_stake(&env, &user);
+    /// Burn the shares needed to pay out `assets` tokens after the withdraw
+    /// fee (`preview_withdraw`); returns the shares burned
+    pub fn withdraw(env: Env, user: Address, assets: i128) -> Result<i128, SharedError> {
+        user.require_auth();
+        Self::require_initialized(&env)?;
+        let shares = Self::preview_withdraw(env.clone(), assets)?;
+        Self::unstake_internal(&env, &user, shares)?;
+        Ok(shares)
+    }
 
-        if user_stake.amount < amount {
+    /// Burn `shares`; returns the tokens paid out after the withdraw fee
+    pub fn redeem(env: Env, user: Address, shares: i128) -> Result<i128, SharedError> {
+        user.require_auth();
+        Self::require_initialized(&env)?;
+        let (payout, _) = Self::unstake_internal(&env, &user, shares)?;
+        Ok(payout)
+    }
+
+    /// Shares `deposit(assets)` would mint now
+    pub fn preview_deposit(env: Env, assets: i128) -> Result<i128, SharedError> {
+        let config = Self::get_config(env.clone())?;
+        let net = safe_sub(assets, apply_bps(assets, config.deposit_fee_bps)?)?;
+        Self::convert_to_shares(env, net)
+    }
+
+    /// Tokens `mint(shares)` would pull now (rounded up)
+    pub fn preview_mint(env: Env, shares: i128) -> Result<i128, SharedError> {
+        let config = Self::get_config(env.clone())?;
+        let stake_token = Self::stake_token(env.clone())?;
+        let net = Self::shares_to_assets_up(&env, &stake_token, shares)?;
+        Self::gross_up(net, config.deposit_fee_bps)
+    }
+
+    /// Shares `withdraw(assets)` would burn now (rounded up)
+    pub fn preview_withdraw(env: Env, assets: i128) -> Result<i128, SharedError> {
+        let config = Self::get_config(env.clone())?;
+        let gross = Self::gross_up(assets, config.withdraw_fee_bps)?;
+        let stake_token = Self::stake_token(env.clone())?;
+        let (total_shares, pool) = Self::share_price(&env, &stake_token)?;
+        mul_div_up(gross, total_shares, pool)
+    }
+
+    /// Tokens `redeem(shares)` would pay out now
+    pub fn preview_redeem(env: Env, shares: i128) -> Result<i128, SharedError> {
+        let config = Self::get_config(env.clone())?;
+        let assets = Self::convert_to_assets(env, shares)?;
+        safe_sub(assets, apply_bps(assets, config.withdraw_fee_bps)?)
+    }
+
+    // ────────────────────────────────────────────────────────────────────────
+    // Relayed Transactions
+    // ────────────────────────────────────────────────────────────────────────
+
+    /// Register (or rotate) the ed25519 key `user` signs relayed calls with
+    pub fn set_signing_key(env: Env, user: Address, key: BytesN<32>) -> Result<(), SharedError> {
+        user.require_auth();
+        signature::set_signing_key(&env, &user, &key);
+        Ok(())
+    }
+
+    /// `claim` submitted by a sponsor, authorized by `user`'s signature over
+    /// `claim(user)` and its next nonce
+    pub fn claim_with_sig(
+        env: Env,
+        user: Address,
+        nonce: u64,
+        sig: BytesN<64>,
+    ) -> Result<Vec<(Address, i128)>, SharedError> {
+        signature::consume(
+            &env,
+            &user,
+            &Symbol::new(&env, "claim"),
+            (user.clone(),),
+            nonce,
+            &sig,
+        )?;
+        Self::claim_internal(&env, &user)
+    }
+
+    // ────────────────────────────────────────────────────────────────────────
+    // Slashing
+    // ────────────────────────────────────────────────────────────────────────
+
+    /// Confiscate `bps` of a user's stake and send it to `recipient`.
+    /// Returns the slashed shares.
+    /// Callable by the admin or the authorized slasher when slashing is enabled.
+    /// Pending rewards are harvested to the user first; they are not slashed.
+    pub fn slash(
+        env: Env,
+        caller: Address,
+        user: Address,
+        bps: u32,
+        recipient: Address,
+    ) -> Result<i128, SharedError> {
+        caller.require_auth();
+        Self::require_initialized(&env)?;
+
+        let config: StakingConfig = env
+            .storage()
+            .instance()
+            .get(&DataKey::Config)
+            .ok_or(SharedError::NotInitialized)?;
+        if !config.slashing_enabled {
+            return Err(SharedError::InvalidState);
+        }
+
+        let admin: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::Admin)
+            .ok_or(SharedError::NotInitialized)?;
+        let slasher: Option<Address> = env.storage().instance().get(&DataKey::Slasher);
+        if caller != admin && Some(caller.clone()) != slasher {
+            return Err(SharedError::Unauthorized);
+        }
+
+        if bps == 0 || bps as i128 > BPS_DENOMINATOR {
+            return Err(SharedError::InvalidBps);
+        }
+
+        let mut user_stake = Self::get_user_stake(&env, &user);
+        let slashed = apply_bps(user_stake.amount, bps)?;
+        if slashed <= 0 {
             return Err(SharedError::InsufficientBalance);
         }
 
         let reward_tokens = Self::get_reward_tokens(&env);
-
-        // Harvest pending rewards before updating stake
         Self::internal_harvest(&env, &user, &mut user_stake, &reward_tokens)?;
 
-        // Update user stake
-        let remaining = safe_sub(user_stake.amount, amount)?;
-        user_stake.amount = remaining;
+        let stake_token: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::StakeToken)
+            .ok_or(SharedError::NotInitialized)?;
+        let slashed_assets = Self::shares_to_assets(&env, &stake_token, slashed)?;
 
-        // Update reward debts
-        // FIX #H1: Use set_reward_debt() to properly track per-token debt
+        let remaining = safe_sub(user_stake.amount, slashed)?;
+        user_stake.amount = remaining;
         for reward_token in reward_tokens.iter() {
             let acc_per_share = Self::get_acc_reward_per_share(&env, &reward_token);
             let new_debt = safe_div(safe_mul(remaining, acc_per_share)?, PRECISION)?;
             user_stake.set_reward_debt(&reward_token, new_debt);
         }
 
-        // Save user stake
         let user_stake_key = DataKey::UserStake(user.clone());
-        env.storage()
-            .persistent()
-            .set(&user_stake_key, &user_stake);
+        set_persistent(&env, &user_stake_key, &user_stake);
 
-        // FIX #M3: Extend TTL for UserStake to prevent expiration
-        env.storage()
-            .persistent()
-            .extend_ttl(&user_stake_key, 200_000, 200_000);
-
-        // Update total staked
-        let total_staked = Self::get_total_staked(&env);
-        let new_total = safe_sub(total_staked, amount)?;
+        let new_total = safe_sub(Self::get_total_staked(&env), slashed)?;
         env.storage()
             .instance()
             .set(&DataKey::TotalStaked, &new_total);
 
-        // Transfer tokens back to user
-        let stake_token: Address = env
-            .storage()
-            .instance()
-            .get(&DataKey::StakeToken)
-            .ok_or(SharedError::NotInitialized)?;
-        let token_client = token::Client::new(&env, &stake_token);
-        token_client.transfer(&env.current_contract_address(), &user, &amount);
+        Self::record_checkpoints(&env, &user, remaining, new_total);
+        Self::sync_staker_registry(&env, &user, remaining);
+
+        token::Client::new(&env, &stake_token).transfer(
+            &env.current_contract_address(),
+            &recipient,
+            &slashed_assets,
+        );
+
+        let events = EventBuilder::new(&env);
+        events.publish(
+            "staking",
+            "slashed",
+            (
+                user,
+                caller,
+                recipient,
+                slashed,
+                remaining,
+                env.ledger().timestamp(),
+            ),
+        );
 
-        emit_unstake(&env, &user, amount, remaining);
         extend_instance_ttl(&env);
 
-        Ok(remaining)
+        Ok(slashed)
     }
 
-    /// Claim pending rewards without unstaking
-    pub fn claim(env: Env, user: Address) -> Result<Vec<(Address, i128)>, SharedError> {
-        user.require_auth();
+    // ────────────────────────────────────────────────────────────────────────
+    // Governance
+    // ────────────────────────────────────────────────────────────────────────
+
+    /// Pin a user's current stake until `until` because it voted on a proposal,
+    /// preventing vote-then-exit. Only callable by the governor.
+    /// Re-locking the same proposal keeps the larger amount and later expiry.
+    pub fn lock_stake_for_vote(
+        env: Env,
+        user: Address,
+        proposal_id: u64,
+        until: u64,
+    ) -> Result<i128, SharedError> {
         Self::require_initialized(&env)?;
-        Self::require_not_paused(&env)?;
 
-        let mut user_stake = Self::get_user_stake(&env, &user);
-        let reward_tokens = Self::get_reward_tokens(&env);
+        let governor: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::Governor)
+            .ok_or(SharedError::ExternalContractNotSet)?;
+        governor.require_auth();
 
-        let rewards = Self::internal_harvest(&env, &user, &mut user_stake, &reward_tokens)?;
+        let now = env.ledger().timestamp();
+        if until <= now {
+            return Err(SharedError::InvalidTimestamp);
+        }
 
-        // Update reward debt
-        // FIX #H1: Use set_reward_debt() to properly track per-token debt
-        for reward_token in reward_tokens.iter() {
-            let acc_per_share = Self::get_acc_reward_per_share(&env, &reward_token);
-            let new_debt = safe_div(safe_mul(user_stake.amount, acc_per_share)?, PRECISION)?;
-            user_stake.set_reward_debt(&reward_token, new_debt);
+        let amount = Self::get_user_stake(&env, &user).amount;
+        if amount <= 0 {
+            return Err(SharedError::InsufficientBalance);
         }
 
-        let user_stake_key = DataKey::UserStake(user.clone());
-        env.storage()
-            .persistent()
-            .set(&user_stake_key, &user_stake);
+        // Drop expired locks, then merge or append
+        let mut locks = Vec::new(&env);
+        let mut merged = false;
+        for mut lock in Self::load_vote_locks(&env, &user).iter() {
+            if lock.until <= now {
+                continue;
+            }
+            if lock.proposal_id == proposal_id {
+                lock.amount = lock.amount.max(amount);
+                lock.until = lock.until.max(until);
+                merged = true;
+            }
+            locks.push_back(lock);
+        }
+        if !merged {
+            if locks.len() >= MAX_VOTE_LOCKS {
+                return Err(SharedError::LimitExceeded);
+            }
+            locks.push_back(VoteLock {
+                proposal_id,
+                amount,
+                until,
+            });
+        }
 
-        // FIX #M3: Extend TTL for UserStake to prevent expiration
-        env.storage()
-            .persistent()
-            .extend_ttl(&user_stake_key, 200_000, 200_000);
+        let key = DataKey::VoteLocks(user.clone());
+        set_persistent(&env, &key, &locks);
+
+        let events = EventBuilder::new(&env);
+        events.publish(
+            "staking",
+            "vote_locked",
+            (user, proposal_id, amount, until, now),
+        );
 
         extend_instance_ttl(&env);
 
-        Ok(rewards)
+        Ok(amount)
     }
 
     // ────────────────────────────────────────────────────────────────────────
     // Reward Management (called by Fee Distributor)
     // ────────────────────────────────────────────────────────────────────────
 
-    /// Add rewards to the pool (called by Fee Distributor)
+    /// Add rewards to the pool (called by Fee Distributor).
+    /// Returns the amount actually received, which may be less than `amount`
+    /// for fee-on-transfer tokens.
     pub fn add_rewards(
         env: Env,
         caller: Address,
         reward_token: Address,
         amount: i128,
-    ) -> Result<(), SharedError> {
+    ) -> Result<i128, SharedError> {
         caller.require_auth();
         Self::require_initialized(&env)?;
 
@@ -318,61 +804,252 @@ impl StakingPool {
         if amount <= 0 {
             return Err(SharedError::InvalidAmount);
         }
+        Self::require_token_allowed(&env, &reward_token)?;
 
-        let total_staked = Self::get_total_staked(&env);
-
-        // FIX #M2: Prevent adding rewards when no stakers to avoid fund loss
-        if total_staked == 0 {
+        // A frozen token may be compromised; don't pull more of it in
+        if Self::get_frozen_reward_tokens(&env).contains(&reward_token) {
             return Err(SharedError::InvalidState);
         }
 
-        // Transfer reward tokens to contract
+        // Ensure reward token is allowlisted and tracked
+        Self::add_reward_token(&env, &reward_token)?;
+
+        // Transfer reward tokens to contract, measuring what actually arrived
+        // so deflationary / fee-on-transfer tokens can't make the pool insolvent
         let token_client = token::Client::new(&env, &reward_token);
-        token_client.transfer(&caller, &env.current_contract_address(), &amount);
+        let this = env.current_contract_address();
+        let balance_before = token_client.balance(&this);
+        token_client.transfer(&caller, &this, &amount);
+        let received = safe_sub(token_client.balance(&this), balance_before)?;
 
-        // Update accumulated rewards
-        let current_acc = Self::get_acc_reward_per_share(&env, &reward_token);
-        let reward_per_share = safe_div(safe_mul(amount, PRECISION)?, total_staked)?;
-        let new_acc = safe_add(current_acc, reward_per_share)?;
-        env.storage()
-            .persistent()
-            .set(&DataKey::AccRewardPerShare(reward_token.clone()), &new_acc);
+        if received <= 0 {
+            return Err(SharedError::InvalidAmount);
+        }
+
+        // FIX #M2: With no stakers there is no one to credit; hold the rewards
+        // as orphaned until `redistribute_orphaned` folds them in
+        let total_staked = Self::get_total_staked(&env);
+        if total_staked == 0 {
+            let orphaned = Self::get_orphaned_rewards(&env, &reward_token);
+            set_persistent(
+                &env,
+                &DataKey::OrphanedRewards(reward_token.clone()),
+                &safe_add(orphaned, received)?,
+            );
+
+            let events = EventBuilder::new(&env);
+            events.publish(
+                "staking",
+                "rewards_orphaned",
+                (reward_token, received, env.ledger().timestamp()),
+            );
+
+            extend_instance_ttl(&env);
+            return Ok(received);
+        }
+
+        Self::accrue_rewards(&env, &reward_token, received, total_staked)?;
+
+        let events = EventBuilder::new(&env);
+        events.publish(
+            "staking",
+            "rewards_added",
+            (reward_token, received, env.ledger().timestamp()),
+        );
+
+        extend_instance_ttl(&env);
+
+        Ok(received)
+    }
+
+    /// Credit rewards that arrived while nobody was staked to the current
+    /// stakers (admin or `KEEPER`). Returns the amount redistributed.
+    pub fn redistribute_orphaned(
+        env: Env,
+        caller: Address,
+        reward_token: Address,
+    ) -> Result<i128, SharedError> {
+        Self::require_initialized(&env)?;
+        Self::require_role(&env, &caller, &KEEPER)?;
+
+        let orphaned = Self::get_orphaned_rewards(&env, &reward_token);
+        if orphaned <= 0 {
+            return Err(SharedError::InvalidAmount);
+        }
+        let total_staked = Self::get_total_staked(&env);
+        if total_staked == 0 {
+            return Err(SharedError::InvalidState);
+        }
 
-        // Update total rewards
-        let total_rewards = Self::get_total_rewards(&env, &reward_token);
-        let new_total = safe_add(total_rewards, amount)?;
         env.storage()
             .persistent()
-            .set(&DataKey::TotalRewards(reward_token.clone()), &new_total);
+            .remove(&DataKey::OrphanedRewards(reward_token.clone()));
+        Self::accrue_rewards(&env, &reward_token, orphaned, total_staked)?;
+
+        let events = EventBuilder::new(&env);
+        events.publish(
+            "staking",
+            "orphaned_redistributed",
+            (reward_token, orphaned, env.ledger().timestamp()),
+        );
+
+        extend_instance_ttl(&env);
+
+        Ok(orphaned)
+    }
+
+    /// Retry handing stake fees the fee distributor rejected earlier
+    /// (paused, source not allowlisted). Anyone can call it; fails while the
+    /// distributor still rejects them. Returns the amount sent.
+    pub fn flush_fees(env: Env) -> Result<i128, SharedError> {
+        Self::require_initialized(&env)?;
+
+        let pending = Self::get_pending_fees(&env);
+        if pending == 0 {
+            return Ok(0);
+        }
+        let stake_token = Self::stake_token(env.clone())?;
+        let fee_distributor: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::FeeDistributor)
+            .ok_or(SharedError::NotInitialized)?;
 
-        // Ensure reward token is tracked
-        Self::add_reward_token(&env, &reward_token);
+        Self::authorize_transfer(&env, &stake_token, &fee_distributor, pending);
+        FeeDistributorClient::new(&env, &fee_distributor)
+            .try_receive_fees(&env.current_contract_address(), &stake_token, &pending)
+            .into_shared()?;
+        env.storage().instance().remove(&DataKey::PendingFees);
 
         let events = EventBuilder::new(&env);
         events.publish(
             "staking",
-            "rewards_added",
-            (reward_token, amount, env.ledger().timestamp()),
+            "fees_flushed",
+            (stake_token, pending, env.ledger().timestamp()),
         );
 
         extend_instance_ttl(&env);
+        Ok(pending)
+    }
 
-        Ok(())
+    /// Forfeit a user's rewards that accrued more than the reward expiry ago
+    /// and are still unclaimed. Forfeited rewards are spread over the other
+    /// stakers through reward-per-share. Callable by anyone (keepers).
+    /// Returns the forfeited amount per reward token.
+    pub fn forfeit_stale_rewards(
+        env: Env,
+        user: Address,
+    ) -> Result<Vec<(Address, i128)>, SharedError> {
+        Self::require_initialized(&env)?;
+        pausable::require_not_paused(&env, PauseGroup::Withdrawals)?;
+
+        let expiry = Self::get_reward_expiry(&env);
+        if expiry == 0 {
+            return Err(SharedError::InvalidState);
+        }
+
+        let mut user_stake = Self::get_user_stake(&env, &user);
+        let now = env.ledger().timestamp();
+        let cutoff = now.saturating_sub(expiry);
+        // Everything accrued since the last harvest is younger than the expiry
+        if user_stake.amount == 0 || cutoff <= user_stake.last_claim_time {
+            return Err(SharedError::CooldownNotElapsed);
+        }
+
+        let others = safe_sub(Self::get_total_staked(&env), user_stake.amount)?;
+        let frozen = Self::get_frozen_reward_tokens(&env);
+        let mut forfeited = Vec::new(&env);
+
+        for reward_token in Self::get_reward_tokens(&env).iter() {
+            if others <= 0 || frozen.contains(&reward_token) {
+                continue;
+            }
+            let stale = Self::stale_rewards(&env, &user_stake, &reward_token, cutoff)?;
+            if stale <= 0 {
+                continue;
+            }
+
+            // Spread over the other stakers; the user's debt absorbs both the
+            // forfeited amount and their own share of the bump
+            let bump = safe_div(safe_mul(stale, PRECISION)?, others)?;
+            let new_acc = safe_add(Self::get_acc_reward_per_share(&env, &reward_token), bump)?;
+            set_persistent(
+                &env,
+                &DataKey::AccRewardPerShare(reward_token.clone()),
+                &new_acc,
+            );
+            Self::write_acc_checkpoint(&env, &reward_token, new_acc);
+
+            let own_share = safe_div(safe_mul(user_stake.amount, bump)?, PRECISION)?;
+            let debt = safe_add(user_stake.get_reward_debt(&reward_token), stale)?;
+            user_stake.set_reward_debt(&reward_token, safe_add(debt, own_share)?);
+
+            let events = EventBuilder::new(&env);
+            events.publish(
+                "staking",
+                "rewards_forfeited",
+                (user.clone(), reward_token.clone(), stale, now),
+            );
+            forfeited.push_back((reward_token, stale));
+        }
+
+        if forfeited.is_empty() {
+            return Ok(forfeited);
+        }
+
+        let user_stake_key = DataKey::UserStake(user);
+        set_persistent(&env, &user_stake_key, &user_stake);
+
+        extend_instance_ttl(&env);
+
+        Ok(forfeited)
     }
 
     // ────────────────────────────────────────────────────────────────────────
     // Admin Functions
     // ────────────────────────────────────────────────────────────────────────
 
-    /// Update staking configuration
-    pub fn update_config(env: Env, new_config: StakingConfig) -> Result<(), SharedError> {
+    /// Set the protocol registry address reported by `contract_info`
+    pub fn set_registry(env: Env, registry: Address) -> Result<(), SharedError> {
+        Self::require_admin(&env)?;
+        env.storage().instance().set(&DataKey::Registry, &registry);
+        extend_instance_ttl(&env);
+        Ok(())
+    }
+
+    /// Set the token registry consulted before accepting a token
+    /// (`None` accepts every token)
+    pub fn set_token_registry(env: Env, registry: Option<Address>) -> Result<(), SharedError> {
         Self::require_admin(&env)?;
+        match registry {
+            Some(registry) => env
+                .storage()
+                .instance()
+                .set(&DataKey::TokenRegistry, &registry),
+            None => env.storage().instance().remove(&DataKey::TokenRegistry),
+        }
+        extend_instance_ttl(&env);
+        Ok(())
+    }
+
+    /// Update staking configuration
+    pub fn update_config(
+        env: Env,
+        caller: Address,
+        new_config: StakingConfig,
+    ) -> Result<(), SharedError> {
+        Self::require_role(&env, &caller, &CONFIG_ADMIN)?;
+        pausable::require_not_paused(&env, PauseGroup::AdminOps)?;
 
-        if new_config.min_stake_amount <= 0 {
+        if new_config.min_stake_amount < MIN_STAKE_AMOUNT {
             return Err(SharedError::InvalidAmount);
         }
+        if !new_config.is_valid() {
+            return Err(SharedError::InvalidBps);
+        }
 
         env.storage().instance().set(&DataKey::Config, &new_config);
+        emit_config_updated(&env, &caller);
         extend_instance_ttl(&env);
 
         Ok(())
@@ -382,341 +1059,2652 @@ impl StakingPool {
     pub fn set_fee_distributor(env: Env, new_distributor: Address) -> Result<(), SharedError> {
         Self::require_admin(&env)?;
 
+        let old_distributor = env.storage().instance().get(&DataKey::FeeDistributor);
         env.storage()
             .instance()
             .set(&DataKey::FeeDistributor, &new_distributor);
+        emit_fee_distributor_changed(&env, old_distributor, &new_distributor);
         extend_instance_ttl(&env);
 
         Ok(())
     }
 
-    /// Set admin address
-    pub fn set_admin(env: Env, new_admin: Address) -> Result<(), SharedError> {
+    /// Set how long rewards may stay unclaimed after accrual before
+    /// `forfeit_stale_rewards` can return them to the pool (0 disables)
+    pub fn set_reward_expiry(env: Env, secs: u64) -> Result<(), SharedError> {
         Self::require_admin(&env)?;
 
-        env.storage().instance().set(&DataKey::Admin, &new_admin);
-        extend_instance_ttl(&env);
+        env.storage().instance().set(&DataKey::RewardExpiry, &secs);
+
+        let events = EventBuilder::new(&env);
+        events.publish(
+            "staking",
+            "reward_expiry_set",
+            (secs, env.ledger().timestamp()),
+        );
 
+        extend_instance_ttl(&env);
         Ok(())
     }
 
-    /// Pause/unpause the contract
-    pub fn set_paused(env: Env, paused: bool) -> Result<(), SharedError> {
+    /// Make harvested rewards vest linearly over `reward_vesting_period`
+    /// seconds (0 pays them out immediately again; existing schedules keep
+    /// vesting). Early claims lose `early_claim_haircut_bps` of the unvested
+    /// part to the other stakers.
+    pub fn set_reward_vesting(
+        env: Env,
+        reward_vesting_period: u64,
+        early_claim_haircut_bps: u32,
+    ) -> Result<(), SharedError> {
         Self::require_admin(&env)?;
 
-        env.storage().instance().set(&DataKey::Paused, &paused);
+        if reward_vesting_period > MAX_REWARD_VESTING_PERIOD {
+            return Err(SharedError::InvalidTimestamp);
+        }
+        let haircut = Bps::new(early_claim_haircut_bps)?;
+        if haircut > MAX_EARLY_CLAIM_HAIRCUT {
+            return Err(SharedError::InvalidBps);
+        }
+
+        let storage = env.storage().instance();
+        storage.set(&DataKey::RewardVestingPeriod, &reward_vesting_period);
+        storage.set(&DataKey::EarlyClaimHaircut, &haircut);
 
         let events = EventBuilder::new(&env);
-        events.publish("staking", "paused", (paused, env.ledger().timestamp()));
+        events.publish(
+            "staking",
+            "reward_vesting_set",
+            (reward_vesting_period, early_claim_haircut_bps),
+        );
 
         extend_instance_ttl(&env);
         Ok(())
     }
 
-    /// Emergency withdrawal of stuck tokens (admin only)
-    pub fn emergency_withdraw(
-        env: Env,
-        token: Address,
-        to: Address,
-        amount: i128,
-    ) -> Result<(), SharedError> {
+    /// Set the treasury receiving its share of early-exit penalties
+    pub fn set_treasury(env: Env, treasury: Address) -> Result<(), SharedError> {
         Self::require_admin(&env)?;
 
-        let paused: bool = env
-            .storage()
-            .instance()
-            .get(&DataKey::Paused)
-            .unwrap_or(false);
-        if !paused {
-            return Err(SharedError::ContractNotPaused);
-        }
+        let storage = env.storage().instance();
+        let old_treasury: Option<Address> = storage.get(&DataKey::Treasury);
+        storage.set(&DataKey::Treasury, &treasury);
 
-        let token_client = token::Client::new(&env, &token);
-        token_client.transfer(&env.current_contract_address(), &to, &amount);
+        emit_treasury_changed(&env, old_treasury, &treasury);
+        extend_instance_ttl(&env);
+        Ok(())
+    }
+
+    /// Set where burned penalties go when the stake token can't burn
+    pub fn set_burn_address(env: Env, burn_address: Address) -> Result<(), SharedError> {
+        Self::require_admin(&env)?;
+
+        env.storage()
+            .instance()
+            .set(&DataKey::BurnAddress, &burn_address);
 
         let events = EventBuilder::new(&env);
-        events.publish("staking", "emergency_withdraw", (token, to, amount));
+        events.publish("staking", "burn_address_set", (burn_address,));
 
+        extend_instance_ttl(&env);
         Ok(())
     }
 
-    // ────────────────────────────────────────────────────────────────────────
-    // Query Functions
-    // ────────────────────────────────────────────────────────────────────────
+    /// Penalize unstaking within `window` seconds of a user's last stake
+    /// (`None` turns it off). `emergency_unstake` is never penalized, and
+    /// the ERC-4626 previews don't account for the penalty.
+    pub fn set_early_exit(env: Env, early_exit: Option<EarlyExit>) -> Result<(), SharedError> {
+        Self::require_admin(&env)?;
 
-    /// Get user stake information
-    pub fn get_stake(env: Env, user: Address) -> UserStake {
-        Self::get_user_stake(&env, &user)
+        let storage = env.storage().instance();
+        match &early_exit {
+            Some(exit) => {
+                if exit.window > MAX_EARLY_EXIT_WINDOW {
+                    return Err(SharedError::InvalidTimestamp);
+                }
+                if exit.penalty_bps > MAX_EARLY_EXIT_PENALTY || !exit.routing.is_valid() {
+                    return Err(SharedError::InvalidBps);
+                }
+                if (exit.routing.treasury_bps > Bps::ZERO && !storage.has(&DataKey::Treasury))
+                    || (exit.routing.burn_bps > Bps::ZERO && !storage.has(&DataKey::BurnAddress))
+                {
+                    return Err(SharedError::NotInitialized);
+                }
+                storage.set(&DataKey::EarlyExit, exit);
+            }
+            None => storage.remove(&DataKey::EarlyExit),
+        }
+
+        let events = EventBuilder::new(&env);
+        events.publish("staking", "early_exit_set", (early_exit,));
+
+        extend_instance_ttl(&env);
+        Ok(())
     }
 
-    /// Get pending rewards for a user
-    pub fn pending_rewards(env: Env, user: Address) -> Vec<(Address, i128)> {
-        let user_stake = Self::get_user_stake(&env, &user);
-        let reward_tokens = Self::get_reward_tokens(&env);
-        let mut rewards = Vec::new(&env);
+    /// Freeze a reward token: claims skip it, accrued rewards are preserved
+    pub fn freeze_reward_token(env: Env, token: Address) -> Result<(), SharedError> {
+        Self::require_admin(&env)?;
 
-        if user_stake.amount == 0 {
-            return rewards;
+        if !Self::get_reward_tokens(&env).contains(&token) {
+            return Err(SharedError::TokenNotFound);
         }
 
-        for reward_token in reward_tokens.iter() {
-            let acc_per_share = Self::get_acc_reward_per_share(&env, &reward_token);
-            // FIX #H1: Use get_reward_debt() to read per-token debt
-            let reward_debt = user_stake.get_reward_debt(&reward_token);
-            let pending =
-                Self::calculate_pending(&user_stake.amount, acc_per_share, reward_debt)
-                    .unwrap_or(0); // Safe: overflow means 0 pending
-            if pending > 0 {
-                rewards.push_back((reward_token, pending));
-            }
+        let mut frozen = Self::get_frozen_reward_tokens(&env);
+        if !frozen.contains(&token) {
+            frozen.push_back(token.clone());
+            env.storage()
+                .instance()
+                .set(&DataKey::FrozenRewardTokens, &frozen);
         }
 
-        rewards
-    }
+        let events = EventBuilder::new(&env);
+        events.publish(
+            "staking",
+            "reward_token_frozen",
+            (token, env.ledger().timestamp()),
+        );
 
-    /// Get total staked amount
-    pub fn total_staked(env: Env) -> i128 {
-        Self::get_total_staked(&env)
+        extend_instance_ttl(&env);
+        Ok(())
     }
 
-    /// Get staking configuration
-    pub fn get_config(env: Env) -> Result<StakingConfig, SharedError> {
-        env.storage()
-            .instance()
-            .get(&DataKey::Config)
-            .ok_or(SharedError::NotInitialized)
-    }
+    /// Unfreeze a reward token; carried-over rewards are paid on next harvest
+    pub fn unfreeze_reward_token(env: Env, token: Address) -> Result<(), SharedError> {
+        Self::require_admin(&env)?;
 
-    /// Get stake token address
-    pub fn stake_token(env: Env) -> Result<Address, SharedError> {
+        let mut frozen = Self::get_frozen_reward_tokens(&env);
+        let index = frozen
+            .first_index_of(&token)
+            .ok_or(SharedError::TokenNotFound)?;
+        frozen.remove(index);
         env.storage()
             .instance()
-            .get(&DataKey::StakeToken)
-            .ok_or(SharedError::NotInitialized)
-    }
+            .set(&DataKey::FrozenRewardTokens, &frozen);
 
-    /// Get all reward tokens
-    pub fn reward_tokens(env: Env) -> Vec<Address> {
-        Self::get_reward_tokens(&env)
-    }
+        let events = EventBuilder::new(&env);
+        events.publish(
+            "staking",
+            "reward_token_unfrozen",
+            (token, env.ledger().timestamp()),
+        );
 
-    /// Get admin address
-    pub fn admin(env: Env) -> Result<Address, SharedError> {
-        env.storage()
-            .instance()
-            .get(&DataKey::Admin)
-            .ok_or(SharedError::NotInitialized)
+        extend_instance_ttl(&env);
+        Ok(())
     }
 
-    /// Check if contract is paused
-    pub fn is_paused(env: Env) -> bool {
-        env.storage()
-            .instance()
-            .get(&DataKey::Paused)
-            .unwrap_or(false)
+    /// Set the governance contract allowed to place vote locks
+    pub fn set_governor(env: Env, governor: Address) -> Result<(), SharedError> {
+        Self::require_admin(&env)?;
+
+        env.storage().instance().set(&DataKey::Governor, &governor);
+        extend_instance_ttl(&env);
+
+        Ok(())
     }
 
-    /// Get APR estimate (based on recent rewards)
-    pub fn get_apr(env: Env, reward_token: Address) -> i128 {
-        let total_staked = Self::get_total_staked(&env);
-        let total_rewards = Self::get_total_rewards(&env, &reward_token);
+    /// Set (or clear) the address authorized to slash stakes
+    pub fn set_slasher(env: Env, slasher: Option<Address>) -> Result<(), SharedError> {
+        Self::require_admin(&env)?;
 
-        if total_staked == 0 || total_rewards == 0 {
-            return 0;
+        match &slasher {
+            Some(addr) => env.storage().instance().set(&DataKey::Slasher, addr),
+            None => env.storage().instance().remove(&DataKey::Slasher),
         }
 
-        // Simple APR calculation: (rewards / staked) * 100
-        // This is a simplified estimate
-        safe_div(safe_mul(total_rewards, 10000).unwrap_or(0), total_staked).unwrap_or(0)
+        let events = EventBuilder::new(&env);
+        events.publish(
+            "staking",
+            "slasher_changed",
+            (slasher, env.ledger().timestamp()),
+        );
+
+        extend_instance_ttl(&env);
+        Ok(())
     }
 
-    // ────────────────────────────────────────────────────────────────────────
-    // Internal Helpers
-    // ────────────────────────────────────────────────────────────────────────
+    /// Select which high-frequency events use compact encoding
+    /// (bitmask of `CompactEventKind::bit()`)
+    pub fn set_compact_events(env: Env, mask: u32) -> Result<(), SharedError> {
+        Self::require_admin(&env)?;
 
-    fn require_initialized(env: &Env) -> Result<(), SharedError> {
-        let initialized: bool = env
-            .storage()
-            .instance()
-            .get(&DataKey::Initialized)
-            .unwrap_or(false);
+        set_compact_mask(&env, mask);
+        extend_instance_ttl(&env);
 
-        if !initialized {
-            return Err(SharedError::NotInitialized);
-        }
         Ok(())
     }
 
-    fn require_not_paused(env: &Env) -> Result<(), SharedError> {
-        let paused: bool = env
-            .storage()
-            .instance()
-            .get(&DataKey::Paused)
-            .unwrap_or(false);
+    /// Approve a token for use as a staking reward
+    pub fn allow_reward_token(env: Env, token: Address) -> Result<(), SharedError> {
+        Self::require_admin(&env)?;
 
-        if paused {
-            return Err(SharedError::ContractPaused);
+        let mut allowed = Self::get_allowed_reward_tokens(&env);
+        for t in allowed.iter() {
+            if t == token {
+                return Ok(());
+            }
         }
+        allowed.push_back(token.clone());
+        env.storage()
+            .instance()
+            .set(&DataKey::AllowedRewardTokens, &allowed);
+
+        let events = EventBuilder::new(&env);
+        events.publish(
+            "staking",
+            "reward_token_allowed",
+            (token, env.ledger().timestamp()),
+        );
+
+        extend_instance_ttl(&env);
         Ok(())
     }
 
-    fn require_admin(env: &Env) -> Result<(), SharedError> {
-        let admin: Address = env
-            .storage()
+    /// Revoke approval for a reward token (already registered tokens keep accruing
+    /// until removed with `remove_reward_token`)
+    pub fn disallow_reward_token(env: Env, token: Address) -> Result<(), SharedError> {
+        Self::require_admin(&env)?;
+
+        let allowed = Self::get_allowed_reward_tokens(&env);
+        let mut new_allowed = Vec::new(&env);
+        for t in allowed.iter() {
+            if t != token {
+                new_allowed.push_back(t);
+            }
+        }
+        env.storage()
             .instance()
-            .get(&DataKey::Admin)
-            .ok_or(SharedError::NotInitialized)?;
+            .set(&DataKey::AllowedRewardTokens, &new_allowed);
 
-        admin.require_auth();
+        let events = EventBuilder::new(&env);
+        events.publish(
+            "staking",
+            "reward_token_disallowed",
+            (token, env.ledger().timestamp()),
+        );
+
+        extend_instance_ttl(&env);
         Ok(())
     }
 
-    fn get_user_stake(env: &Env, user: &Address) -> UserStake {
+    /// Set the maximum number of registered reward tokens
+    pub fn set_max_reward_tokens(env: Env, max: u32) -> Result<(), SharedError> {
+        Self::require_admin(&env)?;
+
+        if max == 0 || max < Self::get_reward_tokens(&env).len() {
+            return Err(SharedError::InvalidInitParams);
+        }
+
         env.storage()
-            .persistent()
-            .get(&DataKey::UserStake(user.clone()))
-            .unwrap_or_else(|| {
-                // Create new UserStake with empty reward_debts Map
-                // FIX #H1: Initialize Map properly for multi-token reward tracking
-                use soroban_sdk::Map;
-                UserStake {
-                    amount: 0,
-                    stake_time: env.ledger().timestamp(),
-                    last_claim_time: env.ledger().timestamp(),
-                    reward_debts: Map::new(env),
-                }
-            })
+            .instance()
+            .set(&DataKey::MaxRewardTokens, &max);
+        extend_instance_ttl(&env);
+
+        Ok(())
     }
 
-    fn get_total_staked(env: &Env) -> i128 {
+    /// Stop iterating a reward token in harvests.
+    /// Requires all rewards of that token to have been claimed (up to rounding dust).
+    pub fn remove_reward_token(env: Env, token: Address) -> Result<(), SharedError> {
+        Self::require_admin(&env)?;
+
+        if Self::get_reward_liability(&env, &token) > MAX_REWARD_DUST
+            || Self::get_frozen_reward_tokens(&env).contains(&token)
+        {
+            return Err(SharedError::InvalidState);
+        }
+
+        let tokens = Self::get_reward_tokens(&env);
+        let mut new_tokens = Vec::new(&env);
+        let mut found = false;
+        for t in tokens.iter() {
+            if t == token {
+                found = true;
+            } else {
+                new_tokens.push_back(t);
+            }
+        }
+        if !found {
+            return Err(SharedError::TokenNotFound);
+        }
+
         env.storage()
             .instance()
-            .get(&DataKey::TotalStaked)
-            .unwrap_or(0)
+            .set(&DataKey::RewardTokens, &new_tokens);
+
+        let events = EventBuilder::new(&env);
+        events.publish(
+            "staking",
+            "reward_token_removed",
+            (token, env.ledger().timestamp()),
+        );
+
+        extend_instance_ttl(&env);
+        Ok(())
+    }
+
+    /// Propose a new admin; it takes over once it calls `accept_admin`
+    pub fn propose_admin(env: Env, new_admin: Address) -> Result<(), SharedError> {
+        Self::require_admin(&env)?;
+
+        let admin = Self::admin(env.clone())?;
+        ownership::propose(&env, &admin, &new_admin)?;
+        extend_instance_ttl(&env);
+
+        Ok(())
+    }
+
+    /// Accept a pending admin proposal (proposed admin only)
+    pub fn accept_admin(env: Env) -> Result<(), SharedError> {
+        let old_admin = Self::admin(env.clone())?;
+        let new_admin = ownership::accept(&env, &old_admin)?;
+
+        env.storage().instance().set(&DataKey::Admin, &new_admin);
+        emit_admin_changed(&env, &old_admin, &new_admin);
+        extend_instance_ttl(&env);
+
+        Ok(())
+    }
+
+    /// Withdraw a pending admin proposal
+    pub fn cancel_admin_transfer(env: Env) -> Result<(), SharedError> {
+        Self::require_admin(&env)?;
+
+        ownership::cancel(&env)?;
+        extend_instance_ttl(&env);
+
+        Ok(())
+    }
+
+    /// Pause/unpause every function group (admin or `PAUSER`)
+    pub fn set_paused(env: Env, caller: Address, paused: bool) -> Result<(), SharedError> {
+        Self::require_role(&env, &caller, &PAUSER)?;
+
+        pausable::set(&env, &PauseConfig::uniform(paused));
+
+        let events = EventBuilder::new(&env);
+        events.publish("staking", "paused", (paused, env.ledger().timestamp()));
+
+        extend_instance_ttl(&env);
+        Ok(())
+    }
+
+    /// Pause individual function groups, e.g. halt deposits while
+    /// withdrawals stay open (admin or `PAUSER`)
+    pub fn set_pause_config(
+        env: Env,
+        caller: Address,
+        config: PauseConfig,
+    ) -> Result<(), SharedError> {
+        Self::require_role(&env, &caller, &PAUSER)?;
+
+        pausable::set(&env, &config);
+
+        let events = EventBuilder::new(&env);
+        events.publish(
+            "staking",
+            "pause_config",
+            (
+                config.deposits,
+                config.withdrawals,
+                config.admin_ops,
+                env.ledger().timestamp(),
+            ),
+        );
+
+        extend_instance_ttl(&env);
+        Ok(())
+    }
+
+    /// Emergency withdrawal of stuck tokens (admin or `TREASURER`, while
+    /// withdrawals are paused)
+    pub fn emergency_withdraw(
+        env: Env,
+        caller: Address,
+        token: Address,
+        to: Address,
+        amount: i128,
+    ) -> Result<(), SharedError> {
+        Self::require_role(&env, &caller, &TREASURER)?;
+
+        pausable::require_paused(&env, PauseGroup::Withdrawals)?;
+
+        let token_client = token::Client::new(&env, &token);
+        token_client.transfer(&env.current_contract_address(), &to, &amount);
+
+        let events = EventBuilder::new(&env);
+        events.publish("staking", "emergency_withdraw", (token, to, amount));
+
+        Ok(())
+    }
+
+    /// Set how far storage TTLs are extended (see `TtlConfig`), e.g. shorter
+    /// lifetimes on testnet
+    pub fn set_ttl_config(env: Env, caller: Address, config: TtlConfig) -> Result<(), SharedError> {
+        Self::require_role(&env, &caller, &CONFIG_ADMIN)?;
+        pausable::require_not_paused(&env, PauseGroup::AdminOps)?;
+
+        set_ttl_config(&env, &config)?;
+        emit_config_updated(&env, &caller);
+        extend_instance_ttl(&env);
+
+        Ok(())
+    }
+
+    // ────────────────────────────────────────────────────────────────────────
+    // Roles (admin only)
+    // ────────────────────────────────────────────────────────────────────────
+
+    /// Grant a role (`PAUSER`, `TREASURER`, `CFG_ADMIN`, `UPGRADER`) to an account
+    pub fn grant_role(env: Env, role: Symbol, account: Address) -> Result<(), SharedError> {
+        Self::require_admin(&env)?;
+        access_control::grant_role(&env, role, account)?;
+        extend_instance_ttl(&env);
+        Ok(())
+    }
+
+    /// Revoke a role from an account
+    pub fn revoke_role(env: Env, role: Symbol, account: Address) -> Result<(), SharedError> {
+        Self::require_admin(&env)?;
+        access_control::revoke_role(&env, role, account)?;
+        extend_instance_ttl(&env);
+        Ok(())
+    }
+
+    // ────────────────────────────────────────────────────────────────────────
+    // Upgrades (admin or UPGRADER)
+    // ────────────────────────────────────────────────────────────────────────
+
+    /// Queue a WASM upgrade behind the upgrade timelock. Returns its eta.
+    pub fn queue_upgrade(
+        env: Env,
+        caller: Address,
+        new_wasm_hash: BytesN<32>,
+    ) -> Result<u64, SharedError> {
+        Self::require_role(&env, &caller, &UPGRADER)?;
+        upgrade::queue(&env, new_wasm_hash)
+    }
+
+    /// Cancel the queued upgrade
+    pub fn cancel_upgrade(env: Env, caller: Address) -> Result<(), SharedError> {
+        Self::require_role(&env, &caller, &UPGRADER)?;
+        upgrade::cancel(&env)
+    }
+
+    /// Switch to the queued WASM once the timelock has elapsed
+    pub fn upgrade(
+        env: Env,
+        caller: Address,
+        new_wasm_hash: BytesN<32>,
+    ) -> Result<(), SharedError> {
+        Self::require_role(&env, &caller, &UPGRADER)?;
+        upgrade::execute(&env, new_wasm_hash)
+    }
+
+    /// Migrate storage to `STORAGE_VERSION` after an upgrade
+    pub fn migrate(env: Env, caller: Address) -> Result<u32, SharedError> {
+        Self::require_role(&env, &caller, &UPGRADER)?;
+        upgrade::migrate(&env, STORAGE_VERSION, |env, from| {
+            if from < 2 {
+                // v2 moved the single pause flag to per-group `PauseConfig`
+                let paused: bool = env
+                    .storage()
+                    .instance()
+                    .get(&DataKey::Paused)
+                    .unwrap_or(false);
+                pausable::set(env, &PauseConfig::uniform(paused));
+                env.storage().instance().remove(&DataKey::Paused);
+            }
+            Ok(())
+        })
+    }
+
+    // ────────────────────────────────────────────────────────────────────────
+    // Query Functions
+    // ────────────────────────────────────────────────────────────────────────
+
+    /// Next nonce `user` must sign relayed calls with
+    pub fn signing_nonce(env: Env, user: Address) -> u64 {
+        signature::nonce(&env, &user)
+    }
+
+    /// Whether an account was granted a role (the admin implicitly holds all)
+    pub fn has_role(env: Env, role: Symbol, account: Address) -> bool {
+        access_control::has_role(&env, &role, &account)
+    }
+
+    /// Get the storage layout version
+    pub fn version(env: Env) -> u32 {
+        upgrade::version(&env)
+    }
+
+    /// Get the queued upgrade, if any
+    pub fn get_pending_upgrade(env: Env) -> Option<PendingUpgrade> {
+        upgrade::pending(&env)
+    }
+
+    /// Get user stake information
+    pub fn get_stake(env: Env, user: Address) -> UserStake {
+        Self::get_user_stake(&env, &user)
+    }
+
+    /// Get pending rewards for a user
+    pub fn pending_rewards(env: Env, user: Address) -> Vec<(Address, i128)> {
+        let user_stake = Self::get_user_stake(&env, &user);
+        let reward_tokens = Self::get_reward_tokens(&env);
+        let mut rewards = Vec::new(&env);
+
+        for reward_token in reward_tokens.iter() {
+            let mut pending = 0;
+            if user_stake.amount > 0 {
+                let acc_per_share = Self::get_acc_reward_per_share(&env, &reward_token);
+                // FIX #H1: Use get_reward_debt() to read per-token debt
+                let reward_debt = user_stake.get_reward_debt(&reward_token);
+                pending = Self::calculate_pending(&user_stake.amount, acc_per_share, reward_debt)
+                    .unwrap_or(0); // Safe: overflow means 0 pending
+            }
+            // Includes rewards carried over while the token was frozen
+            let carried: i128 = get_persistent(
+                &env,
+                &DataKey::FrozenRewards(user.clone(), reward_token.clone()),
+            )
+            .unwrap_or(0);
+            let pending = pending + carried;
+            if pending > 0 {
+                rewards.push_back((reward_token, pending));
+            }
+        }
+
+        rewards
+    }
+
+    /// Get total staked amount
+    pub fn total_staked(env: Env) -> i128 {
+        Self::get_total_staked(&env)
+    }
+
+    /// Get a user's staked amount as of `timestamp` (snapshot voting power)
+    pub fn get_stake_at(env: Env, user: Address, timestamp: u64) -> i128 {
+        Self::checkpoint_lookup(&env, &Some(user), timestamp)
+    }
+
+    /// Get the total staked amount as of `timestamp`
+    pub fn total_staked_at(env: Env, timestamp: u64) -> i128 {
+        Self::checkpoint_lookup(&env, &None, timestamp)
+    }
+
+    /// Get the number of checkpoints recorded for a user
+    pub fn checkpoint_count(env: Env, user: Address) -> u32 {
+        Self::get_checkpoint_count(&env, &Some(user))
+    }
+
+    /// Number of addresses with a non-zero stake
+    pub fn staker_count(env: Env) -> u32 {
+        Self::get_staker_count(&env)
+    }
+
+    /// Enumerate stakers with their current stake.
+    /// Returns up to `limit` entries (capped at 100) starting at index `start`.
+    /// Order is not stable across unstakes (removal swaps in the last entry).
+    pub fn get_stakers_page(env: Env, start: u32, limit: u32) -> Vec<(Address, i128)> {
+        let mut page = Vec::new(&env);
+        let count = Self::get_staker_count(&env);
+        let end = start.saturating_add(limit.min(MAX_PAGE_SIZE)).min(count);
+
+        for index in start..end {
+            if let Some(user) = get_persistent::<DataKey, Address>(&env, &DataKey::StakerAt(index))
+            {
+                let amount = Self::get_user_stake(&env, &user).amount;
+                page.push_back((user, amount));
+            }
+        }
+
+        page
+    }
+
+    /// Get staking configuration
+    pub fn get_config(env: Env) -> Result<StakingConfig, SharedError> {
+        env.storage()
+            .instance()
+            .get(&DataKey::Config)
+            .ok_or(SharedError::NotInitialized)
+    }
+
+    /// Get stake token address
+    pub fn stake_token(env: Env) -> Result<Address, SharedError> {
+        env.storage()
+            .instance()
+            .get(&DataKey::StakeToken)
+            .ok_or(SharedError::NotInitialized)
+    }
+
+    /// Get all reward tokens
+    pub fn reward_tokens(env: Env) -> Vec<Address> {
+        Self::get_reward_tokens(&env)
+    }
+
+    /// Get the governor, if set
+    pub fn governor(env: Env) -> Option<Address> {
+        env.storage().instance().get(&DataKey::Governor)
+    }
+
+    /// Get a user's active vote locks
+    pub fn get_vote_locks(env: Env, user: Address) -> Vec<VoteLock> {
+        let now = env.ledger().timestamp();
+        let mut active = Vec::new(&env);
+        for lock in Self::load_vote_locks(&env, &user).iter() {
+            if lock.until > now {
+                active.push_back(lock);
+            }
+        }
+        active
+    }
+
+    /// Get the stake a user cannot currently unstake due to votes
+    pub fn vote_locked_amount(env: Env, user: Address) -> i128 {
+        Self::get_vote_locked_amount(&env, &user)
+    }
+
+    /// Get the authorized slasher, if any
+    pub fn slasher(env: Env) -> Option<Address> {
+        env.storage().instance().get(&DataKey::Slasher)
+    }
+
+    /// Get frozen reward tokens
+    pub fn frozen_reward_tokens(env: Env) -> Vec<Address> {
+        Self::get_frozen_reward_tokens(&env)
+    }
+
+    /// Get tokens approved as rewards
+    pub fn allowed_reward_tokens(env: Env) -> Vec<Address> {
+        Self::get_allowed_reward_tokens(&env)
+    }
+
+    /// Get the maximum number of registered reward tokens
+    pub fn max_reward_tokens(env: Env) -> u32 {
+        Self::get_max_reward_tokens(&env)
+    }
+
+    /// Get rewards added but not yet claimed for a token
+    pub fn reward_liability(env: Env, reward_token: Address) -> i128 {
+        Self::get_reward_liability(&env, &reward_token)
+    }
+
+    /// Get rewards received while nobody was staked, awaiting
+    /// `redistribute_orphaned`
+    pub fn orphaned_rewards(env: Env, reward_token: Address) -> i128 {
+        Self::get_orphaned_rewards(&env, &reward_token)
+    }
+
+    /// Get the rewards paid out to a user in `reward_token` all-time
+    pub fn total_claimed(env: Env, user: Address, reward_token: Address) -> i128 {
+        get_persistent(&env, &DataKey::TotalClaimed(user, reward_token)).unwrap_or(0)
+    }
+
+    /// Get the rewards paid out to a user all-time, per reward token
+    pub fn lifetime_rewards(env: Env, user: Address) -> Vec<(Address, i128)> {
+        let tokens: Vec<Address> =
+            get_persistent(&env, &DataKey::ClaimedTokens(user.clone())).unwrap_or(Vec::new(&env));
+        let mut rewards = Vec::new(&env);
+        for reward_token in tokens.iter() {
+            let claimed = Self::total_claimed(env.clone(), user.clone(), reward_token.clone());
+            rewards.push_back((reward_token, claimed));
+        }
+        rewards
+    }
+
+    /// Compare each token balance with what the pool owes in it: unclaimed
+    /// (including still-vesting) and orphaned rewards, plus, for the stake
+    /// token, held fees and what every share redeems for at the current pool
+    /// assets (a
+    /// reward token that is also the stake token is reported once, owing both)
+    pub fn solvency_report(env: Env) -> Result<Vec<TokenSolvency>, SharedError> {
+        let this = env.current_contract_address();
+        let stake_token = Self::stake_token(env.clone())?;
+        let mut report = Vec::new(&env);
+        let staked_assets =
+            Self::shares_to_assets(&env, &stake_token, Self::get_total_staked(&env))?;
+        report.push_back(TokenSolvency {
+            token: stake_token.clone(),
+            balance: token::Client::new(&env, &stake_token).balance(&this),
+            liabilities: safe_add(
+                staked_assets,
+                Self::stake_token_obligations(&env, &stake_token)?,
+            )?,
+        });
+        for reward_token in Self::get_reward_tokens(&env).iter() {
+            if reward_token == stake_token {
+                continue;
+            }
+            report.push_back(TokenSolvency {
+                balance: token::Client::new(&env, &reward_token).balance(&this),
+                liabilities: Self::reward_obligations(&env, &reward_token)?,
+                token: reward_token,
+            });
+        }
+        Ok(report)
+    }
+
+    /// Stake fees held until the fee distributor accepts them
+    pub fn pending_fees(env: Env) -> i128 {
+        Self::get_pending_fees(&env)
     }
 
-    fn get_acc_reward_per_share(env: &Env, reward_token: &Address) -> i128 {
-        env.storage()
-            .persistent()
-            .get(&DataKey::AccRewardPerShare(reward_token.clone()))
-            .unwrap_or(0)
+    /// Get admin address
+    pub fn admin(env: Env) -> Result<Address, SharedError> {
+        env.storage()
+            .instance()
+            .get(&DataKey::Admin)
+            .ok_or(SharedError::NotInitialized)
+    }
+
+    /// Get the storage TTL config
+    pub fn ttl_config(env: Env) -> TtlConfig {
+        get_ttl_config(&env)
+    }
+
+    /// Get the token registry, if set
+    pub fn token_registry(env: Env) -> Option<Address> {
+        env.storage().instance().get(&DataKey::TokenRegistry)
+    }
+
+    /// Get the proposed admin awaiting acceptance
+    pub fn pending_admin(env: Env) -> Option<Address> {
+        ownership::pending(&env)
+    }
+
+    /// Whether any function group is paused
+    pub fn is_paused(env: Env) -> bool {
+        pausable::get(&env).is_any()
+    }
+
+    /// Get the pause flags per function group
+    pub fn pause_config(env: Env) -> PauseConfig {
+        pausable::get(&env)
+    }
+
+    /// Describe this contract for explorers and wallets
+    pub fn contract_info(env: Env) -> Result<ContractInfo, SharedError> {
+        let admin = Self::admin(env.clone())?;
+        let config = Self::get_config(env.clone())?;
+        let info = ContractInfo::new(
+            &env,
+            "staking",
+            env!("CARGO_PKG_VERSION"),
+            admin,
+            Self::is_paused(env.clone()),
+            env.storage().instance().get(&DataKey::Registry),
+        );
+        Ok(info
+            .with("min_stake_amount", config.min_stake_amount)
+            .with("cooldown_period", config.cooldown_period as i128)
+            .with("deposit_fee_bps", config.deposit_fee_bps as i128)
+            .with("withdraw_fee_bps", config.withdraw_fee_bps as i128)
+            .with("total_staked", Self::get_total_staked(&env))
+            .with("stakers", Self::get_staker_count(&env) as i128)
+            .with("reward_tokens", Self::get_reward_tokens(&env).len() as i128))
+    }
+
+    /// Get the reward expiry in seconds (0 = forfeiture disabled)
+    pub fn reward_expiry(env: Env) -> u64 {
+        Self::get_reward_expiry(&env)
+    }
+
+    /// Stake shares `assets` stake tokens would mint now (before fees)
+    pub fn convert_to_shares(env: Env, assets: i128) -> Result<i128, SharedError> {
+        let stake_token = Self::stake_token(env.clone())?;
+        Self::assets_to_shares(&env, &stake_token, assets)
+    }
+
+    /// Stake tokens `shares` are worth now (before fees)
+    pub fn convert_to_assets(env: Env, shares: i128) -> Result<i128, SharedError> {
+        let stake_token = Self::stake_token(env.clone())?;
+        Self::shares_to_assets(&env, &stake_token, shares)
+    }
+
+    /// Get the reward vesting period in seconds (0 = rewards paid on claim)
+    pub fn reward_vesting_period(env: Env) -> u64 {
+        Self::get_reward_vesting_period(&env)
+    }
+
+    /// Get the haircut on early claims of vesting rewards
+    pub fn early_claim_haircut(env: Env) -> Bps {
+        Self::get_early_claim_haircut(&env)
+    }
+
+    /// Get the early-exit penalty, if any
+    pub fn early_exit(env: Env) -> Option<EarlyExit> {
+        env.storage().instance().get(&DataKey::EarlyExit)
+    }
+
+    /// Get the treasury receiving early-exit penalties, if set
+    pub fn treasury(env: Env) -> Option<Address> {
+        env.storage().instance().get(&DataKey::Treasury)
+    }
+
+    /// Get a user's rewards still vesting in `reward_token`
+    pub fn get_reward_vest(env: Env, user: Address, reward_token: Address) -> Option<RewardVest> {
+        get_persistent(&env, &DataKey::RewardVest(user, reward_token))
+    }
+
+    /// Get a user's rewards that are currently eligible for forfeiture
+    pub fn forfeitable_rewards(env: Env, user: Address) -> Vec<(Address, i128)> {
+        let mut result = Vec::new(&env);
+        let expiry = Self::get_reward_expiry(&env);
+        let user_stake = Self::get_user_stake(&env, &user);
+        let cutoff = env.ledger().timestamp().saturating_sub(expiry);
+        if expiry == 0 || cutoff <= user_stake.last_claim_time {
+            return result;
+        }
+
+        for reward_token in Self::get_reward_tokens(&env).iter() {
+            let stale = Self::stale_rewards(&env, &user_stake, &reward_token, cutoff).unwrap_or(0);
+            if stale > 0 {
+                result.push_back((reward_token, stale));
+            }
+        }
+        result
+    }
+
+    /// Get APR estimate in basis points: reward-per-share growth over the
+    /// last `APR_WINDOW`, annualized. Assumes reward and stake token are
+    /// valued 1:1.
+    pub fn get_apr(env: Env, reward_token: Address) -> i128 {
+        // Reward-per-share is rewards per staked unit scaled by PRECISION, so its
+        // growth over the window is the return on one staked token
+        let now = env.ledger().timestamp();
+        let acc_now = Self::get_acc_reward_per_share(&env, &reward_token);
+        let acc_start = Self::acc_per_share_at(&env, &reward_token, now.saturating_sub(APR_WINDOW));
+
+        annualized_rate_bps(acc_now - acc_start, PRECISION, APR_WINDOW, SECONDS_PER_YEAR)
+            .unwrap_or(0)
+    }
+
+    // ────────────────────────────────────────────────────────────────────────
+    // Internal Helpers
+    // ────────────────────────────────────────────────────────────────────────
+
+    /// Reject `token` if a token registry is set and does not allow it
+    fn require_token_allowed(env: &Env, token: &Address) -> Result<(), SharedError> {
+        let registry: Option<Address> = env.storage().instance().get(&DataKey::TokenRegistry);
+        if let Some(registry) = registry {
+            if !TokenRegistryClient::new(env, &registry).is_token_allowed(token) {
+                return Err(SharedError::TokenNotAllowed);
+            }
+        }
+        Ok(())
+    }
+
+    fn require_initialized(env: &Env) -> Result<(), SharedError> {
+        let initialized: bool = env
+            .storage()
+            .instance()
+            .get(&DataKey::Initialized)
+            .unwrap_or(false);
+
+        if !initialized {
+            return Err(SharedError::NotInitialized);
+        }
+        Ok(())
+    }
+
+    /// Body of `claim` once `user` has authorized it
+    fn claim_internal(env: &Env, user: &Address) -> Result<Vec<(Address, i128)>, SharedError> {
+        Self::require_initialized(env)?;
+        pausable::require_not_paused(env, PauseGroup::Withdrawals)?;
+
+        let mut user_stake = Self::get_user_stake(env, user);
+        let reward_tokens = Self::get_reward_tokens(env);
+
+        let rewards = Self::internal_harvest(env, user, &mut user_stake, &reward_tokens)?;
+
+        // Update reward debt
+        // FIX #H1: Use set_reward_debt() to properly track per-token debt
+        for reward_token in reward_tokens.iter() {
+            let acc_per_share = Self::get_acc_reward_per_share(env, &reward_token);
+            let new_debt = safe_div(safe_mul(user_stake.amount, acc_per_share)?, PRECISION)?;
+            user_stake.set_reward_debt(&reward_token, new_debt);
+        }
+
+        let user_stake_key = DataKey::UserStake(user.clone());
+        set_persistent(env, &user_stake_key, &user_stake);
+
+        extend_instance_ttl(env);
+
+        Ok(rewards)
+    }
+
+    /// Body of `stake` once `user` has authorized it; returns the shares
+    /// minted and the user's new share balance
+    fn stake_internal(
+        env: &Env,
+        user: &Address,
+        amount: i128,
+    ) -> Result<(i128, i128), SharedError> {
+        pausable::require_not_paused(env, PauseGroup::Deposits)?;
+
+        let config: StakingConfig = env
+            .storage()
+            .instance()
+            .get(&DataKey::Config)
+            .ok_or(SharedError::NotInitialized)?;
+
+        if amount < config.min_stake_amount {
+            return Err(SharedError::AmountBelowMin);
+        }
+
+        let stake_token: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::StakeToken)
+            .ok_or(SharedError::NotInitialized)?;
+
+        // Transfer tokens to contract, measuring what actually arrived so
+        // fee-on-transfer stake tokens can't inflate the pool
+        let token_client = token::Client::new(env, &stake_token);
+        let this = env.current_contract_address();
+        let pool_before = Self::pool_assets(env, &stake_token)?;
+        let balance_before = token_client.balance(&this);
+        token_client.transfer(user, &this, &amount);
+        let received = safe_sub(token_client.balance(&this), balance_before)?;
+
+        let fee = apply_bps(received, config.deposit_fee_bps)?;
+        let net_amount = safe_sub(received, fee)?;
+
+        // Shares are minted against the pool as it stood before this deposit
+        let total_shares = Self::get_total_staked(env);
+        if total_shares > 0 && pool_before == 0 {
+            return Err(SharedError::InvalidState);
+        }
+        let shares = mul_div_down(
+            net_amount,
+            safe_add(total_shares, VIRTUAL_OFFSET)?,
+            safe_add(pool_before, VIRTUAL_OFFSET)?,
+        )?;
+        if shares <= 0 {
+            return Err(SharedError::InvalidAmount);
+        }
+
+        // Get or create user stake
+        let mut user_stake = Self::get_user_stake(env, user);
+        let reward_tokens = Self::get_reward_tokens(env);
+
+        // Harvest pending rewards before updating stake
+        Self::internal_harvest(env, user, &mut user_stake, &reward_tokens)?;
+
+        // Update user stake
+        let new_amount = safe_add(user_stake.amount, shares)?;
+        user_stake.amount = new_amount;
+        user_stake.stake_time = env.ledger().timestamp();
+
+        // Update reward debts for all reward tokens
+        // FIX #H1: Use set_reward_debt() to properly track per-token debt
+        for reward_token in reward_tokens.iter() {
+            let acc_per_share = Self::get_acc_reward_per_share(env, &reward_token);
+            let new_debt = safe_div(safe_mul(new_amount, acc_per_share)?, PRECISION)?;
+            user_stake.set_reward_debt(&reward_token, new_debt);
+        }
+
+        // Save user stake
+        let user_stake_key = DataKey::UserStake(user.clone());
+        set_persistent(env, &user_stake_key, &user_stake);
+
+        // Update total staked
+        let new_total = safe_add(total_shares, shares)?;
+        env.storage()
+            .instance()
+            .set(&DataKey::TotalStaked, &new_total);
+
+        Self::record_checkpoints(env, user, new_amount, new_total);
+        Self::sync_staker_registry(env, user, new_amount);
+
+        Self::route_fee(env, &stake_token, fee)?;
+
+        emit_stake(env, user, amount, new_total, fee);
+        extend_instance_ttl(env);
+
+        Ok((shares, new_amount))
+    }
+
+    /// Body of `unstake` once `user` has authorized it; returns the amount
+    /// paid out and the user's remaining share balance
+    fn unstake_internal(
+        env: &Env,
+        user: &Address,
+        amount: i128,
+    ) -> Result<(i128, i128), SharedError> {
+        pausable::require_not_paused(env, PauseGroup::Withdrawals)?;
+
+        if amount <= 0 {
+            return Err(SharedError::InvalidAmount);
+        }
+
+        let mut user_stake = Self::get_user_stake(env, user);
+
+        if user_stake.amount < amount {
+            return Err(SharedError::InsufficientBalance);
+        }
+
+        // Stake pinned by active votes cannot leave
+        let vote_locked = Self::get_vote_locked_amount(env, user);
+        if user_stake.amount - amount < vote_locked {
+            return Err(SharedError::InsufficientBalance);
+        }
+
+        let reward_tokens = Self::get_reward_tokens(env);
+
+        // Harvest pending rewards before updating stake
+        Self::internal_harvest(env, user, &mut user_stake, &reward_tokens)?;
+
+        let stake_token: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::StakeToken)
+            .ok_or(SharedError::NotInitialized)?;
+        let assets = Self::shares_to_assets(env, &stake_token, amount)?;
+
+        // Update user stake
+        let remaining = safe_sub(user_stake.amount, amount)?;
+        user_stake.amount = remaining;
+
+        // Update reward debts
+        // FIX #H1: Use set_reward_debt() to properly track per-token debt
+        for reward_token in reward_tokens.iter() {
+            let acc_per_share = Self::get_acc_reward_per_share(env, &reward_token);
+            let new_debt = safe_div(safe_mul(remaining, acc_per_share)?, PRECISION)?;
+            user_stake.set_reward_debt(&reward_token, new_debt);
+        }
+
+        // Save user stake
+        let user_stake_key = DataKey::UserStake(user.clone());
+        set_persistent(env, &user_stake_key, &user_stake);
+
+        // Update total staked
+        let total_staked = Self::get_total_staked(env);
+        let new_total = safe_sub(total_staked, amount)?;
+        env.storage()
+            .instance()
+            .set(&DataKey::TotalStaked, &new_total);
+
+        Self::record_checkpoints(env, user, remaining, new_total);
+        Self::sync_staker_registry(env, user, remaining);
+
+        // Transfer tokens back to user
+        let config: StakingConfig = env
+            .storage()
+            .instance()
+            .get(&DataKey::Config)
+            .ok_or(SharedError::NotInitialized)?;
+        let fee = apply_bps(assets, config.withdraw_fee_bps)?;
+        let after_fee = safe_sub(assets, fee)?;
+
+        // Unstaking inside the early-exit window forfeits part of the payout
+        let early_exit: Option<EarlyExit> = env.storage().instance().get(&DataKey::EarlyExit);
+        let early_exit = early_exit.filter(|exit| {
+            env.ledger().timestamp() < user_stake.stake_time.saturating_add(exit.window)
+        });
+        let penalty = match &early_exit {
+            Some(exit) => exit.penalty_bps.apply(after_fee)?,
+            None => 0,
+        };
+        let payout = safe_sub(after_fee, penalty)?;
+
+        let token_client = token::Client::new(env, &stake_token);
+        token_client.transfer(&env.current_contract_address(), user, &payout);
+
+        Self::route_fee(env, &stake_token, fee)?;
+        if let Some(exit) = early_exit {
+            if penalty > 0 {
+                Self::route_penalty(env, &stake_token, user, &exit.routing, penalty)?;
+            }
+        }
+
+        emit_unstake(env, user, amount, remaining, fee);
+        extend_instance_ttl(env);
+
+        Ok((payout, remaining))
+    }
+
+    fn require_admin(env: &Env) -> Result<(), SharedError> {
+        let admin: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::Admin)
+            .ok_or(SharedError::NotInitialized)?;
+
+        admin.require_auth();
+        Ok(())
+    }
+
+    /// Require `caller`'s auth and that it is the admin or holds `role`
+    fn require_role(env: &Env, caller: &Address, role: &Symbol) -> Result<(), SharedError> {
+        let admin: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::Admin)
+            .ok_or(SharedError::NotInitialized)?;
+        access_control::require_role(env, role, caller, &admin)
+    }
+
+    fn get_user_stake(env: &Env, user: &Address) -> UserStake {
+        get_persistent(env, &DataKey::UserStake(user.clone())).unwrap_or_else(|| {
+            // Create new UserStake with empty reward_debts Map
+            // FIX #H1: Initialize Map properly for multi-token reward tracking
+            use soroban_sdk::Map;
+            UserStake {
+                amount: 0,
+                stake_time: env.ledger().timestamp(),
+                last_claim_time: env.ledger().timestamp(),
+                reward_debts: Map::new(env),
+            }
+        })
+    }
+
+    fn get_total_staked(env: &Env) -> i128 {
+        env.storage()
+            .instance()
+            .get(&DataKey::TotalStaked)
+            .unwrap_or(0)
+    }
+
+    fn get_acc_reward_per_share(env: &Env, reward_token: &Address) -> i128 {
+        get_persistent(env, &DataKey::AccRewardPerShare(reward_token.clone())).unwrap_or(0)
+    }
+
+    fn get_total_rewards(env: &Env, reward_token: &Address) -> i128 {
+        get_persistent(env, &DataKey::TotalRewards(reward_token.clone())).unwrap_or(0)
+    }
+
+    fn get_reward_tokens(env: &Env) -> Vec<Address> {
+        env.storage()
+            .instance()
+            .get(&DataKey::RewardTokens)
+            .unwrap_or(Vec::new(env))
+    }
+
+    fn get_allowed_reward_tokens(env: &Env) -> Vec<Address> {
+        env.storage()
+            .instance()
+            .get(&DataKey::AllowedRewardTokens)
+            .unwrap_or(Vec::new(env))
+    }
+
+    fn get_max_reward_tokens(env: &Env) -> u32 {
+        env.storage()
+            .instance()
+            .get(&DataKey::MaxRewardTokens)
+            .unwrap_or(DEFAULT_MAX_REWARD_TOKENS)
+    }
+
+    fn load_vote_locks(env: &Env, user: &Address) -> Vec<VoteLock> {
+        get_persistent(env, &DataKey::VoteLocks(user.clone())).unwrap_or(Vec::new(env))
+    }
+
+    /// Largest stake pinned by any active vote lock
+    fn get_vote_locked_amount(env: &Env, user: &Address) -> i128 {
+        let now = env.ledger().timestamp();
+        let mut locked = 0;
+        for lock in Self::load_vote_locks(env, user).iter() {
+            if lock.until > now && lock.amount > locked {
+                locked = lock.amount;
+            }
+        }
+        locked
+    }
+
+    fn get_frozen_reward_tokens(env: &Env) -> Vec<Address> {
+        env.storage()
+            .instance()
+            .get(&DataKey::FrozenRewardTokens)
+            .unwrap_or(Vec::new(env))
+    }
+
+    fn get_reward_liability(env: &Env, reward_token: &Address) -> i128 {
+        get_persistent(env, &DataKey::RewardLiability(reward_token.clone())).unwrap_or(0)
+    }
+
+    fn add_reward_token(env: &Env, token: &Address) -> Result<(), SharedError> {
+        let mut tokens = Self::get_reward_tokens(env);
+
+        // Check if already in list
+        for t in tokens.iter() {
+            if t == *token {
+                return Ok(());
+            }
+        }
+
+        // New tokens must be allowlisted and fit under the cap
+        let mut allowed = false;
+        for t in Self::get_allowed_reward_tokens(env).iter() {
+            if t == *token {
+                allowed = true;
+                break;
+            }
+        }
+        if !allowed {
+            return Err(SharedError::TokenNotFound);
+        }
+        if tokens.len() >= Self::get_max_reward_tokens(env) {
+            return Err(SharedError::LimitExceeded);
+        }
+
+        tokens.push_back(token.clone());
+        env.storage()
+            .instance()
+            .set(&DataKey::RewardTokens, &tokens);
+
+        Ok(())
+    }
+
+    fn checkpoint_key(owner: &Option<Address>, index: u32) -> DataKey {
+        match owner {
+            Some(user) => DataKey::UserCheckpoint(user.clone(), index),
+            None => DataKey::TotalCheckpoint(index),
+        }
+    }
+
+    fn checkpoint_count_key(owner: &Option<Address>) -> DataKey {
+        match owner {
+            Some(user) => DataKey::UserCheckpointCount(user.clone()),
+            None => DataKey::TotalCheckpointCount,
+        }
+    }
+
+    fn get_checkpoint_count(env: &Env, owner: &Option<Address>) -> u32 {
+        get_persistent(env, &Self::checkpoint_count_key(owner)).unwrap_or(0)
+    }
+
+    fn get_checkpoint(env: &Env, owner: &Option<Address>, index: u32) -> Option<Checkpoint> {
+        get_persistent(env, &Self::checkpoint_key(owner, index))
+    }
+
+    /// Append a checkpoint, overwriting the latest one if it has the same timestamp
+    fn write_checkpoint(env: &Env, owner: &Option<Address>, amount: i128) {
+        let timestamp = env.ledger().timestamp();
+        let count = Self::get_checkpoint_count(env, owner);
+
+        let index = match count.checked_sub(1) {
+            Some(last) => match Self::get_checkpoint(env, owner, last) {
+                Some(cp) if cp.timestamp == timestamp => last,
+                _ => count,
+            },
+            None => 0,
+        };
+
+        let key = Self::checkpoint_key(owner, index);
+        set_persistent(env, &key, &Checkpoint { timestamp, amount });
+
+        if index == count {
+            let count_key = Self::checkpoint_count_key(owner);
+            set_persistent(env, &count_key, &(count + 1));
+        }
+    }
+
+    fn get_staker_count(env: &Env) -> u32 {
+        env.storage()
+            .instance()
+            .get(&DataKey::StakerCount)
+            .unwrap_or(0)
+    }
+
+    /// Add a user to the staker registry when their stake becomes non-zero,
+    /// and swap-remove them when it drops to zero
+    fn sync_staker_registry(env: &Env, user: &Address, amount: i128) {
+        let index_key = DataKey::StakerIndex(user.clone());
+        let existing: Option<u32> = get_persistent(env, &index_key);
+        let count = Self::get_staker_count(env);
+
+        match (existing, amount > 0) {
+            (None, true) => {
+                set_persistent(env, &DataKey::StakerAt(count), user);
+                set_persistent(env, &index_key, &count);
+                env.storage()
+                    .instance()
+                    .set(&DataKey::StakerCount, &(count + 1));
+            }
+            (Some(index), false) => {
+                let last = count - 1;
+                if index != last {
+                    let moved: Address = get_persistent(env, &DataKey::StakerAt(last)).unwrap();
+                    set_persistent(env, &DataKey::StakerAt(index), &moved);
+                    set_persistent(env, &DataKey::StakerIndex(moved), &index);
+                }
+                env.storage().persistent().remove(&DataKey::StakerAt(last));
+                env.storage().persistent().remove(&index_key);
+                env.storage().instance().set(&DataKey::StakerCount, &last);
+            }
+            _ => {}
+        }
+    }
+
+    fn record_checkpoints(env: &Env, user: &Address, user_amount: i128, total: i128) {
+        Self::write_checkpoint(env, &Some(user.clone()), user_amount);
+        Self::write_checkpoint(env, &None, total);
+    }
+
+    /// Binary search for the latest checkpoint at or before `timestamp`
+    fn checkpoint_lookup(env: &Env, owner: &Option<Address>, timestamp: u64) -> i128 {
+        let count = Self::get_checkpoint_count(env, owner);
+        let mut low = 0u32;
+        let mut high = count;
+
+        while low < high {
+            let mid = low + (high - low) / 2;
+            match Self::get_checkpoint(env, owner, mid) {
+                Some(cp) if cp.timestamp <= timestamp => low = mid + 1,
+                _ => high = mid,
+            }
+        }
+
+        if low == 0 {
+            return 0;
+        }
+        Self::get_checkpoint(env, owner, low - 1)
+            .map(|cp| cp.amount)
+            .unwrap_or(0)
+    }
+
+    fn get_reward_expiry(env: &Env) -> u64 {
+        env.storage()
+            .instance()
+            .get(&DataKey::RewardExpiry)
+            .unwrap_or(0)
+    }
+
+    /// Record reward-per-share for a token, overwriting a same-timestamp entry
+    fn write_acc_checkpoint(env: &Env, reward_token: &Address, acc: i128) {
+        let timestamp = env.ledger().timestamp();
+        let count_key = DataKey::AccCheckpointCount(reward_token.clone());
+        let count: u32 = get_persistent(env, &count_key).unwrap_or(0);
+
+        let index = match count.checked_sub(1) {
+            Some(last) => match get_persistent::<DataKey, Checkpoint>(
+                env,
+                &DataKey::AccCheckpoint(reward_token.clone(), last),
+            ) {
+                Some(cp) if cp.timestamp == timestamp => last,
+                _ => count,
+            },
+            None => 0,
+        };
+
+        let key = DataKey::AccCheckpoint(reward_token.clone(), index);
+        set_persistent(
+            env,
+            &key,
+            &Checkpoint {
+                timestamp,
+                amount: acc,
+            },
+        );
+
+        if index == count {
+            set_persistent(env, &count_key, &(count + 1));
+        }
+    }
+
+    /// Reward-per-share of a token as of `timestamp` (binary search)
+    fn acc_per_share_at(env: &Env, reward_token: &Address, timestamp: u64) -> i128 {
+        let count: u32 =
+            get_persistent(env, &DataKey::AccCheckpointCount(reward_token.clone())).unwrap_or(0);
+        let checkpoint = |index: u32| -> Option<Checkpoint> {
+            get_persistent(env, &DataKey::AccCheckpoint(reward_token.clone(), index))
+        };
+
+        let mut low = 0u32;
+        let mut high = count;
+        while low < high {
+            let mid = low + (high - low) / 2;
+            match checkpoint(mid) {
+                Some(cp) if cp.timestamp <= timestamp => low = mid + 1,
+                _ => high = mid,
+            }
+        }
+
+        if low == 0 {
+            return 0;
+        }
+        checkpoint(low - 1).map(|cp| cp.amount).unwrap_or(0)
+    }
+
+    /// Unclaimed rewards of a token that accrued at or before `cutoff`.
+    /// Stake and reward debt are constant since the last harvest, so this is
+    /// the user's accrual up to the cutoff minus their debt.
+    fn stale_rewards(
+        env: &Env,
+        user_stake: &UserStake,
+        reward_token: &Address,
+        cutoff: u64,
+    ) -> Result<i128, SharedError> {
+        let acc_at_cutoff = Self::acc_per_share_at(env, reward_token, cutoff);
+        let stale = Self::calculate_pending(
+            &user_stake.amount,
+            acc_at_cutoff,
+            user_stake.get_reward_debt(reward_token),
+        )?;
+        Ok(stale.max(0))
+    }
+
+    /// Calculate pending rewards with proper error handling (C2 fix)
+    fn calculate_pending(
+        stake_amount: &i128,
+        acc_per_share: i128,
+        reward_debt: i128,
+    ) -> Result<i128, SharedError> {
+        let accumulated = safe_div(safe_mul(*stake_amount, acc_per_share)?, PRECISION)?;
+        safe_sub(accumulated, reward_debt)
+    }
+
+    /// Send a stake fee to the fee distributor. Fees it rejects (paused,
+    /// source not allowlisted) are held in the pool outside the pool assets,
+    /// and go out with the next fee sent (or `flush_fees`), so a failing
+    /// distributor never blocks stakes or unstakes.
+    fn route_fee(env: &Env, stake_token: &Address, fee: i128) -> Result<(), SharedError> {
+        // Dust below the distributor's minimum stays in the pool
+        if fee < MIN_FEE_AMOUNT {
+            return Ok(());
+        }
+
+        let fee_distributor: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::FeeDistributor)
+            .ok_or(SharedError::NotInitialized)?;
+        let held = safe_add(Self::get_pending_fees(env), fee)?;
+
+        // The distributor pulls the fee from this contract
+        Self::authorize_transfer(env, stake_token, &fee_distributor, held);
+        let sent = FeeDistributorClient::new(env, &fee_distributor)
+            .try_receive_fees(&env.current_contract_address(), stake_token, &held)
+            .into_shared()
+            .is_ok();
+        if sent {
+            env.storage().instance().remove(&DataKey::PendingFees);
+        } else {
+            env.storage().instance().set(&DataKey::PendingFees, &held);
+
+            let events = EventBuilder::new(env);
+            events.publish(
+                "staking",
+                "fee_held",
+                (stake_token.clone(), fee, held, env.ledger().timestamp()),
+            );
+        }
+        Ok(())
+    }
+
+    /// Let a contract called next pull `amount` of `token` from the pool
+    fn authorize_transfer(env: &Env, token: &Address, to: &Address, amount: i128) {
+        let this = env.current_contract_address();
+        env.authorize_as_current_contract(vec![
+            env,
+            InvokerContractAuthEntry::Contract(SubContractInvocation {
+                context: ContractContext {
+                    contract: token.clone(),
+                    fn_name: Symbol::new(env, "transfer"),
+                    args: (this, to.clone(), amount).into_val(env),
+                },
+                sub_invocations: Vec::new(env),
+            }),
+        ]);
+    }
+
+    /// Split an early-exit penalty between the stakers (through the fee
+    /// distributor), burn and treasury per `routing`
+    fn route_penalty(
+        env: &Env,
+        stake_token: &Address,
+        user: &Address,
+        routing: &PenaltyRouting,
+        penalty: i128,
+    ) -> Result<(), SharedError> {
+        let this = env.current_contract_address();
+        let token_client = token::Client::new(env, stake_token);
+
+        let split = routing.split(penalty)?;
+
+        Self::route_fee(env, stake_token, split.to_stakers)?;
+
+        let mut burned = true;
+        if split.to_burn > 0 {
+            burned = matches!(token_client.try_burn(&this, &split.to_burn), Ok(Ok(())));
+            if !burned {
+                let burn_address: Address = env
+                    .storage()
+                    .instance()
+                    .get(&DataKey::BurnAddress)
+                    .ok_or(SharedError::NotInitialized)?;
+                token_client.transfer(&this, &burn_address, &split.to_burn);
+            }
+        }
+
+        if split.to_treasury > 0 {
+            let treasury: Address = env
+                .storage()
+                .instance()
+                .get(&DataKey::Treasury)
+                .ok_or(SharedError::NotInitialized)?;
+            token_client.transfer(&this, &treasury, &split.to_treasury);
+        }
+
+        emit_penalty_routed(env, stake_token, user, &split, burned);
+        Ok(())
+    }
+
+    /// Internal harvest with checks-effects-interactions pattern (C1 fix - reentrancy protection)
+    /// Spread `amount` of a reward token over `total_staked` through
+    /// reward-per-share
+    fn accrue_rewards(
+        env: &Env,
+        reward_token: &Address,
+        amount: i128,
+        total_staked: i128,
+    ) -> Result<(), SharedError> {
+        // Update accumulated rewards
+        let current_acc = Self::get_acc_reward_per_share(env, reward_token);
+        let reward_per_share = safe_div(safe_mul(amount, PRECISION)?, total_staked)?;
+        let new_acc = safe_add(current_acc, reward_per_share)?;
+        set_persistent(
+            env,
+            &DataKey::AccRewardPerShare(reward_token.clone()),
+            &new_acc,
+        );
+        Self::write_acc_checkpoint(env, reward_token, new_acc);
+
+        // Update total rewards
+        let total_rewards = Self::get_total_rewards(env, reward_token);
+        set_persistent(
+            env,
+            &DataKey::TotalRewards(reward_token.clone()),
+            &safe_add(total_rewards, amount)?,
+        );
+
+        let liability = Self::get_reward_liability(env, reward_token);
+        set_persistent(
+            env,
+            &DataKey::RewardLiability(reward_token.clone()),
+            &safe_add(liability, amount)?,
+        );
+        Ok(())
+    }
+
+    /// Rewards held in `reward_token` that are not the pool's to spend:
+    /// unclaimed (including still-vesting) plus orphaned
+    fn reward_obligations(env: &Env, reward_token: &Address) -> Result<i128, SharedError> {
+        safe_add(
+            Self::get_reward_liability(env, reward_token),
+            Self::get_orphaned_rewards(env, reward_token),
+        )
+    }
+
+    /// Stake tokens held but not owed to stakers: reward obligations in the
+    /// stake token plus fees held for the fee distributor
+    fn stake_token_obligations(env: &Env, stake_token: &Address) -> Result<i128, SharedError> {
+        safe_add(
+            Self::reward_obligations(env, stake_token)?,
+            Self::get_pending_fees(env),
+        )
+    }
+
+    /// Stake tokens held for stakers: the balance minus rewards owed and
+    /// fees held in the stake token. Rebases and transfer fees show up here,
+    /// not in shares.
+    fn pool_assets(env: &Env, stake_token: &Address) -> Result<i128, SharedError> {
+        let balance = token::Client::new(env, stake_token).balance(&env.current_contract_address());
+        let owed = Self::stake_token_obligations(env, stake_token)?;
+        Ok(safe_sub(balance, owed)?.max(0))
+    }
+
+    /// `(total_shares, pool_assets)`, each with `VIRTUAL_OFFSET` added
+    fn share_price(env: &Env, stake_token: &Address) -> Result<(i128, i128), SharedError> {
+        Ok((
+            safe_add(Self::get_total_staked(env), VIRTUAL_OFFSET)?,
+            safe_add(Self::pool_assets(env, stake_token)?, VIRTUAL_OFFSET)?,
+        ))
+    }
+
+    fn assets_to_shares(
+        env: &Env,
+        stake_token: &Address,
+        assets: i128,
+    ) -> Result<i128, SharedError> {
+        let (total_shares, pool) = Self::share_price(env, stake_token)?;
+        mul_div_down(assets, total_shares, pool)
+    }
+
+    fn shares_to_assets(
+        env: &Env,
+        stake_token: &Address,
+        shares: i128,
+    ) -> Result<i128, SharedError> {
+        let (total_shares, pool) = Self::share_price(env, stake_token)?;
+        mul_div_down(shares, pool, total_shares)
+    }
+
+    fn shares_to_assets_up(
+        env: &Env,
+        stake_token: &Address,
+        shares: i128,
+    ) -> Result<i128, SharedError> {
+        let (total_shares, pool) = Self::share_price(env, stake_token)?;
+        mul_div_up(shares, pool, total_shares)
+    }
+
+    /// Smallest amount that leaves at least `net` after a `fee_bps` fee
+    fn gross_up(net: i128, fee_bps: u32) -> Result<i128, SharedError> {
+        mul_div_up(net, BPS_DENOMINATOR, BPS_DENOMINATOR - fee_bps as i128)
+    }
+
+    fn get_reward_vesting_period(env: &Env) -> u64 {
+        env.storage()
+            .instance()
+            .get(&DataKey::RewardVestingPeriod)
+            .unwrap_or(0)
+    }
+
+    fn get_early_claim_haircut(env: &Env) -> Bps {
+        env.storage()
+            .instance()
+            .get(&DataKey::EarlyClaimHaircut)
+            .unwrap_or(Bps::ZERO)
+    }
+
+    /// Add rewards harvested for `user` to their vesting schedule (restarting
+    /// it for the combined unvested amount) and release what has vested.
+    /// Returns the amount payable now.
+    fn vest_rewards(
+        env: &Env,
+        user: &Address,
+        reward_token: &Address,
+        harvested: i128,
+    ) -> Result<i128, SharedError> {
+        let key = DataKey::RewardVest(user.clone(), reward_token.clone());
+        let existing: Option<RewardVest> = get_persistent(env, &key);
+        let period = Self::get_reward_vesting_period(env);
+        if period == 0 && existing.is_none() {
+            return Ok(harvested);
+        }
+
+        let now = env.ledger().timestamp();
+        let mut vest = existing.unwrap_or(RewardVest {
+            amount: 0,
+            start: now,
+            end: now,
+        });
+
+        let mut payable = if now >= vest.end {
+            vest.amount
+        } else {
+            mul_div_down(
+                vest.amount,
+                (now - vest.start) as i128,
+                (vest.end - vest.start) as i128,
+            )?
+        };
+        vest.amount = safe_sub(vest.amount, payable)?;
+        vest.start = now;
+
+        if period == 0 {
+            payable = safe_add(payable, harvested)?;
+        } else if harvested > 0 {
+            vest.amount = safe_add(vest.amount, harvested)?;
+            vest.end = now.saturating_add(period);
+
+            let events = EventBuilder::new(env);
+            events.publish(
+                "staking",
+                "rewards_vesting",
+                (user.clone(), reward_token.clone(), harvested, vest.end),
+            );
+        }
+
+        if vest.amount > 0 {
+            set_persistent(env, &key, &vest);
+        } else {
+            env.storage().persistent().remove(&key);
+        }
+        Ok(payable)
+    }
+
+    /// Spread `amount` over every staker except `user` (orphaned when
+    /// nobody else is staked)
+    fn credit_other_stakers(
+        env: &Env,
+        user: &Address,
+        reward_token: &Address,
+        amount: i128,
+    ) -> Result<(), SharedError> {
+        let mut user_stake = Self::get_user_stake(env, user);
+        let others = safe_sub(Self::get_total_staked(env), user_stake.amount)?;
+        if others <= 0 {
+            let orphaned = Self::get_orphaned_rewards(env, reward_token);
+            set_persistent(
+                env,
+                &DataKey::OrphanedRewards(reward_token.clone()),
+                &safe_add(orphaned, amount)?,
+            );
+            return Ok(());
+        }
+
+        Self::accrue_rewards(env, reward_token, amount, others)?;
+
+        // `user` was just harvested; moving their debt to the new
+        // reward-per-share keeps them out of this accrual
+        if user_stake.amount > 0 {
+            let acc_per_share = Self::get_acc_reward_per_share(env, reward_token);
+            let debt = safe_div(safe_mul(user_stake.amount, acc_per_share)?, PRECISION)?;
+            user_stake.set_reward_debt(reward_token, debt);
+            set_persistent(env, &DataKey::UserStake(user.clone()), &user_stake);
+        }
+        Ok(())
+    }
+
+    fn get_orphaned_rewards(env: &Env, reward_token: &Address) -> i128 {
+        get_persistent(env, &DataKey::OrphanedRewards(reward_token.clone())).unwrap_or(0)
+    }
+
+    fn get_pending_fees(env: &Env) -> i128 {
+        env.storage()
+            .instance()
+            .get(&DataKey::PendingFees)
+            .unwrap_or(0)
+    }
+
+    /// Add a payout to the user's all-time claimed total
+    fn record_claimed(
+        env: &Env,
+        user: &Address,
+        reward_token: &Address,
+        amount: i128,
+    ) -> Result<(), SharedError> {
+        let key = DataKey::TotalClaimed(user.clone(), reward_token.clone());
+        let claimed: Option<i128> = get_persistent(env, &key);
+        if claimed.is_none() {
+            let tokens_key = DataKey::ClaimedTokens(user.clone());
+            let mut tokens: Vec<Address> =
+                get_persistent(env, &tokens_key).unwrap_or(Vec::new(env));
+            tokens.push_back(reward_token.clone());
+            set_persistent(env, &tokens_key, &tokens);
+        }
+        set_persistent(env, &key, &safe_add(claimed.unwrap_or(0), amount)?);
+        Ok(())
+    }
+
+    fn internal_harvest(
+        env: &Env,
+        user: &Address,
+        user_stake: &mut UserStake,
+        reward_tokens: &Vec<Address>,
+    ) -> Result<Vec<(Address, i128)>, SharedError> {
+        let mut rewards = Vec::new(env);
+        let frozen = Self::get_frozen_reward_tokens(env);
+
+        // CHECKS: Calculate all pending rewards first
+        let mut pending_transfers: Vec<(Address, i128)> = Vec::new(env);
+        for reward_token in reward_tokens.iter() {
+            let mut pending = 0;
+            if user_stake.amount > 0 {
+                let acc_per_share = Self::get_acc_reward_per_share(env, &reward_token);
+                // FIX #H1: Use get_reward_debt() to read per-token debt
+                let reward_debt = user_stake.get_reward_debt(&reward_token);
+                pending = Self::calculate_pending(&user_stake.amount, acc_per_share, reward_debt)
+                    .unwrap_or(0); // Safe: overflow means 0 pending
+            }
+
+            // Frozen tokens are not paid out; accrued rewards are carried
+            // over so the caller can reset reward debt as usual
+            let carried_key = DataKey::FrozenRewards(user.clone(), reward_token.clone());
+            let carried: i128 = get_persistent(env, &carried_key).unwrap_or(0);
+            if frozen.contains(&reward_token) {
+                if pending > 0 {
+                    set_persistent(env, &carried_key, &safe_add(carried, pending)?);
+                }
+                continue;
+            }
+            if carried > 0 {
+                pending = safe_add(pending, carried)?;
+                env.storage().persistent().remove(&carried_key);
+            }
+
+            // Under reward vesting only the vested part is paid now
+            let pending = Self::vest_rewards(env, user, &reward_token, pending)?;
+            if pending > 0 {
+                pending_transfers.push_back((reward_token.clone(), pending));
+            }
+        }
+
+        // EFFECTS: Update state BEFORE external calls (reentrancy protection)
+        user_stake.last_claim_time = env.ledger().timestamp();
+        for (reward_token, pending) in pending_transfers.iter() {
+            let liability = Self::get_reward_liability(env, &reward_token);
+            set_persistent(
+                env,
+                &DataKey::RewardLiability(reward_token.clone()),
+                &(liability - pending),
+            );
+            Self::record_claimed(env, user, &reward_token, pending)?;
+        }
+
+        // INTERACTIONS: Now perform external token transfers
+        for (reward_token, pending) in pending_transfers.iter() {
+            let token_client = token::Client::new(env, &reward_token);
+            token_client.transfer(&env.current_contract_address(), user, &pending);
+
+            emit_claim(env, user, &reward_token, pending);
+            rewards.push_back((reward_token, pending));
+        }
+
+        Ok(rewards)
+    }
+}
+
+// ════════════════════════════════════════════════════════════════════════════
+// Tests
+// ════════════════════════════════════════════════════════════════════════════
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use astro_core_shared::testutils::{create_token, default_staking_config};
+    use soroban_sdk::{
+        symbol_short,
+        testutils::{Address as _, Ledger as _},
+    };
+
+    /// Token that burns 10% of every transfer
+    #[contract]
+    pub struct MockTaxToken;
+
+    #[contractimpl]
+    impl MockTaxToken {
+        pub fn mint(env: Env, to: Address, amount: i128) {
+            let balance = Self::balance(env.clone(), to.clone());
+            env.storage().persistent().set(&to, &(balance + amount));
+        }
+
+        pub fn balance(env: Env, id: Address) -> i128 {
+            env.storage().persistent().get(&id).unwrap_or(0)
+        }
+
+        pub fn transfer(env: Env, from: Address, to: Address, amount: i128) {
+            from.require_auth();
+            let from_balance = Self::balance(env.clone(), from.clone());
+            let to_balance = Self::balance(env.clone(), to.clone());
+            env.storage()
+                .persistent()
+                .set(&from, &(from_balance - amount));
+            env.storage()
+                .persistent()
+                .set(&to, &(to_balance + amount - amount / 10));
+        }
+    }
+
+    /// Minimal fee distributor that pulls fees from the caller. Rejects
+    /// them while paused and, once sources are set, from anyone else.
+    #[contract]
+    pub struct MockFeeDistributor;
+
+    #[contractimpl]
+    impl MockFeeDistributor {
+        pub fn set_paused(env: Env, paused: bool) {
+            env.storage()
+                .instance()
+                .set(&symbol_short!("paused"), &paused);
+        }
+
+        pub fn set_sources(env: Env, sources: Vec<Address>) {
+            env.storage()
+                .instance()
+                .set(&symbol_short!("sources"), &sources);
+        }
+
+        pub fn receive_fees(
+            env: Env,
+            caller: Address,
+            token: Address,
+            amount: i128,
+        ) -> Result<(), SharedError> {
+            caller.require_auth();
+            let storage = env.storage().instance();
+            if storage.get(&symbol_short!("paused")).unwrap_or(false) {
+                return Err(SharedError::ContractPaused);
+            }
+            let sources: Option<Vec<Address>> = storage.get(&symbol_short!("sources"));
+            if sources.is_some_and(|sources| !sources.contains(&caller)) {
+                return Err(SharedError::Unauthorized);
+            }
+            token::Client::new(&env, &token).transfer(
+                &caller,
+                env.current_contract_address(),
+                &amount,
+            );
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_initialize() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let contract_id = env.register(StakingPool, ());
+        let client = StakingPoolClient::new(&env, &contract_id);
+
+        let admin = Address::generate(&env);
+        let stake_token = Address::generate(&env);
+        let fee_distributor = Address::generate(&env);
+
+        client.initialize(
+            &admin,
+            &stake_token,
+            &fee_distributor,
+            &default_staking_config(),
+        );
+
+        assert_eq!(client.admin(), admin);
+        assert_eq!(client.stake_token(), stake_token);
+        assert_eq!(client.total_staked(), 0);
+    }
+
+    #[test]
+    fn test_stake_and_unstake() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let contract_id = env.register(StakingPool, ());
+        let client = StakingPoolClient::new(&env, &contract_id);
+
+        let admin = Address::generate(&env);
+        let fee_distributor = Address::generate(&env);
+        let user = Address::generate(&env);
+
+        // Create stake token
+        let stake_token = create_token(&env, &admin);
+        stake_token.mint(&user, 1_000_000_000_000); // 100,000 tokens
+
+        client.initialize(
+            &admin,
+            &stake_token.address,
+            &fee_distributor,
+            &default_staking_config(),
+        );
+
+        // Stake
+        let stake_amount = 100_000_000_000_i128; // 10,000 tokens
+        let new_balance = client.stake(&user, &stake_amount, &None);
+        assert_eq!(new_balance, stake_amount);
+        assert_eq!(client.total_staked(), stake_amount);
+
+        // Check user stake
+        let user_stake = client.get_stake(&user);
+        assert_eq!(user_stake.amount, stake_amount);
+
+        // Unstake half
+        let unstake_amount = 50_000_000_000_i128;
+        let remaining = client.unstake(&user, &unstake_amount, &None);
+        assert_eq!(remaining, stake_amount - unstake_amount);
+        assert_eq!(client.total_staked(), stake_amount - unstake_amount);
+    }
+
+    #[test]
+    fn test_stake_deadlines() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let client = StakingPoolClient::new(&env, &env.register(StakingPool, ()));
+        let admin = Address::generate(&env);
+        let user = Address::generate(&env);
+        let stake_token = create_token(&env, &admin);
+        stake_token.mint(&user, 1_000_000_000_000);
+        client.initialize(
+            &admin,
+            &stake_token.address,
+            &Address::generate(&env),
+            &default_staking_config(),
+        );
+
+        // Deadlines in the past are rejected
+        let amount = default_staking_config().min_stake_amount;
+        env.ledger().set_timestamp(1000);
+        assert_eq!(
+            client.try_stake(&user, &amount, &Some(999)),
+            Err(Ok(SharedError::DeadlineExpired))
+        );
+        client.stake(&user, &amount, &Some(1000));
+        assert_eq!(
+            client.try_unstake(&user, &amount, &Some(999)),
+            Err(Ok(SharedError::DeadlineExpired))
+        );
+        client.unstake(&user, &amount, &Some(1000));
+        assert_eq!(client.total_staked(), 0);
+    }
+
+    #[test]
+    fn test_orphaned_rewards() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let client = StakingPoolClient::new(&env, &env.register(StakingPool, ()));
+        let admin = Address::generate(&env);
+        let fee_distributor = Address::generate(&env);
+        let user = Address::generate(&env);
+
+        let stake_token = create_token(&env, &admin);
+        let reward_token = create_token(&env, &admin);
+        stake_token.mint(&user, 1_000_000_000_000);
+        reward_token.mint(&fee_distributor, 1_000_000_000_000);
+        client.initialize(
+            &admin,
+            &stake_token.address,
+            &fee_distributor,
+            &default_staking_config(),
+        );
+        client.allow_reward_token(&reward_token.address);
+
+        // No stakers: rewards are held, not credited
+        client.add_rewards(&fee_distributor, &reward_token.address, &5_000_000_000);
+        assert_eq!(
+            client.orphaned_rewards(&reward_token.address),
+            5_000_000_000
+        );
+        assert_eq!(client.reward_liability(&reward_token.address), 0);
+        assert!(client
+            .try_redistribute_orphaned(&admin, &reward_token.address)
+            .is_err());
+
+        client.stake(&user, &100_000_000_000, &None);
+
+        // Only admin or keepers
+        let keeper = Address::generate(&env);
+        assert!(client
+            .try_redistribute_orphaned(&keeper, &reward_token.address)
+            .is_err());
+        client.grant_role(&KEEPER, &keeper);
+        assert_eq!(
+            client.redistribute_orphaned(&keeper, &reward_token.address),
+            5_000_000_000
+        );
+        assert_eq!(client.orphaned_rewards(&reward_token.address), 0);
+
+        client.claim(&user);
+        assert_eq!(reward_token.balance(&user), 5_000_000_000);
+    }
+
+    #[test]
+    fn test_add_rewards_and_claim() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let contract_id = env.register(StakingPool, ());
+        let client = StakingPoolClient::new(&env, &contract_id);
+
+        let admin = Address::generate(&env);
+        let fee_distributor = Address::generate(&env);
+        let user = Address::generate(&env);
+
+        // Create tokens
+        let stake_token = create_token(&env, &admin);
+        let reward_token = create_token(&env, &admin);
+
+        stake_token.mint(&user, 1_000_000_000_000);
+        reward_token.mint(&fee_distributor, 1_000_000_000_000);
+
+        client.initialize(
+            &admin,
+            &stake_token.address,
+            &fee_distributor,
+            &default_staking_config(),
+        );
+
+        // User stakes
+        client.stake(&user, &100_000_000_000, &None);
+
+        // Add rewards
+        client.allow_reward_token(&reward_token.address);
+        client.add_rewards(&fee_distributor, &reward_token.address, &10_000_000_000);
+
+        // Check pending rewards
+        let pending = client.pending_rewards(&user);
+        assert_eq!(pending.len(), 1);
+        let (token, amount) = pending.get(0).unwrap();
+        assert_eq!(token, reward_token.address);
+        assert_eq!(amount, 10_000_000_000);
+
+        // Claim rewards
+        client.claim(&user);
+
+        // Verify user received rewards
+        assert_eq!(reward_token.balance(&user), 10_000_000_000);
+
+        assert_eq!(
+            client.total_claimed(&user, &reward_token.address),
+            10_000_000_000
+        );
+        assert_eq!(
+            client.lifetime_rewards(&user),
+            vec![&env, (reward_token.address.clone(), 10_000_000_000_i128)]
+        );
+
+        let report = client.solvency_report();
+        assert_eq!(report.len(), 2);
+        assert_eq!(report.get(0).unwrap().liabilities, 100_000_000_000);
+        assert!(report.iter().all(|entry| entry.is_solvent()));
+    }
+
+    #[test]
+    fn test_get_apr_trailing_window() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let contract_id = env.register(StakingPool, ());
+        let client = StakingPoolClient::new(&env, &contract_id);
+
+        let admin = Address::generate(&env);
+        let fee_distributor = Address::generate(&env);
+        let user = Address::generate(&env);
+
+        let stake_token = create_token(&env, &admin);
+        let reward_token = create_token(&env, &admin);
+        stake_token.mint(&user, 1_000_000_000_000);
+        reward_token.mint(&fee_distributor, 1_000_000_000_000);
+
+        client.initialize(
+            &admin,
+            &stake_token.address,
+            &fee_distributor,
+            &default_staking_config(),
+        );
+        client.allow_reward_token(&reward_token.address);
+        assert_eq!(client.get_apr(&reward_token.address), 0);
+
+        // 1% of the stake paid out within 30 days annualizes to ~12.16%
+        env.ledger().set_timestamp(10_000_000);
+        client.stake(&user, &100_000_000_000, &None);
+        client.add_rewards(&fee_distributor, &reward_token.address, &1_000_000_000);
+        assert_eq!(client.get_apr(&reward_token.address), 1_216);
+
+        // Once the rewards fall out of the window the rate drops back
+        env.ledger().set_timestamp(10_000_000 + APR_WINDOW + 1);
+        assert_eq!(client.get_apr(&reward_token.address), 0);
+    }
+
+    #[test]
+    fn test_multiple_stakers() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let contract_id = env.register(StakingPool, ());
+        let client = StakingPoolClient::new(&env, &contract_id);
+
+        let admin = Address::generate(&env);
+        let fee_distributor = Address::generate(&env);
+        let user1 = Address::generate(&env);
+        let user2 = Address::generate(&env);
+
+        let stake_token = create_token(&env, &admin);
+        let reward_token = create_token(&env, &admin);
+
+        stake_token.mint(&user1, 1_000_000_000_000);
+        stake_token.mint(&user2, 1_000_000_000_000);
+        reward_token.mint(&fee_distributor, 1_000_000_000_000);
+
+        client.initialize(
+            &admin,
+            &stake_token.address,
+            &fee_distributor,
+            &default_staking_config(),
+        );
+
+        // User1 stakes 75%, User2 stakes 25%
+        client.stake(&user1, &75_000_000_000, &None);
+        client.stake(&user2, &25_000_000_000, &None);
+
+        // Add rewards
+        client.allow_reward_token(&reward_token.address);
+        client.add_rewards(&fee_distributor, &reward_token.address, &100_000_000_000);
+
+        // User1 should get 75% of rewards, User2 gets 25%
+        let pending1 = client.pending_rewards(&user1);
+        let pending2 = client.pending_rewards(&user2);
+
+        let (_, amount1) = pending1.get(0).unwrap();
+        let (_, amount2) = pending2.get(0).unwrap();
+
+        // 75% of 100B = 75B
+        assert_eq!(amount1, 75_000_000_000);
+        // 25% of 100B = 25B
+        assert_eq!(amount2, 25_000_000_000);
+    }
+
+    #[test]
+    fn test_stake_checkpoints() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let contract_id = env.register(StakingPool, ());
+        let client = StakingPoolClient::new(&env, &contract_id);
+
+        let admin = Address::generate(&env);
+        let fee_distributor = Address::generate(&env);
+        let user1 = Address::generate(&env);
+        let user2 = Address::generate(&env);
+
+        let stake_token = create_token(&env, &admin);
+        stake_token.mint(&user1, 1_000_000_000_000);
+        stake_token.mint(&user2, 1_000_000_000_000);
+
+        client.initialize(
+            &admin,
+            &stake_token.address,
+            &fee_distributor,
+            &default_staking_config(),
+        );
+
+        env.ledger().set_timestamp(1000);
+        client.stake(&user1, &100_000_000, &None);
+        client.stake(&user1, &50_000_000, &None); // same timestamp overwrites
+        env.ledger().set_timestamp(2000);
+        client.stake(&user2, &300_000_000, &None);
+        env.ledger().set_timestamp(3000);
+        client.unstake(&user1, &150_000_000, &None);
+
+        assert_eq!(client.checkpoint_count(&user1), 2);
+        assert_eq!(client.get_stake_at(&user1, &999), 0);
+        assert_eq!(client.get_stake_at(&user1, &1000), 150_000_000);
+        assert_eq!(client.get_stake_at(&user1, &2999), 150_000_000);
+        assert_eq!(client.get_stake_at(&user1, &3000), 0);
+        assert_eq!(client.get_stake_at(&user2, &1500), 0);
+        assert_eq!(client.get_stake_at(&user2, &5000), 300_000_000);
+
+        assert_eq!(client.total_staked_at(&1500), 150_000_000);
+        assert_eq!(client.total_staked_at(&2500), 450_000_000);
+        assert_eq!(client.total_staked_at(&3000), 300_000_000);
+    }
+
+    #[test]
+    fn test_deposit_and_withdraw_fees() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let contract_id = env.register(StakingPool, ());
+        let client = StakingPoolClient::new(&env, &contract_id);
+        let fee_distributor = env.register(MockFeeDistributor, ());
+
+        let admin = Address::generate(&env);
+        let user = Address::generate(&env);
+
+        let stake_token = create_token(&env, &admin);
+        stake_token.mint(&user, 1_000_000_000_000);
+
+        let mut config = default_staking_config();
+        config.deposit_fee_bps = 100; // 1%
+        config.withdraw_fee_bps = 200; // 2%
+        client.initialize(&admin, &stake_token.address, &fee_distributor, &config);
+
+        // 1% of 100 tokens goes to the distributor
+        client.stake(&user, &100_000_000_000, &None);
+        assert_eq!(client.get_stake(&user).amount, 99_000_000_000);
+        assert_eq!(client.total_staked(), 99_000_000_000);
+        assert_eq!(stake_token.balance(&fee_distributor), 1_000_000_000);
+
+        // 2% of the unstaked amount goes to the distributor
+        client.unstake(&user, &50_000_000_000, &None);
+        assert_eq!(client.get_stake(&user).amount, 49_000_000_000);
+        assert_eq!(stake_token.balance(&fee_distributor), 2_000_000_000);
+        assert_eq!(stake_token.balance(&user), 949_000_000_000);
+
+        // Fees above 5% are rejected
+        config.withdraw_fee_bps = StakingConfig::MAX_FEE_BPS + 1;
+        assert!(client.try_update_config(&admin, &config).is_err());
+    }
+
+    #[test]
+    fn test_fees_held_while_distributor_paused() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let contract_id = env.register(StakingPool, ());
+        let client = StakingPoolClient::new(&env, &contract_id);
+        let fee_distributor = env.register(MockFeeDistributor, ());
+        let distributor = MockFeeDistributorClient::new(&env, &fee_distributor);
+
+        let admin = Address::generate(&env);
+        let user = Address::generate(&env);
+
+        let stake_token = create_token(&env, &admin);
+        stake_token.mint(&user, 1_000_000_000_000);
+
+        let mut config = default_staking_config();
+        config.deposit_fee_bps = 100; // 1%
+        config.withdraw_fee_bps = 200; // 2%
+        client.initialize(&admin, &stake_token.address, &fee_distributor, &config);
+        client.stake(&user, &100_000_000_000, &None);
+        assert_eq!(stake_token.balance(&fee_distributor), 1_000_000_000);
+
+        // A paused distributor doesn't block the unstake; its fee is held
+        distributor.set_paused(&true);
+        client.unstake(&user, &50_000_000_000, &None);
+        assert_eq!(stake_token.balance(&user), 949_000_000_000);
+        assert_eq!(stake_token.balance(&fee_distributor), 1_000_000_000);
+        assert_eq!(client.pending_fees(), 1_000_000_000);
+
+        // Held fees are not pool assets
+        assert_eq!(client.total_assets(), 49_000_000_000);
+        let report = client.solvency_report();
+        let stake_solvency = report.get(0).unwrap();
+        assert_eq!(stake_solvency.balance, 50_000_000_000);
+        assert_eq!(stake_solvency.liabilities, 50_000_000_000);
+
+        assert_eq!(
+            client.try_flush_fees(),
+            Err(Ok(SharedError::ContractPaused))
+        );
+        distributor.set_paused(&false);
+        assert_eq!(client.flush_fees(), 1_000_000_000);
+        assert_eq!(client.pending_fees(), 0);
+        assert_eq!(stake_token.balance(&fee_distributor), 2_000_000_000);
+        assert_eq!(client.flush_fees(), 0);
+    }
+
+    #[test]
+    fn test_fees_held_while_source_not_allowed() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let contract_id = env.register(StakingPool, ());
+        let client = StakingPoolClient::new(&env, &contract_id);
+        let fee_distributor = env.register(MockFeeDistributor, ());
+        let distributor = MockFeeDistributorClient::new(&env, &fee_distributor);
+
+        let admin = Address::generate(&env);
+        let user = Address::generate(&env);
+
+        let stake_token = create_token(&env, &admin);
+        stake_token.mint(&user, 1_000_000_000_000);
+
+        let mut config = default_staking_config();
+        config.deposit_fee_bps = 100; // 1%
+        config.withdraw_fee_bps = 200; // 2%
+        client.initialize(&admin, &stake_token.address, &fee_distributor, &config);
+
+        // The pool is not an allowed fee source: stake and unstake still go
+        // through, and each rejected fee adds to the held amount
+        distributor.set_sources(&vec![&env, Address::generate(&env)]);
+        client.stake(&user, &100_000_000_000, &None);
+        client.unstake(&user, &50_000_000_000, &None);
+        assert_eq!(stake_token.balance(&user), 949_000_000_000);
+        assert_eq!(stake_token.balance(&fee_distributor), 0);
+        assert_eq!(client.pending_fees(), 2_000_000_000);
+        assert_eq!(client.total_assets(), 49_000_000_000);
+
+        // Once allowed, the next fee takes the held ones along
+        distributor.set_sources(&vec![&env, contract_id.clone()]);
+        client.unstake(&user, &10_000_000_000, &None);
+        assert_eq!(stake_token.balance(&user), 958_800_000_000);
+        assert_eq!(stake_token.balance(&fee_distributor), 2_200_000_000);
+        assert_eq!(client.pending_fees(), 0);
+    }
+
+    #[test]
+    fn test_early_exit_penalty() {
+        let env = Env::default();
+        env.mock_all_auths();
+        env.ledger().set_timestamp(1000);
+
+        let contract_id = env.register(StakingPool, ());
+        let client = StakingPoolClient::new(&env, &contract_id);
+        let fee_distributor = env.register(MockFeeDistributor, ());
+
+        let admin = Address::generate(&env);
+        let user = Address::generate(&env);
+        let treasury = Address::generate(&env);
+        let burn_address = Address::generate(&env);
+
+        let stake_token = create_token(&env, &admin);
+        stake_token.mint(&user, 1_000_000_000_000);
+
+        client.initialize(
+            &admin,
+            &stake_token.address,
+            &fee_distributor,
+            &default_staking_config(),
+        );
+
+        let mut early_exit = EarlyExit {
+            window: 7 * 24 * 60 * 60,
+            penalty_bps: Bps::from_const(1000), // 10%
+            routing: PenaltyRouting::new(5000, 2000, 3000).unwrap(),
+        };
+
+        // Routing to the treasury and burn needs both recipients
+        assert_eq!(
+            client.try_set_early_exit(&Some(early_exit.clone())),
+            Err(Ok(SharedError::NotInitialized))
+        );
+        client.set_treasury(&treasury);
+        client.set_burn_address(&burn_address);
+
+        early_exit.penalty_bps = Bps::from_const(6000);
+        assert_eq!(
+            client.try_set_early_exit(&Some(early_exit.clone())),
+            Err(Ok(SharedError::InvalidBps))
+        );
+        early_exit.penalty_bps = Bps::from_const(1000);
+        client.set_early_exit(&Some(early_exit.clone()));
+        assert_eq!(client.early_exit(), Some(early_exit.clone()));
+
+        client.stake(&user, &100_000_000_000, &None);
+
+        // Inside the window 10% of the payout is split 50/20/30
+        client.unstake(&user, &50_000_000_000, &None);
+        assert_eq!(stake_token.balance(&user), 945_000_000_000);
+        assert_eq!(stake_token.balance(&treasury), 2_500_000_000);
+        assert_eq!(stake_token.balance(&fee_distributor), 1_500_000_000);
+        assert_eq!(stake_token.balance(&burn_address), 0);
+        assert_eq!(stake_token.balance(&contract_id), 50_000_000_000);
+
+        // After the window the rest comes out in full
+        env.ledger().set_timestamp(1000 + early_exit.window);
+        client.unstake(&user, &50_000_000_000, &None);
+        assert_eq!(stake_token.balance(&user), 995_000_000_000);
+
+        client.set_early_exit(&None);
+        assert_eq!(client.early_exit(), None);
+    }
+
+    #[test]
+    fn test_staker_registry() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let contract_id = env.register(StakingPool, ());
+        let client = StakingPoolClient::new(&env, &contract_id);
+
+        let admin = Address::generate(&env);
+        let fee_distributor = Address::generate(&env);
+        let user1 = Address::generate(&env);
+        let user2 = Address::generate(&env);
+        let user3 = Address::generate(&env);
+
+        let stake_token = create_token(&env, &admin);
+        for user in [&user1, &user2, &user3] {
+            stake_token.mint(user, 1_000_000_000_000);
+        }
+
+        client.initialize(
+            &admin,
+            &stake_token.address,
+            &fee_distributor,
+            &default_staking_config(),
+        );
+
+        client.stake(&user1, &100_000_000_000, &None);
+        client.stake(&user2, &200_000_000_000, &None);
+        client.stake(&user3, &300_000_000_000, &None);
+        client.stake(&user1, &100_000_000_000, &None);
+        assert_eq!(client.staker_count(), 3);
+
+        let page = client.get_stakers_page(&0, &2);
+        assert_eq!(page.len(), 2);
+        assert_eq!(page.get(0).unwrap(), (user1.clone(), 200_000_000_000));
+        assert_eq!(page.get(1).unwrap(), (user2.clone(), 200_000_000_000));
+
+        // Full unstake removes the user; the last staker takes its slot
+        client.unstake(&user1, &200_000_000_000, &None);
+        assert_eq!(client.staker_count(), 2);
+        let page = client.get_stakers_page(&0, &10);
+        assert_eq!(page.len(), 2);
+        assert_eq!(page.get(0).unwrap().0, user3);
+        assert_eq!(page.get(1).unwrap().0, user2);
+
+        assert_eq!(client.get_stakers_page(&5, &10).len(), 0);
     }
 
-    fn get_total_rewards(env: &Env, reward_token: &Address) -> i128 {
-        env.storage()
-            .persistent()
-            .get(&DataKey::TotalRewards(reward_token.clone()))
-            .unwrap_or(0)
-    }
+    #[test]
+    fn test_lock_stake_for_vote() {
+        let env = Env::default();
+        env.mock_all_auths();
 
-    fn get_reward_tokens(env: &Env) -> Vec<Address> {
-        env.storage()
-            .instance()
-            .get(&DataKey::RewardTokens)
-            .unwrap_or(Vec::new(env))
-    }
+        let contract_id = env.register(StakingPool, ());
+        let client = StakingPoolClient::new(&env, &contract_id);
 
-    fn add_reward_token(env: &Env, token: &Address) {
-        let mut tokens = Self::get_reward_tokens(env);
+        let admin = Address::generate(&env);
+        let fee_distributor = Address::generate(&env);
+        let governor = Address::generate(&env);
+        let user = Address::generate(&env);
 
-        // Check if already in list
-        for t in tokens.iter() {
-            if t == *token {
-                return;
-            }
-        }
+        let stake_token = create_token(&env, &admin);
+        stake_token.mint(&user, 1_000_000_000_000);
 
-        tokens.push_back(token.clone());
-        env.storage()
-            .instance()
-            .set(&DataKey::RewardTokens, &tokens);
+        client.initialize(
+            &admin,
+            &stake_token.address,
+            &fee_distributor,
+            &default_staking_config(),
+        );
+        env.ledger().set_timestamp(1000);
+        client.stake(&user, &100_000_000_000, &None);
+
+        // No governor configured
+        assert!(client.try_lock_stake_for_vote(&user, &1, &5000).is_err());
+        client.set_governor(&governor);
+
+        let locked = client.lock_stake_for_vote(&user, &1, &5000);
+        assert_eq!(locked, 100_000_000_000);
+        assert_eq!(client.vote_locked_amount(&user), 100_000_000_000);
+
+        // Topping up is fine; only the voted amount is pinned
+        client.stake(&user, &50_000_000_000, &None);
+        assert!(client.try_unstake(&user, &60_000_000_000, &None).is_err());
+        client.unstake(&user, &50_000_000_000, &None);
+
+        // Lock expires with the proposal
+        env.ledger().set_timestamp(5000);
+        assert_eq!(client.vote_locked_amount(&user), 0);
+        assert_eq!(client.get_vote_locks(&user).len(), 0);
+        client.unstake(&user, &100_000_000_000, &None);
     }
 
-    /// Calculate pending rewards with proper error handling (C2 fix)
-    fn calculate_pending(
-        stake_amount: &i128,
-        acc_per_share: i128,
-        reward_debt: i128,
-    ) -> Result<i128, SharedError> {
-        let accumulated = safe_div(safe_mul(*stake_amount, acc_per_share)?, PRECISION)?;
-        safe_sub(accumulated, reward_debt)
-    }
+    #[test]
+    fn test_slash() {
+        let env = Env::default();
+        env.mock_all_auths();
 
-    /// Internal harvest with checks-effects-interactions pattern (C1 fix - reentrancy protection)
-    fn internal_harvest(
-        env: &Env,
-        user: &Address,
-        user_stake: &mut UserStake,
-        reward_tokens: &Vec<Address>,
-    ) -> Result<Vec<(Address, i128)>, SharedError> {
-        let mut rewards = Vec::new(env);
+        let contract_id = env.register(StakingPool, ());
+        let client = StakingPoolClient::new(&env, &contract_id);
 
-        if user_stake.amount == 0 {
-            return Ok(rewards);
-        }
+        let admin = Address::generate(&env);
+        let fee_distributor = Address::generate(&env);
+        let slasher = Address::generate(&env);
+        let insurance = Address::generate(&env);
+        let user = Address::generate(&env);
 
-        // CHECKS: Calculate all pending rewards first
-        let mut pending_transfers: Vec<(Address, i128)> = Vec::new(env);
-        for reward_token in reward_tokens.iter() {
-            let acc_per_share = Self::get_acc_reward_per_share(env, &reward_token);
-            // FIX #H1: Use get_reward_debt() to read per-token debt
-            let reward_debt = user_stake.get_reward_debt(&reward_token);
-            let pending =
-                Self::calculate_pending(&user_stake.amount, acc_per_share, reward_debt)
-                    .unwrap_or(0); // Safe: overflow means 0 pending
+        let stake_token = create_token(&env, &admin);
+        stake_token.mint(&user, 1_000_000_000_000);
 
-            if pending > 0 {
-                pending_transfers.push_back((reward_token.clone(), pending));
-            }
-        }
+        client.initialize(
+            &admin,
+            &stake_token.address,
+            &fee_distributor,
+            &default_staking_config(),
+        );
+        client.stake(&user, &100_000_000_000, &None);
+        client.set_slasher(&Some(slasher.clone()));
+
+        // Disabled by default
+        assert!(client
+            .try_slash(&slasher, &user, &1000, &insurance)
+            .is_err());
+
+        let mut config = default_staking_config();
+        config.slashing_enabled = true;
+        client.update_config(&admin, &config);
+
+        // Random callers cannot slash
+        let outsider = Address::generate(&env);
+        assert!(client
+            .try_slash(&outsider, &user, &1000, &insurance)
+            .is_err());
+
+        // 10% slash
+        let slashed = client.slash(&slasher, &user, &1000, &insurance);
+        assert_eq!(slashed, 10_000_000_000);
+        assert_eq!(stake_token.balance(&insurance), 10_000_000_000);
+        assert_eq!(client.get_stake(&user).amount, 90_000_000_000);
+        assert_eq!(client.total_staked(), 90_000_000_000);
+    }
 
-        // EFFECTS: Update state BEFORE external calls (reentrancy protection)
-        user_stake.last_claim_time = env.ledger().timestamp();
+    #[test]
+    fn test_reward_token_allowlist() {
+        let env = Env::default();
+        env.mock_all_auths();
 
-        // INTERACTIONS: Now perform external token transfers
-        for (reward_token, pending) in pending_transfers.iter() {
-            let token_client = token::Client::new(env, &reward_token);
-            token_client.transfer(&env.current_contract_address(), user, &pending);
+        let contract_id = env.register(StakingPool, ());
+        let client = StakingPoolClient::new(&env, &contract_id);
 
-            emit_claim(env, user, &reward_token, pending);
-            rewards.push_back((reward_token, pending));
-        }
+        let admin = Address::generate(&env);
+        let fee_distributor = Address::generate(&env);
+        let user = Address::generate(&env);
 
-        Ok(rewards)
-    }
-}
+        let stake_token = create_token(&env, &admin);
+        let reward_token = create_token(&env, &admin);
+        let spam_token = create_token(&env, &admin);
 
-// ════════════════════════════════════════════════════════════════════════════
-// Tests
-// ════════════════════════════════════════════════════════════════════════════
+        stake_token.mint(&user, 1_000_000_000_000);
+        reward_token.mint(&fee_distributor, 1_000_000_000_000);
+        spam_token.mint(&fee_distributor, 1_000_000_000_000);
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use soroban_sdk::testutils::Address as _;
+        client.initialize(
+            &admin,
+            &stake_token.address,
+            &fee_distributor,
+            &default_staking_config(),
+        );
+        client.stake(&user, &100_000_000_000, &None);
 
-    fn create_token<'a>(
-        env: &Env,
-        admin: &Address,
-    ) -> (token::Client<'a>, token::StellarAssetClient<'a>) {
-        let contract_id = env.register_stellar_asset_contract_v2(admin.clone());
-        (
-            token::Client::new(env, &contract_id.address()),
-            token::StellarAssetClient::new(env, &contract_id.address()),
-        )
-    }
+        // Unlisted tokens are rejected
+        let result = client.try_add_rewards(&fee_distributor, &spam_token.address, &1_000_000);
+        assert!(result.is_err());
 
-    fn default_config() -> StakingConfig {
-        StakingConfig {
-            min_stake_amount: 10_000_000, // 1 token
-            cooldown_period: 0,
-            max_stake_per_user: 0, // No limit
-            emergency_unlock: false,
-        }
+        // Cap applies to registrations
+        client.set_max_reward_tokens(&1);
+        client.allow_reward_token(&reward_token.address);
+        client.allow_reward_token(&spam_token.address);
+        client.add_rewards(&fee_distributor, &reward_token.address, &10_000_000_000);
+        let result = client.try_add_rewards(&fee_distributor, &spam_token.address, &1_000_000);
+        assert!(result.is_err());
+
+        // Removal requires rewards to be claimed first
+        assert!(client
+            .try_remove_reward_token(&reward_token.address)
+            .is_err());
+        client.claim(&user);
+        assert_eq!(client.reward_liability(&reward_token.address), 0);
+        client.remove_reward_token(&reward_token.address);
+        assert_eq!(client.reward_tokens().len(), 0);
     }
 
     #[test]
-    fn test_initialize() {
+    fn test_add_rewards_fee_on_transfer() {
         let env = Env::default();
         env.mock_all_auths();
 
@@ -724,58 +3712,105 @@ mod tests {
         let client = StakingPoolClient::new(&env, &contract_id);
 
         let admin = Address::generate(&env);
-        let stake_token = Address::generate(&env);
         let fee_distributor = Address::generate(&env);
+        let user = Address::generate(&env);
 
-        client.initialize(&admin, &stake_token, &fee_distributor, &default_config());
+        let stake_token = create_token(&env, &admin);
+        stake_token.mint(&user, 1_000_000_000_000);
 
-        assert_eq!(client.admin(), admin);
-        assert_eq!(client.stake_token(), stake_token);
-        assert_eq!(client.total_staked(), 0);
+        let tax_token = env.register(MockTaxToken, ());
+        let tax_client = MockTaxTokenClient::new(&env, &tax_token);
+        tax_client.mint(&fee_distributor, &1_000_000_000_000);
+
+        client.initialize(
+            &admin,
+            &stake_token.address,
+            &fee_distributor,
+            &default_staking_config(),
+        );
+        client.allow_reward_token(&tax_token);
+        client.stake(&user, &100_000_000_000, &None);
+
+        // Only 90% arrives; accounting uses the received amount
+        let received = client.add_rewards(&fee_distributor, &tax_token, &10_000_000_000);
+        assert_eq!(received, 9_000_000_000);
+        assert_eq!(client.reward_liability(&tax_token), 9_000_000_000);
+        assert_eq!(
+            client.pending_rewards(&user).get(0).unwrap().1,
+            tax_client.balance(&contract_id)
+        );
+
+        // The pool can pay out everything it owes
+        client.claim(&user);
+        assert_eq!(tax_client.balance(&contract_id), 0);
     }
 
     #[test]
-    fn test_stake_and_unstake() {
+    fn test_forfeit_stale_rewards() {
         let env = Env::default();
         env.mock_all_auths();
+        env.ledger().set_timestamp(1000);
 
         let contract_id = env.register(StakingPool, ());
         let client = StakingPoolClient::new(&env, &contract_id);
 
         let admin = Address::generate(&env);
         let fee_distributor = Address::generate(&env);
-        let user = Address::generate(&env);
+        let idle = Address::generate(&env);
+        let active = Address::generate(&env);
 
-        // Create stake token
-        let (stake_token, stake_admin) = create_token(&env, &admin);
-        stake_admin.mint(&user, &1_000_000_000_000); // 100,000 tokens
+        let stake_token = create_token(&env, &admin);
+        let reward_token = create_token(&env, &admin);
+
+        stake_token.mint(&idle, 1_000_000_000_000);
+        stake_token.mint(&active, 1_000_000_000_000);
+        reward_token.mint(&fee_distributor, 1_000_000_000_000);
 
         client.initialize(
             &admin,
             &stake_token.address,
             &fee_distributor,
-            &default_config(),
+            &default_staking_config(),
         );
+        client.allow_reward_token(&reward_token.address);
+        client.stake(&idle, &100_000_000_000, &None);
+        client.stake(&active, &100_000_000_000, &None);
 
-        // Stake
-        let stake_amount = 100_000_000_000_i128; // 10,000 tokens
-        let new_balance = client.stake(&user, &stake_amount);
-        assert_eq!(new_balance, stake_amount);
-        assert_eq!(client.total_staked(), stake_amount);
+        // Disabled by default
+        assert!(client.try_forfeit_stale_rewards(&idle).is_err());
+        client.set_reward_expiry(&(30 * 86400));
 
-        // Check user stake
-        let user_stake = client.get_stake(&user);
-        assert_eq!(user_stake.amount, stake_amount);
+        client.add_rewards(&fee_distributor, &reward_token.address, &10_000_000_000);
 
-        // Unstake half
-        let unstake_amount = 50_000_000_000_i128;
-        let remaining = client.unstake(&user, &unstake_amount);
-        assert_eq!(remaining, stake_amount - unstake_amount);
-        assert_eq!(client.total_staked(), stake_amount - unstake_amount);
+        // Rewards are still fresh
+        env.ledger().set_timestamp(1000 + 10 * 86400);
+        client.add_rewards(&fee_distributor, &reward_token.address, &10_000_000_000);
+        assert!(client.try_forfeit_stale_rewards(&idle).is_err());
+
+        // Only the first batch is older than the expiry
+        env.ledger().set_timestamp(1000 + 35 * 86400);
+        assert_eq!(client.forfeitable_rewards(&idle).len(), 1);
+        client.claim(&active);
+        let forfeited = client.forfeit_stale_rewards(&idle);
+        assert_eq!(forfeited.get(0).unwrap().1, 5_000_000_000);
+
+        // The idle staker keeps the fresh batch; the active staker gets the rest
+        assert_eq!(
+            client.pending_rewards(&idle).get(0).unwrap().1,
+            5_000_000_000
+        );
+        assert_eq!(
+            client.pending_rewards(&active).get(0).unwrap().1,
+            5_000_000_000
+        );
+        assert_eq!(
+            client.reward_liability(&reward_token.address),
+            10_000_000_000
+        );
     }
 
     #[test]
-    fn test_add_rewards_and_claim() {
+    fn test_freeze_reward_token() {
         let env = Env::default();
         env.mock_all_auths();
 
@@ -786,42 +3821,45 @@ mod tests {
         let fee_distributor = Address::generate(&env);
         let user = Address::generate(&env);
 
-        // Create tokens
-        let (stake_token, stake_admin) = create_token(&env, &admin);
-        let (reward_token, reward_admin) = create_token(&env, &admin);
+        let stake_token = create_token(&env, &admin);
+        let reward_token = create_token(&env, &admin);
 
-        stake_admin.mint(&user, &1_000_000_000_000);
-        reward_admin.mint(&fee_distributor, &1_000_000_000_000);
+        stake_token.mint(&user, 1_000_000_000_000);
+        reward_token.mint(&fee_distributor, 1_000_000_000_000);
 
         client.initialize(
             &admin,
             &stake_token.address,
             &fee_distributor,
-            &default_config(),
+            &default_staking_config(),
         );
-
-        // User stakes
-        client.stake(&user, &100_000_000_000);
-
-        // Add rewards
+        client.allow_reward_token(&reward_token.address);
+        client.stake(&user, &100_000_000_000, &None);
         client.add_rewards(&fee_distributor, &reward_token.address, &10_000_000_000);
 
-        // Check pending rewards
-        let pending = client.pending_rewards(&user);
-        assert_eq!(pending.len(), 1);
-        let (token, amount) = pending.get(0).unwrap();
-        assert_eq!(token, reward_token.address);
-        assert_eq!(amount, 10_000_000_000);
+        client.freeze_reward_token(&reward_token.address);
+        assert!(client
+            .try_add_rewards(&fee_distributor, &reward_token.address, &1_000_000)
+            .is_err());
 
-        // Claim rewards
+        // Claims and stake changes skip the frozen token
         client.claim(&user);
+        client.unstake(&user, &50_000_000_000, &None);
+        assert_eq!(reward_token.balance(&user), 0);
+        assert_eq!(
+            client.pending_rewards(&user).get(0).unwrap().1,
+            10_000_000_000
+        );
 
-        // Verify user received rewards
+        // Accrued rewards are paid in full after unfreeze
+        client.unfreeze_reward_token(&reward_token.address);
+        client.claim(&user);
         assert_eq!(reward_token.balance(&user), 10_000_000_000);
+        assert_eq!(client.reward_liability(&reward_token.address), 0);
     }
 
     #[test]
-    fn test_multiple_stakers() {
+    fn test_reward_vesting() {
         let env = Env::default();
         env.mock_all_auths();
 
@@ -833,37 +3871,235 @@ mod tests {
         let user1 = Address::generate(&env);
         let user2 = Address::generate(&env);
 
-        let (stake_token, stake_admin) = create_token(&env, &admin);
-        let (reward_token, reward_admin) = create_token(&env, &admin);
-
-        stake_admin.mint(&user1, &1_000_000_000_000);
-        stake_admin.mint(&user2, &1_000_000_000_000);
-        reward_admin.mint(&fee_distributor, &1_000_000_000_000);
+        let stake_token = create_token(&env, &admin);
+        let reward_token = create_token(&env, &admin);
+        stake_token.mint(&user1, 1_000_000_000_000);
+        stake_token.mint(&user2, 1_000_000_000_000);
+        reward_token.mint(&fee_distributor, 1_000_000_000_000);
 
         client.initialize(
             &admin,
             &stake_token.address,
             &fee_distributor,
-            &default_config(),
+            &default_staking_config(),
+        );
+        assert_eq!(
+            client.try_set_reward_vesting(&1000, &6000),
+            Err(Ok(SharedError::InvalidBps))
         );
+        client.set_reward_vesting(&1000, &2000);
 
-        // User1 stakes 75%, User2 stakes 25%
-        client.stake(&user1, &75_000_000_000);
-        client.stake(&user2, &25_000_000_000);
+        env.ledger().set_timestamp(1000);
+        client.stake(&user1, &100_000_000_000, &None);
+        client.stake(&user2, &100_000_000_000, &None);
+        client.allow_reward_token(&reward_token.address);
+        client.add_rewards(&fee_distributor, &reward_token.address, &10_000_000_000);
 
-        // Add rewards
-        client.add_rewards(&fee_distributor, &reward_token.address, &100_000_000_000);
+        // Harvested rewards start vesting instead of being paid
+        assert_eq!(client.claim(&user1).len(), 0);
+        assert_eq!(
+            client.get_reward_vest(&user1, &reward_token.address),
+            Some(RewardVest {
+                amount: 5_000_000_000,
+                start: 1000,
+                end: 2000,
+            })
+        );
 
-        // User1 should get 75% of rewards, User2 gets 25%
-        let pending1 = client.pending_rewards(&user1);
-        let pending2 = client.pending_rewards(&user2);
+        // Halfway through, half is paid
+        env.ledger().set_timestamp(1500);
+        assert_eq!(
+            client.claim(&user1),
+            vec![&env, (reward_token.address.clone(), 2_500_000_000_i128)]
+        );
 
-        let (_, amount1) = pending1.get(0).unwrap();
-        let (_, amount2) = pending2.get(0).unwrap();
+        // Early exit loses 20% of the unvested half to the other staker
+        assert_eq!(
+            client.claim_vested_early(&user1),
+            vec![&env, (reward_token.address.clone(), 2_000_000_000_i128)]
+        );
+        assert_eq!(reward_token.balance(&user1), 4_500_000_000);
+        assert_eq!(client.get_reward_vest(&user1, &reward_token.address), None);
+        assert_eq!(client.pending_rewards(&user1).len(), 0);
+        assert_eq!(
+            client.pending_rewards(&user2),
+            vec![&env, (reward_token.address.clone(), 5_500_000_000_i128)]
+        );
+        assert!(client.solvency_report().iter().all(|e| e.is_solvent()));
+    }
 
-        // 75% of 100B = 75B
-        assert_eq!(amount1, 75_000_000_000);
-        // 25% of 100B = 25B
-        assert_eq!(amount2, 25_000_000_000);
+    #[test]
+    fn test_share_accounting() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let contract_id = env.register(StakingPool, ());
+        let client = StakingPoolClient::new(&env, &contract_id);
+
+        let admin = Address::generate(&env);
+        let user = Address::generate(&env);
+        let stake_token_id = env.register(MockTaxToken, ());
+        let stake_token = MockTaxTokenClient::new(&env, &stake_token_id);
+        stake_token.mint(&user, &1_000_000_000);
+
+        client.initialize(
+            &admin,
+            &stake_token_id,
+            &Address::generate(&env),
+            &default_staking_config(),
+        );
+
+        // Only the 90% that arrives is credited
+        assert_eq!(client.stake(&user, &100_000_000, &None), 90_000_000);
+        assert_eq!(client.total_staked(), 90_000_000);
+        assert_eq!(client.convert_to_assets(&90_000_000), 90_000_000);
+
+        // A positive rebase doubles the pool; shares are unchanged and the
+        // virtual offset keeps a sliver of the gain
+        stake_token.mint(&contract_id, &90_000_000);
+        assert_eq!(client.total_staked(), 90_000_000);
+        assert_eq!(client.convert_to_assets(&90_000_000), 179_999_000);
+        assert_eq!(client.convert_to_shares(&180_000_000), 90_000_499);
+
+        // Half the shares redeem half the pool (less the token's own tax)
+        assert_eq!(client.unstake(&user, &45_000_000, &None), 45_000_000);
+        assert_eq!(stake_token.balance(&contract_id), 90_000_500);
+        assert_eq!(stake_token.balance(&user), 900_000_000 + 80_999_550);
+    }
+
+    #[test]
+    fn test_donation_inflation_attack() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let contract_id = env.register(StakingPool, ());
+        let client = StakingPoolClient::new(&env, &contract_id);
+
+        let admin = Address::generate(&env);
+        let attacker = Address::generate(&env);
+        let victim = Address::generate(&env);
+        let stake_token = create_token(&env, &admin);
+        stake_token.mint(&attacker, 1_000_000_000_000);
+        stake_token.mint(&victim, 1_000_000_000_000);
+
+        let config = default_staking_config();
+        client.initialize(
+            &admin,
+            &stake_token.address,
+            &Address::generate(&env),
+            &config,
+        );
+
+        // Leave a single share outstanding, then inflate its price
+        client.stake(&attacker, &config.min_stake_amount, &None);
+        client.unstake(&attacker, &(config.min_stake_amount - 1), &None);
+        assert_eq!(client.total_staked(), 1);
+        stake_token.transfer(&attacker, &contract_id, &10_000_000_000);
+
+        // The victim still gets shares worth their deposit, less dust
+        let shares = client.stake(&victim, &1_000_000_000, &None);
+        assert!(shares > 0);
+        assert!(client.convert_to_assets(&shares) >= 999_000_000);
+
+        // Most of the donation went to the virtual shares, not the attacker
+        assert!(client.convert_to_assets(&1) < 10_000_000);
+        assert!(client.solvency_report().iter().all(|e| e.is_solvent()));
+    }
+
+    #[test]
+    fn test_vault_interface() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let contract_id = env.register(StakingPool, ());
+        let client = StakingPoolClient::new(&env, &contract_id);
+        let fee_distributor = env.register(MockFeeDistributor, ());
+
+        let admin = Address::generate(&env);
+        let user = Address::generate(&env);
+        let stake_token = create_token(&env, &admin);
+        stake_token.mint(&user, 1_000_000_000_000);
+
+        let mut config = default_staking_config();
+        config.deposit_fee_bps = 100; // 1%
+        config.withdraw_fee_bps = 200; // 2%
+        client.initialize(&admin, &stake_token.address, &fee_distributor, &config);
+
+        assert_eq!(client.preview_deposit(&100_000_000_000), 99_000_000_000);
+        assert_eq!(client.deposit(&user, &100_000_000_000), 99_000_000_000);
+        assert_eq!(client.total_assets(), 99_000_000_000);
+
+        // Rewards compounding into the pool double the share price
+        stake_token.mint(&contract_id, 99_000_000_000);
+        assert_eq!(client.convert_to_assets(&1_000_000_000), 1_999_999_989);
+
+        // 10 shares cost 20 tokens plus the deposit fee, rounded up
+        let assets = client.preview_mint(&10_000_000_000);
+        assert_eq!(assets, 20_202_020_100);
+        assert_eq!(client.mint(&user, &10_000_000_000), assets);
+        assert_eq!(client.get_stake(&user).amount, 109_000_000_000);
+
+        // Withdraw pays at least the requested amount net of fees
+        let balance = stake_token.balance(&user);
+        let shares = client.preview_withdraw(&49_000_000_000);
+        assert_eq!(client.withdraw(&user, &49_000_000_000), shares);
+        assert!(stake_token.balance(&user) - balance >= 49_000_000_000);
+
+        let balance = stake_token.balance(&user);
+        let payout = client.preview_redeem(&1_000_000_000);
+        assert_eq!(client.redeem(&user, &1_000_000_000), payout);
+        assert_eq!(stake_token.balance(&user) - balance, payout);
+    }
+
+    #[test]
+    fn test_emergency_unstake() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let contract_id = env.register(StakingPool, ());
+        let client = StakingPoolClient::new(&env, &contract_id);
+
+        let admin = Address::generate(&env);
+        let fee_distributor = Address::generate(&env);
+        let user1 = Address::generate(&env);
+        let user2 = Address::generate(&env);
+
+        let stake_token = create_token(&env, &admin);
+        let reward_token = create_token(&env, &admin);
+        stake_token.mint(&user1, 1_000_000_000_000);
+        stake_token.mint(&user2, 1_000_000_000_000);
+        reward_token.mint(&fee_distributor, 1_000_000_000_000);
+
+        let mut config = default_staking_config();
+        config.withdraw_fee_bps = 200;
+        client.initialize(&admin, &stake_token.address, &fee_distributor, &config);
+
+        client.stake(&user1, &100_000_000_000, &None);
+        client.stake(&user2, &100_000_000_000, &None);
+        client.allow_reward_token(&reward_token.address);
+        client.add_rewards(&fee_distributor, &reward_token.address, &10_000_000_000);
+
+        // Only available once the admin enables emergency unlocks
+        assert_eq!(
+            client.try_emergency_unstake(&user1),
+            Err(Ok(SharedError::InvalidState))
+        );
+        config.emergency_unlock = true;
+        client.update_config(&admin, &config);
+
+        // Works while paused: full principal, no fee, no rewards
+        client.set_paused(&admin, &true);
+        assert_eq!(client.emergency_unstake(&user1), 100_000_000_000);
+        assert_eq!(stake_token.balance(&user1), 1_000_000_000_000);
+        assert_eq!(reward_token.balance(&user1), 0);
+        assert_eq!(client.get_stake(&user1).amount, 0);
+        assert_eq!(client.total_staked(), 100_000_000_000);
+
+        // The forfeited rewards went to the remaining staker
+        assert_eq!(
+            client.pending_rewards(&user2),
+            vec![&env, (reward_token.address.clone(), 10_000_000_000_i128)]
+        );
+        assert!(client.solvency_report().iter().all(|e| e.is_solvent()));
     }
 }
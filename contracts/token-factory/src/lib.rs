@@ -0,0 +1,569 @@
+#![no_std]
+
+//! # Token Factory Contract
+//!
+//! Deploys standard SEP-41 tokens from a stored WASM hash and keeps an
+//! on-chain registry of their metadata for the launchpad and bridge.
+//!
+//! ## Features
+//! - One-call token creation with the whole supply minted to the creator
+//! - Supply cap enforced at creation (the factory keeps the mint authority,
+//!   so no further supply can be created)
+//! - `TokenMetadata` registry and per-creator token lists
+//!
+//! ## Token WASM
+//! The stored WASM must expose the standard token constructor
+//! `__constructor(admin, decimal, name, symbol)` and `mint(to, amount)`.
+
+use astro_core_shared::{
+    events::EventBuilder,
+    math::STELLAR_DECIMALS,
+    types::{
+        extend_instance_ttl, get_persistent, get_ttl_config, set_persistent, set_ttl_config,
+        validate_metadata, ContractInfo, SharedError, TokenMetadata, TtlConfig,
+    },
+    upgrade::{self, PendingUpgrade},
+};
+use soroban_sdk::{contract, contractimpl, contracttype, token, Address, BytesN, Env, String, Vec};
+
+// ════════════════════════════════════════════════════════════════════════════
+// Constants
+// ════════════════════════════════════════════════════════════════════════════
+
+/// Storage layout version of this code (see `migrate`)
+const STORAGE_VERSION: u32 = 1;
+
+/// Maximum tokens a single creator can deploy
+const MAX_TOKENS_PER_CREATOR: u32 = 100;
+
+// ════════════════════════════════════════════════════════════════════════════
+// Storage Keys
+// ════════════════════════════════════════════════════════════════════════════
+
+#[contracttype]
+#[derive(Clone, Debug)]
+pub enum DataKey {
+    /// Admin address
+    Admin,
+    /// Whether contract is initialized
+    Initialized,
+    /// Whether contract is paused
+    Paused,
+    /// WASM hash of the token contract deployed by `create_token`
+    TokenWasm,
+    /// Maximum supply of a new token
+    MaxSupply,
+    /// Number of tokens deployed (also the deploy salt nonce)
+    TokenCount,
+    /// Metadata of a factory token (Address -> TokenMetadata)
+    Metadata(Address),
+    /// Tokens deployed by a creator (Address -> Vec<Address>)
+    CreatorTokens(Address),
+    /// Protocol registry address (reported by `contract_info`)
+    Registry,
+}
+
+// ════════════════════════════════════════════════════════════════════════════
+// Contract Implementation
+// ════════════════════════════════════════════════════════════════════════════
+
+#[contract]
+pub struct TokenFactory;
+
+#[contractimpl]
+impl TokenFactory {
+    // ────────────────────────────────────────────────────────────────────────
+    // Initialization
+    // ────────────────────────────────────────────────────────────────────────
+
+    /// Initialize the factory with the token WASM hash and supply cap
+    pub fn initialize(
+        env: Env,
+        admin: Address,
+        token_wasm: BytesN<32>,
+        max_supply: i128,
+    ) -> Result<(), SharedError> {
+        if env.storage().instance().has(&DataKey::Initialized) {
+            return Err(SharedError::AlreadyInitialized);
+        }
+
+        if max_supply <= 0 {
+            return Err(SharedError::InvalidAmount);
+        }
+
+        env.storage().instance().set(&DataKey::Admin, &admin);
+        env.storage()
+            .instance()
+            .set(&DataKey::TokenWasm, &token_wasm);
+        env.storage()
+            .instance()
+            .set(&DataKey::MaxSupply, &max_supply);
+        env.storage().instance().set(&DataKey::TokenCount, &0_u64);
+        env.storage().instance().set(&DataKey::Initialized, &true);
+        upgrade::init_version(&env, STORAGE_VERSION);
+        env.storage().instance().set(&DataKey::Paused, &false);
+
+        extend_instance_ttl(&env);
+
+        let events = EventBuilder::new(&env);
+        events.publish(
+            "token_factory",
+            "initialized",
+            (admin, max_supply, env.ledger().timestamp()),
+        );
+
+        Ok(())
+    }
+
+    // ────────────────────────────────────────────────────────────────────────
+    // Token Creation
+    // ────────────────────────────────────────────────────────────────────────
+
+    /// Deploy a new token and mint `supply` to `creator`
+    pub fn create_token(
+        env: Env,
+        creator: Address,
+        name: String,
+        symbol: String,
+        decimals: u32,
+        supply: i128,
+    ) -> Result<Address, SharedError> {
+        creator.require_auth();
+        Self::require_initialized(&env)?;
+        Self::require_not_paused(&env)?;
+
+        let metadata = TokenMetadata {
+            name,
+            symbol,
+            decimals,
+            creator: creator.clone(),
+            total_supply: supply,
+        };
+        validate_metadata(&metadata)?;
+        if supply <= 0 {
+            return Err(SharedError::InvalidAmount);
+        }
+        if supply > Self::get_max_supply(&env) {
+            return Err(SharedError::AmountExceedsMax);
+        }
+
+        let mut tokens = Self::get_creator_tokens(&env, &creator);
+        if tokens.len() >= MAX_TOKENS_PER_CREATOR {
+            return Err(SharedError::LimitExceeded);
+        }
+
+        // Deploy with the factory as token admin so the supply stays capped
+        let wasm_hash: BytesN<32> = env
+            .storage()
+            .instance()
+            .get(&DataKey::TokenWasm)
+            .ok_or(SharedError::NotInitialized)?;
+        let count = Self::get_token_count(&env);
+        let this = env.current_contract_address();
+        let token = env
+            .deployer()
+            .with_current_contract(Self::salt(&env, count))
+            .deploy_v2(
+                wasm_hash,
+                (
+                    this,
+                    decimals,
+                    metadata.name.clone(),
+                    metadata.symbol.clone(),
+                ),
+            );
+        token::StellarAssetClient::new(&env, &token).mint(&creator, &supply);
+
+        let metadata_key = DataKey::Metadata(token.clone());
+        set_persistent(&env, &metadata_key, &metadata);
+
+        tokens.push_back(token.clone());
+        let creator_key = DataKey::CreatorTokens(creator.clone());
+        set_persistent(&env, &creator_key, &tokens);
+
+        env.storage()
+            .instance()
+            .set(&DataKey::TokenCount, &(count + 1));
+
+        let events = EventBuilder::new(&env);
+        events.publish(
+            "token_factory",
+            "token_created",
+            (token.clone(), creator, supply, env.ledger().timestamp()),
+        );
+
+        extend_instance_ttl(&env);
+        Ok(token)
+    }
+
+    // ────────────────────────────────────────────────────────────────────────
+    // Admin Functions
+    // ────────────────────────────────────────────────────────────────────────
+
+    /// Set the WASM hash used for new tokens (existing tokens are unaffected)
+    pub fn set_token_wasm(env: Env, token_wasm: BytesN<32>) -> Result<(), SharedError> {
+        Self::require_admin(&env)?;
+
+        env.storage()
+            .instance()
+            .set(&DataKey::TokenWasm, &token_wasm);
+        extend_instance_ttl(&env);
+
+        Ok(())
+    }
+
+    /// Set the supply cap for new tokens
+    pub fn set_max_supply(env: Env, max_supply: i128) -> Result<(), SharedError> {
+        Self::require_admin(&env)?;
+
+        if max_supply <= 0 {
+            return Err(SharedError::InvalidAmount);
+        }
+
+        env.storage()
+            .instance()
+            .set(&DataKey::MaxSupply, &max_supply);
+        extend_instance_ttl(&env);
+
+        Ok(())
+    }
+
+    /// Set the protocol registry address reported by `contract_info`
+    pub fn set_registry(env: Env, registry: Address) -> Result<(), SharedError> {
+        Self::require_admin(&env)?;
+        env.storage().instance().set(&DataKey::Registry, &registry);
+        extend_instance_ttl(&env);
+        Ok(())
+    }
+
+    /// Set admin address
+    pub fn set_admin(env: Env, new_admin: Address) -> Result<(), SharedError> {
+        Self::require_admin(&env)?;
+
+        env.storage().instance().set(&DataKey::Admin, &new_admin);
+        extend_instance_ttl(&env);
+
+        Ok(())
+    }
+
+    /// Pause/unpause token creation
+    pub fn set_paused(env: Env, paused: bool) -> Result<(), SharedError> {
+        Self::require_admin(&env)?;
+
+        env.storage().instance().set(&DataKey::Paused, &paused);
+        extend_instance_ttl(&env);
+
+        Ok(())
+    }
+
+    /// Set how far storage TTLs are extended (see `TtlConfig`)
+    pub fn set_ttl_config(env: Env, config: TtlConfig) -> Result<(), SharedError> {
+        Self::require_admin(&env)?;
+
+        set_ttl_config(&env, &config)?;
+        extend_instance_ttl(&env);
+
+        Ok(())
+    }
+
+    // ────────────────────────────────────────────────────────────────────────
+    // Upgrades (admin only)
+    // ────────────────────────────────────────────────────────────────────────
+
+    /// Queue a WASM upgrade behind the upgrade timelock. Returns its eta.
+    pub fn queue_upgrade(env: Env, new_wasm_hash: BytesN<32>) -> Result<u64, SharedError> {
+        Self::require_admin(&env)?;
+        upgrade::queue(&env, new_wasm_hash)
+    }
+
+    /// Cancel the queued upgrade
+    pub fn cancel_upgrade(env: Env) -> Result<(), SharedError> {
+        Self::require_admin(&env)?;
+        upgrade::cancel(&env)
+    }
+
+    /// Switch to the queued WASM once the timelock has elapsed
+    pub fn upgrade(env: Env, new_wasm_hash: BytesN<32>) -> Result<(), SharedError> {
+        Self::require_admin(&env)?;
+        upgrade::execute(&env, new_wasm_hash)
+    }
+
+    /// Migrate storage to `STORAGE_VERSION` after an upgrade
+    pub fn migrate(env: Env) -> Result<u32, SharedError> {
+        Self::require_admin(&env)?;
+        upgrade::migrate(&env, STORAGE_VERSION, |_, _| Ok(()))
+    }
+
+    // ────────────────────────────────────────────────────────────────────────
+    // Query Functions
+    // ────────────────────────────────────────────────────────────────────────
+
+    /// Get the storage layout version
+    pub fn version(env: Env) -> u32 {
+        upgrade::version(&env)
+    }
+
+    /// Get the queued upgrade, if any
+    pub fn get_pending_upgrade(env: Env) -> Option<PendingUpgrade> {
+        upgrade::pending(&env)
+    }
+
+    /// Get the metadata recorded for a factory token
+    pub fn get_metadata(env: Env, token: Address) -> Option<TokenMetadata> {
+        get_persistent(&env, &DataKey::Metadata(token))
+    }
+
+    /// Get the tokens deployed by a creator
+    pub fn tokens_by_creator(env: Env, creator: Address) -> Vec<Address> {
+        Self::get_creator_tokens(&env, &creator)
+    }
+
+    /// Check if a token was deployed by this factory
+    pub fn is_factory_token(env: Env, token: Address) -> bool {
+        env.storage().persistent().has(&DataKey::Metadata(token))
+    }
+
+    /// Number of tokens deployed
+    pub fn token_count(env: Env) -> u64 {
+        Self::get_token_count(&env)
+    }
+
+    /// Supply cap for new tokens
+    pub fn max_supply(env: Env) -> i128 {
+        Self::get_max_supply(&env)
+    }
+
+    /// Get admin address
+    pub fn admin(env: Env) -> Result<Address, SharedError> {
+        env.storage()
+            .instance()
+            .get(&DataKey::Admin)
+            .ok_or(SharedError::NotInitialized)
+    }
+
+    /// Get the storage TTL config
+    pub fn ttl_config(env: Env) -> TtlConfig {
+        get_ttl_config(&env)
+    }
+
+    /// Check if contract is paused
+    pub fn is_paused(env: Env) -> bool {
+        env.storage()
+            .instance()
+            .get(&DataKey::Paused)
+            .unwrap_or(false)
+    }
+
+    /// Describe this contract for explorers and wallets
+    pub fn contract_info(env: Env) -> Result<ContractInfo, SharedError> {
+        let admin = Self::admin(env.clone())?;
+        let info = ContractInfo::new(
+            &env,
+            "token_factory",
+            env!("CARGO_PKG_VERSION"),
+            admin,
+            Self::is_paused(env.clone()),
+            env.storage().instance().get(&DataKey::Registry),
+        );
+        Ok(info
+            .with("max_supply", Self::get_max_supply(&env))
+            .with("token_count", Self::get_token_count(&env) as i128)
+            .with("default_decimals", STELLAR_DECIMALS as i128))
+    }
+
+    // ────────────────────────────────────────────────────────────────────────
+    // Internal Functions
+    // ────────────────────────────────────────────────────────────────────────
+
+    fn require_initialized(env: &Env) -> Result<(), SharedError> {
+        let initialized: bool = env
+            .storage()
+            .instance()
+            .get(&DataKey::Initialized)
+            .unwrap_or(false);
+
+        if !initialized {
+            return Err(SharedError::NotInitialized);
+        }
+        Ok(())
+    }
+
+    fn require_not_paused(env: &Env) -> Result<(), SharedError> {
+        let paused: bool = env
+            .storage()
+            .instance()
+            .get(&DataKey::Paused)
+            .unwrap_or(false);
+
+        if paused {
+            return Err(SharedError::ContractPaused);
+        }
+        Ok(())
+    }
+
+    fn require_admin(env: &Env) -> Result<(), SharedError> {
+        let admin: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::Admin)
+            .ok_or(SharedError::NotInitialized)?;
+
+        admin.require_auth();
+        Ok(())
+    }
+
+    fn get_max_supply(env: &Env) -> i128 {
+        env.storage()
+            .instance()
+            .get(&DataKey::MaxSupply)
+            .unwrap_or(0)
+    }
+
+    fn get_token_count(env: &Env) -> u64 {
+        env.storage()
+            .instance()
+            .get(&DataKey::TokenCount)
+            .unwrap_or(0)
+    }
+
+    fn get_creator_tokens(env: &Env, creator: &Address) -> Vec<Address> {
+        get_persistent(env, &DataKey::CreatorTokens(creator.clone())).unwrap_or(Vec::new(env))
+    }
+
+    /// Deploy salt for the n-th token
+    fn salt(env: &Env, count: u64) -> BytesN<32> {
+        let mut salt = [0u8; 32];
+        salt[24..].copy_from_slice(&count.to_be_bytes());
+        BytesN::from_array(env, &salt)
+    }
+}
+
+// ════════════════════════════════════════════════════════════════════════════
+// Tests
+// ════════════════════════════════════════════════════════════════════════════
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use soroban_sdk::testutils::Address as _;
+
+    /// Minimal SEP-41 stand-in: a Wasm module whose `__constructor(admin,
+    /// decimals, name, symbol)` and `mint(to, amount)` both return void, so
+    /// `create_token` can run its real `deploy_v2` path in a unit test
+    const TOKEN_STUB_WASM: &[u8] = &[
+        0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00, 0x01, 0x0f, 0x02, 0x60, 0x04, 0x7e, 0x7e,
+        0x7e, 0x7e, 0x01, 0x7e, 0x60, 0x02, 0x7e, 0x7e, 0x01, 0x7e, 0x03, 0x03, 0x02, 0x00, 0x01,
+        0x07, 0x18, 0x02, 0x0d, 0x5f, 0x5f, 0x63, 0x6f, 0x6e, 0x73, 0x74, 0x72, 0x75, 0x63, 0x74,
+        0x6f, 0x72, 0x00, 0x00, 0x04, 0x6d, 0x69, 0x6e, 0x74, 0x00, 0x01, 0x0a, 0x0b, 0x02, 0x04,
+        0x00, 0x42, 0x02, 0x0b, 0x04, 0x00, 0x42, 0x02, 0x0b, 0x00, 0x1e, 0x11, 0x63, 0x6f, 0x6e,
+        0x74, 0x72, 0x61, 0x63, 0x74, 0x65, 0x6e, 0x76, 0x6d, 0x65, 0x74, 0x61, 0x76, 0x30, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x19, 0x00, 0x00, 0x00, 0x00,
+    ];
+
+    fn setup(env: &Env) -> (TokenFactoryClient<'_>, Address) {
+        let contract_id = env.register(TokenFactory, ());
+        let client = TokenFactoryClient::new(env, &contract_id);
+        let admin = Address::generate(env);
+        let wasm = BytesN::from_array(env, &[7u8; 32]);
+        client.initialize(&admin, &wasm, &1_000_000_000_000_000);
+        (client, admin)
+    }
+
+    #[test]
+    fn test_initialize() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let (client, admin) = setup(&env);
+        assert_eq!(client.admin(), admin);
+        assert_eq!(client.max_supply(), 1_000_000_000_000_000);
+        assert_eq!(client.token_count(), 0);
+
+        let wasm = BytesN::from_array(&env, &[7u8; 32]);
+        assert!(client.try_initialize(&admin, &wasm, &1).is_err());
+    }
+
+    #[test]
+    fn test_create_token_validation() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let (client, _) = setup(&env);
+        let creator = Address::generate(&env);
+        let name = String::from_str(&env, "Astro Shiba");
+        let symbol = String::from_str(&env, "ASHIB");
+        let empty = String::from_str(&env, "");
+
+        // Supply cap
+        assert!(client
+            .try_create_token(&creator, &name, &symbol, &7, &1_000_000_000_000_001)
+            .is_err());
+        assert!(client
+            .try_create_token(&creator, &name, &symbol, &7, &0)
+            .is_err());
+
+        // Metadata
+        assert!(client
+            .try_create_token(&creator, &empty, &symbol, &7, &1_000)
+            .is_err());
+        assert!(client
+            .try_create_token(&creator, &name, &empty, &7, &1_000)
+            .is_err());
+        assert!(client
+            .try_create_token(&creator, &name, &symbol, &19, &1_000)
+            .is_err());
+
+        client.set_paused(&true);
+        assert!(client
+            .try_create_token(&creator, &name, &symbol, &7, &1_000)
+            .is_err());
+
+        assert_eq!(client.token_count(), 0);
+        assert_eq!(client.tokens_by_creator(&creator).len(), 0);
+    }
+
+    #[test]
+    fn test_create_token_deploys() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let (client, _) = setup(&env);
+        let wasm = env.deployer().upload_contract_wasm(TOKEN_STUB_WASM);
+        client.set_token_wasm(&wasm);
+
+        let creator = Address::generate(&env);
+        let name = String::from_str(&env, "Astro Shiba");
+        let symbol = String::from_str(&env, "ASHIB");
+        let token = client.create_token(&creator, &name, &symbol, &7, &1_000);
+        let second = client.create_token(&creator, &name, &symbol, &7, &2_000);
+        assert_ne!(token, second);
+
+        assert!(client.is_factory_token(&token));
+        assert_eq!(client.token_count(), 2);
+        assert_eq!(
+            client.tokens_by_creator(&creator),
+            Vec::from_array(&env, [token.clone(), second])
+        );
+        let metadata = client.get_metadata(&token).unwrap();
+        assert_eq!(metadata.name, name);
+        assert_eq!(metadata.symbol, symbol);
+        assert_eq!(metadata.decimals, 7);
+        assert_eq!(metadata.creator, creator);
+        assert_eq!(metadata.total_supply, 1_000);
+    }
+
+    #[test]
+    fn test_admin_settings() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let (client, _) = setup(&env);
+        assert!(client.try_set_max_supply(&0).is_err());
+        client.set_max_supply(&5_000);
+        assert_eq!(client.max_supply(), 5_000);
+
+        let token = Address::generate(&env);
+        assert!(client.get_metadata(&token).is_none());
+        assert!(!client.is_factory_token(&token));
+    }
+}
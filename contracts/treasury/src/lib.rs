@@ -11,6 +11,11 @@
 //! - Event emission for transparency and auditing
 //! - Integration with Fee Distributor
 //! - Pause functionality for emergencies
+//! - Slashable spender bonds, sized per spender tier
+//! - Deposit receipts with timelocked refunds for mistaken transfers
+//! - Named sub-accounts ("ops", "grants", ...) with per-token budgets and limits
+//! - Optional allowlist of timelocked withdrawal destinations
+//! - Per-token auto-forward of a share of every deposit (e.g. to an insurance fund)
 //!
 //! ## Why Contract instead of Classic Account?
 //! - Contract addresses (C...) don't need trustlines - they store balances in ContractData
@@ -19,18 +24,54 @@
 //! - Governance-ready
 
 use astro_core_shared::{
-    events::{emit_admin_changed, emit_deposit, emit_paused, emit_withdraw, EventBuilder},
-    types::{extend_instance_ttl, RateLimitConfig, SharedError, TreasuryConfig, WithdrawalTracker},
+    access_control::{self, CONFIG_ADMIN, PAUSER, TREASURER, UPGRADER},
+    events::{
+        emit_admin_changed, emit_config_updated, emit_deposit, emit_fee_distributor_changed,
+        emit_paused, emit_withdraw, EventBuilder,
+    },
+    interfaces::{
+        AmmPairClient, IntoSharedResult, OracleClient, StakingPoolClient, TokenRegistryClient,
+    },
+    limits::{validate_amount, MIN_DEPOSIT_AMOUNT, NO_MAX},
+    math::{mul_div_down, safe_add, safe_sub},
+    ownership,
+    pausable::{self, PauseConfig, PauseGroup},
+    types::{
+        check_optional_deadline, extend_instance_ttl, get_persistent, get_ttl_config,
+        set_persistent, set_ttl_config, Bps, ContractInfo, RateLimitConfig, SharedError,
+        TreasuryConfig, TtlConfig, WithdrawalTracker,
+    },
+    upgrade::{self, PendingUpgrade},
+};
+use soroban_sdk::{
+    auth::{ContractContext, InvokerContractAuthEntry, SubContractInvocation},
+    contract, contractimpl, contracttype, token, vec, Address, BytesN, Env, IntoVal, Map, Symbol,
+    Vec,
 };
-use soroban_sdk::{contract, contractimpl, contracttype, token, Address, Env, Vec};
 
 // ════════════════════════════════════════════════════════════════════════════
 // Constants
 // ════════════════════════════════════════════════════════════════════════════
 
+/// Storage layout version of this code (see `migrate`)
+const STORAGE_VERSION: u32 = 2;
+
 /// Seconds in a day for rate limit reset
 const SECONDS_PER_DAY: u64 = 86400;
 
+/// Delay between queueing and executing a deposit refund
+const REFUND_TIMELOCK: u64 = SECONDS_PER_DAY;
+
+/// Delay before an allowlisted destination (or turning the allowlist off)
+/// takes effect
+const DESTINATION_TIMELOCK: u64 = 2 * SECONDS_PER_DAY;
+
+/// Maximum number of named sub-accounts
+const MAX_SUB_ACCOUNTS: u32 = 20;
+
+/// Length of a category budget period
+const SECONDS_PER_QUARTER: u64 = 90 * SECONDS_PER_DAY;
+
 // ════════════════════════════════════════════════════════════════════════════
 // Storage Keys
 // ════════════════════════════════════════════════════════════════════════════
@@ -44,7 +85,7 @@ pub enum DataKey {
     TokenList,
     /// Whether the contract is initialized
     Initialized,
-    /// Whether the contract is paused
+    /// Legacy pause flag (storage version 1, see `migrate`)
     Paused,
     /// Fee distributor address (authorized to send funds)
     FeeDistributor,
@@ -54,6 +95,168 @@ pub enum DataKey {
     Config,
     /// Withdrawal tracker per token (Address -> WithdrawalTracker)
     WithdrawalTracker(Address),
+    /// Token spenders post bonds in (ASTRO)
+    BondToken,
+    /// Address allowed to slash spender bonds (admin if unset)
+    Governance,
+    /// Required bond for a spender tier (u32 -> i128)
+    BondRequirement(u32),
+    /// Tier assigned to a spender (Address -> u32)
+    SpenderTier(Address),
+    /// Bond posted by a spender (Address -> i128)
+    SpenderBond(Address),
+    /// Sum of all posted bonds (excluded from spendable balance)
+    TotalBonded,
+    /// Next deposit receipt ID
+    NextReceiptId,
+    /// Deposit receipt by ID (u64 -> DepositReceipt)
+    Receipt(u64),
+    /// Protocol registry address (reported by `contract_info`)
+    Registry,
+    /// Token registry consulted before accepting a token (optional)
+    TokenRegistry,
+    /// Names of all sub-accounts
+    SubAccounts,
+    /// Sub-account balance per token ((Symbol, Address) -> i128)
+    SubBalance(Symbol, Address),
+    /// Sum of sub-account balances per token (excluded from free balance)
+    SubAllocated(Address),
+    /// Sub-account daily withdrawal limit per token ((Symbol, Address) -> i128)
+    SubLimit(Symbol, Address),
+    /// Sub-account withdrawal tracker per token ((Symbol, Address) -> WithdrawalTracker)
+    SubTracker(Symbol, Address),
+    /// Price source of a token in a reference token ((token, reference) -> PriceSource)
+    PriceSource(Address, Address),
+    /// Net deposits of a donor per token ((Address, Address) -> i128)
+    DonorTotal(Address, Address),
+    /// Quarterly budget of a spending category per token ((Symbol, Address) -> CategoryBudget)
+    CategoryBudget(Symbol, Address),
+    /// Spending of a category per token ((Symbol, Address) -> CategorySpend)
+    CategorySpend(Symbol, Address),
+    /// Next vesting ID
+    NextVestingId,
+    /// Vesting by ID (u64 -> Vesting)
+    Vesting(u64),
+    /// Unclaimed vesting per token (excluded from free balance)
+    VestingReserved(Address),
+    /// Treasury shares staked in a staking pool (Address -> i128)
+    StakedPosition(Address),
+    /// Staking pools holding protocol-owned stake (Vec<Address>)
+    StakingPools,
+    /// Whether outflows are restricted to allowlisted destinations (bool)
+    DestinationAllowlist,
+    /// When a queued switch-off of the allowlist takes effect (u64)
+    DestinationAllowlistOffAt,
+    /// When an allowlisted destination becomes usable (Address -> u64)
+    Destination(Address),
+    /// Auto-forward rule per token (Address -> AutoForward)
+    AutoForward(Address),
+}
+
+// ════════════════════════════════════════════════════════════════════════════
+// Types
+// ════════════════════════════════════════════════════════════════════════════
+
+/// Record of a direct deposit, used to refund mistaken transfers
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DepositReceipt {
+    /// Receipt ID
+    pub id: u64,
+    /// Depositor
+    pub from: Address,
+    /// Token deposited
+    pub token: Address,
+    /// Amount deposited
+    pub amount: i128,
+    /// Deposit timestamp
+    pub timestamp: u64,
+    /// Earliest refund time once queued (0 = not queued)
+    pub refund_eta: u64,
+    /// Whether the deposit has been refunded
+    pub refunded: bool,
+}
+
+/// Quarterly cap on what a spending category (e.g. `marketing`, `dev`,
+/// `liquidity`) may take out of the treasury in one token
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CategoryBudget {
+    /// Amount the category may spend per quarter
+    pub cap: i128,
+    /// Whether budget left unspent in a quarter carries into the next one
+    /// (at most one extra `cap`, and only into the following quarter)
+    pub rollover: bool,
+}
+
+/// A category's spending in its current quarter
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CategorySpend {
+    /// Quarter index (`timestamp / SECONDS_PER_QUARTER`)
+    pub quarter: u64,
+    /// Spent this quarter
+    pub spent: i128,
+    /// Budget carried over from the previous quarter
+    pub carried: i128,
+}
+
+/// Team allocation streamed from the treasury to a beneficiary
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Vesting {
+    /// Vesting ID
+    pub id: u64,
+    /// Address the tokens vest to
+    pub beneficiary: Address,
+    /// Token vested
+    pub token: Address,
+    /// Total allocation
+    pub total: i128,
+    /// Amount claimed so far
+    pub claimed: i128,
+    /// Vesting start timestamp
+    pub start: u64,
+    /// Seconds after `start` before anything can be claimed
+    pub cliff: u64,
+    /// Seconds after `start` until fully vested
+    pub duration: u64,
+    /// When the vesting was revoked, if it was
+    pub revoked_at: Option<u64>,
+}
+
+/// Share of every deposit of a token forwarded straight to a destination
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct AutoForward {
+    /// Share of each deposit forwarded
+    pub bps: Bps,
+    /// Recipient of the forwarded share
+    pub destination: Address,
+    /// Total forwarded to `destination` under this rule
+    pub forwarded: i128,
+}
+
+/// Where `portfolio_value` prices a token in a reference token
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PriceSource {
+    /// TWAP oracle
+    pub oracle: Address,
+    /// Pair of the token and the reference token tracked by the oracle
+    pub pair: Address,
+    /// TWAP window in seconds
+    pub window: u64,
+}
+
+/// Treasury holdings valued in a reference token by `portfolio_value`
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PortfolioValue {
+    /// Value of the priced holdings in the reference token
+    pub total: i128,
+    /// Held tokens without a price source, not included in `total`
+    pub unpriced: Vec<Address>,
 }
 
 // ════════════════════════════════════════════════════════════════════════════
@@ -107,7 +310,7 @@ impl TreasuryVault {
 
         // Initialize state
         env.storage().instance().set(&DataKey::Initialized, &true);
-        env.storage().instance().set(&DataKey::Paused, &false);
+        upgrade::init_version(&env, STORAGE_VERSION);
 
         extend_instance_ttl(&env);
 
@@ -154,32 +357,152 @@ impl TreasuryVault {
         Ok(())
     }
 
-    /// Direct deposit - transfers tokens and notifies in one call
+    /// Direct deposit - transfers tokens and notifies in one call.
+    /// Returns a receipt ID that can be used to refund the deposit.
+    /// A `memo` (e.g. the fundraising round) marks the deposit as a donation
+    /// and is published with a `donation` event.
     pub fn deposit(
         env: Env,
         from: Address,
         token: Address,
         amount: i128,
-    ) -> Result<(), SharedError> {
+        memo: Option<Symbol>,
+    ) -> Result<u64, SharedError> {
         from.require_auth();
         Self::require_initialized(&env)?;
-        Self::require_not_paused(&env)?;
-
-        if amount <= 0 {
-            return Err(SharedError::InvalidAmount);
-        }
+        pausable::require_not_paused(&env, PauseGroup::Deposits)?;
+        validate_amount(amount, MIN_DEPOSIT_AMOUNT, NO_MAX)?;
+        Self::require_token_allowed(&env, &token)?;
 
         // Transfer tokens to treasury
         let token_client = token::Client::new(&env, &token);
-        token_client.transfer(&from, &env.current_contract_address(), &amount);
+        token_client.transfer(&from, env.current_contract_address(), &amount);
 
         // Track token
         Self::track_token(&env, &token);
+        Self::forward_share(&env, &token, amount)?;
+
+        // Issue receipt
+        let receipt_id: u64 = env
+            .storage()
+            .instance()
+            .get(&DataKey::NextReceiptId)
+            .unwrap_or(1);
+        let receipt = DepositReceipt {
+            id: receipt_id,
+            from: from.clone(),
+            token: token.clone(),
+            amount,
+            timestamp: env.ledger().timestamp(),
+            refund_eta: 0,
+            refunded: false,
+        };
+        Self::save_receipt(&env, &receipt);
+        env.storage()
+            .instance()
+            .set(&DataKey::NextReceiptId, &(receipt_id + 1));
+
+        let donor_key = DataKey::DonorTotal(from.clone(), token.clone());
+        let donor_total: i128 = get_persistent(&env, &donor_key).unwrap_or(0);
+        set_persistent(&env, &donor_key, &safe_add(donor_total, amount)?);
 
         emit_deposit(&env, &token, &from, amount);
+        if let Some(memo) = memo {
+            let events = EventBuilder::new(&env);
+            events.publish(
+                "treasury",
+                "donation",
+                (receipt_id, from, token, amount, memo),
+            );
+        }
         extend_instance_ttl(&env);
 
-        Ok(())
+        Ok(receipt_id)
+    }
+
+    // ────────────────────────────────────────────────────────────────────────
+    // Deposit Refunds (admin only)
+    // ────────────────────────────────────────────────────────────────────────
+
+    /// Queue a refund of a tracked deposit (e.g. funds sent by mistake).
+    /// The refund can be executed after `REFUND_TIMELOCK`.
+    pub fn queue_refund(env: Env, receipt_id: u64) -> Result<u64, SharedError> {
+        Self::require_initialized(&env)?;
+        Self::require_admin(&env)?;
+
+        let mut receipt = Self::get_receipt_internal(&env, receipt_id)?;
+        if receipt.refunded || receipt.refund_eta != 0 {
+            return Err(SharedError::AlreadyExecuted);
+        }
+
+        receipt.refund_eta = env.ledger().timestamp() + REFUND_TIMELOCK;
+        Self::save_receipt(&env, &receipt);
+
+        let events = EventBuilder::new(&env);
+        events.publish(
+            "treasury",
+            "refund_queued",
+            (receipt_id, receipt.from, receipt.amount, receipt.refund_eta),
+        );
+
+        extend_instance_ttl(&env);
+
+        Ok(receipt.refund_eta)
+    }
+
+    /// Return a queued deposit to its sender once the timelock has elapsed.
+    ///
+    /// # Arguments
+    /// * `receipt_id` - Receipt returned by `deposit`
+    pub fn refund_deposit(env: Env, receipt_id: u64) -> Result<i128, SharedError> {
+        Self::require_initialized(&env)?;
+        pausable::require_not_paused(&env, PauseGroup::Withdrawals)?;
+        Self::require_admin(&env)?;
+
+        let mut receipt = Self::get_receipt_internal(&env, receipt_id)?;
+        if receipt.refunded {
+            return Err(SharedError::AlreadyExecuted);
+        }
+        if receipt.refund_eta == 0 {
+            return Err(SharedError::InvalidState);
+        }
+        if env.ledger().timestamp() < receipt.refund_eta {
+            return Err(SharedError::CooldownNotElapsed);
+        }
+        if Self::get_balance(&env, &receipt.token) < receipt.amount {
+            return Err(SharedError::InsufficientBalance);
+        }
+
+        receipt.refunded = true;
+        Self::save_receipt(&env, &receipt);
+
+        let donor_key = DataKey::DonorTotal(receipt.from.clone(), receipt.token.clone());
+        let donor_total: i128 = get_persistent(&env, &donor_key).unwrap_or(0);
+        set_persistent(&env, &donor_key, &safe_sub(donor_total, receipt.amount)?);
+
+        let token_client = token::Client::new(&env, &receipt.token);
+        token_client.transfer(
+            &env.current_contract_address(),
+            &receipt.from,
+            &receipt.amount,
+        );
+
+        let events = EventBuilder::new(&env);
+        events.publish(
+            "treasury",
+            "deposit_refunded",
+            (
+                receipt_id,
+                receipt.from,
+                receipt.token,
+                receipt.amount,
+                env.ledger().timestamp(),
+            ),
+        );
+
+        extend_instance_ttl(&env);
+
+        Ok(receipt.amount)
     }
 
     // ────────────────────────────────────────────────────────────────────────
@@ -187,28 +510,34 @@ impl TreasuryVault {
     // ────────────────────────────────────────────────────────────────────────
 
     /// Withdraw tokens to a specified address.
-    /// Only callable by admin.
+    /// Only callable by the admin or a `TREASURER`.
     ///
     /// # Arguments
     /// * `token` - SAC token address to withdraw
     /// * `to` - Destination address
     /// * `amount` - Amount to withdraw
+    /// * `category` - Budget category charged (see `set_category_budget`)
+    /// * `deadline` - Optional expiry timestamp for pre-signed calls
     pub fn withdraw(
         env: Env,
+        caller: Address,
         token: Address,
         to: Address,
         amount: i128,
+        category: Symbol,
+        deadline: Option<u64>,
     ) -> Result<(), SharedError> {
         Self::require_initialized(&env)?;
-        Self::require_not_paused(&env)?;
-        Self::require_admin(&env)?;
+        check_optional_deadline(&env, deadline)?;
+        pausable::require_not_paused(&env, PauseGroup::Withdrawals)?;
+        Self::require_role(&env, &caller, &TREASURER)?;
 
         if amount <= 0 {
             return Err(SharedError::InvalidAmount);
         }
 
-        // Check rate limits
-        Self::check_and_update_rate_limit(&env, &token, amount)?;
+        // Check the destination, rate limits and the category budget
+        Self::guard_withdrawal(&env, &token, &to, amount, &category)?;
 
         // Check balance
         let balance = Self::get_balance(&env, &token);
@@ -227,15 +556,22 @@ impl TreasuryVault {
     }
 
     /// Withdraw all tokens of a specific type.
-    /// Only callable by admin.
+    /// Only callable by the admin or a `TREASURER`.
     ///
     /// # Arguments
     /// * `token` - SAC token address to withdraw
     /// * `to` - Destination address
-    pub fn withdraw_all(env: Env, token: Address, to: Address) -> Result<i128, SharedError> {
+    /// * `category` - Budget category charged (see `set_category_budget`)
+    pub fn withdraw_all(
+        env: Env,
+        caller: Address,
+        token: Address,
+        to: Address,
+        category: Symbol,
+    ) -> Result<i128, SharedError> {
         Self::require_initialized(&env)?;
-        Self::require_not_paused(&env)?;
-        Self::require_admin(&env)?;
+        pausable::require_not_paused(&env, PauseGroup::Withdrawals)?;
+        Self::require_role(&env, &caller, &TREASURER)?;
 
         // Get full balance
         let balance = Self::get_balance(&env, &token);
@@ -243,8 +579,8 @@ impl TreasuryVault {
             return Err(SharedError::InsufficientBalance);
         }
 
-        // Check rate limits (withdraw_all respects limits)
-        Self::check_and_update_rate_limit(&env, &token, balance)?;
+        // Same destination, rate limit and budget checks as `withdraw`
+        Self::guard_withdrawal(&env, &token, &to, balance, &category)?;
 
         // Transfer all
         let token_client = token::Client::new(&env, &token);
@@ -256,29 +592,34 @@ impl TreasuryVault {
         Ok(balance)
     }
 
-    /// Spend tokens on behalf of treasury (for allowed spenders)
+    /// Spend tokens on behalf of treasury (for allowed spenders), charged
+    /// to a budget category
     pub fn spend(
         env: Env,
         spender: Address,
         token: Address,
         to: Address,
         amount: i128,
+        category: Symbol,
     ) -> Result<(), SharedError> {
         spender.require_auth();
         Self::require_initialized(&env)?;
-        Self::require_not_paused(&env)?;
+        pausable::require_not_paused(&env, PauseGroup::Withdrawals)?;
 
         // Check if spender is allowed
         if !Self::is_allowed_spender(&env, &spender) {
             return Err(SharedError::Unauthorized);
         }
 
+        // Non-admin spenders must hold the bond required for their tier
+        Self::require_bonded(&env, &spender)?;
+
         if amount <= 0 {
             return Err(SharedError::InvalidAmount);
         }
 
-        // Check rate limits for spender withdrawals too
-        Self::check_and_update_rate_limit(&env, &token, amount)?;
+        // Spender payments go through the same guard as withdrawals
+        Self::guard_withdrawal(&env, &token, &to, amount, &category)?;
 
         let balance = Self::get_balance(&env, &token);
         if balance < amount {
@@ -301,79 +642,111 @@ impl TreasuryVault {
     }
 
     // ────────────────────────────────────────────────────────────────────────
-    // Admin Management
+    // Sub-Accounts (admin only)
     // ────────────────────────────────────────────────────────────────────────
 
-    /// Change the admin address.
-    /// Only callable by current admin.
-    ///
-    /// # Arguments
-    /// * `new_admin` - New admin address
-    pub fn set_admin(env: Env, new_admin: Address) -> Result<(), SharedError> {
+    /// Create a named sub-account (e.g. "ops", "grants", "mm").
+    pub fn create_sub_account(env: Env, name: Symbol) -> Result<(), SharedError> {
         Self::require_initialized(&env)?;
         Self::require_admin(&env)?;
 
-        let old_admin: Address = env
-            .storage()
+        let mut accounts = Self::get_sub_accounts(&env);
+        if accounts.contains(&name) {
+            return Err(SharedError::InvalidState);
+        }
+        if accounts.len() >= MAX_SUB_ACCOUNTS {
+            return Err(SharedError::LimitExceeded);
+        }
+        accounts.push_back(name.clone());
+        env.storage()
             .instance()
-            .get(&DataKey::Admin)
-            .ok_or(SharedError::NotInitialized)?;
+            .set(&DataKey::SubAccounts, &accounts);
 
-        env.storage().instance().set(&DataKey::Admin, &new_admin);
+        let events = EventBuilder::new(&env);
+        events.publish(
+            "treasury",
+            "sub_account_created",
+            (name, env.ledger().timestamp()),
+        );
 
-        emit_admin_changed(&env, &old_admin, &new_admin);
         extend_instance_ttl(&env);
 
         Ok(())
     }
 
-    /// Set fee distributor address
-    pub fn set_fee_distributor(env: Env, fee_distributor: Address) -> Result<(), SharedError> {
+    /// Move free treasury balance into a sub-account.
+    ///
+    /// # Arguments
+    /// * `name` - Sub-account to credit
+    /// * `token` - Token to allocate
+    /// * `amount` - Amount taken from the free balance
+    pub fn fund_sub_account(
+        env: Env,
+        name: Symbol,
+        token: Address,
+        amount: i128,
+    ) -> Result<i128, SharedError> {
         Self::require_initialized(&env)?;
         Self::require_admin(&env)?;
+        Self::require_sub_account(&env, &name)?;
+
+        if amount <= 0 {
+            return Err(SharedError::InvalidAmount);
+        }
+        if Self::get_balance(&env, &token) < amount {
+            return Err(SharedError::InsufficientBalance);
+        }
+
+        let balance = safe_add(Self::get_sub_balance(&env, &name, &token), amount)?;
+        Self::set_sub_balance(&env, &name, &token, balance);
+        let allocated = safe_add(Self::get_sub_allocated(&env, &token), amount)?;
+        Self::set_sub_allocated(&env, &token, allocated);
+
+        let events = EventBuilder::new(&env);
+        events.publish(
+            "treasury",
+            "sub_funded",
+            (name, token, amount, env.ledger().timestamp()),
+        );
 
-        env.storage()
-            .instance()
-            .set(&DataKey::FeeDistributor, &fee_distributor);
         extend_instance_ttl(&env);
 
-        Ok(())
+        Ok(balance)
     }
 
-    /// Add an allowed spender
-    pub fn add_spender(env: Env, spender: Address) -> Result<(), SharedError> {
+    /// Move an internal balance between two sub-accounts (no token transfer).
+    pub fn transfer_between_sub_accounts(
+        env: Env,
+        from: Symbol,
+        to: Symbol,
+        token: Address,
+        amount: i128,
+    ) -> Result<(), SharedError> {
         Self::require_initialized(&env)?;
         Self::require_admin(&env)?;
+        Self::require_sub_account(&env, &from)?;
+        Self::require_sub_account(&env, &to)?;
 
-        let mut spenders: Vec<Address> = env
-            .storage()
-            .instance()
-            .get(&DataKey::AllowedSpenders)
-            .unwrap_or(Vec::new(&env));
-
-        // Check if already exists
-        for s in spenders.iter() {
-            if s == spender {
-                return Ok(());
-            }
+        if amount <= 0 {
+            return Err(SharedError::InvalidAmount);
         }
-
-        // Check max spenders limit
-        let config = Self::get_config_internal(&env);
-        if spenders.len() >= config.max_spenders {
-            return Err(SharedError::LimitExceeded);
+        if from == to {
+            return Err(SharedError::InvalidState);
         }
 
-        spenders.push_back(spender.clone());
-        env.storage()
-            .instance()
-            .set(&DataKey::AllowedSpenders, &spenders);
+        let from_balance = Self::get_sub_balance(&env, &from, &token);
+        if from_balance < amount {
+            return Err(SharedError::InsufficientBalance);
+        }
+        Self::set_sub_balance(&env, &from, &token, safe_sub(from_balance, amount)?);
+        let to_balance = safe_add(Self::get_sub_balance(&env, &to, &token), amount)?;
+        Self::set_sub_balance(&env, &to, &token, to_balance);
 
         let events = EventBuilder::new(&env);
         events.publish(
             "treasury",
-            "spender_added",
-            (spender, env.ledger().timestamp()),
+            "sub_transfer",
+            (from, to, token, amount, env.ledger().timestamp()),
         );
 
         extend_instance_ttl(&env);
@@ -381,33 +754,75 @@ impl TreasuryVault {
         Ok(())
     }
 
-    /// Remove an allowed spender
-    pub fn remove_spender(env: Env, spender: Address) -> Result<(), SharedError> {
+    /// Set a sub-account's daily withdrawal limit for a token (0 = unlimited).
+    pub fn set_sub_account_limit(
+        env: Env,
+        name: Symbol,
+        token: Address,
+        daily_limit: i128,
+    ) -> Result<(), SharedError> {
         Self::require_initialized(&env)?;
         Self::require_admin(&env)?;
+        Self::require_sub_account(&env, &name)?;
 
-        let spenders: Vec<Address> = env
-            .storage()
-            .instance()
-            .get(&DataKey::AllowedSpenders)
-            .unwrap_or(Vec::new(&env));
+        if daily_limit < 0 {
+            return Err(SharedError::InvalidAmount);
+        }
 
-        let mut new_spenders = Vec::new(&env);
-        for s in spenders.iter() {
-            if s != spender {
-                new_spenders.push_back(s);
-            }
+        let key = DataKey::SubLimit(name, token);
+        set_persistent(&env, &key, &daily_limit);
+
+        extend_instance_ttl(&env);
+
+        Ok(())
+    }
+
+    /// Withdraw tokens attributed to a sub-account.
+    /// Subject to the sub-account's daily limit and the treasury rate limits.
+    ///
+    /// # Arguments
+    /// * `name` - Sub-account to debit
+    /// * `token` - SAC token address to withdraw
+    /// * `to` - Destination address
+    /// * `amount` - Amount to withdraw
+    pub fn withdraw_from_sub_account(
+        env: Env,
+        name: Symbol,
+        token: Address,
+        to: Address,
+        amount: i128,
+    ) -> Result<(), SharedError> {
+        Self::require_initialized(&env)?;
+        pausable::require_not_paused(&env, PauseGroup::Withdrawals)?;
+        Self::require_admin(&env)?;
+        Self::require_sub_account(&env, &name)?;
+        Self::require_destination(&env, &to)?;
+
+        if amount <= 0 {
+            return Err(SharedError::InvalidAmount);
         }
 
-        env.storage()
-            .instance()
-            .set(&DataKey::AllowedSpenders, &new_spenders);
+        let balance = Self::get_sub_balance(&env, &name, &token);
+        if balance < amount {
+            return Err(SharedError::InsufficientBalance);
+        }
+
+        Self::check_and_update_sub_limit(&env, &name, &token, amount)?;
+        Self::check_and_update_rate_limit(&env, &token, amount)?;
+
+        Self::set_sub_balance(&env, &name, &token, safe_sub(balance, amount)?);
+        let allocated = safe_sub(Self::get_sub_allocated(&env, &token), amount)?;
+        Self::set_sub_allocated(&env, &token, allocated);
+
+        let token_client = token::Client::new(&env, &token);
+        token_client.transfer(&env.current_contract_address(), &to, &amount);
 
+        emit_withdraw(&env, &token, &to, amount);
         let events = EventBuilder::new(&env);
         events.publish(
             "treasury",
-            "spender_removed",
-            (spender, env.ledger().timestamp()),
+            "sub_withdraw",
+            (name, token, to, amount, env.ledger().timestamp()),
         );
 
         extend_instance_ttl(&env);
@@ -415,55 +830,982 @@ impl TreasuryVault {
         Ok(())
     }
 
-    /// Pause/unpause the contract
-    pub fn set_paused(env: Env, paused: bool) -> Result<(), SharedError> {
+    // ────────────────────────────────────────────────────────────────────────
+    // Destination Allowlist (admin only)
+    // ────────────────────────────────────────────────────────────────────────
+
+    /// Turn the destination allowlist on or off. While on, `withdraw`,
+    /// `withdraw_all`, `spend`, `withdraw_from_sub_account` and
+    /// `create_vesting` may only pay allowlisted addresses. Turning it on is immediate; turning it off
+    /// only takes effect after `DESTINATION_TIMELOCK`, so a stolen admin key
+    /// can't drain the treasury before anyone notices.
+    ///
+    /// Returns when the new setting is in effect.
+    pub fn set_destination_allowlist(env: Env, enabled: bool) -> Result<u64, SharedError> {
         Self::require_initialized(&env)?;
         Self::require_admin(&env)?;
 
-        env.storage().instance().set(&DataKey::Paused, &paused);
+        let now = env.ledger().timestamp();
+        let effective_at = if enabled || !Self::destination_allowlist_enabled(env.clone()) {
+            env.storage()
+                .instance()
+                .set(&DataKey::DestinationAllowlist, &enabled);
+            env.storage()
+                .instance()
+                .remove(&DataKey::DestinationAllowlistOffAt);
+            now
+        } else {
+            let off_at: u64 = match env
+                .storage()
+                .instance()
+                .get(&DataKey::DestinationAllowlistOffAt)
+            {
+                Some(off_at) => off_at,
+                None => now + DESTINATION_TIMELOCK,
+            };
+            env.storage()
+                .instance()
+                .set(&DataKey::DestinationAllowlistOffAt, &off_at);
+            off_at
+        };
 
-        let admin: Address = env
-            .storage()
-            .instance()
-            .get(&DataKey::Admin)
-            .ok_or(SharedError::NotInitialized)?;
+        let events = EventBuilder::new(&env);
+        events.publish(
+            "treasury",
+            "destination_allowlist",
+            (enabled, effective_at, now),
+        );
 
-        emit_paused(&env, paused, &admin);
         extend_instance_ttl(&env);
 
-        Ok(())
+        Ok(effective_at)
     }
 
-    /// Update treasury configuration (rate limits, max tokens/spenders)
-    pub fn update_config(env: Env, new_config: TreasuryConfig) -> Result<(), SharedError> {
+    /// Allowlist a destination. It becomes usable after
+    /// `DESTINATION_TIMELOCK`; re-adding a pending destination keeps its
+    /// original time. Returns when the destination becomes usable.
+    pub fn add_destination(env: Env, destination: Address) -> Result<u64, SharedError> {
         Self::require_initialized(&env)?;
         Self::require_admin(&env)?;
 
-        env.storage().instance().set(&DataKey::Config, &new_config);
+        let key = DataKey::Destination(destination.clone());
+        let active_at: u64 = match get_persistent(&env, &key) {
+            Some(active_at) => active_at,
+            None => env.ledger().timestamp() + DESTINATION_TIMELOCK,
+        };
+        set_persistent(&env, &key, &active_at);
 
         let events = EventBuilder::new(&env);
         events.publish(
             "treasury",
-            "config_updated",
-            (
-                new_config.rate_limit.enabled,
-                new_config.rate_limit.daily_limit,
-                new_config.max_tokens,
-                env.ledger().timestamp(),
-            ),
+            "destination_added",
+            (destination, active_at, env.ledger().timestamp()),
         );
 
         extend_instance_ttl(&env);
 
-        Ok(())
+        Ok(active_at)
     }
 
-    // ────────────────────────────────────────────────────────────────────────
-    // View Functions
-    // ────────────────────────────────────────────────────────────────────────
-
-    /// Get the current admin address.
-    pub fn get_admin(env: Env) -> Result<Address, SharedError> {
+    /// Remove a destination from the allowlist (immediate)
+    pub fn remove_destination(env: Env, destination: Address) -> Result<(), SharedError> {
+        Self::require_initialized(&env)?;
+        Self::require_admin(&env)?;
+
+        env.storage()
+            .persistent()
+            .remove(&DataKey::Destination(destination.clone()));
+
+        let events = EventBuilder::new(&env);
+        events.publish(
+            "treasury",
+            "destination_removed",
+            (destination, env.ledger().timestamp()),
+        );
+
+        extend_instance_ttl(&env);
+
+        Ok(())
+    }
+
+    // ────────────────────────────────────────────────────────────────────────
+    // Auto-Forward (admin only)
+    // ────────────────────────────────────────────────────────────────────────
+
+    /// Forward `bps` of every `deposit` of `token` to `destination` as it
+    /// arrives (e.g. 10% of fee inflows to the insurance fund). `bps = 0`
+    /// removes the rule. The destination must pass the allowlist, if on.
+    /// Only `deposit` is covered: plain transfers and `notify_deposit`
+    /// don't move tokens through the treasury, so there is nothing to
+    /// forward from.
+    ///
+    /// The rule's `forwarded` total carries over while the destination
+    /// stays the same and restarts at zero when it changes.
+    pub fn set_auto_forward(
+        env: Env,
+        token: Address,
+        bps: u32,
+        destination: Address,
+    ) -> Result<(), SharedError> {
+        Self::require_initialized(&env)?;
+        Self::require_admin(&env)?;
+        let bps = Bps::new(bps)?;
+
+        let key = DataKey::AutoForward(token.clone());
+        let events = EventBuilder::new(&env);
+        if bps == Bps::ZERO {
+            env.storage().persistent().remove(&key);
+            events.publish(
+                "treasury",
+                "auto_forward_removed",
+                (token, env.ledger().timestamp()),
+            );
+            extend_instance_ttl(&env);
+            return Ok(());
+        }
+
+        Self::require_destination(&env, &destination)?;
+        let existing: Option<AutoForward> = get_persistent(&env, &key);
+        let forwarded = match existing {
+            Some(rule) if rule.destination == destination => rule.forwarded,
+            _ => 0,
+        };
+        let rule = AutoForward {
+            bps,
+            destination: destination.clone(),
+            forwarded,
+        };
+        set_persistent(&env, &key, &rule);
+
+        events.publish(
+            "treasury",
+            "auto_forward_set",
+            (token, bps.get(), destination, env.ledger().timestamp()),
+        );
+
+        extend_instance_ttl(&env);
+
+        Ok(())
+    }
+
+    // ────────────────────────────────────────────────────────────────────────
+    // Spender Bonds
+    // ────────────────────────────────────────────────────────────────────────
+
+    /// Post (or top up) a spender bond in the bond token.
+    ///
+    /// # Arguments
+    /// * `spender` - Spender posting the bond
+    /// * `amount` - Amount of bond token to lock
+    pub fn post_bond(env: Env, spender: Address, amount: i128) -> Result<i128, SharedError> {
+        spender.require_auth();
+        Self::require_initialized(&env)?;
+        pausable::require_not_paused(&env, PauseGroup::Deposits)?;
+
+        if amount <= 0 {
+            return Err(SharedError::InvalidAmount);
+        }
+
+        let bond_token = Self::get_bond_token(&env)?;
+        let token_client = token::Client::new(&env, &bond_token);
+        token_client.transfer(&spender, env.current_contract_address(), &amount);
+
+        let bond = safe_add(Self::get_spender_bond(&env, &spender), amount)?;
+        Self::set_spender_bond(&env, &spender, bond)?;
+
+        let events = EventBuilder::new(&env);
+        events.publish(
+            "treasury",
+            "bond_posted",
+            (spender, amount, bond, env.ledger().timestamp()),
+        );
+
+        extend_instance_ttl(&env);
+
+        Ok(bond)
+    }
+
+    /// Withdraw a spender bond.
+    /// Active spenders may only withdraw the excess above their tier requirement;
+    /// removed spenders may withdraw everything.
+    pub fn withdraw_bond(env: Env, spender: Address, amount: i128) -> Result<i128, SharedError> {
+        spender.require_auth();
+        Self::require_initialized(&env)?;
+
+        if amount <= 0 {
+            return Err(SharedError::InvalidAmount);
+        }
+
+        let bond = Self::get_spender_bond(&env, &spender);
+        if amount > bond {
+            return Err(SharedError::InsufficientBalance);
+        }
+
+        let remaining = safe_sub(bond, amount)?;
+        if Self::is_allowed_spender(&env, &spender)
+            && remaining < Self::required_bond_internal(&env, &spender)
+        {
+            return Err(SharedError::BelowMinimum);
+        }
+
+        Self::set_spender_bond(&env, &spender, remaining)?;
+
+        let bond_token = Self::get_bond_token(&env)?;
+        let token_client = token::Client::new(&env, &bond_token);
+        token_client.transfer(&env.current_contract_address(), &spender, &amount);
+
+        let events = EventBuilder::new(&env);
+        events.publish(
+            "treasury",
+            "bond_withdrawn",
+            (spender, amount, remaining, env.ledger().timestamp()),
+        );
+
+        extend_instance_ttl(&env);
+
+        Ok(remaining)
+    }
+
+    /// Slash a spender bond for misuse of `spend`.
+    /// Only callable by governance (or the admin if no governance is set).
+    ///
+    /// # Arguments
+    /// * `spender` - Spender being slashed
+    /// * `amount` - Amount of bond to confiscate (capped at the posted bond)
+    /// * `recipient` - Address receiving the slashed bond
+    pub fn slash_bond(
+        env: Env,
+        spender: Address,
+        amount: i128,
+        recipient: Address,
+    ) -> Result<i128, SharedError> {
+        Self::require_initialized(&env)?;
+        Self::require_governance(&env)?;
+
+        if amount <= 0 {
+            return Err(SharedError::InvalidAmount);
+        }
+
+        let bond = Self::get_spender_bond(&env, &spender);
+        let slashed = if amount > bond { bond } else { amount };
+        if slashed == 0 {
+            return Err(SharedError::InsufficientBalance);
+        }
+
+        let remaining = safe_sub(bond, slashed)?;
+        Self::set_spender_bond(&env, &spender, remaining)?;
+
+        let bond_token = Self::get_bond_token(&env)?;
+        let token_client = token::Client::new(&env, &bond_token);
+        token_client.transfer(&env.current_contract_address(), &recipient, &slashed);
+
+        let events = EventBuilder::new(&env);
+        events.publish(
+            "treasury",
+            "bond_slashed",
+            (
+                spender,
+                slashed,
+                remaining,
+                recipient,
+                env.ledger().timestamp(),
+            ),
+        );
+
+        extend_instance_ttl(&env);
+
+        Ok(slashed)
+    }
+
+    /// Set the token bonds are posted in.
+    /// Cannot be changed while bonds are outstanding.
+    pub fn set_bond_token(env: Env, bond_token: Address) -> Result<(), SharedError> {
+        Self::require_initialized(&env)?;
+        Self::require_admin(&env)?;
+
+        if Self::get_total_bonded(&env) > 0 {
+            return Err(SharedError::InvalidState);
+        }
+
+        env.storage()
+            .instance()
+            .set(&DataKey::BondToken, &bond_token);
+        extend_instance_ttl(&env);
+
+        Ok(())
+    }
+
+    /// Set (or clear) the quarterly budget of a spending category in `token`.
+    /// `withdraw`, `withdraw_all` and `spend` fail with `LimitExceeded` beyond
+    /// it; a category without a budget cannot spend.
+    pub fn set_category_budget(
+        env: Env,
+        category: Symbol,
+        token: Address,
+        budget: Option<CategoryBudget>,
+    ) -> Result<(), SharedError> {
+        Self::require_initialized(&env)?;
+        Self::require_admin(&env)?;
+
+        let key = DataKey::CategoryBudget(category.clone(), token.clone());
+        match budget {
+            Some(budget) => {
+                if budget.cap < 0 {
+                    return Err(SharedError::InvalidAmount);
+                }
+                set_persistent(&env, &key, &budget);
+            }
+            None => env.storage().persistent().remove(&key),
+        }
+
+        let events = EventBuilder::new(&env);
+        events.publish("treasury", "budget_set", (category, token));
+
+        extend_instance_ttl(&env);
+        Ok(())
+    }
+
+    /// Set (or clear) how `token` is priced in `reference` by `portfolio_value`.
+    /// The pair must trade `token` against `reference`.
+    pub fn set_price_source(
+        env: Env,
+        token: Address,
+        reference: Address,
+        source: Option<PriceSource>,
+    ) -> Result<(), SharedError> {
+        Self::require_initialized(&env)?;
+        Self::require_admin(&env)?;
+
+        let key = DataKey::PriceSource(token.clone(), reference.clone());
+        match source {
+            Some(source) => {
+                let pair = AmmPairClient::new(&env, &source.pair);
+                let token_0 = pair.try_token_0().into_shared()?;
+                let token_1 = pair.try_token_1().into_shared()?;
+                if !((token_0 == token && token_1 == reference)
+                    || (token_0 == reference && token_1 == token))
+                {
+                    return Err(SharedError::InvalidAddress);
+                }
+                set_persistent(&env, &key, &source);
+            }
+            None => env.storage().persistent().remove(&key),
+        }
+        extend_instance_ttl(&env);
+
+        Ok(())
+    }
+
+    /// Set the governance address allowed to slash bonds
+    pub fn set_governance(env: Env, governance: Address) -> Result<(), SharedError> {
+        Self::require_initialized(&env)?;
+        Self::require_admin(&env)?;
+
+        env.storage()
+            .instance()
+            .set(&DataKey::Governance, &governance);
+        extend_instance_ttl(&env);
+
+        Ok(())
+    }
+
+    /// Set the bond required for a spender tier (0 = no bond)
+    pub fn set_bond_requirement(env: Env, tier: u32, amount: i128) -> Result<(), SharedError> {
+        Self::require_initialized(&env)?;
+        Self::require_admin(&env)?;
+
+        if amount < 0 {
+            return Err(SharedError::InvalidAmount);
+        }
+
+        env.storage()
+            .instance()
+            .set(&DataKey::BondRequirement(tier), &amount);
+
+        let events = EventBuilder::new(&env);
+        events.publish(
+            "treasury",
+            "bond_requirement",
+            (tier, amount, env.ledger().timestamp()),
+        );
+
+        extend_instance_ttl(&env);
+
+        Ok(())
+    }
+
+    /// Assign a spender to a bond tier (default tier is 0)
+    pub fn set_spender_tier(env: Env, spender: Address, tier: u32) -> Result<(), SharedError> {
+        Self::require_initialized(&env)?;
+        Self::require_admin(&env)?;
+
+        let key = DataKey::SpenderTier(spender);
+        set_persistent(&env, &key, &tier);
+        extend_instance_ttl(&env);
+
+        Ok(())
+    }
+
+    // ────────────────────────────────────────────────────────────────────────
+    // Team Vesting
+    // ────────────────────────────────────────────────────────────────────────
+
+    /// Reserve `total` of the free balance for `beneficiary`, vesting
+    /// linearly over `duration` seconds from now. Nothing is claimable
+    /// before `cliff` seconds have passed. The whole `total` counts as a
+    /// withdrawal to `beneficiary` now: it must be an allowed destination,
+    /// and is charged to the rate limit and `category`'s budget. Returns the
+    /// vesting ID.
+    pub fn create_vesting(
+        env: Env,
+        beneficiary: Address,
+        token: Address,
+        total: i128,
+        cliff: u64,
+        duration: u64,
+        category: Symbol,
+    ) -> Result<u64, SharedError> {
+        Self::require_initialized(&env)?;
+        Self::require_admin(&env)?;
+
+        if total <= 0 {
+            return Err(SharedError::InvalidAmount);
+        }
+        if duration == 0 || cliff > duration {
+            return Err(SharedError::InvalidTimestamp);
+        }
+        if Self::get_balance(&env, &token) < total {
+            return Err(SharedError::InsufficientBalance);
+        }
+        Self::guard_withdrawal(&env, &token, &beneficiary, total, &category)?;
+
+        let id: u64 = env
+            .storage()
+            .instance()
+            .get(&DataKey::NextVestingId)
+            .unwrap_or(1);
+        env.storage()
+            .instance()
+            .set(&DataKey::NextVestingId, &(id + 1));
+
+        let vesting = Vesting {
+            id,
+            beneficiary: beneficiary.clone(),
+            token: token.clone(),
+            total,
+            claimed: 0,
+            start: env.ledger().timestamp(),
+            cliff,
+            duration,
+            revoked_at: None,
+        };
+        set_persistent(&env, &DataKey::Vesting(id), &vesting);
+        let reserved = safe_add(Self::get_vesting_reserved(&env, &token), total)?;
+        Self::set_vesting_reserved(&env, &token, reserved);
+
+        let events = EventBuilder::new(&env);
+        events.publish(
+            "treasury",
+            "vesting_created",
+            (id, beneficiary, token, total, cliff, duration),
+        );
+
+        extend_instance_ttl(&env);
+
+        Ok(id)
+    }
+
+    /// Transfer the vested, unclaimed part of a vesting to its beneficiary.
+    /// Returns the amount claimed.
+    pub fn claim_vesting(env: Env, id: u64) -> Result<i128, SharedError> {
+        Self::require_initialized(&env)?;
+        pausable::require_not_paused(&env, PauseGroup::Withdrawals)?;
+
+        let mut vesting = Self::load_vesting(&env, id)?;
+        vesting.beneficiary.require_auth();
+
+        let vested = Self::vested_amount(&vesting, env.ledger().timestamp())?;
+        let amount = safe_sub(vested, vesting.claimed)?;
+        if amount <= 0 {
+            return Ok(0);
+        }
+
+        vesting.claimed = vested;
+        set_persistent(&env, &DataKey::Vesting(id), &vesting);
+        let reserved = safe_sub(Self::get_vesting_reserved(&env, &vesting.token), amount)?;
+        Self::set_vesting_reserved(&env, &vesting.token, reserved);
+
+        let token_client = token::Client::new(&env, &vesting.token);
+        token_client.transfer(
+            &env.current_contract_address(),
+            &vesting.beneficiary,
+            &amount,
+        );
+
+        let events = EventBuilder::new(&env);
+        events.publish(
+            "treasury",
+            "vesting_claimed",
+            (id, vesting.beneficiary, amount, env.ledger().timestamp()),
+        );
+
+        extend_instance_ttl(&env);
+
+        Ok(amount)
+    }
+
+    /// Stop a vesting. What has vested stays claimable by the beneficiary;
+    /// the unvested remainder returns to the free balance. Returns it.
+    pub fn revoke_vesting(env: Env, id: u64) -> Result<i128, SharedError> {
+        Self::require_initialized(&env)?;
+        Self::require_admin(&env)?;
+
+        let mut vesting = Self::load_vesting(&env, id)?;
+        if vesting.revoked_at.is_some() {
+            return Err(SharedError::AlreadyExecuted);
+        }
+
+        let now = env.ledger().timestamp();
+        let unvested = safe_sub(vesting.total, Self::vested_amount(&vesting, now)?)?;
+        vesting.revoked_at = Some(now);
+        set_persistent(&env, &DataKey::Vesting(id), &vesting);
+        let reserved = safe_sub(Self::get_vesting_reserved(&env, &vesting.token), unvested)?;
+        Self::set_vesting_reserved(&env, &vesting.token, reserved);
+
+        let events = EventBuilder::new(&env);
+        events.publish("treasury", "vesting_revoked", (id, unvested, now));
+
+        extend_instance_ttl(&env);
+
+        Ok(unvested)
+    }
+
+    // ────────────────────────────────────────────────────────────────────────
+    // Protocol-Owned Stake
+    // ────────────────────────────────────────────────────────────────────────
+
+    /// Stake `amount` of the free balance of `staking_pool`'s stake token
+    /// (admin or governance). Staked funds leave `balance` and are reported
+    /// by `staked_positions`. Staking is an outflow like `withdraw`: the
+    /// pool must be an allowed destination, and `amount` is charged to the
+    /// rate limit and `category`'s budget. Returns the treasury's shares in
+    /// the pool.
+    pub fn stake_treasury_funds(
+        env: Env,
+        staking_pool: Address,
+        amount: i128,
+        category: Symbol,
+    ) -> Result<i128, SharedError> {
+        Self::require_initialized(&env)?;
+        Self::require_governance(&env)?;
+        pausable::require_not_paused(&env, PauseGroup::Withdrawals)?;
+
+        if amount <= 0 {
+            return Err(SharedError::InvalidAmount);
+        }
+
+        let pool = StakingPoolClient::new(&env, &staking_pool);
+        let stake_token = pool.try_stake_token().into_shared()?;
+        if Self::get_balance(&env, &stake_token) < amount {
+            return Err(SharedError::InsufficientBalance);
+        }
+        Self::guard_withdrawal(&env, &stake_token, &staking_pool, amount, &category)?;
+
+        // The pool pulls the tokens from this contract
+        let this = env.current_contract_address();
+        env.authorize_as_current_contract(vec![
+            &env,
+            InvokerContractAuthEntry::Contract(SubContractInvocation {
+                context: ContractContext {
+                    contract: stake_token.clone(),
+                    fn_name: Symbol::new(&env, "transfer"),
+                    args: (this.clone(), staking_pool.clone(), amount).into_val(&env),
+                },
+                sub_invocations: Vec::new(&env),
+            }),
+        ]);
+        let shares = pool.try_stake(&this, &amount, &None).into_shared()?;
+        Self::set_staked_position(&env, &staking_pool, shares);
+
+        let events = EventBuilder::new(&env);
+        events.publish(
+            "treasury",
+            "funds_staked",
+            (staking_pool, stake_token, amount, shares),
+        );
+
+        extend_instance_ttl(&env);
+
+        Ok(shares)
+    }
+
+    /// Unstake `shares` of the treasury's stake in `staking_pool` (admin or
+    /// governance). The tokens and any harvested rewards return to the
+    /// liquid balance. Returns the treasury's remaining shares.
+    pub fn unstake_treasury_funds(
+        env: Env,
+        staking_pool: Address,
+        shares: i128,
+    ) -> Result<i128, SharedError> {
+        Self::require_initialized(&env)?;
+        Self::require_governance(&env)?;
+
+        if shares <= 0 {
+            return Err(SharedError::InvalidAmount);
+        }
+        if shares > Self::get_staked_position(&env, &staking_pool) {
+            return Err(SharedError::InsufficientBalance);
+        }
+
+        let pool = StakingPoolClient::new(&env, &staking_pool);
+        let this = env.current_contract_address();
+        let remaining = pool.try_unstake(&this, &shares, &None).into_shared()?;
+        Self::set_staked_position(&env, &staking_pool, remaining);
+
+        let stake_token = pool.try_stake_token().into_shared()?;
+        Self::track_token(&env, &stake_token);
+
+        let events = EventBuilder::new(&env);
+        events.publish(
+            "treasury",
+            "funds_unstaked",
+            (staking_pool, stake_token, shares, remaining),
+        );
+
+        extend_instance_ttl(&env);
+
+        Ok(remaining)
+    }
+
+    // ────────────────────────────────────────────────────────────────────────
+    // Admin Management
+    // ────────────────────────────────────────────────────────────────────────
+
+    /// Set the protocol registry address reported by `contract_info`
+    pub fn set_registry(env: Env, registry: Address) -> Result<(), SharedError> {
+        Self::require_admin(&env)?;
+        env.storage().instance().set(&DataKey::Registry, &registry);
+        extend_instance_ttl(&env);
+        Ok(())
+    }
+
+    /// Set the token registry consulted before accepting a token
+    /// (`None` accepts every token)
+    pub fn set_token_registry(env: Env, registry: Option<Address>) -> Result<(), SharedError> {
+        Self::require_admin(&env)?;
+        match registry {
+            Some(registry) => env
+                .storage()
+                .instance()
+                .set(&DataKey::TokenRegistry, &registry),
+            None => env.storage().instance().remove(&DataKey::TokenRegistry),
+        }
+        extend_instance_ttl(&env);
+        Ok(())
+    }
+
+    /// Change the admin address.
+    /// Only callable by current admin.
+    ///
+    /// # Arguments
+    /// Propose a new admin; it takes over once it calls `accept_admin`
+    pub fn propose_admin(env: Env, new_admin: Address) -> Result<(), SharedError> {
+        Self::require_initialized(&env)?;
+        Self::require_admin(&env)?;
+
+        let admin = Self::get_admin(env.clone())?;
+        ownership::propose(&env, &admin, &new_admin)?;
+        extend_instance_ttl(&env);
+
+        Ok(())
+    }
+
+    /// Accept a pending admin proposal (proposed admin only)
+    pub fn accept_admin(env: Env) -> Result<(), SharedError> {
+        Self::require_initialized(&env)?;
+        let old_admin = Self::get_admin(env.clone())?;
+        let new_admin = ownership::accept(&env, &old_admin)?;
+
+        env.storage().instance().set(&DataKey::Admin, &new_admin);
+
+        emit_admin_changed(&env, &old_admin, &new_admin);
+        extend_instance_ttl(&env);
+
+        Ok(())
+    }
+
+    /// Withdraw a pending admin proposal
+    pub fn cancel_admin_transfer(env: Env) -> Result<(), SharedError> {
+        Self::require_initialized(&env)?;
+        Self::require_admin(&env)?;
+
+        ownership::cancel(&env)?;
+        extend_instance_ttl(&env);
+
+        Ok(())
+    }
+
+    /// Set fee distributor address
+    pub fn set_fee_distributor(env: Env, fee_distributor: Address) -> Result<(), SharedError> {
+        Self::require_initialized(&env)?;
+        Self::require_admin(&env)?;
+
+        let old_fee_distributor = env.storage().instance().get(&DataKey::FeeDistributor);
+        env.storage()
+            .instance()
+            .set(&DataKey::FeeDistributor, &fee_distributor);
+        emit_fee_distributor_changed(&env, old_fee_distributor, &fee_distributor);
+        extend_instance_ttl(&env);
+
+        Ok(())
+    }
+
+    /// Add an allowed spender
+    pub fn add_spender(env: Env, spender: Address) -> Result<(), SharedError> {
+        Self::require_initialized(&env)?;
+        Self::require_admin(&env)?;
+
+        let mut spenders: Vec<Address> = env
+            .storage()
+            .instance()
+            .get(&DataKey::AllowedSpenders)
+            .unwrap_or(Vec::new(&env));
+
+        // Check if already exists
+        for s in spenders.iter() {
+            if s == spender {
+                return Ok(());
+            }
+        }
+
+        // Check max spenders limit
+        let config = Self::get_config_internal(&env);
+        if spenders.len() >= config.max_spenders {
+            return Err(SharedError::LimitExceeded);
+        }
+
+        spenders.push_back(spender.clone());
+        env.storage()
+            .instance()
+            .set(&DataKey::AllowedSpenders, &spenders);
+
+        let events = EventBuilder::new(&env);
+        events.publish(
+            "treasury",
+            "spender_added",
+            (spender, env.ledger().timestamp()),
+        );
+
+        extend_instance_ttl(&env);
+
+        Ok(())
+    }
+
+    /// Remove an allowed spender
+    pub fn remove_spender(env: Env, spender: Address) -> Result<(), SharedError> {
+        Self::require_initialized(&env)?;
+        Self::require_admin(&env)?;
+
+        let spenders: Vec<Address> = env
+            .storage()
+            .instance()
+            .get(&DataKey::AllowedSpenders)
+            .unwrap_or(Vec::new(&env));
+
+        let mut new_spenders = Vec::new(&env);
+        for s in spenders.iter() {
+            if s != spender {
+                new_spenders.push_back(s);
+            }
+        }
+
+        env.storage()
+            .instance()
+            .set(&DataKey::AllowedSpenders, &new_spenders);
+
+        let events = EventBuilder::new(&env);
+        events.publish(
+            "treasury",
+            "spender_removed",
+            (spender, env.ledger().timestamp()),
+        );
+
+        extend_instance_ttl(&env);
+
+        Ok(())
+    }
+
+    /// Pause/unpause every function group (admin or `PAUSER`)
+    pub fn set_paused(env: Env, caller: Address, paused: bool) -> Result<(), SharedError> {
+        Self::require_initialized(&env)?;
+        Self::require_role(&env, &caller, &PAUSER)?;
+
+        pausable::set(&env, &PauseConfig::uniform(paused));
+
+        emit_paused(&env, paused, &caller);
+        extend_instance_ttl(&env);
+
+        Ok(())
+    }
+
+    /// Pause individual function groups, e.g. halt deposits while
+    /// withdrawals stay open (admin or `PAUSER`)
+    pub fn set_pause_config(
+        env: Env,
+        caller: Address,
+        config: PauseConfig,
+    ) -> Result<(), SharedError> {
+        Self::require_role(&env, &caller, &PAUSER)?;
+
+        pausable::set(&env, &config);
+
+        let events = EventBuilder::new(&env);
+        events.publish(
+            "treasury",
+            "pause_config",
+            (
+                config.deposits,
+                config.withdrawals,
+                config.admin_ops,
+                env.ledger().timestamp(),
+            ),
+        );
+
+        extend_instance_ttl(&env);
+        Ok(())
+    }
+
+    /// Update treasury configuration (rate limits, max tokens/spenders)
+    pub fn update_config(
+        env: Env,
+        caller: Address,
+        new_config: TreasuryConfig,
+    ) -> Result<(), SharedError> {
+        Self::require_initialized(&env)?;
+        Self::require_role(&env, &caller, &CONFIG_ADMIN)?;
+        pausable::require_not_paused(&env, PauseGroup::AdminOps)?;
+
+        env.storage().instance().set(&DataKey::Config, &new_config);
+
+        let events = EventBuilder::new(&env);
+        events.publish(
+            "treasury",
+            "config_updated",
+            (
+                new_config.rate_limit.enabled,
+                new_config.rate_limit.daily_limit,
+                new_config.max_tokens,
+                env.ledger().timestamp(),
+            ),
+        );
+
+        extend_instance_ttl(&env);
+
+        Ok(())
+    }
+
+    /// Set how far storage TTLs are extended (see `TtlConfig`), e.g. shorter
+    /// lifetimes on testnet
+    pub fn set_ttl_config(env: Env, caller: Address, config: TtlConfig) -> Result<(), SharedError> {
+        Self::require_role(&env, &caller, &CONFIG_ADMIN)?;
+        pausable::require_not_paused(&env, PauseGroup::AdminOps)?;
+
+        set_ttl_config(&env, &config)?;
+        emit_config_updated(&env, &caller);
+        extend_instance_ttl(&env);
+
+        Ok(())
+    }
+
+    // ────────────────────────────────────────────────────────────────────────
+    // Roles (admin only)
+    // ────────────────────────────────────────────────────────────────────────
+
+    /// Grant a role (`PAUSER`, `TREASURER`, `CFG_ADMIN`, `UPGRADER`) to an account
+    pub fn grant_role(env: Env, role: Symbol, account: Address) -> Result<(), SharedError> {
+        Self::require_admin(&env)?;
+        access_control::grant_role(&env, role, account)?;
+        extend_instance_ttl(&env);
+        Ok(())
+    }
+
+    /// Revoke a role from an account
+    pub fn revoke_role(env: Env, role: Symbol, account: Address) -> Result<(), SharedError> {
+        Self::require_admin(&env)?;
+        access_control::revoke_role(&env, role, account)?;
+        extend_instance_ttl(&env);
+        Ok(())
+    }
+
+    // ────────────────────────────────────────────────────────────────────────
+    // Upgrades (admin or UPGRADER)
+    // ────────────────────────────────────────────────────────────────────────
+
+    /// Queue a WASM upgrade behind the upgrade timelock. Returns its eta.
+    pub fn queue_upgrade(
+        env: Env,
+        caller: Address,
+        new_wasm_hash: BytesN<32>,
+    ) -> Result<u64, SharedError> {
+        Self::require_role(&env, &caller, &UPGRADER)?;
+        upgrade::queue(&env, new_wasm_hash)
+    }
+
+    /// Cancel the queued upgrade
+    pub fn cancel_upgrade(env: Env, caller: Address) -> Result<(), SharedError> {
+        Self::require_role(&env, &caller, &UPGRADER)?;
+        upgrade::cancel(&env)
+    }
+
+    /// Switch to the queued WASM once the timelock has elapsed
+    pub fn upgrade(
+        env: Env,
+        caller: Address,
+        new_wasm_hash: BytesN<32>,
+    ) -> Result<(), SharedError> {
+        Self::require_role(&env, &caller, &UPGRADER)?;
+        upgrade::execute(&env, new_wasm_hash)
+    }
+
+    /// Migrate storage to `STORAGE_VERSION` after an upgrade
+    pub fn migrate(env: Env, caller: Address) -> Result<u32, SharedError> {
+        Self::require_role(&env, &caller, &UPGRADER)?;
+        upgrade::migrate(&env, STORAGE_VERSION, |env, from| {
+            if from < 2 {
+                // v2 moved the single pause flag to per-group `PauseConfig`
+                let paused: bool = env
+                    .storage()
+                    .instance()
+                    .get(&DataKey::Paused)
+                    .unwrap_or(false);
+                pausable::set(env, &PauseConfig::uniform(paused));
+                env.storage().instance().remove(&DataKey::Paused);
+            }
+            Ok(())
+        })
+    }
+
+    // ────────────────────────────────────────────────────────────────────────
+    // View Functions
+    // ────────────────────────────────────────────────────────────────────────
+
+    /// Whether an account was granted a role (the admin implicitly holds all)
+    pub fn has_role(env: Env, role: Symbol, account: Address) -> bool {
+        access_control::has_role(&env, &role, &account)
+    }
+
+    /// Get the storage layout version
+    pub fn version(env: Env) -> u32 {
+        upgrade::version(&env)
+    }
+
+    /// Get the queued upgrade, if any
+    pub fn get_pending_upgrade(env: Env) -> Option<PendingUpgrade> {
+        upgrade::pending(&env)
+    }
+
+    /// Get the current admin address.
+    pub fn get_admin(env: Env) -> Result<Address, SharedError> {
         Self::require_initialized(&env)?;
         env.storage()
             .instance()
@@ -471,11 +1813,149 @@ impl TreasuryVault {
             .ok_or(SharedError::NotInitialized)
     }
 
+    /// Get the storage TTL config
+    pub fn ttl_config(env: Env) -> TtlConfig {
+        get_ttl_config(&env)
+    }
+
+    /// Get the token registry, if set
+    pub fn token_registry(env: Env) -> Option<Address> {
+        env.storage().instance().get(&DataKey::TokenRegistry)
+    }
+
+    /// Whether outflows are currently restricted to allowlisted destinations
+    pub fn destination_allowlist_enabled(env: Env) -> bool {
+        let enabled: bool = env
+            .storage()
+            .instance()
+            .get(&DataKey::DestinationAllowlist)
+            .unwrap_or(false);
+        let off_at: Option<u64> = env
+            .storage()
+            .instance()
+            .get(&DataKey::DestinationAllowlistOffAt);
+        match off_at {
+            Some(off_at) => enabled && env.ledger().timestamp() < off_at,
+            None => enabled,
+        }
+    }
+
+    /// When an allowlisted destination becomes (or became) usable, if listed
+    pub fn destination_active_at(env: Env, destination: Address) -> Option<u64> {
+        get_persistent(&env, &DataKey::Destination(destination))
+    }
+
+    /// Auto-forward rule of a token, with the total forwarded under it
+    pub fn get_auto_forward(env: Env, token: Address) -> Option<AutoForward> {
+        get_persistent(&env, &DataKey::AutoForward(token))
+    }
+
     /// Get the balance of a specific token.
     pub fn balance(env: Env, token: Address) -> i128 {
         Self::get_balance(&env, &token)
     }
 
+    /// Get the treasury's shares in a staking pool
+    pub fn staked_position(env: Env, staking_pool: Address) -> i128 {
+        Self::get_staked_position(&env, &staking_pool)
+    }
+
+    /// Protocol-owned stake per pool as (pool, stake token, tokens the shares
+    /// are worth now). Not included in `balance`; `portfolio_value` counts
+    /// them.
+    pub fn staked_positions(env: Env) -> Vec<(Address, Address, i128)> {
+        let mut positions = Vec::new(&env);
+        let pools: Vec<Address> = env
+            .storage()
+            .instance()
+            .get(&DataKey::StakingPools)
+            .unwrap_or(Vec::new(&env));
+        for staking_pool in pools.iter() {
+            let shares = Self::get_staked_position(&env, &staking_pool);
+            let pool = StakingPoolClient::new(&env, &staking_pool);
+            let stake_token = match pool.try_stake_token() {
+                Ok(Ok(token)) => token,
+                _ => continue,
+            };
+            let value = match pool.try_convert_to_assets(&shares) {
+                Ok(Ok(value)) => value,
+                _ => shares,
+            };
+            positions.push_back((staking_pool, stake_token, value));
+        }
+        positions
+    }
+
+    /// Value of the treasury's holdings in `reference`: the free balances of
+    /// all tracked tokens plus protocol-owned stake, each token priced at its
+    /// configured oracle TWAP. Held tokens without a price source for
+    /// `reference` are listed in `unpriced` instead of being counted.
+    pub fn portfolio_value(env: Env, reference: Address) -> Result<PortfolioValue, SharedError> {
+        let mut holdings: Map<Address, i128> = Map::new(&env);
+        for token in Self::get_tokens(env.clone()).iter() {
+            let balance = Self::get_balance(&env, &token);
+            if balance > 0 {
+                holdings.set(token, balance);
+            }
+        }
+        for (_, stake_token, staked) in Self::staked_positions(env.clone()).iter() {
+            if staked > 0 {
+                let held = holdings.get(stake_token.clone()).unwrap_or(0);
+                holdings.set(stake_token, safe_add(held, staked)?);
+            }
+        }
+
+        let mut value = PortfolioValue {
+            total: 0,
+            unpriced: Vec::new(&env),
+        };
+        for (token, amount) in holdings.iter() {
+            if token == reference {
+                value.total = safe_add(value.total, amount)?;
+                continue;
+            }
+            let source: Option<PriceSource> = get_persistent(
+                &env,
+                &DataKey::PriceSource(token.clone(), reference.clone()),
+            );
+            match source {
+                Some(source) => {
+                    let priced = OracleClient::new(&env, &source.oracle)
+                        .try_consult(&source.pair, &token, &amount, &source.window)
+                        .into_shared()?;
+                    value.total = safe_add(value.total, priced)?;
+                }
+                None => value.unpriced.push_back(token),
+            }
+        }
+        Ok(value)
+    }
+
+    /// Get the quarterly budget of a spending category in `token`, if set
+    pub fn get_category_budget(
+        env: Env,
+        category: Symbol,
+        token: Address,
+    ) -> Option<CategoryBudget> {
+        get_persistent(&env, &DataKey::CategoryBudget(category, token))
+    }
+
+    /// Get a category's spending in `token` for the current quarter
+    pub fn get_category_spend(env: Env, category: Symbol, token: Address) -> CategorySpend {
+        let budget = Self::category_budget_or_zero(&env, &category, &token);
+        Self::current_category_spend(&env, &category, &token, &budget)
+    }
+
+    /// Get a donor's total deposits of `token`, net of refunds
+    pub fn get_donor_total(env: Env, donor: Address, token: Address) -> i128 {
+        get_persistent(&env, &DataKey::DonorTotal(donor, token)).unwrap_or(0)
+    }
+
+    /// Get the price source of `token` in `reference`, if set
+    pub fn price_source(env: Env, token: Address, reference: Address) -> Option<PriceSource> {
+        get_persistent(&env, &DataKey::PriceSource(token, reference))
+    }
+
     /// Get list of all tokens that have been deposited.
     pub fn get_tokens(env: Env) -> Vec<Address> {
         env.storage()
@@ -497,12 +1977,55 @@ impl TreasuryVault {
         env.storage().instance().has(&DataKey::Initialized)
     }
 
-    /// Check if the contract is paused.
+    /// Get the proposed admin awaiting acceptance
+    pub fn pending_admin(env: Env) -> Option<Address> {
+        ownership::pending(&env)
+    }
+
+    /// Whether any function group is paused.
     pub fn is_paused(env: Env) -> bool {
-        env.storage()
-            .instance()
-            .get(&DataKey::Paused)
-            .unwrap_or(false)
+        pausable::get(&env).is_any()
+    }
+
+    /// Get the pause flags per function group
+    pub fn pause_config(env: Env) -> PauseConfig {
+        pausable::get(&env)
+    }
+
+    /// Describe this contract for explorers and wallets
+    pub fn contract_info(env: Env) -> Result<ContractInfo, SharedError> {
+        let admin = Self::get_admin(env.clone())?;
+        let config = Self::get_config_internal(&env);
+        let info = ContractInfo::new(
+            &env,
+            "treasury",
+            env!("CARGO_PKG_VERSION"),
+            admin,
+            Self::is_paused(env.clone()),
+            env.storage().instance().get(&DataKey::Registry),
+        );
+        Ok(info
+            .with("rate_limit_enabled", config.rate_limit.enabled as i128)
+            .with("max_per_tx", config.rate_limit.max_per_tx)
+            .with("daily_limit", config.rate_limit.daily_limit)
+            .with("max_tokens", config.max_tokens as i128)
+            .with("max_spenders", config.max_spenders as i128)
+            .with("tokens", Self::get_tokens(env.clone()).len() as i128))
+    }
+
+    /// Get all sub-account names
+    pub fn sub_accounts(env: Env) -> Vec<Symbol> {
+        Self::get_sub_accounts(&env)
+    }
+
+    /// Get a sub-account's balance of a token
+    pub fn sub_account_balance(env: Env, name: Symbol, token: Address) -> i128 {
+        Self::get_sub_balance(&env, &name, &token)
+    }
+
+    /// Get a sub-account's daily withdrawal limit for a token (0 = unlimited)
+    pub fn sub_account_limit(env: Env, name: Symbol, token: Address) -> i128 {
+        get_persistent(&env, &DataKey::SubLimit(name, token)).unwrap_or(0)
     }
 
     /// Get fee distributor address
@@ -515,10 +2038,73 @@ impl TreasuryVault {
         Self::get_config_internal(&env)
     }
 
+    /// Get a deposit receipt
+    pub fn get_receipt(env: Env, receipt_id: u64) -> Option<DepositReceipt> {
+        get_persistent(&env, &DataKey::Receipt(receipt_id))
+    }
+
+    /// Get the bond posted by a spender
+    pub fn spender_bond(env: Env, spender: Address) -> i128 {
+        Self::get_spender_bond(&env, &spender)
+    }
+
+    /// Get the bond a spender must hold to spend
+    pub fn required_bond(env: Env, spender: Address) -> i128 {
+        Self::required_bond_internal(&env, &spender)
+    }
+
+    /// Get the bond requirement of a tier
+    pub fn bond_requirement(env: Env, tier: u32) -> i128 {
+        env.storage()
+            .instance()
+            .get(&DataKey::BondRequirement(tier))
+            .unwrap_or(0)
+    }
+
+    /// Get the sum of all posted bonds
+    pub fn total_bonded(env: Env) -> i128 {
+        Self::get_total_bonded(&env)
+    }
+
+    /// Get the bond token
+    pub fn bond_token(env: Env) -> Option<Address> {
+        env.storage().instance().get(&DataKey::BondToken)
+    }
+
+    /// Get a vesting by ID
+    pub fn get_vesting(env: Env, id: u64) -> Option<Vesting> {
+        get_persistent(&env, &DataKey::Vesting(id))
+    }
+
+    /// Amount of a vesting its beneficiary can claim right now
+    pub fn vesting_claimable(env: Env, id: u64) -> Result<i128, SharedError> {
+        let vesting = Self::load_vesting(&env, id)?;
+        safe_sub(
+            Self::vested_amount(&vesting, env.ledger().timestamp())?,
+            vesting.claimed,
+        )
+    }
+
+    /// Get the unclaimed vesting total of a token (excluded from `balance`)
+    pub fn vesting_reserved(env: Env, token: Address) -> i128 {
+        Self::get_vesting_reserved(&env, &token)
+    }
+
     // ────────────────────────────────────────────────────────────────────────
     // Internal Functions
     // ────────────────────────────────────────────────────────────────────────
 
+    /// Reject `token` if a token registry is set and does not allow it
+    fn require_token_allowed(env: &Env, token: &Address) -> Result<(), SharedError> {
+        let registry: Option<Address> = env.storage().instance().get(&DataKey::TokenRegistry);
+        if let Some(registry) = registry {
+            if !TokenRegistryClient::new(env, &registry).is_token_allowed(token) {
+                return Err(SharedError::TokenNotAllowed);
+            }
+        }
+        Ok(())
+    }
+
     fn require_initialized(env: &Env) -> Result<(), SharedError> {
         if !env.storage().instance().has(&DataKey::Initialized) {
             return Err(SharedError::NotInitialized);
@@ -526,28 +2112,145 @@ impl TreasuryVault {
         Ok(())
     }
 
-    fn require_not_paused(env: &Env) -> Result<(), SharedError> {
-        let paused: bool = env
+    fn require_admin(env: &Env) -> Result<(), SharedError> {
+        let admin: Address = env
             .storage()
             .instance()
-            .get(&DataKey::Paused)
-            .unwrap_or(false);
-        if paused {
-            return Err(SharedError::ContractPaused);
-        }
+            .get(&DataKey::Admin)
+            .ok_or(SharedError::NotInitialized)?;
+        admin.require_auth();
         Ok(())
     }
 
-    fn require_admin(env: &Env) -> Result<(), SharedError> {
+    /// Require `caller`'s auth and that it is the admin or holds `role`
+    fn require_role(env: &Env, caller: &Address, role: &Symbol) -> Result<(), SharedError> {
         let admin: Address = env
             .storage()
             .instance()
             .get(&DataKey::Admin)
             .ok_or(SharedError::NotInitialized)?;
-        admin.require_auth();
+        access_control::require_role(env, role, caller, &admin)
+    }
+
+    fn get_receipt_internal(env: &Env, receipt_id: u64) -> Result<DepositReceipt, SharedError> {
+        get_persistent(env, &DataKey::Receipt(receipt_id)).ok_or(SharedError::InvalidState)
+    }
+
+    fn save_receipt(env: &Env, receipt: &DepositReceipt) {
+        let key = DataKey::Receipt(receipt.id);
+        set_persistent(env, &key, receipt);
+    }
+
+    /// Reject a payout to a destination that isn't usable while the
+    /// allowlist is on
+    fn require_destination(env: &Env, to: &Address) -> Result<(), SharedError> {
+        if !Self::destination_allowlist_enabled(env.clone()) {
+            return Ok(());
+        }
+        match Self::destination_active_at(env.clone(), to.clone()) {
+            Some(active_at) if env.ledger().timestamp() >= active_at => Ok(()),
+            _ => Err(SharedError::Unauthorized),
+        }
+    }
+
+    /// Send the auto-forward share of a deposit on to its destination
+    fn forward_share(env: &Env, token: &Address, amount: i128) -> Result<(), SharedError> {
+        let key = DataKey::AutoForward(token.clone());
+        let mut rule: AutoForward = match get_persistent(env, &key) {
+            Some(rule) => rule,
+            None => return Ok(()),
+        };
+        let share = rule.bps.apply(amount)?;
+        if share == 0 {
+            return Ok(());
+        }
+
+        token::Client::new(env, token).transfer(
+            &env.current_contract_address(),
+            &rule.destination,
+            &share,
+        );
+        rule.forwarded = safe_add(rule.forwarded, share)?;
+        set_persistent(env, &key, &rule);
+
+        let events = EventBuilder::new(env);
+        events.publish(
+            "treasury",
+            "auto_forwarded",
+            (
+                token.clone(),
+                rule.destination,
+                share,
+                rule.forwarded,
+                env.ledger().timestamp(),
+            ),
+        );
+
+        Ok(())
+    }
+
+    fn require_governance(env: &Env) -> Result<(), SharedError> {
+        let governance: Address = match env.storage().instance().get(&DataKey::Governance) {
+            Some(governance) => governance,
+            None => env
+                .storage()
+                .instance()
+                .get(&DataKey::Admin)
+                .ok_or(SharedError::NotInitialized)?,
+        };
+        governance.require_auth();
+        Ok(())
+    }
+
+    /// Check a spender holds the bond required for their tier (admin is exempt)
+    fn require_bonded(env: &Env, spender: &Address) -> Result<(), SharedError> {
+        let admin: Option<Address> = env.storage().instance().get(&DataKey::Admin);
+        if admin.as_ref() == Some(spender) {
+            return Ok(());
+        }
+        if Self::get_spender_bond(env, spender) < Self::required_bond_internal(env, spender) {
+            return Err(SharedError::BelowMinimum);
+        }
+        Ok(())
+    }
+
+    fn required_bond_internal(env: &Env, spender: &Address) -> i128 {
+        let tier: u32 = get_persistent(env, &DataKey::SpenderTier(spender.clone())).unwrap_or(0);
+        env.storage()
+            .instance()
+            .get(&DataKey::BondRequirement(tier))
+            .unwrap_or(0)
+    }
+
+    fn get_bond_token(env: &Env) -> Result<Address, SharedError> {
+        env.storage()
+            .instance()
+            .get(&DataKey::BondToken)
+            .ok_or(SharedError::ExternalContractNotSet)
+    }
+
+    fn get_spender_bond(env: &Env, spender: &Address) -> i128 {
+        get_persistent(env, &DataKey::SpenderBond(spender.clone())).unwrap_or(0)
+    }
+
+    /// Store a spender bond and keep the bonded total in sync
+    fn set_spender_bond(env: &Env, spender: &Address, bond: i128) -> Result<(), SharedError> {
+        let previous = Self::get_spender_bond(env, spender);
+        let total = safe_add(safe_sub(Self::get_total_bonded(env), previous)?, bond)?;
+        env.storage().instance().set(&DataKey::TotalBonded, &total);
+
+        let key = DataKey::SpenderBond(spender.clone());
+        set_persistent(env, &key, &bond);
         Ok(())
     }
 
+    fn get_total_bonded(env: &Env) -> i128 {
+        env.storage()
+            .instance()
+            .get(&DataKey::TotalBonded)
+            .unwrap_or(0)
+    }
+
     fn is_allowed_spender(env: &Env, spender: &Address) -> bool {
         // Admin is always allowed
         if let Some(admin) = env
@@ -560,26 +2263,253 @@ impl TreasuryVault {
             }
         }
 
-        // Check allowed spenders list
-        let spenders: Vec<Address> = env
-            .storage()
-            .instance()
-            .get(&DataKey::AllowedSpenders)
-            .unwrap_or(Vec::new(env));
+        // Check allowed spenders list
+        let spenders: Vec<Address> = env
+            .storage()
+            .instance()
+            .get(&DataKey::AllowedSpenders)
+            .unwrap_or(Vec::new(env));
+
+        for s in spenders.iter() {
+            if s == *spender {
+                return true;
+            }
+        }
+
+        false
+    }
+
+    fn get_staked_position(env: &Env, staking_pool: &Address) -> i128 {
+        get_persistent(env, &DataKey::StakedPosition(staking_pool.clone())).unwrap_or(0)
+    }
+
+    /// Record the treasury's shares in `staking_pool`, listing the pool while
+    /// the position is open
+    fn set_staked_position(env: &Env, staking_pool: &Address, shares: i128) {
+        let key = DataKey::StakedPosition(staking_pool.clone());
+        let mut pools: Vec<Address> = env
+            .storage()
+            .instance()
+            .get(&DataKey::StakingPools)
+            .unwrap_or(Vec::new(env));
+        let listed = pools.first_index_of(staking_pool);
+
+        if shares > 0 {
+            set_persistent(env, &key, &shares);
+            if listed.is_none() {
+                pools.push_back(staking_pool.clone());
+            }
+        } else {
+            env.storage().persistent().remove(&key);
+            if let Some(index) = listed {
+                pools.remove(index);
+            }
+        }
+        env.storage().instance().set(&DataKey::StakingPools, &pools);
+    }
+
+    /// Get spendable token balance from SAC (posted bonds and sub-account
+    /// allocations excluded)
+    fn get_balance(env: &Env, token: &Address) -> i128 {
+        let token_client = token::Client::new(env, token);
+        let balance = token_client.balance(&env.current_contract_address())
+            - Self::get_sub_allocated(env, token)
+            - Self::get_vesting_reserved(env, token);
+
+        let bond_token: Option<Address> = env.storage().instance().get(&DataKey::BondToken);
+        if bond_token.as_ref() == Some(token) {
+            return balance - Self::get_total_bonded(env);
+        }
+        balance
+    }
+
+    fn category_budget_or_zero(env: &Env, category: &Symbol, token: &Address) -> CategoryBudget {
+        get_persistent(
+            env,
+            &DataKey::CategoryBudget(category.clone(), token.clone()),
+        )
+        .unwrap_or(CategoryBudget {
+            cap: 0,
+            rollover: false,
+        })
+    }
+
+    /// Spending of a category in the current quarter. Entering a new
+    /// quarter resets `spent`; with rollover, the budget left unspent in
+    /// the previous quarter (at most `cap`) is carried.
+    fn current_category_spend(
+        env: &Env,
+        category: &Symbol,
+        token: &Address,
+        budget: &CategoryBudget,
+    ) -> CategorySpend {
+        let quarter = env.ledger().timestamp() / SECONDS_PER_QUARTER;
+        let stored: Option<CategorySpend> = get_persistent(
+            env,
+            &DataKey::CategorySpend(category.clone(), token.clone()),
+        );
+        match stored {
+            Some(spend) if spend.quarter == quarter => spend,
+            Some(spend) if budget.rollover && spend.quarter + 1 == quarter => CategorySpend {
+                quarter,
+                spent: 0,
+                carried: budget
+                    .cap
+                    .saturating_add(spend.carried)
+                    .saturating_sub(spend.spent)
+                    .clamp(0, budget.cap),
+            },
+            _ => CategorySpend {
+                quarter,
+                spent: 0,
+                carried: 0,
+            },
+        }
+    }
+
+    /// Checks every outflow of the free balance shares: the destination
+    /// allowlist, the rate limit and `category`'s quarterly budget
+    fn guard_withdrawal(
+        env: &Env,
+        token: &Address,
+        to: &Address,
+        amount: i128,
+        category: &Symbol,
+    ) -> Result<(), SharedError> {
+        Self::require_destination(env, to)?;
+        Self::check_and_update_rate_limit(env, token, amount)?;
+        Self::check_and_update_category(env, category, token, amount)
+    }
+
+    /// Charge `amount` to a category's quarterly budget
+    fn check_and_update_category(
+        env: &Env,
+        category: &Symbol,
+        token: &Address,
+        amount: i128,
+    ) -> Result<(), SharedError> {
+        let budget = Self::category_budget_or_zero(env, category, token);
+        let mut spend = Self::current_category_spend(env, category, token, &budget);
+
+        let available = safe_sub(safe_add(budget.cap, spend.carried)?, spend.spent)?;
+        if amount > available {
+            return Err(SharedError::LimitExceeded);
+        }
+        spend.spent = safe_add(spend.spent, amount)?;
+        set_persistent(
+            env,
+            &DataKey::CategorySpend(category.clone(), token.clone()),
+            &spend,
+        );
+
+        let events = EventBuilder::new(env);
+        events.publish(
+            "treasury",
+            "category_spend",
+            (category.clone(), token.clone(), amount, spend.spent),
+        );
+        Ok(())
+    }
+
+    fn get_sub_accounts(env: &Env) -> Vec<Symbol> {
+        env.storage()
+            .instance()
+            .get(&DataKey::SubAccounts)
+            .unwrap_or(Vec::new(env))
+    }
+
+    fn require_sub_account(env: &Env, name: &Symbol) -> Result<(), SharedError> {
+        if !Self::get_sub_accounts(env).contains(name) {
+            return Err(SharedError::InvalidState);
+        }
+        Ok(())
+    }
+
+    fn get_sub_balance(env: &Env, name: &Symbol, token: &Address) -> i128 {
+        get_persistent(env, &DataKey::SubBalance(name.clone(), token.clone())).unwrap_or(0)
+    }
+
+    fn set_sub_balance(env: &Env, name: &Symbol, token: &Address, balance: i128) {
+        let key = DataKey::SubBalance(name.clone(), token.clone());
+        set_persistent(env, &key, &balance);
+    }
+
+    fn load_vesting(env: &Env, id: u64) -> Result<Vesting, SharedError> {
+        get_persistent(env, &DataKey::Vesting(id)).ok_or(SharedError::InvalidState)
+    }
+
+    /// Amount of `vesting` vested at `now`, frozen at its revocation time
+    fn vested_amount(vesting: &Vesting, now: u64) -> Result<i128, SharedError> {
+        let now = match vesting.revoked_at {
+            Some(revoked_at) => now.min(revoked_at),
+            None => now,
+        };
+        let elapsed = now.saturating_sub(vesting.start);
+        if elapsed < vesting.cliff {
+            return Ok(0);
+        }
+        if elapsed >= vesting.duration {
+            return Ok(vesting.total);
+        }
+        mul_div_down(vesting.total, elapsed as i128, vesting.duration as i128)
+    }
+
+    fn get_vesting_reserved(env: &Env, token: &Address) -> i128 {
+        get_persistent(env, &DataKey::VestingReserved(token.clone())).unwrap_or(0)
+    }
+
+    fn set_vesting_reserved(env: &Env, token: &Address, reserved: i128) {
+        let key = DataKey::VestingReserved(token.clone());
+        set_persistent(env, &key, &reserved);
+    }
+
+    fn get_sub_allocated(env: &Env, token: &Address) -> i128 {
+        get_persistent(env, &DataKey::SubAllocated(token.clone())).unwrap_or(0)
+    }
+
+    fn set_sub_allocated(env: &Env, token: &Address, allocated: i128) {
+        let key = DataKey::SubAllocated(token.clone());
+        set_persistent(env, &key, &allocated);
+    }
+
+    /// Check and update a sub-account's daily withdrawal limit
+    fn check_and_update_sub_limit(
+        env: &Env,
+        name: &Symbol,
+        token: &Address,
+        amount: i128,
+    ) -> Result<(), SharedError> {
+        let daily_limit: i128 =
+            get_persistent(env, &DataKey::SubLimit(name.clone(), token.clone())).unwrap_or(0);
+        if daily_limit == 0 {
+            return Ok(());
+        }
+
+        let current_time = env.ledger().timestamp();
+        let key = DataKey::SubTracker(name.clone(), token.clone());
+        let mut tracker: WithdrawalTracker =
+            get_persistent(env, &key).unwrap_or(WithdrawalTracker {
+                amount_withdrawn: 0,
+                period_start: current_time,
+                last_withdrawal: 0,
+            });
+
+        // Reset daily limit if new day
+        if current_time >= tracker.period_start + SECONDS_PER_DAY {
+            tracker.amount_withdrawn = 0;
+            tracker.period_start = current_time;
+        }
 
-        for s in spenders.iter() {
-            if s == *spender {
-                return true;
-            }
+        let new_total = safe_add(tracker.amount_withdrawn, amount)?;
+        if new_total > daily_limit {
+            return Err(SharedError::DailyLimitExceeded);
         }
+        tracker.amount_withdrawn = new_total;
+        tracker.last_withdrawal = current_time;
 
-        false
-    }
+        set_persistent(env, &key, &tracker);
 
-    /// Get token balance from SAC
-    fn get_balance(env: &Env, token: &Address) -> i128 {
-        let token_client = token::Client::new(env, token);
-        token_client.balance(&env.current_contract_address())
+        Ok(())
     }
 
     /// Track a token if not already tracked
@@ -646,15 +2576,14 @@ impl TreasuryVault {
         }
 
         // Get or create withdrawal tracker
-        let mut tracker: WithdrawalTracker = env
-            .storage()
-            .persistent()
-            .get(&DataKey::WithdrawalTracker(token.clone()))
-            .unwrap_or(WithdrawalTracker {
-                amount_withdrawn: 0,
-                period_start: current_time,
-                last_withdrawal: 0,
-            });
+        let mut tracker: WithdrawalTracker =
+            get_persistent(env, &DataKey::WithdrawalTracker(token.clone())).unwrap_or(
+                WithdrawalTracker {
+                    amount_withdrawn: 0,
+                    period_start: current_time,
+                    last_withdrawal: 0,
+                },
+            );
 
         // Reset daily limit if new day
         if current_time >= tracker.period_start + SECONDS_PER_DAY {
@@ -681,9 +2610,7 @@ impl TreasuryVault {
 
         // Update tracker
         tracker.last_withdrawal = current_time;
-        env.storage()
-            .persistent()
-            .set(&DataKey::WithdrawalTracker(token.clone()), &tracker);
+        set_persistent(env, &DataKey::WithdrawalTracker(token.clone()), &tracker);
 
         Ok(())
     }
@@ -696,17 +2623,57 @@ impl TreasuryVault {
 #[cfg(test)]
 mod test {
     use super::*;
-    use soroban_sdk::testutils::Address as _;
+    use astro_core_shared::testutils::create_token;
+    use astro_core_shared::{limits::MIN_STAKE_AMOUNT, types::StakingConfig};
+    use soroban_sdk::testutils::{Address as _, Ledger as _};
+
+    /// Give the `ops` category an ample quarterly budget in `token`
+    fn fund_ops(env: &Env, treasury: &TreasuryVaultClient, token: &Address) -> Symbol {
+        let ops = Symbol::new(env, "ops");
+        let budget = CategoryBudget {
+            cap: 1_000_000,
+            rollover: false,
+        };
+        treasury.set_category_budget(&ops, token, &Some(budget));
+        ops
+    }
 
-    fn create_token_contract<'a>(
-        env: &Env,
-        admin: &Address,
-    ) -> (token::Client<'a>, token::StellarAssetClient<'a>) {
-        let contract_address = env.register_stellar_asset_contract_v2(admin.clone());
-        (
-            token::Client::new(env, &contract_address.address()),
-            token::StellarAssetClient::new(env, &contract_address.address()),
-        )
+    /// Pair that only reports its tokens
+    #[contract]
+    pub struct MockPair;
+
+    #[contractimpl]
+    impl MockPair {
+        pub fn init(env: Env, token_0: Address, token_1: Address) {
+            env.storage().instance().set(&0u32, &(token_0, token_1));
+        }
+
+        pub fn token_0(env: Env) -> Address {
+            let (token_0, _): (Address, Address) = env.storage().instance().get(&0u32).unwrap();
+            token_0
+        }
+
+        pub fn token_1(env: Env) -> Address {
+            let (_, token_1): (Address, Address) = env.storage().instance().get(&0u32).unwrap();
+            token_1
+        }
+    }
+
+    /// Oracle pricing every token at 2 units of the other pair token
+    #[contract]
+    pub struct MockOracle;
+
+    #[contractimpl]
+    impl MockOracle {
+        pub fn consult(
+            _env: Env,
+            _pair: Address,
+            _token_in: Address,
+            amount_in: i128,
+            _window: u64,
+        ) -> i128 {
+            amount_in * 2
+        }
     }
 
     #[test]
@@ -753,23 +2720,32 @@ mod test {
         treasury.initialize(&admin);
 
         // Setup token
-        let (token_client, token_admin) = create_token_contract(&env, &admin);
+        let token_client = create_token(&env, &admin);
 
         // Mint tokens to user
-        token_admin.mint(&user, &1000);
-
-        // User deposits to treasury
-        treasury.deposit(&user, &token_client.address, &500);
+        token_client.mint(&user, 1000);
+
+        // User deposits to treasury; dust is rejected
+        assert!(treasury
+            .try_deposit(
+                &user,
+                &token_client.address,
+                &(MIN_DEPOSIT_AMOUNT - 1),
+                &None
+            )
+            .is_err());
+        treasury.deposit(&user, &token_client.address, &500, &None);
 
         // Check balance
         assert_eq!(treasury.balance(&token_client.address), 500);
 
         // Admin withdraws
-        treasury.withdraw(&token_client.address, &admin, &200);
+        let ops = fund_ops(&env, &treasury, &token_client.address);
+        treasury.withdraw(&admin, &token_client.address, &admin, &200, &ops, &None);
         assert_eq!(treasury.balance(&token_client.address), 300);
 
         // Withdraw all remaining
-        let withdrawn = treasury.withdraw_all(&token_client.address, &admin);
+        let withdrawn = treasury.withdraw_all(&admin, &token_client.address, &admin, &ops);
         assert_eq!(withdrawn, 300);
         assert_eq!(treasury.balance(&token_client.address), 0);
     }
@@ -788,15 +2764,15 @@ mod test {
         treasury.initialize(&admin);
 
         // Setup multiple tokens
-        let (token1_client, token1_admin) = create_token_contract(&env, &admin);
-        let (token2_client, token2_admin) = create_token_contract(&env, &admin);
+        let token1_client = create_token(&env, &admin);
+        let token2_client = create_token(&env, &admin);
 
         // Mint and deposit both tokens
-        token1_admin.mint(&user, &1000);
-        token2_admin.mint(&user, &2000);
+        token1_client.mint(&user, 1000);
+        token2_client.mint(&user, 2000);
 
-        treasury.deposit(&user, &token1_client.address, &500);
-        treasury.deposit(&user, &token2_client.address, &1000);
+        treasury.deposit(&user, &token1_client.address, &500, &None);
+        treasury.deposit(&user, &token2_client.address, &1000, &None);
 
         // Check balances
         assert_eq!(treasury.balance(&token1_client.address), 500);
@@ -807,6 +2783,108 @@ mod test {
         assert_eq!(tokens.len(), 2);
     }
 
+    #[test]
+    fn test_portfolio_value() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let admin = Address::generate(&env);
+        let user = Address::generate(&env);
+        let treasury = TreasuryVaultClient::new(&env, &env.register(TreasuryVault, ()));
+        treasury.initialize(&admin);
+
+        let usdc = create_token(&env, &admin);
+        let astro = create_token(&env, &admin);
+        let other = create_token(&env, &admin);
+        usdc.mint(&user, 1000);
+        astro.mint(&user, 1000);
+        other.mint(&user, 1000);
+        treasury.deposit(&user, &usdc.address, &300, &None);
+        treasury.deposit(&user, &astro.address, &500, &None);
+        treasury.deposit(&user, &other.address, &700, &None);
+
+        let pair = MockPairClient::new(&env, &env.register(MockPair, ()));
+        pair.init(&astro.address, &usdc.address);
+        let source = PriceSource {
+            oracle: env.register(MockOracle, ()),
+            pair: pair.address.clone(),
+            window: 3600,
+        };
+
+        // The pair must trade the token against the reference
+        assert_eq!(
+            treasury.try_set_price_source(&other.address, &usdc.address, &Some(source.clone())),
+            Err(Ok(SharedError::InvalidAddress))
+        );
+        treasury.set_price_source(&astro.address, &usdc.address, &Some(source));
+
+        // 300 USDC + 500 ASTRO at 2 USDC; the unpriced token is reported
+        let value = treasury.portfolio_value(&usdc.address);
+        assert_eq!(value.total, 1300);
+        assert_eq!(value.unpriced, vec![&env, other.address.clone()]);
+
+        // Staked ASTRO still counts
+        let pool = env.register(astro_staking::StakingPool, ());
+        astro_staking::StakingPoolClient::new(&env, &pool).initialize(
+            &admin,
+            &astro.address,
+            &Address::generate(&env),
+            &StakingConfig {
+                min_stake_amount: MIN_STAKE_AMOUNT,
+                cooldown_period: 0,
+                max_stake_per_user: 0,
+                emergency_unlock: false,
+                slashing_enabled: false,
+                deposit_fee_bps: 0,
+                withdraw_fee_bps: 0,
+            },
+        );
+        let ops = fund_ops(&env, &treasury, &astro.address);
+        treasury.stake_treasury_funds(&pool, &200, &ops);
+        assert_eq!(treasury.balance(&astro.address), 300);
+        assert_eq!(treasury.portfolio_value(&usdc.address).total, 1300);
+
+        treasury.set_price_source(&astro.address, &usdc.address, &None);
+        let value = treasury.portfolio_value(&usdc.address);
+        assert_eq!(value.total, 300);
+        assert_eq!(value.unpriced.len(), 2);
+        assert!(value.unpriced.contains(&astro.address));
+    }
+
+    #[test]
+    fn test_token_registry() {
+        use astro_core_shared::types::TokenStatus;
+        use astro_registry::{TokenRegistry, TokenRegistryClient};
+
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let admin = Address::generate(&env);
+        let user = Address::generate(&env);
+
+        let treasury = TreasuryVaultClient::new(&env, &env.register(TreasuryVault, ()));
+        treasury.initialize(&admin);
+        let registry = TokenRegistryClient::new(&env, &env.register(TokenRegistry, ()));
+        registry.initialize(&admin, &false);
+
+        let good = create_token(&env, &admin);
+        let scam = create_token(&env, &admin);
+        good.mint(&user, 1000);
+        scam.mint(&user, 1000);
+        registry.set_token_status(&scam.address, &TokenStatus::Denied);
+
+        // Not consulted until set
+        treasury.deposit(&user, &scam.address, &100, &None);
+
+        treasury.set_token_registry(&Some(registry.address.clone()));
+        assert_eq!(
+            treasury.try_deposit(&user, &scam.address, &100, &None),
+            Err(Ok(SharedError::TokenNotAllowed))
+        );
+        treasury.deposit(&user, &good.address, &100, &None);
+        assert_eq!(treasury.balance(&good.address), 100);
+    }
+
     #[test]
     fn test_spender_system() {
         let env = Env::default();
@@ -820,17 +2898,18 @@ mod test {
         let treasury = TreasuryVaultClient::new(&env, &treasury_id);
         treasury.initialize(&admin);
 
-        let (token_client, token_admin) = create_token_contract(&env, &admin);
-        token_admin.mint(&admin, &1000);
+        let token_client = create_token(&env, &admin);
+        token_client.mint(&admin, 1000);
 
         // Admin deposits
-        treasury.deposit(&admin, &token_client.address, &1000);
+        treasury.deposit(&admin, &token_client.address, &1000, &None);
 
         // Add spender
         treasury.add_spender(&spender);
+        let ops = fund_ops(&env, &treasury, &token_client.address);
 
         // Spender can spend
-        treasury.spend(&spender, &token_client.address, &recipient, &500);
+        treasury.spend(&spender, &token_client.address, &recipient, &500, &ops);
         assert_eq!(treasury.balance(&token_client.address), 500);
         assert_eq!(token_client.balance(&recipient), 500);
 
@@ -838,10 +2917,265 @@ mod test {
         treasury.remove_spender(&spender);
 
         // Spender can no longer spend
-        let result = treasury.try_spend(&spender, &token_client.address, &recipient, &100);
+        let result = treasury.try_spend(&spender, &token_client.address, &recipient, &100, &ops);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_spender_bond() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let admin = Address::generate(&env);
+        let spender = Address::generate(&env);
+        let recipient = Address::generate(&env);
+        let insurance = Address::generate(&env);
+
+        let treasury_id = env.register(TreasuryVault, ());
+        let treasury = TreasuryVaultClient::new(&env, &treasury_id);
+        treasury.initialize(&admin);
+
+        let astro = create_token(&env, &admin);
+        astro.mint(&admin, 1000);
+        astro.mint(&spender, 500);
+        treasury.deposit(&admin, &astro.address, &1000, &None);
+
+        treasury.set_bond_token(&astro.address);
+        treasury.set_bond_requirement(&1, &300);
+        treasury.add_spender(&spender);
+        treasury.set_spender_tier(&spender, &1);
+        let ops = fund_ops(&env, &treasury, &astro.address);
+
+        // Unbonded spender cannot spend
+        let result = treasury.try_spend(&spender, &astro.address, &recipient, &100, &ops);
+        assert!(result.is_err());
+
+        // Bond is locked and excluded from the spendable balance
+        treasury.post_bond(&spender, &400);
+        assert_eq!(treasury.total_bonded(), 400);
+        assert_eq!(treasury.balance(&astro.address), 1000);
+
+        treasury.spend(&spender, &astro.address, &recipient, &100, &ops);
+        assert_eq!(treasury.balance(&astro.address), 900);
+
+        // Only the excess above the tier requirement can be withdrawn
+        assert!(treasury.try_withdraw_bond(&spender, &200).is_err());
+        treasury.withdraw_bond(&spender, &100);
+        assert_eq!(treasury.spender_bond(&spender), 300);
+
+        // Governance slashes the bond
+        let slashed = treasury.slash_bond(&spender, &150, &insurance);
+        assert_eq!(slashed, 150);
+        assert_eq!(astro.balance(&insurance), 150);
+        assert_eq!(treasury.total_bonded(), 150);
+
+        // Under-bonded spender is blocked again
+        let result = treasury.try_spend(&spender, &astro.address, &recipient, &100, &ops);
         assert!(result.is_err());
     }
 
+    #[test]
+    fn test_refund_deposit() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let admin = Address::generate(&env);
+        let user = Address::generate(&env);
+
+        let treasury_id = env.register(TreasuryVault, ());
+        let treasury = TreasuryVaultClient::new(&env, &treasury_id);
+        treasury.initialize(&admin);
+
+        let token_client = create_token(&env, &admin);
+        token_client.mint(&user, 1000);
+
+        env.ledger().set_timestamp(1000);
+        let round = Symbol::new(&env, "round_1");
+        let receipt_id = treasury.deposit(&user, &token_client.address, &400, &Some(round));
+        let receipt = treasury.get_receipt(&receipt_id).unwrap();
+        assert_eq!(receipt.from, user);
+        assert_eq!(receipt.amount, 400);
+        assert_eq!(treasury.get_donor_total(&user, &token_client.address), 400);
+
+        // Must be queued first, then wait out the timelock
+        assert!(treasury.try_refund_deposit(&receipt_id).is_err());
+        treasury.queue_refund(&receipt_id);
+        assert!(treasury.try_refund_deposit(&receipt_id).is_err());
+
+        env.ledger().set_timestamp(1000 + REFUND_TIMELOCK);
+        let refunded = treasury.refund_deposit(&receipt_id);
+        assert_eq!(refunded, 400);
+        assert_eq!(token_client.balance(&user), 1000);
+        assert_eq!(treasury.get_donor_total(&user, &token_client.address), 0);
+        assert_eq!(treasury.balance(&token_client.address), 0);
+
+        // Cannot refund twice
+        assert!(treasury.try_refund_deposit(&receipt_id).is_err());
+    }
+
+    #[test]
+    fn test_category_budgets() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let admin = Address::generate(&env);
+        let user = Address::generate(&env);
+        let treasury = TreasuryVaultClient::new(&env, &env.register(TreasuryVault, ()));
+        treasury.initialize(&admin);
+
+        let token_client = create_token(&env, &admin);
+        let token = token_client.address.clone();
+        token_client.mint(&user, 1000);
+        treasury.deposit(&user, &token, &1000, &None);
+
+        // A category without a budget cannot spend
+        let marketing = Symbol::new(&env, "marketing");
+        assert_eq!(
+            treasury.try_withdraw(&admin, &token, &admin, &100, &marketing, &None),
+            Err(Ok(SharedError::LimitExceeded))
+        );
+        assert_eq!(
+            treasury.try_withdraw_all(&admin, &token, &admin, &marketing),
+            Err(Ok(SharedError::LimitExceeded))
+        );
+
+        let budget = CategoryBudget {
+            cap: 200,
+            rollover: true,
+        };
+        treasury.set_category_budget(&marketing, &token, &Some(budget));
+        treasury.withdraw(&admin, &token, &admin, &150, &marketing, &None);
+        assert_eq!(treasury.get_category_spend(&marketing, &token).spent, 150);
+        assert!(treasury
+            .try_withdraw(&admin, &token, &admin, &100, &marketing, &None)
+            .is_err());
+
+        // The unspent 50 rolls into the next quarter
+        env.ledger().set_timestamp(SECONDS_PER_QUARTER);
+        let spend = treasury.get_category_spend(&marketing, &token);
+        assert_eq!((spend.spent, spend.carried), (0, 50));
+        treasury.withdraw(&admin, &token, &admin, &250, &marketing, &None);
+        assert!(treasury
+            .try_withdraw(&admin, &token, &admin, &1, &marketing, &None)
+            .is_err());
+    }
+
+    #[test]
+    fn test_sub_accounts() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let admin = Address::generate(&env);
+        let user = Address::generate(&env);
+        let grantee = Address::generate(&env);
+
+        let treasury_id = env.register(TreasuryVault, ());
+        let treasury = TreasuryVaultClient::new(&env, &treasury_id);
+        treasury.initialize(&admin);
+
+        let token_client = create_token(&env, &admin);
+        let token = token_client.address.clone();
+        token_client.mint(&user, 1000);
+        treasury.deposit(&user, &token, &1000, &None);
+
+        let ops = Symbol::new(&env, "ops");
+        let grants = Symbol::new(&env, "grants");
+        treasury.create_sub_account(&ops);
+        treasury.create_sub_account(&grants);
+        assert!(treasury.try_create_sub_account(&ops).is_err());
+
+        // Allocations leave the free balance
+        fund_ops(&env, &treasury, &token);
+        treasury.fund_sub_account(&ops, &token, &600);
+        assert_eq!(treasury.balance(&token), 400);
+        assert!(treasury
+            .try_withdraw(&admin, &token, &admin, &500, &ops, &None)
+            .is_err());
+
+        treasury.transfer_between_sub_accounts(&ops, &grants, &token, &200);
+        assert_eq!(treasury.sub_account_balance(&ops, &token), 400);
+        assert_eq!(treasury.sub_account_balance(&grants, &token), 200);
+
+        // Per-sub-account daily limit
+        treasury.set_sub_account_limit(&grants, &token, &150);
+        treasury.withdraw_from_sub_account(&grants, &token, &grantee, &100);
+        assert!(treasury
+            .try_withdraw_from_sub_account(&grants, &token, &grantee, &100)
+            .is_err());
+        env.ledger().set_timestamp(SECONDS_PER_DAY + 1);
+        treasury.withdraw_from_sub_account(&grants, &token, &grantee, &100);
+
+        assert_eq!(token_client.balance(&grantee), 200);
+        assert_eq!(treasury.sub_account_balance(&grants, &token), 0);
+        assert_eq!(treasury.balance(&token), 400);
+    }
+
+    #[test]
+    fn test_vesting() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let admin = Address::generate(&env);
+        let user = Address::generate(&env);
+        let member = Address::generate(&env);
+        let treasury = TreasuryVaultClient::new(&env, &env.register(TreasuryVault, ()));
+        treasury.initialize(&admin);
+
+        let token_client = create_token(&env, &admin);
+        let token = token_client.address.clone();
+        token_client.mint(&user, 1000);
+        treasury.deposit(&user, &token, &1000, &None);
+
+        env.ledger().set_timestamp(1000);
+        let team = Symbol::new(&env, "team");
+        assert_eq!(
+            treasury.try_create_vesting(&member, &token, &1001, &100, &400, &team),
+            Err(Ok(SharedError::InsufficientBalance))
+        );
+        assert_eq!(
+            treasury.try_create_vesting(&member, &token, &400, &500, &400, &team),
+            Err(Ok(SharedError::InvalidTimestamp))
+        );
+
+        // Vesting is a withdrawal: it needs budget in its category
+        assert_eq!(
+            treasury.try_create_vesting(&member, &token, &400, &100, &400, &team),
+            Err(Ok(SharedError::LimitExceeded))
+        );
+        let budget = CategoryBudget {
+            cap: 400,
+            rollover: false,
+        };
+        treasury.set_category_budget(&team, &token, &Some(budget));
+        let id = treasury.create_vesting(&member, &token, &400, &100, &400, &team);
+        assert_eq!(treasury.balance(&token), 600);
+        assert_eq!(treasury.vesting_reserved(&token), 400);
+        assert_eq!(treasury.get_category_spend(&team, &token).spent, 400);
+        assert_eq!(
+            treasury.try_create_vesting(&member, &token, &100, &100, &400, &team),
+            Err(Ok(SharedError::LimitExceeded))
+        );
+
+        // Nothing before the cliff, then linear
+        env.ledger().set_timestamp(1050);
+        assert_eq!(treasury.claim_vesting(&id), 0);
+        env.ledger().set_timestamp(1100);
+        assert_eq!(treasury.claim_vesting(&id), 100);
+        env.ledger().set_timestamp(1200);
+        assert_eq!(treasury.vesting_claimable(&id), 100);
+
+        // Vested part stays claimable, the rest returns to the free balance
+        assert_eq!(treasury.revoke_vesting(&id), 200);
+        assert!(treasury.try_revoke_vesting(&id).is_err());
+        assert_eq!(treasury.balance(&token), 800);
+        env.ledger().set_timestamp(2000);
+        assert_eq!(treasury.claim_vesting(&id), 100);
+        assert_eq!(treasury.claim_vesting(&id), 0);
+        assert_eq!(token_client.balance(&member), 200);
+        assert_eq!(treasury.vesting_reserved(&token), 0);
+        assert_eq!(treasury.balance(&token), 800);
+    }
+
     #[test]
     fn test_pause() {
         let env = Env::default();
@@ -854,25 +3188,113 @@ mod test {
         let treasury = TreasuryVaultClient::new(&env, &treasury_id);
         treasury.initialize(&admin);
 
-        let (token_client, token_admin) = create_token_contract(&env, &admin);
-        token_admin.mint(&user, &1000);
+        let token_client = create_token(&env, &admin);
+        token_client.mint(&user, 1000);
 
         // Pause contract
-        treasury.set_paused(&true);
+        treasury.set_paused(&admin, &true);
         assert!(treasury.is_paused());
 
         // Deposit should fail when paused
-        let result = treasury.try_deposit(&user, &token_client.address, &500);
+        let result = treasury.try_deposit(&user, &token_client.address, &500, &None);
         assert!(result.is_err());
 
         // Unpause
-        treasury.set_paused(&false);
+        treasury.set_paused(&admin, &false);
 
         // Deposit should work now
-        treasury.deposit(&user, &token_client.address, &500);
+        treasury.deposit(&user, &token_client.address, &500, &None);
         assert_eq!(treasury.balance(&token_client.address), 500);
     }
 
+    #[test]
+    fn test_pause_deposits_only() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let admin = Address::generate(&env);
+        let user = Address::generate(&env);
+
+        let treasury_id = env.register(TreasuryVault, ());
+        let treasury = TreasuryVaultClient::new(&env, &treasury_id);
+        treasury.initialize(&admin);
+
+        let token_client = create_token(&env, &admin);
+        token_client.mint(&user, 1000);
+        treasury.deposit(&user, &token_client.address, &500, &None);
+
+        let config = PauseConfig {
+            deposits: true,
+            ..PauseConfig::default()
+        };
+        treasury.set_pause_config(&admin, &config);
+        assert_eq!(treasury.pause_config(), config);
+        assert!(treasury.is_paused());
+
+        // Deposits halt while withdrawals stay open
+        assert!(treasury
+            .try_deposit(&user, &token_client.address, &100, &None)
+            .is_err());
+        let ops = fund_ops(&env, &treasury, &token_client.address);
+        treasury.withdraw(&admin, &token_client.address, &user, &200, &ops, &None);
+        assert_eq!(treasury.balance(&token_client.address), 300);
+
+        // Config changes are a separate group
+        treasury.set_pause_config(&admin, &PauseConfig::uniform(false));
+        treasury.set_pause_config(
+            &admin,
+            &PauseConfig {
+                admin_ops: true,
+                ..PauseConfig::default()
+            },
+        );
+        assert!(treasury
+            .try_update_config(&admin, &treasury.get_config())
+            .is_err());
+        treasury.deposit(&user, &token_client.address, &100, &None);
+    }
+
+    #[test]
+    fn test_roles_separate_pause_and_fund_keys() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let admin = Address::generate(&env);
+        let pauser = Address::generate(&env);
+        let treasurer = Address::generate(&env);
+        let user = Address::generate(&env);
+
+        let treasury_id = env.register(TreasuryVault, ());
+        let treasury = TreasuryVaultClient::new(&env, &treasury_id);
+        treasury.initialize(&admin);
+
+        let token_client = create_token(&env, &admin);
+        token_client.mint(&user, 1000);
+        treasury.deposit(&user, &token_client.address, &1000, &None);
+
+        treasury.grant_role(&PAUSER, &pauser);
+        treasury.grant_role(&TREASURER, &treasurer);
+        let ops = fund_ops(&env, &treasury, &token_client.address);
+        assert!(treasury.has_role(&PAUSER, &pauser));
+        assert!(!treasury.has_role(&TREASURER, &pauser));
+
+        // Each key can only do its own job
+        assert!(treasury
+            .try_withdraw(&pauser, &token_client.address, &pauser, &100, &ops, &None)
+            .is_err());
+        assert!(treasury.try_set_paused(&treasurer, &true).is_err());
+        treasury.withdraw(&treasurer, &token_client.address, &user, &100, &ops, &None);
+        assert_eq!(token_client.balance(&user), 100);
+
+        treasury.set_paused(&pauser, &true);
+        assert!(treasury.is_paused());
+        treasury.set_paused(&pauser, &false);
+
+        // Revoked keys lose access
+        treasury.revoke_role(&PAUSER, &pauser);
+        assert!(treasury.try_set_paused(&pauser, &true).is_err());
+    }
+
     #[test]
     fn test_change_admin() {
         let env = Env::default();
@@ -887,7 +3309,213 @@ mod test {
 
         assert_eq!(treasury.get_admin(), admin1);
 
-        treasury.set_admin(&admin2);
+        // Nothing changes until the proposed admin accepts
+        treasury.propose_admin(&admin2);
+        assert_eq!(treasury.pending_admin(), Some(admin2.clone()));
+        assert_eq!(treasury.get_admin(), admin1);
+
+        treasury.cancel_admin_transfer();
+        assert!(treasury.try_accept_admin().is_err());
+
+        treasury.propose_admin(&admin2);
+        treasury.accept_admin();
         assert_eq!(treasury.get_admin(), admin2);
+        assert_eq!(treasury.pending_admin(), None);
+    }
+
+    #[test]
+    fn test_protocol_owned_stake() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let admin = Address::generate(&env);
+        let user = Address::generate(&env);
+        let treasury_id = env.register(TreasuryVault, ());
+        let treasury = TreasuryVaultClient::new(&env, &treasury_id);
+        treasury.initialize(&admin);
+
+        let token_client = create_token(&env, &admin);
+        let token = token_client.address.clone();
+        token_client.mint(&user, 1000);
+        treasury.deposit(&user, &token, &1000, &None);
+
+        let pool = env.register(astro_staking::StakingPool, ());
+        astro_staking::StakingPoolClient::new(&env, &pool).initialize(
+            &admin,
+            &token,
+            &Address::generate(&env),
+            &StakingConfig {
+                min_stake_amount: MIN_STAKE_AMOUNT,
+                cooldown_period: 0,
+                max_stake_per_user: 0,
+                emergency_unlock: false,
+                slashing_enabled: false,
+                deposit_fee_bps: 0,
+                withdraw_fee_bps: 0,
+            },
+        );
+
+        let ops = Symbol::new(&env, "ops");
+        assert_eq!(
+            treasury.try_stake_treasury_funds(&pool, &1001, &ops),
+            Err(Ok(SharedError::InsufficientBalance))
+        );
+
+        // Staking is an outflow: it needs budget and an allowed destination
+        assert_eq!(
+            treasury.try_stake_treasury_funds(&pool, &400, &ops),
+            Err(Ok(SharedError::LimitExceeded))
+        );
+        fund_ops(&env, &treasury, &token);
+        treasury.set_destination_allowlist(&true);
+        assert_eq!(
+            treasury.try_stake_treasury_funds(&pool, &400, &ops),
+            Err(Ok(SharedError::Unauthorized))
+        );
+        let active_at = treasury.add_destination(&pool);
+        env.ledger().set_timestamp(active_at);
+
+        assert_eq!(treasury.stake_treasury_funds(&pool, &400, &ops), 400);
+        assert_eq!(treasury.get_category_spend(&ops, &token).spent, 400);
+
+        // Staked funds are tracked apart from the liquid balance
+        assert_eq!(treasury.balance(&token), 600);
+        assert_eq!(treasury.staked_position(&pool), 400);
+        assert_eq!(
+            treasury.staked_positions(),
+            vec![&env, (pool.clone(), token.clone(), 400_i128)]
+        );
+
+        assert_eq!(
+            treasury.try_unstake_treasury_funds(&pool, &401),
+            Err(Ok(SharedError::InsufficientBalance))
+        );
+        assert_eq!(treasury.unstake_treasury_funds(&pool, &400), 0);
+        assert_eq!(treasury.balance(&token), 1000);
+        assert_eq!(treasury.staked_positions().len(), 0);
+    }
+
+    #[test]
+    fn test_destination_allowlist() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let admin = Address::generate(&env);
+        let vendor = Address::generate(&env);
+        let attacker = Address::generate(&env);
+
+        let treasury_id = env.register(TreasuryVault, ());
+        let treasury = TreasuryVaultClient::new(&env, &treasury_id);
+        treasury.initialize(&admin);
+
+        let token_client = create_token(&env, &admin);
+        let token = token_client.address.clone();
+        token_client.mint(&admin, 1000);
+        treasury.deposit(&admin, &token, &1000, &None);
+        let ops = fund_ops(&env, &treasury, &token);
+
+        env.ledger().set_timestamp(1000);
+        assert_eq!(treasury.set_destination_allowlist(&true), 1000);
+        assert!(treasury.destination_allowlist_enabled());
+
+        // A fresh destination only becomes usable after the timelock
+        let active_at = treasury.add_destination(&vendor);
+        assert_eq!(active_at, 1000 + DESTINATION_TIMELOCK);
+        assert_eq!(
+            treasury.try_withdraw(&admin, &token, &vendor, &100, &ops, &None),
+            Err(Ok(SharedError::Unauthorized))
+        );
+        env.ledger().set_timestamp(active_at);
+        treasury.withdraw(&admin, &token, &vendor, &100, &ops, &None);
+        assert_eq!(token_client.balance(&vendor), 100);
+
+        // Unlisted addresses are refused on every outflow path
+        assert_eq!(
+            treasury.try_withdraw(&admin, &token, &attacker, &100, &ops, &None),
+            Err(Ok(SharedError::Unauthorized))
+        );
+        assert_eq!(
+            treasury.try_withdraw_all(&admin, &token, &attacker, &ops),
+            Err(Ok(SharedError::Unauthorized))
+        );
+        assert_eq!(
+            treasury.try_spend(&admin, &token, &attacker, &100, &ops),
+            Err(Ok(SharedError::Unauthorized))
+        );
+        assert_eq!(
+            treasury.try_create_vesting(&attacker, &token, &100, &0, &100, &ops),
+            Err(Ok(SharedError::Unauthorized))
+        );
+
+        // Turning the allowlist off is timelocked too
+        let off_at = treasury.set_destination_allowlist(&false);
+        assert_eq!(off_at, active_at + DESTINATION_TIMELOCK);
+        assert!(treasury.destination_allowlist_enabled());
+        env.ledger().set_timestamp(off_at);
+        assert!(!treasury.destination_allowlist_enabled());
+        treasury.withdraw(&admin, &token, &attacker, &100, &ops, &None);
+
+        // Removal is immediate
+        treasury.set_destination_allowlist(&true);
+        treasury.remove_destination(&vendor);
+        assert_eq!(treasury.destination_active_at(&vendor), None);
+        assert_eq!(
+            treasury.try_withdraw(&admin, &token, &vendor, &100, &ops, &None),
+            Err(Ok(SharedError::Unauthorized))
+        );
+    }
+
+    #[test]
+    fn test_auto_forward() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let admin = Address::generate(&env);
+        let insurance = Address::generate(&env);
+        let reserve = Address::generate(&env);
+
+        let treasury_id = env.register(TreasuryVault, ());
+        let treasury = TreasuryVaultClient::new(&env, &treasury_id);
+        treasury.initialize(&admin);
+
+        let token_client = create_token(&env, &admin);
+        let token = token_client.address.clone();
+        token_client.mint(&admin, 10_000);
+
+        assert_eq!(
+            treasury.try_set_auto_forward(&token, &10_001, &insurance),
+            Err(Ok(SharedError::InvalidBps))
+        );
+
+        // 10% of every deposit goes straight to the insurance fund
+        treasury.set_auto_forward(&token, &1000, &insurance);
+        treasury.deposit(&admin, &token, &1000, &None);
+        treasury.deposit(&admin, &token, &2000, &None);
+        assert_eq!(token_client.balance(&insurance), 300);
+        assert_eq!(token_client.balance(&treasury_id), 2700);
+        let rule = treasury.get_auto_forward(&token).unwrap();
+        assert_eq!(rule.bps.get(), 1000);
+        assert_eq!(rule.forwarded, 300);
+
+        // Same destination keeps the total, a new one restarts it
+        treasury.set_auto_forward(&token, &500, &insurance);
+        assert_eq!(treasury.get_auto_forward(&token).unwrap().forwarded, 300);
+        treasury.set_auto_forward(&token, &500, &reserve);
+        treasury.deposit(&admin, &token, &1000, &None);
+        assert_eq!(token_client.balance(&reserve), 50);
+        assert_eq!(treasury.get_auto_forward(&token).unwrap().forwarded, 50);
+
+        // The destination must pass the allowlist
+        treasury.set_destination_allowlist(&true);
+        assert_eq!(
+            treasury.try_set_auto_forward(&token, &500, &insurance),
+            Err(Ok(SharedError::Unauthorized))
+        );
+
+        // Zero removes the rule
+        treasury.set_auto_forward(&token, &0, &reserve);
+        assert_eq!(treasury.get_auto_forward(&token), None);
+        treasury.deposit(&admin, &token, &1000, &None);
+        assert_eq!(token_client.balance(&reserve), 50);
     }
 }
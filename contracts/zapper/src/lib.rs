@@ -0,0 +1,572 @@
+#![no_std]
+
+//! # Zapper Contract
+//!
+//! Bundles multi-contract housekeeping into a single transaction.
+//!
+//! ## Features
+//! - `claim_all`: harvest staking rewards and unlock every expired lock
+//! - `zap_in`: provide liquidity from a single token, optionally locking the
+//!   LP shares
+//!
+//! The zapper holds no funds. It calls the staking pool, pairs and locker on
+//! the user's behalf, so the user authorizes those calls as part of the same
+//! transaction and tokens move directly between them and the user.
+
+use astro_core_shared::{
+    events::EventBuilder,
+    interfaces::{AmmPairClient, IntoSharedResult, LiquidityLockerClient, StakingPoolClient},
+    types::{
+        extend_instance_ttl, get_ttl_config, set_ttl_config, ContractInfo, SharedError, TtlConfig,
+    },
+    upgrade::{self, PendingUpgrade},
+};
+use soroban_sdk::{contract, contractimpl, contracttype, Address, BytesN, Env, Vec};
+
+// ════════════════════════════════════════════════════════════════════════════
+// Constants
+// ════════════════════════════════════════════════════════════════════════════
+
+/// Storage layout version of this code (see `migrate`)
+const STORAGE_VERSION: u32 = 1;
+
+/// Maximum number of locks unlocked by one `claim_all`
+pub const MAX_UNLOCKS_PER_CALL: u32 = 20;
+
+// ════════════════════════════════════════════════════════════════════════════
+// Types
+// ════════════════════════════════════════════════════════════════════════════
+
+/// What a `claim_all` collected
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct ClaimAllResult {
+    /// Rewards harvested from the staking pool (token, amount)
+    pub rewards: Vec<(Address, i128)>,
+    /// Locks released by the locker (lock id, amount returned)
+    pub unlocked: Vec<(u64, i128)>,
+}
+
+/// What a `zap_in` provided
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct ZapInResult {
+    /// Token 0 added to the pair
+    pub amount_0: i128,
+    /// Token 1 added to the pair
+    pub amount_1: i128,
+    /// LP shares minted to the user
+    pub liquidity: i128,
+    /// Locker lock holding the LP shares, if they were locked
+    pub lock_id: Option<u64>,
+}
+
+// ════════════════════════════════════════════════════════════════════════════
+// Storage Keys
+// ════════════════════════════════════════════════════════════════════════════
+
+#[contracttype]
+#[derive(Clone, Debug)]
+pub enum DataKey {
+    /// Admin address
+    Admin,
+    /// Whether contract is initialized
+    Initialized,
+    /// Staking pool address
+    StakingPool,
+    /// Liquidity locker address
+    Locker,
+    /// Protocol registry address (reported by `contract_info`)
+    Registry,
+}
+
+// ════════════════════════════════════════════════════════════════════════════
+// Contract Implementation
+// ════════════════════════════════════════════════════════════════════════════
+
+#[contract]
+pub struct Zapper;
+
+#[contractimpl]
+impl Zapper {
+    // ────────────────────────────────────────────────────────────────────────
+    // Initialization
+    // ────────────────────────────────────────────────────────────────────────
+
+    /// Initialize the zapper
+    pub fn initialize(
+        env: Env,
+        admin: Address,
+        staking_pool: Address,
+        locker: Address,
+    ) -> Result<(), SharedError> {
+        if env.storage().instance().has(&DataKey::Initialized) {
+            return Err(SharedError::AlreadyInitialized);
+        }
+
+        env.storage().instance().set(&DataKey::Admin, &admin);
+        env.storage()
+            .instance()
+            .set(&DataKey::StakingPool, &staking_pool);
+        env.storage().instance().set(&DataKey::Locker, &locker);
+        env.storage().instance().set(&DataKey::Initialized, &true);
+        upgrade::init_version(&env, STORAGE_VERSION);
+
+        extend_instance_ttl(&env);
+
+        let events = EventBuilder::new(&env);
+        events.publish(
+            "zapper",
+            "initialized",
+            (admin, staking_pool, locker, env.ledger().timestamp()),
+        );
+
+        Ok(())
+    }
+
+    // ────────────────────────────────────────────────────────────────────────
+    // User Functions
+    // ────────────────────────────────────────────────────────────────────────
+
+    /// Harvest `user`'s staking rewards and unlock their expired locks.
+    ///
+    /// Fails if the claim fails. Locks that cannot be unlocked yet (e.g. the
+    /// locker's unlock buffer has not elapsed) are skipped, and at most
+    /// `MAX_UNLOCKS_PER_CALL` locks are released per call.
+    pub fn claim_all(env: Env, user: Address) -> Result<ClaimAllResult, SharedError> {
+        user.require_auth();
+        let staking_pool = Self::staking_pool(env.clone())?;
+        let locker = Self::locker(env.clone())?;
+
+        let rewards = StakingPoolClient::new(&env, &staking_pool)
+            .try_claim(&user)
+            .into_shared()?;
+
+        let locker_client = LiquidityLockerClient::new(&env, &locker);
+        let now = env.ledger().timestamp();
+        let mut unlocked = Vec::new(&env);
+        for lock in locker_client.get_user_locks(&user).iter() {
+            if unlocked.len() >= MAX_UNLOCKS_PER_CALL {
+                break;
+            }
+            if !lock.is_unlockable(now) {
+                continue;
+            }
+            if let Ok(amount) = locker_client.try_unlock(&user, &lock.id).into_shared() {
+                unlocked.push_back((lock.id, amount));
+            }
+        }
+
+        let events = EventBuilder::new(&env);
+        events.publish(
+            "zapper",
+            "claim_all",
+            (user, rewards.len(), unlocked.len(), now),
+        );
+
+        extend_instance_ttl(&env);
+        Ok(ClaimAllResult { rewards, unlocked })
+    }
+
+    /// Provide liquidity to `pair` from `amount` of one of its tokens.
+    ///
+    /// Half of `amount` is swapped for the other token and both halves are
+    /// added as liquidity. Whatever the pool ratio leaves unused stays in the
+    /// user's wallet. With `lock_until`, the minted LP shares are locked in
+    /// the locker for the user until then.
+    ///
+    /// Fails with `BelowMinimum` if fewer than `min_lp` shares are minted.
+    pub fn zap_in(
+        env: Env,
+        user: Address,
+        token_in: Address,
+        amount: i128,
+        pair: Address,
+        min_lp: i128,
+        lock_until: Option<u64>,
+    ) -> Result<ZapInResult, SharedError> {
+        user.require_auth();
+        if amount <= 0 || min_lp < 0 {
+            return Err(SharedError::InvalidAmount);
+        }
+
+        let pair_client = AmmPairClient::new(&env, &pair);
+        let token_0 = pair_client.try_token_0().into_shared()?;
+        let token_1 = pair_client.try_token_1().into_shared()?;
+        if token_in != token_0 && token_in != token_1 {
+            return Err(SharedError::TokenNotFound);
+        }
+
+        // Slippage is bounded by `min_lp`, so the swap itself takes any output
+        let swap_in = amount / 2;
+        let kept = amount - swap_in;
+        let swapped = pair_client
+            .try_swap(&user, &token_in, &swap_in, &0)
+            .into_shared()?;
+        let (desired_0, desired_1) = if token_in == token_0 {
+            (kept, swapped)
+        } else {
+            (swapped, kept)
+        };
+
+        let now = env.ledger().timestamp();
+        let (amount_0, amount_1, liquidity) = pair_client
+            .try_add_liquidity(&user, &desired_0, &desired_1, &0, &0, &now)
+            .into_shared()?;
+        if liquidity < min_lp {
+            return Err(SharedError::BelowMinimum);
+        }
+
+        let lock_id = match lock_until {
+            Some(unlock_time) => {
+                let locker = Self::locker(env.clone())?;
+                Some(
+                    LiquidityLockerClient::new(&env, &locker)
+                        .try_lock(&user, &pair, &liquidity, &unlock_time)
+                        .into_shared()?,
+                )
+            }
+            None => None,
+        };
+
+        let events = EventBuilder::new(&env);
+        events.publish(
+            "zapper",
+            "zap_in",
+            (user, pair, token_in, amount, liquidity, lock_id, now),
+        );
+
+        extend_instance_ttl(&env);
+        Ok(ZapInResult {
+            amount_0,
+            amount_1,
+            liquidity,
+            lock_id,
+        })
+    }
+
+    // ────────────────────────────────────────────────────────────────────────
+    // Admin Functions
+    // ────────────────────────────────────────────────────────────────────────
+
+    /// Set the staking pool address
+    pub fn set_staking_pool(env: Env, staking_pool: Address) -> Result<(), SharedError> {
+        Self::require_admin(&env)?;
+        env.storage()
+            .instance()
+            .set(&DataKey::StakingPool, &staking_pool);
+        extend_instance_ttl(&env);
+        Ok(())
+    }
+
+    /// Set the liquidity locker address
+    pub fn set_locker(env: Env, locker: Address) -> Result<(), SharedError> {
+        Self::require_admin(&env)?;
+        env.storage().instance().set(&DataKey::Locker, &locker);
+        extend_instance_ttl(&env);
+        Ok(())
+    }
+
+    /// Set the protocol registry address reported by `contract_info`
+    pub fn set_registry(env: Env, registry: Address) -> Result<(), SharedError> {
+        Self::require_admin(&env)?;
+        env.storage().instance().set(&DataKey::Registry, &registry);
+        extend_instance_ttl(&env);
+        Ok(())
+    }
+
+    /// Set admin address
+    pub fn set_admin(env: Env, new_admin: Address) -> Result<(), SharedError> {
+        Self::require_admin(&env)?;
+
+        env.storage().instance().set(&DataKey::Admin, &new_admin);
+        extend_instance_ttl(&env);
+
+        Ok(())
+    }
+
+    /// Set how far storage TTLs are extended (see `TtlConfig`)
+    pub fn set_ttl_config(env: Env, config: TtlConfig) -> Result<(), SharedError> {
+        Self::require_admin(&env)?;
+
+        set_ttl_config(&env, &config)?;
+        extend_instance_ttl(&env);
+
+        Ok(())
+    }
+
+    // ────────────────────────────────────────────────────────────────────────
+    // Upgrades (admin only)
+    // ────────────────────────────────────────────────────────────────────────
+
+    /// Queue a WASM upgrade behind the upgrade timelock. Returns its eta.
+    pub fn queue_upgrade(env: Env, new_wasm_hash: BytesN<32>) -> Result<u64, SharedError> {
+        Self::require_admin(&env)?;
+        upgrade::queue(&env, new_wasm_hash)
+    }
+
+    /// Cancel the queued upgrade
+    pub fn cancel_upgrade(env: Env) -> Result<(), SharedError> {
+        Self::require_admin(&env)?;
+        upgrade::cancel(&env)
+    }
+
+    /// Switch to the queued WASM once the timelock has elapsed
+    pub fn upgrade(env: Env, new_wasm_hash: BytesN<32>) -> Result<(), SharedError> {
+        Self::require_admin(&env)?;
+        upgrade::execute(&env, new_wasm_hash)
+    }
+
+    /// Migrate storage to `STORAGE_VERSION` after an upgrade
+    pub fn migrate(env: Env) -> Result<u32, SharedError> {
+        Self::require_admin(&env)?;
+        upgrade::migrate(&env, STORAGE_VERSION, |_, _| Ok(()))
+    }
+
+    // ────────────────────────────────────────────────────────────────────────
+    // Query Functions
+    // ────────────────────────────────────────────────────────────────────────
+
+    /// Get the storage layout version
+    pub fn version(env: Env) -> u32 {
+        upgrade::version(&env)
+    }
+
+    /// Get the queued upgrade, if any
+    pub fn get_pending_upgrade(env: Env) -> Option<PendingUpgrade> {
+        upgrade::pending(&env)
+    }
+
+    /// Get the staking pool address
+    pub fn staking_pool(env: Env) -> Result<Address, SharedError> {
+        env.storage()
+            .instance()
+            .get(&DataKey::StakingPool)
+            .ok_or(SharedError::NotInitialized)
+    }
+
+    /// Get the liquidity locker address
+    pub fn locker(env: Env) -> Result<Address, SharedError> {
+        env.storage()
+            .instance()
+            .get(&DataKey::Locker)
+            .ok_or(SharedError::NotInitialized)
+    }
+
+    /// Get admin address
+    pub fn admin(env: Env) -> Result<Address, SharedError> {
+        env.storage()
+            .instance()
+            .get(&DataKey::Admin)
+            .ok_or(SharedError::NotInitialized)
+    }
+
+    /// Get the storage TTL config
+    pub fn ttl_config(env: Env) -> TtlConfig {
+        get_ttl_config(&env)
+    }
+
+    /// Describe this contract for explorers and wallets
+    pub fn contract_info(env: Env) -> Result<ContractInfo, SharedError> {
+        let admin = Self::admin(env.clone())?;
+        Ok(ContractInfo::new(
+            &env,
+            "zapper",
+            env!("CARGO_PKG_VERSION"),
+            admin,
+            false,
+            env.storage().instance().get(&DataKey::Registry),
+        ))
+    }
+
+    // ────────────────────────────────────────────────────────────────────────
+    // Internal Functions
+    // ────────────────────────────────────────────────────────────────────────
+
+    fn require_admin(env: &Env) -> Result<(), SharedError> {
+        let admin: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::Admin)
+            .ok_or(SharedError::NotInitialized)?;
+
+        admin.require_auth();
+        Ok(())
+    }
+}
+
+// ════════════════════════════════════════════════════════════════════════════
+// Tests
+// ════════════════════════════════════════════════════════════════════════════
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use astro_core_shared::{
+        testutils::{create_token, default_staking_config},
+        types::{Bps, LockConfig, PenaltyRouting},
+    };
+    use astro_locker::LiquidityLocker;
+    use astro_pair::AmmPair;
+    use astro_staking::StakingPool;
+    use soroban_sdk::{
+        contractimpl,
+        testutils::{Address as _, Ledger},
+        token,
+    };
+
+    /// Fee distributor that accepts protocol fees from pairs
+    #[contract]
+    pub struct MockFeeDistributor;
+
+    #[contractimpl]
+    impl MockFeeDistributor {
+        pub fn receive_fees(env: Env, caller: Address, token: Address, amount: i128) {
+            caller.require_auth();
+            token::Client::new(&env, &token).transfer(
+                &caller,
+                env.current_contract_address(),
+                &amount,
+            );
+        }
+    }
+
+    fn register_locker<'a>(env: &Env, admin: &Address) -> astro_locker::LiquidityLockerClient<'a> {
+        let locker =
+            astro_locker::LiquidityLockerClient::new(env, &env.register(LiquidityLocker, ()));
+        locker.initialize(
+            admin,
+            &Address::generate(env),
+            &LockConfig {
+                min_lock_duration: 86_400,
+                max_lock_duration: 31_536_000,
+                early_unlock_enabled: false,
+                early_unlock_penalty_bps: Bps::from_const(0),
+                penalty_routing: PenaltyRouting::TREASURY_ONLY,
+                unlock_buffer: 0,
+            },
+        );
+        locker
+    }
+
+    #[test]
+    fn test_claim_all() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let admin = Address::generate(&env);
+        let distributor = Address::generate(&env);
+        let user = Address::generate(&env);
+        let astro = create_token(&env, &admin);
+        let lp = create_token(&env, &admin);
+
+        let staking_id = env.register(StakingPool, ());
+        let staking = astro_staking::StakingPoolClient::new(&env, &staking_id);
+        staking.initialize(
+            &admin,
+            &astro.address,
+            &distributor,
+            &default_staking_config(),
+        );
+
+        let locker = register_locker(&env, &admin);
+        let zapper = ZapperClient::new(&env, &env.register(Zapper, ()));
+        zapper.initialize(&admin, &staking_id, &locker.address);
+
+        astro.mint(&user, 10_000_000);
+        staking.stake(&user, &10_000_000, &None);
+        staking.allow_reward_token(&astro.address);
+        astro.mint(&distributor, 500);
+        staking.add_rewards(&distributor, &astro.address, &500);
+
+        lp.mint(&user, 300);
+        let now = env.ledger().timestamp();
+        let short = locker.lock(&user, &lp.address, &100, &(now + 86_400));
+        let long = locker.lock(&user, &lp.address, &200, &(now + 10 * 86_400));
+
+        env.ledger().with_mut(|l| l.timestamp = now + 2 * 86_400);
+        let result = zapper.claim_all(&user);
+
+        assert_eq!(result.rewards.len(), 1);
+        assert_eq!(result.rewards.get(0).unwrap().1, 500);
+        assert_eq!(astro.balance(&user), 500);
+        assert_eq!(result.unlocked.len(), 1);
+        assert_eq!(result.unlocked.get(0).unwrap(), (short, 100));
+        assert_eq!(lp.balance(&user), 100);
+        assert!(!locker.get_lock(&long).unwrap().unlocked);
+    }
+
+    #[test]
+    fn test_zap_in() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let admin = Address::generate(&env);
+        let provider = Address::generate(&env);
+        let user = Address::generate(&env);
+        let token_0 = create_token(&env, &admin);
+        let token_1 = create_token(&env, &admin);
+        token_0.mint(&provider, 1_000_000_000_000);
+        token_1.mint(&provider, 1_000_000_000_000);
+        token_0.mint(&user, 100_000_000);
+
+        let pair_id = env.register(AmmPair, ());
+        let pair = astro_pair::AmmPairClient::new(&env, &pair_id);
+        let fee_to = env.register(MockFeeDistributor, ());
+        pair.initialize(&token_0.address, &token_1.address, &admin, &fee_to);
+        let now = env.ledger().timestamp();
+        pair.add_liquidity(
+            &provider,
+            &1_000_000_000_000,
+            &1_000_000_000_000,
+            &0,
+            &0,
+            &now,
+        );
+
+        let locker = register_locker(&env, &admin);
+        let zapper = ZapperClient::new(&env, &env.register(Zapper, ()));
+        zapper.initialize(&admin, &Address::generate(&env), &locker.address);
+
+        // Unreachable minimum
+        assert_eq!(
+            zapper
+                .try_zap_in(
+                    &user,
+                    &token_0.address,
+                    &100_000_000,
+                    &pair_id,
+                    &100_000_000,
+                    &None
+                )
+                .err(),
+            Some(Ok(SharedError::BelowMinimum))
+        );
+
+        let result = zapper.zap_in(
+            &user,
+            &token_0.address,
+            &100_000_000,
+            &pair_id,
+            &1,
+            &Some(now + 7 * 86_400),
+        );
+        assert!(result.liquidity > 0);
+        // The swapped side is used in full; the token_0 remainder is dust
+        assert_eq!(token_1.balance(&user), 0);
+        assert!(token_0.balance(&user) < 1_000_000);
+
+        let lock = locker.get_lock(&result.lock_id.unwrap()).unwrap();
+        assert_eq!(lock.owner, user);
+        assert_eq!(lock.amount, result.liquidity);
+        assert_eq!(pair.balance(&user), 0);
+        assert_eq!(pair.balance(&locker.address), result.liquidity);
+
+        assert_eq!(
+            zapper
+                .try_zap_in(&user, &Address::generate(&env), &10, &pair_id, &0, &None)
+                .err(),
+            Some(Ok(SharedError::TokenNotFound))
+        );
+    }
+}
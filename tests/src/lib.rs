@@ -0,0 +1,155 @@
+//! # Astro Integration Tests
+//!
+//! Cross-contract flows run against the release WASM of each contract, so
+//! calls go through the same spec and encoding as on-chain. A shared
+//! interface that drifts from the contract it calls (e.g. the fee
+//! distributor's `add_rewards` call into the staking pool) fails here even
+//! when each contract's own unit tests pass.
+//!
+//! ```bash
+//! cargo build --target wasm32-unknown-unknown --release
+//! cargo test --manifest-path tests/Cargo.toml
+//! ```
+
+use astro_core_shared::testutils::{self, TestToken};
+use soroban_sdk::{Address, Env};
+
+pub use astro_core_shared::testutils::{advance_time, GENESIS_TIMESTAMP, ONE_TOKEN};
+
+pub mod treasury {
+    soroban_sdk::contractimport!(
+        file = "../target/wasm32-unknown-unknown/release/astro_treasury.wasm"
+    );
+}
+
+pub mod fee_distributor {
+    soroban_sdk::contractimport!(
+        file = "../target/wasm32-unknown-unknown/release/astro_fee_distributor.wasm"
+    );
+}
+
+pub mod staking {
+    soroban_sdk::contractimport!(
+        file = "../target/wasm32-unknown-unknown/release/astro_staking.wasm"
+    );
+}
+
+pub mod locker {
+    soroban_sdk::contractimport!(
+        file = "../target/wasm32-unknown-unknown/release/astro_locker.wasm"
+    );
+}
+
+pub mod pair {
+    soroban_sdk::contractimport!(file = "../target/wasm32-unknown-unknown/release/astro_pair.wasm");
+}
+
+/// Treasury, fee distributor, staking pool and locker deployed from WASM
+/// and wired together (50/30/20 fee split, ASTRO staked and paid out)
+pub struct Protocol<'a> {
+    /// Env with every auth mocked
+    pub env: Env,
+    /// Admin of every contract and issuer of every test token
+    pub admin: Address,
+    /// Burn recipient of the fee split
+    pub burn: Address,
+    /// Stake and reward token
+    pub astro: TestToken<'a>,
+    /// Treasury vault
+    pub treasury: treasury::Client<'a>,
+    /// Fee distributor
+    pub fee_distributor: fee_distributor::Client<'a>,
+    /// ASTRO staking pool
+    pub staking: staking::Client<'a>,
+    /// Liquidity locker
+    pub locker: locker::Client<'a>,
+}
+
+impl<'a> Protocol<'a> {
+    /// Deploy and wire the core contracts
+    pub fn deploy() -> Self {
+        let env = testutils::env();
+        let admin = testutils::user(&env);
+        let burn = testutils::user(&env);
+        let astro = testutils::create_token(&env, &admin);
+
+        let treasury = treasury::Client::new(&env, &env.register(treasury::WASM, ()));
+        let fee_distributor =
+            fee_distributor::Client::new(&env, &env.register(fee_distributor::WASM, ()));
+        let staking = staking::Client::new(&env, &env.register(staking::WASM, ()));
+        let locker = locker::Client::new(&env, &env.register(locker::WASM, ()));
+
+        treasury.initialize(&admin);
+        fee_distributor.initialize(&admin, &treasury.address, &staking.address, &burn);
+        staking.initialize(
+            &admin,
+            &astro.address,
+            &fee_distributor.address,
+            &staking::StakingConfig {
+                min_stake_amount: ONE_TOKEN,
+                cooldown_period: 0,
+                max_stake_per_user: 0,
+                emergency_unlock: false,
+                slashing_enabled: false,
+                deposit_fee_bps: 0,
+                withdraw_fee_bps: 0,
+            },
+        );
+        locker.initialize(
+            &admin,
+            &treasury.address,
+            &locker::LockConfig {
+                min_lock_duration: 86_400,
+                max_lock_duration: 31_536_000,
+                early_unlock_enabled: true,
+                early_unlock_penalty_bps: locker::Bps(2500),
+                penalty_routing: locker::PenaltyRouting {
+                    treasury_bps: locker::Bps(10_000),
+                    burn_bps: locker::Bps(0),
+                    stakers_bps: locker::Bps(0),
+                },
+                unlock_buffer: 0,
+            },
+        );
+
+        treasury.set_fee_distributor(&fee_distributor.address);
+        locker.set_fee_distributor(&fee_distributor.address);
+        staking.allow_reward_token(&astro.address);
+
+        Self {
+            env,
+            admin,
+            burn,
+            astro,
+            treasury,
+            fee_distributor,
+            staking,
+            locker,
+        }
+    }
+
+    /// A fresh account
+    pub fn user(&self) -> Address {
+        testutils::user(&self.env)
+    }
+
+    /// A fresh account holding `amount` ASTRO
+    pub fn funded_user(&self, amount: i128) -> Address {
+        let user = self.user();
+        self.astro.mint(&user, amount);
+        user
+    }
+
+    /// Deploy another token issued by the admin
+    pub fn token(&self) -> TestToken<'a> {
+        testutils::create_token(&self.env, &self.admin)
+    }
+
+    /// Hand `amount` of ASTRO to the fee distributor, as a pair's swap fee
+    /// would be
+    pub fn collect_fees(&self, amount: i128) {
+        let payer = self.funded_user(amount);
+        self.fee_distributor
+            .receive_fees(&payer, &self.astro.address, &amount);
+    }
+}
@@ -0,0 +1,41 @@
+//! Fees → fee distributor → staking rewards → claim
+
+use astro_integration_tests::{Protocol, ONE_TOKEN};
+use soroban_sdk::vec;
+
+#[test]
+fn test_fees_reach_stakers_and_are_claimed() {
+    let p = Protocol::deploy();
+    let alice = p.funded_user(1_000 * ONE_TOKEN);
+    let bob = p.funded_user(1_000 * ONE_TOKEN);
+    p.staking.stake(&alice, &(100 * ONE_TOKEN), &None);
+    p.staking.stake(&bob, &(300 * ONE_TOKEN), &None);
+
+    // 50/30/20: the staking pool pulls its share through `add_rewards`
+    p.collect_fees(40 * ONE_TOKEN);
+    let result = p.fee_distributor.distribute(&p.astro.address, &None);
+    assert_eq!(result.treasury_amount, 20 * ONE_TOKEN);
+    assert_eq!(result.staking_amount, 12 * ONE_TOKEN);
+    assert_eq!(result.burn_amount, 8 * ONE_TOKEN);
+    assert_eq!(p.treasury.balance(&p.astro.address), 20 * ONE_TOKEN);
+    assert_eq!(p.astro.balance(&p.burn), 8 * ONE_TOKEN);
+    assert_eq!(
+        p.fee_distributor.get_pending_distribution(&p.astro.address),
+        0
+    );
+
+    // Rewards split pro rata to stake
+    assert_eq!(
+        p.staking.pending_rewards(&alice),
+        vec![&p.env, (p.astro.address.clone(), 3 * ONE_TOKEN)]
+    );
+    assert_eq!(
+        p.staking.claim(&bob),
+        vec![&p.env, (p.astro.address.clone(), 9 * ONE_TOKEN)]
+    );
+    assert_eq!(p.astro.balance(&bob), 709 * ONE_TOKEN);
+
+    // Principal is untouched by the rewards sitting in the same token
+    p.staking.unstake(&alice, &(100 * ONE_TOKEN), &None);
+    assert_eq!(p.astro.balance(&alice), 1_003 * ONE_TOKEN);
+}
@@ -0,0 +1,41 @@
+//! Graduation → lock: a graduated token's liquidity is seeded in an AMM pair
+//! and the LP shares are locked, which is what launch checks look for
+
+use astro_integration_tests::{pair, Protocol, GENESIS_TIMESTAMP, ONE_TOKEN};
+
+#[test]
+fn test_graduated_liquidity_is_locked() {
+    let p = Protocol::deploy();
+    let token = p.token();
+    let creator = p.funded_user(1_000 * ONE_TOKEN);
+    token.mint(&creator, 1_000 * ONE_TOKEN);
+
+    let pair_id = p.env.register(pair::WASM, ());
+    let pair = pair::Client::new(&p.env, &pair_id);
+    pair.initialize(&token.address, &p.astro.address, &p.admin, &p.admin);
+    pair.add_liquidity(
+        &creator,
+        &(1_000 * ONE_TOKEN),
+        &(1_000 * ONE_TOKEN),
+        &0,
+        &0,
+        &GENESIS_TIMESTAMP,
+    );
+    let lp = pair.balance(&creator);
+    assert!(lp > 0);
+
+    let unlock_time = GENESIS_TIMESTAMP + 180 * 86_400;
+    let lock_id = p.locker.lock(&creator, &pair_id, &lp, &unlock_time);
+    assert_eq!(pair.balance(&creator), 0);
+
+    // The locker reads the pair's supply across the contract boundary
+    assert!(p
+        .locker
+        .is_liquidity_locked(&pair_id, &9_900, &(90 * 86_400)));
+    assert!(p.locker.try_unlock(&creator, &lock_id).is_err());
+
+    // Early exit pays the penalty in LP shares to the treasury
+    let received = p.locker.early_unlock(&creator, &lock_id);
+    assert_eq!(received + pair.balance(&p.treasury.address), lp);
+    assert!(!p.locker.is_liquidity_locked(&pair_id, &1, &0));
+}
@@ -0,0 +1,76 @@
+//! Treasury rate limits on fees that arrive from the fee distributor
+
+use astro_integration_tests::{advance_time, treasury, Protocol, ONE_TOKEN};
+use soroban_sdk::Symbol;
+
+#[test]
+fn test_rate_limits_cover_every_outflow() {
+    let p = Protocol::deploy();
+    let ops = Symbol::new(&p.env, "ops");
+    let vendor = p.user();
+    let spender = p.user();
+
+    p.collect_fees(100 * ONE_TOKEN);
+    p.fee_distributor.distribute(&p.astro.address, &None);
+    assert_eq!(p.treasury.balance(&p.astro.address), 50 * ONE_TOKEN);
+
+    p.treasury.update_config(
+        &p.admin,
+        &treasury::TreasuryConfig {
+            rate_limit: treasury::RateLimitConfig {
+                max_per_tx: 0,
+                daily_limit: 10 * ONE_TOKEN,
+                cooldown_seconds: 0,
+                enabled: true,
+            },
+            max_tokens: 100,
+            max_spenders: 50,
+        },
+    );
+    p.treasury.set_category_budget(
+        &ops,
+        &p.astro.address,
+        &Some(treasury::CategoryBudget {
+            cap: 1_000 * ONE_TOKEN,
+            rollover: false,
+        }),
+    );
+    p.treasury.add_spender(&spender);
+
+    // Admin withdrawals and spender payments share one daily limit
+    p.treasury.withdraw(
+        &p.admin,
+        &p.astro.address,
+        &vendor,
+        &(6 * ONE_TOKEN),
+        &ops,
+        &None,
+    );
+    p.treasury
+        .spend(&spender, &p.astro.address, &vendor, &(4 * ONE_TOKEN), &ops);
+    assert_eq!(
+        p.treasury
+            .try_spend(&spender, &p.astro.address, &vendor, &1, &ops)
+            .err(),
+        Some(Ok(treasury::SharedError::DailyLimitExceeded))
+    );
+    assert_eq!(
+        p.treasury
+            .try_withdraw_all(&p.admin, &p.astro.address, &vendor, &ops)
+            .err(),
+        Some(Ok(treasury::SharedError::DailyLimitExceeded))
+    );
+
+    // A new day reopens the limit
+    advance_time(&p.env, 86_400);
+    p.treasury.withdraw(
+        &p.admin,
+        &p.astro.address,
+        &vendor,
+        &(10 * ONE_TOKEN),
+        &ops,
+        &None,
+    );
+    assert_eq!(p.astro.balance(&vendor), 20 * ONE_TOKEN);
+    assert_eq!(p.treasury.balance(&p.astro.address), 30 * ONE_TOKEN);
+}